@@ -0,0 +1,72 @@
+//! Concurrent opportunity throughput of the memory store, single lock against the default shards
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use offchain_bot::dex::DexType;
+use offchain_bot::models::{ArbitrageOpportunity, OpportunityStatus, Pool, Token};
+use offchain_bot::services::memory_store::{MemoryStore, DEFAULT_OPPORTUNITY_SHARDS};
+use offchain_bot::utils::clock::SystemClock;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+const TASKS: usize = 8;
+const SAVES_PER_TASK: usize = 250;
+
+fn opportunity() -> ArbitrageOpportunity {
+    let sol = Token::new(Pubkey::new_unique(), "SOL".to_string(), "SOL".to_string(), 9);
+    let usdc = Token::new(Pubkey::new_unique(), "USDC".to_string(), "USDC".to_string(), 6);
+    let pool = |dex_type| {
+        Pool::new("pool".to_string(), dex_type, sol.clone(), usdc.clone(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique())
+    };
+    ArbitrageOpportunity::new(sol.clone(), usdc.clone(), pool(DexType::Raydium), pool(DexType::Whirlpool))
+}
+
+/// Each task saves opportunities, reading one back and moving it on every tenth and running a
+/// profit-floor search every fiftieth
+async fn save_and_search(store: &MemoryStore, template: &ArbitrageOpportunity, round: usize) {
+    let tasks: Vec<_> = (0..TASKS)
+        .map(|task| {
+            let store = store.clone();
+            let template = template.clone();
+            tokio::spawn(async move {
+                for i in 0..SAVES_PER_TASK {
+                    let mut opportunity = template.clone();
+                    opportunity.id = format!("{}-{}-{}", round, task, i);
+                    opportunity.net_profit = Decimal::from(i);
+                    opportunity.timestamp = Utc::now();
+                    store.save_opportunity(&opportunity).await.unwrap();
+                    if i % 10 == 0 {
+                        store.get_opportunity(&opportunity.id).await;
+                        store.update_opportunity_status(&opportunity.id, OpportunityStatus::Executing).await.unwrap();
+                    }
+                    if i % 50 == 0 {
+                        store.search_opportunities(Some(Decimal::from(240)), None, None).await;
+                    }
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+fn concurrent_save_and_search(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread().worker_threads(TASKS).enable_all().build().unwrap();
+    let template = opportunity();
+    let mut group = c.benchmark_group("memory_store_concurrent_save_and_search");
+    for shards in [1, DEFAULT_OPPORTUNITY_SHARDS] {
+        let store = runtime.block_on(async { MemoryStore::new_sharded(10_000, 1_000, shards, SystemClock::shared()) });
+        let mut round = 0;
+        group.bench_with_input(BenchmarkId::new("shards", shards), &shards, |b, _| {
+            b.iter(|| {
+                round += 1;
+                runtime.block_on(save_and_search(&store, &template, round))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, concurrent_save_and_search);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+-- Records are stored as JSON; the extra columns are what queries filter and aggregate on.
+-- Kept to SQL that both Postgres and SQLite accept.
+
+CREATE TABLE IF NOT EXISTS opportunities (
+    id TEXT PRIMARY KEY,
+    status TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    net_profit TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_opportunities_status ON opportunities (status);
+
+CREATE TABLE IF NOT EXISTS strategies (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS executions (
+    id TEXT PRIMARY KEY,
+    opportunity_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    executed_at BIGINT NOT NULL,
+    actual_profit TEXT,
+    total_cost TEXT,
+    data TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_executions_status ON executions (status);
+CREATE INDEX IF NOT EXISTS idx_executions_executed_at ON executions (executed_at);
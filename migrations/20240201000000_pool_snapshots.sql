@@ -0,0 +1,10 @@
+-- Pool reserves over time, for backtests and replay
+
+CREATE TABLE IF NOT EXISTS pool_snapshots (
+    pool_address TEXT NOT NULL,
+    captured_at BIGINT NOT NULL,
+    data TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_pool_snapshots_captured_at ON pool_snapshots (captured_at);
+CREATE INDEX IF NOT EXISTS idx_pool_snapshots_pool ON pool_snapshots (pool_address, captured_at);
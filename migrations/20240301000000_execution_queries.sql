@@ -0,0 +1,15 @@
+-- Columns that report queries filter and aggregate on, so they don't have to decode every record.
+-- Amounts are also kept as floating point for SUM; the exact decimals stay in the text columns.
+-- Rows written before this migration get their pair and strategy filled in at startup.
+
+ALTER TABLE executions ADD COLUMN pair TEXT;
+ALTER TABLE executions ADD COLUMN strategy TEXT;
+ALTER TABLE executions ADD COLUMN profit_value DOUBLE PRECISION;
+ALTER TABLE executions ADD COLUMN cost_value DOUBLE PRECISION;
+
+UPDATE executions SET
+    profit_value = CAST(actual_profit AS DOUBLE PRECISION),
+    cost_value = CAST(total_cost AS DOUBLE PRECISION);
+
+CREATE INDEX IF NOT EXISTS idx_executions_pair ON executions (pair, executed_at);
+CREATE INDEX IF NOT EXISTS idx_executions_strategy ON executions (strategy, executed_at);
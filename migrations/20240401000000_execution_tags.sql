@@ -0,0 +1,14 @@
+-- Execution tags, one row per key, so queries can filter on any tag without decoding records.
+-- `executions.tags` holds the JSON object they were indexed from; rows written before this
+-- migration have it NULL and get their tags indexed at startup.
+
+ALTER TABLE executions ADD COLUMN tags TEXT;
+
+CREATE TABLE IF NOT EXISTS execution_tags (
+    execution_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (execution_id, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_execution_tags_key ON execution_tags (key, value);
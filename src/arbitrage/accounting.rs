@@ -0,0 +1,255 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::models::{ArbitrageExecution, ExecutionStatus};
+
+/// Execution tag holding the Jito tip paid, in lamports
+pub const TIP_TAG: &str = "tip_lamports";
+
+/// Length of the periods realized P&L is booked into, by UTC date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Daily,
+    Monthly,
+}
+
+impl Period {
+    fn key(&self, execution: &ArbitrageExecution) -> String {
+        match self {
+            Period::Daily => execution.execution_time.format("%Y-%m-%d").to_string(),
+            Period::Monthly => execution.execution_time.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+impl FromStr for Period {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "daily" => Ok(Period::Daily),
+            "monthly" => Ok(Period::Monthly),
+            other => anyhow::bail!("Unknown period '{}', expected daily or monthly", other),
+        }
+    }
+}
+
+/// What a P&L line is broken down by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dimension {
+    /// Token the profit is realized in: the one the route starts and ends with
+    Token,
+    Pair,
+    Strategy,
+    /// Value of a tag asked for in the export, keyed `name=value`
+    Tag,
+}
+
+impl Dimension {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dimension::Token => "token",
+            Dimension::Pair => "pair",
+            Dimension::Strategy => "strategy",
+            Dimension::Tag => "tag",
+        }
+    }
+
+    /// Line key of the execution; `tag` names the tag a `Tag` line is for
+    fn key(&self, execution: &ArbitrageExecution, tag: &str) -> String {
+        match self {
+            Dimension::Token => match &execution.opportunity.route {
+                Some(route) => route.input_token.symbol.clone(),
+                None => execution.opportunity.quote_token.symbol.clone(),
+            },
+            Dimension::Pair => execution.opportunity.pair(),
+            Dimension::Strategy => execution.tags.get("strategy").cloned().unwrap_or_else(|| "untagged".to_string()),
+            Dimension::Tag => format!("{}={}", tag, execution.tags.get(tag).map_or("untagged", String::as_str)),
+        }
+    }
+}
+
+/// Realized P&L of one period and one token, pair, strategy or tag value
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PnlLine {
+    pub period: String,
+    pub dimension: Dimension,
+    pub key: String,
+    pub executions: u64,
+    pub confirmed: u64,
+    pub failed: u64,
+    /// Realized profit, in the token the route starts from
+    pub profit: Decimal,
+    /// Network fees, in SOL
+    pub fees: Decimal,
+    /// Jito tips, in SOL
+    pub tips: Decimal,
+}
+
+impl PnlLine {
+    fn new(period: String, dimension: Dimension, key: String) -> Self {
+        Self {
+            period,
+            dimension,
+            key,
+            executions: 0,
+            confirmed: 0,
+            failed: 0,
+            profit: Decimal::ZERO,
+            fees: Decimal::ZERO,
+            tips: Decimal::ZERO,
+        }
+    }
+
+    /// Profit less fees and tips, summed across units as the report's net profit is
+    pub fn net(&self) -> Decimal {
+        self.profit - self.fees - self.tips
+    }
+
+    fn add(&mut self, execution: &ArbitrageExecution) {
+        let tips = execution
+            .tags
+            .get(TIP_TAG)
+            .and_then(|lamports| lamports.parse::<i64>().ok())
+            .map(|lamports| Decimal::new(lamports, 9))
+            .unwrap_or_default();
+        let cost = execution.total_cost.unwrap_or_default();
+        self.executions += 1;
+        match execution.execution_status {
+            ExecutionStatus::Confirmed => self.confirmed += 1,
+            _ => self.failed += 1,
+        }
+        self.profit += execution.actual_profit.unwrap_or_default();
+        self.tips += tips.min(cost);
+        self.fees += (cost - tips).max(Decimal::ZERO);
+    }
+}
+
+/// Book finished executions into per-period lines for each dimension, plus one per value of
+/// each tag in `tags`, ordered by period then dimension then key
+pub fn ledger(executions: &[ArbitrageExecution], period: Period, tags: &[String]) -> Vec<PnlLine> {
+    let mut lines: BTreeMap<(String, Dimension, String), PnlLine> = BTreeMap::new();
+    let finished = executions
+        .iter()
+        .filter(|e| matches!(e.execution_status, ExecutionStatus::Confirmed | ExecutionStatus::Failed));
+    for execution in finished {
+        let period = period.key(execution);
+        let keys = [Dimension::Token, Dimension::Pair, Dimension::Strategy]
+            .into_iter()
+            .map(|dimension| (dimension, dimension.key(execution, "")))
+            .chain(tags.iter().map(|tag| (Dimension::Tag, Dimension::Tag.key(execution, tag))));
+        for (dimension, key) in keys {
+            lines
+                .entry((period.clone(), dimension, key.clone()))
+                .or_insert_with(|| PnlLine::new(period.clone(), dimension, key))
+                .add(execution);
+        }
+    }
+    lines.into_values().collect()
+}
+
+/// Quote a CSV field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn to_csv(lines: &[PnlLine]) -> String {
+    let mut csv = String::from("period,dimension,key,executions,confirmed,failed,profit,fees,tips,net\n");
+    for line in lines {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{}\n",
+            line.period,
+            line.dimension.as_str(),
+            csv_field(&line.key),
+            line.executions,
+            line.confirmed,
+            line.failed,
+            line.profit,
+            line.fees,
+            line.tips,
+            line.net()
+        ));
+    }
+    csv
+}
+
+pub fn to_json(lines: &[PnlLine]) -> Result<String> {
+    #[derive(Serialize)]
+    struct Row<'a> {
+        #[serde(flatten)]
+        line: &'a PnlLine,
+        net: Decimal,
+    }
+    let rows: Vec<Row> = lines.iter().map(|line| Row { line, net: line.net() }).collect();
+    Ok(serde_json::to_string_pretty(&rows)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::DexType;
+    use crate::utils::fixtures;
+    use chrono::{TimeZone, Utc};
+
+    fn execution(day: u32, status: ExecutionStatus, profit: i64, cost: i64, tip: i64) -> ArbitrageExecution {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = |dex_type: DexType| fixtures::pool(dex_type, &sol, &usdc);
+        let opportunity = fixtures::opportunity_between(pool(DexType::Raydium), pool(DexType::Whirlpool));
+        let mut execution = ArbitrageExecution::new(opportunity);
+        execution.execution_status = status;
+        execution.execution_time = Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        execution.actual_profit = Some(Decimal::new(profit, 2));
+        execution.total_cost = Some(Decimal::new(cost, 6));
+        execution.tags.insert(TIP_TAG.to_string(), (tip * 1000).to_string());
+        execution.tags.insert("strategy".to_string(), "spread, wide".to_string());
+        execution
+    }
+
+    #[test]
+    fn test_ledger_books_fees_and_tips_per_period_and_dimension() {
+        let executions = vec![
+            execution(1, ExecutionStatus::Confirmed, 150, 20, 10),
+            // A failed bundle pays no tip
+            execution(1, ExecutionStatus::Failed, 0, 15, 0),
+            execution(2, ExecutionStatus::Confirmed, 50, 20, 10),
+            execution(2, ExecutionStatus::Submitted, 999, 0, 0),
+        ];
+
+        let daily = ledger(&executions, Period::Daily, &[]);
+        // Three dimensions on each of two days
+        assert_eq!(daily.len(), 6);
+        let first = &daily[0];
+        assert_eq!((first.period.as_str(), first.dimension, first.key.as_str()), ("2026-03-01", Dimension::Token, "USDC"));
+        assert_eq!((first.executions, first.confirmed, first.failed), (2, 1, 1));
+        assert_eq!(first.profit, Decimal::new(150, 2));
+        assert_eq!(first.tips, Decimal::new(10, 6));
+        assert_eq!(first.fees, Decimal::new(25, 6));
+        assert_eq!(first.net(), Decimal::new(1_499_965, 6));
+
+        let monthly = ledger(&executions, Period::Monthly, &[]);
+        assert_eq!(monthly.len(), 3);
+        assert_eq!(monthly[0].profit, Decimal::from(2));
+
+        // Asked-for tags get a line per value, untagged executions included
+        let mut executions = executions;
+        executions[0].tags.insert("experiment".to_string(), "a".to_string());
+        let by_tag = ledger(&executions, Period::Monthly, &["experiment".to_string()]);
+        let tagged: Vec<_> = by_tag
+            .iter()
+            .filter(|line| line.dimension == Dimension::Tag)
+            .map(|line| (line.key.as_str(), line.executions))
+            .collect();
+        assert_eq!(tagged, vec![("experiment=a", 1), ("experiment=untagged", 2)]);
+
+        let csv = to_csv(&monthly);
+        assert!(csv.lines().any(|line| line.starts_with("2026-03,strategy,\"spread, wide\",3,2,1,")));
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+use std::str::FromStr;
+use tracing::info;
+
+use crate::{
+    config::BenchmarkConfig,
+    services::oracle::{reference_price, PriceOracle},
+};
+
+/// Starting capital and the benchmark asset's price when the bot started, so its P&L can be set
+/// against simply holding that asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkBaseline {
+    pub started_at: DateTime<Utc>,
+    /// Capital in quote units
+    pub starting_capital: Decimal,
+    pub asset_mint: String,
+    pub quote_mint: String,
+    /// Asset price in quote units at the start
+    pub asset_price: Decimal,
+}
+
+impl BenchmarkBaseline {
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Some(serde_json::from_str(&data).with_context(|| format!("Invalid benchmark baseline {}", path.display()))?))
+    }
+
+    /// The recorded baseline, or a new one priced now and written to the configured path. An
+    /// existing baseline is kept across restarts so the comparison spans the whole deployment.
+    pub async fn load_or_record(config: &BenchmarkConfig, oracle: &dyn PriceOracle, now: DateTime<Utc>) -> Result<Self> {
+        let path = Path::new(&config.path);
+        if let Some(baseline) = Self::load(path)? {
+            return Ok(baseline);
+        }
+
+        let baseline = Self {
+            started_at: now,
+            starting_capital: Decimal::from_f64(config.starting_capital)
+                .filter(|capital| *capital > Decimal::ZERO)
+                .ok_or_else(|| anyhow::anyhow!("benchmark.starting_capital must be positive"))?,
+            asset_mint: config.asset_mint.clone(),
+            quote_mint: config.quote_mint.clone(),
+            asset_price: asset_price(config, oracle).await?,
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&baseline)?)?;
+        info!(
+            "Recorded benchmark baseline: {} quote units, asset at {}",
+            baseline.starting_capital, baseline.asset_price
+        );
+        Ok(baseline)
+    }
+}
+
+/// Price of the benchmark asset in the quote asset, from the oracle
+pub async fn asset_price(config: &BenchmarkConfig, oracle: &dyn PriceOracle) -> Result<Decimal> {
+    let asset = Pubkey::from_str(&config.asset_mint).with_context(|| format!("Invalid benchmark mint {}", config.asset_mint))?;
+    let quote = Pubkey::from_str(&config.quote_mint).with_context(|| format!("Invalid benchmark mint {}", config.quote_mint))?;
+    let prices = oracle.get_prices(&[asset, quote]).await?;
+    reference_price(&prices, &asset, &quote)
+        .ok_or_else(|| anyhow::anyhow!("{} has no price for {} in {}", oracle.name(), asset, quote))
+}
+
+/// The bot's capital against holding the benchmark asset since the baseline, in quote units
+#[derive(Debug, Clone, PartialEq)]
+pub struct HodlComparison {
+    pub baseline: BenchmarkBaseline,
+    pub asset_price: Decimal,
+    /// Starting capital plus the bot's net profit since the baseline
+    pub bot_value: Decimal,
+    /// Starting capital converted into the asset at the start and valued now
+    pub hodl_value: Decimal,
+}
+
+impl HodlComparison {
+    pub fn new(baseline: BenchmarkBaseline, asset_price: Decimal, net_profit: Decimal) -> Self {
+        let hodl_value = if baseline.asset_price.is_zero() {
+            baseline.starting_capital
+        } else {
+            baseline.starting_capital * asset_price / baseline.asset_price
+        };
+        Self {
+            bot_value: baseline.starting_capital + net_profit,
+            hodl_value,
+            asset_price,
+            baseline,
+        }
+    }
+
+    /// Bot value less the held value; positive when the bot beat holding
+    pub fn excess(&self) -> Decimal {
+        self.bot_value - self.hodl_value
+    }
+
+    pub fn render(&self) -> Vec<String> {
+        let ret = |value: Decimal| {
+            let capital = self.baseline.starting_capital;
+            ((value - capital) / capital * Decimal::from(100)).round_dp(2)
+        };
+        vec![
+            format!(
+                "Since {}: starting capital {}, asset {} -> {}",
+                self.baseline.started_at.format("%Y-%m-%d %H:%M UTC"),
+                self.baseline.starting_capital.round_dp(6),
+                self.baseline.asset_price.round_dp(6),
+                self.asset_price.round_dp(6)
+            ),
+            format!("  Bot:  {} ({}%)", self.bot_value.round_dp(6), ret(self.bot_value)),
+            format!("  HODL: {} ({}%)", self.hodl_value.round_dp(6), ret(self.hodl_value)),
+            format!(
+                "  Bot {} holding by {}",
+                if self.excess() >= Decimal::ZERO { "beat" } else { "trailed" },
+                self.excess().abs().round_dp(6)
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hodl_comparison_values_capital_at_the_new_price() {
+        let baseline = BenchmarkBaseline {
+            started_at: Utc::now(),
+            starting_capital: Decimal::from(1000),
+            asset_mint: "So11111111111111111111111111111111111111112".to_string(),
+            quote_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            asset_price: Decimal::from(100),
+        };
+
+        // SOL up 10%: holding made 100, the bot made 40
+        let comparison = HodlComparison::new(baseline.clone(), Decimal::from(110), Decimal::from(40));
+        assert_eq!(comparison.hodl_value, Decimal::from(1100));
+        assert_eq!(comparison.bot_value, Decimal::from(1040));
+        assert_eq!(comparison.excess(), Decimal::from(-60));
+        assert!(comparison.render()[3].contains("trailed holding by 60"));
+
+        // SOL down 20%: the same 40 beats holding by 240
+        let comparison = HodlComparison::new(baseline, Decimal::from(80), Decimal::from(40));
+        assert_eq!(comparison.excess(), Decimal::from(240));
+    }
+}
@@ -0,0 +1,254 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::ScannerConfig,
+    dex::{DexInterface, DexType},
+    models::Pool,
+    services::fee_model::SolPrices,
+};
+
+/// USDC, the asset pools are valued in; DEX APIs report 24h volume in USD as well
+pub const VALUATION_MINT: Pubkey = Pubkey::from_str_const("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+
+/// A token pair ranked by the bootstrap command
+#[derive(Debug, Clone)]
+pub struct PairCandidate {
+    pub mint_a: String,
+    pub mint_b: String,
+    pub symbol_a: String,
+    pub symbol_b: String,
+    pub pool_count: usize,
+    pub dex_count: usize,
+    /// Liquidity of the pools whose tokens could be valued, in USDC
+    pub total_tvl: Decimal,
+    pub min_pool_tvl: Decimal,
+    /// Reported 24h volume across the pools, in USD
+    pub volume_24h: Decimal,
+    /// Smallest `reserve_a + reserve_b` of the pools, the measure `scanner.min_liquidity` filters on
+    pub min_pool_reserves: Decimal,
+}
+
+impl PairCandidate {
+    pub fn pair_key(&self) -> String {
+        format!("{}/{}", self.mint_a, self.mint_b)
+    }
+}
+
+/// Result of a bootstrap run
+#[derive(Debug, Clone)]
+pub struct BootstrapReport {
+    pub candidates: Vec<PairCandidate>,
+    pub suggested: ScannerConfig,
+}
+
+#[derive(Serialize)]
+struct ConfigFragment<'a> {
+    scanner: &'a ScannerConfig,
+}
+
+/// Cold-start helper that derives a pair allowlist from live DEX data
+pub struct Bootstrapper;
+
+impl Bootstrapper {
+    /// Query all DEXes and build a suggested scanner configuration
+    pub async fn run(
+        dex_instances: &HashMap<DexType, Box<dyn DexInterface>>,
+        top_n: usize,
+    ) -> Result<BootstrapReport> {
+        let mut all_pools = HashMap::new();
+
+        for (dex_type, dex_instance) in dex_instances {
+            match dex_instance.get_pools().await {
+                Ok(pools) => {
+                    info!("Bootstrap: {} pools from {}", pools.len(), dex_instance.get_name());
+                    all_pools.insert(dex_type.clone(), pools);
+                }
+                Err(e) => warn!("Bootstrap: failed to get pools from {}: {}", dex_instance.get_name(), e),
+            }
+        }
+
+        // Volume is only reported per pool, so it is fetched for pools that could form a pair
+        let mut volumes = HashMap::new();
+        for pool in Self::group_pairs(&all_pools).values().flatten() {
+            let Some(dex_instance) = dex_instances.get(&pool.dex_type) else {
+                continue;
+            };
+            match dex_instance.get_pool_state(&pool.pool_address).await {
+                Ok(state) => {
+                    volumes.insert(pool.pool_address, state.volume_24h);
+                }
+                Err(e) => debug!("Bootstrap: no volume for pool {}: {}", pool.pool_address, e),
+            }
+        }
+
+        let candidates = Self::rank_pairs(&all_pools, &volumes, top_n);
+        let suggested = Self::suggest_config(&candidates);
+
+        Ok(BootstrapReport { candidates, suggested })
+    }
+
+    /// Active pools keyed by their pair of mints, for pairs quoted on at least two pools
+    fn group_pairs(all_pools: &HashMap<DexType, Vec<Pool>>) -> HashMap<(String, String), Vec<&Pool>> {
+        let mut grouped: HashMap<(String, String), Vec<&Pool>> = HashMap::new();
+        for pool in all_pools.values().flatten() {
+            if !pool.is_active {
+                continue;
+            }
+            let (a, b) = (pool.token_a.mint.to_string(), pool.token_b.mint.to_string());
+            let key = if a < b { (a, b) } else { (b, a) };
+            grouped.entry(key).or_default().push(pool);
+        }
+        grouped.retain(|_, pools| pools.len() >= 2);
+        grouped
+    }
+
+    /// Value of a pool's reserves in USDC. A side whose token cannot be priced counts as much as
+    /// the other, as an AMM holds equal value on both; None when neither can be priced.
+    fn pool_tvl(pool: &Pool, prices: &SolPrices) -> Option<Decimal> {
+        let value_a = prices.convert(pool.reserve_a, &pool.token_a.mint, &VALUATION_MINT);
+        let value_b = prices.convert(pool.reserve_b, &pool.token_b.mint, &VALUATION_MINT);
+        match (value_a, value_b) {
+            (Some(a), Some(b)) => Some(a + b),
+            (Some(side), None) | (None, Some(side)) => Some(side * Decimal::TWO),
+            (None, None) => None,
+        }
+    }
+
+    /// Rank pairs quoted on at least two pools by 24h volume, then by liquidity valued in USDC
+    pub fn rank_pairs(all_pools: &HashMap<DexType, Vec<Pool>>, volumes: &HashMap<Pubkey, Decimal>, top_n: usize) -> Vec<PairCandidate> {
+        let prices = SolPrices::from_pools(all_pools.values().flatten());
+
+        let mut candidates: Vec<PairCandidate> = Self::group_pairs(all_pools)
+            .into_iter()
+            .map(|((mint_a, mint_b), pools)| {
+                let symbol_of = |mint: &str| {
+                    pools
+                        .iter()
+                        .find_map(|p| {
+                            if p.token_a.mint.to_string() == mint {
+                                Some(p.token_a.symbol.clone())
+                            } else if p.token_b.mint.to_string() == mint {
+                                Some(p.token_b.symbol.clone())
+                            } else {
+                                None
+                            }
+                        })
+                        .unwrap_or_default()
+                };
+                let dexes: HashSet<&DexType> = pools.iter().map(|p| &p.dex_type).collect();
+                let tvls: Vec<Decimal> = pools.iter().filter_map(|p| Self::pool_tvl(p, &prices)).collect();
+
+                PairCandidate {
+                    symbol_a: symbol_of(&mint_a),
+                    symbol_b: symbol_of(&mint_b),
+                    pool_count: pools.len(),
+                    dex_count: dexes.len(),
+                    total_tvl: tvls.iter().sum(),
+                    min_pool_tvl: tvls.iter().min().copied().unwrap_or(Decimal::ZERO),
+                    volume_24h: pools.iter().filter_map(|p| volumes.get(&p.pool_address)).sum(),
+                    min_pool_reserves: pools.iter().map(|p| p.reserve_a + p.reserve_b).min().unwrap_or(Decimal::ZERO),
+                    mint_a,
+                    mint_b,
+                }
+            })
+            .collect();
+
+        // Pairs spanning several venues first, then the most traded, then the deepest
+        candidates.sort_by(|a, b| {
+            b.dex_count
+                .cmp(&a.dex_count)
+                .then_with(|| b.volume_24h.cmp(&a.volume_24h))
+                .then_with(|| b.total_tvl.cmp(&a.total_tvl))
+        });
+        candidates.truncate(top_n);
+        candidates
+    }
+
+    /// Derive scanner settings from ranked pairs
+    pub fn suggest_config(candidates: &[PairCandidate]) -> ScannerConfig {
+        let mut pool_floors: Vec<Decimal> = candidates.iter().map(|c| c.min_pool_reserves).collect();
+        pool_floors.sort();
+
+        // Use the lower quartile of the per-pair smallest pool as the liquidity floor
+        let min_liquidity = pool_floors
+            .get(pool_floors.len() / 4)
+            .and_then(|d| d.to_f64())
+            .unwrap_or(0.0);
+
+        ScannerConfig {
+            pair_allowlist: candidates.iter().map(|c| c.pair_key()).collect(),
+            min_liquidity,
+            ..Default::default()
+        }
+    }
+
+    /// Write the suggested settings as a TOML config fragment
+    pub fn write_fragment(report: &BootstrapReport, path: &str) -> Result<()> {
+        let mut content = String::from("# Generated by `offchain-bot bootstrap`; merge into config/local.toml\n");
+        for candidate in &report.candidates {
+            content.push_str(&format!(
+                "# {}/{}: {} pools on {} DEXes, TVL {} USDC, 24h volume {} USD\n",
+                candidate.symbol_a, candidate.symbol_b, candidate.pool_count, candidate.dex_count, candidate.total_tvl, candidate.volume_24h
+            ));
+        }
+        content.push('\n');
+        content.push_str(&toml::to_string(&ConfigFragment { scanner: &report.suggested })?);
+
+        if let Some(parent) = std::path::Path::new(path).parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Token;
+
+    #[test]
+    fn test_pairs_are_valued_in_usdc_and_ranked_by_volume() {
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "Solana".to_string(), 9);
+        let usdc = Token::new(VALUATION_MINT, "USDC".to_string(), "USD Coin".to_string(), 6);
+        let bonk = Token::new(Pubkey::new_unique(), "BONK".to_string(), "Bonk".to_string(), 5);
+        let pool = |dex_type: DexType, a: &Token, b: &Token, reserve_a: i64, reserve_b: i64| {
+            Pool::new(String::new(), dex_type, a.clone(), b.clone(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default())
+                .update_reserves(Decimal::from(reserve_a), Decimal::from(reserve_b))
+        };
+        let sol_usdc = [pool(DexType::Raydium, &sol, &usdc, 1_000, 150_000), pool(DexType::Whirlpool, &usdc, &sol, 1_500, 10)];
+        // A billion BONK outweighs every other reserve, but is worth 10 SOL
+        let bonk_sol = [pool(DexType::Raydium, &bonk, &sol, 1_000_000_000, 10), pool(DexType::Whirlpool, &bonk, &sol, 500_000_000, 5)];
+        let all_pools = HashMap::from([
+            (DexType::Raydium, vec![sol_usdc[0].clone(), bonk_sol[0].clone()]),
+            (DexType::Whirlpool, vec![sol_usdc[1].clone(), bonk_sol[1].clone()]),
+        ]);
+
+        let ranked = Bootstrapper::rank_pairs(&all_pools, &HashMap::new(), 10);
+        let by_symbol = |symbol: &str| ranked.iter().find(|c| c.symbol_a == symbol || c.symbol_b == symbol).unwrap();
+        assert_eq!(by_symbol("USDC").total_tvl, Decimal::from(303_000));
+        assert_eq!(by_symbol("BONK").total_tvl, Decimal::from(4_500));
+        assert_eq!(by_symbol("BONK").min_pool_tvl, Decimal::from(1_500));
+        assert!(ranked[0].symbol_a == "USDC" || ranked[0].symbol_b == "USDC");
+
+        // Volume comes before depth
+        let volumes = HashMap::from([(bonk_sol[0].pool_address, Decimal::from(2_000_000)), (sol_usdc[0].pool_address, Decimal::from(500_000))]);
+        let ranked = Bootstrapper::rank_pairs(&all_pools, &volumes, 10);
+        assert!(ranked[0].symbol_a == "BONK" || ranked[0].symbol_b == "BONK");
+        assert_eq!(ranked[0].volume_24h, Decimal::from(2_000_000));
+
+        // The suggested floor stays in the raw reserve sums the scanner filters on
+        assert_eq!(by_symbol("USDC").min_pool_reserves, Decimal::from(1_510));
+        let suggested = Bootstrapper::suggest_config(&ranked);
+        assert_eq!(suggested.min_liquidity, 1_510.0);
+        assert_eq!(suggested.pair_allowlist.len(), 2);
+    }
+}
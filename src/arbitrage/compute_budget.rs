@@ -0,0 +1,130 @@
+use anyhow::Result;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+
+use crate::services::solana::SolanaService;
+use crate::services::wallet::Wallets;
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = Pubkey::from_str_const("ComputeBudget111111111111111111111111111111");
+
+/// Most compute units a transaction may request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Largest serialized transaction a leader accepts
+const PACKET_DATA_SIZE: usize = 1232;
+
+/// `SetComputeUnitLimit` tag in the compute budget program's instruction enum
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+
+/// Request `units` compute units instead of the default 200k per instruction
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_LIMIT];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, Vec::new())
+}
+
+fn is_compute_unit_limit(instruction: &Instruction) -> bool {
+    instruction.program_id == COMPUTE_BUDGET_PROGRAM_ID && instruction.data.first() == Some(&SET_COMPUTE_UNIT_LIMIT)
+}
+
+/// Limit covering `units_consumed` with `margin` to spare, for state moving between simulation and landing
+pub fn compute_unit_limit(units_consumed: u64, margin: f64) -> u32 {
+    let units = (units_consumed as f64 * margin.max(1.0)).ceil();
+    units.clamp(1.0, MAX_COMPUTE_UNIT_LIMIT as f64) as u32
+}
+
+/// The instructions a legacy transaction was compiled from
+pub(crate) fn instructions(transaction: &Transaction) -> Vec<Instruction> {
+    let message = &transaction.message;
+    message
+        .instructions
+        .iter()
+        .map(|compiled| Instruction {
+            program_id: message.account_keys[compiled.program_id_index as usize],
+            accounts: compiled
+                .accounts
+                .iter()
+                .map(|&index| {
+                    let index = index as usize;
+                    AccountMeta {
+                        pubkey: message.account_keys[index],
+                        is_signer: message.is_signer(index),
+                        is_writable: message.is_maybe_writable(index, None),
+                    }
+                })
+                .collect(),
+            data: compiled.data.clone(),
+        })
+        .collect()
+}
+
+/// Re-sign `transaction` with `instructions` in place of its own, keeping its blockhash and payer
+pub(crate) fn rebuild(transaction: &Transaction, instructions: &[Instruction], wallets: &Wallets) -> Result<Transaction> {
+    let rebuilt = wallets.build_transaction(instructions, transaction.message.recent_blockhash)?;
+    if rebuilt.message.account_keys.first() != transaction.message.account_keys.first() {
+        anyhow::bail!("transaction is not paid by the configured fee payer");
+    }
+    let size = bincode::serialized_size(&rebuilt)? as usize;
+    if size > PACKET_DATA_SIZE {
+        anyhow::bail!("rebuilt transaction is {} bytes, over the {} byte limit", size, PACKET_DATA_SIZE);
+    }
+    Ok(rebuilt)
+}
+
+/// Rebuild and re-sign `transaction` requesting `units` compute units, replacing any limit it set
+pub fn with_compute_unit_limit(transaction: &Transaction, units: u32, wallets: &Wallets) -> Result<Transaction> {
+    let mut instructions = instructions(transaction);
+    instructions.retain(|instruction| !is_compute_unit_limit(instruction));
+    instructions.insert(0, set_compute_unit_limit(units));
+    rebuild(transaction, &instructions, wallets)
+}
+
+/// Simulate `transaction` and request the compute units it consumed times `margin`, so its
+/// priority fee is paid on what it uses rather than the default allowance
+pub async fn fit_compute_budget(
+    solana: &SolanaService,
+    wallets: &Wallets,
+    transaction: &Transaction,
+    margin: f64,
+) -> Result<Transaction> {
+    let simulation = solana.simulate_transaction(transaction, &[]).await?;
+    if let Some(error) = simulation.err {
+        anyhow::bail!("simulation failed: {:?}", error);
+    }
+    let units_consumed = simulation
+        .units_consumed
+        .ok_or_else(|| anyhow::anyhow!("simulation did not report compute units"))?;
+    with_compute_unit_limit(transaction, compute_unit_limit(units_consumed, margin), wallets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, signature::{Keypair, Signer}};
+    use solana_system_interface::instruction as system_instruction;
+
+    #[test]
+    fn test_replaces_the_compute_unit_limit_and_re_signs() {
+        assert_eq!(compute_unit_limit(48_000, 1.2), 57_600);
+        assert_eq!(compute_unit_limit(48_000, 0.5), 48_000);
+        assert_eq!(compute_unit_limit(1_300_000, 1.2), MAX_COMPUTE_UNIT_LIMIT);
+
+        let wallets = Wallets::new(Keypair::new(), None);
+        let owner = wallets.owner().pubkey();
+        let transfer = system_instruction::transfer(&owner, &Pubkey::new_unique(), 1);
+        let original = wallets
+            .build_transaction(&[set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT), transfer.clone()], Hash::new_unique())
+            .unwrap();
+
+        let fitted = with_compute_unit_limit(&original, 57_600, &wallets).unwrap();
+        assert_eq!(instructions(&fitted), vec![set_compute_unit_limit(57_600), transfer]);
+        assert_eq!(fitted.message.recent_blockhash, original.message.recent_blockhash);
+        assert!(fitted.verify().is_ok());
+
+        // Another wallet cannot re-sign it
+        assert!(with_compute_unit_limit(&original, 57_600, &Wallets::new(Keypair::new(), None)).is_err());
+    }
+}
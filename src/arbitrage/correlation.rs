@@ -0,0 +1,146 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::config::CorrelationConfig;
+use crate::models::ArbitrageOpportunity;
+use crate::utils::math::MathUtils;
+use crate::utils::time::TimeUtils;
+
+/// Tag listing the in-flight pairs an opportunity's spread correlates with
+pub const CORRELATED_TAG: &str = "correlated_with";
+
+/// Pairwise correlation of pair spreads; `values[i][j]` is None while either series is too thin
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CorrelationMatrix {
+    pub generated_at: DateTime<Utc>,
+    pub pairs: Vec<String>,
+    pub values: Vec<Vec<Option<Decimal>>>,
+}
+
+/// Widest spread seen per pair in each time bucket, over a rolling window of buckets.
+///
+/// A bucket without an opportunity counts as no spread, so pairs whose spreads open together correlate.
+#[derive(Debug)]
+pub struct SpreadHistory {
+    bucket: Duration,
+    buckets: i64,
+    min_samples: usize,
+    series: HashMap<String, BTreeMap<i64, Decimal>>,
+    latest: i64,
+}
+
+impl SpreadHistory {
+    pub fn new(config: &CorrelationConfig) -> Self {
+        Self {
+            bucket: Duration::seconds(config.bucket_seconds.max(1) as i64),
+            buckets: config.window_buckets.max(2) as i64,
+            min_samples: config.min_samples.max(2),
+            series: HashMap::new(),
+            latest: i64::MIN,
+        }
+    }
+
+    pub fn record(&mut self, opportunity: &ArbitrageOpportunity) {
+        let index = TimeUtils::get_window_index(opportunity.timestamp, self.bucket);
+        let spread = self.series.entry(opportunity.pair()).or_default().entry(index).or_default();
+        *spread = (*spread).max(opportunity.profit_percentage);
+
+        if index > self.latest {
+            self.latest = index;
+            let oldest = self.latest - self.buckets + 1;
+            self.series.retain(|_, series| {
+                series.retain(|bucket, _| *bucket >= oldest);
+                !series.is_empty()
+            });
+        }
+    }
+
+    /// Correlation of two pairs' spreads over the window, when both have enough buckets with a spread
+    pub fn correlation(&self, a: &str, b: &str) -> Option<Decimal> {
+        let (a, b) = (self.series.get(a)?, self.series.get(b)?);
+        if a.len() < self.min_samples || b.len() < self.min_samples {
+            return None;
+        }
+        let window = (self.latest - self.buckets + 1)..=self.latest;
+        let dense = |series: &BTreeMap<i64, Decimal>| -> Vec<Decimal> {
+            window.clone().map(|bucket| series.get(&bucket).copied().unwrap_or_default()).collect()
+        };
+        MathUtils::correlation(&dense(a), &dense(b))
+    }
+
+    pub fn matrix(&self) -> CorrelationMatrix {
+        let mut pairs: Vec<String> = self.series.keys().cloned().collect();
+        pairs.sort();
+        let values = pairs
+            .iter()
+            .map(|a| pairs.iter().map(|b| self.correlation(a, b)).collect())
+            .collect();
+        CorrelationMatrix { generated_at: Utc::now(), pairs, values }
+    }
+
+    /// Pairs among `others` whose spreads move with `pair`'s at or beyond `threshold`
+    pub fn correlated_with<'a>(
+        &self,
+        pair: &str,
+        others: impl IntoIterator<Item = &'a str>,
+        threshold: Decimal,
+    ) -> Vec<(String, Decimal)> {
+        others
+            .into_iter()
+            .filter(|other| *other != pair)
+            .filter_map(|other| {
+                let rho = self.correlation(pair, other)?;
+                (rho >= threshold).then(|| (other.to_string(), rho))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::DexType;
+    use crate::models::Token;
+    use crate::utils::fixtures;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_pairs_spreading_together_correlate() {
+        let token = |symbol: &str| Token::new(Pubkey::new_unique(), symbol.to_string(), symbol.to_string(), 9);
+        let (sol, msol, bonk, usdc) = (token("SOL"), token("MSOL"), token("BONK"), token("USDC"));
+        let start = Utc::now();
+        let opportunity = |base: &Token, minute: i64, spread: i64| {
+            let pool = fixtures::pool(DexType::Raydium, base, &usdc);
+            let mut opportunity = fixtures::opportunity_between(pool.clone(), pool);
+            opportunity.timestamp = start + Duration::minutes(minute);
+            opportunity.profit_percentage = Decimal::new(spread, 3);
+            opportunity
+        };
+        let config = CorrelationConfig { bucket_seconds: 60, window_buckets: 30, min_samples: 4, ..CorrelationConfig::default() };
+        let mut history = SpreadHistory::new(&config);
+        // SOL and mSOL spreads open in the same minutes; BONK's at other times
+        for (minute, spread) in [(0, 5), (3, 8), (7, 4), (12, 9), (20, 6)] {
+            history.record(&opportunity(&sol, minute, spread));
+            history.record(&opportunity(&msol, minute, spread + 1));
+            history.record(&opportunity(&bonk, minute + 1, spread));
+        }
+
+        let together = history.correlation("SOL/USDC", "MSOL/USDC").unwrap();
+        assert!(together > Decimal::new(9, 1));
+        assert!(history.correlation("SOL/USDC", "BONK/USDC").unwrap() < Decimal::ZERO);
+        assert_eq!(
+            history.correlated_with("SOL/USDC", ["SOL/USDC", "MSOL/USDC", "BONK/USDC"], Decimal::new(8, 1)),
+            vec![("MSOL/USDC".to_string(), together)]
+        );
+
+        let matrix = history.matrix();
+        assert_eq!(matrix.pairs, vec!["BONK/USDC", "MSOL/USDC", "SOL/USDC"]);
+        assert_eq!(matrix.values[2][1], Some(together));
+
+        // Thin series are left out until they have enough buckets
+        history.record(&opportunity(&token("JUP"), 21, 3));
+        assert_eq!(history.correlation("JUP/USDC", "SOL/USDC"), None);
+    }
+}
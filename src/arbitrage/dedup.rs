@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+use crate::models::ArbitrageOpportunity;
+
+/// What makes two detections the same opportunity: the pools traded through, in order, and the pair
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OpportunityKey {
+    pools: Vec<Pubkey>,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+}
+
+impl OpportunityKey {
+    pub fn of(opportunity: &ArbitrageOpportunity) -> Self {
+        let pools = match &opportunity.route {
+            Some(route) => route.pools.iter().map(|pool| pool.pool_address).collect(),
+            None => vec![opportunity.buy_pool.pool_address, opportunity.sell_pool.pool_address],
+        };
+        Self {
+            pools,
+            base_mint: opportunity.base_token.mint,
+            quote_mint: opportunity.quote_token.mint,
+        }
+    }
+}
+
+/// How a detection relates to the opportunities already handed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Coalesced {
+    New,
+    /// A live opportunity seen again with different prices; it keeps the earlier ID
+    Update,
+    /// A live opportunity seen again at the same prices; nothing to hand on
+    Unchanged,
+}
+
+#[derive(Debug, Clone)]
+struct Seen {
+    id: String,
+    expiry: DateTime<Utc>,
+    buy_price: Decimal,
+    sell_price: Decimal,
+    net_profit: Decimal,
+}
+
+/// Opportunities handed to the engine and still live, by key, so a spread the scanner sees every
+/// cycle reaches the engine as updates to one opportunity instead of a new ID each time
+#[derive(Debug, Default)]
+pub struct OpportunityDeduper {
+    seen: HashMap<OpportunityKey, Seen>,
+}
+
+impl OpportunityDeduper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Classify a detection, giving a repeat of a live opportunity that opportunity's ID
+    pub fn coalesce(&mut self, opportunity: &mut ArbitrageOpportunity, now: DateTime<Utc>) -> Coalesced {
+        let key = OpportunityKey::of(opportunity);
+        let seen = Seen {
+            id: opportunity.id.clone(),
+            expiry: opportunity.expiry,
+            buy_price: opportunity.buy_price,
+            sell_price: opportunity.sell_price,
+            net_profit: opportunity.net_profit,
+        };
+        match self.seen.get_mut(&key) {
+            Some(previous) if previous.expiry >= now => {
+                opportunity.id = previous.id.clone();
+                if (previous.buy_price, previous.sell_price, previous.net_profit)
+                    == (seen.buy_price, seen.sell_price, seen.net_profit)
+                {
+                    return Coalesced::Unchanged;
+                }
+                *previous = Seen { id: previous.id.clone(), ..seen };
+                Coalesced::Update
+            }
+            _ => {
+                self.seen.insert(key, seen);
+                Coalesced::New
+            }
+        }
+    }
+
+    /// Forget opportunities that have expired
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        self.seen.retain(|_, seen| seen.expiry >= now);
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::DexType;
+    use crate::utils::fixtures;
+
+    #[test]
+    fn test_repeats_keep_their_id_until_expiry() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = |dex_type: DexType| fixtures::pool(dex_type, &sol, &usdc);
+        let first = fixtures::opportunity_between(pool(DexType::Raydium), pool(DexType::Whirlpool));
+        let detect = || fixtures::opportunity_between(first.buy_pool.clone(), first.sell_pool.clone());
+        let mut deduper = OpportunityDeduper::new();
+        let now = first.timestamp;
+
+        let mut opportunity = first.clone();
+        assert_eq!(deduper.coalesce(&mut opportunity, now), Coalesced::New);
+
+        let mut repeat = detect();
+        assert_eq!(deduper.coalesce(&mut repeat, now), Coalesced::Unchanged);
+        assert_eq!(repeat.id, first.id);
+
+        let mut moved = detect();
+        moved.sell_price += Decimal::ONE;
+        assert_eq!(deduper.coalesce(&mut moved, now), Coalesced::Update);
+        assert_eq!(moved.id, first.id);
+
+        // The other direction is a different opportunity
+        let mut reverse = fixtures::opportunity_between(first.sell_pool.clone(), first.buy_pool.clone());
+        assert_eq!(deduper.coalesce(&mut reverse, now), Coalesced::New);
+
+        // Once the update expires the spread starts over under a new ID
+        let later = moved.expiry + chrono::Duration::seconds(1);
+        deduper.prune(later);
+        assert!(deduper.is_empty());
+        let mut fresh = detect();
+        assert_eq!(deduper.coalesce(&mut fresh, later), Coalesced::New);
+        assert_ne!(fresh.id, first.id);
+    }
+}
@@ -1,34 +1,115 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
-use std::collections::HashMap;
-use std::sync::Arc;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::{mpsc, RwLock};
-use tracing::{info, warn, error};
+use tracing::{debug, info, warn, error};
 
 use crate::{
-    config::AppConfig,
-    dex::{DexInterface, DexType},
+    config::{AppConfig, ArbitrageConfig, BlacklistConfig, FingerprintConfig, RestartPolicy},
+    dex::{DexHealthProber, DexHealthTracker, DexInterface, DexType},
     models::{
         ArbitrageOpportunity, ArbitrageStrategy, ArbitrageExecution, 
-        ArbitrageMetrics, Token, Pool, RiskScore, ExecutionStatus
+        ArbitrageMetrics, RiskScore, ExecutionStatus,
+        OpportunityStatus, ParameterChange, PoolSnapshot,
+    },
+    services::{
+        database::{DatabaseService, RecoveryWorker},
+        memory_store::MemoryStore,
+        metrics::MetricsRegistry,
+        pool_blacklist::PoolBlacklist,
+        fingerprints::FingerprintStore,
+        codec::CodecKind,
+        events::{BotEvent, EventBus},
+        journal::SharedJournal,
+        solana::SolanaService,
+        wallet::Wallets,
+        wallet_watcher::WalletWatcher,
+        oracle::{PriceOracle, PythOracle},
+        tenancy::{TenantRegistry, NAMESPACE_TAG, WALLET_TAG},
+        account_subscriber::AccountSubscriber,
+        balances::BalanceManager,
+        fee_model::FeeModel,
+        token_registry::TokenRegistry,
+        enrichment::{DasBackend, FILL_TAG_PREFIX},
+        metaplex::MetaplexSource,
+        alert_rules::{AlertAction, AlertRuleEngine, FiredAlert, RulesWorker},
+        maintenance::{MaintenanceSchedule, MaintenanceWorker},
+        dead_man::{DeadManSwitch, DeadManWorker},
+        archive::Compactor,
+        readiness::Readiness,
+        token_safety::TokenScreener,
+        rent_reclaimer::RentReclaimer,
+        notifier::{Notification, NotificationReceiver, NotifierWorker},
+        supervisor::TaskSupervisor,
+        keepalive::{KeepAliveRegistry, KeepAliveWorker},
+    },
+    arbitrage::{
+        correlation::{CorrelationMatrix, SpreadHistory, CORRELATED_TAG},
+        scanner::OpportunityScanner,
+        executor::{ArbitrageExecutor, BundleSubmitter, LegSubmitter, OpportunityReceiver},
+        position_watcher::{HoldingReport, PositionWatcher},
+        flattener::Flattener,
+        benchmark::BenchmarkBaseline,
+        outcome,
+        queue::{Lane, LaneQueue, PriorityQueue},
+        pair_stats::PairTracker,
+        risk::{self, RiskManager},
+        scoring::OpportunityScorer,
+        strategy::{StrategyBuilder, StrategyManager, StrategyMetrics},
+    },
+    utils::{
+        alloc_audit::AllocScope,
+        clock::{Clock, SharedClock, SystemClock},
+        panic_guard::{guard, lock_or_recover, PanicError},
     },
-    services::{database::DatabaseService, memory_store::{MemoryStore, StorageUsage}},
-    arbitrage::{scanner::OpportunityScanner, executor::ArbitrageExecutor},
 };
 
+/// Opportunities buffered between scanner and engine before the oldest are dropped
+const DETECTION_QUEUE_CAPACITY: usize = 10_000;
+
+/// Validated opportunities waiting for a free execution slot
+const EXECUTION_QUEUE_CAPACITY: usize = 1_000;
+
 pub struct ArbitrageEngine {
     config: AppConfig,
     database: Option<Arc<DatabaseService>>,
     memory_store: Arc<MemoryStore>,
-    strategies: Arc<RwLock<HashMap<String, ArbitrageStrategy>>>,
+    strategies: Arc<RwLock<StrategyManager>>,
     active_opportunities: Arc<RwLock<HashMap<String, ArbitrageOpportunity>>>,
     executions: Arc<RwLock<Vec<ArbitrageExecution>>>,
     dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
-    opportunity_sender: mpsc::Sender<ArbitrageOpportunity>,
-    opportunity_receiver: mpsc::Receiver<ArbitrageOpportunity>,
+    opportunities: Arc<LaneQueue<ArbitrageOpportunity>>,
+    opportunity_receiver: OpportunityReceiver,
     execution_sender: mpsc::Sender<ArbitrageExecution>,
     execution_receiver: mpsc::Receiver<ArbitrageExecution>,
+    metrics: MetricsRegistry,
+    paused: Arc<AtomicBool>,
+    alert_sender: mpsc::Sender<FiredAlert>,
+    alert_receiver: mpsc::Receiver<FiredAlert>,
+    notification_sender: mpsc::Sender<Notification>,
+    notification_receiver: NotificationReceiver,
+    blacklist: Arc<PoolBlacklist>,
+    fingerprints: Arc<FingerprintStore>,
+    position_reports: Arc<RwLock<Vec<HoldingReport>>>,
+    dex_health: DexHealthTracker,
+    events: EventBus,
+    supervisor: TaskSupervisor,
+    tenants: Arc<TenantRegistry>,
+    spreads: Arc<Mutex<SpreadHistory>>,
+    dead_man: Arc<DeadManSwitch>,
+    risk: Arc<RiskManager>,
+    pairs: Arc<PairTracker>,
+    scorer: OpportunityScorer,
+    fees: Arc<FeeModel>,
+    token_safety: Arc<TokenScreener>,
+    readiness: Readiness,
+    enrichment: Option<Arc<DasBackend>>,
+    clock: SharedClock,
 }
 
 impl ArbitrageEngine {
@@ -37,8 +118,9 @@ impl ArbitrageEngine {
         database: Option<Arc<DatabaseService>>,
         dex_instances: HashMap<DexType, Box<dyn DexInterface>>,
     ) -> Self {
-        let (opportunity_sender, opportunity_receiver) = mpsc::channel(10000); // Increase buffer size
         let (execution_sender, execution_receiver) = mpsc::channel(10000);
+        let (alert_sender, alert_receiver) = mpsc::channel(100);
+        let (notification_sender, notification_receiver) = mpsc::channel(1000);
         
         // Create memory store instance
         let memory_config = config.get_memory_store_config();
@@ -47,111 +129,703 @@ impl ArbitrageEngine {
             memory_config.max_executions,
         ));
 
+        let blacklist = PoolBlacklist::load(config.blacklist.clone()).unwrap_or_else(|e| {
+            // Don't persist over a file this release couldn't read
+            warn!("Failed to load pool blacklist, starting empty without persistence: {}", e);
+            PoolBlacklist::new(BlacklistConfig { path: String::new(), ..config.blacklist.clone() })
+        });
+        let fingerprints = FingerprintStore::load(config.fingerprints.clone()).unwrap_or_else(|e| {
+            warn!("Failed to load opportunity fingerprints, starting empty without persistence: {}", e);
+            FingerprintStore::new(FingerprintConfig { path: String::new(), ..config.fingerprints.clone() })
+        });
+        let dex_health = DexHealthTracker::new(config.dex_health.clone());
+        let metrics = MetricsRegistry::new();
+        let clock = SystemClock::shared();
+        let supervisor = TaskSupervisor::new(config.supervisor.clone(), metrics.clone())
+            .with_alerts(alert_sender.clone());
+        let dex_instances = Arc::new(dex_instances);
+        if config.submission.dry_run {
+            info!("Dry run: opportunities are quoted and recorded, nothing is submitted");
+        }
+        let mut token_safety = TokenScreener::new(&config.token_safety);
+        if config.token_safety.enabled {
+            match SolanaService::new(&config.solana.rpc_url) {
+                Ok(solana) => token_safety = token_safety.with_solana(Arc::new(solana.with_rpc_config(&config.solana))),
+                Err(e) => warn!("Token screening disabled, no RPC client: {}", e),
+            }
+        }
+
         Self {
+            spreads: Arc::new(Mutex::new(SpreadHistory::new(&config.correlation))),
+            dead_man: Arc::new(DeadManSwitch::new(&config.dead_man, clock.now())),
+            risk: Arc::new(RiskManager::new(&config.risk, clock.now())),
+            pairs: Arc::new(PairTracker::new(&config.pair_stats)),
+            scorer: OpportunityScorer::new(&config.scoring, dex_health.clone()),
+            fees: Arc::new(FeeModel::new(&config)),
+            token_safety: Arc::new(token_safety),
+            readiness: Readiness::new(&config.service),
+            enrichment: DasBackend::from_config(&config.enrichment).map(Arc::new),
+            clock,
             config,
             database,
             memory_store,
-            strategies: Arc::new(RwLock::new(HashMap::new())),
+            strategies: Arc::new(RwLock::new(StrategyManager::new())),
             active_opportunities: Arc::new(RwLock::new(HashMap::new())),
             executions: Arc::new(RwLock::new(Vec::new())),
-            dex_instances: Arc::new(dex_instances),
-            opportunity_sender,
-            opportunity_receiver,
+            dex_instances,
+            opportunities: Arc::new(LaneQueue::new(DETECTION_QUEUE_CAPACITY)),
+            opportunity_receiver: Arc::new(PriorityQueue::new(EXECUTION_QUEUE_CAPACITY)),
             execution_sender,
             execution_receiver,
+            metrics,
+            paused: Arc::new(AtomicBool::new(false)),
+            alert_sender,
+            alert_receiver,
+            notification_sender,
+            notification_receiver: Arc::new(tokio::sync::Mutex::new(notification_receiver)),
+            blacklist: Arc::new(blacklist),
+            fingerprints: Arc::new(fingerprints),
+            position_reports: Arc::new(RwLock::new(Vec::new())),
+            dex_health,
+            events: EventBus::default(),
+            supervisor,
+            tenants: Arc::new(TenantRegistry::default()),
+        }
+    }
+
+    /// Drive expiry, windows and cleanup from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        let memory_config = self.config.get_memory_store_config();
+        self.memory_store = Arc::new(MemoryStore::new_with_clock(
+            memory_config.max_opportunities,
+            memory_config.max_executions,
+            clock.clone(),
+        ));
+        self.dead_man = Arc::new(DeadManSwitch::new(&self.config.dead_man, clock.now()));
+        self.risk = Arc::new(RiskManager::new(&self.config.risk, clock.now()));
+        self.clock = clock;
+        self
+    }
+
+    /// Isolate strategies, wallets and budgets of the given tenants
+    pub fn with_tenants(mut self, tenants: TenantRegistry) -> Self {
+        if tenants.is_enabled() {
+            info!("Multi-tenant mode: strategies and reports are scoped per API token");
         }
+        self.tenants = Arc::new(tenants);
+        self
     }
 
     /// Start the arbitrage engine
     pub async fn start(&mut self) -> Result<()> {
         info!("Starting arbitrage engine with memory store...");
+        self.readiness.starting("Starting engine workers");
+        
+        // Bring back what the last run held before anything reads the store
+        self.restore_memory_snapshot().await;
         
         // Load strategies
         self.load_strategies().await?;
         
+        // Price the starting capital once, before anything trades
+        self.record_benchmark_baseline().await;
+        
+        // Capture events before any producer starts
+        self.start_journal()?;
+        
+        // Start the DEX health prober
+        self.start_health_prober().await?;
+        
         // Start the opportunity scanner
         self.start_opportunity_scanner().await?;
         
         // Start the executor
         self.start_executor().await?;
         
+        // Keep submit-path connections warm; endpoints register as the executor and adapters are built
+        self.start_keep_alive().await?;
+        
+        // Start the alert rules worker
+        self.start_rules_worker().await?;
+        
+        // Start the position watcher
+        self.start_position_watcher().await?;
+        
+        // Alert on funds leaving bot wallets in transactions the bot did not sign
+        self.start_wallet_watcher().await?;
+        self.start_maintenance_worker().await?;
+        self.start_dead_man_worker().await?;
+        self.start_archive_compactor().await;
+        self.start_memory_snapshotter().await;
+        self.start_rent_reclaimer().await?;
+        self.start_flattener().await?;
+        self.start_notifier().await?;
+        self.start_database_recovery().await;
+        
+        // Workers are up and connections warm; let the service manager route traffic and alerts here
+        self.readiness.ready();
+        self.readiness.start_keep_alive();
+        
         // Start the main loop
         self.main_loop().await?;
         
         Ok(())
     }
 
-    /// Load arbitrage strategies
-    async fn load_strategies(&self) -> Result<()> {
-        let mut strategies = self.strategies.write().await;
-        
-        // Create default strategy
-        let default_strategy = ArbitrageStrategy::new(
+    /// Strategy applied when no others are configured
+    pub fn default_strategy(config: &ArbitrageConfig) -> ArbitrageStrategy {
+        ArbitrageStrategy::new(
             "default".to_string(),
             "Default arbitrage strategy".to_string(),
-            Decimal::from_f64(self.config.arbitrage.min_profit_threshold).unwrap_or(Decimal::from(1) / Decimal::from(100)), // Default 1%
-            Decimal::from_f64(self.config.arbitrage.max_slippage).unwrap_or(Decimal::from(1) / Decimal::from(100)), // Default 1%
+            Decimal::from_f64(config.min_profit_threshold).unwrap_or(Decimal::from(1) / Decimal::from(100)), // Default 1%
+            Decimal::from_f64(config.max_slippage).unwrap_or(Decimal::from(1) / Decimal::from(100)), // Default 1%
             Decimal::from(5) / Decimal::from(1000), // 0.5% max price impact
             Decimal::from(1000), // Minimum liquidity 1000
-            vec![DexType::Raydium, DexType::Meteora, DexType::Whirlpool, DexType::Pump],
+            DexType::pool_venues(),
             RiskScore::Medium,
-        );
+        )
+    }
+
+    /// Make strategies of `kind` loadable; register kinds before `start` so stored definitions
+    /// naming them are built
+    pub async fn register_strategy_kind(&self, kind: &str, builder: StrategyBuilder) {
+        self.strategies.write().await.register_kind(kind, builder);
+    }
+
+    /// Load arbitrage strategies
+    async fn load_strategies(&self) -> Result<()> {
+        let mut strategies = self.strategies.write().await;
         
-        strategies.insert(default_strategy.id.clone(), default_strategy);
+        // Create default strategy
+        let default_strategy = Self::default_strategy(&self.config.arbitrage);
         
-        // Load strategies from memory store
-        let memory_strategies = self.memory_store.get_strategies().await;
-        for strategy in memory_strategies {
-            strategies.insert(strategy.id.clone(), strategy);
+        strategies.add_strategy(default_strategy)?;
+        
+        // Then those defined in [[strategies]]; a bad entry stops startup
+        for (definition, entry) in self.config.strategy_definitions()?.into_iter().zip(1..) {
+            let name = definition.name.clone();
+            strategies
+                .add_strategy(definition)
+                .with_context(|| format!("Invalid [[strategies]] entry {} ('{}')", entry, name))?;
         }
         
+        // Load strategies from memory store
+        let mut stored = self.memory_store.get_strategies().await;
+        
         // If database is available, load from database as well
         if let Some(ref db) = self.database {
             if let Ok(db_strategies) = db.get_strategies().await {
-                for strategy in db_strategies {
-                    strategies.insert(strategy.id.clone(), strategy);
-                }
+                stored.extend(db_strategies);
+            }
+        }
+        for strategy in stored {
+            let name = strategy.name.clone();
+            if let Err(e) = strategies.add_strategy(strategy) {
+                warn!("Skipping strategy '{}': {}", name, e);
             }
         }
         
+        // Strategies sizing from their record start from the executions restored so far
+        strategies.refresh_records(&self.memory_store).await;
         info!("Loaded {} strategies", strategies.len());
         Ok(())
     }
 
+    /// Start journaling engine events, if enabled
+    fn start_journal(&self) -> Result<()> {
+        if !self.config.journal.enabled {
+            return Ok(());
+        }
+        
+        let journal = SharedJournal::from_config(&self.config.journal)?;
+        journal.capture(&self.events, CodecKind::parse(&self.config.journal.codec)?.codec());
+        journal.start_flusher(tokio::time::Duration::from_millis(self.config.journal.flush_interval_ms.max(1)));
+        info!("Journaling events to {}", self.config.journal.path);
+        
+        Ok(())
+    }
+
+    /// Start the DEX health prober
+    async fn start_health_prober(&self) -> Result<()> {
+        let dex_instances = self.dex_instances.clone();
+        let tracker = self.dex_health.clone();
+        let interval = tokio::time::Duration::from_secs(self.config.dex_health.probe_interval_seconds.max(1));
+        
+        self.supervisor
+            .spawn("health_prober", RestartPolicy::Always, move || {
+                DexHealthProber::new(dex_instances.clone(), tracker.clone(), interval).start()
+            })
+            .await;
+        
+        Ok(())
+    }
+
     /// Start the opportunity scanner
     async fn start_opportunity_scanner(&self) -> Result<()> {
-        let scanner = OpportunityScanner::new(
-            self.dex_instances.clone(),
-            self.opportunity_sender.clone(),
-            self.config.clone(),
-        );
-        
-        tokio::spawn(async move {
-            if let Err(e) = scanner.start().await {
-                error!("Opportunity scanner failed: {}", e);
+        let dex_instances = self.dex_instances.clone();
+        let queue = self.opportunities.clone();
+        let config = self.config.clone();
+        let blacklist = self.blacklist.clone();
+        let health = self.dex_health.clone();
+        let fees = self.fees.clone();
+        let clock = self.clock.clone();
+        let oracle: Option<Arc<dyn PriceOracle>> = if config.oracle.enabled {
+            info!("Checking pool prices against Pyth ({} feeds)", config.oracle.feeds.len());
+            Some(Arc::new(PythOracle::new(&config.oracle)?))
+        } else {
+            None
+        };
+        // Mint accounts are read even when tokens are not named, for their program and extensions
+        let solana = Arc::new(SolanaService::new(&config.solana.rpc_url)?.with_rpc_config(&config.solana));
+        let mut registry = TokenRegistry::from_config(&config.token_registry)?.with_mint_accounts(solana.clone());
+        if config.token_registry.enabled {
+            if let Some(das) = self.enrichment.clone() {
+                info!("Naming tokens missing from the token list through {}", config.enrichment.provider);
+                registry = registry.with_source(das);
             }
-        });
+        }
+        if config.token_registry.enabled && config.token_registry.metaplex_fallback {
+            registry = registry.with_source(Arc::new(MetaplexSource::new(solana)));
+        }
+        let tokens = Arc::new(registry);
+        
+        self.supervisor
+            .spawn("scanner", RestartPolicy::Backoff, move || {
+                let mut scanner = OpportunityScanner::new(dex_instances.clone(), queue.clone(), config.clone())
+                    .with_blacklist(blacklist.clone())
+                    .with_health_tracker(health.clone())
+                    .with_fee_model(fees.clone())
+                    .with_clock(clock.clone());
+                if let Some(oracle) = &oracle {
+                    scanner = scanner.with_oracle(oracle.clone());
+                }
+                scanner = scanner.with_token_registry(tokens.clone());
+                scanner.start()
+            })
+            .await;
         
         Ok(())
     }
 
     /// Start the executor
     async fn start_executor(&self) -> Result<()> {
-        let executor = ArbitrageExecutor::new(
-            self.dex_instances.clone(),
-            self.execution_sender.clone(),
-            self.config.clone(),
+        let dex_instances = self.dex_instances.clone();
+        let sender = self.execution_sender.clone();
+        let opportunities = self.opportunity_receiver.clone();
+        let strategies = self.strategies.clone();
+        let fees = self.fees.clone();
+        let clock = self.clock.clone();
+        let config = self.config.clone();
+        let bundles = BundleSubmitter::from_config(&config)?.map(Arc::new);
+        let legs = LegSubmitter::from_config(&config)?.map(Arc::new);
+        let mut balances = None;
+        if let Some(bundles) = &bundles {
+            info!("Submitting executions as Jito bundles");
+            balances = self.start_balance_tracker(bundles.wallet(), bundles.fee_payer()).await?;
+        } else if let Some(legs) = &legs {
+            info!("Submitting executions leg by leg");
+            balances = self.start_balance_tracker(legs.wallet(), legs.fee_payer()).await?;
+        }
+        
+        self.supervisor
+            .spawn("executor", RestartPolicy::Backoff, move || {
+                let mut executor = ArbitrageExecutor::new(dex_instances.clone(), sender.clone(), config.clone())
+                    .with_opportunities(opportunities.clone())
+                    .with_strategies(strategies.clone())
+                    .with_fee_model(fees.clone())
+                    .with_clock(clock.clone());
+                if let Some(bundles) = &bundles {
+                    executor = executor.with_bundles(bundles.clone());
+                }
+                if let Some(legs) = &legs {
+                    executor = executor.with_legs(legs.clone());
+                }
+                if let Some(balances) = &balances {
+                    executor = executor.with_balances(balances.clone());
+                }
+                executor.start()
+            })
+            .await;
+        
+        Ok(())
+    }
+
+    /// Track the trading wallet's balances so the executor can size trades to them, and the fee
+    /// payer's so trades stop before it runs out of SOL for fees
+    async fn start_balance_tracker(&self, wallet: Pubkey, fee_payer: Pubkey) -> Result<Option<Arc<BalanceManager>>> {
+        if !self.config.balances.enabled {
+            return Ok(None);
+        }
+
+        let solana = Arc::new(SolanaService::new(&self.config.solana.rpc_url)?.with_rpc_config(&self.config.solana));
+        let balances = Arc::new(
+            BalanceManager::new(solana, wallet, &self.config.balances)
+                .with_wrapped_sol(self.config.submission.wrap_sol)
+                .with_fee_payer(fee_payer)
+                .with_metrics(self.metrics.clone()),
         );
+        // Without websocket or geyser ingestion balances are only re-read on the timer
+        let subscriber = AccountSubscriber::spawn(&self.config)?;
+
+        let tracker = balances.clone();
+        self.supervisor
+            .spawn("balances", RestartPolicy::Backoff, move || tracker.clone().start(subscriber.clone()))
+            .await;
+
+        Ok(Some(balances))
+    }
+
+    /// Start the keep-alive pinger for Jito and RPC endpoints
+    async fn start_keep_alive(&self) -> Result<()> {
+        let connections = &self.config.connections;
+        if connections.keep_alive_interval_seconds == 0 {
+            return Ok(());
+        }
         
-        tokio::spawn(async move {
-            if let Err(e) = executor.start().await {
-                error!("Arbitrage executor failed: {}", e);
-            }
-        });
+        let registry = KeepAliveRegistry::global().clone();
+        let metrics = self.metrics.clone();
+        let interval = tokio::time::Duration::from_secs(connections.keep_alive_interval_seconds);
+        if connections.prewarm {
+            KeepAliveWorker::new(registry.clone(), metrics.clone(), interval).prewarm().await;
+        }
+        
+        self.supervisor
+            .spawn("keep_alive", RestartPolicy::Always, move || {
+                KeepAliveWorker::new(registry.clone(), metrics.clone(), interval).start()
+            })
+            .await;
         
         Ok(())
     }
 
+    /// Start the alert rules worker
+    async fn start_rules_worker(&self) -> Result<()> {
+        let alerts_config = &self.config.alerts;
+        if !alerts_config.enabled || alerts_config.rules.is_empty() {
+            return Ok(());
+        }
+        
+        // Validate the rules up front; each restart rebuilds them from the same config
+        AlertRuleEngine::from_config(alerts_config)?;
+        let alerts_config = alerts_config.clone();
+        let metrics = self.metrics.clone();
+        let sender = self.alert_sender.clone();
+        let interval = tokio::time::Duration::from_secs(alerts_config.evaluation_interval_seconds.max(1));
+        
+        self.supervisor
+            .spawn("rules_worker", RestartPolicy::Backoff, move || {
+                let rule_engine = AlertRuleEngine::from_config(&alerts_config);
+                let (metrics, sender) = (metrics.clone(), sender.clone());
+                async move { RulesWorker::new(rule_engine?, metrics, sender, interval).start().await }
+            })
+            .await;
+        
+        Ok(())
+    }
+
+    /// Start the position watcher
+    async fn start_position_watcher(&self) -> Result<()> {
+        let watcher_config = &self.config.position_watcher;
+        if !watcher_config.enabled {
+            return Ok(());
+        }
+
+        let wallets = self.watched_wallets(&watcher_config.wallets)?;
+
+        let solana = Arc::new(
+            SolanaService::new(&self.config.solana.rpc_url)?
+                .with_rpc_config(&self.config.solana)
+                .with_account_cache(&self.config.account_cache),
+        );
+        let dex_instances = self.dex_instances.clone();
+        let watcher_config = watcher_config.clone();
+        let metrics = self.metrics.clone();
+        let reports = self.position_reports.clone();
+        let clock = self.clock.clone();
+
+        self.supervisor
+            .spawn("position_watcher", RestartPolicy::Backoff, move || {
+                PositionWatcher::new(
+                    solana.clone(),
+                    dex_instances.clone(),
+                    wallets.clone(),
+                    &watcher_config,
+                    metrics.clone(),
+                )
+                .with_reports(reports.clone())
+                .with_clock(clock.clone())
+                .start()
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Configured wallets, or the bot's own wallet and fee payer when none are listed
+    fn watched_wallets(&self, configured: &[String]) -> Result<Vec<Pubkey>> {
+        if !configured.is_empty() {
+            return Ok(configured.iter().map(|w| Pubkey::from_str(w)).collect::<Result<Vec<_>, _>>()?);
+        }
+        let wallets = Wallets::load(&self.config.wallet)?;
+        let mut pubkeys = vec![wallets.owner().pubkey()];
+        if wallets.has_separate_fee_payer() {
+            pubkeys.push(wallets.fee_payer().pubkey());
+        }
+        Ok(pubkeys)
+    }
+
+    /// Start the wallet activity watcher
+    async fn start_wallet_watcher(&self) -> Result<()> {
+        let watch_config = &self.config.wallet_watch;
+        if !watch_config.enabled {
+            return Ok(());
+        }
+
+        let wallets = self.watched_wallets(&watch_config.wallets)?;
+        let solana = Arc::new(SolanaService::new(&self.config.solana.rpc_url)?.with_rpc_config(&self.config.solana));
+        // A connection of its own, so pool subscriptions cannot crowd out the security watch
+        let subscriber = AccountSubscriber::start(&self.config.solana.ws_url, &self.config.websocket, None)?;
+        let watch_config = watch_config.clone();
+        let alerts = self.alert_sender.clone();
+
+        self.supervisor
+            .spawn("wallet_watcher", RestartPolicy::Backoff, move || {
+                WalletWatcher::new(solana.clone(), subscriber.clone(), wallets.clone(), &watch_config, alerts.clone()).start()
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Start the maintenance window worker
+    async fn start_maintenance_worker(&self) -> Result<()> {
+        let maintenance_config = &self.config.maintenance;
+        if !maintenance_config.enabled || maintenance_config.windows.is_empty() {
+            return Ok(());
+        }
+
+        // Validate the windows up front; each restart rebuilds them from the same config
+        MaintenanceSchedule::from_config(maintenance_config)?;
+        let maintenance_config = maintenance_config.clone();
+        let sender = self.alert_sender.clone();
+        let interval = tokio::time::Duration::from_secs(maintenance_config.check_interval_seconds.max(1));
+
+        self.supervisor
+            .spawn("maintenance", RestartPolicy::Backoff, move || {
+                let schedule = MaintenanceSchedule::from_config(&maintenance_config);
+                let sender = sender.clone();
+                async move { MaintenanceWorker::new(schedule?, sender, interval).start().await }
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Bring the database back, replaying buffered writes, if it is lost mid-run
+    async fn start_database_recovery(&self) {
+        let Some(database) = self.database.clone() else {
+            return;
+        };
+        let metrics = self.metrics.clone();
+        let interval = tokio::time::Duration::from_secs(self.config.database.recovery_interval_seconds.max(1));
+
+        self.supervisor
+            .spawn("database_recovery", RestartPolicy::Always, move || {
+                RecoveryWorker::new(database.clone(), metrics.clone(), interval).start()
+            })
+            .await;
+    }
+
+    /// Send heartbeats out and watch for the operator's coming in
+    async fn start_dead_man_worker(&self) -> Result<()> {
+        let dead_man_config = &self.config.dead_man;
+        if !dead_man_config.enabled {
+            return Ok(());
+        }
+
+        let dead_man_config = dead_man_config.clone();
+        let switch = self.dead_man.clone();
+        let sender = self.alert_sender.clone();
+
+        self.supervisor
+            .spawn("dead_man", RestartPolicy::Backoff, move || {
+                let worker = DeadManWorker::new(&dead_man_config, switch.clone(), sender.clone());
+                async move { worker?.start().await }
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Close empty token accounts of the owner wallet to recover their rent, if enabled
+    async fn start_rent_reclaimer(&self) -> Result<()> {
+        let reclaim_config = &self.config.rent_reclaim;
+        if !reclaim_config.enabled {
+            return Ok(());
+        }
+
+        let mut reclaim_config = reclaim_config.clone();
+        // Accounts of configured pairs are never closed, and a dry run only reports
+        reclaim_config
+            .keep_mints
+            .extend(self.config.scanner.pair_allowlist.iter().flat_map(|pair| pair.split('/')).map(str::to_string));
+        reclaim_config.report_only |= self.config.submission.dry_run;
+        let solana = Arc::new(SolanaService::new(&self.config.solana.rpc_url)?.with_rpc_config(&self.config.solana));
+        let wallets = Arc::new(Wallets::load(&self.config.wallet)?);
+        let metrics = self.metrics.clone();
+        let dex_instances = self.dex_instances.clone();
+
+        self.supervisor
+            .spawn("rent_reclaimer", RestartPolicy::Backoff, move || {
+                let reclaimer = RentReclaimer::new(&reclaim_config, solana.clone(), wallets.clone(), metrics.clone())
+                    .map(|reclaimer| reclaimer.with_pools(dex_instances.clone()));
+                async move { reclaimer?.start().await }
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Deliver notifications to Telegram and Discord, if enabled
+    async fn start_notifier(&self) -> Result<()> {
+        let notifier_config = &self.config.notifier;
+        if !notifier_config.enabled {
+            return Ok(());
+        }
+
+        // Validate channels and schedule up front; each restart rebuilds them from the same config
+        NotifierWorker::new(notifier_config, self.notification_receiver.clone(), self.executions.clone(), self.clock.clone())?;
+        let notifier_config = notifier_config.clone();
+        let receiver = self.notification_receiver.clone();
+        let executions = self.executions.clone();
+        let clock = self.clock.clone();
+
+        self.supervisor
+            .spawn("notifier", RestartPolicy::Backoff, move || {
+                let worker = NotifierWorker::new(&notifier_config, receiver.clone(), executions.clone(), clock.clone());
+                async move { worker?.start().await }
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Queue a notification for the notifier worker; dropped when it is off or falling behind
+    fn notify(&self, notification: Notification) {
+        if !self.config.notifier.enabled {
+            return;
+        }
+        if self.notification_sender.try_send(notification).is_err() {
+            warn!("Notification queue full, dropping notification");
+        }
+    }
+
+    /// Record the HODL baseline the report compares against, unless one exists or benchmarking is off
+    async fn record_benchmark_baseline(&self) {
+        if !self.config.benchmark.enabled {
+            return;
+        }
+        let recorded = match PythOracle::new(&self.config.oracle) {
+            Ok(oracle) => BenchmarkBaseline::load_or_record(&self.config.benchmark, &oracle, self.clock.now()).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = recorded {
+            warn!("Failed to record benchmark baseline: {}", e);
+        }
+    }
+
+    /// Sell leftover inventory into the base asset on schedule, if enabled
+    async fn start_flattener(&self) -> Result<()> {
+        let flatten_config = &self.config.flatten;
+        if !flatten_config.enabled {
+            return Ok(());
+        }
+
+        let flatten_config = flatten_config.clone();
+        let solana = Arc::new(SolanaService::new(&self.config.solana.rpc_url)?.with_rpc_config(&self.config.solana));
+        let wallet = Wallets::load(&self.config.wallet)?.owner().pubkey();
+        let dex_instances = self.dex_instances.clone();
+        let clock = self.clock.clone();
+        let metrics = self.metrics.clone();
+
+        self.supervisor
+            .spawn("flattener", RestartPolicy::Backoff, move || {
+                let flattener = Flattener::new(&flatten_config, solana.clone(), dex_instances.clone(), wallet, clock.clone(), metrics.clone());
+                async move { flattener?.start().await }
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Roll old journals and snapshots into daily archives, if enabled
+    async fn start_archive_compactor(&self) {
+        if !self.config.archive.enabled {
+            return;
+        }
+        let config = self.config.archive.clone();
+        let protected = vec![
+            std::path::PathBuf::from(&self.config.journal.path),
+            std::path::PathBuf::from(&self.config.database.buffer_path),
+            std::path::PathBuf::from(&self.config.memory_store.snapshot_path),
+        ];
+        let database = self.database.clone();
+
+        self.supervisor
+            .spawn("archive", RestartPolicy::Backoff, move || {
+                Compactor::new(config.clone(), protected.clone(), database.clone()).start()
+            })
+            .await;
+    }
+
+    /// Restore the memory store from its snapshot, seeding the execution history with it
+    async fn restore_memory_snapshot(&self) {
+        let path = &self.config.memory_store.snapshot_path;
+        if path.is_empty() {
+            return;
+        }
+        match self.memory_store.load_snapshot(std::path::Path::new(path)).await {
+            Ok(Some(snapshot)) => {
+                info!(
+                    "Restored memory store snapshot from {}: {} opportunities, {} executions",
+                    snapshot.taken_at,
+                    snapshot.opportunities.len(),
+                    snapshot.executions.len()
+                );
+                let mut executions = self.executions.write().await;
+                let skip = snapshot.executions.len().saturating_sub(self.config.memory_store.max_executions.max(1));
+                *executions = snapshot.executions.into_iter().skip(skip).collect();
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to restore memory store snapshot {}: {}", path, e),
+        }
+    }
+
+    /// Periodically snapshot the memory store to disk
+    async fn start_memory_snapshotter(&self) {
+        let path = std::path::PathBuf::from(&self.config.memory_store.snapshot_path);
+        if path.as_os_str().is_empty() {
+            return;
+        }
+        let memory_store = self.memory_store.clone();
+        let interval = tokio::time::Duration::from_secs(self.config.memory_store.snapshot_interval_seconds.max(1));
+
+        self.supervisor
+            .spawn("memory_snapshot", RestartPolicy::Backoff, move || {
+                let memory_store = memory_store.clone();
+                let path = path.clone();
+                async move {
+                    let mut ticker = tokio::time::interval(interval);
+                    ticker.tick().await;
+                    loop {
+                        ticker.tick().await;
+                        memory_store.save_snapshot(&path).await?;
+                    }
+                }
+            })
+            .await;
+    }
+
     /// Main loop
     async fn main_loop(&mut self) -> Result<()> {
         info!("Arbitrage engine main loop started");
@@ -159,10 +833,8 @@ impl ArbitrageEngine {
         loop {
             tokio::select! {
                 // Handle new arbitrage opportunities
-                opportunity = self.opportunity_receiver.recv() => {
-                    if let Some(opportunity) = opportunity {
-                        self.process_opportunity(opportunity).await?;
-                    }
+                (lane, opportunity) = self.opportunities.recv() => {
+                    self.process_opportunity(lane, opportunity).await?;
                 }
                 
                 // Handle execution results
@@ -172,40 +844,142 @@ impl ArbitrageEngine {
                     }
                 }
                 
+                // Handle fired alerts
+                alert = self.alert_receiver.recv() => {
+                    if let Some(alert) = alert {
+                        self.handle_alert(alert);
+                    }
+                }
+                
                 // Periodically cleanup expired opportunities
                 _ = tokio::time::sleep(tokio::time::Duration::from_secs(30)) => { // Reduce cleanup interval
                     self.cleanup_expired_opportunities().await?;
+                    if let Err(e) = self.blacklist.prune_expired().await {
+                        warn!("Failed to prune pool blacklist: {}", e);
+                    }
+                    if let Err(e) = self.fingerprints.prune_expired().await {
+                        warn!("Failed to prune opportunity fingerprints: {}", e);
+                    }
+                    self.publish_metrics().await;
                 }
             }
         }
     }
 
     /// Process a new arbitrage opportunity
-    async fn process_opportunity(&self, opportunity: ArbitrageOpportunity) -> Result<()> {
+    async fn process_opportunity(&self, lane: Lane, opportunity: ArbitrageOpportunity) -> Result<()> {
+        // Every detected spread feeds the correlation history, whether or not it is acted on
+        lock_or_recover(&self.spreads, "spread history").record(&opportunity);
+        // Strategies learn from it too, once they have judged it
+        let observed = opportunity.clone();
+        let result = self.dispatch_opportunity(lane, opportunity).await;
+        self.strategies.read().await.observe(&observed);
+        result
+    }
+
+    /// Screen a detected opportunity, pick the strategy that takes it and queue it for execution
+    async fn dispatch_opportunity(&self, mut lane: Lane, mut opportunity: ArbitrageOpportunity) -> Result<()> {
+        // Skip new opportunities while execution is paused
+        if self.is_paused() {
+            return Ok(());
+        }
+
         // Check whether the opportunity is still valid
-        if opportunity.is_expired() {
+        if opportunity.is_expired_on(self.clock.as_ref()) {
             return Ok(());
         }
 
+        // Already executed, possibly by the instance before a restart
+        if self.fingerprints.is_duplicate(&opportunity).await {
+            debug!("Skipping opportunity {} matching a recent execution", opportunity.id);
+            self.metrics.increment("duplicate_opportunities", 1.0).await;
+            return Ok(());
+        }
+
+        // Refuse rug-prone and listed mints before any strategy sees them
+        if let Some(report) = self.token_safety.screen_opportunity(&opportunity, self.clock.now()).await {
+            let issue = &report.issues[0];
+            debug!("Skipping opportunity {}: mint {} {}", opportunity.id, report.mint, issue);
+            self.metrics
+                .increment(&MetricsRegistry::labeled("token_safety_rejections", &[("issue", issue.label())]), 1.0)
+                .await;
+            return Ok(());
+        }
+
+        // Let pairs that keep failing cool down, and stop letting poor ones jump the queue
+        if let Err(rejection) = self.pairs.check(&opportunity, self.clock.now()) {
+            debug!("Skipping opportunity {} on {}: {}", opportunity.id, opportunity.pair(), rejection);
+            self.metrics
+                .increment(&MetricsRegistry::labeled("pair_rejections", &[("reason", rejection.label())]), 1.0)
+                .await;
+            return Ok(());
+        }
+        if lane == Lane::Fast && self.pairs.is_deprioritized(&opportunity) {
+            lane = Lane::Normal;
+        }
+
+        let allocations = AllocScope::start();
+        
         // Apply strategy filters
-        let strategies = self.strategies.read().await;
-        let suitable_strategy = strategies.values().find(|s| s.is_opportunity_suitable(&opportunity));
+        let suitable_strategy = match self
+            .select_strategy(&opportunity)
+            .await
+        {
+            Some(strategy) => strategy,
+            None => return Ok(()),
+        };
         
-        if suitable_strategy.is_none() {
-            return Ok(());
+        // Propagate strategy tags so executions can be grouped by experiment
+        opportunity.merge_tags(&suitable_strategy.tags);
+        if let Some(tenant) = self.tenants.tenant(&suitable_strategy.namespace) {
+            opportunity.tags.insert(NAMESPACE_TAG.to_string(), tenant.namespace.clone());
+            if let Some(wallet) = tenant.wallet {
+                opportunity.tags.insert(WALLET_TAG.to_string(), wallet.to_string());
+            }
         }
+        opportunity.tags.entry("strategy".to_string()).or_insert_with(|| suitable_strategy.name.clone());
+        opportunity.tags.insert("lane".to_string(), lane.as_str().to_string());
 
-        // Check if the same opportunity already exists
+        // A repeat of a known opportunity refreshes it rather than competing with it
         let mut active_opportunities = self.active_opportunities.write().await;
-        if active_opportunities.contains_key(&opportunity.id) {
+        if let Some(existing) = active_opportunities.get_mut(&opportunity.id) {
+            if existing.status == OpportunityStatus::Pending {
+                existing.refresh_from(&opportunity);
+                if let Err(e) = self.memory_store.update_opportunity(existing).await {
+                    warn!("Failed to update opportunity in memory store: {}", e);
+                }
+                self.metrics.increment("opportunity_updates", 1.0).await;
+            }
             return Ok(());
         }
 
         // Validate profitability
-        if !opportunity.is_profitable(Decimal::from_f64(self.config.arbitrage.min_profit_threshold).unwrap_or(Decimal::from(1) / Decimal::from(100))) {
+        if !self.clears_profit_threshold(&opportunity) {
             return Ok(());
         }
 
+        // Consult the circuit breaker before anything is dispatched
+        let trade_size = Decimal::from_f64(self.config.submission.trade_size).unwrap_or(Decimal::ONE);
+        let in_flight: Decimal = active_opportunities
+            .values()
+            .filter(|o| matches!(o.status, OpportunityStatus::Pending | OpportunityStatus::Executing))
+            .map(|o| risk::capital(o, trade_size))
+            .sum();
+        if let Err(breach) = self.risk.check(in_flight, risk::capital(&opportunity, trade_size), self.clock.now()) {
+            debug!("Skipping opportunity {}: {}", opportunity.id, breach);
+            self.metrics
+                .increment(&MetricsRegistry::labeled("risk_rejections", &[("limit", breach.label())]), 1.0)
+                .await;
+            return Ok(());
+        }
+
+        if !self.admit(&mut active_opportunities, lane).await {
+            return Ok(());
+        }
+        self.flag_correlated(&mut opportunity, &active_opportunities).await;
+        let score = self.scorer.score(&opportunity, self.clock.now()).await;
+        opportunity.tags.insert("score".to_string(), format!("{:.6}", score));
+
         // Add to active opportunities
         active_opportunities.insert(opportunity.id.clone(), opportunity.clone());
         
@@ -219,37 +993,189 @@ impl ArbitrageEngine {
             if let Err(e) = db.save_opportunity(&opportunity).await {
                 warn!("Failed to save opportunity to database: {}", e);
             }
+            if self.config.database.capture_pool_snapshots {
+                for pool in [&opportunity.buy_pool, &opportunity.sell_pool] {
+                    if let Err(e) = db.save_pool_snapshot(&PoolSnapshot::from_pool(pool, opportunity.timestamp)).await {
+                        warn!("Failed to save pool snapshot to database: {}", e);
+                    }
+                }
+            }
         }
 
         info!("New arbitrage opportunity: {}", opportunity);
+        self.events.publish(BotEvent::opportunity(&opportunity));
         
-        // Send to executor
-        if let Err(e) = self.execution_sender.send(ArbitrageExecution::new(opportunity)).await {
-            error!("Failed to send opportunity to executor: {}", e);
+        // Hand to the executor, fast lane then best score first; quoting and submission run off the detection path
+        if let Some(dropped) = self.opportunity_receiver.push(lane, score, opportunity) {
+            debug!("Execution queue full, dropped lowest-scored opportunity {}", dropped.id);
+        }
+        
+        if let Some(stats) = allocations.finish() {
+            debug!(allocations = stats.allocations, bytes = stats.bytes, "Submit path allocations");
+            self.metrics.set("submit_allocations", stats.allocations as f64).await;
+            self.metrics.set("submit_allocated_bytes", stats.bytes as f64).await;
         }
 
         Ok(())
     }
 
+    /// Whether net profit, as a fraction of the input, exceeds `min_profit_threshold`
+    fn clears_profit_threshold(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        let threshold = Decimal::from_f64(self.config.arbitrage.min_profit_threshold).unwrap_or(Decimal::from(1) / Decimal::from(100));
+        opportunity.clears_profit_threshold(threshold)
+    }
+
+    /// Make room for one more in-flight opportunity. At `max_concurrent_opportunities`, a fast-lane
+    /// opportunity cancels the oldest normal-lane one still waiting in the execution queue, taking
+    /// it out so it never trades; anything else is turned away.
+    async fn admit(&self, active_opportunities: &mut HashMap<String, ArbitrageOpportunity>, lane: Lane) -> bool {
+        let capacity = self.config.arbitrage.max_concurrent_opportunities.max(1);
+        let in_flight = active_opportunities
+            .values()
+            .filter(|o| matches!(o.status, OpportunityStatus::Pending | OpportunityStatus::Executing))
+            .count();
+        if in_flight < capacity {
+            return true;
+        }
+
+        let mut candidates: Vec<&ArbitrageOpportunity> = match lane {
+            Lane::Fast => active_opportunities
+                .values()
+                .filter(|o| o.status == OpportunityStatus::Pending && o.has_tag("lane", Lane::Normal.as_str()))
+                .collect(),
+            Lane::Normal => Vec::new(),
+        };
+        candidates.sort_by_key(|o| o.timestamp);
+        // One the executor has already taken is under way and can no longer be preempted
+        let victim = candidates
+            .into_iter()
+            .find_map(|candidate| self.opportunity_receiver.remove(|queued| queued.id == candidate.id))
+            .map(|o| o.id);
+        let Some(victim) = victim else {
+            self.metrics
+                .increment(&MetricsRegistry::labeled("lane_rejections", &[("lane", lane.as_str())]), 1.0)
+                .await;
+            return false;
+        };
+
+        if let Some(opportunity) = active_opportunities.get_mut(&victim) {
+            opportunity.update_status(OpportunityStatus::Cancelled);
+        }
+        if let Err(e) = self.memory_store.update_opportunity_status(&victim, OpportunityStatus::Cancelled).await {
+            warn!("Failed to update preempted opportunity status in memory store: {}", e);
+        }
+        debug!("Preempted normal-lane opportunity {} for fast-lane work", victim);
+        self.metrics.increment("lane_preemptions", 1.0).await;
+        true
+    }
+
+    /// Warn about and tag an opportunity whose spread moves with that of a pair already in flight,
+    /// since both then ride the same underlying move
+    async fn flag_correlated(&self, opportunity: &mut ArbitrageOpportunity, active_opportunities: &HashMap<String, ArbitrageOpportunity>) {
+        let in_flight: HashSet<String> = active_opportunities
+            .values()
+            .filter(|o| matches!(o.status, OpportunityStatus::Pending | OpportunityStatus::Executing))
+            .map(ArbitrageOpportunity::pair)
+            .collect();
+        if in_flight.is_empty() {
+            return;
+        }
+        let threshold = Decimal::from_f64(self.config.correlation.warn_threshold).unwrap_or(Decimal::ONE);
+        let pair = opportunity.pair();
+        let correlated = lock_or_recover(&self.spreads, "spread history").correlated_with(
+            &pair,
+            in_flight.iter().map(String::as_str),
+            threshold,
+        );
+        if correlated.is_empty() {
+            return;
+        }
+
+        let names: Vec<String> = correlated.iter().map(|(other, rho)| format!("{} ({:.2})", other, rho)).collect();
+        warn!("Opportunity {} on {} is correlated with in-flight {}", opportunity.id, pair, names.join(", "));
+        self.metrics.increment("correlated_executions", 1.0).await;
+        opportunity.tags.insert(
+            CORRELATED_TAG.to_string(),
+            correlated.into_iter().map(|(other, _)| other).collect::<Vec<_>>().join(","),
+        );
+    }
+
+    /// Tag a confirmed execution with each hop's fill, decoded by the enrichment provider
+    async fn tag_fills(&self, execution: &mut ArbitrageExecution) {
+        let (Some(das), Some(signature)) = (&self.enrichment, &execution.transaction_signature) else {
+            return;
+        };
+        match das.get_swap_events(signature).await {
+            Ok(events) => {
+                for (hop, event) in events.iter().enumerate() {
+                    execution.tags.insert(format!("{}{}", FILL_TAG_PREFIX, hop), event.fill_tag());
+                }
+            }
+            Err(e) => warn!("Failed to decode fills of execution {}: {}", execution.id, e),
+        }
+    }
+
     /// Process an execution result
-    async fn process_execution(&self, execution: ArbitrageExecution) -> Result<()> {
+    async fn process_execution(&self, mut execution: ArbitrageExecution) -> Result<()> {
+        if execution.execution_status == ExecutionStatus::Failed {
+            if let Some(error) = execution.error_message.as_deref() {
+                if execution.failure_reason.is_none() {
+                    execution.failure_reason = outcome::classify_failure(error, &[]);
+                }
+                if execution.program_error.is_none() {
+                    execution.program_error = crate::dex::errors::decode_failure(error, &[]);
+                }
+            }
+            if let Some(program_error) = &execution.program_error {
+                warn!("Execution {} failed with {}", execution.id, program_error);
+            }
+            let label = execution.failure_reason.map(|r| r.label()).unwrap_or(outcome::UNCLASSIFIED);
+            self.metrics
+                .increment(&MetricsRegistry::labeled("execution_failures", &[("reason", label)]), 1.0)
+                .await;
+        }
+
+        if execution.execution_status == ExecutionStatus::Confirmed {
+            self.tag_fills(&mut execution).await;
+        }
+
+        // A dry run traded nothing: it is logged and published, but no record, limit or statistic
+        // counts it
+        if execution.execution_status == ExecutionStatus::Simulated {
+            if let Some(opportunity) = self.active_opportunities.write().await.get_mut(&execution.opportunity.id) {
+                opportunity.update_status(crate::models::OpportunityStatus::Completed);
+            }
+            info!(
+                "Dry run {} would have made {} for {} SOL in fees",
+                execution.id,
+                execution.actual_profit.unwrap_or_default(),
+                execution.total_cost.unwrap_or_default()
+            );
+            self.metrics.increment("dry_run_executions", 1.0).await;
+            self.events.publish(BotEvent::execution(&execution));
+            return Ok(());
+        }
+
         // Update active opportunity status
-        let mut active_opportunities = self.active_opportunities.write().await;
-        if let Some(opportunity) = active_opportunities.get_mut(&execution.opportunity.id) {
-            // Update opportunity status based on execution status
-            let new_status = match execution.execution_status {
-                ExecutionStatus::Confirmed => crate::models::OpportunityStatus::Completed,
-                ExecutionStatus::Failed => crate::models::OpportunityStatus::Failed,
-                ExecutionStatus::Cancelled => crate::models::OpportunityStatus::Expired,
-                _ => crate::models::OpportunityStatus::Pending,
-            };
-            opportunity.update_status(new_status);
+        {
+            let mut active_opportunities = self.active_opportunities.write().await;
+            if let Some(opportunity) = active_opportunities.get_mut(&execution.opportunity.id) {
+                // Update opportunity status based on execution status
+                let new_status = match execution.execution_status {
+                    ExecutionStatus::Confirmed => crate::models::OpportunityStatus::Completed,
+                    ExecutionStatus::Failed => crate::models::OpportunityStatus::Failed,
+                    ExecutionStatus::Cancelled => crate::models::OpportunityStatus::Expired,
+                    _ => crate::models::OpportunityStatus::Pending,
+                };
+                opportunity.update_status(new_status);
+            }
         }
 
         // Save execution result to memory store (primary storage)
         if let Err(e) = self.memory_store.save_execution(&execution).await {
             warn!("Failed to save execution to memory store: {}", e);
         }
+        self.strategies.read().await.refresh_records(&self.memory_store).await;
         
         // If database is available, also save to database (backup)
         if let Some(ref db) = self.database {
@@ -258,21 +1184,244 @@ impl ArbitrageEngine {
             }
         }
 
-        // Add to execution history
-        let mut executions = self.executions.write().await;
-        executions.push(execution.clone());
+        if matches!(execution.execution_status, ExecutionStatus::Submitted | ExecutionStatus::Confirmed) {
+            if let Err(e) = self.fingerprints.record(&execution.opportunity).await {
+                warn!("Failed to persist opportunity fingerprint: {}", e);
+            }
+        }
+
+        if let Some(breach) = self.risk.record(&execution, &self.fees.sol_prices(), self.clock.now()) {
+            if let Err(e) = self.alert_sender.send(self.risk.alert(&breach, self.clock.now())).await {
+                warn!("Failed to dispatch risk alert: {}", e);
+            }
+        }
+
+        self.pairs.record(&execution, self.clock.now());
+
+        // Track failing pools for the blacklist
+        if let Err(e) = self.blacklist.record_execution(&execution, self.clock.now()).await {
+            warn!("Failed to update pool blacklist: {}", e);
+        }
+
+        // Add to execution history, keeping the same bound as the memory store
+        {
+            let mut executions = self.executions.write().await;
+            executions.push(execution.clone());
+            let max_executions = self.config.memory_store.max_executions.max(1);
+            if executions.len() > max_executions {
+                let excess = executions.len() - max_executions;
+                executions.drain(..excess);
+            }
+        }
 
         info!("Execution completed: {} - {:?}", execution.id, execution.execution_status);
+        self.events.publish(BotEvent::execution(&execution));
+        if matches!(execution.execution_status, ExecutionStatus::Confirmed | ExecutionStatus::Failed) {
+            self.notify(Notification::Execution(Box::new(execution)));
+        }
+        
+        self.publish_metrics().await;
 
         Ok(())
     }
 
+    /// Handle an alert fired by the rules worker
+    fn handle_alert(&self, alert: FiredAlert) {
+        self.events.publish(BotEvent::alert(&alert));
+        match &alert.action {
+            AlertAction::Notify(channel) => {
+                warn!("[alert -> {}] {}", channel, alert.message);
+                self.notify(Notification::Alert { channel: channel.clone(), alert: alert.clone() });
+            }
+            AlertAction::Pause => {
+                warn!("[alert] {} - pausing execution", alert.message);
+                self.pause();
+            }
+            AlertAction::Resume => {
+                info!("[alert] {} - resuming execution", alert.message);
+                self.resume();
+            }
+            AlertAction::Log => {
+                info!("[alert] {}", alert.message);
+            }
+        }
+    }
+
+    /// First active strategy accepting the opportunity, skipping tenants that spent their daily loss budget.
+    ///
+    /// A strategy whose evaluation panics is disabled and reported instead of taking down the loop.
+    async fn select_strategy(&self, opportunity: &ArbitrageOpportunity) -> Option<ArbitrageStrategy> {
+        let exhausted = if self.tenants.is_enabled() {
+            self.tenants.exhausted_budgets(&self.executions.read().await, self.clock.now())
+        } else {
+            HashSet::new()
+        };
+        let mut panicked = Vec::new();
+        let selected = {
+            let mut strategies = self.strategies.write().await;
+            let mut evaluated = Vec::new();
+            let mut selected = None;
+            for (definition, strategy) in strategies.active().filter(|(s, _)| !exhausted.contains(&s.namespace)) {
+                match guard(&format!("Strategy '{}'", definition.name), || strategy.should_execute(opportunity)) {
+                    Ok(true) => {
+                        selected = Some(definition.clone());
+                        break;
+                    }
+                    Ok(false) => evaluated.push(definition.id.clone()),
+                    Err(e) => panicked.push((definition.id.clone(), e)),
+                }
+            }
+            for id in evaluated {
+                strategies.record_evaluation(&id, false);
+            }
+            if let Some(strategy) = &selected {
+                strategies.record_evaluation(&strategy.id, true);
+            }
+            selected
+        };
+        
+        for (strategy_id, error) in panicked {
+            self.disable_strategy(&strategy_id, error).await;
+        }
+        selected
+    }
+
+    /// Deactivate a strategy after a panic and raise an alert
+    async fn disable_strategy(&self, strategy_id: &str, error: PanicError) {
+        let name = {
+            let mut strategies = self.strategies.write().await;
+            strategies.record_panic(strategy_id);
+            match strategies.set_active(strategy_id, false, self.clock.now()) {
+                Some(name) => name,
+                None => return,
+            }
+        };
+        error!("{}; strategy disabled", error);
+        
+        self.metrics
+            .increment(&MetricsRegistry::labeled("strategy_panics", &[("strategy", &name)]), 1.0)
+            .await;
+        let alert = FiredAlert {
+            rule_name: format!("strategy:{}", name),
+            action: AlertAction::Notify(self.config.supervisor.alert_channel.clone()),
+            message: format!("{}; strategy disabled", error),
+            value: 1.0,
+            timestamp: self.clock.now(),
+        };
+        if self.alert_sender.try_send(alert).is_err() {
+            warn!("Alert queue full, dropped alert for strategy '{}'", name);
+        }
+    }
+
+    /// Publish engine gauges to the metrics registry
+    async fn publish_metrics(&self) {
+        let since = self.clock.now() - chrono::Duration::hours(1);
+        let (executions_1h, successful_1h) = {
+            let executions = self.executions.read().await;
+            let recent: Vec<&ArbitrageExecution> = executions
+                .iter()
+                .filter(|e| e.execution_time >= since)
+                .collect();
+            let successful = recent
+                .iter()
+                .filter(|e| e.execution_status == ExecutionStatus::Confirmed)
+                .count();
+            (recent.len(), successful)
+        };
+        let active_count = self.active_opportunities.read().await.len();
+        
+        // Per-tag execution counts, exported as labelled gauges
+        let mut tagged_counts: HashMap<String, f64> = HashMap::new();
+        for execution in self.executions.read().await.iter().filter(|e| e.execution_time >= since) {
+            for (key, value) in &execution.tags {
+                let name = MetricsRegistry::labeled("executions_1h", &[(key.as_str(), value.as_str())]);
+                *tagged_counts.entry(name).or_insert(0.0) += 1.0;
+            }
+        }
+        // Tags that left the window drop out instead of holding their last count
+        self.metrics.set_family("executions_1h", tagged_counts).await;
+        
+        self.metrics.set("executions_1h", executions_1h as f64).await;
+        if executions_1h > 0 {
+            self.metrics.set("success_rate_1h", successful_1h as f64 / executions_1h as f64).await;
+        }
+        self.metrics.set("active_opportunities", active_count as f64).await;
+        self.metrics.set("execution_history", self.executions.read().await.len() as f64).await;
+        self.metrics.set("detection_queue_depth", self.opportunities.len() as f64).await;
+        self.metrics.set("detection_queue_dropped", self.opportunities.dropped() as f64).await;
+        self.metrics.set("fast_lane_depth", self.opportunities.lane_len(Lane::Fast) as f64).await;
+        self.metrics.set("execution_queue_depth", self.opportunity_receiver.len() as f64).await;
+        self.metrics.set("execution_queue_dropped", self.opportunity_receiver.dropped() as f64).await;
+        self.metrics.set("paused", if self.is_paused() { 1.0 } else { 0.0 }).await;
+        self.metrics.set("risk_halted", if self.risk.halted().is_some() { 1.0 } else { 0.0 }).await;
+    }
+
+    /// Pause execution of new opportunities
+    pub fn pause(&self) {
+        if !self.paused.swap(true, Ordering::SeqCst) {
+            self.events.publish(BotEvent::engine_state(true));
+        }
+    }
+
+    /// Resume execution of new opportunities, lifting any circuit-breaker halt
+    pub fn resume(&self) {
+        self.risk.reset();
+        if self.paused.swap(false, Ordering::SeqCst) {
+            self.events.publish(BotEvent::engine_state(false));
+        }
+    }
+
+    /// Whether execution is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Get the DEX adapters the engine trades on
+    pub fn dex_instances(&self) -> Arc<HashMap<DexType, Box<dyn DexInterface>>> {
+        self.dex_instances.clone()
+    }
+
+    /// Get the metrics registry
+    pub fn metrics(&self) -> MetricsRegistry {
+        self.metrics.clone()
+    }
+
+    /// Get the registry of supervised background tasks
+    pub fn supervisor(&self) -> TaskSupervisor {
+        self.supervisor.clone()
+    }
+
+    /// Get the engine event bus
+    pub fn events(&self) -> EventBus {
+        self.events.clone()
+    }
+
+    /// Get the per-DEX health tracker
+    pub fn dex_health(&self) -> DexHealthTracker {
+        self.dex_health.clone()
+    }
+
+    /// Get the latest position watcher reports
+    pub async fn get_position_reports(&self) -> Vec<HoldingReport> {
+        self.handle().get_position_reports().await
+    }
+
+    /// Get the pool blacklist
+    pub fn blacklist(&self) -> Arc<PoolBlacklist> {
+        self.blacklist.clone()
+    }
+
+    /// Get the per-pair cooldowns and hit rates
+    pub fn pair_stats(&self) -> Arc<PairTracker> {
+        self.pairs.clone()
+    }
+
     /// Cleanup expired arbitrage opportunities
     async fn cleanup_expired_opportunities(&self) -> Result<()> {
         let mut active_opportunities = self.active_opportunities.write().await;
         let expired_ids: Vec<String> = active_opportunities
             .iter()
-            .filter(|(_, opportunity)| opportunity.is_expired())
+            .filter(|(_, opportunity)| opportunity.is_expired_on(self.clock.as_ref()))
             .map(|(id, _)| id.clone())
             .collect();
 
@@ -304,50 +1453,129 @@ impl ArbitrageEngine {
 
     /// Get arbitrage metrics
     pub async fn get_metrics(&self) -> Result<ArbitrageMetrics> {
-        let active_opportunities = self.active_opportunities.read().await;
+        let total_opportunities = self.active_opportunities.read().await.len() as u64;
         let executions = self.executions.read().await;
-        
-        let total_opportunities = active_opportunities.len() as u64;
-        let executed_opportunities = executions.len() as u64;
-        let successful_executions = executions
-            .iter()
-            .filter(|e| e.execution_status == crate::models::ExecutionStatus::Confirmed)
-            .count() as u64;
-        
-        let total_profit: Decimal = executions
-            .iter()
-            .filter_map(|e| e.actual_profit)
-            .sum();
-        
-        let total_fees: Decimal = executions
+        Ok(outcome::summarize(total_opportunities, &executions))
+    }
+
+    /// Cloneable access to strategies, opportunities and executions for callers outside the main loop
+    pub fn handle(&self) -> EngineHandle {
+        EngineHandle {
+            memory_store: self.memory_store.clone(),
+            database: self.database.clone(),
+            strategies: self.strategies.clone(),
+            active_opportunities: self.active_opportunities.clone(),
+            executions: self.executions.clone(),
+            events: self.events.clone(),
+            tenants: self.tenants.clone(),
+            spreads: self.spreads.clone(),
+            dead_man: self.dead_man.clone(),
+            position_reports: self.position_reports.clone(),
+            clock: self.clock.clone(),
+        }
+    }
+
+    /// Add a new arbitrage strategy
+    pub async fn add_strategy(&self, strategy: ArbitrageStrategy) -> Result<()> {
+        self.handle().add_strategy(strategy).await
+    }
+
+    /// Update a strategy
+    pub async fn update_strategy(&self, strategy: ArbitrageStrategy) -> Result<()> {
+        self.handle().update_strategy(strategy).await
+    }
+
+    /// Remove a strategy
+    pub async fn remove_strategy(&self, strategy_id: &str) -> Result<()> {
+        self.handle().remove_strategy(strategy_id).await
+    }
+
+    /// Get all active arbitrage opportunities
+    pub async fn get_active_opportunities(&self) -> Vec<ArbitrageOpportunity> {
+        self.handle().get_active_opportunities().await
+    }
+
+    /// Get execution history
+    pub async fn get_execution_history(&self, limit: Option<usize>) -> Vec<ArbitrageExecution> {
+        self.handle().get_execution_history(limit).await
+    }
+
+    /// Get executions carrying a specific tag
+    pub async fn get_executions_by_tag(&self, key: &str, value: &str) -> Vec<ArbitrageExecution> {
+        let executions = self.executions.read().await;
+        executions
             .iter()
-            .filter_map(|e| e.total_cost)
-            .sum();
-        
-        let net_profit = total_profit - total_fees;
-        let success_rate = if executed_opportunities > 0 {
-            Decimal::from(successful_executions) / Decimal::from(executed_opportunities)
-        } else {
-            Decimal::ZERO
-        };
+            .filter(|e| e.has_tag(key, value))
+            .cloned()
+            .collect()
+    }
 
-        Ok(ArbitrageMetrics {
-            total_opportunities,
-            executed_opportunities,
-            successful_executions,
-            total_profit,
-            total_fees,
-            net_profit,
-            success_rate,
-            average_execution_time: None, // Would need to calculate from execution data
-            timestamp: chrono::Utc::now(),
-        })
+    /// Get opportunities carrying a specific tag
+    pub async fn get_opportunities_by_tag(&self, key: &str, value: &str) -> Vec<ArbitrageOpportunity> {
+        self.memory_store.get_opportunities_by_tag(key, value).await
+    }
+
+    /// Get memory store usage
+    pub async fn get_storage_usage(&self) -> crate::services::StorageUsage {
+        self.handle().get_storage_usage().await
+    }
+
+    /// Search arbitrage opportunities (using memory store fast search)
+    pub async fn search_opportunities(
+        &self,
+        min_profit: Option<Decimal>,
+        max_risk: Option<RiskScore>,
+        dex_types: Option<Vec<DexType>>,
+    ) -> Vec<ArbitrageOpportunity> {
+        self.memory_store.search_opportunities(min_profit, max_risk, dex_types).await
+    }
+}
+
+/// Shared view of a running engine's strategies, opportunities and executions.
+///
+/// `ArbitrageEngine::start` holds the engine for as long as it runs, so the control API works through this.
+#[derive(Clone)]
+pub struct EngineHandle {
+    memory_store: Arc<MemoryStore>,
+    database: Option<Arc<DatabaseService>>,
+    strategies: Arc<RwLock<StrategyManager>>,
+    active_opportunities: Arc<RwLock<HashMap<String, ArbitrageOpportunity>>>,
+    executions: Arc<RwLock<Vec<ArbitrageExecution>>>,
+    events: EventBus,
+    tenants: Arc<TenantRegistry>,
+    spreads: Arc<Mutex<SpreadHistory>>,
+    dead_man: Arc<DeadManSwitch>,
+    position_reports: Arc<RwLock<Vec<HoldingReport>>>,
+    clock: SharedClock,
+}
+
+impl EngineHandle {
+    /// Get all strategies, active or not
+    pub async fn get_strategies(&self) -> Vec<ArbitrageStrategy> {
+        let mut strategies: Vec<ArbitrageStrategy> = self.strategies.read().await.definitions().cloned().collect();
+        strategies.sort_by(|a, b| a.id.cmp(&b.id));
+        strategies
+    }
+
+    /// Tenants sharing the engine
+    pub fn tenants(&self) -> Arc<TenantRegistry> {
+        self.tenants.clone()
+    }
+
+    /// Get a strategy by id
+    pub async fn get_strategy(&self, strategy_id: &str) -> Option<ArbitrageStrategy> {
+        self.strategies.read().await.get_strategy(strategy_id).cloned()
+    }
+
+    /// Evaluation counts of every strategy, by id
+    pub async fn get_strategy_metrics(&self) -> HashMap<String, StrategyMetrics> {
+        self.strategies.read().await.all_metrics()
     }
 
     /// Add a new arbitrage strategy
     pub async fn add_strategy(&self, strategy: ArbitrageStrategy) -> Result<()> {
         let mut strategies = self.strategies.write().await;
-        strategies.insert(strategy.id.clone(), strategy.clone());
+        strategies.add_strategy(strategy.clone())?;
         
         // Save to memory store
         if let Err(e) = self.memory_store.save_strategy(&strategy).await {
@@ -368,7 +1596,7 @@ impl ArbitrageEngine {
     /// Update a strategy
     pub async fn update_strategy(&self, strategy: ArbitrageStrategy) -> Result<()> {
         let mut strategies = self.strategies.write().await;
-        strategies.insert(strategy.id.clone(), strategy.clone());
+        strategies.add_strategy(strategy.clone())?;
         
         // Update memory store
         if let Err(e) = self.memory_store.update_strategy(&strategy).await {
@@ -386,10 +1614,19 @@ impl ArbitrageEngine {
         Ok(())
     }
 
+    /// Log a runtime strategy change with its previous and new values and publish it, so the
+    /// journal keeps an audit trail
+    pub fn record_strategy_change(&self, strategy_id: &str, changes: Vec<ParameterChange>, forced: bool) {
+        for change in &changes {
+            info!(target: "audit", strategy = strategy_id, forced, "Strategy parameter changed: {}", change);
+        }
+        self.events.publish(BotEvent::strategy_updated(strategy_id, changes, forced));
+    }
+
     /// Remove a strategy
     pub async fn remove_strategy(&self, strategy_id: &str) -> Result<()> {
         let mut strategies = self.strategies.write().await;
-        if let Some(strategy) = strategies.remove(strategy_id) {
+        if let Some(strategy) = strategies.remove_strategy(strategy_id) {
             // Delete from memory store
             if let Err(e) = self.memory_store.delete_strategy(strategy_id).await {
                 warn!("Failed to delete strategy from memory store: {}", e);
@@ -429,24 +1666,39 @@ impl ArbitrageEngine {
         result
     }
 
+    /// Record an inbound heartbeat from the operator's system
+    pub fn heartbeat(&self) {
+        self.dead_man.beat(self.clock.now());
+    }
+
+    /// Correlation of pair spreads over the rolling window
+    pub fn spread_correlations(&self) -> CorrelationMatrix {
+        lock_or_recover(&self.spreads, "spread history").matrix()
+    }
+
+    /// Get every opportunity the memory store still holds, active or not
+    pub async fn get_opportunity_history(&self) -> Vec<ArbitrageOpportunity> {
+        self.memory_store.search_opportunities(None, None, None).await
+    }
+
     /// Get memory store usage
     pub async fn get_storage_usage(&self) -> crate::services::StorageUsage {
         self.memory_store.get_storage_usage().await
     }
 
-    /// Search arbitrage opportunities (using memory store fast search)
-    pub async fn search_opportunities(
-        &self,
-        min_profit: Option<Decimal>,
-        max_risk: Option<RiskScore>,
-        dex_types: Option<Vec<DexType>>,
-    ) -> Vec<ArbitrageOpportunity> {
-        self.memory_store.search_opportunities(min_profit, max_risk, dex_types).await
+    /// Latest position watcher reports; empty until the watcher's first pass
+    pub async fn get_position_reports(&self) -> Vec<HoldingReport> {
+        self.position_reports.read().await.clone()
     }
 }
 
 impl ArbitrageExecution {
-    fn new(opportunity: ArbitrageOpportunity) -> Self {
+    pub(crate) fn new(opportunity: ArbitrageOpportunity) -> Self {
+        Self::new_with_clock(opportunity, &SystemClock)
+    }
+
+    /// Execution of `opportunity` started at `clock`'s current time
+    pub(crate) fn new_with_clock(opportunity: ArbitrageOpportunity, clock: &dyn Clock) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             opportunity: opportunity.clone(),
@@ -454,7 +1706,7 @@ impl ArbitrageExecution {
                 vec![],
                 opportunity.base_token.clone(),
                 opportunity.quote_token.clone(),
-                Decimal::ZERO,
+                opportunity.optimal_input().unwrap_or(Decimal::ZERO),
             ),
             transaction_signature: None,
             execution_status: crate::models::ExecutionStatus::Pending,
@@ -462,8 +1714,227 @@ impl ArbitrageExecution {
             gas_price: None,
             total_cost: None,
             actual_profit: None,
-            execution_time: chrono::Utc::now(),
+            execution_time: clock.now(),
             error_message: None,
+            failure_reason: None,
+            program_error: None,
+            tags: opportunity.tags,
+            config_snapshot: None,
+            send_options: Default::default(),
+            simulation_logs: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::strategy::{Strategy, StrategyParameters};
+    use crate::utils::clock::{Clock, TestClock};
+    use crate::utils::fixtures::opportunity;
+
+    /// A definition whose verdict is scripted instead of taken from its thresholds
+    struct Scripted(ArbitrageStrategy, fn(&ArbitrageOpportunity) -> bool);
+
+    impl Strategy for Scripted {
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+
+        fn description(&self) -> &str {
+            self.0.description()
+        }
+
+        fn should_execute(&self, opportunity: &ArbitrageOpportunity) -> bool {
+            (self.1)(opportunity)
+        }
+
+        fn calculate_optimal_amount(&self, opportunity: &ArbitrageOpportunity) -> Option<Decimal> {
+            self.0.calculate_optimal_amount(opportunity)
         }
+
+        fn get_parameters(&self) -> StrategyParameters {
+            self.0.get_parameters()
+        }
+
+        fn validate(&self) -> Result<()> {
+            self.0.validate()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_strategy_is_disabled_and_reported() {
+        let mut engine = ArbitrageEngine::new(AppConfig::bundled_default(), None, HashMap::new());
+        let scripted = |verdict: fn(&ArbitrageOpportunity) -> bool| -> StrategyBuilder {
+            Arc::new(move |definition| Ok(Box::new(Scripted(definition.clone(), verdict))))
+        };
+        engine.register_strategy_kind("reject", scripted(|_| false)).await;
+        engine.register_strategy_kind("accept", scripted(|_| true)).await;
+        engine.register_strategy_kind("fragile", scripted(|_| panic!("decimal overflow"))).await;
+
+        let default = ArbitrageEngine::default_strategy(&engine.config.arbitrage);
+        let definition = |name: &str| {
+            let mut strategy = default.clone().with_kind(name, serde_json::Value::Null);
+            strategy.id = name.to_string();
+            strategy.name = name.to_string();
+            strategy
+        };
+        engine.add_strategy(definition("reject")).await.unwrap();
+        engine.add_strategy(definition("fragile")).await.unwrap();
+
+        // Rejecting everything else makes every active strategy get evaluated
+        let opportunity = opportunity();
+        assert!(engine.select_strategy(&opportunity).await.is_none());
+        assert!(engine.select_strategy(&opportunity).await.is_none());
+
+        assert!(!engine.handle().get_strategy("fragile").await.unwrap().is_active);
+        assert_eq!(engine.metrics.get("strategy_panics{strategy=fragile}").await, Some(1.0));
+        let alert = engine.alert_receiver.try_recv().unwrap();
+        assert_eq!(alert.rule_name, "strategy:fragile");
+        assert!(alert.message.contains("decimal overflow"));
+        assert!(engine.alert_receiver.try_recv().is_err());
+
+        engine.add_strategy(definition("accept")).await.unwrap();
+        let selected = engine.select_strategy(&opportunity).await.unwrap();
+        assert_eq!(selected.name, "accept");
+
+        let metrics = engine.handle().get_strategy_metrics().await;
+        assert_eq!(metrics["fragile"], StrategyMetrics { evaluations: 1, selections: 0, panics: 1 });
+        assert_eq!(metrics["reject"].selections, 0);
+        assert_eq!(metrics["accept"].selections, 1);
+
+        // A definition of an unregistered kind is refused
+        assert!(engine.add_strategy(definition("unknown")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_configured_strategies_are_loaded_and_bad_entries_named() {
+        let entry = |toml: &str| toml::from_str::<crate::config::StrategyConfig>(toml).unwrap();
+        let mut config = AppConfig::bundled_default();
+        config.strategies = vec![
+            entry("name = \"wide\"\nmin_profit_threshold = 0.02\nsupported_dexes = [\"Raydium\"]\nrisk_tolerance = \"Low\"\nposition_size_multiplier = 0.5\nmax_trade_amount = 0.3"),
+            entry("name = \"all-venues\"\nenabled = false"),
+        ];
+        let engine = ArbitrageEngine::new(config.clone(), None, HashMap::new());
+        engine.load_strategies().await.unwrap();
+
+        let wide = engine.handle().get_strategy("wide").await.unwrap();
+        assert_eq!(wide.min_profit_threshold, Decimal::new(2, 2));
+        assert_eq!(wide.supported_dexes, vec![DexType::Raydium]);
+        assert_eq!(wide.risk_tolerance, RiskScore::Low);
+        assert_eq!(wide.trade_size(Decimal::ONE), Decimal::new(3, 1));
+        let all_venues = engine.handle().get_strategy("all-venues").await.unwrap();
+        assert_eq!(all_venues.supported_dexes, DexType::pool_venues());
+        assert!(!all_venues.is_active);
+
+        // Errors point at the entry at fault
+        config.strategies.push(entry("name = \"negative\"\nmin_profit_threshold = -0.01"));
+        let error = format!("{:#}", config.strategy_definitions().unwrap_err());
+        assert!(error.contains("entry 3 ('negative')"), "{}", error);
+        config.strategies[2] = entry("name = \"wide\"");
+        assert!(format!("{:#}", config.strategy_definitions().unwrap_err()).contains("entry 3 ('wide')"));
+        config.strategies[2] = entry("name = \"plugin\"\nkind = \"martingale\"");
+        assert!(config.strategy_definitions().is_ok());
+        let engine = ArbitrageEngine::new(config, None, HashMap::new());
+        let error = format!("{:#}", engine.load_strategies().await.unwrap_err());
+        assert!(error.contains("entry 3 ('plugin')") && error.contains("unknown kind"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_expiry_follows_injected_clock() {
+        let clock = TestClock::new(chrono::Utc::now());
+        let engine = ArbitrageEngine::new(AppConfig::bundled_default(), None, HashMap::new()).with_clock(clock.clone());
+        let mut opportunity = opportunity();
+        opportunity.expiry = clock.now() + chrono::Duration::seconds(30);
+        engine.active_opportunities.write().await.insert(opportunity.id.clone(), opportunity.clone());
+
+        clock.advance(chrono::Duration::seconds(29));
+        engine.cleanup_expired_opportunities().await.unwrap();
+        assert_eq!(engine.active_opportunities.read().await.len(), 1);
+
+        clock.advance(chrono::Duration::seconds(2));
+        assert!(opportunity.is_expired_on(clock.as_ref()));
+        engine.cleanup_expired_opportunities().await.unwrap();
+        assert!(engine.active_opportunities.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_profit_threshold_applies_to_profit_per_unit_of_input() {
+        let mut config = AppConfig::bundled_default();
+        config.arbitrage.min_profit_threshold = 0.004;
+        let engine = ArbitrageEngine::new(config, None, HashMap::new());
+
+        // 0.5 on 100 is 0.5%, clearing 0.4%
+        let mut sized = opportunity();
+        sized.net_profit = Decimal::new(5, 1);
+        sized.tags.insert("estimated_input".to_string(), "100".to_string());
+        assert!(engine.clears_profit_threshold(&sized));
+        // The same profit on 1,000 is 0.05%
+        sized.tags.insert("estimated_input".to_string(), "1000".to_string());
+        assert!(!engine.clears_profit_threshold(&sized));
+        // Without a size there is nothing to measure it against
+        sized.tags.remove("estimated_input");
+        assert!(!engine.clears_profit_threshold(&sized));
+    }
+
+    #[tokio::test]
+    async fn test_tagged_execution_gauges_follow_the_window() {
+        let clock = TestClock::new(chrono::Utc::now());
+        let engine = ArbitrageEngine::new(AppConfig::bundled_default(), None, HashMap::new()).with_clock(clock.clone());
+        let mut execution = ArbitrageExecution::new(opportunity());
+        execution.execution_time = clock.now();
+        execution.tags.insert("experiment".to_string(), "a".to_string());
+        engine.executions.write().await.push(execution);
+
+        engine.publish_metrics().await;
+        assert_eq!(engine.metrics.get("executions_1h{experiment=a}").await, Some(1.0));
+        assert_eq!(engine.metrics.get("executions_1h").await, Some(1.0));
+
+        clock.advance(chrono::Duration::minutes(61));
+        engine.publish_metrics().await;
+        assert_eq!(engine.metrics.get("executions_1h{experiment=a}").await, None);
+        assert_eq!(engine.metrics.get("executions_1h").await, Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_dry_runs_are_kept_out_of_trade_records() {
+        let engine = ArbitrageEngine::new(AppConfig::bundled_default(), None, HashMap::new());
+        let mut execution = ArbitrageExecution::new(opportunity());
+        execution.execution_status = ExecutionStatus::Simulated;
+        execution.actual_profit = Some(Decimal::ONE);
+        engine.process_execution(execution).await.unwrap();
+
+        assert!(engine.executions.read().await.is_empty());
+        assert!(engine.memory_store.get_executions_by_status(ExecutionStatus::Simulated).await.is_empty());
+        assert_eq!(engine.metrics.get("dry_run_executions").await, Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn test_fast_lane_preempts_pending_normal_admission() {
+        let mut config = AppConfig::bundled_default();
+        config.arbitrage.max_concurrent_opportunities = 1;
+        let engine = ArbitrageEngine::new(config, None, HashMap::new());
+
+        let mut normal = opportunity();
+        normal.tags.insert("lane".to_string(), "normal".to_string());
+        let mut fleeting = opportunity();
+        fleeting.expiry = chrono::Utc::now() + chrono::Duration::milliseconds(300);
+        assert_eq!(Lane::classify(&normal, &engine.config.lanes, engine.clock.now()), Lane::Normal);
+        assert_eq!(Lane::classify(&fleeting, &engine.config.lanes, engine.clock.now()), Lane::Fast);
+
+        // Already taken by the executor: under way, so nothing can be preempted
+        let mut active = HashMap::from([(normal.id.clone(), normal.clone())]);
+        assert!(!engine.admit(&mut active, Lane::Fast).await);
+        assert_eq!(active[&normal.id].status, OpportunityStatus::Pending);
+
+        engine.opportunity_receiver.push(Lane::Normal, 1.0, normal.clone());
+        assert!(!engine.admit(&mut active, Lane::Normal).await);
+        assert!(engine.admit(&mut active, Lane::Fast).await);
+        assert_eq!(active[&normal.id].status, OpportunityStatus::Cancelled);
+        // Taken out of the queue, so the executor never trades it
+        assert!(engine.opportunity_receiver.is_empty());
+        assert_eq!(engine.metrics.get("lane_preemptions").await, Some(1.0));
+        assert_eq!(engine.metrics.get("lane_rejections{lane=normal}").await, Some(1.0));
+        assert_eq!(engine.metrics.get("lane_rejections{lane=fast}").await, Some(1.0));
     }
 }
@@ -1,17 +1,424 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    transaction::Transaction,
+};
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::mpsc;
-use tokio::time::{sleep, Duration};
-use tracing::{info, warn, error};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, info, warn, error};
 
 use crate::{
-    config::AppConfig,
-    dex::{DexInterface, DexType},
-    models::{ArbitrageExecution, ArbitrageOpportunity, ExecutionStatus, RiskScore},
+    arbitrage::accounting::TIP_TAG,
+    arbitrage::compute_budget::fit_compute_budget,
+    arbitrage::preflight::{Preflight, PreflightError, PreflightReport},
+    arbitrage::queue::PriorityQueue,
+    arbitrage::strategy::StrategyManager,
+    arbitrage::token_accounts::{opened_accounts, prepend, unwrap_sol as unwrap_sol_instruction, AccountSetup},
+    config::{AppConfig, RoundingMode},
+    dex::{DexInterface, DexType, SwapSigner},
+    models::{
+        ArbitrageExecution, ArbitrageOpportunity, ArbitrageStrategy, ConfigSnapshot, ExecutionStatus, Pool, PoolQuote,
+        RiskScore, SendOptions, Token,
+    },
+    services::balances::BalanceManager,
+    services::fee_model::{scale_bid, FeeModel},
+    services::keepalive::KeepAliveRegistry,
+    services::leader_schedule::{LeaderScheduleTracker, SubmissionPlan, SubmissionRoute, SLOT_DURATION},
+    services::jito::{tip_instruction, JitoConfig, JitoService, MAX_BUNDLE_TRANSACTIONS},
+    services::solana::TransactionOutcome,
+    utils::clock::{SharedClock, SystemClock},
+    utils::panic_guard::lock_or_recover,
+    utils::precision::{from_base_units, to_base_units},
 };
 
+/// Submits the legs of an arbitrage as one Jito bundle with a tip, so they land together or not at all
+pub struct BundleSubmitter {
+    jito: Arc<JitoService>,
+    signer: SwapSigner,
+    tip_lamports: u64,
+    status_timeout: Duration,
+    preflight: Preflight,
+    simulate_before_submit: bool,
+    compute_unit_margin: f64,
+    create_token_accounts: bool,
+    wrap_sol: bool,
+    /// Upcoming leaders, when submission follows them
+    schedule: Option<tokio::sync::Mutex<LeaderScheduleTracker>>,
+}
+
+impl BundleSubmitter {
+    /// None unless bundles are enabled; errors when they are but no wallet is configured
+    pub fn from_config(config: &AppConfig) -> Result<Option<Self>> {
+        if !config.submission.bundles_enabled || config.submission.dry_run {
+            return Ok(None);
+        }
+        let signer = SwapSigner::from_config(config)?
+            .ok_or_else(|| anyhow::anyhow!("Bundle submission requires wallet.keypair_path"))?;
+        let jito = Arc::new(JitoService::with_connections(
+            JitoConfig {
+                base_url: config.solana.jito_url.clone(),
+                auth_header: config.solana.jito_auth_header.clone(),
+                ..Default::default()
+            },
+            &config.connections,
+        )?);
+        KeepAliveRegistry::global().register(jito.clone());
+
+        Ok(Some(Self {
+            preflight: Preflight::new(signer.solana.clone())
+                .with_bundle_simulation(jito.clone(), &config.submission.bundle_simulation_url),
+            simulate_before_submit: config.submission.simulate_before_submit,
+            compute_unit_margin: ExecutionConfig::from_config(config).compute_unit_margin,
+            create_token_accounts: config.submission.create_token_accounts,
+            wrap_sol: config.submission.wrap_sol,
+            schedule: config
+                .submission
+                .leader_aware
+                .then(|| tokio::sync::Mutex::new(LeaderScheduleTracker::new(config.submission.clone()))),
+            jito,
+            signer,
+            tip_lamports: config.submission.bundle_tip_lamports,
+            status_timeout: Duration::from_secs(config.submission.bundle_status_timeout_seconds.max(1)),
+        }))
+    }
+
+    /// Wallet that signs every leg
+    pub fn wallet(&self) -> solana_program::pubkey::Pubkey {
+        self.signer.wallets.owner().pubkey()
+    }
+
+    /// Wallet paying fees and tips
+    pub fn fee_payer(&self) -> solana_program::pubkey::Pubkey {
+        self.signer.wallets.fee_payer().pubkey()
+    }
+
+    /// How to submit now: a bundle when the current or a nearby leader runs Jito, otherwise an RPC
+    /// plan, which holds the bundle. Bundles right away when not leader aware or the schedule cannot be read.
+    pub async fn plan(&self) -> SubmissionPlan {
+        let Some(schedule) = &self.schedule else {
+            return SubmissionPlan::default();
+        };
+        let mut schedule = schedule.lock().await;
+        let slot = match schedule.refresh(&self.signer.solana).await {
+            Ok(()) => self.signer.solana.get_slot_info().await,
+            Err(e) => Err(e),
+        };
+        match slot {
+            Ok(slot) => schedule.plan(slot),
+            Err(e) => {
+                debug!("Leader schedule unavailable, bundling without it: {}", e);
+                SubmissionPlan::default()
+            }
+        }
+    }
+
+    /// Simulates `legs` legs before they are sent: always for multi-leg routes, whose atomicity
+    /// is only worth tipping for once the whole bundle is known to pass, otherwise when enabled
+    pub fn preflight(&self, legs: usize) -> Option<&Preflight> {
+        (self.simulate_before_submit || legs > 1).then_some(&self.preflight)
+    }
+
+    /// Accounts the legs will trade through, wrapping `input_amount` when the route starts from wSOL
+    pub fn account_setup(&self, tokens: impl IntoIterator<Item = Token>, start_token: &Token, input_amount: Decimal) -> AccountSetup {
+        let setup = AccountSetup::new(tokens);
+        if self.wrap_sol && start_token.mint == spl_token_interface::native_mint::id() {
+            let lamports = to_base_units(input_amount, start_token.decimals, RoundingMode::Ceil).unwrap_or(0);
+            return setup.with_wrap(lamports);
+        }
+        setup
+    }
+
+    /// Run the account setup ahead of the first leg: create missing associated token accounts
+    /// and wrap the SOL it spends. Also returns how many token accounts it opens for good.
+    pub async fn prepare_accounts(&self, mut legs: Vec<Transaction>, setup: &AccountSetup) -> Result<(Vec<Transaction>, usize)> {
+        let Some(first) = legs.first() else {
+            return Ok((legs, 0));
+        };
+        let setup = if self.create_token_accounts {
+            setup.clone()
+        } else {
+            AccountSetup { tokens: Vec::new(), ..setup.clone() }
+        };
+        let payer = self.signer.wallets.fee_payer().pubkey();
+        let instructions = setup.resolve(&self.signer.solana, &payer, &self.wallet()).await?;
+        if instructions.is_empty() {
+            return Ok((legs, 0));
+        }
+        debug!("Prepending {} account setup instruction(s) to the first leg", instructions.len());
+        let opened = opened_accounts(&instructions);
+        legs[0] = prepend(first, instructions, &self.signer.wallets)?;
+        Ok((legs, opened))
+    }
+
+    /// Set each leg's compute unit limit from a simulation of it; a leg that cannot be simulated on
+    /// its own, such as one spending what an earlier leg buys, keeps the limit it was built with
+    pub async fn fit_compute_budgets(&self, legs: Vec<Transaction>) -> Vec<Transaction> {
+        if self.compute_unit_margin <= 0.0 {
+            return legs;
+        }
+        let mut fitted = Vec::with_capacity(legs.len());
+        for (i, leg) in legs.into_iter().enumerate() {
+            match fit_compute_budget(&self.signer.solana, &self.signer.wallets, &leg, self.compute_unit_margin).await {
+                Ok(budgeted) => fitted.push(budgeted),
+                Err(e) => {
+                    debug!("Leg {} keeps its compute budget: {}", i, e);
+                    fitted.push(leg);
+                }
+            }
+        }
+        fitted
+    }
+
+    /// Append the tip transaction to the legs. The tip goes last so it is only paid if every leg succeeds.
+    /// With `unwrap_sol` the same transaction closes the wSOL account, returning its balance as SOL.
+    pub async fn build_bundle(&self, mut legs: Vec<Transaction>, unwrap_sol: bool, tip_lamports: u64) -> Result<Vec<Transaction>> {
+        if legs.is_empty() || legs.len() >= MAX_BUNDLE_TRANSACTIONS {
+            anyhow::bail!("Cannot bundle {} legs plus a tip", legs.len());
+        }
+        let payer = self.signer.wallets.fee_payer().pubkey();
+        let blockhash = self.signer.solana.get_recent_blockhash().await?;
+        let mut instructions = Vec::new();
+        if unwrap_sol && self.wrap_sol {
+            instructions.push(unwrap_sol_instruction(&self.wallet()));
+        }
+        instructions.push(tip_instruction(&payer, tip_lamports));
+        legs.push(self.signer.wallets.build_transaction(&instructions, blockhash)?);
+        Ok(legs)
+    }
+
+    /// Send the legs with a tip of `tip_lamports`, returning the bundle id
+    pub async fn submit(&self, legs: Vec<Transaction>, unwrap_sol: bool, tip_lamports: u64) -> Result<String> {
+        let bundle = self.build_bundle(legs, unwrap_sol, tip_lamports).await?;
+        self.jito.send_bundle(&bundle).await
+    }
+
+    /// How long a submitted bundle is tracked before it is given up on
+    pub fn status_timeout(&self) -> Duration {
+        self.status_timeout
+    }
+
+    pub fn tip_lamports(&self) -> u64 {
+        self.tip_lamports
+    }
+
+    /// Tip bid under `plan`
+    pub fn tip_for(&self, plan: &SubmissionPlan) -> u64 {
+        scale_bid(self.tip_lamports, plan.fee_multiplier)
+    }
+
+    /// What the legs did on-chain once every one has confirmed or any has failed; None while
+    /// they are still pending. A bundle lands atomically, so one failed leg settles it.
+    pub async fn leg_outcomes(&self, signatures: &[Signature]) -> Result<Option<Vec<TransactionOutcome>>> {
+        let statuses = self.signer.solana.get_signature_confirmations(signatures).await?;
+        let failed = statuses.iter().any(|status| matches!(status, Some(Err(_))));
+        if !failed && statuses.iter().any(Option::is_none) {
+            return Ok(None);
+        }
+
+        let wallet = self.wallet();
+        let mut outcomes = Vec::new();
+        for (signature, _) in signatures.iter().zip(&statuses).filter(|(_, status)| status.is_some()) {
+            outcomes.push(self.signer.solana.get_transaction_outcome(signature, &wallet).await?);
+        }
+        Ok(Some(outcomes))
+    }
+}
+
+/// Realised profit in the start token and total cost in SOL of landed legs: the wallet's net change
+/// in the token the route starts from, and every leg's fee plus the tip
+pub fn settle(outcomes: &[TransactionOutcome], start_mint: &Pubkey, tip_lamports: u64) -> (Decimal, Decimal) {
+    let (raw, decimals) = outcomes
+        .iter()
+        .filter_map(|outcome| outcome.token_deltas.get(start_mint))
+        .fold((0i128, 0u8), |(sum, _), (delta, decimals)| (sum + delta, *decimals));
+    let profit = Decimal::from_i128_with_scale(raw, decimals as u32);
+    let lamports: u64 = outcomes.iter().map(|outcome| outcome.fee).sum::<u64>() + tip_lamports;
+    (profit, Decimal::from_i128_with_scale(lamports as i128, 9))
+}
+
+/// Sends the legs of an arbitrage one after another through each venue's own swap path, when
+/// bundles are off or no Jito leader is near. Each leg has to confirm before the next is sent, since it spends what the
+/// previous one bought; nothing makes the route atomic, so a later leg failing leaves the wallet
+/// holding the intermediate token.
+pub struct LegSubmitter {
+    signer: SwapSigner,
+    status_timeout: Duration,
+}
+
+impl LegSubmitter {
+    /// None when dry runs or bundles handle submission; a bundle is never split into legs, since
+    /// one leg could land without the others. Errors when no wallet is configured
+    pub fn from_config(config: &AppConfig) -> Result<Option<Self>> {
+        if config.submission.bundles_enabled || config.submission.dry_run {
+            return Ok(None);
+        }
+        let signer = SwapSigner::from_config(config)?.ok_or_else(|| {
+            anyhow::anyhow!("Submitting swaps requires wallet.keypair_path; enable submission.dry_run to only quote")
+        })?;
+        Ok(Some(Self::new(signer, Duration::from_secs(config.submission.bundle_status_timeout_seconds.max(1)))))
+    }
+
+    pub fn new(signer: SwapSigner, status_timeout: Duration) -> Self {
+        Self { signer, status_timeout }
+    }
+
+    /// Wallet that signs every leg
+    pub fn wallet(&self) -> solana_program::pubkey::Pubkey {
+        self.signer.wallets.owner().pubkey()
+    }
+
+    /// Wallet paying fees and tips
+    pub fn fee_payer(&self) -> solana_program::pubkey::Pubkey {
+        self.signer.wallets.fee_payer().pubkey()
+    }
+
+    /// Wait for a sent leg to confirm; errors when it fails on-chain or does not land in time
+    pub async fn await_confirmation(&self, signature: &Signature) -> Result<()> {
+        let deadline = Instant::now() + self.status_timeout;
+        loop {
+            match self.signer.solana.get_signature_confirmations(&[*signature]).await {
+                Ok(statuses) => match statuses.into_iter().next().flatten() {
+                    Some(Ok(_)) => return Ok(()),
+                    Some(Err(e)) => anyhow::bail!("Leg {} failed: {}", signature, e),
+                    None => {}
+                },
+                Err(e) => debug!("Failed to check status of leg {}: {}", signature, e),
+            }
+            if Instant::now() >= deadline {
+                anyhow::bail!("Leg {} did not land within {:?}", signature, self.status_timeout);
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    /// What the confirmed legs did on-chain
+    pub async fn leg_outcomes(&self, signatures: &[Signature]) -> Result<Vec<TransactionOutcome>> {
+        let wallet = self.wallet();
+        let mut outcomes = Vec::with_capacity(signatures.len());
+        for signature in signatures {
+            outcomes.push(self.signer.solana.get_transaction_outcome(signature, &wallet).await?);
+        }
+        Ok(outcomes)
+    }
+}
+
+/// Quote a leg on its own pool. For the sell leg of a two-pool opportunity, Jupiter's best route is
+/// quoted too and used instead when it returns more.
+async fn quote_leg<'a>(
+    dex_instances: &'a HashMap<DexType, Box<dyn DexInterface>>,
+    pool: &Pool,
+    input_token: &Token,
+    output_token: &Token,
+    amount: Decimal,
+    aggregate: bool,
+) -> Result<(&'a dyn DexInterface, PoolQuote)> {
+    let dex = dex_instances
+        .get(&pool.dex_type)
+        .ok_or_else(|| anyhow::anyhow!("No {:?} adapter for pool {}", pool.dex_type, pool.id))?;
+    let quote = dex.get_quote(input_token, output_token, amount, Some(&pool.pool_address)).await?;
+    let Some(jupiter) = dex_instances.get(&DexType::Jupiter).filter(|_| aggregate) else {
+        return Ok((dex.as_ref(), quote));
+    };
+    match jupiter.get_quote(input_token, output_token, amount, None).await {
+        Ok(routed) if routed.output_amount > quote.output_amount => {
+            debug!(
+                "Selling through {} for {} {} instead of {} on pool {}",
+                routed.pool.id, routed.output_amount, output_token.symbol, quote.output_amount, pool.id
+            );
+            Ok((jupiter.as_ref(), routed))
+        }
+        Ok(_) => Ok((dex.as_ref(), quote)),
+        Err(e) => {
+            debug!("Jupiter quote failed, selling on pool {}: {}", pool.id, e);
+            Ok((dex.as_ref(), quote))
+        }
+    }
+}
+
+/// Quotes every leg of an opportunity at current prices and records the outcome as a simulated
+/// execution, without building or submitting transactions
+#[derive(Clone)]
+pub struct DryRunner {
+    dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
+    trade_size: Decimal,
+    max_slippage: Decimal,
+    route_sell_leg: bool,
+}
+
+impl DryRunner {
+    /// None unless dry-run mode is on
+    pub fn from_config(dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>, config: &AppConfig) -> Option<Self> {
+        config.submission.dry_run.then(|| Self {
+            dex_instances,
+            trade_size: Decimal::from_f64(config.submission.trade_size).unwrap_or(Decimal::ONE),
+            max_slippage: Decimal::from_f64(config.arbitrage.max_slippage).unwrap_or(Decimal::ZERO),
+            route_sell_leg: config.dex.jupiter.route_sell_leg,
+        })
+    }
+
+    /// Chain quotes through every leg and return the amount in and out, in the starting token.
+    /// `trade_size` overrides the configured size of a two-pool opportunity.
+    pub async fn quote_round_trip(&self, opportunity: &ArbitrageOpportunity, trade_size: Option<Decimal>) -> Result<(Decimal, Decimal)> {
+        let input_amount = match &opportunity.route {
+            Some(route) => route.input_amount,
+            None => trade_size.unwrap_or(self.trade_size),
+        };
+        let mut amount = input_amount;
+        let legs = ArbitrageExecutor::legs(opportunity);
+        let sell_leg = legs.len() - 1;
+        for (i, (pool, input_token, output_token)) in legs.into_iter().enumerate() {
+            let aggregate = self.route_sell_leg && opportunity.route.is_none() && i == sell_leg;
+            let (_, quote) = quote_leg(&self.dex_instances, &pool, &input_token, &output_token, amount, aggregate).await?;
+            if quote.output_amount <= Decimal::ZERO {
+                anyhow::bail!("Pool {} quotes nothing for {} {}", pool.id, amount, input_token.symbol);
+            }
+            if quote.price_impact > self.max_slippage {
+                anyhow::bail!("Price impact {} on pool {} exceeds max slippage {}", quote.price_impact, pool.id, self.max_slippage);
+            }
+            amount = quote.output_amount;
+        }
+        Ok((input_amount, amount))
+    }
+
+    /// Settle `execution` from fresh quotes taken at `now`: simulated with the quoted profit and the
+    /// network cost, in SOL, that `fees` expects the legs to pay, or failed if a leg does not validate
+    pub async fn execute(&self, mut execution: ArbitrageExecution, fees: &FeeModel, now: DateTime<Utc>) -> ArbitrageExecution {
+        execution.tags.insert("dry_run".to_string(), "true".to_string());
+        execution.execution_time = now;
+        // Sized by the strategy or the wallet when tagged
+        let trade_size = execution.tags.get("trade_size").and_then(|size| Decimal::from_str(size).ok());
+        match self.quote_round_trip(&execution.opportunity, trade_size).await {
+            Ok((input_amount, output_amount)) => {
+                // Quotes already take the swap fees out of the output; the cost is what landing
+                // the legs would take, as `settle` records it for a real trade
+                let legs = ArbitrageExecutor::legs(&execution.opportunity).len();
+                execution.execution_status = ExecutionStatus::Simulated;
+                execution.actual_profit = Some(output_amount - input_amount);
+                execution.total_cost = Some(from_base_units(fees.estimate(legs, 0).total(), 9));
+                info!(
+                    "Dry run of opportunity {}: {} in, {} out, estimated profit {}",
+                    execution.opportunity.id,
+                    input_amount,
+                    output_amount,
+                    output_amount - input_amount
+                );
+            }
+            Err(e) => {
+                execution.execution_status = ExecutionStatus::Failed;
+                execution.error_message = Some(e.to_string());
+            }
+        }
+        execution
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ExecutionStrategy {
     Immediate,
@@ -19,12 +426,24 @@ pub enum ExecutionStrategy {
     Conditional,
 }
 
+/// Validated opportunities waiting for the executor, best score first; shared so a restarted
+/// executor resumes the queue
+pub type OpportunityReceiver = Arc<PriorityQueue<ArbitrageOpportunity>>;
+
 pub struct ArbitrageExecutor {
     dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
     execution_sender: mpsc::Sender<ArbitrageExecution>,
     config: AppConfig,
     max_concurrent_executions: usize,
-    active_executions: HashMap<String, ArbitrageExecution>,
+    active_executions: Mutex<HashMap<String, ArbitrageExecution>>,
+    bundles: Option<Arc<BundleSubmitter>>,
+    legs: Option<Arc<LegSubmitter>>,
+    dry_run: Option<DryRunner>,
+    opportunities: Option<OpportunityReceiver>,
+    strategies: Option<Arc<RwLock<StrategyManager>>>,
+    balances: Option<Arc<BalanceManager>>,
+    fees: Arc<FeeModel>,
+    clock: SharedClock,
 }
 
 impl ArbitrageExecutor {
@@ -34,91 +453,465 @@ impl ArbitrageExecutor {
         config: AppConfig,
     ) -> Self {
         Self {
+            dry_run: DryRunner::from_config(dex_instances.clone(), &config),
             dex_instances,
             execution_sender,
             config: config.clone(),
-            max_concurrent_executions: config.arbitrage.max_concurrent_opportunities as usize,
-            active_executions: HashMap::new(),
+            max_concurrent_executions: config.arbitrage.max_concurrent_opportunities.max(1),
+            active_executions: Mutex::new(HashMap::new()),
+            bundles: None,
+            legs: None,
+            opportunities: None,
+            strategies: None,
+            balances: None,
+            fees: Arc::new(FeeModel::new(&config)),
+            clock: SystemClock::shared(),
         }
     }
 
-    /// Start the executor
-    pub async fn start(mut self) -> Result<()> {
-        info!("Starting arbitrage executor...");
-        
+    /// Execute opportunities taken from this queue
+    pub fn with_opportunities(mut self, opportunities: OpportunityReceiver) -> Self {
+        self.opportunities = Some(opportunities);
+        self
+    }
+
+    /// Resolve the strategy that selected each opportunity, for its config snapshot and urgency
+    pub fn with_strategies(mut self, strategies: Arc<RwLock<StrategyManager>>) -> Self {
+        self.strategies = Some(strategies);
+        self
+    }
+
+    /// Submit opportunities as Jito bundles
+    pub fn with_bundles(mut self, bundles: Arc<BundleSubmitter>) -> Self {
+        self.bundles = Some(bundles);
+        self
+    }
+
+    /// Send opportunities leg by leg through each venue's swap path
+    pub fn with_legs(mut self, legs: Arc<LegSubmitter>) -> Self {
+        self.legs = Some(legs);
+        self
+    }
+
+    /// Check quoted routes against execution costs priced by this model
+    pub fn with_fee_model(mut self, fees: Arc<FeeModel>) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Check each trade against the wallet's balances before building it
+    pub fn with_balances(mut self, balances: Arc<BalanceManager>) -> Self {
+        self.balances = Some(balances);
+        self
+    }
+
+    /// Stamp and expire executions by `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Start-token amount the first leg spends: the route's own size for cycles, otherwise the
+    /// configured trade size as the strategy scales and caps it
+    fn input_amount(&self, opportunity: &ArbitrageOpportunity, strategy: Option<&ArbitrageStrategy>) -> Decimal {
+        match &opportunity.route {
+            Some(route) => route.input_amount,
+            None => {
+                let configured = Decimal::from_f64(self.config.submission.trade_size).unwrap_or(Decimal::ONE);
+                let Some(optimal) = opportunity.optimal_input() else {
+                    return strategy.map_or(configured, |strategy| strategy.trade_size(configured));
+                };
+                // The profit-maximizing size, within the scanner's and the strategy's limits
+                let scanner_max = Decimal::from_f64(self.config.scanner.max_trade_amount).unwrap_or(Decimal::ZERO);
+                let optimal = if scanner_max > Decimal::ZERO { optimal.min(scanner_max) } else { optimal };
+                strategy.and_then(|strategy| strategy.max_trade_amount).map_or(optimal, |max| optimal.min(max))
+            }
+        }
+    }
+
+    /// Swaps that realise the opportunity, in order: the route's hops for cycles, otherwise sell the
+    /// base token into the buy pool and buy it back from the sell pool, as the scanner priced it
+    fn legs(opportunity: &ArbitrageOpportunity) -> Vec<(Pool, Token, Token)> {
+        match &opportunity.route {
+            Some(route) => {
+                let mut current = route.input_token.clone();
+                route
+                    .pools
+                    .iter()
+                    .map(|pool| {
+                        let next = if pool.token_a.mint == current.mint { pool.token_b.clone() } else { pool.token_a.clone() };
+                        let leg = (pool.clone(), current.clone(), next.clone());
+                        current = next;
+                        leg
+                    })
+                    .collect()
+            }
+            None => vec![
+                (opportunity.buy_pool.clone(), opportunity.base_token.clone(), opportunity.quote_token.clone()),
+                (opportunity.sell_pool.clone(), opportunity.quote_token.clone(), opportunity.base_token.clone()),
+            ],
+        }
+    }
+
+    /// Quote every leg at current prices, each spending what the previous one is guaranteed to
+    /// deliver after slippage. Returns the venue and quote of each leg and the last leg's output.
+    async fn quote_route(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        input_amount: Decimal,
+    ) -> Result<(Vec<(&dyn DexInterface, PoolQuote)>, Decimal)> {
+        let slippage = Decimal::from_f64(self.config.arbitrage.max_slippage).unwrap_or(Decimal::ZERO);
+        let mut amount = input_amount;
+        let mut quoted_output = Decimal::ZERO;
+        let mut quotes = Vec::new();
+        let legs = Self::legs(opportunity);
+        let sell_leg = legs.len() - 1;
+        for (i, (pool, input_token, output_token)) in legs.into_iter().enumerate() {
+            let aggregate = self.config.dex.jupiter.route_sell_leg && opportunity.route.is_none() && i == sell_leg;
+            let (dex, quote) = quote_leg(&self.dex_instances, &pool, &input_token, &output_token, amount, aggregate).await?;
+            quoted_output = quote.output_amount;
+            // The next leg can only spend what this one is guaranteed to deliver
+            amount = quote.output_amount * (Decimal::ONE - slippage);
+            quotes.push((dex, quote));
+        }
+        Ok((quotes, quoted_output))
+    }
+
+    /// Last go/no-go on fresh quotes: the route has to pay for its own execution
+    fn check_cost(&self, quoted_profit: Decimal, legs: usize, opened: usize, start_token: &Token, plan: &SubmissionPlan) -> Result<()> {
+        let cost = self.fees.estimate(legs, opened).with_fee_multiplier(plan.fee_multiplier);
+        match self.fees.in_token(cost.total(), start_token) {
+            Some(cost_in_token) if quoted_profit <= cost_in_token => {
+                anyhow::bail!(
+                    "Quoted profit {} {} does not cover the execution cost of {} ({} lamports)",
+                    quoted_profit,
+                    start_token.symbol,
+                    cost_in_token,
+                    cost.total()
+                );
+            }
+            Some(_) => Ok(()),
+            None => anyhow::bail!("No SOL price for {} to check the execution cost against", start_token.symbol),
+        }
+    }
+
+    /// Quote, build and sign every leg, simulate them when pre-flight applies, then send them as one
+    /// bundle tipped as `plan` bids. Returns the bundle id, the legs' signatures and what the
+    /// simulation showed.
+    pub async fn execute_bundle(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        input_amount: Decimal,
+        plan: &SubmissionPlan,
+    ) -> Result<(String, Vec<Signature>, Option<PreflightReport>)> {
+        let bundles = self
+            .bundles
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Bundle submission is not configured"))?;
+        let wallet = bundles.wallet();
+        let slippage = Decimal::from_f64(self.config.arbitrage.max_slippage).unwrap_or(Decimal::ZERO);
+
+        let legs = Self::legs(opportunity);
+        let start_token = legs[0].1.clone();
+        let setup = bundles.account_setup(
+            legs.iter().flat_map(|(_, input, output)| [input.clone(), output.clone()]),
+            &start_token,
+            input_amount,
+        );
+        let (quotes, quoted_output) = self.quote_route(opportunity, input_amount).await?;
+        let mut transactions = Vec::new();
+        for (dex, quote) in &quotes {
+            transactions.push(dex.build_swap_transaction(quote, &wallet, slippage).await?);
+        }
+        let (transactions, opened) = bundles.prepare_accounts(transactions, &setup).await?;
+        self.check_cost(quoted_output - input_amount, transactions.len(), opened, &start_token, plan)?;
+        let transactions = bundles.fit_compute_budgets(transactions).await;
+
+        let report = match bundles.preflight(transactions.len()) {
+            Some(preflight) => {
+                let report = preflight
+                    .run(&transactions, &wallet, &start_token, quoted_output - input_amount, slippage)
+                    .await?;
+                debug!(
+                    "Pre-flight of opportunity {}: {} CU, simulated profit {}",
+                    opportunity.id, report.compute_units, report.simulated_profit
+                );
+                Some(report)
+            }
+            None => None,
+        };
+
+        let signatures = transactions.iter().map(|transaction| transaction.signatures[0]).collect();
+        let bundle_id = bundles.submit(transactions, setup.touches_wsol(), bundles.tip_for(plan)).await?;
+        info!("Submitted bundle {} for opportunity {}", bundle_id, opportunity.id);
+        Ok((bundle_id, signatures, report))
+    }
+
+    /// Quote every leg, then send them one at a time, each once the previous has confirmed, and
+    /// settle the execution from what the confirmed legs did. Every leg sent is recorded in the
+    /// `leg_signatures` tag, including when a later one fails.
+    async fn execute_legs(&self, legs: &LegSubmitter, execution: &mut ArbitrageExecution, input_amount: Decimal) -> Result<()> {
+        let opportunity = execution.opportunity.clone();
+        let wallet = legs.wallet();
+        let slippage = Decimal::from_f64(self.config.arbitrage.max_slippage).unwrap_or(Decimal::ZERO);
+        let start_token = Self::legs(&opportunity)[0].1.clone();
+        let (quotes, quoted_output) = self.quote_route(&opportunity, input_amount).await?;
+        self.check_cost(quoted_output - input_amount, quotes.len(), 0, &start_token, &SubmissionPlan::default())?;
+
+        let mut signatures: Vec<Signature> = Vec::new();
+        for (i, (dex, quote)) in quotes.iter().enumerate() {
+            let sent = dex.execute_swap(quote, &wallet, slippage, &execution.send_options).await?;
+            let signature = Signature::from_str(&sent)
+                .map_err(|_| anyhow::anyhow!("{} returned no transaction signature for leg {}: {}", dex.get_name(), i, sent))?;
+            signatures.push(signature);
+            let leg_signatures: Vec<String> = signatures.iter().map(Signature::to_string).collect();
+            execution.tags.insert("leg_signatures".to_string(), leg_signatures.join(","));
+            execution.execution_status = ExecutionStatus::Submitted;
+            execution.transaction_signature.get_or_insert_with(|| signature.to_string());
+            debug!("Sent leg {} of opportunity {} as {}", i, opportunity.id, signature);
+            legs.await_confirmation(&signature).await?;
+        }
+
+        let outcomes = legs.leg_outcomes(&signatures).await?;
+        let (profit, cost) = settle(&outcomes, &start_token.mint, 0);
+        execution.execution_status = ExecutionStatus::Confirmed;
+        execution.actual_profit = Some(profit);
+        execution.total_cost = Some(cost);
+        execution.gas_used = Some(outcomes.iter().filter_map(|outcome| outcome.compute_units).sum());
+        info!("Execution {} confirmed: realised {} {}, cost {} SOL", execution.id, profit, start_token.symbol, cost);
+        Ok(())
+    }
+
+    /// Take opportunities off the queue and execute up to `max_concurrent_executions` at once
+    pub async fn start(self) -> Result<()> {
+        let opportunities = self
+            .opportunities
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Executor has no opportunity queue"))?;
+        info!("Starting arbitrage executor with up to {} concurrent executions", self.max_concurrent_executions);
+
+        let executor = Arc::new(self);
+        let slots = Arc::new(Semaphore::new(executor.max_concurrent_executions));
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
         loop {
-            // Clean up completed executions
-            self.cleanup_completed_executions();
-            
-            // Check if a new arbitrage can be executed
-            if self.active_executions.len() < self.max_concurrent_executions {
-                // Ideally, fetch a new arbitrage opportunity from the queue
-                // Temporarily skipped
+            tokio::select! {
+                next = Self::next_opportunity(&slots, &opportunities) => {
+                    let Some((permit, opportunity)) = next? else {
+                        info!("Opportunity queue closed, stopping executor");
+                        return Ok(());
+                    };
+                    // Queued behind slower executions for too long
+                    if opportunity.is_expired_on(&*executor.clock) {
+                        debug!("Dropping expired opportunity {} before execution", opportunity.id);
+                        continue;
+                    }
+                    let executor = executor.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = executor.execute_opportunity(opportunity).await {
+                            error!("Execution failed: {}", e);
+                        }
+                        drop(permit);
+                    });
+                }
+
+                _ = ticker.tick() => {
+                    executor.cleanup_completed_executions();
+                    executor.monitor_active_executions().await?;
+                }
             }
-            
-            // Monitor active executions
-            self.monitor_active_executions().await?;
-            
-            sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    /// Wait for a free execution slot, then for an opportunity to fill it; None once the queue closes
+    async fn next_opportunity(
+        slots: &Arc<Semaphore>,
+        opportunities: &OpportunityReceiver,
+    ) -> Result<Option<(OwnedSemaphorePermit, ArbitrageOpportunity)>> {
+        let permit = slots.clone().acquire_owned().await?;
+        Ok(opportunities.recv().await.map(|opportunity| (permit, opportunity)))
+    }
+
+    /// The strategy named in the opportunity's `strategy` tag, if it is still loaded
+    async fn strategy_for(&self, opportunity: &ArbitrageOpportunity) -> Option<ArbitrageStrategy> {
+        let name = opportunity.tags.get("strategy")?;
+        let strategies = self.strategies.as_ref()?.read().await;
+        strategies.find_by_name(name).cloned()
+    }
+
+    /// Report a finished execution and free its slot in the active set
+    async fn report(&self, execution: ArbitrageExecution) {
+        lock_or_recover(&self.active_executions, "active executions").remove(&execution.id);
+        if let Err(e) = self.execution_sender.send(execution).await {
+            error!("Failed to send execution to queue: {}", e);
         }
     }
 
     /// Execute an arbitrage opportunity
-    pub async fn execute_opportunity(&mut self, opportunity: ArbitrageOpportunity) -> Result<()> {
-        if self.active_executions.len() >= self.max_concurrent_executions {
+    pub async fn execute_opportunity(&self, opportunity: ArbitrageOpportunity) -> Result<()> {
+        if lock_or_recover(&self.active_executions, "active executions").len() >= self.max_concurrent_executions {
             warn!("Maximum concurrent executions reached, skipping opportunity: {}", opportunity.id);
             return Ok(());
         }
         
+        // Size the trade to what the wallet holds, keeping SOL back for fees
+        let strategy = self.strategy_for(&opportunity).await;
+        let mut requested = self.input_amount(&opportunity, strategy.as_ref());
+        // Strategies sizing from their record, like Kelly, may stake less
+        if let (Some(strategy), Some(strategies)) = (&strategy, &self.strategies) {
+            if let Some(stake) = strategies.read().await.optimal_amount(&strategy.name, &opportunity) {
+                requested = requested.min(stake);
+            }
+        }
+        let mut input_amount = requested;
+        let input_token = Self::legs(&opportunity)[0].1.clone();
+        if let Some(balances) = &self.balances {
+            match balances.claim(&input_token, requested) {
+                Ok(sized) => input_amount = sized,
+                Err(e) => {
+                    warn!("Skipping opportunity {}: {}", opportunity.id, e);
+                    return Ok(());
+                }
+            }
+        }
+
         info!("Executing arbitrage opportunity: {}", opportunity.id);
         
         // Create execution record
-        let execution = ArbitrageExecution {
-            id: uuid::Uuid::new_v4().to_string(),
-            opportunity: opportunity.clone(),
-            route: crate::models::ArbitrageRoute::new(
-                vec![],
-                opportunity.base_token.clone(),
-                opportunity.quote_token.clone(),
-                Decimal::ZERO,
-            ),
-            transaction_signature: None,
-            execution_status: ExecutionStatus::Executing,
-            gas_used: None,
-            gas_price: None,
-            total_cost: None,
-            actual_profit: None,
-            execution_time: chrono::Utc::now(),
-            error_message: None,
-        };
-        
-        // Add to active executions list
-        self.active_executions.insert(execution.id.clone(), execution.clone());
-        
-        // Send to execution queue
-        if let Err(e) = self.execution_sender.send(execution).await {
-            error!("Failed to send execution to queue: {}", e);
+        let mut execution = ArbitrageExecution::new_with_clock(opportunity.clone(), &*self.clock);
+        execution.execution_status = ExecutionStatus::Executing;
+        execution.config_snapshot = Some(ConfigSnapshot::capture(&self.config, strategy.as_ref()));
+        if let Some(strategy) = &strategy {
+            execution.send_options = SendOptions::for_urgency(strategy.urgency);
+        }
+        if input_amount < requested {
+            info!("Downsized opportunity {} from {} to {} to fit the wallet", opportunity.id, requested, input_amount);
+            execution.tags.insert("requested_size".to_string(), requested.to_string());
+        }
+        if input_amount != self.input_amount(&opportunity, None) {
+            execution.tags.insert("trade_size".to_string(), input_amount.to_string());
+        }
+        lock_or_recover(&self.active_executions, "active executions").insert(execution.id.clone(), execution.clone());
+
+        if let Some(dry_run) = &self.dry_run {
+            let execution = dry_run.execute(execution, &self.fees, self.clock.now()).await;
+            // A dry run spends nothing
+            if let Some(balances) = &self.balances {
+                balances.release(&input_token, input_amount);
+            }
+            self.report(execution).await;
+            return Ok(());
         }
         
+        let mut plan = match &self.bundles {
+            Some(bundles) => bundles.plan().await,
+            None => SubmissionPlan::default(),
+        };
+        // Away from Jito leaders a bundle would not land. Hold it for the next one rather than
+        // splitting it into legs, any of which could land without the others.
+        if let Some(bundles) = &self.bundles {
+            while plan.route == SubmissionRoute::Rpc && !opportunity.is_expired_on(&*self.clock) {
+                sleep(SLOT_DURATION).await;
+                plan = bundles.plan().await;
+            }
+        }
+        if let Some(leader) = &plan.leader {
+            execution.tags.insert("leader".to_string(), leader.clone());
+        }
+        let mut bundled = false;
+        if let Some(bundles) = self.bundles.clone() {
+            if plan.delay_slots > 0 {
+                sleep(SLOT_DURATION * plan.delay_slots as u32).await;
+            }
+            let result = if plan.route == SubmissionRoute::Bundle {
+                self.execute_bundle(&opportunity, input_amount, &plan).await
+            } else {
+                execution.tags.insert("submission_route".to_string(), "held".to_string());
+                Err(anyhow::anyhow!("No Jito leader came up before opportunity {} expired", opportunity.id))
+            };
+            match result {
+                Ok((bundle_id, signatures, preflight)) => {
+                    if let Some(report) = preflight {
+                        execution.tags.insert("simulated_compute_units".to_string(), report.compute_units.to_string());
+                        execution.tags.insert("simulated_profit".to_string(), report.simulated_profit.to_string());
+                        execution
+                            .tags
+                            .insert("simulation".to_string(), if report.bundled { "bundle" } else { "sequential" }.to_string());
+                        execution.simulation_logs = report.logs;
+                    }
+                    execution.execution_status = ExecutionStatus::Submitted;
+                    execution.transaction_signature = Some(bundle_id.clone());
+                    execution.tags.insert(TIP_TAG.to_string(), bundles.tip_for(&plan).to_string());
+                    let leg_signatures: Vec<String> = signatures.iter().map(Signature::to_string).collect();
+                    execution.tags.insert("leg_signatures".to_string(), leg_signatures.join(","));
+                    lock_or_recover(&self.active_executions, "active executions").insert(execution.id.clone(), execution.clone());
+
+                    // The slot stays taken until the legs settle on-chain or tracking times out
+                    let deadline = Instant::now() + bundles.status_timeout();
+                    while execution.execution_status == ExecutionStatus::Submitted {
+                        if Instant::now() >= deadline {
+                            let error = format!("Bundle {} did not land within {:?}", bundle_id, bundles.status_timeout());
+                            execution.mark_failed(&error, &[]);
+                            break;
+                        }
+                        sleep(Duration::from_millis(500)).await;
+                        if let Err(e) = self.check_execution_status(&mut execution, &signatures).await {
+                            debug!("Failed to check status of bundle {}: {}", bundle_id, e);
+                        }
+                    }
+                    bundled = true;
+                }
+                Err(e) => {
+                    warn!("Bundle submission failed for opportunity {}: {}", opportunity.id, e);
+                    match e.downcast_ref::<PreflightError>() {
+                        Some(preflight) => {
+                            execution.tags.insert("preflight".to_string(), "aborted".to_string());
+                            execution.simulation_logs = preflight.logs().to_vec();
+                            execution.mark_failed(&e.to_string(), preflight.logs());
+                        }
+                        None => execution.mark_failed(&e.to_string(), &[]),
+                    }
+                }
+            }
+        } else if let Some(legs) = self.legs.clone() {
+            if let Err(e) = self.execute_legs(&legs, &mut execution, input_amount).await {
+                warn!("Leg submission failed for opportunity {}: {}", opportunity.id, e);
+                execution.mark_failed(&e.to_string(), &[]);
+            }
+        } else {
+            execution.mark_failed("No submission path is configured", &[]);
+        }
+
+        // A trade that sent nothing spent nothing, and neither did a bundle that failed, since a
+        // bundle lands whole or not at all
+        if execution.execution_status == ExecutionStatus::Failed && (bundled || execution.transaction_signature.is_none()) {
+            if let Some(balances) = &self.balances {
+                balances.release(&input_token, input_amount);
+            }
+        }
+        self.report(execution).await;
         Ok(())
     }
 
     /// Monitor active executions
-    async fn monitor_active_executions(&mut self) -> Result<()> {
+    async fn monitor_active_executions(&self) -> Result<()> {
         let mut completed_executions = Vec::new();
+        let mut active_executions = lock_or_recover(&self.active_executions, "active executions");
         
         // Collect execution IDs to check
-        let execution_ids: Vec<String> = self.active_executions.keys().cloned().collect();
+        let execution_ids: Vec<String> = active_executions.keys().cloned().collect();
         
         for id in execution_ids {
-            if let Some(execution) = self.active_executions.get(&id) {
+            if let Some(execution) = active_executions.get(&id) {
                 match execution.execution_status {
                     ExecutionStatus::Pending | ExecutionStatus::Submitted | ExecutionStatus::Executing => {
                         // Check execution status - needs refactor to avoid borrow checker issues
                         // Temporarily skip status check and mark for checking directly
                         // TODO: Refactor this to properly handle borrowing
                     }
-                    ExecutionStatus::Confirmed | ExecutionStatus::Failed | ExecutionStatus::Cancelled => {
+                    ExecutionStatus::Confirmed
+                    | ExecutionStatus::Failed
+                    | ExecutionStatus::Cancelled
+                    | ExecutionStatus::Simulated => {
                         completed_executions.push(id);
                     }
                 }
@@ -127,49 +920,58 @@ impl ArbitrageExecutor {
         
         // Remove completed executions
         for id in completed_executions {
-            self.active_executions.remove(&id);
+            active_executions.remove(&id);
         }
         
         Ok(())
     }
 
-    /// Check execution status
-    async fn check_execution_status(&self, execution: &mut ArbitrageExecution) -> Result<()> {
-        // Ideally, check the transaction status on-chain
-        // Temporarily simulate status updates
-        
-        match execution.execution_status {
-            ExecutionStatus::Pending => {
-                // Simulate submitting the transaction
-                execution.execution_status = ExecutionStatus::Submitted;
-                execution.transaction_signature = Some("mock_signature".to_string());
-            }
-            ExecutionStatus::Submitted => {
-                // Simulate confirming the transaction
-                execution.execution_status = ExecutionStatus::Confirmed;
-                execution.actual_profit = Some(Decimal::from(100)); // Simulated profit
-            }
-            _ => {}
+    /// Move a submitted execution to Confirmed or Failed once its legs settle on-chain, recording
+    /// the realised profit and cost read from the confirmed transactions
+    async fn check_execution_status(&self, execution: &mut ArbitrageExecution, signatures: &[Signature]) -> Result<()> {
+        let Some(bundles) = &self.bundles else {
+            return Ok(());
+        };
+        let Some(outcomes) = bundles.leg_outcomes(signatures).await? else {
+            return Ok(());
+        };
+        if let Some(slot) = outcomes.iter().map(|outcome| outcome.slot).max() {
+            execution.tags.insert("bundle_slot".to_string(), slot.to_string());
         }
-        
+        if let Some(failed) = outcomes.iter().find(|outcome| outcome.err.is_some()) {
+            execution.mark_failed(failed.err.as_deref().unwrap_or_default(), &failed.logs);
+            return Ok(());
+        }
+
+        let start_token = Self::legs(&execution.opportunity)[0].1.clone();
+        // The tip actually bid, which the submission plan may have raised
+        let tip = execution.tags.get(TIP_TAG).and_then(|tip| tip.parse().ok()).unwrap_or(bundles.tip_lamports());
+        let (profit, cost) = settle(&outcomes, &start_token.mint, tip);
+        execution.execution_status = ExecutionStatus::Confirmed;
+        execution.actual_profit = Some(profit);
+        execution.total_cost = Some(cost);
+        execution.gas_used = Some(outcomes.iter().filter_map(|outcome| outcome.compute_units).sum());
+        execution.tags.insert(TIP_TAG.to_string(), tip.to_string());
+        info!("Execution {} confirmed: realised {} {}, cost {} SOL", execution.id, profit, start_token.symbol, cost);
         Ok(())
     }
 
     /// Cleanup completed executions
-    fn cleanup_completed_executions(&mut self) {
-        let completed_ids: Vec<String> = self.active_executions
+    fn cleanup_completed_executions(&self) {
+        let mut active_executions = lock_or_recover(&self.active_executions, "active executions");
+        let completed_ids: Vec<String> = active_executions
             .iter()
             .filter(|(_, execution)| {
                 matches!(
                     execution.execution_status,
-                    ExecutionStatus::Confirmed | ExecutionStatus::Failed | ExecutionStatus::Cancelled
+                    ExecutionStatus::Confirmed | ExecutionStatus::Failed | ExecutionStatus::Cancelled | ExecutionStatus::Simulated
                 )
             })
             .map(|(id, _)| id.clone())
             .collect();
         
         for id in completed_ids {
-            self.active_executions.remove(&id);
+            active_executions.remove(&id);
         }
     }
 
@@ -177,7 +979,7 @@ impl ArbitrageExecutor {
     pub fn get_execution_stats(&self) -> ExecutionStats {
         let mut stats = ExecutionStats::default();
         
-        for execution in self.active_executions.values() {
+        for execution in lock_or_recover(&self.active_executions, "active executions").values() {
             stats.total_executions += 1;
             
             match execution.execution_status {
@@ -192,8 +994,8 @@ impl ArbitrageExecutor {
     }
 
     /// Cancel execution
-    pub fn cancel_execution(&mut self, execution_id: &str) -> Result<()> {
-        if let Some(execution) = self.active_executions.get_mut(execution_id) {
+    pub fn cancel_execution(&self, execution_id: &str) -> Result<()> {
+        if let Some(execution) = lock_or_recover(&self.active_executions, "active executions").get_mut(execution_id) {
             execution.execution_status = ExecutionStatus::Cancelled;
             info!("Cancelled execution: {}", execution_id);
         }
@@ -202,8 +1004,8 @@ impl ArbitrageExecutor {
     }
 
     /// Retry a failed execution
-    pub async fn retry_execution(&mut self, execution_id: &str) -> Result<()> {
-        if let Some(execution) = self.active_executions.get_mut(execution_id) {
+    pub async fn retry_execution(&self, execution_id: &str) -> Result<()> {
+        if let Some(execution) = lock_or_recover(&self.active_executions, "active executions").get_mut(execution_id) {
             if execution.execution_status == ExecutionStatus::Failed {
                 execution.execution_status = ExecutionStatus::Pending;
                 execution.error_message = None;
@@ -273,6 +1075,8 @@ pub struct ExecutionConfig {
     pub timeout: Duration,
     pub slippage_tolerance: Decimal,
     pub gas_price_multiplier: f64,
+    /// Legs request their simulated compute units times this; 0 keeps the limit they were built with
+    pub compute_unit_margin: f64,
 }
 
 impl Default for ExecutionConfig {
@@ -284,6 +1088,266 @@ impl Default for ExecutionConfig {
             timeout: Duration::from_secs(30),
             slippage_tolerance: Decimal::from(1) / Decimal::from(100), // 1%
             gas_price_multiplier: 1.1,
+            compute_unit_margin: 1.2,
+        }
+    }
+}
+
+impl ExecutionConfig {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            timeout: Duration::from_secs(config.arbitrage.execution_timeout_seconds),
+            slippage_tolerance: Decimal::from_f64(config.arbitrage.max_slippage).unwrap_or_default(),
+            gas_price_multiplier: config.arbitrage.gas_price_multiplier,
+            compute_unit_margin: config.submission.compute_unit_margin,
+            ..Self::default()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::mock::MockDex;
+    use crate::utils::fixtures;
+
+    #[test]
+    fn test_settle_reads_profit_from_the_start_token_and_adds_the_tip_to_fees() {
+        let usdc = Pubkey::new_unique();
+        let leg = |delta: i128, fee: u64| TransactionOutcome {
+            fee,
+            token_deltas: HashMap::from([(usdc, (delta, 6)), (Pubkey::new_unique(), (-delta, 9))]),
+            ..Default::default()
+        };
+
+        // Spent 100 USDC on the buy leg and got 100.25 back on the sell leg
+        let (profit, cost) = settle(&[leg(-100_000_000, 5_000), leg(100_250_000, 5_000)], &usdc, 10_000);
+        assert_eq!(profit, Decimal::new(25, 2));
+        assert_eq!(cost, Decimal::new(20_000, 9));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_records_quoted_profit() {
+        let dexes = Arc::new(MockDex::standard_set());
+        let mut config = AppConfig::bundled_default();
+        assert!(DryRunner::from_config(dexes.clone(), &config).is_none());
+        config.submission.dry_run = true;
+        let runner = DryRunner::from_config(dexes.clone(), &config).unwrap();
+
+        let tokens = dexes[&DexType::Raydium].get_supported_tokens().await.unwrap();
+        let pool = |dex_type: DexType| {
+            let dexes = dexes.clone();
+            let (sol, usdc) = (tokens[0].clone(), tokens[1].clone());
+            async move { dexes[&dex_type].get_pools_by_tokens(&sol, &usdc).await.unwrap().remove(0) }
+        };
+        // Whirlpool's mock pool prices SOL one USDC above Raydium's: sell it there, buy it back on Raydium
+        let opportunity = fixtures::opportunity_between(pool(DexType::Whirlpool).await, pool(DexType::Raydium).await);
+
+        let fees = FeeModel::new(&config);
+        let execution = runner.execute(ArbitrageExecution::new(opportunity.clone()), &fees, chrono::Utc::now()).await;
+        assert_eq!(execution.execution_status, ExecutionStatus::Simulated);
+        assert_eq!(execution.tags.get("dry_run").map(String::as_str), Some("true"));
+        assert!(execution.transaction_signature.is_none());
+        assert!(execution.actual_profit.unwrap() > Decimal::ZERO);
+        // Network cost of two legs in SOL, as a landed trade records it
+        assert_eq!(execution.total_cost, Some(from_base_units(fees.estimate(2, 0).total(), 9)));
+
+        // A leg no adapter can quote fails validation instead of recording a profit
+        let mut unknown = opportunity;
+        unknown.sell_pool.pool_address = solana_program::pubkey::Pubkey::new_unique();
+        let execution = runner.execute(ArbitrageExecution::new(unknown), &fees, chrono::Utc::now()).await;
+        assert_eq!(execution.execution_status, ExecutionStatus::Failed);
+        assert!(execution.actual_profit.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_executor_drains_queue_with_strategy_snapshot() {
+        let dexes = Arc::new(MockDex::standard_set());
+        let mut config = AppConfig::bundled_default();
+        config.submission.dry_run = true;
+        config.arbitrage.max_concurrent_opportunities = 2;
+        config.fees.priority_fee_lamports = 25_000;
+        config.submission.bundles_enabled = false;
+        config.submission.bundle_tip_lamports = 50_000;
+
+        let tokens = dexes[&DexType::Raydium].get_supported_tokens().await.unwrap();
+        let raydium = dexes[&DexType::Raydium].get_pools_by_tokens(&tokens[0], &tokens[1]).await.unwrap().remove(0);
+        let whirlpool = dexes[&DexType::Whirlpool].get_pools_by_tokens(&tokens[0], &tokens[1]).await.unwrap().remove(0);
+        let strategy = crate::arbitrage::engine::ArbitrageEngine::default_strategy(&config.arbitrage);
+        let mut strategies = StrategyManager::new();
+        strategies.add_strategy(strategy.clone()).unwrap();
+        let strategies = Arc::new(RwLock::new(strategies));
+
+        let opportunities = Arc::new(PriorityQueue::new(10));
+        let (execution_sender, mut execution_receiver) = mpsc::channel(10);
+        let executor = ArbitrageExecutor::new(dexes.clone(), execution_sender, config)
+            .with_opportunities(opportunities.clone())
+            .with_strategies(strategies);
+        let worker = tokio::spawn(executor.start());
+
+        for _ in 0..3 {
+            let mut opportunity = fixtures::opportunity_between(whirlpool.clone(), raydium.clone());
+            opportunity.tags.insert("strategy".to_string(), strategy.name.clone());
+            opportunities.push(crate::arbitrage::queue::Lane::Normal, 1.0, opportunity);
+        }
+        for _ in 0..3 {
+            let execution = tokio::time::timeout(Duration::from_secs(5), execution_receiver.recv()).await.unwrap().unwrap();
+            assert_eq!(execution.execution_status, ExecutionStatus::Simulated);
+            let mut snapshot = execution.config_snapshot.unwrap();
+            assert_eq!(snapshot.strategy_id.as_deref(), Some(strategy.id.as_str()));
+            assert_eq!(snapshot.priority_fee_lamports, 25_000);
+            assert_eq!(snapshot.bundle_tip_lamports, 0);
+            assert!(snapshot.verify());
+            snapshot.priority_fee_lamports = 0;
+            assert!(!snapshot.verify());
+        }
+
+        // Snapshots stored before fee settings were sealed still verify against their own fields
+        let legacy: ConfigSnapshot = serde_json::from_value(serde_json::json!({
+            "min_profit_threshold": 0.01, "max_slippage": 0.005, "gas_price_multiplier": 1.2,
+            "max_concurrent_opportunities": 10, "execution_timeout_seconds": 30,
+            "strategy_id": null, "strategy_name": null, "strategy_version": null,
+            "strategy_min_profit_threshold": null, "strategy_max_slippage": null,
+            "captured_at": "2024-03-01T00:00:00Z",
+            "hash": "313304d365ac3366e81f424d3a9e7be9919f6aaf53ed33fb6ad9891043be87a0",
+        }))
+        .unwrap();
+        assert_eq!(legacy.hash_version, 1);
+        assert!(legacy.verify());
+        assert!(!ConfigSnapshot { hash_version: ConfigSnapshot::HASH_VERSION, ..legacy.clone() }.verify());
+        assert!(!ConfigSnapshot { hash_version: 99, ..legacy }.verify());
+
+        // Closing the queue stops the executor
+        opportunities.close();
+        tokio::time::timeout(Duration::from_secs(5), worker).await.unwrap().unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_two_pool_trades_are_sized_to_the_optimal_input() {
+        let dexes = Arc::new(MockDex::standard_set());
+        let mut config = AppConfig::bundled_default();
+        config.scanner.max_trade_amount = 0.0;
+        let tokens = dexes[&DexType::Raydium].get_supported_tokens().await.unwrap();
+        let raydium = dexes[&DexType::Raydium].get_pools_by_tokens(&tokens[0], &tokens[1]).await.unwrap().remove(0);
+        let whirlpool = dexes[&DexType::Whirlpool].get_pools_by_tokens(&tokens[0], &tokens[1]).await.unwrap().remove(0);
+        let opportunity = fixtures::opportunity_between(whirlpool, raydium);
+        let optimal = opportunity.optimal_input().unwrap();
+
+        let (sender, _receiver) = mpsc::channel(1);
+        let executor = ArbitrageExecutor::new(dexes.clone(), sender.clone(), config.clone());
+        assert_eq!(executor.input_amount(&opportunity, None), optimal);
+
+        // The strategy's and the scanner's limits cap it
+        let mut strategy = crate::arbitrage::engine::ArbitrageEngine::default_strategy(&config.arbitrage);
+        strategy.max_trade_amount = Some(optimal / Decimal::from(2));
+        assert_eq!(executor.input_amount(&opportunity, Some(&strategy)), optimal / Decimal::from(2));
+        config.scanner.max_trade_amount = 0.25;
+        let executor = ArbitrageExecutor::new(dexes, sender, config);
+        assert_eq!(executor.input_amount(&opportunity, None), optimal.min(Decimal::new(25, 2)));
+    }
+
+    #[tokio::test]
+    async fn test_sell_leg_routes_through_jupiter_when_it_pays_more() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = |dex_type: DexType, usdc_reserve: i64| {
+            fixtures::pool(dex_type, &sol, &usdc)
+                .update_reserves(Decimal::from(10_000), Decimal::from(usdc_reserve))
+                .with_fee_rate(Decimal::new(25, 4))
+        };
+        let raydium = pool(DexType::Raydium, 1_500_000);
+        let mut dexes: HashMap<DexType, Box<dyn DexInterface>> = HashMap::new();
+        dexes.insert(DexType::Raydium, Box::new(MockDex::new(DexType::Raydium, vec![raydium.clone()])));
+        dexes.insert(DexType::Jupiter, Box::new(MockDex::new(DexType::Jupiter, vec![pool(DexType::Jupiter, 1_520_000)])));
+
+        let (dex, routed) = quote_leg(&dexes, &raydium, &sol, &usdc, Decimal::ONE, true).await.unwrap();
+        assert_eq!(dex.get_dex_type(), DexType::Jupiter);
+        let (dex, direct) = quote_leg(&dexes, &raydium, &sol, &usdc, Decimal::ONE, false).await.unwrap();
+        assert_eq!(dex.get_dex_type(), DexType::Raydium);
+        assert!(routed.output_amount > direct.output_amount);
+
+        // A worse aggregated route leaves the sell on the opportunity's own pool
+        dexes.insert(DexType::Jupiter, Box::new(MockDex::new(DexType::Jupiter, vec![pool(DexType::Jupiter, 1_480_000)])));
+        let (dex, _) = quote_leg(&dexes, &raydium, &sol, &usdc, Decimal::ONE, true).await.unwrap();
+        assert_eq!(dex.get_dex_type(), DexType::Raydium);
+    }
+
+    #[test]
+    fn test_leader_aware_bundling_never_splits_bundles_into_legs() {
+        let mut config = AppConfig::bundled_default();
+        config.wallet.keypair_path.clear();
+        config.submission.bundles_enabled = true;
+        assert!(LegSubmitter::from_config(&config).unwrap().is_none());
+        // Away from Jito leaders the bundle is held, so there are still no legs to send
+        config.submission.leader_aware = true;
+        assert!(LegSubmitter::from_config(&config).unwrap().is_none());
+        config.submission.bundles_enabled = false;
+        assert!(LegSubmitter::from_config(&config).is_err());
+        assert_eq!(SubmissionPlan::default().route, SubmissionRoute::Bundle);
+    }
+
+    /// A JSON-RPC node that reports every signature as failed on-chain
+    async fn failing_node() -> Arc<crate::services::solana::SolanaService> {
+        use serde_json::json;
+
+        let url = fixtures::rpc_node(|method, _| {
+            Ok(match method {
+                "getSignatureStatuses" => json!({
+                    "context": { "slot": 1 },
+                    "value": [{ "slot": 1, "confirmations": 0, "err": { "InstructionError": [0, { "Custom": 6001 }] }, "status": { "Err": { "InstructionError": [0, { "Custom": 6001 }] } }, "confirmationStatus": "confirmed" }],
+                }),
+                method => panic!("unexpected RPC call {}", method),
+            })
+        })
+        .await;
+        Arc::new(crate::services::solana::SolanaService::new(&url).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_legs_are_sent_in_turn_and_a_failed_leg_fails_the_execution() {
+        let dexes = Arc::new(MockDex::standard_set());
+        let mut config = AppConfig::bundled_default();
+        // Without bundles or a dry run, submitting needs a wallet
+        assert!(LegSubmitter::from_config(&config).is_err());
+        config.submission.dry_run = true;
+        assert!(LegSubmitter::from_config(&config).unwrap().is_none());
+        config.submission.dry_run = false;
+        config.arbitrage.max_slippage = 0.0;
+
+        let tokens = dexes[&DexType::Raydium].get_supported_tokens().await.unwrap();
+        let raydium = dexes[&DexType::Raydium].get_pools_by_tokens(&tokens[0], &tokens[1]).await.unwrap().remove(0);
+        let whirlpool = dexes[&DexType::Whirlpool].get_pools_by_tokens(&tokens[0], &tokens[1]).await.unwrap().remove(0);
+        let fees = Arc::new(FeeModel::new(&config));
+        fees.update_sol_prices(&HashMap::from([(DexType::Raydium, vec![raydium.clone()])]));
+        let opportunity = fixtures::opportunity_between(whirlpool, raydium);
+
+        let solana = failing_node().await;
+        let signer = SwapSigner {
+            sender: Arc::new(crate::services::sender::RpcSender::new(solana.clone())),
+            solana,
+            wallets: Arc::new(crate::services::wallet::Wallets::new(solana_sdk::signature::Keypair::new(), None)),
+            precision: Default::default(),
+            dry_run: false,
+            tenant_wallets: HashMap::new(),
+        };
+        let legs = Arc::new(LegSubmitter::new(signer, Duration::from_secs(5)));
+        let (execution_sender, mut execution_receiver) = mpsc::channel(10);
+        let executor = ArbitrageExecutor::new(dexes.clone(), execution_sender.clone(), config.clone())
+            .with_fee_model(fees)
+            .with_legs(legs);
+        executor.execute_opportunity(opportunity.clone()).await.unwrap();
+
+        // The first leg failed, so the second was never sent
+        let execution = execution_receiver.recv().await.unwrap();
+        assert_eq!(execution.execution_status, ExecutionStatus::Failed);
+        assert!(execution.error_message.unwrap().contains("failed"));
+        let leg_signatures = execution.tags.get("leg_signatures").unwrap();
+        assert_eq!(leg_signatures.split(',').count(), 1);
+        assert_eq!(execution.transaction_signature.as_deref(), Some(leg_signatures.as_str()));
+
+        // With no way to submit, an execution fails rather than being reported in flight
+        let executor = ArbitrageExecutor::new(dexes, execution_sender, config);
+        executor.execute_opportunity(opportunity).await.unwrap();
+        let execution = execution_receiver.recv().await.unwrap();
+        assert_eq!(execution.execution_status, ExecutionStatus::Failed);
+    }
+}
@@ -0,0 +1,285 @@
+use anyhow::Result;
+use chrono::{DateTime, Timelike, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    config::FlattenConfig,
+    dex::{DexInterface, DexType},
+    models::{Pool, PoolQuote, SendOptions, Token},
+    services::{metrics::MetricsRegistry, solana::{SolanaService, TokenHolding}},
+    utils::{clock::SharedClock, cron::CronSchedule},
+};
+
+/// How often the schedule is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(20);
+
+/// One holding converted, or left alone, by a flattening run
+#[derive(Debug, Clone, Serialize)]
+pub struct FlattenedPosition {
+    pub mint: String,
+    pub amount: Decimal,
+    /// Base amount the exit was quoted at
+    pub received: Decimal,
+    /// Value at the mid price less what was received: fees, price impact and spread
+    pub cost: Decimal,
+    pub venue: String,
+    /// Signature of the swap; None when nothing was sent
+    pub signature: Option<String>,
+    pub error: Option<String>,
+}
+
+/// What one flattening run did, reported apart from trading PnL
+#[derive(Debug, Clone, Serialize)]
+pub struct FlattenReport {
+    pub base_mint: String,
+    pub positions: Vec<FlattenedPosition>,
+    pub received: Decimal,
+    pub cost: Decimal,
+    pub ran_at: DateTime<Utc>,
+}
+
+impl FlattenReport {
+    pub fn flattened(&self) -> usize {
+        self.positions.iter().filter(|p| p.signature.is_some()).count()
+    }
+}
+
+/// Cost of exiting `amount` for `received` against the mid price, in the base asset. Without a
+/// mid price the quote's own price impact stands in for the distance from it.
+pub fn flattening_cost(amount: Decimal, received: Decimal, mid_price: Option<Decimal>, price_impact: Decimal) -> Decimal {
+    let fair = match mid_price {
+        Some(price) => amount * price,
+        None if price_impact < Decimal::ONE => received / (Decimal::ONE - price_impact),
+        None => received,
+    };
+    (fair - received).max(Decimal::ZERO)
+}
+
+/// Active pools trading `token` directly against `base`
+fn direct_pools<'a>(pools: &'a [Pool], token: &'a Token, base: &'a Token) -> impl Iterator<Item = &'a Pool> {
+    let mints = |p: &Pool| (p.token_a.mint, p.token_b.mint);
+    pools
+        .iter()
+        .filter(move |p| p.is_active && (mints(p) == (token.mint, base.mint) || mints(p) == (base.mint, token.mint)))
+}
+
+/// Mid price of `token` in `base` on the direct pool with the deepest base reserve
+fn mid_price(pools: &[Pool], token: &Token, base: &Token) -> Option<Decimal> {
+    direct_pools(pools, token, base)
+        .max_by_key(|p| if p.token_a.mint == base.mint { p.reserve_a } else { p.reserve_b })
+        .and_then(|p| p.get_price(base))
+}
+
+/// At a scheduled time, sells every holding of the owner wallet that is not the base asset or a
+/// kept quote asset into the base asset through the best quoted route, so each day starts flat
+pub struct Flattener {
+    solana: Arc<SolanaService>,
+    dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
+    wallet: Pubkey,
+    base_mint: Pubkey,
+    keep: HashSet<Pubkey>,
+    schedule: CronSchedule,
+    min_value: Decimal,
+    max_price_impact: Decimal,
+    slippage: Decimal,
+    report_only: bool,
+    clock: SharedClock,
+    metrics: MetricsRegistry,
+}
+
+impl Flattener {
+    pub fn new(
+        config: &FlattenConfig,
+        solana: Arc<SolanaService>,
+        dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
+        wallet: Pubkey,
+        clock: SharedClock,
+        metrics: MetricsRegistry,
+    ) -> Result<Self> {
+        let parse = |mint: &String| Pubkey::from_str(mint).map_err(|e| anyhow::anyhow!("Invalid flatten mint {}: {}", mint, e));
+        Ok(Self {
+            solana,
+            dex_instances,
+            wallet,
+            base_mint: parse(&config.base_mint)?,
+            keep: config.keep_mints.iter().map(parse).collect::<Result<_>>()?,
+            schedule: CronSchedule::parse(&config.schedule)?,
+            min_value: Decimal::from_f64(config.min_value).unwrap_or(Decimal::ZERO),
+            max_price_impact: Decimal::from_f64(config.max_price_impact).unwrap_or(Decimal::ONE),
+            slippage: Decimal::from_f64(config.slippage).unwrap_or(Decimal::new(1, 2)),
+            report_only: config.report_only,
+            clock,
+            metrics,
+        })
+    }
+
+    /// Start the worker loop, flattening once in each minute the schedule matches
+    pub async fn start(self) -> Result<()> {
+        info!("Starting flattener into {}{}", self.base_mint, if self.report_only { " (report only)" } else { "" });
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        let mut last_run: Option<DateTime<Utc>> = None;
+
+        loop {
+            interval.tick().await;
+            let now = self.clock.now();
+            let minute = now.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(now);
+            if !self.schedule.matches(now) || last_run == Some(minute) {
+                continue;
+            }
+            last_run = Some(minute);
+
+            match self.run_once().await {
+                Ok(report) => {
+                    info!(
+                        "Flattened {} of {} positions into {} {}, costing {}",
+                        report.flattened(), report.positions.len(), report.received, report.base_mint, report.cost
+                    );
+                    self.metrics.increment("flattening_cost", report.cost.try_into().unwrap_or(0.0)).await;
+                    self.metrics.increment("positions_flattened", report.flattened() as f64).await;
+                }
+                Err(e) => error!("Flattening failed: {}", e),
+            }
+        }
+    }
+
+    /// Convert what should be converted now
+    pub async fn run_once(&self) -> Result<FlattenReport> {
+        let base = self.token(&self.base_mint).await?;
+        let mut pools = Vec::new();
+        for dex_instance in self.dex_instances.values() {
+            match dex_instance.get_pools().await {
+                Ok(dex_pools) => pools.extend(dex_pools),
+                Err(e) => debug!("Flattener: failed to get pools from {}: {}", dex_instance.get_name(), e),
+            }
+        }
+
+        let mut report = FlattenReport {
+            base_mint: self.base_mint.to_string(),
+            positions: Vec::new(),
+            received: Decimal::ZERO,
+            cost: Decimal::ZERO,
+            ran_at: self.clock.now(),
+        };
+        for holding in self.solana.get_token_holdings(&self.wallet).await? {
+            if holding.amount == 0 || holding.is_frozen || holding.mint == self.base_mint || self.keep.contains(&holding.mint) {
+                continue;
+            }
+            if let Some(position) = self.flatten(&holding, &base, &pools).await {
+                if position.signature.is_some() {
+                    report.received += position.received;
+                    report.cost += position.cost;
+                }
+                report.positions.push(position);
+            }
+        }
+        Ok(report)
+    }
+
+    async fn token(&self, mint: &Pubkey) -> Result<Token> {
+        let decimals = self
+            .solana
+            .get_mint_decimals(mint)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("{} is not a mint", mint))?;
+        let symbol = mint.to_string()[..4].to_string();
+        let token = Token::new(*mint, symbol.clone(), symbol, decimals);
+        Ok(match self.solana.get_mint_program(mint).await? {
+            Some((program, extensions)) => token.with_token_program(program, extensions),
+            None => token,
+        })
+    }
+
+    /// Sell one holding through the best route; None for dust not worth the fees
+    async fn flatten(&self, holding: &TokenHolding, base: &Token, pools: &[Pool]) -> Option<FlattenedPosition> {
+        let token = Token::new(holding.mint, String::new(), String::new(), holding.decimals).with_token_program(holding.program_id, Vec::new());
+        let amount = Decimal::from(holding.amount) / Decimal::from(10u64.pow(holding.decimals as u32));
+        let mut position = FlattenedPosition {
+            mint: holding.mint.to_string(),
+            amount,
+            received: Decimal::ZERO,
+            cost: Decimal::ZERO,
+            venue: String::new(),
+            signature: None,
+            error: None,
+        };
+
+        let mut quotes = self.quotes(&token, base, amount, pools).await;
+        quotes.retain(|(_, quote)| quote.price_impact <= self.max_price_impact);
+        quotes.sort_by_key(|(_, quote)| std::cmp::Reverse(quote.output_amount));
+        let Some((_, best)) = quotes.first() else {
+            warn!("No route flattens {} {} into {}", amount, holding.mint, base.mint);
+            position.error = Some("no route".to_string());
+            return Some(position);
+        };
+        if best.output_amount < self.min_value {
+            debug!("Leaving {} {} worth {} in place", amount, holding.mint, best.output_amount);
+            return None;
+        }
+
+        let mid = mid_price(pools, &token, base);
+        for (dex_type, quote) in &quotes {
+            position.received = quote.output_amount;
+            position.cost = flattening_cost(amount, quote.output_amount, mid, quote.price_impact);
+            position.venue = format!("{:?}", dex_type);
+            if self.report_only {
+                info!("Report only: would sell {} {} on {} for {}", amount, holding.mint, position.venue, quote.output_amount);
+                return Some(position);
+            }
+            match self.dex_instances[dex_type].execute_swap(quote, &self.wallet, self.slippage, &SendOptions::default()).await {
+                Ok(signature) => {
+                    position.signature = Some(signature);
+                    position.error = None;
+                    return Some(position);
+                }
+                // Only some venues can sign; the next best route may
+                Err(e) => position.error = Some(e.to_string()),
+            }
+        }
+        warn!("Failed to flatten {} {}: {}", amount, holding.mint, position.error.as_deref().unwrap_or_default());
+        Some(position)
+    }
+
+    /// Quotes from every pool pairing the token with the base directly, and from the aggregator
+    async fn quotes(&self, token: &Token, base: &Token, amount: Decimal, pools: &[Pool]) -> Vec<(DexType, PoolQuote)> {
+        let mut quotes = Vec::new();
+        for pool in direct_pools(pools, token, base) {
+            let Some(dex) = self.dex_instances.get(&pool.dex_type) else {
+                continue;
+            };
+            match dex.get_quote(token, base, amount, Some(&pool.pool_address)).await {
+                Ok(quote) => quotes.push((pool.dex_type.clone(), quote)),
+                Err(e) => debug!("Flattener: no quote from pool {}: {}", pool.id, e),
+            }
+        }
+        if let Some(jupiter) = self.dex_instances.get(&DexType::Jupiter) {
+            match jupiter.get_quote(token, base, amount, None).await {
+                Ok(quote) => quotes.push((DexType::Jupiter, quote)),
+                Err(e) => debug!("Flattener: no aggregator quote for {}: {}", token.mint, e),
+            }
+        }
+        quotes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_is_measured_against_mid_price() {
+        // 10 tokens at a mid of 1.5 sold for 14.7: 0.3 lost to fees and impact
+        assert_eq!(flattening_cost(Decimal::from(10), Decimal::new(147, 1), Some(Decimal::new(15, 1)), Decimal::ZERO), Decimal::new(3, 1));
+        // Without a direct pool the quote's impact stands in: 9.8 at 2% impact was worth 10
+        assert_eq!(flattening_cost(Decimal::from(10), Decimal::new(98, 1), None, Decimal::new(2, 2)), Decimal::new(2, 1));
+        // A fill better than mid costs nothing
+        assert_eq!(flattening_cost(Decimal::from(10), Decimal::from(16), Some(Decimal::new(15, 1)), Decimal::ZERO), Decimal::ZERO);
+    }
+}
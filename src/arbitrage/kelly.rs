@@ -0,0 +1,223 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tracing::debug;
+
+use crate::arbitrage::strategy::{Strategy, StrategyParameters};
+use crate::models::{ArbitrageOpportunity, ArbitrageStrategy};
+use crate::services::memory_store::{MemoryStore, OutcomeStats};
+use crate::utils::panic_guard::lock_or_recover;
+
+/// Kind name of `KellyStrategy` in strategy definitions
+pub const KELLY_STRATEGY_KIND: &str = "kelly";
+
+/// How a Kelly strategy turns track record into trade size
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KellyParameters {
+    /// Capital the fraction is taken of, in the input token
+    pub bankroll: Decimal,
+    /// Never stake more than this fraction, however good the record
+    pub max_fraction: Decimal,
+    /// Scale applied to the full Kelly fraction; half-Kelly trades some growth for far less variance
+    pub kelly_multiplier: Decimal,
+    /// Executions needed before the record is trusted
+    pub min_trades: u64,
+    /// Fraction staked until then
+    pub warmup_fraction: Decimal,
+    /// Days of executions the record covers
+    pub lookback_days: i64,
+}
+
+impl Default for KellyParameters {
+    fn default() -> Self {
+        Self {
+            bankroll: Decimal::from(100),
+            max_fraction: Decimal::new(25, 2),
+            kelly_multiplier: Decimal::new(5, 1),
+            min_trades: 30,
+            warmup_fraction: Decimal::new(1, 2),
+            lookback_days: 7,
+        }
+    }
+}
+
+/// Full Kelly fraction `p − q / b` for win rate `p` and win/loss ratio `b`; never negative
+pub fn kelly_fraction(stats: &OutcomeStats) -> Decimal {
+    let p = stats.win_rate();
+    if stats.wins == 0 {
+        return Decimal::ZERO;
+    }
+    // Losses that cost nothing leave no reason to hold back
+    if stats.average_loss <= Decimal::ZERO {
+        return p;
+    }
+    let b = stats.average_win / stats.average_loss;
+    (p - (Decimal::ONE - p) / b).max(Decimal::ZERO)
+}
+
+/// Filters like the base strategy, and stakes a Kelly fraction of the bankroll derived from the
+/// win rate and win and loss sizes of recent executions.
+///
+/// Strategies are evaluated synchronously, so the record is a snapshot taken by `refresh`, or
+/// handed over through `update_record` by the strategy manager.
+pub struct KellyStrategy {
+    base: Box<dyn Strategy>,
+    kelly: KellyParameters,
+    stats: Mutex<OutcomeStats>,
+}
+
+impl KellyStrategy {
+    pub fn new(base: impl Strategy + 'static, kelly: KellyParameters) -> Self {
+        Self { base: Box::new(base), kelly, stats: Mutex::new(OutcomeStats::default()) }
+    }
+
+    /// Build from a definition filtering on its own thresholds, reading its `parameters`; none
+    /// gives the defaults
+    pub fn from_definition(definition: &ArbitrageStrategy) -> Result<Self> {
+        let kelly = if definition.parameters.is_null() {
+            KellyParameters::default()
+        } else {
+            serde_json::from_value(definition.parameters.clone())?
+        };
+        Ok(Self::new(definition.clone(), kelly))
+    }
+
+    /// Take a fresh snapshot of the execution record
+    pub async fn refresh(&self, store: &MemoryStore) {
+        self.update_record(store.get_outcome_stats(self.kelly.lookback_days).await);
+    }
+
+    fn fraction_for(&self, stats: &OutcomeStats) -> Decimal {
+        let fraction = if stats.trades < self.kelly.min_trades {
+            self.kelly.warmup_fraction
+        } else {
+            kelly_fraction(stats) * self.kelly.kelly_multiplier
+        };
+        fraction.min(self.kelly.max_fraction).max(Decimal::ZERO)
+    }
+
+    /// Fraction of the bankroll staked per trade on the current record
+    pub fn fraction(&self) -> Decimal {
+        self.fraction_for(&lock_or_recover(&self.stats, "kelly stats"))
+    }
+}
+
+impl Strategy for KellyStrategy {
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+
+    fn description(&self) -> &str {
+        self.base.description()
+    }
+
+    /// No edge on the record means no stake
+    fn should_execute(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        self.base.should_execute(opportunity) && self.fraction() > Decimal::ZERO
+    }
+
+    fn calculate_optimal_amount(&self, opportunity: &ArbitrageOpportunity) -> Option<Decimal> {
+        let optimal = self.base.calculate_optimal_amount(opportunity)?;
+        let stake = self.kelly.bankroll * self.fraction();
+        (stake > Decimal::ZERO).then(|| optimal.min(stake))
+    }
+
+    fn record_lookback_days(&self) -> Option<i64> {
+        Some(self.kelly.lookback_days)
+    }
+
+    fn update_record(&self, stats: OutcomeStats) {
+        debug!("{}: {} trades, win rate {}, stake {}", self.base.name(), stats.trades, stats.win_rate(), self.fraction_for(&stats));
+        *lock_or_recover(&self.stats, "kelly stats") = stats;
+    }
+
+    fn get_parameters(&self) -> StrategyParameters {
+        self.base.get_parameters()
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.base.validate()?;
+        if self.kelly.bankroll <= Decimal::ZERO {
+            anyhow::bail!("Kelly bankroll must be positive");
+        }
+        if self.kelly.max_fraction <= Decimal::ZERO || self.kelly.max_fraction > Decimal::ONE {
+            anyhow::bail!("Kelly max fraction must be in (0, 1]");
+        }
+        if self.kelly.kelly_multiplier <= Decimal::ZERO {
+            anyhow::bail!("Kelly multiplier must be positive");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::strategy::{StrategyFactory, StrategyManager};
+    use crate::dex::DexType;
+    use crate::models::{ArbitrageExecution, ExecutionStatus, RiskScore};
+    use crate::utils::fixtures;
+
+    #[tokio::test]
+    async fn test_stake_follows_track_record() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = |sol_reserve: i64, usdc_reserve: i64| {
+            let mut pool = fixtures::pool(DexType::Raydium, &sol, &usdc);
+            pool.reserve_a = Decimal::from(sol_reserve);
+            pool.reserve_b = Decimal::from(usdc_reserve);
+            pool.fee_rate = Decimal::new(3, 3);
+            pool
+        };
+        let opportunity = fixtures::opportunity_between(pool(10_000, 1_550_000), pool(10_000, 1_500_000));
+
+        let store = MemoryStore::new(100, 1000);
+        let kelly = KellyStrategy::new(
+            StrategyFactory::create_aggressive_strategy(),
+            KellyParameters { bankroll: Decimal::from(10), min_trades: 10, ..KellyParameters::default() },
+        );
+        kelly.validate().unwrap();
+        // No record yet: the warm-up stake
+        kelly.refresh(&store).await;
+        assert_eq!(kelly.fraction(), Decimal::new(1, 2));
+        assert_eq!(kelly.calculate_optimal_amount(&opportunity), Some(Decimal::new(1, 1)));
+
+        // Six in ten trades win 2%, the rest lose 1%: full Kelly is 0.6 − 0.4 / 2 = 0.4
+        for i in 0..10 {
+            let mut execution = ArbitrageExecution::new(opportunity.clone());
+            execution.route.input_amount = Decimal::from(5);
+            execution.execution_status = ExecutionStatus::Confirmed;
+            execution.actual_profit = Some(if i < 6 { Decimal::new(1, 1) } else { Decimal::new(-5, 2) });
+            store.save_execution(&execution).await.unwrap();
+        }
+        kelly.refresh(&store).await;
+        assert_eq!(kelly_fraction(&store.get_outcome_stats(1).await), Decimal::new(4, 1));
+        // Half-Kelly
+        assert_eq!(kelly.fraction(), Decimal::new(2, 1));
+        assert_eq!(kelly.calculate_optimal_amount(&opportunity), Some(Decimal::from(2)));
+        assert!(kelly.should_execute(&opportunity));
+
+        // Built from a definition of the kelly kind and refreshed by the manager
+        let definition = ArbitrageStrategy::new(
+            "kelly".to_string(),
+            String::new(),
+            Decimal::ZERO,
+            Decimal::ONE,
+            Decimal::ONE,
+            Decimal::ZERO,
+            vec![DexType::Raydium],
+            RiskScore::Critical,
+        )
+        .with_kind(KELLY_STRATEGY_KIND, serde_json::json!({ "bankroll": 10, "min_trades": 10 }));
+        let mut manager = StrategyManager::new();
+        manager.add_strategy(definition.clone()).unwrap();
+        assert_eq!(manager.optimal_amount("kelly", &opportunity), Some(Decimal::new(1, 1)));
+        manager.refresh_records(&store).await;
+        assert_eq!(manager.optimal_amount("kelly", &opportunity), Some(Decimal::from(2)));
+
+        // A losing record stakes nothing
+        let losing = OutcomeStats { trades: 40, wins: 10, average_win: Decimal::new(1, 2), average_loss: Decimal::new(1, 2) };
+        assert_eq!(kelly_fraction(&losing), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tracing::debug;
+
+use crate::arbitrage::strategy::{Strategy, StrategyParameters};
+use crate::models::{ArbitrageOpportunity, ArbitrageStrategy};
+use crate::utils::math::MathUtils;
+use crate::utils::panic_guard::lock_or_recover;
+
+/// Kind name of `MeanReversionStrategy` in strategy definitions
+pub const MEAN_REVERSION_STRATEGY_KIND: &str = "mean_reversion";
+
+/// How a mean-reversion strategy tells a real dislocation from noise
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeanReversionParameters {
+    /// Spreads remembered per pool pair
+    pub window: usize,
+    /// Standard deviations above the mean a spread must reach to trigger
+    pub std_dev_multiplier: Decimal,
+    /// Spreads seen on a pair before it may trigger
+    pub min_samples: usize,
+}
+
+impl Default for MeanReversionParameters {
+    fn default() -> Self {
+        Self {
+            window: 30,
+            std_dev_multiplier: Decimal::TWO,
+            min_samples: 10,
+        }
+    }
+}
+
+/// Filters like the threshold strategy, and only executes when the spread between the pair's
+/// pools breaks out above the upper Bollinger band of the spreads seen on that pair before.
+///
+/// Pools of the same pair drift apart and back by small amounts all the time; trading each of
+/// those differences mostly pays fees. Spreads are sampled from every detected opportunity through
+/// `observe`, keyed by buy and sell pool.
+pub struct MeanReversionStrategy {
+    base: ArbitrageStrategy,
+    parameters: MeanReversionParameters,
+    spreads: Mutex<HashMap<(Pubkey, Pubkey), VecDeque<Decimal>>>,
+}
+
+impl MeanReversionStrategy {
+    pub fn new(base: ArbitrageStrategy, parameters: MeanReversionParameters) -> Self {
+        Self { base, parameters, spreads: Mutex::new(HashMap::new()) }
+    }
+
+    /// Build from a definition, reading its `parameters`; none gives the defaults
+    pub fn from_definition(definition: &ArbitrageStrategy) -> Result<Self> {
+        let parameters = if definition.parameters.is_null() {
+            MeanReversionParameters::default()
+        } else {
+            serde_json::from_value(definition.parameters.clone())?
+        };
+        Ok(Self::new(definition.clone(), parameters))
+    }
+
+    /// Upper band over the spreads recorded for the opportunity's pools, None until enough are seen
+    pub fn upper_band(&self, opportunity: &ArbitrageOpportunity) -> Option<Decimal> {
+        let spreads = lock_or_recover(&self.spreads, "mean reversion spreads");
+        let history: Vec<Decimal> = spreads.get(&Self::pair_key(opportunity))?.iter().copied().collect();
+        if history.len() < self.parameters.min_samples {
+            return None;
+        }
+        let (upper, _, _) = MathUtils::bollinger_bands(&history, history.len(), self.parameters.std_dev_multiplier)?;
+        upper.last().copied()
+    }
+
+    fn record_spread(&self, opportunity: &ArbitrageOpportunity) {
+        let mut spreads = lock_or_recover(&self.spreads, "mean reversion spreads");
+        let history = spreads.entry(Self::pair_key(opportunity)).or_default();
+        history.push_back(opportunity.profit_percentage);
+        while history.len() > self.parameters.window {
+            history.pop_front();
+        }
+    }
+
+    fn pair_key(opportunity: &ArbitrageOpportunity) -> (Pubkey, Pubkey) {
+        (opportunity.buy_pool.pool_address, opportunity.sell_pool.pool_address)
+    }
+}
+
+impl Strategy for MeanReversionStrategy {
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+
+    fn description(&self) -> &str {
+        self.base.description()
+    }
+
+    /// The spread is judged against the history before it; it joins it once observed
+    fn should_execute(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        let Some(upper_band) = self.upper_band(opportunity) else {
+            return false;
+        };
+        let breakout = opportunity.profit_percentage > upper_band;
+        if breakout {
+            debug!("{}: spread {} above band {} on {}", self.base.name, opportunity.profit_percentage, upper_band, opportunity.pair());
+        }
+        breakout && self.base.should_execute(opportunity)
+    }
+
+    fn calculate_optimal_amount(&self, opportunity: &ArbitrageOpportunity) -> Option<Decimal> {
+        self.base.calculate_optimal_amount(opportunity)
+    }
+
+    fn observe(&self, opportunity: &ArbitrageOpportunity) {
+        self.record_spread(opportunity);
+    }
+
+    fn get_parameters(&self) -> StrategyParameters {
+        self.base.get_parameters()
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.base.validate()?;
+        if self.parameters.min_samples < 2 {
+            anyhow::bail!("Mean reversion needs at least 2 samples for a standard deviation");
+        }
+        if self.parameters.window < self.parameters.min_samples {
+            anyhow::bail!("Mean reversion window must hold at least min_samples spreads");
+        }
+        if self.parameters.std_dev_multiplier <= Decimal::ZERO {
+            anyhow::bail!("Mean reversion std_dev_multiplier must be positive");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::DexType;
+    use crate::models::RiskScore;
+    use crate::utils::fixtures;
+
+    #[test]
+    fn test_only_spreads_beyond_the_band_trigger() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = |dex_type: DexType| fixtures::pool(dex_type, &sol, &usdc);
+        let template = fixtures::opportunity_between(pool(DexType::Raydium), pool(DexType::Meteora));
+        let spread = |basis_points: i64| {
+            let mut opportunity = template.clone();
+            opportunity.profit_percentage = Decimal::new(basis_points, 4);
+            opportunity.risk_score = RiskScore::Low;
+            opportunity
+        };
+
+        let definition = ArbitrageStrategy::new(
+            "reversion".to_string(),
+            String::new(),
+            Decimal::ZERO,
+            Decimal::new(1, 2),
+            Decimal::new(5, 3),
+            Decimal::from(1000),
+            DexType::pool_venues(),
+            RiskScore::Medium,
+        )
+        .with_kind(MEAN_REVERSION_STRATEGY_KIND, serde_json::json!({ "window": 10, "min_samples": 5 }));
+        let strategy = MeanReversionStrategy::from_definition(&definition).unwrap();
+        strategy.validate().unwrap();
+
+        // Noise around 50 bps builds the history without ever triggering
+        for basis_points in [47, 53, 48, 52, 50, 49, 51, 50] {
+            assert!(!strategy.should_execute(&spread(basis_points)));
+            strategy.observe(&spread(basis_points));
+        }
+        // Mean 50, sample deviation 2: the band sits at 54
+        assert_eq!(strategy.upper_band(&spread(0)).unwrap().round_dp(4), Decimal::new(54, 4));
+        assert!(!strategy.should_execute(&spread(53)));
+        assert!(strategy.should_execute(&spread(90)));
+
+        // Other pools of the same tokens keep their own history
+        let mut elsewhere = spread(90);
+        elsewhere.buy_pool = pool(DexType::Whirlpool);
+        assert!(!strategy.should_execute(&elsewhere));
+
+        let bad = definition.clone().with_kind(MEAN_REVERSION_STRATEGY_KIND, serde_json::json!({ "window": 3, "min_samples": 5 }));
+        assert!(MeanReversionStrategy::from_definition(&bad).unwrap().validate().is_err());
+    }
+}
@@ -1,7 +1,30 @@
 pub mod engine;
 pub mod strategy;
+pub mod kelly;
+pub mod mean_reversion;
 pub mod scanner;
 pub mod executor;
+pub mod preflight;
+pub mod compute_budget;
+pub mod token_accounts;
+pub mod risk;
+pub mod pair_stats;
+pub mod bootstrap;
+pub mod position_watcher;
+pub mod flattener;
+pub mod simulator;
+pub mod outcome;
+pub mod soak;
+pub mod routes;
+pub mod queue;
+pub mod scoring;
+pub mod backtest;
+pub mod seasonality;
+pub mod correlation;
+pub mod projection;
+pub mod benchmark;
+pub mod accounting;
+pub mod dedup;
 
 pub use engine::*;
 pub use strategy::*;
@@ -0,0 +1,122 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::models::{ArbitrageExecution, ArbitrageMetrics, ExecutionStatus, FailureReason};
+
+/// Key used for failed executions no reason could be derived for
+pub const UNCLASSIFIED: &str = "unclassified";
+
+/// Message fragments, lower-cased, checked in order before falling back to decoded program errors
+const PATTERNS: &[(&str, FailureReason)] = &[
+    ("front-run", FailureReason::FrontRun),
+    ("frontrun", FailureReason::FrontRun),
+    ("sandwich", FailureReason::FrontRun),
+    ("slippage", FailureReason::SlippageExceeded),
+    ("amount out below minimum", FailureReason::SlippageExceeded),
+    ("less than minimum amount out", FailureReason::SlippageExceeded),
+    ("blockhash not found", FailureReason::BlockhashExpired),
+    ("blockhashnotfound", FailureReason::BlockhashExpired),
+    ("block height exceeded", FailureReason::BlockhashExpired),
+    ("insufficient funds", FailureReason::InsufficientFunds),
+    ("insufficient lamports", FailureReason::InsufficientFunds),
+    ("insufficientfundsforfee", FailureReason::InsufficientFunds),
+    ("timed out", FailureReason::Timeout),
+    ("timeout", FailureReason::Timeout),
+];
+
+const CUSTOM_ERROR_PREFIX: &str = "custom program error: 0x";
+
+/// Derive a failure reason from an execution error and its transaction logs
+pub fn classify_failure(error: &str, logs: &[String]) -> Option<FailureReason> {
+    let lines: Vec<String> = std::iter::once(error)
+        .chain(logs.iter().map(String::as_str))
+        .map(str::to_lowercase)
+        .collect();
+
+    for (pattern, reason) in PATTERNS {
+        if lines.iter().any(|line| line.contains(pattern)) {
+            return Some(*reason);
+        }
+    }
+
+    if let Some(reason) = crate::dex::errors::decode_failure(error, logs).and_then(|info| info.failure_reason()) {
+        return Some(reason);
+    }
+
+    lines.iter().find_map(|line| custom_error_code(line)).map(FailureReason::ProgramError)
+}
+
+/// Parse the code out of `custom program error: 0x1771`
+pub fn custom_error_code(line: &str) -> Option<u32> {
+    let start = line.to_lowercase().find(CUSTOM_ERROR_PREFIX)? + CUSTOM_ERROR_PREFIX.len();
+    let hex: String = line[start..].chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+/// Count failed executions by reason label
+pub fn failure_breakdown<'a>(executions: impl IntoIterator<Item = &'a ArbitrageExecution>) -> HashMap<String, u64> {
+    let mut counts = HashMap::new();
+    for execution in executions {
+        if execution.execution_status != ExecutionStatus::Failed {
+            continue;
+        }
+        let label = execution.failure_reason.map(|r| r.label()).unwrap_or(UNCLASSIFIED);
+        *counts.entry(label.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Profit, fees, success rate and failure breakdown over a set of executions
+pub fn summarize(total_opportunities: u64, executions: &[ArbitrageExecution]) -> ArbitrageMetrics {
+    let executed_opportunities = executions.len() as u64;
+    let successful_executions = executions
+        .iter()
+        .filter(|e| e.execution_status == ExecutionStatus::Confirmed)
+        .count() as u64;
+
+    let total_profit: Decimal = executions.iter().filter_map(|e| e.actual_profit).sum();
+    let total_fees: Decimal = executions.iter().filter_map(|e| e.total_cost).sum();
+
+    let success_rate = if executed_opportunities > 0 {
+        Decimal::from(successful_executions) / Decimal::from(executed_opportunities)
+    } else {
+        Decimal::ZERO
+    };
+
+    ArbitrageMetrics {
+        total_opportunities,
+        executed_opportunities,
+        successful_executions,
+        total_profit,
+        total_fees,
+        net_profit: total_profit - total_fees,
+        success_rate,
+        average_execution_time: None, // Would need to calculate from execution data
+        failure_reasons: failure_breakdown(executions),
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_failure() {
+        assert_eq!(
+            classify_failure("Transaction simulation failed: Blockhash not found", &[]),
+            Some(FailureReason::BlockhashExpired)
+        );
+        let logs = vec![
+            "Program log: Error: insufficient funds".to_string(),
+            "Program Tokenkeg failed: custom program error: 0x1".to_string(),
+        ];
+        assert_eq!(classify_failure("transaction failed", &logs), Some(FailureReason::InsufficientFunds));
+        assert_eq!(
+            classify_failure("Error processing Instruction 2: custom program error: 0x1771", &[]),
+            Some(FailureReason::ProgramError(0x1771))
+        );
+        assert_eq!(classify_failure("confirmation timed out after 30s", &[]), Some(FailureReason::Timeout));
+        assert_eq!(classify_failure("something else", &[]), None);
+    }
+}
@@ -0,0 +1,215 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tracing::info;
+
+use crate::config::PairStatsConfig;
+use crate::models::{ArbitrageExecution, ArbitrageOpportunity, ExecutionStatus};
+use crate::utils::panic_guard::lock_or_recover;
+
+/// Base and quote mint
+pub type PairKey = (Pubkey, Pubkey);
+
+pub fn pair_key(opportunity: &ArbitrageOpportunity) -> PairKey {
+    (opportunity.base_token.mint, opportunity.quote_token.mint)
+}
+
+/// Why an opportunity on a pair is turned away
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum PairRejection {
+    #[error("Pair is cooling down until {until} after {failures} failed execution(s) in a row")]
+    Cooldown { until: DateTime<Utc>, failures: u32 },
+
+    #[error("Pair hits {hit_rate:.2} of the time, so needs a profit of {required} (got {profit})")]
+    LowHitRate { hit_rate: f64, required: Decimal, profit: Decimal },
+}
+
+impl PairRejection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PairRejection::Cooldown { .. } => "cooldown",
+            PairRejection::LowHitRate { .. } => "low_hit_rate",
+        }
+    }
+}
+
+/// Recent track record of one pair
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PairStats {
+    /// Latest finished executions, newest last; true for a profitable one
+    pub outcomes: VecDeque<bool>,
+    pub consecutive_failures: u32,
+    pub cooldown_until: Option<DateTime<Utc>>,
+}
+
+impl PairStats {
+    /// Fraction of the remembered executions that were profitable
+    pub fn hit_rate(&self) -> Option<f64> {
+        if self.outcomes.is_empty() {
+            return None;
+        }
+        Some(self.outcomes.iter().filter(|hit| **hit).count() as f64 / self.outcomes.len() as f64)
+    }
+}
+
+/// Per-pair cooldowns and hit rates, so the bot stops hammering a pair it keeps losing on.
+///
+/// A failed execution cools the pair down, doubling with each failure in a row. A pair whose
+/// rolling hit rate falls below `min_hit_rate` leaves the fast lane and only trades opportunities
+/// clearing `low_hit_rate_min_profit`; front-run and failed executions both count as misses.
+#[derive(Debug)]
+pub struct PairTracker {
+    config: PairStatsConfig,
+    pairs: Mutex<HashMap<PairKey, PairStats>>,
+}
+
+impl PairTracker {
+    pub fn new(config: &PairStatsConfig) -> Self {
+        Self { config: config.clone(), pairs: Mutex::new(HashMap::new()) }
+    }
+
+    /// Record a confirmed or failed execution; anything still in flight is ignored
+    pub fn record(&self, execution: &ArbitrageExecution, now: DateTime<Utc>) {
+        let hit = match execution.execution_status {
+            ExecutionStatus::Confirmed => execution.actual_profit.is_none_or(|profit| profit > Decimal::ZERO),
+            ExecutionStatus::Failed => false,
+            _ => return,
+        };
+        let mut pairs = lock_or_recover(&self.pairs, "pair stats");
+        let stats = pairs.entry(pair_key(&execution.opportunity)).or_default();
+        stats.outcomes.push_back(hit);
+        while stats.outcomes.len() > self.config.window.max(1) {
+            stats.outcomes.pop_front();
+        }
+
+        if execution.execution_status == ExecutionStatus::Failed {
+            stats.consecutive_failures += 1;
+            let cooldown = self.cooldown(stats.consecutive_failures);
+            stats.cooldown_until = Some(now + cooldown);
+            info!(
+                "Cooling down {} for {}s after {} failed execution(s) in a row",
+                execution.opportunity.pair(),
+                cooldown.num_seconds(),
+                stats.consecutive_failures
+            );
+        } else {
+            stats.consecutive_failures = 0;
+            stats.cooldown_until = None;
+        }
+    }
+
+    /// `cooldown_seconds` doubled for each failure after the first, capped at `max_cooldown_seconds`
+    fn cooldown(&self, failures: u32) -> Duration {
+        let factor = 2u64.saturating_pow(failures.saturating_sub(1));
+        let seconds = self.config.cooldown_seconds.saturating_mul(factor).min(self.config.max_cooldown_seconds);
+        Duration::seconds(seconds as i64)
+    }
+
+    /// Whether the opportunity's pair may trade now
+    pub fn check(&self, opportunity: &ArbitrageOpportunity, now: DateTime<Utc>) -> Result<(), PairRejection> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        let pairs = lock_or_recover(&self.pairs, "pair stats");
+        let Some(stats) = pairs.get(&pair_key(opportunity)) else {
+            return Ok(());
+        };
+        if let Some(until) = stats.cooldown_until.filter(|until| *until > now) {
+            return Err(PairRejection::Cooldown { until, failures: stats.consecutive_failures });
+        }
+        if let Some(hit_rate) = self.poor_hit_rate(stats) {
+            let required = Decimal::from_f64(self.config.low_hit_rate_min_profit).unwrap_or(Decimal::ZERO);
+            if opportunity.profit_percentage < required {
+                return Err(PairRejection::LowHitRate { hit_rate, required, profit: opportunity.profit_percentage });
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the opportunity's pair hits too rarely to jump the queue
+    pub fn is_deprioritized(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        self.config.enabled
+            && lock_or_recover(&self.pairs, "pair stats")
+                .get(&pair_key(opportunity))
+                .and_then(|stats| self.poor_hit_rate(stats))
+                .is_some()
+    }
+
+    /// The hit rate when enough executions are known and it is below `min_hit_rate`
+    fn poor_hit_rate(&self, stats: &PairStats) -> Option<f64> {
+        if stats.outcomes.len() < self.config.min_executions {
+            return None;
+        }
+        stats.hit_rate().filter(|hit_rate| *hit_rate < self.config.min_hit_rate)
+    }
+
+    /// Track record of a pair
+    pub fn stats(&self, pair: &PairKey) -> Option<PairStats> {
+        lock_or_recover(&self.pairs, "pair stats").get(pair).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::DexType;
+    use crate::utils::fixtures;
+
+    #[test]
+    fn test_failures_cool_the_pair_down_and_misses_raise_the_bar() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = |dex_type: DexType| fixtures::pool(dex_type, &sol, &usdc);
+        let mut opportunity = fixtures::opportunity_between(pool(DexType::Raydium), pool(DexType::Meteora));
+        opportunity.profit_percentage = Decimal::new(1, 2);
+        let traded = opportunity.clone();
+        let finished = |status: ExecutionStatus, profit: i64| {
+            let mut execution = ArbitrageExecution::new(traded.clone());
+            execution.execution_status = status;
+            execution.actual_profit = Some(Decimal::new(profit, 2));
+            execution
+        };
+
+        let config = PairStatsConfig {
+            cooldown_seconds: 10,
+            max_cooldown_seconds: 30,
+            window: 4,
+            min_executions: 4,
+            min_hit_rate: 0.5,
+            low_hit_rate_min_profit: 0.02,
+            ..PairStatsConfig::default()
+        };
+        let tracker = PairTracker::new(&config);
+        let now = Utc::now();
+        assert_eq!(tracker.check(&opportunity, now), Ok(()));
+
+        // 10s, then 20s, then capped at 30s
+        for (failures, cooldown) in [(1, 10), (2, 20), (3, 30)] {
+            tracker.record(&finished(ExecutionStatus::Failed, 0), now);
+            assert_eq!(
+                tracker.check(&opportunity, now),
+                Err(PairRejection::Cooldown { until: now + Duration::seconds(cooldown), failures })
+            );
+        }
+        let later = now + Duration::seconds(31);
+        // Three misses in the window are not enough executions to judge the hit rate yet
+        assert_eq!(tracker.check(&opportunity, later), Ok(()));
+        assert!(!tracker.is_deprioritized(&opportunity));
+
+        // A win ends the cooldown; one hit in four leaves the pair needing 2%
+        tracker.record(&finished(ExecutionStatus::Confirmed, 5), later);
+        assert!(tracker.is_deprioritized(&opportunity));
+        assert!(matches!(tracker.check(&opportunity, later), Err(PairRejection::LowHitRate { .. })));
+        opportunity.profit_percentage = Decimal::new(3, 2);
+        assert_eq!(tracker.check(&opportunity, later), Ok(()));
+
+        // Older misses roll out of the window; unprofitable confirmations still count as misses
+        tracker.record(&finished(ExecutionStatus::Confirmed, 5), later);
+        assert!(!tracker.is_deprioritized(&opportunity));
+        tracker.record(&finished(ExecutionStatus::Confirmed, -1), later);
+        assert_eq!(tracker.stats(&pair_key(&opportunity)).unwrap().hit_rate(), Some(0.5));
+    }
+}
@@ -0,0 +1,269 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+
+use crate::{
+    config::PositionWatcherConfig,
+    dex::{DexInterface, DexType},
+    models::Pool,
+    services::{fee_model::SolPrices, metrics::MetricsRegistry, solana::{SolanaService, TokenHolding}},
+    utils::clock::{SharedClock, SystemClock},
+};
+
+/// Whether a held token can be exited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HoldingStatus {
+    /// A quote pool can absorb the position
+    Sellable,
+    /// The token account is frozen by the mint authority
+    Frozen,
+    /// Pools exist but cannot absorb the position
+    NoLiquidity,
+    /// No active pool pairs the token with a quote mint
+    NoRoute,
+}
+
+/// Valuation of a single non-quote holding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldingReport {
+    pub wallet: String,
+    pub token_account: String,
+    pub mint: String,
+    pub amount: Decimal,
+    pub status: HoldingStatus,
+    /// What the best exit receives, valued in the watcher's valuation mint
+    pub exit_value: Option<Decimal>,
+    pub exit_pool: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl HoldingReport {
+    pub fn is_unsellable(&self) -> bool {
+        self.status != HoldingStatus::Sellable
+    }
+}
+
+/// Periodically values non-quote holdings of bot wallets and flags ones that cannot be exited
+pub struct PositionWatcher {
+    solana: Arc<SolanaService>,
+    dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
+    wallets: Vec<Pubkey>,
+    quote_mints: HashSet<String>,
+    /// Exits into different quote mints are compared in this one
+    valuation_mint: Pubkey,
+    max_exit_price_impact: Decimal,
+    interval: Duration,
+    reports: Arc<RwLock<Vec<HoldingReport>>>,
+    metrics: MetricsRegistry,
+    clock: SharedClock,
+}
+
+impl PositionWatcher {
+    pub fn new(
+        solana: Arc<SolanaService>,
+        dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
+        wallets: Vec<Pubkey>,
+        config: &PositionWatcherConfig,
+        metrics: MetricsRegistry,
+    ) -> Self {
+        Self {
+            solana,
+            dex_instances,
+            wallets,
+            quote_mints: config.quote_mints.iter().cloned().collect(),
+            valuation_mint: config
+                .quote_mints
+                .iter()
+                .find_map(|mint| mint.parse().ok())
+                .unwrap_or_else(spl_token_interface::native_mint::id),
+            max_exit_price_impact: Decimal::from_f64(config.max_exit_price_impact).unwrap_or(Decimal::ONE),
+            interval: Duration::from_secs(config.interval_seconds),
+            reports: Arc::new(RwLock::new(Vec::new())),
+            metrics,
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Stamp reports by `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Publish reports into a shared handle, e.g. one owned by the engine
+    pub fn with_reports(mut self, reports: Arc<RwLock<Vec<HoldingReport>>>) -> Self {
+        self.reports = reports;
+        self
+    }
+
+    /// Shared handle to the latest reports
+    pub fn reports(&self) -> Arc<RwLock<Vec<HoldingReport>>> {
+        self.reports.clone()
+    }
+
+    /// Start the watcher loop
+    pub async fn start(self) -> Result<()> {
+        info!("Starting position watcher for {} wallet(s)", self.wallets.len());
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.check_once().await {
+                error!("Position check failed: {}", e);
+            }
+        }
+    }
+
+    /// Value every non-quote holding once and publish the results
+    pub async fn check_once(&self) -> Result<Vec<HoldingReport>> {
+        let mut pools = Vec::new();
+        for dex_instance in self.dex_instances.values() {
+            match dex_instance.get_pools().await {
+                Ok(dex_pools) => pools.extend(dex_pools),
+                Err(e) => warn!("Position watcher: failed to get pools from {}: {}", dex_instance.get_name(), e),
+            }
+        }
+
+        let prices = SolPrices::from_pools(&pools);
+        let mut reports = Vec::new();
+        for wallet in &self.wallets {
+            for holding in self.solana.get_token_holdings(wallet).await? {
+                if holding.amount == 0 || self.quote_mints.contains(&holding.mint.to_string()) {
+                    continue;
+                }
+                reports.push(self.assess(wallet, &holding, &pools, &prices));
+            }
+        }
+
+        let unsellable = reports.iter().filter(|r| r.is_unsellable()).count();
+        for report in reports.iter().filter(|r| r.is_unsellable()) {
+            warn!(
+                "Unsellable holding {} {} in {}: {:?}",
+                report.amount, report.mint, report.wallet, report.status
+            );
+        }
+        self.metrics.set("unsellable_holdings", unsellable as f64).await;
+
+        *self.reports.write().await = reports.clone();
+        Ok(reports)
+    }
+
+    /// Find the best exit for a holding among quote pools. Outputs are valued in the valuation
+    /// mint through SOL; an exit into a quote mint that cannot be priced is not counted.
+    pub fn assess(&self, wallet: &Pubkey, holding: &TokenHolding, pools: &[Pool], prices: &SolPrices) -> HoldingReport {
+        let amount = Decimal::from(holding.amount) / Decimal::from(10u64.pow(holding.decimals as u32));
+        let mut report = HoldingReport {
+            wallet: wallet.to_string(),
+            token_account: holding.address.to_string(),
+            mint: holding.mint.to_string(),
+            amount,
+            status: HoldingStatus::NoRoute,
+            exit_value: None,
+            exit_pool: None,
+            checked_at: self.clock.now(),
+        };
+
+        if holding.is_frozen {
+            report.status = HoldingStatus::Frozen;
+            return report;
+        }
+
+        let exits = pools.iter().filter(|p| p.is_active).filter_map(|pool| {
+            let (token, quote) = if pool.token_a.mint == holding.mint {
+                (&pool.token_a, &pool.token_b)
+            } else if pool.token_b.mint == holding.mint {
+                (&pool.token_b, &pool.token_a)
+            } else {
+                return None;
+            };
+            self.quote_mints.contains(&quote.mint.to_string()).then_some((pool, token, quote))
+        });
+
+        for (pool, token, quote) in exits {
+            report.status = HoldingStatus::NoLiquidity;
+
+            let Some(output) = pool.calculate_output_amount(amount, token) else {
+                continue;
+            };
+            let impact = pool.calculate_price_impact(amount, token).unwrap_or(Decimal::ONE);
+            if output <= Decimal::ZERO || impact > self.max_exit_price_impact {
+                continue;
+            }
+            let Some(value) = prices.convert(output, &quote.mint, &self.valuation_mint) else {
+                continue;
+            };
+
+            if report.exit_value.is_none_or(|best| value > best) {
+                report.exit_value = Some(value);
+                report.exit_pool = Some(pool.id.clone());
+            }
+        }
+
+        if report.exit_value.is_some() {
+            report.status = HoldingStatus::Sellable;
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Token;
+
+    #[test]
+    fn test_exits_into_different_quote_mints_are_compared_in_the_valuation_mint() {
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "Solana".to_string(), 9);
+        let usdc = Token::new(Pubkey::new_unique(), "USDC".to_string(), "USD Coin".to_string(), 6);
+        let held = Token::new(Pubkey::new_unique(), "WIF".to_string(), "dogwifhat".to_string(), 6);
+        let pool = |id: &str, a: &Token, b: &Token, reserve_a: i64, reserve_b: i64| {
+            let mut pool = Pool::new(id.to_string(), DexType::Raydium, a.clone(), b.clone(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default())
+                .update_reserves(Decimal::from(reserve_a), Decimal::from(reserve_b));
+            pool.is_active = true;
+            pool
+        };
+        // 10 WIF sell for about 100 USDC, or about 1 SOL worth 150 USDC
+        let pools = vec![
+            pool("sol-usdc", &sol, &usdc, 10_000, 1_500_000),
+            pool("wif-usdc", &held, &usdc, 1_000_000, 10_000_000),
+            pool("wif-sol", &held, &sol, 1_000_000, 100_000),
+        ];
+        let config = PositionWatcherConfig {
+            enabled: true,
+            interval_seconds: 60,
+            quote_mints: vec![usdc.mint.to_string(), sol.mint.to_string()],
+            wallets: Vec::new(),
+            max_exit_price_impact: 0.5,
+        };
+        let solana = Arc::new(SolanaService::new("http://127.0.0.1:1").unwrap());
+        let watcher = PositionWatcher::new(solana, Arc::new(HashMap::new()), Vec::new(), &config, MetricsRegistry::new());
+        let holding = TokenHolding {
+            address: Pubkey::new_unique(),
+            mint: held.mint,
+            amount: 10_000_000,
+            decimals: 6,
+            is_frozen: false,
+            program_id: spl_token_interface::id(),
+            lamports: 0,
+        };
+
+        let report = watcher.assess(&Pubkey::new_unique(), &holding, &pools, &SolPrices::from_pools(&pools));
+        assert_eq!(report.status, HoldingStatus::Sellable);
+        assert_eq!(report.exit_pool.as_deref(), Some("wif-sol"));
+        let value = report.exit_value.unwrap();
+        assert!(value > Decimal::from(149) && value < Decimal::from(150), "{}", value);
+
+        // Without a SOL price for USDC the SOL exit cannot be valued, and the USDC one stands
+        let report = watcher.assess(&Pubkey::new_unique(), &holding, &pools, &SolPrices::from_pools(&pools[1..]));
+        assert_eq!(report.exit_pool.as_deref(), Some("wif-usdc"));
+        assert!(report.exit_value.unwrap() < Decimal::from(100));
+    }
+}
@@ -0,0 +1,266 @@
+use rust_decimal::Decimal;
+use solana_program::program_pack::Pack;
+use solana_rpc_client_api::response::UiAccount;
+use solana_sdk::{pubkey::Pubkey, transaction::Transaction};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::models::Token;
+use crate::services::jito::{BundleSimulation, JitoService};
+use crate::services::solana::SolanaService;
+
+/// Why a pre-flight stopped a submission
+#[derive(Debug, thiserror::Error)]
+pub enum PreflightError {
+    #[error("Simulation of leg {leg} failed: {error}")]
+    Failed { leg: usize, error: String, logs: Vec<String> },
+
+    #[error("Simulated profit {simulated} is below {floor} (expected {expected} less slippage)")]
+    ProfitEvaporated { simulated: Decimal, expected: Decimal, floor: Decimal },
+
+    #[error("Simulation unavailable: {0}")]
+    Rpc(#[from] anyhow::Error),
+}
+
+impl PreflightError {
+    /// Program logs of a failed simulation, for classifying the failure
+    pub fn logs(&self) -> &[String] {
+        match self {
+            PreflightError::Failed { logs, .. } => logs,
+            _ => &[],
+        }
+    }
+}
+
+/// What simulating every leg showed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreflightReport {
+    pub compute_units: u64,
+    /// Change in the start token's balance, summed over the legs
+    pub simulated_profit: Decimal,
+    /// Program logs of every leg, in order
+    pub logs: Vec<String>,
+    /// Whether the legs ran as one bundle, each on the state the previous one left
+    pub bundled: bool,
+}
+
+/// Raw amount of an SPL Token or Token-2022 account in a simulation's returned post-state
+fn token_amount(account: &UiAccount) -> Option<u64> {
+    let data = account.data.decode()?;
+    // Token-2022 extensions follow the shared base layout
+    let base = data.get(..spl_token_interface::state::Account::LEN)?;
+    Some(spl_token_interface::state::Account::unpack(base).ok()?.amount)
+}
+
+/// Abort unless the simulated profit is positive and within slippage of what the quotes promised
+fn check_profit(simulated: Decimal, expected: Decimal, slippage: Decimal) -> Result<(), PreflightError> {
+    let floor = (expected * (Decimal::ONE - slippage)).max(Decimal::ZERO);
+    if simulated <= Decimal::ZERO || simulated < floor {
+        return Err(PreflightError::ProfitEvaporated { simulated, expected, floor });
+    }
+    Ok(())
+}
+
+/// Start-token profit and failure of a simulated bundle, reading the watched account after the last leg
+fn bundle_outcome(simulation: &BundleSimulation, before: i128) -> Result<(u64, i128, Vec<String>), PreflightError> {
+    let logs: Vec<String> = simulation
+        .transaction_results
+        .iter()
+        .flat_map(|result| result.logs.clone().unwrap_or_default())
+        .collect();
+    if let Some((leg, error)) = simulation.failure() {
+        return Err(PreflightError::Failed { leg, error, logs });
+    }
+    let compute_units = simulation.transaction_results.iter().filter_map(|result| result.units_consumed).sum();
+    let after = simulation
+        .transaction_results
+        .last()
+        .and_then(|result| result.post_execution_accounts.as_ref()?.first())
+        .and_then(token_amount)
+        .unwrap_or(0) as i128;
+    Ok((compute_units, after - before, logs))
+}
+
+/// Simulates the legs before anything is sent.
+///
+/// With a `simulateBundle` endpoint the legs run in order as the bundle would, and the start
+/// token's final balance gives the profit. Otherwise each leg is simulated with
+/// `simulateTransaction` against current state, so a later leg needs the wallet to already hold
+/// its input, and the balance changes add up to the round trip's profit.
+pub struct Preflight {
+    solana: Arc<SolanaService>,
+    bundle: Option<(Arc<JitoService>, String)>,
+}
+
+impl Preflight {
+    pub fn new(solana: Arc<SolanaService>) -> Self {
+        Self { solana, bundle: None }
+    }
+
+    /// Simulate legs as one bundle through the `simulateBundle` RPC at `url`
+    pub fn with_bundle_simulation(mut self, jito: Arc<JitoService>, url: &str) -> Self {
+        if !url.is_empty() {
+            self.bundle = Some((jito, url.to_string()));
+        }
+        self
+    }
+
+    /// Start token balance before the legs run: zero when the account does not exist yet, since
+    /// the route creates it, but an error when it cannot be read
+    async fn balance_before(&self, account: &Pubkey) -> anyhow::Result<u64> {
+        match self.solana.get_token_account_balance(account).await {
+            Ok(amount) => Ok(amount),
+            Err(e) => match self.solana.get_multiple_accounts(&[*account]).await?.as_slice() {
+                [None] => Ok(0),
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Simulate `legs` spending `start_token` from `wallet`, expecting `expected_profit` of it back
+    pub async fn run(
+        &self,
+        legs: &[Transaction],
+        wallet: &Pubkey,
+        start_token: &Token,
+        expected_profit: Decimal,
+        slippage: Decimal,
+    ) -> Result<PreflightReport, PreflightError> {
+        let account = start_token.associated_token_address(wallet);
+        let before = self.balance_before(&account).await? as i128;
+
+        if let Some((jito, url)) = &self.bundle {
+            match jito.simulate_bundle(url, legs, &[account]).await {
+                Ok(simulation) => {
+                    let (compute_units, delta, logs) = bundle_outcome(&simulation, before)?;
+                    debug!("Bundle of {} legs simulated: {} CU, {} balance changed by {}", legs.len(), compute_units, start_token.symbol, delta);
+                    let simulated_profit = Decimal::from_i128_with_scale(delta, start_token.decimals as u32);
+                    check_profit(simulated_profit, expected_profit, slippage)?;
+                    return Ok(PreflightReport { compute_units, simulated_profit, logs, bundled: true });
+                }
+                Err(e) => warn!("Bundle simulation unavailable, simulating legs one by one: {}", e),
+            }
+        }
+
+        let mut compute_units = 0;
+        let mut delta: i128 = 0;
+        let mut all_logs = Vec::new();
+        for (leg, transaction) in legs.iter().enumerate() {
+            let result = self.solana.simulate_transaction(transaction, &[account]).await?;
+            all_logs.extend(result.logs.clone().unwrap_or_default());
+            if let Some(error) = &result.err {
+                return Err(PreflightError::Failed { leg, error: format!("{:?}", error), logs: all_logs });
+            }
+            compute_units += result.units_consumed.unwrap_or(0);
+            let after = result
+                .accounts
+                .as_ref()
+                .and_then(|accounts| accounts.first()?.as_ref())
+                .and_then(token_amount)
+                .unwrap_or(0) as i128;
+            debug!("Leg {} simulated: {} CU, {} balance {} -> {}", leg, result.units_consumed.unwrap_or(0), start_token.symbol, before, after);
+            delta += after - before;
+        }
+
+        let simulated_profit = Decimal::from_i128_with_scale(delta, start_token.decimals as u32);
+        check_profit(simulated_profit, expected_profit, slippage)?;
+        Ok(PreflightReport { compute_units, simulated_profit, logs: all_logs, bundled: false })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_simulated_balance_and_checks_profit() {
+        use base64::Engine;
+
+        let mut data = vec![0u8; spl_token_interface::state::Account::LEN];
+        let account = spl_token_interface::state::Account {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount: 1_250_000,
+            state: spl_token_interface::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        account.pack_into_slice(&mut data);
+        let ui_account = serde_json::json!({
+            "lamports": 2039280,
+            "data": [base64::engine::general_purpose::STANDARD.encode(&data), "base64"],
+            "owner": spl_token_interface::id().to_string(),
+            "executable": false,
+            "rentEpoch": 0,
+            "space": data.len()
+        });
+        let result: solana_rpc_client_api::response::RpcSimulateTransactionResult = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "logs": [],
+            "accounts": [ui_account.clone()],
+            "unitsConsumed": 48000
+        }))
+        .unwrap();
+        assert_eq!(result.accounts.unwrap()[0].as_ref().and_then(token_amount), Some(1_250_000));
+
+        // A bundle reads the balance once, after its last leg
+        let simulation: BundleSimulation = serde_json::from_value(serde_json::json!({
+            "summary": "succeeded",
+            "transactionResults": [
+                { "err": null, "logs": ["buy"], "unitsConsumed": 60000, "postExecutionAccounts": null },
+                { "err": null, "logs": ["sell"], "unitsConsumed": 48000, "postExecutionAccounts": [ui_account] }
+            ]
+        }))
+        .unwrap();
+        let (compute_units, delta, logs) = bundle_outcome(&simulation, 1_000_000).unwrap();
+        assert_eq!((compute_units, delta), (108_000, 250_000));
+        assert_eq!(logs, vec!["buy".to_string(), "sell".to_string()]);
+
+        // Results stop at the leg that failed
+        let failed: BundleSimulation = serde_json::from_value(serde_json::json!({
+            "summary": { "failed": { "error": { "TransactionFailure": [[], "InstructionError"] }, "tx_signature": null } },
+            "transactionResults": [{ "err": null, "logs": ["buy"], "unitsConsumed": 60000 }]
+        }))
+        .unwrap();
+        assert!(matches!(bundle_outcome(&failed, 0), Err(PreflightError::Failed { leg: 1, .. })));
+
+        let expected = Decimal::new(10, 2);
+        // Within 1% of the quoted profit passes; less, or no profit at all, aborts
+        assert!(check_profit(Decimal::new(995, 4), expected, Decimal::new(1, 2)).is_ok());
+        assert!(matches!(
+            check_profit(Decimal::new(98, 3), expected, Decimal::new(1, 2)),
+            Err(PreflightError::ProfitEvaporated { .. })
+        ));
+        assert!(check_profit(Decimal::ZERO, Decimal::ZERO, Decimal::new(1, 2)).is_err());
+
+        let failed = PreflightError::Failed {
+            leg: 1,
+            error: "InstructionError(2, Custom(6022))".to_string(),
+            logs: vec!["Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc failed: custom program error: 0x1786".to_string()],
+        };
+        assert!(failed.to_string().starts_with("Simulation of leg 1 failed"));
+        assert!(crate::dex::errors::decode_failure(&failed.to_string(), failed.logs()).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_missing_start_account_counts_as_empty_but_a_failed_read_is_an_error() {
+        use serde_json::json;
+
+        // Balances cannot be read; the account exists only when `exists` says so
+        let preflight = |exists: bool| async move {
+            let url = crate::utils::fixtures::rpc_node(move |method, _| match method {
+                "getTokenAccountBalance" => Err(json!({ "code": -32602, "message": "Invalid param: could not find account" })),
+                "getMultipleAccounts" => {
+                    let account = exists.then(|| json!({ "lamports": 1, "data": ["", "base64"], "owner": spl_token_interface::id().to_string(), "executable": false, "rentEpoch": 0, "space": 0 }));
+                    Ok(json!({ "context": { "slot": 1 }, "value": [account] }))
+                }
+                method => panic!("unexpected RPC call {}", method),
+            })
+            .await;
+            Preflight::new(Arc::new(SolanaService::new(&url).unwrap()))
+        };
+
+        let account = Pubkey::new_unique();
+        assert_eq!(preflight(false).await.balance_before(&account).await.unwrap(), 0);
+        assert!(preflight(true).await.balance_before(&account).await.is_err());
+    }
+}
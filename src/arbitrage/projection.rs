@@ -0,0 +1,168 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+
+use crate::models::{ArbitrageExecution, ArbitrageStrategy, ExecutionStatus};
+use crate::utils::math::MathUtils;
+
+/// Drawdowns, as fractions of peak equity, whose odds are reported
+const DRAWDOWN_LEVELS: [Decimal; 3] = [
+    Decimal::from_parts(5, 0, 0, false, 2),
+    Decimal::from_parts(10, 0, 0, false, 2),
+    Decimal::from_parts(20, 0, 0, false, 2),
+];
+
+/// How to run the projection
+#[derive(Debug, Clone)]
+pub struct ProjectionSettings {
+    pub runs: usize,
+    /// Executions per simulated run
+    pub trades: usize,
+    /// Equity drawdowns are measured against
+    pub starting_capital: Decimal,
+    pub seed: u64,
+}
+
+/// Distribution of simulated P&L over `trades` executions
+#[derive(Debug, Clone, PartialEq)]
+pub struct PnlProjection {
+    /// Past outcomes sampled from
+    pub samples: usize,
+    pub runs: usize,
+    pub trades: usize,
+    pub mean: Decimal,
+    pub p5: Decimal,
+    pub median: Decimal,
+    pub p95: Decimal,
+    pub probability_of_loss: Decimal,
+    /// Chance the worst drawdown of a run reaches each level
+    pub drawdown_odds: Vec<(Decimal, Decimal)>,
+}
+
+/// Net result of each finished execution: realized profit less costs, so failures count their fees
+pub fn outcomes(executions: &[ArbitrageExecution]) -> Vec<Decimal> {
+    executions
+        .iter()
+        .filter(|e| matches!(e.execution_status, ExecutionStatus::Confirmed | ExecutionStatus::Failed))
+        .map(|e| e.actual_profit.unwrap_or_default() - e.total_cost.unwrap_or_default())
+        .collect()
+}
+
+/// Executions by strategies that are still active, so the projection reflects the current mix.
+/// Without any known strategy everything is kept.
+pub fn current_mix(executions: Vec<ArbitrageExecution>, strategies: &[ArbitrageStrategy]) -> Vec<ArbitrageExecution> {
+    let active: HashSet<&str> = strategies.iter().filter(|s| s.is_active).map(|s| s.name.as_str()).collect();
+    if active.is_empty() {
+        return executions;
+    }
+    executions
+        .into_iter()
+        .filter(|e| e.tags.get("strategy").is_some_and(|name| active.contains(name.as_str())))
+        .collect()
+}
+
+fn percentile(sorted: &[Decimal], fraction: Decimal) -> Decimal {
+    let index = (fraction * Decimal::from(sorted.len() - 1)).round().to_usize().unwrap_or_default();
+    sorted[index.min(sorted.len() - 1)]
+}
+
+impl PnlProjection {
+    /// Bootstrap `settings.runs` sequences of past outcomes; None without outcomes to sample
+    pub fn simulate(outcomes: &[Decimal], settings: &ProjectionSettings) -> Option<Self> {
+        if outcomes.is_empty() || settings.runs == 0 || settings.trades == 0 {
+            return None;
+        }
+        let mut rng = StdRng::seed_from_u64(settings.seed);
+        let mut totals = Vec::with_capacity(settings.runs);
+        let mut drawdown_hits = vec![0usize; DRAWDOWN_LEVELS.len()];
+        let mut equity = Vec::with_capacity(settings.trades + 1);
+
+        for _ in 0..settings.runs {
+            equity.clear();
+            equity.push(settings.starting_capital);
+            let mut balance = settings.starting_capital;
+            for _ in 0..settings.trades {
+                balance += outcomes[rng.gen_range(0..outcomes.len())];
+                equity.push(balance);
+            }
+            totals.push(balance - settings.starting_capital);
+
+            // Ruin is a full drawdown, even where equity went negative
+            let drawdown = if equity.iter().any(|e| *e <= Decimal::ZERO) {
+                Decimal::ONE
+            } else {
+                MathUtils::max_drawdown(&equity).unwrap_or_default()
+            };
+            for (hits, level) in drawdown_hits.iter_mut().zip(DRAWDOWN_LEVELS) {
+                if drawdown >= level {
+                    *hits += 1;
+                }
+            }
+        }
+
+        totals.sort();
+        let runs = Decimal::from(settings.runs);
+        Some(Self {
+            samples: outcomes.len(),
+            runs: settings.runs,
+            trades: settings.trades,
+            mean: totals.iter().sum::<Decimal>() / runs,
+            p5: percentile(&totals, Decimal::new(5, 2)),
+            median: percentile(&totals, Decimal::new(5, 1)),
+            p95: percentile(&totals, Decimal::new(95, 2)),
+            probability_of_loss: Decimal::from(totals.iter().filter(|t| **t < Decimal::ZERO).count()) / runs,
+            drawdown_odds: DRAWDOWN_LEVELS
+                .into_iter()
+                .zip(drawdown_hits)
+                .map(|(level, hits)| (level, Decimal::from(hits) / runs))
+                .collect(),
+        })
+    }
+
+    /// Render the projection as lines for the terminal
+    pub fn render(&self) -> Vec<String> {
+        let percent = |value: Decimal| (value * Decimal::from(100)).round_dp(1);
+        let mut lines = vec![
+            format!("{} runs of {} executions sampled from {} past outcomes", self.runs, self.trades, self.samples),
+            format!(
+                "P&L: mean {}  p5 {}  median {}  p95 {}",
+                self.mean.round_dp(6),
+                self.p5.round_dp(6),
+                self.median.round_dp(6),
+                self.p95.round_dp(6)
+            ),
+            format!("P(loss): {}%", percent(self.probability_of_loss)),
+        ];
+        for (level, odds) in &self.drawdown_odds {
+            lines.push(format!("P(drawdown >= {}%): {}%", percent(*level), percent(*odds)));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projection_bounds_and_drawdown_odds() {
+        let settings = ProjectionSettings { runs: 500, trades: 50, starting_capital: Decimal::from(100), seed: 7 };
+        // Win 1 four times out of five, lose 2 otherwise: +0.4 per trade on average
+        let outcomes: Vec<Decimal> = [1, 1, 1, 1, -2].into_iter().map(Decimal::from).collect();
+        let projection = PnlProjection::simulate(&outcomes, &settings).unwrap();
+
+        assert!(projection.p5 <= projection.median && projection.median <= projection.p95);
+        assert!(projection.mean > Decimal::from(10) && projection.mean < Decimal::from(30));
+        assert!(projection.p95 <= Decimal::from(50));
+        assert!(projection.probability_of_loss < Decimal::new(5, 2));
+        // Losing 20 of a 100+ peak takes ten straight losses; the odds fall with the level
+        let odds: Vec<Decimal> = projection.drawdown_odds.iter().map(|(_, odds)| *odds).collect();
+        assert!(odds[0] >= odds[1] && odds[1] >= odds[2]);
+        assert!(odds[2] < Decimal::new(1, 2));
+
+        // Same seed, same projection
+        assert_eq!(PnlProjection::simulate(&outcomes, &settings), Some(projection));
+        assert_eq!(PnlProjection::simulate(&[], &settings), None);
+    }
+}
@@ -0,0 +1,362 @@
+use crossbeam_queue::ArrayQueue;
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+use crate::{config::LanesConfig, models::ArbitrageOpportunity, utils::panic_guard::lock_or_recover};
+
+/// Bounded lock-free queue that evicts the oldest entry when full.
+///
+/// Producers never wait, so a burst of discoveries cannot stall the scanner; stale opportunities
+/// are the ones given up. Meant for a single consumer.
+pub struct DropOldestQueue<T> {
+    queue: ArrayQueue<T>,
+    notify: Notify,
+    dropped: AtomicU64,
+}
+
+impl<T> DropOldestQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: ArrayQueue::new(capacity.max(1)),
+            notify: Notify::new(),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Enqueue an item, returning the entry evicted to make room
+    pub fn push(&self, item: T) -> Option<T> {
+        let evicted = self.queue.force_push(item);
+        if evicted.is_some() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        self.notify.notify_one();
+        evicted
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        self.queue.pop()
+    }
+
+    /// Wait for the next item; cancel-safe, since an item is only taken when it is returned
+    pub async fn recv(&self) -> T {
+        loop {
+            if let Some(item) = self.queue.pop() {
+                return item;
+            }
+            // notify_one stores a permit, so a push between the pop and this await is not missed
+            self.notify.notified().await;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    /// Items evicted since the queue was created
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Execution priority of a queued opportunity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lane {
+    /// Expected to vanish within a couple of slots, e.g. migrations and liquidations
+    Fast,
+    Normal,
+}
+
+impl Lane {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Lane::Fast => "fast",
+            Lane::Normal => "normal",
+        }
+    }
+
+    /// Fast when the opportunity expires within `fast_lane_max_slots` slots of `now`
+    pub fn classify(opportunity: &ArbitrageOpportunity, config: &LanesConfig, now: chrono::DateTime<chrono::Utc>) -> Lane {
+        let remaining_ms = (opportunity.expiry - now).num_milliseconds().max(0) as u64;
+        if remaining_ms < config.fast_lane_max_slots * config.slot_ms.max(1) {
+            Lane::Fast
+        } else {
+            Lane::Normal
+        }
+    }
+}
+
+/// Two drop-oldest lanes behind one consumer; the fast lane is always drained first
+pub struct LaneQueue<T> {
+    fast: DropOldestQueue<T>,
+    normal: DropOldestQueue<T>,
+    notify: Notify,
+}
+
+impl<T> LaneQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            fast: DropOldestQueue::new(capacity),
+            normal: DropOldestQueue::new(capacity),
+            notify: Notify::new(),
+        }
+    }
+
+    fn lane(&self, lane: Lane) -> &DropOldestQueue<T> {
+        match lane {
+            Lane::Fast => &self.fast,
+            Lane::Normal => &self.normal,
+        }
+    }
+
+    /// Enqueue on a lane, returning the entry evicted from that lane to make room
+    pub fn push(&self, lane: Lane, item: T) -> Option<T> {
+        let evicted = self.lane(lane).push(item);
+        self.notify.notify_one();
+        evicted
+    }
+
+    pub fn try_pop(&self) -> Option<(Lane, T)> {
+        self.fast
+            .try_pop()
+            .map(|item| (Lane::Fast, item))
+            .or_else(|| self.normal.try_pop().map(|item| (Lane::Normal, item)))
+    }
+
+    /// Wait for the next item, fast lane first; cancel-safe like `DropOldestQueue::recv`
+    pub async fn recv(&self) -> (Lane, T) {
+        loop {
+            if let Some(entry) = self.try_pop() {
+                return entry;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.fast.len() + self.normal.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fast.is_empty() && self.normal.is_empty()
+    }
+
+    pub fn lane_len(&self, lane: Lane) -> usize {
+        self.lane(lane).len()
+    }
+
+    /// Items evicted from both lanes since the queue was created
+    pub fn dropped(&self) -> u64 {
+        self.fast.dropped() + self.normal.dropped()
+    }
+}
+
+struct Scored<T> {
+    lane: Lane,
+    score: f64,
+    sequence: u64,
+    item: T,
+}
+
+impl<T> PartialEq for Scored<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl<T> Eq for Scored<T> {}
+
+impl<T> PartialOrd for Scored<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Fast lane first, then higher score, then first in
+impl<T> Ord for Scored<T> {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        (self.lane == Lane::Fast)
+            .cmp(&(other.lane == Lane::Fast))
+            .then_with(|| self.score.total_cmp(&other.score))
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// Bounded queue handing out fast-lane items before normal ones and, within a lane, the
+/// highest-scored first; equal scores leave in arrival order.
+///
+/// Like `DropOldestQueue`, producers never wait: when full, the lowest-scored entry is given up.
+/// Meant for a single consumer.
+pub struct PriorityQueue<T> {
+    heap: Mutex<BinaryHeap<Scored<T>>>,
+    capacity: usize,
+    sequence: AtomicU64,
+    notify: Notify,
+    dropped: AtomicU64,
+    closed: AtomicBool,
+}
+
+impl<T> PriorityQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            heap: Mutex::new(BinaryHeap::new()),
+            capacity: capacity.max(1),
+            sequence: AtomicU64::new(0),
+            notify: Notify::new(),
+            dropped: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Enqueue an item, returning the lowest-ranked entry given up to make room, possibly itself
+    pub fn push(&self, lane: Lane, score: f64, item: T) -> Option<T> {
+        let entry = Scored { lane, score, sequence: self.sequence.fetch_add(1, Ordering::Relaxed), item };
+        let evicted = {
+            let mut heap = lock_or_recover(&self.heap, "priority queue");
+            heap.push(entry);
+            if heap.len() > self.capacity {
+                let mut entries = std::mem::take(&mut *heap).into_vec();
+                let lowest = entries.iter().enumerate().min_by(|(_, a), (_, b)| a.cmp(b)).map(|(i, _)| i);
+                let evicted = lowest.map(|i| entries.swap_remove(i).item);
+                *heap = entries.into();
+                evicted
+            } else {
+                None
+            }
+        };
+        if evicted.is_some() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        self.notify.notify_one();
+        evicted
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        lock_or_recover(&self.heap, "priority queue").pop().map(|entry| entry.item)
+    }
+
+    /// Take out the first queued item `matches` accepts, if one is still waiting
+    pub fn remove(&self, matches: impl Fn(&T) -> bool) -> Option<T> {
+        let mut heap = lock_or_recover(&self.heap, "priority queue");
+        let mut entries = std::mem::take(&mut *heap).into_vec();
+        let removed = entries.iter().position(|entry| matches(&entry.item)).map(|i| entries.swap_remove(i).item);
+        *heap = entries.into();
+        removed
+    }
+
+    /// Wait for the best item; None once the queue is closed and drained. Cancel-safe, since an
+    /// item is only taken when it is returned
+    pub async fn recv(&self) -> Option<T> {
+        loop {
+            if let Some(item) = self.try_pop() {
+                return Some(item);
+            }
+            if self.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// Stop waiting consumers once the remaining items are taken
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.notify.notify_one();
+    }
+
+    pub fn len(&self) -> usize {
+        lock_or_recover(&self.heap, "priority queue").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Items given up since the queue was created
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_fast_lane_jumps_queued_normal_work() {
+        let queue = Arc::new(LaneQueue::new(8));
+        queue.push(Lane::Normal, 1);
+        queue.push(Lane::Normal, 2);
+        queue.push(Lane::Fast, 3);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.recv().await, (Lane::Fast, 3));
+        assert_eq!(queue.recv().await, (Lane::Normal, 1));
+
+        let consumer = tokio::spawn({
+            let queue = queue.clone();
+            async move { (queue.recv().await, queue.recv().await) }
+        });
+        tokio::task::yield_now().await;
+        queue.push(Lane::Fast, 4);
+        assert_eq!(consumer.await.unwrap(), ((Lane::Normal, 2), (Lane::Fast, 4)));
+    }
+
+    #[tokio::test]
+    async fn test_priority_queue_serves_best_score_and_drops_worst() {
+        let queue = Arc::new(PriorityQueue::new(3));
+        queue.push(Lane::Normal, 1.0, "a");
+        queue.push(Lane::Normal, 3.0, "b");
+        queue.push(Lane::Normal, 1.0, "c");
+        assert_eq!(queue.push(Lane::Normal, 2.0, "d"), Some("c"));
+        assert_eq!(queue.push(Lane::Normal, 0.5, "e"), Some("e"));
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(queue.recv().await, Some("b"));
+        assert_eq!(queue.recv().await, Some("d"));
+
+        // Fast-lane work goes ahead of better-scored normal work, and is the last to be dropped
+        queue.push(Lane::Normal, 5.0, "f");
+        queue.push(Lane::Fast, 0.1, "g");
+        assert_eq!(queue.push(Lane::Fast, 0.2, "h"), Some("a"));
+        assert_eq!(queue.recv().await, Some("h"));
+        assert_eq!(queue.recv().await, Some("g"));
+        assert_eq!(queue.recv().await, Some("f"));
+
+        queue.push(Lane::Normal, 1.0, "i");
+        queue.close();
+        assert_eq!(queue.recv().await, Some("i"));
+        assert_eq!(queue.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_oldest_and_wakes_consumer() {
+        let queue = Arc::new(DropOldestQueue::new(3));
+        for i in 0..5 {
+            queue.push(i);
+        }
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(queue.recv().await, 2);
+        assert_eq!(queue.try_pop(), Some(3));
+        assert_eq!(queue.try_pop(), Some(4));
+        assert!(queue.is_empty());
+
+        let consumer = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.recv().await }
+        });
+        tokio::task::yield_now().await;
+        queue.push(7);
+        assert_eq!(consumer.await.unwrap(), 7);
+    }
+}
@@ -0,0 +1,276 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tracing::warn;
+
+use crate::config::RiskConfig;
+use crate::models::{ArbitrageExecution, ArbitrageOpportunity, ExecutionStatus};
+use crate::services::alert_rules::{AlertAction, FiredAlert};
+use crate::services::fee_model::SolPrices;
+use crate::utils::panic_guard::lock_or_recover;
+
+/// A risk threshold an execution would cross
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RiskBreach {
+    #[error("{count} consecutive failed executions (limit {limit})")]
+    ConsecutiveFailures { count: u32, limit: u32 },
+
+    #[error("Lost {loss} today (limit {limit})")]
+    DailyLoss { loss: Decimal, limit: Decimal },
+
+    #[error("{in_flight} already at risk, {requested} more would exceed {limit}")]
+    CapitalAtRisk { in_flight: Decimal, requested: Decimal, limit: Decimal },
+
+    #[error("Realized profit averaged {shortfall} below estimates over the last executions (limit {limit})")]
+    AbnormalSlippage { shortfall: Decimal, limit: Decimal },
+}
+
+impl RiskBreach {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RiskBreach::ConsecutiveFailures { .. } => "consecutive_failures",
+            RiskBreach::DailyLoss { .. } => "daily_loss",
+            RiskBreach::CapitalAtRisk { .. } => "capital_at_risk",
+            RiskBreach::AbnormalSlippage { .. } => "abnormal_slippage",
+        }
+    }
+}
+
+/// Capital an opportunity ties up: its route's input, or the configured trade size
+pub fn capital(opportunity: &ArbitrageOpportunity, trade_size: Decimal) -> Decimal {
+    opportunity.route.as_ref().map(|route| route.input_amount).unwrap_or(trade_size)
+}
+
+#[derive(Debug)]
+struct RiskState {
+    consecutive_failures: u32,
+    day: NaiveDate,
+    /// Net profit of executions finished on `day`, in SOL
+    daily_pnl: Decimal,
+    /// Shortfall of realized against estimated profit, as a fraction of the estimate, newest last
+    shortfalls: VecDeque<Decimal>,
+    /// Set by failures or slippage; holds until reset
+    halted: Option<RiskBreach>,
+}
+
+impl RiskState {
+    fn roll_day(&mut self, now: DateTime<Utc>) {
+        if now.date_naive() != self.day {
+            self.day = now.date_naive();
+            self.daily_pnl = Decimal::ZERO;
+        }
+    }
+}
+
+/// Circuit breaker consulted before every execution.
+///
+/// Consecutive failures and abnormal slippage halt execution until `reset`; the daily loss limit
+/// lifts at UTC midnight; capital at risk only turns away opportunities that would exceed it.
+/// A limit of zero disables that check.
+#[derive(Debug)]
+pub struct RiskManager {
+    enabled: bool,
+    max_consecutive_failures: u32,
+    daily_loss_limit: Option<Decimal>,
+    max_capital_at_risk: Option<Decimal>,
+    max_slippage_shortfall: Option<Decimal>,
+    slippage_window: usize,
+    alert_channel: String,
+    state: Mutex<RiskState>,
+}
+
+impl RiskManager {
+    pub fn new(config: &RiskConfig, now: DateTime<Utc>) -> Self {
+        let limit = |value: f64| Decimal::from_f64(value).filter(|limit| *limit > Decimal::ZERO);
+        Self {
+            enabled: config.enabled,
+            max_consecutive_failures: config.max_consecutive_failures,
+            daily_loss_limit: limit(config.daily_loss_limit),
+            max_capital_at_risk: limit(config.max_capital_at_risk),
+            max_slippage_shortfall: limit(config.max_slippage_shortfall),
+            slippage_window: config.slippage_window.max(1),
+            alert_channel: config.alert_channel.clone(),
+            state: Mutex::new(RiskState {
+                consecutive_failures: 0,
+                day: now.date_naive(),
+                daily_pnl: Decimal::ZERO,
+                shortfalls: VecDeque::new(),
+                halted: None,
+            }),
+        }
+    }
+
+    /// Whether an execution tying up `requested` may go ahead while `in_flight` is already at risk
+    pub fn check(&self, in_flight: Decimal, requested: Decimal, now: DateTime<Utc>) -> Result<(), RiskBreach> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let mut state = lock_or_recover(&self.state, "risk manager");
+        if let Some(breach) = &state.halted {
+            return Err(breach.clone());
+        }
+        state.roll_day(now);
+        if let Some(limit) = self.daily_loss_limit {
+            if state.daily_pnl <= -limit {
+                return Err(RiskBreach::DailyLoss { loss: -state.daily_pnl, limit });
+            }
+        }
+        if let Some(limit) = self.max_capital_at_risk {
+            if in_flight + requested > limit {
+                return Err(RiskBreach::CapitalAtRisk { in_flight, requested, limit });
+            }
+        }
+        Ok(())
+    }
+
+    /// Account for a finished execution, returning the breach it trips, if any.
+    ///
+    /// Profit is realized in the route's start token and costs are paid in SOL, so the day's
+    /// P&L converts the profit to SOL through `prices`; without a price only the cost counts.
+    pub fn record(&self, execution: &ArbitrageExecution, prices: &SolPrices, now: DateTime<Utc>) -> Option<RiskBreach> {
+        if !self.enabled {
+            return None;
+        }
+        let mut state = lock_or_recover(&self.state, "risk manager");
+        state.roll_day(now);
+        let cost = execution.total_cost.unwrap_or(Decimal::ZERO);
+        match execution.execution_status {
+            ExecutionStatus::Confirmed => {
+                state.consecutive_failures = 0;
+                let profit = execution.actual_profit.unwrap_or(Decimal::ZERO);
+                let opportunity = &execution.opportunity;
+                let start = opportunity.route.as_ref().map_or(&opportunity.base_token, |route| &route.input_token);
+                let profit_sol = prices.convert(profit, &start.mint, &spl_token_interface::native_mint::id()).unwrap_or_else(|| {
+                    warn!("No SOL price for {}, counting only the cost of execution {} against the daily loss", start.symbol, execution.id);
+                    Decimal::ZERO
+                });
+                state.daily_pnl += profit_sol - cost;
+                let estimated = execution.opportunity.estimated_profit;
+                if execution.actual_profit.is_some() && estimated > Decimal::ZERO {
+                    state.shortfalls.push_back((estimated - profit) / estimated);
+                    while state.shortfalls.len() > self.slippage_window {
+                        state.shortfalls.pop_front();
+                    }
+                }
+            }
+            ExecutionStatus::Failed => {
+                state.consecutive_failures += 1;
+                state.daily_pnl -= cost;
+            }
+            _ => return None,
+        }
+        if state.halted.is_some() {
+            return None;
+        }
+
+        let breach = if self.max_consecutive_failures > 0 && state.consecutive_failures >= self.max_consecutive_failures {
+            Some(RiskBreach::ConsecutiveFailures { count: state.consecutive_failures, limit: self.max_consecutive_failures })
+        } else {
+            self.max_slippage_shortfall.and_then(|limit| {
+                if state.shortfalls.len() < self.slippage_window {
+                    return None;
+                }
+                let shortfall = state.shortfalls.iter().sum::<Decimal>() / Decimal::from(state.shortfalls.len());
+                (shortfall > limit).then_some(RiskBreach::AbnormalSlippage { shortfall: shortfall.round_dp(4), limit })
+            })
+        };
+        if let Some(breach) = &breach {
+            warn!("Risk limit breached, halting execution: {}", breach);
+            state.halted = Some(breach.clone());
+        }
+        breach
+    }
+
+    /// The breach execution is halted on, if any
+    pub fn halted(&self) -> Option<RiskBreach> {
+        lock_or_recover(&self.state, "risk manager").halted.clone()
+    }
+
+    /// Lift a halt and start counting failures and slippage afresh; the day's losses still count
+    pub fn reset(&self) {
+        let mut state = lock_or_recover(&self.state, "risk manager");
+        state.halted = None;
+        state.consecutive_failures = 0;
+        state.shortfalls.clear();
+    }
+
+    /// Notice to send when `breach` halts execution
+    pub fn alert(&self, breach: &RiskBreach, now: DateTime<Utc>) -> FiredAlert {
+        FiredAlert {
+            rule_name: format!("risk_{}", breach.label()),
+            action: AlertAction::Notify(self.alert_channel.clone()),
+            message: format!("Execution halted: {}", breach),
+            value: 0.0,
+            timestamp: now,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::DexType;
+    use crate::models::Token;
+    use crate::utils::fixtures;
+
+    #[test]
+    fn test_breaches_halt_until_reset() {
+        let config = RiskConfig {
+            max_consecutive_failures: 3,
+            daily_loss_limit: 5.0,
+            max_capital_at_risk: 10.0,
+            max_slippage_shortfall: 0.5,
+            slippage_window: 2,
+            ..RiskConfig::default()
+        };
+        let now = Utc::now();
+        let risk = RiskManager::new(&config, now);
+
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = || fixtures::pool(DexType::Raydium, &sol, &usdc);
+        let finished = |status: ExecutionStatus, profit: i64, cost: i64| {
+            let mut opportunity = fixtures::opportunity_between(pool(), pool());
+            opportunity.estimated_profit = Decimal::from(2);
+            let mut execution = ArbitrageExecution::new(opportunity);
+            execution.actual_profit = (status == ExecutionStatus::Confirmed).then(|| Decimal::from(profit));
+            execution.execution_status = status;
+            execution.total_cost = Some(Decimal::from(cost));
+            execution
+        };
+        // One SOL buys two of the start token
+        let native = Token::new(spl_token_interface::native_mint::id(), "WSOL".to_string(), "Wrapped SOL".to_string(), 9);
+        let market = fixtures::pool(DexType::Whirlpool, &native, &sol).update_reserves(Decimal::from(10), Decimal::from(20));
+        let prices = SolPrices::from_pools([&market]);
+
+        assert!(risk.check(Decimal::from(6), Decimal::from(4), now).is_ok());
+        assert!(matches!(risk.check(Decimal::from(7), Decimal::from(4), now), Err(RiskBreach::CapitalAtRisk { .. })));
+
+        // A success in between restarts the count
+        for status in [ExecutionStatus::Failed, ExecutionStatus::Failed, ExecutionStatus::Confirmed, ExecutionStatus::Failed, ExecutionStatus::Failed] {
+            assert_eq!(risk.record(&finished(status, 2, 0), &prices, now), None);
+        }
+        let breach = risk.record(&finished(ExecutionStatus::Failed, 0, 0), &prices, now);
+        assert_eq!(breach, Some(RiskBreach::ConsecutiveFailures { count: 3, limit: 3 }));
+        assert_eq!(risk.check(Decimal::ZERO, Decimal::ONE, now), Err(breach.unwrap()));
+        risk.reset();
+        assert!(risk.check(Decimal::ZERO, Decimal::ONE, now).is_ok());
+
+        // Landing well short of the estimate every time
+        assert_eq!(risk.record(&finished(ExecutionStatus::Confirmed, 0, 0), &prices, now), None);
+        assert!(matches!(risk.record(&finished(ExecutionStatus::Confirmed, 0, 0), &prices, now), Some(RiskBreach::AbnormalSlippage { .. })));
+        risk.reset();
+
+        // Losses count in SOL; a profit without a SOL price leaves only its cost
+        risk.record(&finished(ExecutionStatus::Confirmed, -7, 0), &SolPrices::default(), now);
+        risk.record(&finished(ExecutionStatus::Confirmed, -7, 1), &prices, now);
+        risk.reset();
+        assert!(risk.check(Decimal::ZERO, Decimal::ONE, now).is_ok());
+        // Losses stop trading for the rest of the day, whatever the resets
+        risk.record(&finished(ExecutionStatus::Failed, 0, 2), &prices, now);
+        risk.reset();
+        assert_eq!(risk.check(Decimal::ZERO, Decimal::ONE, now), Err(RiskBreach::DailyLoss { loss: Decimal::new(55, 1), limit: Decimal::from(5) }));
+        assert!(risk.check(Decimal::ZERO, Decimal::ONE, now + chrono::Duration::days(1)).is_ok());
+    }
+}
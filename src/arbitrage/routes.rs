@@ -0,0 +1,158 @@
+use rust_decimal::Decimal;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+
+use crate::{
+    config::TriangularConfig,
+    models::{ArbitrageRoute, Pool, Token},
+};
+
+/// Finds cycles through three or more pools that return more of the start token than they spend,
+/// e.g. SOL -> USDC -> BONK -> SOL
+pub struct RouteFinder {
+    max_hops: usize,
+    input_amount: Decimal,
+    min_profit: Decimal,
+}
+
+impl RouteFinder {
+    pub fn new(config: &TriangularConfig, min_profit: Decimal) -> Self {
+        Self {
+            max_hops: config.max_hops.max(3),
+            input_amount: Decimal::try_from(config.input_amount).unwrap_or(Decimal::ONE),
+            min_profit,
+        }
+    }
+
+    /// Profitable cycles starting from any of the given tokens, best first, and how many cycles were evaluated
+    pub fn find_profitable(&self, pools: &[Pool], starts: &[Token]) -> (Vec<ArbitrageRoute>, usize) {
+        let mut evaluated = 0;
+        let mut routes = Vec::new();
+        for start in starts {
+            for cycle in self.find_cycles(pools, start) {
+                evaluated += 1;
+                let mut route = ArbitrageRoute::new(
+                    cycle.iter().map(|&i| pools[i].clone()).collect(),
+                    start.clone(),
+                    start.clone(),
+                    self.input_amount,
+                );
+                let Some(output) = route.calculate_expected_output() else {
+                    continue;
+                };
+                if output >= self.input_amount * (Decimal::ONE + self.min_profit) {
+                    routes.push(route);
+                }
+            }
+        }
+        routes.sort_by_key(|route| std::cmp::Reverse(route.expected_output));
+        (routes, evaluated)
+    }
+
+    /// Every simple cycle of 3 to `max_hops` pools that starts and ends at `start`, as pool indices.
+    /// No pool and no intermediate token is visited twice.
+    pub fn find_cycles(&self, pools: &[Pool], start: &Token) -> Vec<Vec<usize>> {
+        let mut adjacency: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+        for (i, pool) in pools.iter().enumerate() {
+            adjacency.entry(pool.token_a.mint).or_default().push(i);
+            adjacency.entry(pool.token_b.mint).or_default().push(i);
+        }
+
+        let mut cycles = Vec::new();
+        let mut path = Vec::new();
+        let mut visited = vec![start.mint];
+        self.extend(pools, &adjacency, start.mint, start.mint, &mut path, &mut visited, &mut cycles);
+        cycles
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn extend(
+        &self,
+        pools: &[Pool],
+        adjacency: &HashMap<Pubkey, Vec<usize>>,
+        start: Pubkey,
+        current: Pubkey,
+        path: &mut Vec<usize>,
+        visited: &mut Vec<Pubkey>,
+        cycles: &mut Vec<Vec<usize>>,
+    ) {
+        if path.len() >= self.max_hops {
+            return;
+        }
+        for &index in adjacency.get(&current).map(Vec::as_slice).unwrap_or_default() {
+            if path.contains(&index) {
+                continue;
+            }
+            let pool = &pools[index];
+            let next = if pool.token_a.mint == current { pool.token_b.mint } else { pool.token_a.mint };
+
+            if next == start {
+                if path.len() + 1 >= 3 {
+                    let mut cycle = path.clone();
+                    cycle.push(index);
+                    cycles.push(cycle);
+                }
+                continue;
+            }
+            if visited.contains(&next) {
+                continue;
+            }
+
+            path.push(index);
+            visited.push(next);
+            self.extend(pools, adjacency, start, next, path, visited, cycles);
+            visited.pop();
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::DexType;
+
+    fn token(symbol: &str) -> Token {
+        Token::new(Pubkey::new_unique(), symbol.to_string(), symbol.to_string(), 6)
+    }
+
+    fn pool(a: &Token, b: &Token, reserve_a: i64, reserve_b: i64) -> Pool {
+        Pool::new(
+            format!("{}-{}", a.symbol, b.symbol),
+            DexType::Raydium,
+            a.clone(),
+            b.clone(),
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            Pubkey::default(),
+        )
+        .update_reserves(Decimal::from(reserve_a), Decimal::from(reserve_b))
+        .with_fee_rate(Decimal::new(25, 4))
+    }
+
+    #[test]
+    fn test_finds_mispriced_triangle() {
+        let (sol, usdc, bonk) = (token("SOL"), token("USDC"), token("BONK"));
+        let config = TriangularConfig { max_hops: 3, input_amount: 1.0, ..Default::default() };
+        let finder = RouteFinder::new(&config, Decimal::new(1, 3));
+
+        // SOL is 150 USDC, BONK is 0.00002 USDC, but the SOL/BONK pool prices SOL at 8M BONK
+        let pools = vec![
+            pool(&sol, &usdc, 100_000, 15_000_000),
+            pool(&usdc, &bonk, 1_000_000, 50_000_000_000),
+            pool(&sol, &bonk, 100_000, 800_000_000_000),
+        ];
+
+        // Both directions around the triangle, and nothing shorter than three hops
+        assert_eq!(finder.find_cycles(&pools, &sol).len(), 2);
+        assert!(finder.find_cycles(&pools[..2], &sol).is_empty());
+
+        let (routes, evaluated) = finder.find_profitable(&pools, std::slice::from_ref(&sol));
+        assert_eq!(evaluated, 2);
+        assert_eq!(routes.len(), 1);
+        // SOL -> BONK -> USDC -> SOL: 8M BONK sells for 160 USDC, which buys ~1.067 SOL before fees
+        let route = &routes[0];
+        assert_eq!(route.pools[0].id, "SOL-BONK");
+        assert!(route.expected_output > Decimal::new(105, 2));
+    }
+}
@@ -1,95 +1,459 @@
 use anyhow::Result;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Instant;
+use solana_program::pubkey::Pubkey;
 use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
-use tracing::{info, warn, error};
+use tracing::{debug, info, warn, error};
 
 use crate::{
-    config::AppConfig,
-    dex::{DexInterface, DexType},
+    config::{AppConfig, FeeOnTransferPolicy},
+    dex::{DexHealthTracker, DexInterface, DexType, PoolUpdate},
     models::{ArbitrageOpportunity, Token, Pool, RiskScore},
+    arbitrage::{dedup::{Coalesced, OpportunityDeduper}, queue::{Lane, LaneQueue}, routes::RouteFinder},
+    services::{fee_model::FeeModel, oracle::{self, OraclePrice, PriceOracle}, token_registry::TokenRegistry, PoolBlacklist},
+    utils::{
+        alloc_audit::{AllocScope, AllocStats},
+        clock::{SharedClock, SystemClock},
+        panic_guard::guard_async,
+    },
 };
 
+/// Upper bounds (exclusive) of the spread histogram buckets, as fractions
+const SPREAD_BUCKETS: [(&str, f64); 4] = [("<0.1%", 0.001), ("0.1-0.3%", 0.003), ("0.3-1%", 0.01), ("1-3%", 0.03)];
+
+/// Counters collected over one scan cycle and logged at its end
+#[derive(Debug, Clone, Default)]
+pub struct ScanSummary {
+    pub pools_by_dex: BTreeMap<String, usize>,
+    pub fetch_ms_by_dex: BTreeMap<String, u64>,
+    pub fetch_errors: usize,
+    /// Pool reserve updates applied since the previous cycle; zero for polling cycles
+    pub pool_updates: usize,
+    pub pairs_evaluated: usize,
+    pub opportunities_found: usize,
+    /// Repeats of live opportunities handed on as updates under the earlier ID
+    pub coalesced: usize,
+    /// Multi-hop cycles quoted by the route finder
+    pub cycles_evaluated: usize,
+    /// Pairs or pools dropped, keyed by reason
+    pub filtered: BTreeMap<&'static str, usize>,
+    /// Price differences seen, bucketed by spread
+    pub spreads: BTreeMap<&'static str, usize>,
+    pub cycle_ms: u64,
+    /// Heap allocations during the cycle, with the `alloc-audit` feature
+    pub allocations: Option<AllocStats>,
+}
+
+impl ScanSummary {
+    fn filter(&mut self, reason: &'static str, count: usize) {
+        if count > 0 {
+            *self.filtered.entry(reason).or_default() += count;
+        }
+    }
+
+    fn record_spread(&mut self, spread: Decimal) {
+        let spread: f64 = spread.try_into().unwrap_or(0.0);
+        let bucket = SPREAD_BUCKETS
+            .iter()
+            .find(|(_, upper)| spread < *upper)
+            .map(|(label, _)| *label)
+            .unwrap_or(">=3%");
+        *self.spreads.entry(bucket).or_default() += 1;
+    }
+
+    /// Emit the summary as a single structured log line
+    pub fn log(&self) {
+        info!(
+            cycle_ms = self.cycle_ms,
+            allocations = self.allocations.map(|a| a.allocations),
+            allocated_bytes = self.allocations.map(|a| a.bytes),
+            pools = ?self.pools_by_dex,
+            fetch_ms = ?self.fetch_ms_by_dex,
+            fetch_errors = self.fetch_errors,
+            pool_updates = self.pool_updates,
+            pairs_evaluated = self.pairs_evaluated,
+            found = self.opportunities_found,
+            coalesced = self.coalesced,
+            cycles = self.cycles_evaluated,
+            filtered = ?self.filtered,
+            spreads = ?self.spreads,
+            "Scan cycle summary"
+        );
+    }
+}
+
 pub struct OpportunityScanner {
     dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
-    opportunity_sender: mpsc::Sender<ArbitrageOpportunity>,
+    opportunities: Arc<LaneQueue<ArbitrageOpportunity>>,
     config: AppConfig,
     scan_interval: Duration,
+    blacklist: Option<Arc<PoolBlacklist>>,
+    health: Option<DexHealthTracker>,
+    oracle: Option<Arc<dyn PriceOracle>>,
+    fees: Arc<FeeModel>,
+    tokens: Option<Arc<TokenRegistry>>,
+    /// Pools from the last full refresh, kept current by streamed updates
+    pools: HashMap<DexType, Vec<Pool>>,
+    subscribed: HashSet<Pubkey>,
+    /// DEXes whose adapters cannot stream updates
+    unsubscribable: HashSet<DexType>,
+    deduper: OpportunityDeduper,
+    clock: SharedClock,
 }
 
 impl OpportunityScanner {
     pub fn new(
         dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
-        opportunity_sender: mpsc::Sender<ArbitrageOpportunity>,
+        opportunities: Arc<LaneQueue<ArbitrageOpportunity>>,
         config: AppConfig,
     ) -> Self {
         Self {
+            fees: Arc::new(FeeModel::new(&config)),
+            tokens: None,
             dex_instances,
-            opportunity_sender,
+            opportunities,
             config,
             scan_interval: Duration::from_secs(5), // Scan every 5 seconds
+            blacklist: None,
+            health: None,
+            oracle: None,
+            pools: HashMap::new(),
+            subscribed: HashSet::new(),
+            unsubscribable: HashSet::new(),
+            deduper: OpportunityDeduper::new(),
+            clock: SystemClock::shared(),
         }
     }
 
-    /// Start the scanner
+    /// Stamp, expire and deduplicate opportunities by `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Net out execution costs with this model, and keep its SOL prices current from scans
+    pub fn with_fee_model(mut self, fees: Arc<FeeModel>) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Name the tokens of every fetched pool from this registry
+    pub fn with_token_registry(mut self, tokens: Arc<TokenRegistry>) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+
+    /// Skip pools on the given blacklist
+    pub fn with_blacklist(mut self, blacklist: Arc<PoolBlacklist>) -> Self {
+        self.blacklist = Some(blacklist);
+        self
+    }
+
+    /// Apply per-DEX latency budgets and favour faster venues
+    pub fn with_health_tracker(mut self, health: DexHealthTracker) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Skip pools priced far from the oracle and grade opportunity risk by oracle deviation
+    pub fn with_oracle(mut self, oracle: Arc<dyn PriceOracle>) -> Self {
+        self.oracle = Some(oracle);
+        self
+    }
+
+    /// Usable oracle prices for every token in `all_pools`; empty without an oracle or when it fails
+    async fn oracle_prices(&self, all_pools: &HashMap<DexType, Vec<Pool>>) -> HashMap<Pubkey, OraclePrice> {
+        let Some(oracle) = &self.oracle else {
+            return HashMap::new();
+        };
+        let mints: Vec<Pubkey> = all_pools
+            .values()
+            .flatten()
+            .flat_map(|pool| [pool.token_a.mint, pool.token_b.mint])
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let settings = &self.config.oracle;
+        let max_deviation = Decimal::try_from(settings.max_deviation).unwrap_or(Decimal::ZERO);
+        match oracle.get_prices(&mints).await {
+            Ok(prices) => {
+                let now = self.clock.now();
+                prices
+                    .into_iter()
+                    .filter(|(_, price)| price.is_usable(now, settings.max_age_seconds, max_deviation))
+                    .collect()
+            }
+            Err(e) => {
+                warn!("Failed to fetch {} prices, scanning without them: {}", oracle.name(), e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Start the scanner. Pools are refreshed in full periodically; in between, every streamed
+    /// reserve change triggers a rescan of the cached pools.
     pub async fn start(mut self) -> Result<()> {
         info!("Starting opportunity scanner...");
+        let (update_sender, mut updates) = mpsc::channel(1024);
         
         loop {
             if let Err(e) = self.scan_opportunities().await {
                 error!("Error scanning opportunities: {}", e);
             }
+            self.subscribe_new_pools(&update_sender).await;
             
-            sleep(self.scan_interval).await;
+            // Polling stays the only trigger until a subscription is live
+            let interval = if self.subscribed.is_empty() {
+                self.scan_interval
+            } else {
+                Duration::from_secs(self.config.websocket.refresh_interval_seconds.max(1))
+            };
+            let refresh = sleep(interval);
+            tokio::pin!(refresh);
+            loop {
+                tokio::select! {
+                    _ = &mut refresh => break,
+                    Some(update) = updates.recv() => {
+                        // Coalesce a burst of updates into one rescan
+                        let mut applied = usize::from(self.apply_update(update));
+                        while let Ok(update) = updates.try_recv() {
+                            applied += usize::from(self.apply_update(update));
+                        }
+                        if applied > 0 {
+                            if let Err(e) = self.rescan_cached(applied).await {
+                                error!("Error scanning opportunities: {}", e);
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 
+    /// Subscribe to reserve changes of pools not streamed yet, up to `websocket.max_subscriptions`.
+    /// Over geyser, only the configured pools are streamed when any are.
+    async fn subscribe_new_pools(&mut self, sender: &mpsc::Sender<(DexType, Pubkey, PoolUpdate)>) {
+        if !self.config.websocket.enabled && !self.config.geyser.enabled {
+            return;
+        }
+        let geyser_pools: HashSet<String> = if self.config.geyser.enabled {
+            self.config.geyser.pools.iter().cloned().collect()
+        } else {
+            HashSet::new()
+        };
+        for (dex_type, pools) in &self.pools {
+            if self.unsubscribable.contains(dex_type) {
+                continue;
+            }
+            let Some(dex) = self.dex_instances.get(dex_type) else {
+                continue;
+            };
+            for pool in pools {
+                if self.subscribed.len() >= self.config.websocket.max_subscriptions {
+                    return;
+                }
+                if self.subscribed.contains(&pool.pool_address) {
+                    continue;
+                }
+                if !geyser_pools.is_empty() && !geyser_pools.contains(&pool.pool_address.to_string()) {
+                    continue;
+                }
+                let mut stream = match dex.subscribe_pool_updates(pool).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        warn!("{} cannot stream pool updates: {}", dex_type, e);
+                        self.unsubscribable.insert(dex_type.clone());
+                        break;
+                    }
+                };
+                self.subscribed.insert(pool.pool_address);
+                let (sender, dex_type, address) = (sender.clone(), dex_type.clone(), pool.pool_address);
+                tokio::spawn(async move {
+                    while let Some(update) = stream.update_receiver.recv().await {
+                        if sender.send((dex_type.clone(), address, update)).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    /// Apply a streamed reserve change to the cached pool, returning whether anything changed
+    fn apply_update(&mut self, (dex_type, address, update): (DexType, Pubkey, PoolUpdate)) -> bool {
+        let PoolUpdate::ReserveChange { reserve_a, reserve_b, timestamp } = update else {
+            return false;
+        };
+        let Some(pool) = self
+            .pools
+            .get_mut(&dex_type)
+            .and_then(|pools| pools.iter_mut().find(|pool| pool.pool_address == address))
+        else {
+            return false;
+        };
+        if pool.reserve_a == reserve_a && pool.reserve_b == reserve_b {
+            return false;
+        }
+        pool.set_reserves(reserve_a, reserve_b, timestamp);
+        true
+    }
+
+    /// Look for opportunities in the cached pools after streamed updates
+    async fn rescan_cached(&mut self, pool_updates: usize) -> Result<()> {
+        let mut summary = ScanSummary { pool_updates, ..Default::default() };
+        for (dex_type, pools) in &self.pools {
+            summary.pools_by_dex.insert(dex_type.to_string(), pools.len());
+        }
+        self.detect(summary, Instant::now(), AllocScope::start()).await
+    }
+
     /// Scan for arbitrage opportunities
     async fn scan_opportunities(&mut self) -> Result<()> {
+        let cycle_started = Instant::now();
+        let allocations = AllocScope::start();
+        let mut summary = ScanSummary::default();
         let mut all_pools = HashMap::new();
         
         // Fetch pools from all DEXes
         for (dex_type, dex_instance) in self.dex_instances.iter() {
-            match dex_instance.get_pools().await {
+            let fetch_started = Instant::now();
+            // A panicking adapter counts as a failed fetch rather than killing the scanner
+            let fetch = async {
+                guard_async(&format!("{} get_pools", dex_type), dex_instance.get_pools()).await?
+            };
+            let result = match &self.health {
+                Some(health) => health.measure(dex_type, fetch).await,
+                None => fetch.await,
+            };
+            summary
+                .fetch_ms_by_dex
+                .insert(dex_type.to_string(), fetch_started.elapsed().as_millis() as u64);
+            match result {
                 Ok(pools) => {
+                    summary.pools_by_dex.insert(dex_type.to_string(), pools.len());
                     all_pools.insert(dex_type.clone(), pools.clone());
                     info!("Retrieved {} pools from {}", pools.len(), dex_instance.get_name());
                 }
                 Err(e) => {
+                    summary.fetch_errors += 1;
                     warn!("Failed to get pools from {}: {}", dex_instance.get_name(), e);
                 }
             }
         }
         
+        if let Some(tokens) = &self.tokens {
+            tokens.enrich_pools(&mut all_pools, self.clock.now()).await;
+        }
+        self.pools = all_pools;
+        self.detect(summary, cycle_started, allocations).await
+    }
+
+    /// Find opportunities in the cached pools and hand them to the engine
+    async fn detect(&mut self, mut summary: ScanSummary, cycle_started: Instant, allocations: AllocScope) -> Result<()> {
         // Find arbitrage opportunities
-        let opportunities = self.find_arbitrage_opportunities(&all_pools).await?;
+        let opportunities = self.find_opportunities_with_summary(&self.pools, &mut summary).await?;
         
-        // Send arbitrage opportunities
-        for opportunity in opportunities {
-            if let Err(e) = self.opportunity_sender.send(opportunity).await {
-                error!("Failed to send opportunity: {}", e);
+        // Hand opportunities to the engine; when it falls behind the stalest ones are dropped.
+        // Repeats of a live opportunity go as updates to it, and not at all when nothing moved.
+        let now = self.clock.now();
+        self.deduper.prune(now);
+        for mut opportunity in opportunities {
+            match self.deduper.coalesce(&mut opportunity, now) {
+                Coalesced::New => {}
+                Coalesced::Update => summary.coalesced += 1,
+                Coalesced::Unchanged => {
+                    summary.filter("unchanged_repeat", 1);
+                    continue;
+                }
+            }
+            let lane = Lane::classify(&opportunity, &self.config.lanes, now);
+            if let Some(evicted) = self.opportunities.push(lane, opportunity) {
+                debug!("Detection queue full, dropped opportunity {}", evicted.id);
             }
         }
         
+        summary.cycle_ms = cycle_started.elapsed().as_millis() as u64;
+        summary.allocations = allocations.finish();
+        summary.log();
+        
         Ok(())
     }
 
     /// Find arbitrage opportunities
-    async fn find_arbitrage_opportunities(
+    pub async fn find_arbitrage_opportunities(
         &self,
         all_pools: &HashMap<DexType, Vec<Pool>>,
+    ) -> Result<Vec<ArbitrageOpportunity>> {
+        self.find_opportunities_with_summary(all_pools, &mut ScanSummary::default()).await
+    }
+
+    /// Find arbitrage opportunities, counting what was evaluated and filtered
+    async fn find_opportunities_with_summary(
+        &self,
+        all_pools: &HashMap<DexType, Vec<Pool>>,
+        summary: &mut ScanSummary,
     ) -> Result<Vec<ArbitrageOpportunity>> {
         let mut opportunities = Vec::new();
         
         // Get all token pairs
         let token_pairs = self.get_token_pairs(all_pools);
+        summary.pairs_evaluated = token_pairs.len();
+        
+        let min_liquidity = Decimal::try_from(self.config.scanner.min_liquidity).unwrap_or(Decimal::ZERO);
+        let blacklisted: HashSet<String> = match &self.blacklist {
+            Some(blacklist) => blacklist.entries().await.into_iter().map(|e| e.pool_address).collect(),
+            None => HashSet::new(),
+        };
+        let oracle_prices = self.oracle_prices(all_pools).await;
+        self.fees.update_sol_prices(all_pools);
+        let max_deviation = Decimal::try_from(self.config.oracle.max_deviation).unwrap_or(Decimal::ZERO);
         
         for (token_a, token_b) in token_pairs {
-            let pools_for_pair = self.get_pools_for_token_pair(all_pools, &token_a, &token_b);
+            if !self.config.scanner.is_pair_allowed(&token_a.mint.to_string(), &token_b.mint.to_string()) {
+                summary.filter("pair_not_allowed", 1);
+                continue;
+            }
+
+            if self.config.scanner.fee_on_transfer == FeeOnTransferPolicy::Exclude
+                && (token_a.has_transfer_fee() || token_b.has_transfer_fee())
+            {
+                summary.filter("transfer_fee", 1);
+                continue;
+            }
+            
+            let candidates = self.get_pools_for_token_pair(all_pools, &token_a, &token_b);
+            let candidate_count = candidates.len();
+            let liquid: Vec<Pool> = candidates
+                .into_iter()
+                .filter(|pool| pool.reserve_a + pool.reserve_b >= min_liquidity)
+                .collect();
+            summary.filter("low_liquidity_pool", candidate_count - liquid.len());
+            let liquid_count = liquid.len();
+            let pools_for_pair: Vec<Pool> = liquid
+                .into_iter()
+                .filter(|pool| !blacklisted.contains(&pool.pool_address.to_string()))
+                .collect();
+            summary.filter("blacklisted_pool", liquid_count - pools_for_pair.len());
+
+            // A pool far from the oracle price has most likely not caught up; its spread is not real
+            // `get_price(token_a)` is token_b priced in token_a
+            let reference = oracle::reference_price(&oracle_prices, &token_b.mint, &token_a.mint);
+            let pool_deviation = |pool: &Pool| {
+                let price = pool.get_price(&token_a)?;
+                Some(oracle::deviation(price, reference?))
+            };
+            let unfiltered_count = pools_for_pair.len();
+            let pools_for_pair: Vec<Pool> = pools_for_pair
+                .into_iter()
+                .filter(|pool| pool_deviation(pool).is_none_or(|d| d <= max_deviation))
+                .collect();
+            summary.filter("oracle_deviation", unfiltered_count - pools_for_pair.len());
             
             if pools_for_pair.len() < 2 {
+                summary.filter("single_venue", 1);
                 continue; // Need at least two pools for arbitrage
             }
             
@@ -98,23 +462,101 @@ impl OpportunityScanner {
             
             // Filter profitable opportunities
             for (buy_pool, sell_pool, _price_diff, profit_percentage) in price_differences {
-                if profit_percentage >= Decimal::try_from(self.config.arbitrage.min_profit_threshold).unwrap_or(Decimal::ZERO) {
-                    let opportunity = ArbitrageOpportunity::new(
+                summary.record_spread(profit_percentage);
+                if profit_percentage < Decimal::try_from(self.config.arbitrage.min_profit_threshold).unwrap_or(Decimal::ZERO) {
+                    summary.filter("below_threshold", 1);
+                } else {
+                    let mut opportunity = ArbitrageOpportunity::new_with_clock(
                         token_a.clone(),
                         token_b.clone(),
                         buy_pool.clone(),
                         sell_pool.clone(),
+                        &*self.clock,
                     );
+                    if let (Some(buy), Some(sell)) = (pool_deviation(&buy_pool), pool_deviation(&sell_pool)) {
+                        opportunity.apply_oracle_deviation(buy.max(sell), max_deviation);
+                    }
+                    // A route whose execution cost cannot be priced cannot be shown to pay for it
+                    let Some(network_cost) = self.network_cost(2, &token_a) else {
+                        summary.filter("unpriced_cost", 1);
+                        continue;
+                    };
+                    // Without a size that profits, net profit stays zero and the engine passes on it
+                    if let Some(input) = self.calculate_optimal_amount(&buy_pool, &sell_pool, &token_a) {
+                        opportunity.estimate_round_trip(input, network_cost);
+                        opportunity.tags.insert("estimated_input".to_string(), input.round_dp(9).normalize().to_string());
+                    }
                     
                     opportunities.push(opportunity);
                 }
             }
         }
         
+        if self.config.scanner.triangular.enabled {
+            for mut opportunity in self.find_cycle_opportunities(all_pools, &blacklisted, min_liquidity, summary) {
+                let hops = opportunity.route.as_ref().map_or(0, |route| route.pools.len());
+                let Some(network_cost) = self.network_cost(hops, &opportunity.base_token) else {
+                    summary.filter("unpriced_cost", 1);
+                    continue;
+                };
+                opportunity.estimated_fees += network_cost;
+                opportunity.net_profit -= network_cost;
+                opportunities.push(opportunity);
+            }
+        }
+        
+        // Send opportunities on faster, more reliable venues first
+        if let Some(health) = &self.health {
+            let weights = health.weights().await;
+            let weight_of = |dex_type: &DexType| weights.get(dex_type).copied().unwrap_or(1.0);
+            let score = |o: &ArbitrageOpportunity| {
+                let profit: f64 = o.profit_percentage.try_into().unwrap_or(0.0);
+                profit * weight_of(&o.buy_pool.dex_type).min(weight_of(&o.sell_pool.dex_type))
+            };
+            opportunities.sort_by(|a, b| score(b).partial_cmp(&score(a)).unwrap_or(std::cmp::Ordering::Equal));
+        }
+        
+        summary.opportunities_found = opportunities.len();
         info!("Found {} arbitrage opportunities", opportunities.len());
         Ok(opportunities)
     }
 
+    /// Opportunities for profitable multi-hop cycles through eligible pools
+    fn find_cycle_opportunities(
+        &self,
+        all_pools: &HashMap<DexType, Vec<Pool>>,
+        blacklisted: &HashSet<String>,
+        min_liquidity: Decimal,
+        summary: &mut ScanSummary,
+    ) -> Vec<ArbitrageOpportunity> {
+        let triangular = &self.config.scanner.triangular;
+        let exclude_transfer_fees = self.config.scanner.fee_on_transfer == FeeOnTransferPolicy::Exclude;
+        let pools: Vec<Pool> = all_pools
+            .values()
+            .flatten()
+            .filter(|pool| pool.reserve_a + pool.reserve_b >= min_liquidity)
+            .filter(|pool| !blacklisted.contains(&pool.pool_address.to_string()))
+            .filter(|pool| !exclude_transfer_fees || !(pool.token_a.has_transfer_fee() || pool.token_b.has_transfer_fee()))
+            .cloned()
+            .collect();
+
+        // Start tokens carry their metadata from whichever pool lists them
+        let mut starts: Vec<Token> = Vec::new();
+        for pool in &pools {
+            for token in [&pool.token_a, &pool.token_b] {
+                let mint = token.mint.to_string();
+                if triangular.start_mints.contains(&mint) && !starts.iter().any(|t| t.mint == token.mint) {
+                    starts.push(token.clone());
+                }
+            }
+        }
+
+        let min_profit = Decimal::try_from(self.config.arbitrage.min_profit_threshold).unwrap_or(Decimal::ZERO);
+        let (routes, evaluated) = RouteFinder::new(triangular, min_profit).find_profitable(&pools, &starts);
+        summary.cycles_evaluated = evaluated;
+        routes.into_iter().filter_map(|route| ArbitrageOpportunity::from_cycle(route, &*self.clock)).collect()
+    }
+
     /// Get all token pairs
     fn get_token_pairs(&self, all_pools: &HashMap<DexType, Vec<Pool>>) -> Vec<(Token, Token)> {
         let mut token_pairs = std::collections::HashSet::new();
@@ -163,6 +605,12 @@ impl OpportunityScanner {
         token_b: &Token,
     ) -> Vec<(Pool, Pool, Decimal, Decimal)> {
         let mut price_differences = Vec::new();
+
+        // Each token is transferred twice in a round trip; transfer fees eat into the spread
+        let retained = [token_a, token_b]
+            .iter()
+            .map(|token| Decimal::ONE - token.transfer_fee_rate())
+            .fold(Decimal::ONE, |acc, r| acc * r * r);
         
         for i in 0..pools.len() {
             for j in i + 1..pools.len() {
@@ -184,6 +632,7 @@ impl OpportunityScanner {
                     } else {
                         price_diff / price_a
                     };
+                    let profit_percentage = (Decimal::ONE + profit_percentage) * retained - Decimal::ONE;
                     
                     let (buy_pool, sell_pool) = if price_a < price_b {
                         (pool_a.clone(), pool_b.clone())
@@ -227,30 +676,237 @@ impl OpportunityScanner {
         true
     }
 
-    /// Calculate optimal trade amount
+    /// Profit-maximizing amount of `token_a` for the round trip, capped at `max_trade_amount`
     fn calculate_optimal_amount(
         &self,
         buy_pool: &Pool,
         sell_pool: &Pool,
         token_a: &Token,
     ) -> Option<Decimal> {
-        let buy_price = buy_pool.get_price(token_a)?;
-        let sell_price = sell_pool.get_price(token_a)?;
-        
-        if sell_price <= buy_price {
-            return None; // No arbitrage opportunity
+        let optimal = buy_pool.optimal_round_trip_input(sell_pool, token_a)?;
+        let max_trade_amount = Decimal::try_from(self.config.scanner.max_trade_amount).unwrap_or(Decimal::ZERO);
+        if max_trade_amount > Decimal::ZERO {
+            Some(optimal.min(max_trade_amount))
+        } else {
+            Some(optimal)
         }
-        
-        // Simple arbitrage amount calculation
-        // Real implementations need to consider additional factors
-        let max_amount = std::cmp::min(
-            buy_pool.reserve_a,
-            sell_pool.reserve_b,
-        );
-        
-        // Cap the maximum trade amount
-        let max_trade_amount = Decimal::from(10000); // Maximum trade amount
-        
-        Some(std::cmp::min(max_amount, max_trade_amount))
+    }
+
+    /// Execution cost of `legs` transactions in `token`, assuming the accounts they use exist.
+    ///
+    /// None when no pool prices `token` in SOL
+    pub(crate) fn network_cost(&self, legs: usize, token: &Token) -> Option<Decimal> {
+        self.fees.in_token(self.fees.estimate(legs, 0).total(), token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::path::PathBuf;
+
+    /// Recorded pool states and the exact opportunity set the scanner must find in them
+    #[derive(Serialize, Deserialize)]
+    struct GoldenCase {
+        description: String,
+        min_profit_threshold: f64,
+        pools: Vec<Pool>,
+        expected: Vec<GoldenOpportunity>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+    struct GoldenOpportunity {
+        base: String,
+        quote: String,
+        buy_pool: String,
+        sell_pool: String,
+        profit_percentage: String,
+    }
+
+    impl From<&ArbitrageOpportunity> for GoldenOpportunity {
+        fn from(opportunity: &ArbitrageOpportunity) -> Self {
+            Self {
+                base: opportunity.base_token.symbol.clone(),
+                quote: opportunity.quote_token.symbol.clone(),
+                buy_pool: opportunity.buy_pool.id.clone(),
+                sell_pool: opportunity.sell_pool.id.clone(),
+                profit_percentage: opportunity.profit_percentage.round_dp(10).normalize().to_string(),
+            }
+        }
+    }
+
+    fn golden_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/scanner")
+    }
+
+    /// Set UPDATE_GOLDEN=1 to rewrite the expected sets after an intentional change
+    #[tokio::test]
+    async fn test_golden_opportunities() {
+        let update = std::env::var("UPDATE_GOLDEN").is_ok();
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(golden_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty(), "no golden files found");
+
+        for path in paths {
+            let mut case: GoldenCase = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+            let mut config = AppConfig::bundled_default();
+            config.arbitrage.min_profit_threshold = case.min_profit_threshold;
+            let scanner = OpportunityScanner::new(Arc::new(HashMap::new()), Arc::new(LaneQueue::new(1)), config);
+
+            let mut all_pools: HashMap<DexType, Vec<Pool>> = HashMap::new();
+            for pool in &case.pools {
+                all_pools.entry(pool.dex_type.clone()).or_default().push(pool.clone());
+            }
+
+            let mut found: Vec<GoldenOpportunity> = scanner
+                .find_arbitrage_opportunities(&all_pools)
+                .await
+                .unwrap()
+                .iter()
+                .map(GoldenOpportunity::from)
+                .collect();
+            found.sort();
+
+            if update {
+                case.expected = found;
+                std::fs::write(&path, serde_json::to_string_pretty(&case).unwrap() + "\n").unwrap();
+                continue;
+            }
+
+            let mut expected = case.expected.clone();
+            expected.sort();
+            assert_eq!(found, expected, "golden mismatch in {}", path.display());
+        }
+    }
+
+    struct FixedOracle(HashMap<Pubkey, OraclePrice>);
+
+    #[async_trait::async_trait]
+    impl PriceOracle for FixedOracle {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn get_prices(&self, _mints: &[Pubkey]) -> Result<HashMap<Pubkey, OraclePrice>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oracle_filters_stale_pools() {
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "Solana".to_string(), 9);
+        let usdc = Token::new(Pubkey::new_unique(), "USDC".to_string(), "USD Coin".to_string(), 6);
+        let pool = |id: &str, dex_type: DexType, usdc_reserve: i64| {
+            Pool::new(id.to_string(), dex_type, sol.clone(), usdc.clone(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default())
+                .update_reserves(Decimal::from(10_000), Decimal::from(usdc_reserve))
+        };
+        let mut all_pools: HashMap<DexType, Vec<Pool>> = HashMap::new();
+        all_pools.insert(DexType::Raydium, vec![pool("fresh-low", DexType::Raydium, 1_500_000)]);
+        all_pools.insert(DexType::Meteora, vec![pool("fresh-high", DexType::Meteora, 1_515_000)]);
+        // Ten percent above the market: a pool that has not been updated
+        all_pools.insert(DexType::Whirlpool, vec![pool("stale", DexType::Whirlpool, 1_650_000)]);
+
+        let mut config = AppConfig::bundled_default();
+        config.arbitrage.min_profit_threshold = 0.001;
+        let usd = |price: i64| OraclePrice { price: Decimal::from(price), confidence: Decimal::ZERO, publish_time: chrono::Utc::now() };
+        let oracle = FixedOracle(HashMap::from([(sol.mint, usd(150)), (usdc.mint, usd(1))]));
+        let scanner = OpportunityScanner::new(Arc::new(HashMap::new()), Arc::new(LaneQueue::new(1)), config)
+            .with_oracle(Arc::new(oracle));
+
+        let mut summary = ScanSummary::default();
+        let found = scanner.find_opportunities_with_summary(&all_pools, &mut summary).await.unwrap();
+        assert_eq!(summary.filtered.get("oracle_deviation"), Some(&1));
+        assert_eq!(found.len(), 1);
+        let mut pools = [found[0].buy_pool.id.as_str(), found[0].sell_pool.id.as_str()];
+        pools.sort();
+        assert_eq!(pools, ["fresh-high", "fresh-low"]);
+        // One percent off the oracle, measured in whichever direction the pair is oriented
+        let deviation: Decimal = found[0].tags["oracle_deviation"].parse().unwrap();
+        assert!((deviation - Decimal::new(1, 2)).abs() < Decimal::new(2, 4), "{}", deviation);
+    }
+
+    #[tokio::test]
+    async fn test_net_profit_is_estimated_at_the_optimal_size_after_costs() {
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "Solana".to_string(), 9);
+        // Sorts after wSOL, so SOL is the base token profit is counted in
+        let usdc = Token::new(Pubkey::new_from_array([255; 32]), "USDC".to_string(), "USD Coin".to_string(), 6);
+        let pool = |id: &str, dex_type: DexType, usdc_reserve: i64| {
+            Pool::new(id.to_string(), dex_type, sol.clone(), usdc.clone(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default())
+                .update_reserves(Decimal::from(10_000), Decimal::from(usdc_reserve))
+                .with_fee_rate(Decimal::new(25, 4))
+        };
+        let mut all_pools: HashMap<DexType, Vec<Pool>> = HashMap::new();
+        all_pools.insert(DexType::Raydium, vec![pool("low", DexType::Raydium, 1_500_000)]);
+        all_pools.insert(DexType::Meteora, vec![pool("high", DexType::Meteora, 1_530_000)]);
+
+        let mut config = AppConfig::bundled_default();
+        config.arbitrage.min_profit_threshold = 0.001;
+        config.scanner.max_trade_amount = 0.0;
+        config.fees.priority_fee_lamports = 95_000;
+        config.submission.bundles_enabled = true;
+        config.submission.bundle_tip_lamports = 1_000_000;
+        let detected_at = chrono::Utc::now() - chrono::Duration::days(1);
+        let clock = crate::utils::clock::TestClock::new(detected_at);
+        let scan = |config: AppConfig| {
+            let (all_pools, clock) = (all_pools.clone(), clock.clone());
+            async move {
+                let scanner =
+                    OpportunityScanner::new(Arc::new(HashMap::new()), Arc::new(LaneQueue::new(1)), config).with_clock(clock);
+                let mut found = scanner.find_arbitrage_opportunities(&all_pools).await.unwrap();
+                assert_eq!(found.len(), 1);
+                found.remove(0)
+            }
+        };
+
+        // Two legs of 5,000 + 95,000 lamports and a 1,000,000 lamport tip: 0.0012 SOL
+        let optimal = scan(config.clone()).await;
+        let input: Decimal = optimal.tags["estimated_input"].parse().unwrap();
+        let quote = optimal.buy_pool.calculate_output_amount(input, &optimal.base_token).unwrap();
+        let output = optimal.sell_pool.calculate_output_amount(quote, &optimal.quote_token).unwrap();
+        assert!((optimal.net_profit - (output - input - Decimal::new(12, 4))).abs() < Decimal::new(1, 6));
+        assert!(optimal.net_profit > Decimal::ZERO);
+        assert!(optimal.estimated_profit > optimal.net_profit);
+        assert_eq!(optimal.estimated_fees, optimal.estimated_profit - optimal.net_profit);
+        // Stamped by the scanner's clock rather than the system's
+        assert_eq!(optimal.timestamp, detected_at);
+
+        // A capped size leaves profit on the table
+        config.scanner.max_trade_amount = 1.0;
+        let capped = scan(config).await;
+        assert_eq!(capped.tags["estimated_input"], "1");
+        assert!(capped.net_profit < optimal.net_profit);
+
+        // Nothing prices a pair without SOL against its execution cost, so it is dropped
+        let (usdt, bonk) = (usdc.clone(), Token::new(Pubkey::new_unique(), "BONK".to_string(), "Bonk".to_string(), 5));
+        let unpriced = |id: &str, dex_type: DexType, bonk_reserve: i64| {
+            Pool::new(id.to_string(), dex_type, usdt.clone(), bonk.clone(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default())
+                .update_reserves(Decimal::from(1_000_000), Decimal::from(bonk_reserve))
+                .with_fee_rate(Decimal::new(25, 4))
+        };
+        let mut all_pools: HashMap<DexType, Vec<Pool>> = HashMap::new();
+        all_pools.insert(DexType::Raydium, vec![unpriced("low", DexType::Raydium, 50_000_000)]);
+        all_pools.insert(DexType::Meteora, vec![unpriced("high", DexType::Meteora, 51_000_000)]);
+        let scanner = OpportunityScanner::new(Arc::new(HashMap::new()), Arc::new(LaneQueue::new(1)), AppConfig::bundled_default());
+        assert!(scanner.find_arbitrage_opportunities(&all_pools).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_summary_buckets_spreads_and_skips_empty_filters() {
+        let mut summary = ScanSummary::default();
+        for spread in ["0.0005", "0.002", "0.002", "0.05"] {
+            summary.record_spread(spread.parse().unwrap());
+        }
+        summary.filter("below_threshold", 0);
+
+        assert_eq!(summary.spreads.get("<0.1%"), Some(&1));
+        assert_eq!(summary.spreads.get("0.1-0.3%"), Some(&2));
+        assert_eq!(summary.spreads.get(">=3%"), Some(&1));
+        assert!(summary.filtered.is_empty());
     }
 }
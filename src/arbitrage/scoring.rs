@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::config::ScoringConfig;
+use crate::dex::health::DexHealthTracker;
+use crate::models::{ArbitrageOpportunity, Pool, RiskScore};
+
+/// Execution priority of an opportunity: what it pays, discounted by how likely it is to still
+/// be there by the time its transactions land.
+///
+/// Profit percentage is scaled down per risk level, halves for every `freshness_half_life_ms`
+/// the stalest pool update is old, and is multiplied by the venue weight of every pool, which
+/// falls with the DEX's measured response latency and failure rate.
+pub struct OpportunityScorer {
+    config: ScoringConfig,
+    health: DexHealthTracker,
+}
+
+impl OpportunityScorer {
+    pub fn new(config: &ScoringConfig, health: DexHealthTracker) -> Self {
+        Self { config: config.clone(), health }
+    }
+
+    pub async fn score(&self, opportunity: &ArbitrageOpportunity, now: DateTime<Utc>) -> f64 {
+        let pools = Self::pools(opportunity);
+        let mut latency = 1.0;
+        for pool in &pools {
+            latency *= self.health.weight(&pool.dex_type).await;
+        }
+        let oldest_update = pools.iter().map(|pool| pool.last_updated).min().unwrap_or(now);
+        let profit = opportunity.profit_percentage.to_f64().unwrap_or(0.0);
+        profit * self.risk_factor(&opportunity.risk_score) * self.freshness(oldest_update, now) * latency
+    }
+
+    /// `1 - risk_discount` for each level above Low
+    fn risk_factor(&self, risk: &RiskScore) -> f64 {
+        let level = match risk {
+            RiskScore::Low => 0,
+            RiskScore::Medium => 1,
+            RiskScore::High => 2,
+            RiskScore::Critical => 3,
+        };
+        (1.0 - self.config.risk_discount.clamp(0.0, 1.0)).powi(level)
+    }
+
+    /// 1 for a pool updated just now, halving every `freshness_half_life_ms`
+    fn freshness(&self, last_updated: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+        if self.config.freshness_half_life_ms == 0 {
+            return 1.0;
+        }
+        let age_ms = (now - last_updated).num_milliseconds().max(0) as f64;
+        0.5f64.powf(age_ms / self.config.freshness_half_life_ms as f64)
+    }
+
+    fn pools(opportunity: &ArbitrageOpportunity) -> Vec<&Pool> {
+        match &opportunity.route {
+            Some(route) => route.pools.iter().collect(),
+            None => vec![&opportunity.buy_pool, &opportunity.sell_pool],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DexHealthConfig;
+    use crate::dex::DexType;
+    use crate::utils::fixtures;
+    use chrono::Duration;
+    use rust_decimal::Decimal;
+
+    #[tokio::test]
+    async fn test_stale_slow_and_risky_opportunities_rank_lower() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let now = Utc::now();
+        let pool = |dex_type: DexType, age_ms: i64| {
+            let mut pool = fixtures::pool(dex_type, &sol, &usdc);
+            pool.last_updated = now - Duration::milliseconds(age_ms);
+            pool
+        };
+        let opportunity = |buy: Pool, sell: Pool, basis_points: i64, risk: RiskScore| {
+            let mut opportunity = fixtures::opportunity_between(buy, sell);
+            opportunity.profit_percentage = Decimal::new(basis_points, 4);
+            opportunity.risk_score = risk;
+            opportunity
+        };
+
+        let health = DexHealthTracker::new(DexHealthConfig { reference_latency_ms: 100, ..DexHealthConfig::default() });
+        health.record(&DexType::Phoenix, std::time::Duration::from_millis(300), Ok(())).await;
+        let scorer = OpportunityScorer::new(&ScoringConfig { risk_discount: 0.5, freshness_half_life_ms: 1000 }, health);
+
+        let fresh = opportunity(pool(DexType::Raydium, 0), pool(DexType::Meteora, 0), 100, RiskScore::Low);
+        assert!((scorer.score(&fresh, now).await - 0.01).abs() < 1e-9);
+
+        // One half-life behind, or one risk level up, halves the score
+        let stale = opportunity(pool(DexType::Raydium, 1000), pool(DexType::Meteora, 0), 100, RiskScore::Low);
+        assert!((scorer.score(&stale, now).await - 0.005).abs() < 1e-9);
+        let risky = opportunity(pool(DexType::Raydium, 0), pool(DexType::Meteora, 0), 100, RiskScore::Medium);
+        assert!((scorer.score(&risky, now).await - 0.005).abs() < 1e-9);
+
+        // A venue answering in 3x the reference latency quarters it, outweighing a bigger spread
+        let slow = opportunity(pool(DexType::Raydium, 0), pool(DexType::Phoenix, 0), 300, RiskScore::Low);
+        assert!((scorer.score(&slow, now).await - 0.0075).abs() < 1e-9);
+        assert!(scorer.score(&slow, now).await < scorer.score(&fresh, now).await);
+    }
+}
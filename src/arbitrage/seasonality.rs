@@ -0,0 +1,146 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::models::{ArbitrageExecution, ArbitrageOpportunity, ExecutionStatus};
+use crate::utils::time::TimeUtils;
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+/// 1970-01-01, the first daily window, was a Thursday
+const EPOCH_WEEKDAY: i64 = 4;
+
+/// Opportunities and their outcomes within one hour of the day or day of the week
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct SeasonBucket {
+    /// "00".."23" or "Sun".."Sat", in UTC
+    pub slot: String,
+    pub opportunities: u64,
+    pub average_profit_percentage: Decimal,
+    pub estimated_profit: Decimal,
+    pub executions: u64,
+    pub successful_executions: u64,
+    /// Realized profit less costs of the executions of opportunities seen in this slot
+    pub net_profit: Decimal,
+}
+
+impl SeasonBucket {
+    fn new(slot: String) -> Self {
+        Self { slot, ..Default::default() }
+    }
+
+    fn add_opportunity(&mut self, opportunity: &ArbitrageOpportunity) {
+        let total = self.average_profit_percentage * Decimal::from(self.opportunities) + opportunity.profit_percentage;
+        self.opportunities += 1;
+        self.average_profit_percentage = total / Decimal::from(self.opportunities);
+        self.estimated_profit += opportunity.estimated_profit;
+    }
+
+    fn add_execution(&mut self, execution: &ArbitrageExecution) {
+        self.executions += 1;
+        if execution.execution_status == ExecutionStatus::Confirmed {
+            self.successful_executions += 1;
+        }
+        self.net_profit += execution.actual_profit.unwrap_or_default() - execution.total_cost.unwrap_or_default();
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PairSeasonality {
+    /// "BASE/QUOTE" by symbol
+    pub pair: String,
+    pub by_hour: Vec<SeasonBucket>,
+    pub by_weekday: Vec<SeasonBucket>,
+}
+
+impl PairSeasonality {
+    fn new(pair: String) -> Self {
+        Self {
+            pair,
+            by_hour: (0..24).map(|hour| SeasonBucket::new(format!("{:02}", hour))).collect(),
+            by_weekday: WEEKDAYS.iter().map(|day| SeasonBucket::new(day.to_string())).collect(),
+        }
+    }
+
+    fn buckets(&mut self, time: DateTime<Utc>) -> (&mut SeasonBucket, &mut SeasonBucket) {
+        let hour = TimeUtils::get_window_index(time, Duration::hours(1)).rem_euclid(24) as usize;
+        let weekday = (TimeUtils::get_window_index(time, Duration::days(1)) + EPOCH_WEEKDAY).rem_euclid(7) as usize;
+        (&mut self.by_hour[hour], &mut self.by_weekday[weekday])
+    }
+
+    /// The hour of day whose executions netted the most, if any executed
+    pub fn best_hour(&self) -> Option<&SeasonBucket> {
+        self.by_hour.iter().filter(|b| b.executions > 0).max_by_key(|b| b.net_profit)
+    }
+}
+
+/// How often opportunities show up, and how well they pay, by hour of day and day of week per pair
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SeasonalityReport {
+    pub generated_at: DateTime<Utc>,
+    pub pairs: Vec<PairSeasonality>,
+}
+
+impl SeasonalityReport {
+    /// Executions are placed by when their opportunity was detected, so both line up in one slot
+    pub fn build(opportunities: &[ArbitrageOpportunity], executions: &[ArbitrageExecution]) -> Self {
+        let mut pairs: BTreeMap<String, PairSeasonality> = BTreeMap::new();
+        for opportunity in opportunities {
+            let pair = opportunity.pair();
+            let (hour, weekday) = pairs.entry(pair.clone()).or_insert_with(|| PairSeasonality::new(pair)).buckets(opportunity.timestamp);
+            hour.add_opportunity(opportunity);
+            weekday.add_opportunity(opportunity);
+        }
+        for execution in executions {
+            let pair = execution.opportunity.pair();
+            let (hour, weekday) = pairs
+                .entry(pair.clone())
+                .or_insert_with(|| PairSeasonality::new(pair))
+                .buckets(execution.opportunity.timestamp);
+            hour.add_execution(execution);
+            weekday.add_execution(execution);
+        }
+        Self { generated_at: Utc::now(), pairs: pairs.into_values().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::DexType;
+    use crate::utils::fixtures;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_buckets_by_hour_and_weekday() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = || fixtures::pool(DexType::Raydium, &sol, &usdc);
+        let opportunity = |time: DateTime<Utc>, percent: i64| {
+            let mut opportunity = fixtures::opportunity_between(pool(), pool());
+            opportunity.timestamp = time;
+            opportunity.profit_percentage = Decimal::from(percent);
+            opportunity.estimated_profit = Decimal::ONE;
+            opportunity
+        };
+        // Monday 14:xx twice, Tuesday 03:xx once
+        let monday = Utc.with_ymd_and_hms(2026, 10, 19, 14, 5, 0).unwrap();
+        let tuesday = Utc.with_ymd_and_hms(2026, 10, 20, 3, 59, 0).unwrap();
+        let opportunities = vec![opportunity(monday, 1), opportunity(monday + Duration::minutes(30), 3), opportunity(tuesday, 2)];
+        let mut execution = ArbitrageExecution::new(opportunities[0].clone());
+        execution.execution_status = ExecutionStatus::Confirmed;
+        execution.actual_profit = Some(Decimal::from(5));
+        execution.total_cost = Some(Decimal::ONE);
+
+        let report = SeasonalityReport::build(&opportunities, &[execution]);
+        assert_eq!(report.pairs.len(), 1);
+        let pair = &report.pairs[0];
+        assert_eq!(pair.pair, "SOL/USDC");
+        assert_eq!(pair.by_hour[14].opportunities, 2);
+        assert_eq!(pair.by_hour[14].average_profit_percentage, Decimal::from(2));
+        assert_eq!(pair.by_hour[3].opportunities, 1);
+        assert_eq!(pair.by_weekday[1].slot, "Mon");
+        assert_eq!(pair.by_weekday[1].estimated_profit, Decimal::from(2));
+        assert_eq!(pair.by_weekday[2].opportunities, 1);
+        assert_eq!(pair.best_hour().map(|b| (b.slot.as_str(), b.net_profit)), Some(("14", Decimal::from(4))));
+    }
+}
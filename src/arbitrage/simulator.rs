@@ -0,0 +1,402 @@
+use anyhow::Result;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::{
+    arbitrage::{engine::ArbitrageEngine, queue::LaneQueue, scanner::OpportunityScanner},
+    config::{AppConfig, PrecisionConfig, RoundingMode},
+    dex::DexType,
+    models::{ArbitrageOpportunity, Pool, Token},
+    utils::precision::round_to_decimals,
+};
+
+/// A synthetic token in a scenario
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioToken {
+    pub symbol: String,
+    /// Generated when omitted
+    pub mint: Option<String>,
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+}
+
+fn default_decimals() -> u8 {
+    6
+}
+
+/// A synthetic constant-product pool whose price follows a path
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioPool {
+    pub id: String,
+    pub dex: DexType,
+    pub token_a: String,
+    pub token_b: String,
+    /// Reserve of token A; token B's reserve is derived from the price
+    pub reserve_a: f64,
+    #[serde(default)]
+    pub fee_rate: f64,
+    /// `[seconds, price of A in B]` points, linearly interpolated
+    pub prices: Vec<(f64, f64)>,
+}
+
+impl ScenarioPool {
+    /// Price at `t` seconds, holding the first and last points flat outside the path
+    pub fn price_at(&self, t: f64) -> Option<f64> {
+        let first = self.prices.first()?;
+        if t <= first.0 {
+            return Some(first.1);
+        }
+        for window in self.prices.windows(2) {
+            let ((t0, p0), (t1, p1)) = (window[0], window[1]);
+            if t <= t1 {
+                let fraction = if t1 > t0 { (t - t0) / (t1 - t0) } else { 1.0 };
+                return Some(p0 + (p1 - p0) * fraction);
+            }
+        }
+        self.prices.last().map(|p| p.1)
+    }
+}
+
+/// Synthetic pools and price paths loaded from a TOML file
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    pub duration_seconds: f64,
+    /// Simulated time between scan cycles
+    #[serde(default = "default_step_seconds")]
+    pub step_seconds: f64,
+    /// Amount of the opportunity's base token sent round the two pools
+    pub trade_size: f64,
+    /// Overrides `arbitrage.min_profit_threshold`
+    pub min_profit_threshold: Option<f64>,
+    pub tokens: Vec<ScenarioToken>,
+    pub pools: Vec<ScenarioPool>,
+}
+
+fn default_step_seconds() -> f64 {
+    1.0
+}
+
+impl Scenario {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read scenario {}: {}", path, e))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let scenario: Scenario = toml::from_str(content)?;
+        if scenario.step_seconds <= 0.0 {
+            anyhow::bail!("step_seconds must be positive");
+        }
+        Ok(scenario)
+    }
+}
+
+/// What the engine did with a detected opportunity
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimulatedDecision {
+    Executed { profit: Decimal },
+    Rejected { reason: String },
+}
+
+/// One detected opportunity and its outcome
+#[derive(Debug, Clone)]
+pub struct SimulatedOpportunity {
+    pub time_seconds: f64,
+    pub pair: String,
+    pub buy_pool: String,
+    pub sell_pool: String,
+    pub profit_percentage: Decimal,
+    pub decision: SimulatedDecision,
+}
+
+/// Result of a scenario run
+#[derive(Debug, Clone, Default)]
+pub struct SimulationReport {
+    pub steps: usize,
+    pub opportunities: Vec<SimulatedOpportunity>,
+    /// Detected opportunities skipped because a pool could not quote the trade size, or the
+    /// execution cost could not be priced in the base token
+    pub unquoted: usize,
+}
+
+impl SimulationReport {
+    pub fn executed(&self) -> impl Iterator<Item = &SimulatedOpportunity> {
+        self.opportunities
+            .iter()
+            .filter(|o| matches!(o.decision, SimulatedDecision::Executed { .. }))
+    }
+
+    pub fn total_profit(&self) -> Decimal {
+        self.executed()
+            .map(|o| match o.decision {
+                SimulatedDecision::Executed { profit } => profit,
+                SimulatedDecision::Rejected { .. } => Decimal::ZERO,
+            })
+            .sum()
+    }
+}
+
+/// Runs the scanner and the engine's acceptance checks over a scenario in accelerated time
+pub struct Simulator {
+    config: AppConfig,
+    scenario: Scenario,
+    tokens: HashMap<String, Token>,
+}
+
+impl Simulator {
+    pub fn new(mut config: AppConfig, scenario: Scenario) -> Result<Self> {
+        if let Some(threshold) = scenario.min_profit_threshold {
+            config.arbitrage.min_profit_threshold = threshold;
+        }
+
+        let mut tokens = HashMap::new();
+        for token in &scenario.tokens {
+            let mint = match &token.mint {
+                Some(mint) => Pubkey::from_str(mint)?,
+                None => Pubkey::new_unique(),
+            };
+            tokens.insert(
+                token.symbol.clone(),
+                Token::new(mint, token.symbol.clone(), token.symbol.clone(), token.decimals),
+            );
+        }
+
+        for pool in &scenario.pools {
+            for symbol in [&pool.token_a, &pool.token_b] {
+                if !tokens.contains_key(symbol) {
+                    anyhow::bail!("Pool {} references unknown token {}", pool.id, symbol);
+                }
+            }
+            if pool.prices.is_empty() {
+                anyhow::bail!("Pool {} has no price path", pool.id);
+            }
+        }
+
+        Ok(Self { config, scenario, tokens })
+    }
+
+    /// Pool states at `t` seconds into the scenario
+    pub fn pools_at(&self, t: f64) -> HashMap<DexType, Vec<Pool>> {
+        let mut all_pools: HashMap<DexType, Vec<Pool>> = HashMap::new();
+        for spec in &self.scenario.pools {
+            let price = spec.price_at(t).unwrap_or_default();
+            let reserve_a = Decimal::from_f64(spec.reserve_a).unwrap_or_default();
+            let reserve_b = Decimal::from_f64(spec.reserve_a * price).unwrap_or_default();
+
+            let pool = Pool::new(
+                spec.id.clone(),
+                spec.dex.clone(),
+                self.tokens[&spec.token_a].clone(),
+                self.tokens[&spec.token_b].clone(),
+                Pubkey::new_unique(),
+                Pubkey::default(),
+                Pubkey::default(),
+            )
+            .update_reserves(reserve_a, reserve_b)
+            .with_fee_rate(Decimal::from_f64(spec.fee_rate).unwrap_or_default());
+            all_pools.entry(spec.dex.clone()).or_default().push(pool);
+        }
+        all_pools
+    }
+
+    /// Step through the scenario, scanning and deciding at every step
+    pub async fn run(&self) -> Result<SimulationReport> {
+        // The scanner only publishes from its own loop; the queue is unused here
+        let queue = Arc::new(LaneQueue::new(1));
+        let scanner = OpportunityScanner::new(Arc::new(HashMap::new()), queue, self.config.clone());
+        let strategy = ArbitrageEngine::default_strategy(&self.config.arbitrage);
+        let min_profit = Decimal::from_f64(self.config.arbitrage.min_profit_threshold).unwrap_or(Decimal::ZERO);
+        let trade_size = Decimal::from_f64(self.scenario.trade_size).unwrap_or_default();
+
+        let mut report = SimulationReport::default();
+        let mut t = 0.0;
+        while t <= self.scenario.duration_seconds {
+            let all_pools = self.pools_at(t);
+            for mut opportunity in scanner.find_arbitrage_opportunities(&all_pools).await? {
+                let priced = scanner
+                    .network_cost(2, &opportunity.base_token)
+                    .and_then(|cost| Self::price_trade(&mut opportunity, trade_size, cost, &self.config.precision));
+                if priced.is_none() {
+                    report.unquoted += 1;
+                    continue;
+                }
+
+                let decision = if !strategy.is_opportunity_suitable(&opportunity) {
+                    SimulatedDecision::Rejected { reason: "strategy filter".to_string() }
+                } else if !opportunity.clears_profit_threshold(min_profit) {
+                    SimulatedDecision::Rejected { reason: "net profit below threshold".to_string() }
+                } else {
+                    SimulatedDecision::Executed { profit: opportunity.net_profit }
+                };
+
+                report.opportunities.push(SimulatedOpportunity {
+                    time_seconds: t,
+                    pair: format!("{}/{}", opportunity.base_token.symbol, opportunity.quote_token.symbol),
+                    buy_pool: opportunity.buy_pool.id.clone(),
+                    sell_pool: opportunity.sell_pool.id.clone(),
+                    profit_percentage: opportunity.profit_percentage,
+                    decision,
+                });
+            }
+            report.steps += 1;
+            t += self.scenario.step_seconds;
+        }
+
+        Ok(report)
+    }
+
+    /// Base token gained by routing `amount` through the buy pool and back through the sell pool,
+    /// with amounts held at mint precision as they would be on-chain
+    pub fn round_trip_profit(
+        opportunity: &ArbitrageOpportunity,
+        amount: Decimal,
+        precision: &PrecisionConfig,
+    ) -> Option<Decimal> {
+        let (base, quote_token) = (&opportunity.base_token, &opportunity.quote_token);
+        let amount = round_to_decimals(amount, base.decimals, precision.input_rounding);
+        let quote = opportunity.buy_pool.calculate_output_amount(amount, base)?;
+        // Swap programs truncate outputs to whole base units
+        let quote = round_to_decimals(quote, quote_token.decimals, RoundingMode::Floor);
+        let back = opportunity.sell_pool.calculate_output_amount(quote, quote_token)?;
+        Some(round_to_decimals(back, base.decimals, RoundingMode::Floor) - amount)
+    }
+
+    /// Re-estimate an opportunity at the size actually traded, as the scanner does at its own size:
+    /// `estimated_profit` before swap fees, `estimated_fees` as the swap fees plus `network_cost`,
+    /// and `net_profit` after both. Returns the round trip after swap fees, or None, leaving the
+    /// opportunity as it was, when either pool cannot quote the size
+    pub fn price_trade(
+        opportunity: &mut ArbitrageOpportunity,
+        amount: Decimal,
+        network_cost: Decimal,
+        precision: &PrecisionConfig,
+    ) -> Option<Decimal> {
+        let profit = Self::round_trip_profit(opportunity, amount, precision)?;
+        let mut fee_free = opportunity.clone();
+        fee_free.buy_pool = fee_free.buy_pool.with_fee_rate(Decimal::ZERO);
+        fee_free.sell_pool = fee_free.sell_pool.with_fee_rate(Decimal::ZERO);
+        let fee_free_profit = Self::round_trip_profit(&fee_free, amount, precision)?;
+
+        opportunity.estimated_profit = fee_free_profit;
+        opportunity.net_profit = profit - network_cost;
+        opportunity.estimated_fees = fee_free_profit - opportunity.net_profit;
+        opportunity.tags.insert("estimated_input".to_string(), amount.round_dp(9).normalize().to_string());
+        Some(profit)
+    }
+
+    /// Render a report as lines for the terminal
+    pub fn render(report: &SimulationReport) -> Vec<String> {
+        let mut lines = Vec::new();
+        for o in &report.opportunities {
+            let outcome = match &o.decision {
+                SimulatedDecision::Executed { profit } => format!("executed profit={}", profit.round_dp(6)),
+                SimulatedDecision::Rejected { reason } => format!("rejected ({})", reason),
+            };
+            lines.push(format!(
+                "t={:>7.1}s {} buy={} sell={} spread={:.4}% {}",
+                o.time_seconds,
+                o.pair,
+                o.buy_pool,
+                o.sell_pool,
+                (o.profit_percentage * Decimal::from(100)).to_f64().unwrap_or_default(),
+                outcome
+            ));
+        }
+        lines.push(format!(
+            "{} steps, {} opportunities detected, {} unquoted, {} executed, total profit {}",
+            report.steps,
+            report.opportunities.len() + report.unquoted,
+            report.unquoted,
+            report.executed().count(),
+            report.total_profit().round_dp(6)
+        ));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+        name = "diverging SOL/USDC"
+        duration_seconds = 10
+        step_seconds = 5
+        trade_size = 1
+        min_profit_threshold = 0.001
+
+        [[tokens]]
+        symbol = "SOL"
+        mint = "So11111111111111111111111111111111111111112"
+        decimals = 9
+
+        [[tokens]]
+        symbol = "USDC"
+
+        [[pools]]
+        id = "ray"
+        dex = "Raydium"
+        token_a = "SOL"
+        token_b = "USDC"
+        reserve_a = 10000
+        prices = [[0, 150.0], [10, 150.0]]
+
+        [[pools]]
+        id = "met"
+        dex = "Meteora"
+        token_a = "SOL"
+        token_b = "USDC"
+        reserve_a = 10000
+        prices = [[0, 150.0], [10, 156.0]]
+    "#;
+
+    #[tokio::test]
+    async fn test_scenario_detects_divergence() {
+        let config = AppConfig::bundled_default();
+        let scenario = Scenario::parse(SCENARIO).unwrap();
+        assert_eq!(scenario.pools[1].price_at(5.0), Some(153.0));
+
+        let report = Simulator::new(config, scenario).unwrap().run().await.unwrap();
+        assert_eq!(report.steps, 3);
+        // No spread at t=0; the widening spread is traded at t=5 and t=10
+        assert!(report.opportunities.iter().all(|o| o.time_seconds > 0.0));
+        assert_eq!(report.executed().count(), 2);
+        assert!(report.total_profit() > Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_swap_fees_are_charged_once_at_the_trade_size() {
+        let config = AppConfig::bundled_default();
+        // 1% per pool: the 2% spread at t=5 is all fees, the 4% spread at t=10 clears them
+        let scenario = Scenario::parse(&SCENARIO.replace("reserve_a = 10000", "reserve_a = 10000\nfee_rate = 0.01")).unwrap();
+        let simulator = Simulator::new(config.clone(), scenario).unwrap();
+        let report = simulator.run().await.unwrap();
+        assert_eq!(report.unquoted, 0);
+        let executed: Vec<_> = report.executed().map(|o| o.time_seconds).collect();
+        assert_eq!(executed, vec![10.0]);
+
+        let pools = simulator.pools_at(10.0);
+        let (ray, met) = (pools[&DexType::Raydium][0].clone(), pools[&DexType::Meteora][0].clone());
+        // SOL is dearer on Meteora: sell it there and buy it back on Raydium
+        let mut opportunity = ArbitrageOpportunity::new(ray.token_a.clone(), ray.token_b.clone(), met, ray);
+        let network_cost = Decimal::new(1, 4);
+        let profit = Simulator::price_trade(&mut opportunity, Decimal::ONE, network_cost, &config.precision).unwrap();
+        assert_eq!(opportunity.net_profit, profit - network_cost);
+        // Fees are the two pools' 1% cuts of the trade plus the network cost, not twice that
+        let swap_fees = opportunity.estimated_fees - network_cost;
+        assert!(swap_fees > Decimal::new(19, 3) && swap_fees < Decimal::new(21, 3), "{}", swap_fees);
+        assert!(opportunity.clears_profit_threshold(Decimal::new(1, 2)));
+
+        // A pool that cannot quote the size is skipped, not priced at zero
+        opportunity.sell_pool.reserve_a = Decimal::ZERO;
+        assert!(Simulator::price_trade(&mut opportunity, Decimal::ONE, network_cost, &config.precision).is_none());
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::Result;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::{arbitrage::engine::ArbitrageEngine, config::AppConfig, dex::mock::MockDex};
+
+/// Process and engine resource usage at one point of a soak run
+#[derive(Debug, Clone, Default)]
+pub struct ResourceSample {
+    pub elapsed_seconds: u64,
+    pub rss_bytes: Option<u64>,
+    pub open_fds: Option<usize>,
+    pub alive_tasks: usize,
+    pub execution_history: usize,
+    pub active_opportunities: usize,
+}
+
+impl ResourceSample {
+    /// Sample the current process; RSS and descriptors are only available on Linux
+    pub fn capture(elapsed: Duration, execution_history: usize, active_opportunities: usize) -> Self {
+        Self {
+            elapsed_seconds: elapsed.as_secs(),
+            rss_bytes: read_rss_bytes(),
+            open_fds: std::fs::read_dir("/proc/self/fd").ok().map(|dir| dir.count()),
+            alive_tasks: tokio::runtime::Handle::current().metrics().num_alive_tasks(),
+            execution_history,
+            active_opportunities,
+        }
+    }
+}
+
+fn read_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// How much growth past the warm-up baseline a soak run tolerates
+#[derive(Debug, Clone)]
+pub struct SoakLimits {
+    /// Fraction of baseline RSS
+    pub max_rss_growth: f64,
+    pub max_fd_growth: usize,
+    pub max_task_growth: usize,
+    pub max_execution_history: usize,
+    /// Samples discarded while caches and pools fill
+    pub warmup_samples: usize,
+}
+
+impl SoakLimits {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            max_rss_growth: 0.5,
+            max_fd_growth: 16,
+            max_task_growth: 32,
+            max_execution_history: config.memory_store.max_executions,
+            warmup_samples: 2,
+        }
+    }
+}
+
+/// Samples of a soak run and the limits they broke
+#[derive(Debug, Clone, Default)]
+pub struct SoakReport {
+    pub samples: Vec<ResourceSample>,
+    pub violations: Vec<String>,
+}
+
+impl SoakReport {
+    /// Compare every post-warm-up sample against the first one
+    pub fn evaluate(samples: Vec<ResourceSample>, limits: &SoakLimits) -> Self {
+        let mut violations = Vec::new();
+        let steady = &samples[limits.warmup_samples.min(samples.len())..];
+
+        if let Some(baseline) = steady.first() {
+            if let Some(base_rss) = baseline.rss_bytes {
+                let ceiling = base_rss as f64 * (1.0 + limits.max_rss_growth);
+                if let Some(peak) = steady.iter().filter_map(|s| s.rss_bytes).max() {
+                    if peak as f64 > ceiling {
+                        violations.push(format!("RSS grew from {} to {} bytes", base_rss, peak));
+                    }
+                }
+            }
+            if let Some(base_fds) = baseline.open_fds {
+                if let Some(peak) = steady.iter().filter_map(|s| s.open_fds).max() {
+                    if peak > base_fds + limits.max_fd_growth {
+                        violations.push(format!("open descriptors grew from {} to {}", base_fds, peak));
+                    }
+                }
+            }
+            let peak_tasks = steady.iter().map(|s| s.alive_tasks).max().unwrap_or(0);
+            if peak_tasks > baseline.alive_tasks + limits.max_task_growth {
+                violations.push(format!("tasks grew from {} to {}", baseline.alive_tasks, peak_tasks));
+            }
+        }
+
+        let peak_history = samples.iter().map(|s| s.execution_history).max().unwrap_or(0);
+        if peak_history > limits.max_execution_history {
+            violations.push(format!(
+                "execution history reached {} entries, limit {}",
+                peak_history, limits.max_execution_history
+            ));
+        }
+
+        Self { samples, violations }
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Render samples and verdict as lines for the terminal
+    pub fn render(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{:>8}  {:>10}  {:>5}  {:>6}  {:>10}  {:>6}",
+            "ELAPSED", "RSS_KB", "FDS", "TASKS", "EXECUTIONS", "ACTIVE"
+        )];
+        for s in &self.samples {
+            lines.push(format!(
+                "{:>7}s  {:>10}  {:>5}  {:>6}  {:>10}  {:>6}",
+                s.elapsed_seconds,
+                s.rss_bytes.map(|b| (b / 1024).to_string()).unwrap_or_else(|| "-".to_string()),
+                s.open_fds.map(|f| f.to_string()).unwrap_or_else(|| "-".to_string()),
+                s.alive_tasks,
+                s.execution_history,
+                s.active_opportunities
+            ));
+        }
+        if self.is_bounded() {
+            lines.push("Resource usage stayed bounded".to_string());
+        } else {
+            lines.extend(self.violations.iter().map(|v| format!("VIOLATION: {}", v)));
+        }
+        lines
+    }
+}
+
+/// Runs the full engine against mock venues for a long period, sampling resource usage
+pub struct SoakRunner {
+    config: AppConfig,
+    duration: Duration,
+    sample_interval: Duration,
+    limits: SoakLimits,
+}
+
+impl SoakRunner {
+    pub fn new(mut config: AppConfig, duration: Duration, sample_interval: Duration) -> Self {
+        // Nothing outside the process is touched
+        config.position_watcher.enabled = false;
+        config.blacklist.path = String::new();
+        let limits = SoakLimits::from_config(&config);
+        Self { config, duration, sample_interval, limits }
+    }
+
+    pub fn with_limits(mut self, limits: SoakLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub async fn run(self) -> Result<SoakReport> {
+        let mut engine = ArbitrageEngine::new(self.config.clone(), None, MockDex::standard_set());
+        let metrics = engine.metrics();
+        let engine_task = tokio::spawn(async move { engine.start().await });
+
+        let started = Instant::now();
+        let mut samples = Vec::new();
+        while started.elapsed() < self.duration {
+            tokio::time::sleep(self.sample_interval).await;
+            if engine_task.is_finished() {
+                anyhow::bail!("Engine exited {}s into the soak run", started.elapsed().as_secs());
+            }
+
+            let gauge = |name: &'static str| {
+                let metrics = metrics.clone();
+                async move { metrics.get(name).await.unwrap_or(0.0) as usize }
+            };
+            let sample = ResourceSample::capture(
+                started.elapsed(),
+                gauge("execution_history").await,
+                gauge("active_opportunities").await,
+            );
+            info!(
+                rss = ?sample.rss_bytes,
+                fds = ?sample.open_fds,
+                tasks = sample.alive_tasks,
+                executions = sample.execution_history,
+                "Soak sample"
+            );
+            samples.push(sample);
+        }
+
+        engine_task.abort();
+        Ok(SoakReport::evaluate(samples, &self.limits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(rss_kb: u64, fds: usize, tasks: usize, history: usize) -> ResourceSample {
+        ResourceSample {
+            rss_bytes: Some(rss_kb * 1024),
+            open_fds: Some(fds),
+            alive_tasks: tasks,
+            execution_history: history,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_evaluate_flags_growth_after_warmup() {
+        let limits = SoakLimits {
+            max_rss_growth: 0.5,
+            max_fd_growth: 4,
+            max_task_growth: 4,
+            max_execution_history: 100,
+            warmup_samples: 1,
+        };
+
+        // The warm-up sample is ignored even though it is far smaller
+        let steady = vec![sample(1, 10, 5, 0), sample(1000, 20, 9, 50), sample(1400, 22, 10, 100)];
+        assert!(SoakReport::evaluate(steady, &limits).is_bounded());
+
+        let leaking = vec![sample(1000, 20, 9, 0), sample(1000, 20, 9, 50), sample(2000, 40, 30, 101)];
+        let report = SoakReport::evaluate(leaking, &limits);
+        assert_eq!(report.violations.len(), 4);
+    }
+}
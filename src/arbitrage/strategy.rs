@@ -2,9 +2,14 @@ use anyhow::Result;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use crate::models::{ArbitrageOpportunity, RiskScore};
+use crate::arbitrage::kelly::{KellyStrategy, KELLY_STRATEGY_KIND};
+use crate::arbitrage::mean_reversion::{MeanReversionStrategy, MEAN_REVERSION_STRATEGY_KIND};
+use crate::services::memory_store::{MemoryStore, OutcomeStats};
+use crate::models::{ArbitrageOpportunity, ArbitrageStrategy, RiskScore, THRESHOLD_STRATEGY_KIND};
 use crate::dex::DexType;
 
 /// Arbitrage strategy interface
@@ -20,6 +25,18 @@ pub trait Strategy: Send + Sync {
     
     /// Calculate optimal trade amount
     fn calculate_optimal_amount(&self, opportunity: &ArbitrageOpportunity) -> Option<Decimal>;
+
+    /// Learn from a detection once it has been judged; called for every detected opportunity,
+    /// whether or not this strategy was consulted on it
+    fn observe(&self, _opportunity: &ArbitrageOpportunity) {}
+
+    /// Days of execution history the strategy learns from; None when it keeps no record
+    fn record_lookback_days(&self) -> Option<i64> {
+        None
+    }
+
+    /// Replace the strategy's snapshot of the execution record over `record_lookback_days`
+    fn update_record(&self, _stats: OutcomeStats) {}
     
     /// Get strategy parameters
     fn get_parameters(&self) -> StrategyParameters;
@@ -83,24 +100,10 @@ impl Strategy for BaseArbitrageStrategy {
     }
     
     fn calculate_optimal_amount(&self, opportunity: &ArbitrageOpportunity) -> Option<Decimal> {
-        let buy_pool = &opportunity.buy_pool;
-        let sell_pool = &opportunity.sell_pool;
-        
-        // Calculate maximum tradable amount
-        let max_buy_amount = std::cmp::min(
-            buy_pool.reserve_a,
-            buy_pool.reserve_b,
-        );
-        
-        let max_sell_amount = std::cmp::min(
-            sell_pool.reserve_a,
-            sell_pool.reserve_b,
-        );
-        
-        let max_amount = std::cmp::min(max_buy_amount, max_sell_amount);
+        let optimal_amount = opportunity.optimal_input()?;
         
-        // Apply strategy constraints
-        let strategy_amount = max_amount * self.parameters.position_size_multiplier;
+        // Scale down for cautious strategies; sizing past the optimum only gives profit back
+        let strategy_amount = optimal_amount * self.parameters.position_size_multiplier.min(Decimal::ONE);
         
         // Cap the maximum trade amount
         let max_trade_amount = self.parameters.max_trade_amount;
@@ -145,6 +148,63 @@ impl Strategy for BaseArbitrageStrategy {
     }
 }
 
+/// A definition filters on its own thresholds; kinds with more to them wrap it
+impl Strategy for ArbitrageStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn should_execute(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        self.is_active && self.is_opportunity_suitable(opportunity)
+    }
+
+    fn calculate_optimal_amount(&self, opportunity: &ArbitrageOpportunity) -> Option<Decimal> {
+        let optimal_amount = opportunity.optimal_input()?;
+        Some(self.max_trade_amount.map_or(optimal_amount, |max| optimal_amount.min(max)))
+    }
+
+    fn get_parameters(&self) -> StrategyParameters {
+        let defaults = StrategyParameters::default();
+        StrategyParameters {
+            min_profit_threshold: self.min_profit_threshold,
+            max_slippage: self.max_slippage,
+            max_price_impact: self.max_price_impact,
+            min_liquidity: self.min_liquidity,
+            max_trade_amount: self.max_trade_amount.unwrap_or(defaults.max_trade_amount),
+            position_size_multiplier: self.position_size_multiplier,
+            supported_dexes: self.supported_dexes.clone(),
+            max_risk_score: self.risk_tolerance.clone(),
+            ..defaults
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            anyhow::bail!("Strategy name cannot be empty");
+        }
+        if self.min_profit_threshold < Decimal::ZERO {
+            anyhow::bail!("Min profit threshold cannot be negative");
+        }
+        if self.max_slippage < Decimal::ZERO {
+            anyhow::bail!("Max slippage cannot be negative");
+        }
+        if self.supported_dexes.is_empty() {
+            anyhow::bail!("At least one DEX must be supported");
+        }
+        if self.position_size_multiplier <= Decimal::ZERO {
+            anyhow::bail!("Position size multiplier must be positive");
+        }
+        if self.max_trade_amount.is_some_and(|max| max <= Decimal::ZERO) {
+            anyhow::bail!("Max trade amount must be positive");
+        }
+        Ok(())
+    }
+}
+
 /// Strategy parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrategyParameters {
@@ -181,7 +241,7 @@ impl Default for StrategyParameters {
             min_liquidity: Decimal::from(1000),
             max_trade_amount: Decimal::from(10000),
             position_size_multiplier: Decimal::from(1),
-            supported_dexes: vec![DexType::Raydium, DexType::Meteora, DexType::Whirlpool, DexType::Pump],
+            supported_dexes: vec![DexType::Raydium, DexType::Meteora, DexType::Whirlpool, DexType::Pump, DexType::OrcaLegacy, DexType::Phoenix],
             max_risk_score: RiskScore::Medium,
             execution_delay_seconds: 0,
             max_retries: 3,
@@ -264,48 +324,197 @@ impl StrategyFactory {
     }
 }
 
-/// Strategy manager
+/// Builds the evaluator for a strategy definition of a registered kind
+pub type StrategyBuilder = Arc<dyn Fn(&ArbitrageStrategy) -> Result<Box<dyn Strategy>> + Send + Sync>;
+
+/// How often a strategy was consulted and how it answered
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StrategyMetrics {
+    /// Opportunities evaluated
+    pub evaluations: u64,
+    /// Opportunities the strategy was selected for
+    pub selections: u64,
+    /// Evaluations that panicked
+    pub panics: u64,
+}
+
+impl StrategyMetrics {
+    /// Fraction of evaluations that selected the strategy
+    pub fn selection_rate(&self) -> f64 {
+        if self.evaluations == 0 {
+            return 0.0;
+        }
+        self.selections as f64 / self.evaluations as f64
+    }
+}
+
+/// A loaded strategy: its definition and the evaluator built from it
+struct LoadedStrategy {
+    definition: ArbitrageStrategy,
+    strategy: Box<dyn Strategy>,
+    metrics: StrategyMetrics,
+}
+
+/// Strategy definitions by id, each evaluated through the `Strategy` built for its kind.
+///
+/// Kinds are plugins: `register_kind` maps a name to a builder, and a definition naming that
+/// kind is built with it when added. The threshold, mean-reversion and Kelly kinds are always
+/// registered.
 pub struct StrategyManager {
-    strategies: HashMap<String, Box<dyn Strategy>>,
+    kinds: HashMap<String, StrategyBuilder>,
+    strategies: HashMap<String, LoadedStrategy>,
+}
+
+impl Default for StrategyManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StrategyManager {
     pub fn new() -> Self {
-        Self {
+        let mut manager = Self {
+            kinds: HashMap::new(),
             strategies: HashMap::new(),
-        }
+        };
+        manager.register_kind(THRESHOLD_STRATEGY_KIND, Arc::new(|definition| Ok(Box::new(definition.clone()))));
+        manager.register_kind(
+            MEAN_REVERSION_STRATEGY_KIND,
+            Arc::new(|definition| Ok(Box::new(MeanReversionStrategy::from_definition(definition)?))),
+        );
+        manager.register_kind(KELLY_STRATEGY_KIND, Arc::new(|definition| Ok(Box::new(KellyStrategy::from_definition(definition)?))));
+        manager
     }
-    
-    /// Add a strategy
-    pub fn add_strategy(&mut self, strategy: Box<dyn Strategy>) {
-        self.strategies.insert(strategy.name().to_string(), strategy);
+
+    /// Make definitions of `kind` buildable, replacing any builder registered under that name
+    pub fn register_kind(&mut self, kind: &str, builder: StrategyBuilder) {
+        self.kinds.insert(kind.to_string(), builder);
     }
-    
+
+    /// Registered kinds, sorted
+    pub fn kinds(&self) -> Vec<String> {
+        let mut kinds: Vec<String> = self.kinds.keys().cloned().collect();
+        kinds.sort();
+        kinds
+    }
+
+    /// Build and validate a strategy from its definition, replacing one with the same id.
+    /// Metrics carry over a replacement.
+    pub fn add_strategy(&mut self, definition: ArbitrageStrategy) -> Result<()> {
+        let builder = self
+            .kinds
+            .get(&definition.kind)
+            .ok_or_else(|| anyhow::anyhow!("Strategy '{}' has unknown kind '{}'", definition.name, definition.kind))?;
+        let strategy = builder(&definition)?;
+        strategy.validate()?;
+        let metrics = self.strategies.remove(&definition.id).map(|loaded| loaded.metrics).unwrap_or_default();
+        self.strategies.insert(definition.id.clone(), LoadedStrategy { definition, strategy, metrics });
+        Ok(())
+    }
+
     /// Remove a strategy
-    pub fn remove_strategy(&mut self, name: &str) -> Option<Box<dyn Strategy>> {
-        self.strategies.remove(name)
+    pub fn remove_strategy(&mut self, id: &str) -> Option<ArbitrageStrategy> {
+        self.strategies.remove(id).map(|loaded| loaded.definition)
     }
-    
-    /// Get a strategy
-    pub fn get_strategy(&self, name: &str) -> Option<&Box<dyn Strategy>> {
-        self.strategies.get(name)
+
+    /// Get a strategy's definition
+    pub fn get_strategy(&self, id: &str) -> Option<&ArbitrageStrategy> {
+        self.strategies.get(id).map(|loaded| &loaded.definition)
     }
-    
-    /// Get all strategies
-    pub fn get_all_strategies(&self) -> &HashMap<String, Box<dyn Strategy>> {
-        &self.strategies
+
+    /// First strategy with the given name
+    pub fn find_by_name(&self, name: &str) -> Option<&ArbitrageStrategy> {
+        self.definitions().find(|definition| definition.name == name)
     }
-    
+
+    /// Every definition, active or not
+    pub fn definitions(&self) -> impl Iterator<Item = &ArbitrageStrategy> {
+        self.strategies.values().map(|loaded| &loaded.definition)
+    }
+
+    pub fn len(&self) -> usize {
+        self.strategies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strategies.is_empty()
+    }
+
+    /// Activate or deactivate a strategy, returning its name
+    pub fn set_active(&mut self, id: &str, active: bool, now: DateTime<Utc>) -> Option<String> {
+        let definition = &mut self.strategies.get_mut(id)?.definition;
+        definition.is_active = active;
+        definition.updated_at = now;
+        Some(definition.name.clone())
+    }
+
+    /// Active definitions with their evaluators, oldest first so the first to accept an
+    /// opportunity is the same on every run
+    pub fn active(&self) -> impl Iterator<Item = (&ArbitrageStrategy, &dyn Strategy)> {
+        let mut active: Vec<&LoadedStrategy> = self.strategies.values().filter(|loaded| loaded.definition.is_active).collect();
+        active.sort_by(|a, b| (a.definition.created_at, &a.definition.id).cmp(&(b.definition.created_at, &b.definition.id)));
+        active.into_iter().map(|loaded| (&loaded.definition, loaded.strategy.as_ref()))
+    }
+
+    /// Hand every strategy that learns from its record a fresh snapshot of the store's executions
+    pub async fn refresh_records(&self, store: &MemoryStore) {
+        for loaded in self.strategies.values() {
+            if let Some(days) = loaded.strategy.record_lookback_days() {
+                loaded.strategy.update_record(store.get_outcome_stats(days).await);
+            }
+        }
+    }
+
+    /// What strategy `name` would trade of the opportunity; None when it is unknown or sizes nothing
+    pub fn optimal_amount(&self, name: &str, opportunity: &ArbitrageOpportunity) -> Option<Decimal> {
+        let loaded = self.strategies.values().find(|loaded| loaded.definition.name == name)?;
+        loaded.strategy.calculate_optimal_amount(opportunity)
+    }
+
+    /// Let every strategy, active or not, learn from a detection
+    pub fn observe(&self, opportunity: &ArbitrageOpportunity) {
+        for loaded in self.strategies.values() {
+            loaded.strategy.observe(opportunity);
+        }
+    }
+
+    /// Count an evaluation of strategy `id` and whether it was selected
+    pub fn record_evaluation(&mut self, id: &str, selected: bool) {
+        if let Some(loaded) = self.strategies.get_mut(id) {
+            loaded.metrics.evaluations += 1;
+            loaded.metrics.selections += u64::from(selected);
+        }
+    }
+
+    /// Count an evaluation of strategy `id` that panicked
+    pub fn record_panic(&mut self, id: &str) {
+        if let Some(loaded) = self.strategies.get_mut(id) {
+            loaded.metrics.evaluations += 1;
+            loaded.metrics.panics += 1;
+        }
+    }
+
+    /// Metrics of strategy `id`
+    pub fn metrics(&self, id: &str) -> Option<&StrategyMetrics> {
+        self.strategies.get(id).map(|loaded| &loaded.metrics)
+    }
+
+    /// Metrics of every strategy, by id
+    pub fn all_metrics(&self) -> HashMap<String, StrategyMetrics> {
+        self.strategies.iter().map(|(id, loaded)| (id.clone(), loaded.metrics.clone())).collect()
+    }
+
     /// Evaluate an arbitrage opportunity
     pub fn evaluate_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Vec<StrategyEvaluation> {
         let mut evaluations = Vec::new();
         
-        for strategy in self.strategies.values() {
+        for (definition, strategy) in self.active() {
             let should_execute = strategy.should_execute(opportunity);
             let optimal_amount = strategy.calculate_optimal_amount(opportunity);
             let parameters = strategy.get_parameters();
             
             let evaluation = StrategyEvaluation {
+                strategy_id: definition.id.clone(),
                 strategy_name: strategy.name().to_string(),
                 should_execute,
                 optimal_amount,
@@ -326,14 +535,17 @@ impl StrategyManager {
     fn calculate_strategy_score(
         &self,
         opportunity: &ArbitrageOpportunity,
-        strategy: &Box<dyn Strategy>,
+        strategy: &dyn Strategy,
     ) -> f64 {
         let mut score = 0.0;
         
         // Profit score
-        let profit_score = (opportunity.profit_percentage / strategy.get_parameters().min_profit_threshold)
-            .to_f64()
-            .unwrap_or(0.0);
+        let min_profit_threshold = strategy.get_parameters().min_profit_threshold;
+        let profit_score = if min_profit_threshold > Decimal::ZERO {
+            (opportunity.profit_percentage / min_profit_threshold).to_f64().unwrap_or(0.0)
+        } else {
+            0.0
+        };
         score += profit_score * 0.4;
         
         // Risk score
@@ -357,11 +569,111 @@ impl StrategyManager {
 }
 
 /// Strategy evaluation result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct StrategyEvaluation {
+    pub strategy_id: String,
     pub strategy_name: String,
     pub should_execute: bool,
     pub optimal_amount: Option<Decimal>,
     pub parameters: StrategyParameters,
     pub score: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fixtures;
+
+    /// Accepts every opportunity with at least `min_buy_reserve` in the buy pool
+    struct ReserveFloor {
+        name: String,
+        min_buy_reserve: Decimal,
+    }
+
+    impl Strategy for ReserveFloor {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn description(&self) -> &str {
+            "Reserve floor"
+        }
+
+        fn should_execute(&self, opportunity: &ArbitrageOpportunity) -> bool {
+            opportunity.buy_pool.reserve_a >= self.min_buy_reserve
+        }
+
+        fn calculate_optimal_amount(&self, _: &ArbitrageOpportunity) -> Option<Decimal> {
+            None
+        }
+
+        fn get_parameters(&self) -> StrategyParameters {
+            StrategyParameters::default()
+        }
+
+        fn validate(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_definitions_are_built_through_their_registered_kind() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = |dex_type: DexType| {
+            fixtures::pool(dex_type, &sol, &usdc)
+                .update_reserves(Decimal::from(5_000), Decimal::from(750_000))
+        };
+        let opportunity = fixtures::opportunity_between(pool(DexType::Raydium), pool(DexType::Meteora));
+
+        let mut manager = StrategyManager::new();
+        let definition = |kind: &str, parameters: serde_json::Value| {
+            ArbitrageStrategy::new(
+                kind.to_string(),
+                String::new(),
+                Decimal::ZERO,
+                Decimal::new(1, 2),
+                Decimal::new(5, 3),
+                Decimal::from(1000),
+                vec![DexType::Raydium, DexType::Meteora],
+                RiskScore::High,
+            )
+            .with_kind(kind, parameters)
+        };
+        let floor = definition("reserve_floor", serde_json::json!({ "min_buy_reserve": 10_000 }));
+        assert!(manager.add_strategy(floor.clone()).is_err());
+
+        manager.register_kind(
+            "reserve_floor",
+            Arc::new(|definition| {
+                let min_buy_reserve = definition.parameters["min_buy_reserve"]
+                    .as_i64()
+                    .ok_or_else(|| anyhow::anyhow!("min_buy_reserve is required"))?;
+                Ok(Box::new(ReserveFloor { name: definition.name.clone(), min_buy_reserve: Decimal::from(min_buy_reserve) }))
+            }),
+        );
+        assert!(manager.add_strategy(definition("reserve_floor", serde_json::Value::Null)).is_err());
+        manager.add_strategy(floor.clone()).unwrap();
+        let threshold = definition(THRESHOLD_STRATEGY_KIND, serde_json::Value::Null);
+        manager.add_strategy(threshold.clone()).unwrap();
+        assert_eq!(manager.kinds(), vec!["kelly".to_string(), "mean_reversion".to_string(), "reserve_floor".to_string(), "threshold".to_string()]);
+
+        let evaluations = manager.evaluate_opportunity(&opportunity);
+        let accepted = |id: &str| evaluations.iter().find(|e| e.strategy_id == id).unwrap().should_execute;
+        assert!(!accepted(&floor.id));
+        assert!(accepted(&threshold.id));
+
+        // Consulted oldest first, whatever order the map holds them in
+        let order: Vec<&str> = manager.active().map(|(definition, _)| definition.id.as_str()).collect();
+        let mut expected = [&floor, &threshold];
+        expected.sort_by_key(|definition| (definition.created_at, definition.id.clone()));
+        assert_eq!(order, expected.map(|definition| definition.id.as_str()));
+
+        // Deactivated strategies are not evaluated; metrics survive a replacement
+        manager.record_evaluation(&threshold.id, true);
+        manager.set_active(&threshold.id, false, Utc::now());
+        assert_eq!(manager.evaluate_opportunity(&opportunity).len(), 1);
+        manager.add_strategy(threshold.clone()).unwrap();
+        assert_eq!(manager.metrics(&threshold.id).unwrap().selections, 1);
+        assert_eq!(manager.find_by_name("reserve_floor").unwrap().id, floor.id);
+    }
+}
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, transaction::Transaction};
+use solana_system_interface::instruction as system_instruction;
+use spl_associated_token_account_interface::instruction::create_associated_token_account_idempotent;
+use std::collections::HashMap;
+
+use crate::arbitrage::compute_budget;
+use crate::dex::pool_accounts::token_account_balance;
+use crate::models::Token;
+use crate::services::solana::SolanaService;
+use crate::services::wallet::Wallets;
+
+/// Accounts a route trades through and what the wallet already has of them
+#[derive(Debug, Clone, Default)]
+pub struct AccountSetup {
+    /// Tokens whose associated account the legs read or write, deduplicated by mint
+    pub tokens: Vec<Token>,
+    /// Raw wSOL the first leg spends; the shortfall is wrapped from the owner's SOL
+    pub wrap_lamports: u64,
+}
+
+impl AccountSetup {
+    pub fn new(tokens: impl IntoIterator<Item = Token>) -> Self {
+        let mut unique: Vec<Token> = Vec::new();
+        for token in tokens {
+            if !unique.iter().any(|seen| seen.mint == token.mint) {
+                unique.push(token);
+            }
+        }
+        Self { tokens: unique, wrap_lamports: 0 }
+    }
+
+    pub fn with_wrap(mut self, lamports: u64) -> Self {
+        self.wrap_lamports = lamports;
+        self
+    }
+
+    /// Whether any leg trades wSOL, so the owner's wSOL account is worth closing afterwards
+    pub fn touches_wsol(&self) -> bool {
+        self.tokens.iter().any(|token| token.mint == spl_token_interface::native_mint::id())
+    }
+
+    /// Instructions to run ahead of the first leg, given the raw balance of each associated account
+    /// that exists: a create for every missing one, then a top-up of wSOL to `wrap_lamports`.
+    /// Creates are idempotent, so an account opened in the meantime does not fail the bundle.
+    pub fn instructions(&self, payer: &Pubkey, owner: &Pubkey, existing: &HashMap<Pubkey, u64>) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        for token in &self.tokens {
+            if !existing.contains_key(&token.associated_token_address(owner)) {
+                instructions.push(create_associated_token_account_idempotent(payer, owner, &token.mint, &token.token_program));
+            }
+        }
+
+        let wsol = wsol_account(owner);
+        let shortfall = self.wrap_lamports.saturating_sub(existing.get(&wsol).copied().unwrap_or(0));
+        if shortfall > 0 {
+            if !existing.contains_key(&wsol) && !self.touches_wsol() {
+                instructions.push(create_associated_token_account_idempotent(
+                    payer,
+                    owner,
+                    &spl_token_interface::native_mint::id(),
+                    &spl_token_interface::id(),
+                ));
+            }
+            instructions.push(system_instruction::transfer(owner, &wsol, shortfall));
+            instructions.push(
+                spl_token_interface::instruction::sync_native(&spl_token_interface::id(), &wsol)
+                    .expect("the SPL Token program id is valid"),
+            );
+        }
+        instructions
+    }
+
+    /// Read which of the accounts exist and build the setup instructions
+    pub async fn resolve(&self, solana: &SolanaService, payer: &Pubkey, owner: &Pubkey) -> Result<Vec<Instruction>> {
+        let mut addresses: Vec<Pubkey> = self.tokens.iter().map(|token| token.associated_token_address(owner)).collect();
+        addresses.push(wsol_account(owner));
+        let accounts = solana.get_multiple_accounts(&addresses).await?;
+
+        let existing = addresses
+            .iter()
+            .zip(accounts)
+            .filter_map(|(address, account)| {
+                let amount = token_account_balance(&account?.data).map(|(_, amount)| amount).unwrap_or(0);
+                Some((*address, amount))
+            })
+            .collect();
+        Ok(self.instructions(payer, owner, &existing))
+    }
+}
+
+/// Token accounts the setup instructions open whose rent stays locked; the wSOL account is
+/// closed again with the tip, returning its rent
+pub fn opened_accounts(instructions: &[Instruction]) -> usize {
+    instructions
+        .iter()
+        .filter(|instruction| instruction.program_id == spl_associated_token_account_interface::program::id())
+        .filter(|instruction| instruction.accounts.get(3).is_some_and(|mint| mint.pubkey != spl_token_interface::native_mint::id()))
+        .count()
+}
+
+/// The owner's wrapped SOL account
+pub fn wsol_account(owner: &Pubkey) -> Pubkey {
+    spl_associated_token_account_interface::address::get_associated_token_address(owner, &spl_token_interface::native_mint::id())
+}
+
+/// Close the owner's wSOL account, returning its balance and rent as SOL
+pub fn unwrap_sol(owner: &Pubkey) -> Instruction {
+    spl_token_interface::instruction::close_account(&spl_token_interface::id(), &wsol_account(owner), owner, owner, &[])
+        .expect("the SPL Token program id is valid")
+}
+
+/// Run `setup` at the start of `transaction`, re-signing it
+pub fn prepend(transaction: &Transaction, setup: Vec<Instruction>, wallets: &Wallets) -> Result<Transaction> {
+    let mut instructions = setup;
+    instructions.extend(compute_budget::instructions(transaction));
+    compute_budget::rebuild(transaction, &instructions, wallets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_creates_missing_accounts_and_wraps_the_shortfall() {
+        let (payer, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "SOL".to_string(), 9);
+        let usdc = Token::new(Pubkey::new_unique(), "USDC".to_string(), "USDC".to_string(), 6);
+        let setup = AccountSetup::new([sol.clone(), usdc.clone(), sol.clone()]).with_wrap(1_000_000_000);
+        assert_eq!(setup.tokens.len(), 2);
+        assert!(setup.touches_wsol());
+
+        // Nothing exists yet: two creates, then the full amount wrapped
+        let instructions = setup.instructions(&payer, &owner, &HashMap::new());
+        let programs: Vec<Pubkey> = instructions.iter().map(|instruction| instruction.program_id).collect();
+        assert_eq!(
+            programs,
+            vec![
+                spl_associated_token_account_interface::program::id(),
+                spl_associated_token_account_interface::program::id(),
+                solana_system_interface::program::id(),
+                spl_token_interface::id(),
+            ]
+        );
+        assert_eq!(instructions[2], system_instruction::transfer(&owner, &wsol_account(&owner), 1_000_000_000));
+
+        // Both accounts open and 0.4 SOL already wrapped: only the rest is wrapped
+        let existing = HashMap::from([(wsol_account(&owner), 400_000_000), (usdc.associated_token_address(&owner), 0)]);
+        let instructions = setup.instructions(&payer, &owner, &existing);
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[0], system_instruction::transfer(&owner, &wsol_account(&owner), 600_000_000));
+
+        // Enough wrapped already: nothing to do
+        let existing = HashMap::from([(wsol_account(&owner), 1_000_000_000), (usdc.associated_token_address(&owner), 0)]);
+        assert!(setup.instructions(&payer, &owner, &existing).is_empty());
+    }
+}
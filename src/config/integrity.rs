@@ -0,0 +1,219 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::{debug, info};
+
+use crate::utils::crypto::{CryptoUtils, SignatureUtils};
+
+/// Base58 ed25519 public key (a Solana address) config files must be signed with; when set,
+/// unsigned or tampered files are refused
+pub const VERIFY_KEY_ENV: &str = "ARBITRAGE_BOT_CONFIG_VERIFY_KEY";
+
+/// Prefix of the environment variables that override config values
+pub const ENV_OVERRIDE_PREFIX: &str = "ARBITRAGE_BOT";
+
+/// Why a config file was refused
+#[derive(Debug, thiserror::Error)]
+pub enum IntegrityError {
+    #[error("{0} has no signature sidecar but {VERIFY_KEY_ENV} requires one")]
+    MissingSignature(PathBuf),
+
+    #[error("Signature of {0} does not verify; the file was changed after signing")]
+    BadSignature(PathBuf),
+
+    #[error("Checksum of {path} is {actual}, sidecar expects {expected}")]
+    ChecksumMismatch { path: PathBuf, expected: String, actual: String },
+
+    #[error("{VERIFY_KEY_ENV} requires signed config, but unsigned overrides are set: {}", .0.join(", "))]
+    UnsignedOverrides(Vec<String>),
+
+    #[error("Invalid {what}: {reason}")]
+    Invalid { what: String, reason: String },
+
+    #[error("Cannot read {path}: {source}")]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+/// How far a loaded file could be vouched for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    Signed,
+    Checksummed,
+    /// No sidecar, and no key asking for one
+    Unverified,
+}
+
+/// `<file>.sig`: hex ed25519 signature of the file's bytes
+pub fn signature_path(path: &Path) -> PathBuf {
+    sidecar(path, "sig")
+}
+
+/// `<file>.sha256`: hex SHA-256 of the file's bytes, optionally followed by the file name as
+/// `sha256sum` prints it
+pub fn checksum_path(path: &Path) -> PathBuf {
+    sidecar(path, "sha256")
+}
+
+fn sidecar(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, IntegrityError> {
+    std::fs::read(path).map_err(|source| IntegrityError::Io { path: path.to_path_buf(), source })
+}
+
+/// Checks config and strategy files against their sidecars before they are parsed
+#[derive(Debug, Clone, Default)]
+pub struct ConfigVerifier {
+    key: Option<Pubkey>,
+}
+
+impl ConfigVerifier {
+    /// Require signatures by the key in `VERIFY_KEY_ENV`, if it is set
+    pub fn from_env() -> Result<Self, IntegrityError> {
+        match std::env::var(VERIFY_KEY_ENV) {
+            Ok(key) if !key.trim().is_empty() => {
+                let key = Pubkey::from_str(key.trim()).map_err(|e| IntegrityError::Invalid {
+                    what: VERIFY_KEY_ENV.to_string(),
+                    reason: e.to_string(),
+                })?;
+                Ok(Self::default().with_key(key))
+            }
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn with_key(mut self, key: Pubkey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Verify `path` against its sidecars. With a key every file must carry a valid signature;
+    /// without one a checksum sidecar, where present, must match.
+    pub fn verify(&self, path: &Path) -> Result<Verification, IntegrityError> {
+        let content = read(path)?;
+
+        let checksum = checksum_path(path);
+        let checksummed = checksum.exists();
+        if checksummed {
+            let sidecar = String::from_utf8_lossy(&read(&checksum)?).to_string();
+            let expected = sidecar.split_whitespace().next().unwrap_or_default().to_lowercase();
+            let actual = hex::encode(CryptoUtils::sha256(&content));
+            if expected != actual {
+                return Err(IntegrityError::ChecksumMismatch { path: path.to_path_buf(), expected, actual });
+            }
+        }
+
+        let Some(key) = &self.key else {
+            debug!("{} loaded {}", path.display(), if checksummed { "with matching checksum" } else { "unverified" });
+            return Ok(if checksummed { Verification::Checksummed } else { Verification::Unverified });
+        };
+        let signature = signature_path(path);
+        if !signature.exists() {
+            return Err(IntegrityError::MissingSignature(path.to_path_buf()));
+        }
+        let invalid = |reason: String| IntegrityError::Invalid { what: format!("signature {}", signature.display()), reason };
+        let bytes = hex::decode(String::from_utf8_lossy(&read(&signature)?).trim()).map_err(|e| invalid(e.to_string()))?;
+        if !SignatureUtils::verify_signature(&key.to_bytes(), &content, &bytes).map_err(invalid)? {
+            return Err(IntegrityError::BadSignature(path.to_path_buf()));
+        }
+        info!("{} signature verified", path.display());
+        Ok(Verification::Signed)
+    }
+
+    /// Whether every file has to carry a valid signature
+    pub fn requires_signatures(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Refuse environment overrides of config values when signatures are required, since nothing
+    /// vouches for them. `vars` is the process environment.
+    pub fn check_env_overrides(&self, vars: impl IntoIterator<Item = (String, String)>) -> Result<(), IntegrityError> {
+        if !self.requires_signatures() {
+            return Ok(());
+        }
+        let prefix = format!("{}_", ENV_OVERRIDE_PREFIX);
+        let mut overrides: Vec<String> = vars
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| name.starts_with(&prefix) && name != VERIFY_KEY_ENV)
+            .collect();
+        if overrides.is_empty() {
+            return Ok(());
+        }
+        overrides.sort();
+        Err(IntegrityError::UnsignedOverrides(overrides))
+    }
+
+    /// Verify `path` if it exists; optional config files are skipped when absent
+    pub fn verify_optional(&self, path: &Path) -> Result<Option<Verification>, IntegrityError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        self.verify(path).map(Some)
+    }
+}
+
+/// Write the signature and checksum sidecars of `path`, returning the signature's path
+pub fn sign(path: &Path, keypair: &Keypair) -> Result<PathBuf, IntegrityError> {
+    let content = read(path)?;
+    let write = |target: PathBuf, body: String| {
+        std::fs::write(&target, body).map_err(|source| IntegrityError::Io { path: target.clone(), source })?;
+        Ok(target)
+    };
+    write(checksum_path(path), format!("{}\n", hex::encode(CryptoUtils::sha256(&content))))?;
+    write(signature_path(path), format!("{}\n", hex::encode(keypair.sign_message(&content).as_ref())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_files_verify_until_tampered() {
+        let dir = std::env::temp_dir().join(format!("config-integrity-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("local.toml");
+        std::fs::write(&path, "[arbitrage]\nmin_profit_threshold = 0.01\n").unwrap();
+
+        let unkeyed = ConfigVerifier::default();
+        assert_eq!(unkeyed.verify(&path).unwrap(), Verification::Unverified);
+        assert!(unkeyed.verify_optional(&dir.join("missing.toml")).unwrap().is_none());
+
+        let keypair = Keypair::new();
+        let keyed = ConfigVerifier::default().with_key(keypair.pubkey());
+        assert!(matches!(keyed.verify(&path), Err(IntegrityError::MissingSignature(_))));
+
+        sign(&path, &keypair).unwrap();
+        assert_eq!(keyed.verify(&path).unwrap(), Verification::Signed);
+        assert_eq!(unkeyed.verify(&path).unwrap(), Verification::Checksummed);
+        // Signed by someone else
+        let other = ConfigVerifier::default().with_key(Keypair::new().pubkey());
+        assert!(matches!(other.verify(&path), Err(IntegrityError::BadSignature(_))));
+
+        std::fs::write(&path, "[arbitrage]\nmin_profit_threshold = 0.0001\n").unwrap();
+        assert!(matches!(unkeyed.verify(&path), Err(IntegrityError::ChecksumMismatch { .. })));
+        std::fs::remove_file(checksum_path(&path)).unwrap();
+        assert!(matches!(keyed.verify(&path), Err(IntegrityError::BadSignature(_))));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_env_overrides_are_refused_when_signatures_are_required() {
+        let var = |name: &str| (name.to_string(), "1".to_string());
+        let vars = vec![var(VERIFY_KEY_ENV), var("ARBITRAGE_BOT_ARBITRAGE__MIN_PROFIT_THRESHOLD"), var("HOME")];
+
+        assert!(ConfigVerifier::default().check_env_overrides(vars.clone()).is_ok());
+        let keyed = ConfigVerifier::default().with_key(Keypair::new().pubkey());
+        assert!(keyed.check_env_overrides(vec![var(VERIFY_KEY_ENV), var("HOME")]).is_ok());
+        match keyed.check_env_overrides(vars) {
+            Err(IntegrityError::UnsignedOverrides(names)) => assert_eq!(names, vec!["ARBITRAGE_BOT_ARBITRAGE__MIN_PROFIT_THRESHOLD"]),
+            other => panic!("expected refused overrides, got {:?}", other),
+        }
+    }
+}
@@ -1,8 +1,16 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use config::{Config, Environment, File};
 
+use crate::arbitrage::strategy::Strategy;
+use crate::dex::DexType;
+use crate::models::{ArbitrageStrategy, RiskScore, Urgency, THRESHOLD_STRATEGY_KIND};
+use crate::services::fee_model::{DEFAULT_LAMPORTS_PER_SIGNATURE, DEFAULT_TOKEN_ACCOUNT_RENT_LAMPORTS};
+
+pub mod integrity;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub database: DatabaseConfig,
@@ -11,6 +19,88 @@ pub struct AppConfig {
     pub dex: DexConfig,
     pub arbitrage: ArbitrageConfig,
     pub logging: LoggingConfig,
+    #[serde(default)]
+    pub scanner: ScannerConfig,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    #[serde(default)]
+    pub enrichment: EnrichmentConfig,
+    #[serde(default)]
+    pub submission: SubmissionConfig,
+    #[serde(default)]
+    pub fees: FeeConfig,
+    #[serde(default)]
+    pub wallet: WalletConfig,
+    #[serde(default)]
+    pub blacklist: BlacklistConfig,
+    #[serde(default)]
+    pub position_watcher: PositionWatcherConfig,
+    #[serde(default)]
+    pub dex_health: DexHealthConfig,
+    #[serde(default)]
+    pub journal: JournalConfig,
+    #[serde(default)]
+    pub idl: IdlConfig,
+    #[serde(default)]
+    pub precision: PrecisionConfig,
+    #[serde(default)]
+    pub control_api: ControlApiConfig,
+    #[serde(default)]
+    pub supervisor: SupervisorConfig,
+    #[serde(default)]
+    pub affinity: AffinityConfig,
+    #[serde(default)]
+    pub connections: ConnectionConfig,
+    #[serde(default)]
+    pub tpu: TpuConfig,
+    #[serde(default)]
+    pub lanes: LanesConfig,
+    #[serde(default)]
+    pub scoring: ScoringConfig,
+    #[serde(default)]
+    pub websocket: WebSocketConfig,
+    #[serde(default)]
+    pub fingerprints: FingerprintConfig,
+    #[serde(default)]
+    pub account_cache: AccountCacheConfig,
+    #[serde(default)]
+    pub wallet_watch: WalletWatchConfig,
+    #[serde(default)]
+    pub oracle: OracleConfig,
+    #[serde(default)]
+    pub token_registry: TokenRegistryConfig,
+    #[serde(default)]
+    pub tenancy: TenancyConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub correlation: CorrelationConfig,
+    #[serde(default)]
+    pub dead_man: DeadManConfig,
+    #[serde(default)]
+    pub geyser: GeyserConfig,
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+    #[serde(default)]
+    pub risk: RiskConfig,
+    #[serde(default)]
+    pub pair_stats: PairStatsConfig,
+    #[serde(default)]
+    pub service: ServiceConfig,
+    #[serde(default)]
+    pub token_safety: TokenSafetyConfig,
+    #[serde(default)]
+    pub rent_reclaim: RentReclaimConfig,
+    #[serde(default)]
+    pub flatten: FlattenConfig,
+    #[serde(default)]
+    pub benchmark: BenchmarkConfig,
+    #[serde(default)]
+    pub notifier: NotifierConfig,
+    #[serde(default)]
+    pub balances: BalanceConfig,
+    #[serde(default)]
+    pub strategies: Vec<StrategyConfig>,
     pub environment: String,
 }
 
@@ -19,6 +109,14 @@ pub struct DatabaseConfig {
     pub url: String,
     pub max_connections: u32,
     pub timeout_seconds: u64,
+    /// Writes are journaled here while the database is unreachable and replayed once it is back;
+    /// empty drops them instead
+    pub buffer_path: String,
+    pub buffer_capacity_mb: u64,
+    /// How often a lost database is probed
+    pub recovery_interval_seconds: u64,
+    /// Store the reserves of both pools of every opportunity, as seen at detection
+    pub capture_pool_snapshots: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +126,15 @@ pub struct MemoryStoreConfig {
     pub max_executions: usize,
     pub cleanup_interval_seconds: u64,
     pub data_retention_days: u64,
+    /// Contents are snapshotted here and restored at startup; empty keeps them in memory only
+    #[serde(default)]
+    pub snapshot_path: String,
+    #[serde(default = "default_snapshot_interval_seconds")]
+    pub snapshot_interval_seconds: u64,
+}
+
+fn default_snapshot_interval_seconds() -> u64 {
+    60
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +144,16 @@ pub struct SolanaConfig {
     pub commitment: String,
     pub jito_url: String,
     pub jito_auth_header: String,
+    /// Retries of an RPC call failing with a timeout, dropped connection or throttling
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Limit on each attempt of an RPC call
+    #[serde(default = "default_rpc_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+fn default_rpc_timeout_seconds() -> u64 {
+    30
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,14 +162,43 @@ pub struct DexConfig {
     pub meteora: DexEndpointConfig,
     pub whirlpool: DexEndpointConfig,
     pub pump: DexEndpointConfig,
+    /// Venues below are skipped while their base_url is empty
+    #[serde(default)]
+    pub orca_legacy: DexEndpointConfig,
+    #[serde(default)]
+    pub phoenix: DexEndpointConfig,
+    #[serde(default)]
+    pub jupiter: JupiterConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DexEndpointConfig {
     pub base_url: String,
     pub api_key: String,
     pub timeout_seconds: u64,
     pub rate_limit: u32,
+    /// Retries of an API call failing with a timeout, dropped connection, throttling or server error
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+/// Jupiter aggregator; quotes are best routes across venues rather than single pools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JupiterConfig {
+    pub enabled: bool,
+    /// Swap API root serving `/quote` and `/swap`
+    pub base_url: String,
+    pub api_key: String,
+    pub timeout_seconds: u64,
+    pub rate_limit: u32,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Execute the sell leg through Jupiter when its route returns more than the opportunity's sell pool
+    pub route_sell_leg: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,17 +218,1132 @@ pub struct LoggingConfig {
     pub max_files: u32,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScannerConfig {
+    /// Allowed pairs as "MINT_A/MINT_B"; empty means all pairs are scanned
+    #[serde(default)]
+    pub pair_allowlist: Vec<String>,
+    /// Minimum pool liquidity (reserve_a + reserve_b) considered by the scanner
+    #[serde(default)]
+    pub min_liquidity: f64,
+    /// How pairs with Token-2022 transfer-fee mints are handled
+    #[serde(default)]
+    pub fee_on_transfer: FeeOnTransferPolicy,
+    /// Multi-hop cycles through three or more pools
+    #[serde(default)]
+    pub triangular: TriangularConfig,
+    /// Cap on the profit-maximizing size profit is estimated at, in base tokens; 0 leaves it uncapped
+    #[serde(default)]
+    pub max_trade_amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriangularConfig {
+    pub enabled: bool,
+    /// Longest cycle searched, in pools; at least 3
+    pub max_hops: usize,
+    /// Mints cycles start and end at
+    pub start_mints: Vec<String>,
+    /// Amount of the start token each cycle is quoted with
+    pub input_amount: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeOnTransferPolicy {
+    /// Quote with the transfer fee deducted
+    #[default]
+    Adjust,
+    /// Skip pairs involving transfer-fee mints
+    Exclude,
+}
+
+/// How an amount is brought to a mint's decimal precision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingMode {
+    /// Toward zero
+    Floor,
+    /// Away from zero
+    Ceil,
+    /// Half away from zero
+    Nearest,
+}
+
+/// Rounding applied when amounts become on-chain integer units
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrecisionConfig {
+    /// Input amounts; flooring never spends more than the wallet was asked to
+    pub input_rounding: RoundingMode,
+    /// Minimum outputs; rounding up never accepts less than the slippage bound
+    pub min_out_rounding: RoundingMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertsConfig {
+    pub enabled: bool,
+    pub evaluation_interval_seconds: u64,
+    #[serde(default)]
+    pub rules: Vec<AlertRuleConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRuleConfig {
+    pub name: String,
+    /// Rule expression, e.g. "if success_rate_1h < 0.5 then notify telegram"
+    pub rule: String,
+    #[serde(default)]
+    pub cooldown_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrichmentConfig {
+    /// "none", "helius" or "triton"
+    pub provider: String,
+    #[serde(default)]
+    pub api_key: String,
+    /// DAS endpoint; required for triton, derived from api_key for helius when empty
+    #[serde(default)]
+    pub rpc_url: String,
+    pub timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionConfig {
+    /// Pick bundle vs RPC submission from the upcoming leader
+    pub leader_aware: bool,
+    /// Identities of Jito-enabled validators
+    #[serde(default)]
+    pub jito_validators: Vec<String>,
+    /// Max slots to wait for a Jito leader; beyond them the bundle is held for a later one
+    pub max_delay_slots: u64,
+    /// Priority fee and tip multiplier when no Jito leader is upcoming
+    pub non_jito_fee_multiplier: f64,
+    /// Additional RPC nodes that signed transactions are rebroadcast to
+    #[serde(default)]
+    pub rebroadcast_rpc_urls: Vec<String>,
+    pub rebroadcast_interval_ms: u64,
+    /// Upper bound on rebroadcasting, in case blockhash expiry cannot be observed
+    pub rebroadcast_timeout_seconds: u64,
+    /// Submit both legs of an arbitrage as one Jito bundle
+    pub bundles_enabled: bool,
+    /// Tip paid to the block engine with every bundle
+    pub bundle_tip_lamports: u64,
+    /// How long to wait for a bundle to land before marking it failed
+    pub bundle_status_timeout_seconds: u64,
+    /// Base-token size of a two-pool arbitrage whose pools give no closed-form optimum
+    pub trade_size: f64,
+    /// Simulate every leg before submitting, and abort when one fails or the profit is gone.
+    /// Bundles of more than one leg are always simulated.
+    pub simulate_before_submit: bool,
+    /// Jito-Solana RPC serving `simulateBundle`; empty, or unreachable, simulates legs one by one
+    #[serde(default)]
+    pub bundle_simulation_url: String,
+    /// Each leg requests the compute units its simulation consumed times this, instead of the
+    /// default allowance its priority fee is charged on; 0 keeps the legs as built
+    #[serde(default = "default_compute_unit_margin")]
+    pub compute_unit_margin: f64,
+    /// Open any associated token account the legs need ahead of the first leg
+    #[serde(default = "default_true")]
+    pub create_token_accounts: bool,
+    /// Wrap the SOL a route starting from wSOL spends ahead of the first leg, and close the wSOL
+    /// account with the tip so proceeds come back as SOL
+    #[serde(default = "default_true")]
+    pub wrap_sol: bool,
+    /// Quote and validate opportunities instead of submitting. The simulated executions are logged
+    /// and published as events, but no record, risk limit or statistic counts them.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Execution costs netted out of estimated profit; the bundle tip is `submission.bundle_tip_lamports`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeeConfig {
+    pub lamports_per_signature: u64,
+    /// Priority fee expected per leg
+    pub priority_fee_lamports: u64,
+    /// Rent locked in each token account a route has to open
+    pub token_account_rent_lamports: u64,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            lamports_per_signature: DEFAULT_LAMPORTS_PER_SIGNATURE,
+            priority_fee_lamports: 10_000,
+            token_account_rent_lamports: DEFAULT_TOKEN_ACCOUNT_RENT_LAMPORTS,
+        }
+    }
+}
+
+fn default_compute_unit_margin() -> f64 {
+    1.2
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WalletConfig {
+    /// Keypair file of the token-holding wallet
+    #[serde(default)]
+    pub keypair_path: String,
+    /// Optional keypair file of a dedicated fee payer
+    #[serde(default)]
+    pub fee_payer_keypair_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlacklistConfig {
+    /// Automatically blacklist pools after repeated bad executions
+    pub enabled: bool,
+    /// Failed or unprofitable executions within the window before a pool is blacklisted
+    pub failure_threshold: usize,
+    pub window_seconds: u64,
+    /// How long a pool stays blacklisted
+    pub ttl_seconds: u64,
+    /// JSON file the blacklist is persisted to; empty keeps it in memory only
+    #[serde(default)]
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionWatcherConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    /// Mints counted as exits (e.g. USDC, wSOL); holdings of these are not checked. Exit values are
+    /// reported in the first, and exits into the others are converted to it through SOL
+    pub quote_mints: Vec<String>,
+    /// Wallets to watch; defaults to the configured wallet and fee payer
+    #[serde(default)]
+    pub wallets: Vec<String>,
+    /// A pool whose price impact for the full position exceeds this cannot absorb it
+    pub max_exit_price_impact: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DexHealthConfig {
+    pub probe_interval_seconds: u64,
+    /// Request timeout is this multiple of the measured p95 latency
+    pub timeout_multiplier: f64,
+    pub min_timeout_ms: u64,
+    /// Also used as the timeout before any latency has been measured
+    pub max_timeout_ms: u64,
+    /// Latency at which a venue's route weight is halved
+    pub reference_latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalConfig {
+    pub enabled: bool,
+    pub path: String,
+    /// Size of the ring buffer; the oldest events are overwritten once full
+    pub capacity_mb: u64,
+    pub flush_interval_ms: u64,
+    /// Event encoding: "bincode", "json" or "proto". Importing a journal decodes with the same codec.
+    #[serde(default = "default_journal_codec")]
+    pub codec: String,
+}
+
+fn default_journal_codec() -> String {
+    "bincode".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlConfig {
+    /// Directory of bundled Anchor IDL JSON files
+    pub dir: String,
+    /// Fetch IDLs published on-chain for programs without a bundled one
+    pub fetch_on_chain: bool,
+    /// Anchor programs an IDL is expected for
+    #[serde(default)]
+    pub programs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlApiConfig {
+    pub enabled: bool,
+    pub bind: String,
+    /// Per-venue limit when comparing quotes
+    pub quote_timeout_ms: u64,
+    /// Strategy updates that move a threshold by more than this factor need `?force=true`
+    pub max_strategy_change_factor: f64,
+}
+
+/// What happens when a supervised background task exits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RestartPolicy {
+    /// Restart after the initial backoff every time
+    Always,
+    /// Restart with exponentially growing delays
+    Backoff,
+    /// Leave the task stopped
+    Never,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorConfig {
+    pub initial_backoff_ms: u64,
+    /// Backoff ceiling; a run lasting longer than this resets the backoff
+    pub max_backoff_ms: u64,
+    /// Notification channel for task failures and panics
+    pub alert_channel: String,
+    /// Per-task overrides of the built-in restart policies, keyed by task name
+    #[serde(default)]
+    pub policies: HashMap<String, RestartPolicy>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AffinityConfig {
+    /// Pin runtime worker threads to dedicated cores
+    #[serde(default)]
+    pub enabled: bool,
+    /// Cores for the runtime worker threads, one thread per core
+    #[serde(default)]
+    pub runtime_cores: Vec<usize>,
+    /// Use the cores of this NUMA node when `runtime_cores` is empty
+    #[serde(default)]
+    pub numa_node: Option<usize>,
+    /// Core reserved for the websocket ingestion thread
+    #[serde(default)]
+    pub ingestion_core: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionConfig {
+    /// Ping submit-path endpoints this often so their connections stay open; 0 disables
+    pub keep_alive_interval_seconds: u64,
+    pub tcp_keepalive_seconds: u64,
+    /// Idle pooled connections are closed after this long
+    pub pool_idle_timeout_seconds: u64,
+    /// Open connections at startup instead of on the first submit
+    pub prewarm: bool,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            keep_alive_interval_seconds: 15,
+            tcp_keepalive_seconds: 30,
+            pool_idle_timeout_seconds: 90,
+            prewarm: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TpuConfig {
+    /// Send non-bundle transactions over QUIC straight to leaders instead of through RPC
+    pub enabled: bool,
+    /// Slots ahead whose leaders receive each transaction
+    pub fanout_slots: u64,
+    /// How often leader TPU addresses are re-read from gossip
+    pub contact_refresh_seconds: u64,
+    pub connect_timeout_ms: u64,
+}
+
+impl Default for TpuConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fanout_slots: 8,
+            contact_refresh_seconds: 60,
+            connect_timeout_ms: 2000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanesConfig {
+    /// Opportunities expected to expire within this many slots take the fast lane
+    pub fast_lane_max_slots: u64,
+    pub slot_ms: u64,
+}
+
+impl Default for LanesConfig {
+    fn default() -> Self {
+        Self {
+            fast_lane_max_slots: 2,
+            slot_ms: 400,
+        }
+    }
+}
+
+/// How queued opportunities are ranked for execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    /// Fraction of the score given up per risk level above Low
+    pub risk_discount: f64,
+    /// Pool staleness that halves the score; zero ignores pool freshness
+    pub freshness_half_life_ms: u64,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            risk_discount: 0.25,
+            freshness_half_life_ms: 2000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketConfig {
+    /// Subscribe to pool accounts over `solana.ws_url` and rescan whenever one changes
+    pub enabled: bool,
+    pub commitment: String,
+    /// Most pools subscribed at once; the rest are only seen on full refreshes
+    pub max_subscriptions: usize,
+    /// Interval between full pool refreshes while subscriptions are live
+    pub refresh_interval_seconds: u64,
+    pub ping_interval_seconds: u64,
+    /// First reconnect delay, doubled on each failure up to 30 seconds
+    pub reconnect_backoff_ms: u64,
+}
+
+/// Rolls old journal and snapshot files, and old pool snapshot rows, into daily zstd archives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    pub enabled: bool,
+    /// Directory scanned for files to archive; subdirectories are left alone
+    pub data_dir: String,
+    pub archive_dir: String,
+    /// File extensions that are archived once older than the retention
+    pub extensions: Vec<String>,
+    pub retention_days: u64,
+    /// Also move pool snapshot rows past the retention out of the database
+    pub database_snapshots: bool,
+    pub compression_level: i32,
+    pub interval_seconds: u64,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            data_dir: "data".to_string(),
+            archive_dir: "data/archive".to_string(),
+            extensions: vec!["journal".to_string(), "jsonl".to_string(), "csv".to_string()],
+            retention_days: 14,
+            database_snapshots: true,
+            compression_level: 3,
+            interval_seconds: 3600,
+        }
+    }
+}
+
+/// Account updates from a Yellowstone gRPC endpoint in place of the RPC websocket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeyserConfig {
+    pub enabled: bool,
+    /// `https://` or, for endpoints without TLS, `http://`
+    pub endpoint: String,
+    /// Sent as the `x-token` header; empty sends none
+    pub x_token: String,
+    pub commitment: String,
+    /// Pool addresses to stream; empty streams every pool the scanner tracks
+    #[serde(default)]
+    pub pools: Vec<String>,
+    /// First reconnect delay, doubled on each failure up to 30 seconds
+    pub reconnect_backoff_ms: u64,
+}
+
+impl Default for GeyserConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            x_token: String::new(),
+            commitment: "processed".to_string(),
+            pools: Vec::new(),
+            reconnect_backoff_ms: 500,
+        }
+    }
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            commitment: "processed".to_string(),
+            max_subscriptions: 200,
+            refresh_interval_seconds: 60,
+            ping_interval_seconds: 30,
+            reconnect_backoff_ms: 500,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FingerprintConfig {
+    /// Skip opportunities matching one executed within the TTL, including by a previous run
+    pub enabled: bool,
+    pub ttl_seconds: u64,
+    /// Width of the price buckets, in basis points
+    pub price_bucket_bps: u32,
+    /// JSON file fingerprints are persisted to; empty keeps them in memory only
+    pub path: String,
+}
+
+impl Default for FingerprintConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_seconds: 120,
+            price_bucket_bps: 10,
+            path: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountCacheConfig {
+    /// Serve repeated account reads from memory instead of RPC
+    pub enabled: bool,
+    /// Slots an account read stays valid for; 0 reuses it only within the slot it was fetched in
+    pub max_age_slots: u64,
+    /// Slot duration used to estimate the current slot between responses
+    pub slot_ms: u64,
+    pub max_entries: usize,
+}
+
+impl Default for AccountCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_age_slots: 0,
+            slot_ms: 400,
+            max_entries: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletWatchConfig {
+    pub enabled: bool,
+    /// Wallets to watch; defaults to the configured wallet and fee payer
+    #[serde(default)]
+    pub wallets: Vec<String>,
+    /// Also watch the token accounts the wallets hold at startup
+    pub watch_token_accounts: bool,
+    /// Pause execution as well as notifying when funds leave in a transaction the bot did not sign
+    pub auto_pause: bool,
+    pub alert_channel: String,
+    /// Signatures fetched per balance drop
+    pub signature_limit: usize,
+}
+
+impl Default for WalletWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            wallets: Vec::new(),
+            watch_token_accounts: true,
+            auto_pause: false,
+            alert_channel: "ops".to_string(),
+            signature_limit: 20,
+        }
+    }
+}
+
+/// A Pyth price feed for a token mint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleFeed {
+    pub mint: String,
+    /// Hex feed id, with or without the 0x prefix
+    pub feed_id: String,
+}
+
+/// Where mint symbols, names and decimals are looked up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenRegistryConfig {
+    /// Name tokens from the sources below; mint accounts are read regardless
+    pub enabled: bool,
+    /// Jupiter token list; empty leaves only the built-in wSOL entry
+    pub token_list_url: String,
+    /// How long a downloaded list is reused
+    pub refresh_seconds: u64,
+    pub timeout_seconds: u64,
+    /// A mint no source knows is looked up again after this long
+    pub miss_retry_seconds: u64,
+    /// Name mints missing from the list after their on-chain Metaplex metadata
+    pub metaplex_fallback: bool,
+    /// Mints with a Token-2022 transfer fee are read again after this long, to pick up fee changes
+    pub transfer_fee_refresh_seconds: u64,
+}
+
+impl Default for TokenRegistryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            token_list_url: "https://lite-api.jup.ag/tokens/v1/tagged/verified".to_string(),
+            refresh_seconds: 3600,
+            timeout_seconds: 10,
+            miss_retry_seconds: 600,
+            metaplex_fallback: true,
+            transfer_fee_refresh_seconds: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleConfig {
+    pub enabled: bool,
+    pub hermes_url: String,
+    /// Pools whose price is further than this fraction from the oracle's are skipped as stale
+    pub max_deviation: f64,
+    /// Oracle prices published longer ago than this are not used
+    pub max_age_seconds: u64,
+    /// Fetched prices are reused for this long
+    pub cache_ms: u64,
+    pub timeout_seconds: u64,
+    #[serde(default)]
+    pub feeds: Vec<OracleFeed>,
+}
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        let feed = |mint: &str, feed_id: &str| OracleFeed { mint: mint.to_string(), feed_id: feed_id.to_string() };
+        Self {
+            enabled: false,
+            hermes_url: "https://hermes.pyth.network".to_string(),
+            max_deviation: 0.02,
+            max_age_seconds: 60,
+            cache_ms: 2000,
+            timeout_seconds: 5,
+            feeds: vec![
+                feed("So11111111111111111111111111111111111111112", "0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d"),
+                feed("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "0xeaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a"),
+                feed("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", "0x2b89b9dc8fdf9f34709a5b106b472f0f39bb6ca9ce04b0fd7f2e971688e2e53b"),
+            ],
+        }
+    }
+}
+
+/// An operator sharing the instance, isolated in its own namespace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantConfig {
+    pub namespace: String,
+    /// Bearer token the tenant authenticates with on the control API
+    pub api_token: String,
+    /// Wallet the tenant's executions sign with; an empty keypair path shares the main wallet
+    #[serde(default)]
+    pub wallet: WalletConfig,
+    /// Net loss within a UTC day after which the tenant's strategies stop trading; 0 disables
+    #[serde(default)]
+    pub daily_loss_limit: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TenancyConfig {
+    pub enabled: bool,
+    /// Bearer token with access to every namespace; empty leaves the control API to tenants only
+    #[serde(default)]
+    pub operator_token: String,
+    #[serde(default)]
+    pub tenants: Vec<TenantConfig>,
+}
+
+/// A recurring window during which execution is paused
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceWindowConfig {
+    pub name: String,
+    /// Five-field cron expression in UTC for when the window opens
+    pub schedule: String,
+    pub duration_minutes: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    pub enabled: bool,
+    pub check_interval_seconds: u64,
+    /// How long before a window opens to send notice of it
+    pub notice_minutes: u32,
+    pub alert_channel: String,
+    #[serde(default)]
+    pub windows: Vec<MaintenanceWindowConfig>,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_interval_seconds: 30,
+            notice_minutes: 15,
+            alert_channel: "ops".to_string(),
+            windows: Vec::new(),
+        }
+    }
+}
+
+/// Heartbeats out to an external monitor and, optionally, in from an operator system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadManConfig {
+    pub enabled: bool,
+    /// Pinged every interval, healthchecks.io style; empty sends no heartbeats
+    pub ping_url: String,
+    pub interval_seconds: u64,
+    /// Pause execution when `POST /heartbeat` goes quiet for `inbound_timeout_seconds`
+    pub require_inbound: bool,
+    pub inbound_timeout_seconds: u64,
+    pub alert_channel: String,
+}
+
+impl Default for DeadManConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ping_url: String::new(),
+            interval_seconds: 60,
+            require_inbound: false,
+            inbound_timeout_seconds: 300,
+            alert_channel: "ops".to_string(),
+        }
+    }
+}
+
+/// Rolling correlation of pair spreads, used to flag concurrent executions riding the same move
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrelationConfig {
+    pub bucket_seconds: u64,
+    /// Buckets in the rolling window
+    pub window_buckets: usize,
+    /// Buckets with a spread a pair needs before it is correlated at all
+    pub min_samples: usize,
+    /// Warn when an opportunity's spread correlates at least this much with one in flight
+    pub warn_threshold: f64,
+}
+
+impl Default for CorrelationConfig {
+    fn default() -> Self {
+        Self {
+            bucket_seconds: 60,
+            window_buckets: 240,
+            min_samples: 10,
+            warn_threshold: 0.8,
+        }
+    }
+}
+
+/// Circuit breaker thresholds; a limit of zero disables that check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskConfig {
+    pub enabled: bool,
+    /// Halt after this many failed executions in a row
+    pub max_consecutive_failures: u32,
+    /// Stop for the rest of the UTC day once net losses reach this, in SOL
+    pub daily_loss_limit: f64,
+    /// Turn away opportunities that would put more than this in flight at once
+    pub max_capital_at_risk: f64,
+    /// Halt when realized profit averages this fraction below estimates over `slippage_window`
+    pub max_slippage_shortfall: f64,
+    pub slippage_window: usize,
+    pub alert_channel: String,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_consecutive_failures: 5,
+            daily_loss_limit: 0.0,
+            max_capital_at_risk: 0.0,
+            max_slippage_shortfall: 0.5,
+            slippage_window: 10,
+            alert_channel: "ops".to_string(),
+        }
+    }
+}
+
+/// Per-pair cooldowns after failed executions and rolling hit rates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PairStatsConfig {
+    pub enabled: bool,
+    /// Cooldown after a failed execution on a pair, doubled for each further failure in a row
+    pub cooldown_seconds: u64,
+    pub max_cooldown_seconds: u64,
+    /// Finished executions per pair the hit rate is computed over
+    pub window: usize,
+    /// Executions on a pair before its hit rate counts
+    pub min_executions: usize,
+    /// Pairs hitting less often leave the fast lane
+    pub min_hit_rate: f64,
+    /// Profit fraction an opportunity on such a pair must still reach
+    pub low_hit_rate_min_profit: f64,
+}
+
+impl Default for PairStatsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cooldown_seconds: 30,
+            max_cooldown_seconds: 600,
+            window: 20,
+            min_executions: 5,
+            min_hit_rate: 0.3,
+            low_hit_rate_min_profit: 0.02,
+        }
+    }
+}
+
+/// Running under systemd or a container orchestrator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceConfig {
+    /// Send readiness, status and watchdog pings over `NOTIFY_SOCKET` when it is set
+    pub notify: bool,
+    /// Run the doctor checks before reporting ready, and refuse to start when any fails
+    pub self_tests: bool,
+    /// Status file `--healthcheck` reads; empty writes none
+    pub health_file: String,
+    /// How often the status file is refreshed when systemd sets no watchdog
+    pub status_interval_seconds: u64,
+    /// `--healthcheck` fails when the status file is older than this
+    pub healthcheck_stale_seconds: u64,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            notify: true,
+            self_tests: false,
+            health_file: "data/health.json".to_string(),
+            status_interval_seconds: 10,
+            healthcheck_stale_seconds: 30,
+        }
+    }
+}
+
+/// Screening of mints before they are traded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenSafetyConfig {
+    pub enabled: bool,
+    /// Mints traded without screening, e.g. stablecoins whose issuers keep mint and freeze authority
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+    /// Mints never traded
+    #[serde(default)]
+    pub blocklist: Vec<String>,
+    /// Refuse mints whose supply can still be inflated
+    pub reject_mint_authority: bool,
+    /// Refuse mints whose holders' accounts can be frozen
+    pub reject_freeze_authority: bool,
+    /// Refuse Token-2022 mints charging more than this on transfer
+    pub max_transfer_fee_bps: u16,
+    /// Refuse Token-2022 mints with a transfer hook program
+    pub reject_transfer_hook: bool,
+    /// Refuse mints whose Metaplex metadata its update authority can still rewrite
+    #[serde(default)]
+    pub reject_mutable_metadata: bool,
+    /// Refuse mints whose largest account holds more than this fraction of supply; zero disables
+    pub max_top_holder_share: f64,
+    /// How long a screening result is trusted before the mint is fetched again
+    pub cache_ttl_seconds: u64,
+}
+
+impl Default for TokenSafetyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowlist: Vec::new(),
+            blocklist: Vec::new(),
+            reject_mint_authority: true,
+            reject_freeze_authority: true,
+            max_transfer_fee_bps: 100,
+            reject_transfer_hook: true,
+            reject_mutable_metadata: false,
+            max_top_holder_share: 0.8,
+            cache_ttl_seconds: 3600,
+        }
+    }
+}
+
+/// Closing empty token accounts of the owner wallet to recover their rent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RentReclaimConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    /// Mints whose accounts are never closed, empty or not, on top of those of the allowlisted
+    /// pairs and of every pool the venues list
+    #[serde(default)]
+    pub keep_mints: Vec<String>,
+    /// Most accounts closed per run
+    pub max_accounts_per_run: usize,
+    /// Log what would be closed without sending anything; always the case in a dry run
+    pub report_only: bool,
+}
+
+impl Default for RentReclaimConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_seconds: 21600,
+            keep_mints: Vec::new(),
+            max_accounts_per_run: 20,
+            report_only: false,
+        }
+    }
+}
+
+/// Converting leftover inventory into the base asset once a day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlattenConfig {
+    pub enabled: bool,
+    /// Five-field cron expression in UTC for when to flatten
+    pub schedule: String,
+    /// Mint everything is sold into, e.g. wSOL or USDC
+    pub base_mint: String,
+    /// Quote assets held as they are besides the base
+    #[serde(default)]
+    pub keep_mints: Vec<String>,
+    /// Holdings whose exit is quoted below this, in the base asset, are left alone
+    pub min_value: f64,
+    /// Routes moving the price more than this are not taken
+    pub max_price_impact: f64,
+    pub slippage: f64,
+    /// Log what would be sold without sending anything
+    pub report_only: bool,
+}
+
+impl Default for FlattenConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: "55 23 * * *".to_string(),
+            base_mint: "So11111111111111111111111111111111111111112".to_string(),
+            keep_mints: Vec::new(),
+            min_value: 0.01,
+            max_price_impact: 0.02,
+            slippage: 0.01,
+            report_only: false,
+        }
+    }
+}
+
+/// Checking the wallet can pay for a trade before its legs are built
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceConfig {
+    pub enabled: bool,
+    /// Seconds between full RPC reads of the wallet's balances; account subscriptions keep them
+    /// current in between when websocket or geyser ingestion is enabled
+    pub refresh_interval_seconds: u64,
+    /// Lamports the fee payer keeps back for transaction fees, tips and rent; trades leaving less are rejected
+    pub fee_reserve_lamports: u64,
+    /// Shrink a trade to what the wallet holds instead of rejecting it
+    pub allow_downsize: bool,
+    /// Smallest fraction of the intended size still worth trading after downsizing
+    pub min_trade_fraction: f64,
+}
+
+impl Default for BalanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            refresh_interval_seconds: 30,
+            fee_reserve_lamports: 10_000_000,
+            allow_downsize: true,
+            min_trade_fraction: 0.25,
+        }
+    }
+}
+
+/// A strategy loaded at startup alongside the default one built from [arbitrage]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StrategyConfig {
+    /// Unique among the entries; also the strategy's id
+    pub name: String,
+    pub description: String,
+    /// Registered strategy kind that evaluates opportunities for it
+    pub kind: String,
+    pub enabled: bool,
+    /// Fractions, e.g. 0.005 for 0.5%
+    pub min_profit_threshold: f64,
+    pub max_slippage: f64,
+    pub max_price_impact: f64,
+    pub min_liquidity: f64,
+    /// Venues both pools must be on; empty for every venue with pools of its own
+    pub supported_dexes: Vec<DexType>,
+    /// Riskiest opportunity accepted
+    pub risk_tolerance: RiskScore,
+    /// Scale on `submission.trade_size`
+    pub position_size_multiplier: f64,
+    /// Largest trade in the start token; 0 for no cap
+    pub max_trade_amount: f64,
+    pub urgency: Urgency,
+    pub tags: HashMap<String, String>,
+    /// Settings of the kind's own
+    pub parameters: serde_json::Value,
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            description: String::new(),
+            kind: THRESHOLD_STRATEGY_KIND.to_string(),
+            enabled: true,
+            min_profit_threshold: 0.005,
+            max_slippage: 0.01,
+            max_price_impact: 0.005,
+            min_liquidity: 1000.0,
+            supported_dexes: Vec::new(),
+            risk_tolerance: RiskScore::Medium,
+            position_size_multiplier: 1.0,
+            max_trade_amount: 0.0,
+            urgency: Urgency::default(),
+            tags: HashMap::new(),
+            parameters: serde_json::Value::Null,
+        }
+    }
+}
+
+/// Comparing the bot's P&L with holding its starting capital in one asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkConfig {
+    pub enabled: bool,
+    /// Where the baseline recorded at first start is kept
+    pub path: String,
+    /// Capital the bot started with, in quote units
+    pub starting_capital: f64,
+    /// Asset the capital is compared to having held, e.g. wSOL
+    pub asset_mint: String,
+    /// Asset P&L is counted in, e.g. USDC; both need an oracle feed
+    pub quote_mint: String,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "data/benchmark.json".to_string(),
+            starting_capital: 1000.0,
+            asset_mint: "So11111111111111111111111111111111111111112".to_string(),
+            quote_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+        }
+    }
+}
+
+/// A destination notifications can be addressed to by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifierChannelConfig {
+    /// Name alert rules and `alert_channel` settings refer to, e.g. "ops"
+    pub name: String,
+    /// "telegram" or "discord"
+    pub kind: String,
+    #[serde(default)]
+    pub bot_token: String,
+    #[serde(default)]
+    pub chat_id: String,
+    #[serde(default = "default_telegram_api_url")]
+    pub api_url: String,
+    #[serde(default)]
+    pub webhook_url: String,
+}
+
+fn default_telegram_api_url() -> String {
+    "https://api.telegram.org".to_string()
+}
+
+/// Message templates; `{placeholder}`s are filled per notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotifierTemplates {
+    /// {pair} {strategy} {profit} {cost} {signature}
+    pub execution: String,
+    /// As `execution`, plus {reason} {error}
+    pub failure: String,
+    /// {rule} {message} {value}
+    pub alert: String,
+    /// {date} {executions} {confirmed} {failed} {profit} {cost} {net}
+    pub summary: String,
+}
+
+impl Default for NotifierTemplates {
+    fn default() -> Self {
+        Self {
+            execution: "Executed {pair} ({strategy}): profit {profit}, cost {cost}\n{signature}".to_string(),
+            failure: "Failed {pair} ({strategy}): {reason}\n{error}".to_string(),
+            alert: "[{rule}] {message}".to_string(),
+            summary: "Summary for {date}: {executions} executions, {confirmed} confirmed, {failed} failed, net {net} (profit {profit}, cost {cost})".to_string(),
+        }
+    }
+}
+
+/// Telegram and Discord notifications of executions, alerts and daily P&L
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifierConfig {
+    pub enabled: bool,
+    /// Channel executions, failures and summaries go to; alerts go to the channel they name
+    pub channel: String,
+    pub notify_executions: bool,
+    pub notify_failures: bool,
+    /// Five-field cron expression in UTC for the daily P&L summary; empty sends none
+    pub summary_schedule: String,
+    /// Messages per channel per minute; the rest are dropped and counted in the next one. 0 is unlimited
+    pub max_per_minute: usize,
+    pub timeout_seconds: u64,
+    #[serde(default)]
+    pub channels: Vec<NotifierChannelConfig>,
+    #[serde(default)]
+    pub templates: NotifierTemplates,
+}
+
+impl Default for NotifierConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            channel: "ops".to_string(),
+            notify_executions: true,
+            notify_failures: true,
+            summary_schedule: "0 0 * * *".to_string(),
+            max_per_minute: 20,
+            timeout_seconds: 10,
+            channels: Vec::new(),
+            templates: NotifierTemplates::default(),
+        }
+    }
+}
+
 impl AppConfig {
     pub fn load() -> Result<Self, config::ConfigError> {
-        let settings = config::Config::builder()
-            .add_source(config::File::with_name("config/default"))
-            .add_source(config::File::with_name("config/local").required(false))
-            .add_source(config::Environment::with_prefix("ARBITRAGE_BOT"))
+        const DEFAULT_PATH: &str = "config/default.toml";
+        const LOCAL_PATH: &str = "config/local.toml";
+
+        // Refuse tampered files before anything in them is read. Only these exact files are
+        // loaded, so no unverified sibling in another format can stand in for them.
+        let verifier = integrity::ConfigVerifier::from_env().map_err(|e| config::ConfigError::Message(e.to_string()))?;
+        for path in [DEFAULT_PATH, LOCAL_PATH] {
+            verifier
+                .verify_optional(std::path::Path::new(path))
+                .map_err(|e| config::ConfigError::Message(e.to_string()))?;
+        }
+        verifier
+            .check_env_overrides(std::env::vars())
+            .map_err(|e| config::ConfigError::Message(e.to_string()))?;
+
+        let settings = Config::builder()
+            .add_source(File::new(DEFAULT_PATH, config::FileFormat::Toml))
+            .add_source(File::new(LOCAL_PATH, config::FileFormat::Toml).required(false))
+            .add_source(Environment::with_prefix(integrity::ENV_OVERRIDE_PREFIX))
             .build()?;
 
         settings.try_deserialize()
     }
 
+    /// The bundled config/default.toml, independent of the working directory and environment
+    #[cfg(test)]
+    pub(crate) fn bundled_default() -> Self {
+        // `environment` sits under [logging] when default.toml is parsed as plain TOML
+        let content = format!("environment = \"test\"\n{}", include_str!("../../config/default.toml"));
+        toml::from_str(&content).expect("bundled default.toml is valid")
+    }
+
     pub fn validate(&self) -> Result<()> {
         // Validate required fields
         if self.solana.rpc_url.is_empty() {
@@ -94,9 +1355,33 @@ impl AppConfig {
         if self.arbitrage.min_profit_threshold <= 0.0 {
             anyhow::bail!("Min profit threshold must be positive");
         }
+        for rule in &self.alerts.rules {
+            crate::services::alert_rules::AlertRule::from_config(rule)?;
+        }
+        crate::services::codec::CodecKind::parse(&self.journal.codec).context("Invalid [journal] codec")?;
+        self.strategy_definitions()?;
         Ok(())
     }
 
+    /// Strategies defined in [[strategies]], checked against the thresholds every kind shares.
+    /// Errors name the offending entry.
+    pub fn strategy_definitions(&self) -> Result<Vec<ArbitrageStrategy>> {
+        let mut names = HashSet::new();
+        self.strategies
+            .iter()
+            .zip(1..)
+            .map(|(entry, position)| {
+                let context = || format!("Invalid [[strategies]] entry {} ('{}')", position, entry.name);
+                if !names.insert(entry.name.as_str()) {
+                    return Err(anyhow::anyhow!("name is already used by an earlier entry")).with_context(context);
+                }
+                let strategy = ArbitrageStrategy::from_config(entry).with_context(context)?;
+                Strategy::validate(&strategy).with_context(context)?;
+                Ok(strategy)
+            })
+            .collect()
+    }
+
     pub fn get_memory_store_config(&self) -> MemoryStoreConfig {
         self.memory_store.clone()
     }
@@ -114,6 +1399,186 @@ impl Default for MemoryStoreConfig {
             max_executions: 50000,
             cleanup_interval_seconds: 300, // 5 minutes
             data_retention_days: 7,
+            snapshot_path: String::new(),
+            snapshot_interval_seconds: default_snapshot_interval_seconds(),
+        }
+    }
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            evaluation_interval_seconds: 30,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            provider: "none".to_string(),
+            api_key: String::new(),
+            rpc_url: String::new(),
+            timeout_seconds: 5,
+        }
+    }
+}
+
+impl Default for SubmissionConfig {
+    fn default() -> Self {
+        Self {
+            leader_aware: false,
+            jito_validators: Vec::new(),
+            max_delay_slots: 1,
+            non_jito_fee_multiplier: 1.5,
+            rebroadcast_rpc_urls: Vec::new(),
+            rebroadcast_interval_ms: 500,
+            rebroadcast_timeout_seconds: 90,
+            bundles_enabled: false,
+            bundle_tip_lamports: 10_000,
+            bundle_status_timeout_seconds: 30,
+            trade_size: 1.0,
+            simulate_before_submit: true,
+            bundle_simulation_url: String::new(),
+            compute_unit_margin: 1.2,
+            create_token_accounts: true,
+            wrap_sol: true,
+            dry_run: false,
+        }
+    }
+}
+
+impl Default for BlacklistConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            failure_threshold: 3,
+            window_seconds: 3600,
+            ttl_seconds: 86400,
+            path: String::new(),
+        }
+    }
+}
+
+impl Default for PositionWatcherConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_seconds: 300,
+            quote_mints: vec![
+                "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                "So11111111111111111111111111111111111111112".to_string(),
+            ],
+            wallets: Vec::new(),
+            max_exit_price_impact: 0.5,
+        }
+    }
+}
+
+impl Default for DexHealthConfig {
+    fn default() -> Self {
+        Self {
+            probe_interval_seconds: 15,
+            timeout_multiplier: 3.0,
+            min_timeout_ms: 200,
+            max_timeout_ms: 10000,
+            reference_latency_ms: 250,
+        }
+    }
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "data/events.journal".to_string(),
+            capacity_mb: 64,
+            flush_interval_ms: 1000,
+            codec: default_journal_codec(),
+        }
+    }
+}
+
+impl Default for PrecisionConfig {
+    fn default() -> Self {
+        Self {
+            input_rounding: RoundingMode::Floor,
+            min_out_rounding: RoundingMode::Ceil,
+        }
+    }
+}
+
+impl Default for TriangularConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_hops: 3,
+            start_mints: vec![
+                "So11111111111111111111111111111111111111112".to_string(),
+                "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            ],
+            input_amount: 1.0,
+        }
+    }
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 60_000,
+            alert_channel: "ops".to_string(),
+            policies: HashMap::new(),
+        }
+    }
+}
+
+impl Default for JupiterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_url: "https://lite-api.jup.ag/swap/v1".to_string(),
+            api_key: String::new(),
+            timeout_seconds: 5,
+            rate_limit: 60,
+            max_retries: 3,
+            route_sell_leg: true,
+        }
+    }
+}
+
+impl Default for ControlApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:8088".to_string(),
+            quote_timeout_ms: 3000,
+            max_strategy_change_factor: 2.0,
+        }
+    }
+}
+
+impl Default for IdlConfig {
+    fn default() -> Self {
+        Self {
+            dir: "idl".to_string(),
+            fetch_on_chain: false,
+            programs: Vec::new(),
+        }
+    }
+}
+
+impl ScannerConfig {
+    /// Whether the pair is allowed, regardless of mint order
+    pub fn is_pair_allowed(&self, mint_a: &str, mint_b: &str) -> bool {
+        if self.pair_allowlist.is_empty() {
+            return true;
         }
+        self.pair_allowlist.iter().any(|pair| match pair.split_once('/') {
+            Some((a, b)) => (a == mint_a && b == mint_b) || (a == mint_b && b == mint_a),
+            None => false,
+        })
     }
 }
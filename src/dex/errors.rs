@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::FailureReason;
+
+/// Custom error codes of one on-chain program
+struct ProgramErrors {
+    program: &'static str,
+    program_ids: &'static [&'static str],
+    errors: &'static [(u32, &'static str)],
+}
+
+const RAYDIUM_AMM: ProgramErrors = ProgramErrors {
+    program: "Raydium AMM",
+    program_ids: &["675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8"],
+    errors: &[
+        (0, "AlreadyInUse"),
+        (1, "InvalidProgramAddress"),
+        (2, "ExpectedMint"),
+        (3, "ExpectedAccount"),
+        (4, "InvalidCoinVault"),
+        (5, "InvalidPCVault"),
+        (6, "InvalidTokenLP"),
+        (7, "InvalidDestTokenCoin"),
+        (8, "InvalidDestTokenPC"),
+        (9, "InvalidPoolMint"),
+        (10, "InvalidOpenOrders"),
+        (11, "InvalidSerumMarket"),
+        (12, "InvalidMarketProgram"),
+        (13, "InvalidTargetOrders"),
+        (14, "AccountNeedWriteable"),
+        (15, "AccountNeedReadOnly"),
+        (16, "InvalidCoinMint"),
+        (17, "InvalidPCMint"),
+        (18, "InvalidOwner"),
+        (19, "InvalidSupply"),
+        (20, "InvalidDelegate"),
+        (21, "InvalidSignAccount"),
+        (22, "InvalidStatus"),
+        (23, "InvalidInstruction"),
+        (24, "WrongAccountsNumber"),
+        (29, "InvalidInput"),
+        (30, "ExceededSlippage"),
+        (31, "CalculationExRateFailure"),
+        (32, "CheckedSubOverflow"),
+        (33, "CheckedAddOverflow"),
+        (34, "CheckedMulOverflow"),
+        (35, "CheckedDivOverflow"),
+        (36, "CheckedEmptyFunds"),
+        (40, "InsufficientFunds"),
+    ],
+};
+
+const WHIRLPOOL: ProgramErrors = ProgramErrors {
+    program: "Orca Whirlpool",
+    program_ids: &["whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"],
+    errors: &[
+        (6000, "InvalidEnum"),
+        (6001, "InvalidStartTick"),
+        (6002, "TickArrayExistInPool"),
+        (6003, "TickArrayIndexOutofBounds"),
+        (6004, "InvalidTickSpacing"),
+        (6005, "ClosePositionNotEmpty"),
+        (6006, "DivideByZero"),
+        (6007, "NumberCastError"),
+        (6008, "NumberDownCastError"),
+        (6009, "TickNotFound"),
+        (6010, "InvalidTickIndex"),
+        (6011, "SqrtPriceOutOfBounds"),
+        (6012, "LiquidityZero"),
+        (6013, "LiquidityTooHigh"),
+        (6014, "LiquidityOverflow"),
+        (6015, "LiquidityUnderflow"),
+        (6016, "LiquidityNetError"),
+        (6017, "TokenMaxExceeded"),
+        (6018, "TokenMinSubceeded"),
+        (6019, "MissingOrInvalidDelegate"),
+        (6020, "InvalidPositionTokenAmount"),
+        (6021, "InvalidTimestampConversion"),
+        (6022, "InvalidTimestamp"),
+        (6023, "InvalidTickArraySequence"),
+        (6024, "InvalidTokenMintOrder"),
+        (6025, "RewardNotInitialized"),
+        (6026, "InvalidRewardIndex"),
+        (6027, "RewardVaultAmountInsufficient"),
+        (6028, "FeeRateMaxExceeded"),
+        (6029, "ProtocolFeeRateMaxExceeded"),
+        (6030, "MultiplicationShiftRightOverflow"),
+        (6031, "MulDivOverflow"),
+        (6032, "MulDivInvalidInput"),
+        (6033, "MultiplicationOverflow"),
+        (6034, "InvalidSqrtPriceLimitDirection"),
+        (6035, "ZeroTradableAmount"),
+        (6036, "AmountOutBelowMinimum"),
+        (6037, "AmountInAboveMaximum"),
+        (6038, "TickArraySequenceInvalidIndex"),
+        (6039, "AmountCalcOverflow"),
+        (6040, "AmountRemainingOverflow"),
+    ],
+};
+
+const METEORA_DLMM: ProgramErrors = ProgramErrors {
+    program: "Meteora DLMM",
+    program_ids: &["LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo"],
+    errors: &[
+        (6000, "InvalidStartBinIndex"),
+        (6001, "InvalidBinId"),
+        (6002, "InvalidInput"),
+        (6003, "ExceededAmountSlippageTolerance"),
+        (6004, "ExceededBinSlippageTolerance"),
+        (6005, "CompositionFactorFlawed"),
+        (6006, "NonPresetBinStep"),
+        (6007, "ZeroLiquidity"),
+        (6008, "InvalidPosition"),
+        (6009, "BinArrayNotFound"),
+        (6010, "InvalidTokenMint"),
+        (6011, "InvalidAccountForSingleDeposit"),
+        (6012, "PairInsufficientLiquidity"),
+    ],
+};
+
+/// SPL Token; Token-2022 shares the same codes for the base instructions
+const TOKEN: ProgramErrors = ProgramErrors {
+    program: "SPL Token",
+    program_ids: &[
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+    ],
+    errors: &[
+        (0, "NotRentExempt"),
+        (1, "InsufficientFunds"),
+        (2, "InvalidMint"),
+        (3, "MintMismatch"),
+        (4, "OwnerMismatch"),
+        (5, "FixedSupply"),
+        (6, "AlreadyInUse"),
+        (7, "InvalidNumberOfProvidedSigners"),
+        (8, "InvalidNumberOfRequiredSigners"),
+        (9, "UninitializedState"),
+        (10, "NativeNotSupported"),
+        (11, "NonNativeHasBalance"),
+        (12, "InvalidInstruction"),
+        (13, "InvalidState"),
+        (14, "Overflow"),
+        (15, "AuthorityTypeNotSupported"),
+        (16, "MintCannotFreeze"),
+        (17, "AccountFrozen"),
+        (18, "MintDecimalsMismatch"),
+        (19, "NonNativeNotSupported"),
+    ],
+};
+
+const PROGRAMS: &[ProgramErrors] = &[RAYDIUM_AMM, WHIRLPOOL, METEORA_DLMM, TOKEN];
+
+/// Error names that mean the quoted price moved past the allowed slippage
+const SLIPPAGE_ERRORS: &[&str] = &[
+    "ExceededSlippage",
+    "TokenMaxExceeded",
+    "TokenMinSubceeded",
+    "AmountOutBelowMinimum",
+    "AmountInAboveMaximum",
+    "ExceededAmountSlippageTolerance",
+    "ExceededBinSlippageTolerance",
+];
+
+const FAILED_MARKER: &str = " failed: custom program error: 0x";
+
+/// A custom program error resolved to its name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramErrorInfo {
+    pub program: String,
+    pub program_id: Option<String>,
+    pub code: u32,
+    pub name: String,
+}
+
+impl ProgramErrorInfo {
+    /// Failure reason implied by the error, if more specific than a bare program error
+    pub fn failure_reason(&self) -> Option<FailureReason> {
+        if SLIPPAGE_ERRORS.contains(&self.name.as_str()) {
+            Some(FailureReason::SlippageExceeded)
+        } else if self.name == "InsufficientFunds" {
+            Some(FailureReason::InsufficientFunds)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for ProgramErrorInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} (0x{:x})", self.program, self.name, self.code)
+    }
+}
+
+/// Look up a custom error code for a known program
+pub fn decode_program_error(program_id: &str, code: u32) -> Option<ProgramErrorInfo> {
+    let program = PROGRAMS.iter().find(|p| p.program_ids.contains(&program_id))?;
+    lookup(program, code).map(|mut info| {
+        info.program_id = Some(program_id.to_string());
+        info
+    })
+}
+
+/// Decode the failing custom program error from transaction logs, falling back to the error
+/// message alone when the code belongs to exactly one known program
+pub fn decode_failure(error: &str, logs: &[String]) -> Option<ProgramErrorInfo> {
+    // "Program <id> failed: custom program error: 0x1771"
+    for line in logs.iter().map(String::as_str).chain(std::iter::once(error)) {
+        if let Some(at) = line.find(FAILED_MARKER) {
+            let program_id = line[..at].rsplit(' ').next().unwrap_or_default();
+            let decoded = crate::arbitrage::outcome::custom_error_code(line)
+                .and_then(|code| decode_program_error(program_id, code));
+            if decoded.is_some() {
+                return decoded;
+            }
+        }
+    }
+
+    let code = std::iter::once(error)
+        .chain(logs.iter().map(String::as_str))
+        .find_map(crate::arbitrage::outcome::custom_error_code)?;
+    let mut matches = PROGRAMS.iter().filter_map(|program| lookup(program, code));
+    match (matches.next(), matches.next()) {
+        (Some(info), None) => Some(info),
+        _ => None,
+    }
+}
+
+fn lookup(program: &ProgramErrors, code: u32) -> Option<ProgramErrorInfo> {
+    let (_, name) = program.errors.iter().find(|(c, _)| *c == code)?;
+    Some(ProgramErrorInfo {
+        program: program.program.to_string(),
+        program_id: None,
+        code,
+        name: name.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_failure_from_logs() {
+        let logs = vec![
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc invoke [1]".to_string(),
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc failed: custom program error: 0x1794".to_string(),
+        ];
+        let info = decode_failure("Transaction failed", &logs).unwrap();
+        assert_eq!(info.program, "Orca Whirlpool");
+        assert_eq!(info.name, "AmountOutBelowMinimum");
+        assert_eq!(info.failure_reason(), Some(FailureReason::SlippageExceeded));
+
+        // 0x1e only exists in the Raydium table
+        let info = decode_failure("Error processing Instruction 1: custom program error: 0x1e", &[]).unwrap();
+        assert_eq!(info.name, "ExceededSlippage");
+        // 0x1771 is defined by both Whirlpool and Meteora, so needs the program id
+        assert_eq!(decode_failure("custom program error: 0x1771", &[]), None);
+    }
+}
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use anyhow::Result;
-use crate::dex::{DexInterface, DexConnectionConfig, DexType};
+use tracing::warn;
+use crate::dex::{DexInterface, DexConnectionConfig, DexType, SwapSigner};
+use crate::services::account_subscriber::AccountSubscriber;
 
 pub struct DexFactory {
     dex_instances: HashMap<DexType, Box<dyn DexInterface>>,
@@ -17,85 +19,211 @@ impl DexFactory {
     pub async fn create_all_dexes(config: &crate::config::AppConfig) -> Result<HashMap<DexType, Box<dyn DexInterface>>> {
         let mut factory = Self::new();
         
+        // Swaps are only signed when a wallet is configured
+        let signer = SwapSigner::from_config(config).unwrap_or_else(|e| {
+            warn!("Swap signing disabled: {}", e);
+            None
+        });
+        // Pool updates are streamed when websocket subscriptions are enabled
+        let subscriber = AccountSubscriber::spawn(config).unwrap_or_else(|e| {
+            warn!("Pool update subscriptions disabled: {}", e);
+            None
+        });
+        
         // Create Raydium DEX
-        if let Ok(raydium) = factory.create_raydium_dex(&config.dex.raydium).await {
+        if let Ok(raydium) = factory.create_raydium_dex(&config.dex.raydium, signer.clone(), subscriber.clone()).await {
             factory.dex_instances.insert(DexType::Raydium, raydium);
         }
         
         // Create Meteora DEX
-        if let Ok(meteora) = factory.create_meteora_dex(&config.dex.meteora).await {
+        if let Ok(meteora) = factory.create_meteora_dex(&config.dex.meteora, subscriber.clone()).await {
             factory.dex_instances.insert(DexType::Meteora, meteora);
         }
         
         // Create Whirlpool DEX
-        if let Ok(whirlpool) = factory.create_whirlpool_dex(&config.dex.whirlpool).await {
+        if let Ok(whirlpool) = factory.create_whirlpool_dex(&config.dex.whirlpool, subscriber.clone()).await {
             factory.dex_instances.insert(DexType::Whirlpool, whirlpool);
         }
         
         // Create Pump DEX
-        if let Ok(pump) = factory.create_pump_dex(&config.dex.pump).await {
+        if let Ok(pump) = factory.create_pump_dex(&config.dex.pump, subscriber.clone()).await {
             factory.dex_instances.insert(DexType::Pump, pump);
         }
+
+        // Create Orca legacy DEX, when configured
+        if !config.dex.orca_legacy.base_url.is_empty() {
+            if let Ok(orca) = factory.create_orca_legacy_dex(&config.dex.orca_legacy, subscriber).await {
+                factory.dex_instances.insert(DexType::OrcaLegacy, orca);
+            }
+        }
+
+        // Create Phoenix order book DEX, when configured
+        if !config.dex.phoenix.base_url.is_empty() {
+            if let Ok(phoenix) = factory.create_phoenix_dex(&config.dex.phoenix).await {
+                factory.dex_instances.insert(DexType::Phoenix, phoenix);
+            }
+        }
+
+        // Create Jupiter aggregator, when enabled
+        if config.dex.jupiter.enabled {
+            match factory.create_jupiter_dex(&config.dex.jupiter, signer, config.submission.wrap_sol).await {
+                Ok(jupiter) => {
+                    factory.dex_instances.insert(DexType::Jupiter, jupiter);
+                }
+                Err(e) => warn!("Jupiter disabled: {}", e),
+            }
+        }
         
         Ok(factory.dex_instances)
     }
 
     /// Create Raydium DEX instance
-    async fn create_raydium_dex(&self, config: &crate::config::DexEndpointConfig) -> Result<Box<dyn DexInterface>> {
+    async fn create_raydium_dex(
+        &self,
+        config: &crate::config::DexEndpointConfig,
+        signer: Option<SwapSigner>,
+        subscriber: Option<AccountSubscriber>,
+    ) -> Result<Box<dyn DexInterface>> {
         let dex_config = DexConnectionConfig {
             base_url: config.base_url.clone(),
             api_key: Some(config.api_key.clone()),
             timeout_seconds: config.timeout_seconds,
-            max_retries: 3,
+            max_retries: config.max_retries,
             rate_limit: config.rate_limit,
         };
         
-        let raydium_dex = crate::dex::raydium::RaydiumDex::new(dex_config)?;
+        let mut raydium_dex = crate::dex::raydium::RaydiumDex::new(dex_config)?;
+        if let Some(signer) = signer {
+            raydium_dex = raydium_dex.with_signer(signer);
+        }
+        if let Some(subscriber) = subscriber {
+            raydium_dex = raydium_dex.with_subscriber(subscriber);
+        }
         Ok(Box::new(raydium_dex))
     }
 
     /// Create Meteora DEX instance
-    async fn create_meteora_dex(&self, config: &crate::config::DexEndpointConfig) -> Result<Box<dyn DexInterface>> {
+    async fn create_meteora_dex(
+        &self,
+        config: &crate::config::DexEndpointConfig,
+        subscriber: Option<AccountSubscriber>,
+    ) -> Result<Box<dyn DexInterface>> {
         let dex_config = DexConnectionConfig {
             base_url: config.base_url.clone(),
             api_key: Some(config.api_key.clone()),
             timeout_seconds: config.timeout_seconds,
-            max_retries: 3,
+            max_retries: config.max_retries,
             rate_limit: config.rate_limit,
         };
         
-        let meteora_dex = crate::dex::meteora::MeteoraDex::new(dex_config)?;
+        let mut meteora_dex = crate::dex::meteora::MeteoraDex::new(dex_config)?;
+        if let Some(subscriber) = subscriber {
+            meteora_dex = meteora_dex.with_subscriber(subscriber);
+        }
         Ok(Box::new(meteora_dex))
     }
 
     /// Create Whirlpool DEX instance
-    async fn create_whirlpool_dex(&self, config: &crate::config::DexEndpointConfig) -> Result<Box<dyn DexInterface>> {
+    async fn create_whirlpool_dex(
+        &self,
+        config: &crate::config::DexEndpointConfig,
+        subscriber: Option<AccountSubscriber>,
+    ) -> Result<Box<dyn DexInterface>> {
         let dex_config = DexConnectionConfig {
             base_url: config.base_url.clone(),
             api_key: Some(config.api_key.clone()),
             timeout_seconds: config.timeout_seconds,
-            max_retries: 3,
+            max_retries: config.max_retries,
             rate_limit: config.rate_limit,
         };
         
-        let whirlpool_dex = crate::dex::whirlpool::WhirlpoolDex::new(dex_config)?;
+        let mut whirlpool_dex = crate::dex::whirlpool::WhirlpoolDex::new(dex_config)?;
+        if let Some(subscriber) = subscriber {
+            whirlpool_dex = whirlpool_dex.with_subscriber(subscriber);
+        }
         Ok(Box::new(whirlpool_dex))
     }
 
     /// Create Pump DEX instance
-    async fn create_pump_dex(&self, config: &crate::config::DexEndpointConfig) -> Result<Box<dyn DexInterface>> {
+    async fn create_pump_dex(
+        &self,
+        config: &crate::config::DexEndpointConfig,
+        subscriber: Option<AccountSubscriber>,
+    ) -> Result<Box<dyn DexInterface>> {
         let dex_config = DexConnectionConfig {
             base_url: config.base_url.clone(),
             api_key: Some(config.api_key.clone()),
             timeout_seconds: config.timeout_seconds,
-            max_retries: 3,
+            max_retries: config.max_retries,
             rate_limit: config.rate_limit,
         };
         
-        let pump_dex = crate::dex::pump::PumpDex::new(dex_config)?;
+        let mut pump_dex = crate::dex::pump::PumpDex::new(dex_config)?;
+        if let Some(subscriber) = subscriber {
+            pump_dex = pump_dex.with_subscriber(subscriber);
+        }
         Ok(Box::new(pump_dex))
     }
 
+    /// Create Orca legacy DEX instance
+    async fn create_orca_legacy_dex(
+        &self,
+        config: &crate::config::DexEndpointConfig,
+        subscriber: Option<AccountSubscriber>,
+    ) -> Result<Box<dyn DexInterface>> {
+        let dex_config = DexConnectionConfig {
+            base_url: config.base_url.clone(),
+            api_key: Some(config.api_key.clone()),
+            timeout_seconds: config.timeout_seconds,
+            max_retries: config.max_retries,
+            rate_limit: config.rate_limit,
+        };
+
+        let mut orca_dex = crate::dex::orca::OrcaLegacyDex::new(dex_config)?;
+        if let Some(subscriber) = subscriber {
+            orca_dex = orca_dex.with_subscriber(subscriber);
+        }
+        Ok(Box::new(orca_dex))
+    }
+
+    /// Create Phoenix DEX instance
+    async fn create_phoenix_dex(
+        &self,
+        config: &crate::config::DexEndpointConfig,
+    ) -> Result<Box<dyn DexInterface>> {
+        let dex_config = DexConnectionConfig {
+            base_url: config.base_url.clone(),
+            api_key: Some(config.api_key.clone()),
+            timeout_seconds: config.timeout_seconds,
+            max_retries: config.max_retries,
+            rate_limit: config.rate_limit,
+        };
+
+        Ok(Box::new(crate::dex::phoenix::PhoenixDex::new(dex_config)?))
+    }
+
+    /// Create Jupiter aggregator instance
+    async fn create_jupiter_dex(
+        &self,
+        config: &crate::config::JupiterConfig,
+        signer: Option<SwapSigner>,
+        wrapped_sol: bool,
+    ) -> Result<Box<dyn DexInterface>> {
+        let dex_config = DexConnectionConfig {
+            base_url: config.base_url.clone(),
+            api_key: Some(config.api_key.clone()),
+            timeout_seconds: config.timeout_seconds,
+            max_retries: config.max_retries,
+            rate_limit: config.rate_limit,
+        };
+
+        let mut jupiter_dex = crate::dex::jupiter::JupiterDex::new(dex_config)?.with_wrapped_sol(wrapped_sol);
+        if let Some(signer) = signer {
+            jupiter_dex = jupiter_dex.with_signer(signer);
+        }
+        Ok(Box::new(jupiter_dex))
+    }
+
     /// Get DEX instance of a specific type
     pub fn get_dex(&self, dex_type: &DexType) -> Option<&Box<dyn DexInterface>> {
         self.dex_instances.get(dex_type)
@@ -0,0 +1,254 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::config::DexHealthConfig;
+use crate::dex::{DexConnectionStatus, DexHealthCheck, DexInterface, DexType};
+
+/// Number of latency samples kept per DEX
+const MAX_SAMPLES: usize = 50;
+
+/// Rolling request statistics for one DEX
+#[derive(Debug, Clone, Default)]
+struct DexHealthStats {
+    latencies_ms: VecDeque<u64>,
+    outcomes: VecDeque<bool>,
+    error_count: u64,
+    last_successful_request: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+impl DexHealthStats {
+    fn record(&mut self, latency: Duration, success: bool, error: Option<String>) {
+        if self.latencies_ms.len() == MAX_SAMPLES {
+            self.latencies_ms.pop_front();
+        }
+        if self.outcomes.len() == MAX_SAMPLES {
+            self.outcomes.pop_front();
+        }
+        self.latencies_ms.push_back(latency.as_millis() as u64);
+        self.outcomes.push_back(success);
+
+        if success {
+            self.last_successful_request = Some(Utc::now());
+            self.last_error = None;
+        } else {
+            self.error_count += 1;
+            self.last_error = error;
+        }
+    }
+
+    fn percentile_ms(&self, percentile: f64) -> Option<u64> {
+        if self.latencies_ms.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = self.latencies_ms.iter().copied().collect();
+        sorted.sort_unstable();
+        let index = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+        sorted.get(index).copied()
+    }
+
+    fn success_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 1.0;
+        }
+        self.outcomes.iter().filter(|ok| **ok).count() as f64 / self.outcomes.len() as f64
+    }
+}
+
+/// Measured per-DEX latency and success rate, used for request budgets and venue weighting
+#[derive(Clone)]
+pub struct DexHealthTracker {
+    config: DexHealthConfig,
+    stats: Arc<RwLock<HashMap<DexType, DexHealthStats>>>,
+}
+
+impl DexHealthTracker {
+    pub fn new(config: DexHealthConfig) -> Self {
+        Self {
+            config,
+            stats: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Record the outcome of a request
+    pub async fn record(&self, dex_type: &DexType, latency: Duration, result: Result<(), String>) {
+        let mut stats = self.stats.write().await;
+        let entry = stats.entry(dex_type.clone()).or_default();
+        match result {
+            Ok(()) => entry.record(latency, true, None),
+            Err(e) => entry.record(latency, false, Some(e)),
+        }
+    }
+
+    /// Current health of a DEX
+    pub async fn health(&self, dex_type: &DexType) -> DexHealthCheck {
+        let stats = self.stats.read().await;
+        let Some(entry) = stats.get(dex_type) else {
+            return DexHealthCheck {
+                status: DexConnectionStatus::Connecting,
+                response_time_ms: 0,
+                last_successful_request: None,
+                error_count: 0,
+                success_rate: 1.0,
+            };
+        };
+
+        let status = match (&entry.last_error, entry.outcomes.back()) {
+            (Some(error), Some(false)) => DexConnectionStatus::Error(error.clone()),
+            (None, Some(false)) => DexConnectionStatus::Disconnected,
+            _ => DexConnectionStatus::Connected,
+        };
+
+        DexHealthCheck {
+            status,
+            response_time_ms: entry.percentile_ms(0.5).unwrap_or(0),
+            last_successful_request: entry.last_successful_request,
+            error_count: entry.error_count,
+            success_rate: entry.success_rate(),
+        }
+    }
+
+    /// Request timeout derived from the measured p95 latency
+    pub async fn timeout_for(&self, dex_type: &DexType) -> Duration {
+        let stats = self.stats.read().await;
+        let budget_ms = match stats.get(dex_type).and_then(|s| s.percentile_ms(0.95)) {
+            Some(p95) => (p95 as f64 * self.config.timeout_multiplier) as u64,
+            None => self.config.max_timeout_ms,
+        };
+        Duration::from_millis(budget_ms.clamp(self.config.min_timeout_ms, self.config.max_timeout_ms))
+    }
+
+    /// Venue weight in (0, 1]: lower for slow or unreliable DEXes
+    pub async fn weight(&self, dex_type: &DexType) -> f64 {
+        let stats = self.stats.read().await;
+        let Some(entry) = stats.get(dex_type) else {
+            return 1.0;
+        };
+        let median_ms = entry.percentile_ms(0.5).unwrap_or(0) as f64;
+        let reference_ms = self.config.reference_latency_ms.max(1) as f64;
+        entry.success_rate() / (1.0 + median_ms / reference_ms)
+    }
+
+    /// Weights for all tracked DEXes
+    pub async fn weights(&self) -> HashMap<DexType, f64> {
+        let dex_types: Vec<DexType> = self.stats.read().await.keys().cloned().collect();
+        let mut weights = HashMap::new();
+        for dex_type in dex_types {
+            let weight = self.weight(&dex_type).await;
+            weights.insert(dex_type, weight);
+        }
+        weights
+    }
+
+    /// Run a request under the DEX's timeout budget and record its outcome
+    pub async fn measure<T, F>(&self, dex_type: &DexType, request: F) -> Result<T>
+    where
+        F: std::future::Future<Output = Result<T>>,
+    {
+        let budget = self.timeout_for(dex_type).await;
+        let started = Instant::now();
+        let result = match tokio::time::timeout(budget, request).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow::anyhow!("{} request exceeded {}ms budget", dex_type, budget.as_millis())),
+        };
+
+        let outcome = match &result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        };
+        self.record(dex_type, started.elapsed(), outcome).await;
+        result
+    }
+}
+
+/// Background prober that keeps per-DEX health fresh even when the scanner is idle
+pub struct DexHealthProber {
+    dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
+    tracker: DexHealthTracker,
+    interval: Duration,
+}
+
+impl DexHealthProber {
+    pub fn new(
+        dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>,
+        tracker: DexHealthTracker,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            dex_instances,
+            tracker,
+            interval,
+        }
+    }
+
+    /// Start the probe loop
+    pub async fn start(self) -> Result<()> {
+        info!("Starting DEX health prober for {} DEXes", self.dex_instances.len());
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+            self.probe_once().await;
+        }
+    }
+
+    /// Probe every DEX once
+    pub async fn probe_once(&self) {
+        for (dex_type, dex_instance) in self.dex_instances.iter() {
+            let result = self
+                .tracker
+                .measure(dex_type, async {
+                    match dex_instance.is_connected().await? {
+                        true => Ok(()),
+                        false => Err(anyhow::anyhow!("health endpoint unavailable")),
+                    }
+                })
+                .await;
+
+            match result {
+                Ok(()) => debug!("{} health probe ok", dex_instance.get_name()),
+                Err(e) => warn!("{} health probe failed: {}", dex_instance.get_name(), e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> DexHealthConfig {
+        DexHealthConfig {
+            probe_interval_seconds: 15,
+            timeout_multiplier: 2.0,
+            min_timeout_ms: 100,
+            max_timeout_ms: 5000,
+            reference_latency_ms: 100,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_budget_and_weight() {
+        let tracker = DexHealthTracker::new(config());
+        assert_eq!(tracker.timeout_for(&DexType::Raydium).await, Duration::from_millis(5000));
+
+        for _ in 0..10 {
+            tracker.record(&DexType::Raydium, Duration::from_millis(100), Ok(())).await;
+            tracker.record(&DexType::Meteora, Duration::from_millis(400), Ok(())).await;
+        }
+        tracker.record(&DexType::Meteora, Duration::from_millis(400), Err("boom".to_string())).await;
+
+        assert_eq!(tracker.timeout_for(&DexType::Raydium).await, Duration::from_millis(200));
+        assert_eq!(tracker.timeout_for(&DexType::Meteora).await, Duration::from_millis(800));
+        assert!(tracker.weight(&DexType::Raydium).await > tracker.weight(&DexType::Meteora).await);
+
+        let health = tracker.health(&DexType::Meteora).await;
+        assert_eq!(health.error_count, 1);
+        assert_eq!(health.status, DexConnectionStatus::Error("boom".to_string()));
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use tokio::time::{timeout, Duration};
+
+use crate::dex::interface::{is_transient_error, DexConnectionConfig, DexError};
+use crate::dex::rate_limit::DexRateLimiter;
+use crate::utils::retry::RetryPolicy;
+
+/// JSON API client shared by the pool venue adapters.
+///
+/// Every call waits its turn with the venue's rate limiter, is cut off at the request timeout and
+/// retried on transient failures; non-success statuses become `DexError`s.
+pub struct DexHttpClient {
+    client: Client,
+    rate_limiter: DexRateLimiter,
+    retry: RetryPolicy,
+    base_url: String,
+    timeout: Duration,
+}
+
+impl DexHttpClient {
+    /// Client for the venue `name` described by `config`
+    pub fn new(name: &str, config: &DexConnectionConfig) -> Result<Self> {
+        let timeout = Duration::from_secs(config.timeout_seconds);
+        Ok(Self {
+            client: Client::builder().timeout(timeout).build()?,
+            rate_limiter: DexRateLimiter::from_config(name, config),
+            retry: RetryPolicy::new(config.max_retries),
+            base_url: config.base_url.clone(),
+            timeout,
+        })
+    }
+
+    /// GET `endpoint` under the base URL and parse the JSON response
+    pub async fn get<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
+        let url = format!("{}{}", self.base_url, endpoint);
+        self.retry.run(|| async {
+            self.rate_limiter.acquire().await?;
+            let response = timeout(self.timeout, self.client.get(&url).send()).await??;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                return Err(DexError::from_status(status, response.text().await?).into());
+            }
+
+            Ok(response.json().await?)
+        }, is_transient_error).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Json, Router};
+
+    #[tokio::test]
+    async fn test_get_parses_json_and_maps_failed_statuses() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route("/pools", get(|| async { Json(serde_json::json!([{ "id": "pool" }])) }))
+            .route("/missing", get(|| async { (StatusCode::NOT_FOUND, "no such pool") }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let config = DexConnectionConfig { base_url, api_key: None, timeout_seconds: 5, max_retries: 0, rate_limit: 0 };
+        let http = DexHttpClient::new("Test", &config).unwrap();
+        let pools: Vec<serde_json::Value> = http.get("/pools").await.unwrap();
+        assert_eq!(pools[0]["id"], "pool");
+
+        let error = http.get::<serde_json::Value>("/missing").await.unwrap_err();
+        assert!(error.downcast_ref::<DexError>().is_some(), "{}", error);
+        assert!(!is_transient_error(&error));
+    }
+}
@@ -0,0 +1,603 @@
+use anyhow::Result;
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+use crate::services::solana::SolanaService;
+
+/// Nesting limit for defined types, guarding against recursive IDLs
+const MAX_DEPTH: usize = 32;
+/// Seed of the account Anchor publishes a program's IDL under
+const IDL_SEED: &str = "anchor:idl";
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// A type reference in an Anchor IDL, in either the legacy or the 0.30 spelling
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum IdlType {
+    Primitive(String),
+    Vec { vec: Box<IdlType> },
+    Option { option: Box<IdlType> },
+    COption { coption: Box<IdlType> },
+    /// Length is a number, or a generic reference this decoder does not resolve
+    Array { array: (Box<IdlType>, Value) },
+    Defined { defined: IdlDefined },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum IdlDefined {
+    Name(String),
+    Named { name: String },
+}
+
+impl IdlDefined {
+    fn name(&self) -> &str {
+        match self {
+            IdlDefined::Name(name) | IdlDefined::Named { name } => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum IdlFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<IdlType>),
+}
+
+impl Default for IdlFields {
+    fn default() -> Self {
+        IdlFields::Named(Vec::new())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlVariant {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Option<IdlFields>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlTypeDefBody {
+    Struct {
+        #[serde(default)]
+        fields: IdlFields,
+    },
+    Enum { variants: Vec<IdlVariant> },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlTypeDef {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub body: IdlTypeDefBody,
+}
+
+/// An account or event; 0.30 IDLs carry the layout in `types` and an explicit discriminator
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlItem {
+    pub name: String,
+    #[serde(default, rename = "type")]
+    pub body: Option<IdlTypeDefBody>,
+    /// Legacy event layout
+    #[serde(default)]
+    pub fields: Option<Vec<IdlField>>,
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlInstruction {
+    pub name: String,
+    #[serde(default)]
+    pub accounts: Vec<Value>,
+    #[serde(default)]
+    pub args: Vec<IdlField>,
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct IdlMetadata {
+    name: Option<String>,
+    address: Option<String>,
+}
+
+/// Raw IDL document as published by Anchor
+#[derive(Debug, Clone, Deserialize)]
+struct IdlDocument {
+    name: Option<String>,
+    address: Option<String>,
+    #[serde(default)]
+    metadata: IdlMetadata,
+    #[serde(default)]
+    instructions: Vec<IdlInstruction>,
+    #[serde(default)]
+    accounts: Vec<IdlItem>,
+    #[serde(default)]
+    types: Vec<IdlTypeDef>,
+    #[serde(default)]
+    events: Vec<IdlItem>,
+}
+
+/// Something decoded against an IDL: the matched definition's name and its fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    pub name: String,
+    pub data: Value,
+}
+
+/// A decoded instruction with its account names in IDL order
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedInstruction {
+    pub name: String,
+    pub args: Value,
+    pub account_names: Vec<String>,
+}
+
+/// An Anchor IDL indexed by discriminator for generic decoding
+#[derive(Debug, Clone)]
+pub struct Idl {
+    pub name: String,
+    pub program_id: Option<Pubkey>,
+    instructions: Vec<([u8; 8], IdlInstruction)>,
+    accounts: Vec<([u8; 8], IdlItem)>,
+    events: Vec<([u8; 8], IdlItem)>,
+    types: HashMap<String, IdlTypeDef>,
+}
+
+impl Idl {
+    pub fn parse(json: &str) -> Result<Self> {
+        let document: IdlDocument = serde_json::from_str(json)?;
+        let name = document
+            .name
+            .or(document.metadata.name)
+            .unwrap_or_else(|| "unknown".to_string());
+        let program_id = document
+            .address
+            .or(document.metadata.address)
+            .map(|address| Pubkey::from_str(&address))
+            .transpose()?;
+
+        let instructions = document
+            .instructions
+            .into_iter()
+            .map(|ix| (discriminator(&ix.discriminator, "global", &to_snake_case(&ix.name)), ix))
+            .collect();
+        let accounts = document
+            .accounts
+            .into_iter()
+            .map(|account| (discriminator(&account.discriminator, "account", &account.name), account))
+            .collect();
+        let events = document
+            .events
+            .into_iter()
+            .map(|event| (discriminator(&event.discriminator, "event", &event.name), event))
+            .collect();
+        let types = document.types.into_iter().map(|t| (t.name.clone(), t)).collect();
+
+        Ok(Self { name, program_id, instructions, accounts, events, types })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read IDL {}: {}", path.display(), e))?;
+        Self::parse(&content).map_err(|e| anyhow::anyhow!("Invalid IDL {}: {}", path.display(), e))
+    }
+
+    /// Decode account data by its 8-byte discriminator
+    pub fn decode_account(&self, data: &[u8]) -> Result<Decoded> {
+        let account = find(&self.accounts, data)?;
+        let body = match &account.body {
+            Some(body) => body,
+            None => &self.type_def(&account.name)?.body,
+        };
+        let mut reader = Reader::new(&data[8..]);
+        Ok(Decoded {
+            name: account.name.clone(),
+            data: self.decode_body(body, &mut reader, 0)?,
+        })
+    }
+
+    /// Decode instruction data by its 8-byte discriminator
+    pub fn decode_instruction(&self, data: &[u8]) -> Result<DecodedInstruction> {
+        let instruction = find(&self.instructions, data)?;
+        let mut reader = Reader::new(&data[8..]);
+        Ok(DecodedInstruction {
+            name: instruction.name.clone(),
+            args: self.decode_named(&instruction.args, &mut reader, 0)?,
+            account_names: flatten_account_names(&instruction.accounts),
+        })
+    }
+
+    /// Decode an event emitted through `emit!`, i.e. the bytes of a `Program data:` log line
+    pub fn decode_event(&self, data: &[u8]) -> Result<Decoded> {
+        let event = find(&self.events, data)?;
+        let mut reader = Reader::new(&data[8..]);
+        let data = match (&event.fields, &event.body) {
+            (Some(fields), _) => self.decode_named(fields, &mut reader, 0)?,
+            (None, Some(body)) => self.decode_body(body, &mut reader, 0)?,
+            (None, None) => self.decode_body(&self.type_def(&event.name)?.body, &mut reader, 0)?,
+        };
+        Ok(Decoded { name: event.name.clone(), data })
+    }
+
+    fn type_def(&self, name: &str) -> Result<&IdlTypeDef> {
+        self.types
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("IDL {} has no type {}", self.name, name))
+    }
+
+    fn decode_body(&self, body: &IdlTypeDefBody, reader: &mut Reader, depth: usize) -> Result<Value> {
+        match body {
+            IdlTypeDefBody::Struct { fields } => self.decode_fields(fields, reader, depth),
+            IdlTypeDefBody::Enum { variants } => {
+                let index = reader.take(1)?[0] as usize;
+                let variant = variants
+                    .get(index)
+                    .ok_or_else(|| anyhow::anyhow!("Enum variant {} out of range", index))?;
+                match &variant.fields {
+                    None => Ok(Value::String(variant.name.clone())),
+                    Some(fields) => Ok(json!({ variant.name.clone(): self.decode_fields(fields, reader, depth)? })),
+                }
+            }
+        }
+    }
+
+    fn decode_fields(&self, fields: &IdlFields, reader: &mut Reader, depth: usize) -> Result<Value> {
+        match fields {
+            IdlFields::Named(fields) => self.decode_named(fields, reader, depth),
+            IdlFields::Tuple(types) => types
+                .iter()
+                .map(|ty| self.decode_value(ty, reader, depth))
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array),
+        }
+    }
+
+    fn decode_named(&self, fields: &[IdlField], reader: &mut Reader, depth: usize) -> Result<Value> {
+        let mut object = Map::new();
+        for field in fields {
+            object.insert(field.name.clone(), self.decode_value(&field.ty, reader, depth)?);
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn decode_value(&self, ty: &IdlType, reader: &mut Reader, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            anyhow::bail!("IDL type nesting exceeds {}", MAX_DEPTH);
+        }
+        Ok(match ty {
+            IdlType::Primitive(name) => reader.primitive(name)?,
+            IdlType::Vec { vec } => {
+                let len = reader.u32()? as usize;
+                (0..len)
+                    .map(|_| self.decode_value(vec, reader, depth + 1))
+                    .collect::<Result<Vec<_>>>()
+                    .map(Value::Array)?
+            }
+            IdlType::Option { option } => match reader.take(1)?[0] {
+                0 => Value::Null,
+                _ => self.decode_value(option, reader, depth + 1)?,
+            },
+            IdlType::COption { coption } => match reader.u32()? {
+                0 => Value::Null,
+                _ => self.decode_value(coption, reader, depth + 1)?,
+            },
+            IdlType::Array { array: (item, len) } => {
+                let len = len
+                    .as_u64()
+                    .ok_or_else(|| anyhow::anyhow!("Unsupported generic array length {}", len))?;
+                (0..len)
+                    .map(|_| self.decode_value(item, reader, depth + 1))
+                    .collect::<Result<Vec<_>>>()
+                    .map(Value::Array)?
+            }
+            IdlType::Defined { defined } => {
+                self.decode_body(&self.type_def(defined.name())?.body, reader, depth + 1)?
+            }
+        })
+    }
+}
+
+/// IDLs keyed by program id
+#[derive(Debug, Clone, Default)]
+pub struct IdlRegistry {
+    idls: HashMap<Pubkey, Idl>,
+}
+
+impl IdlRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every `*.json` IDL in a directory; IDLs without an address are skipped
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let mut registry = Self::new();
+        let dir = dir.as_ref();
+        if !dir.exists() {
+            return Ok(registry);
+        }
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let idl = Idl::load(&path)?;
+            match idl.program_id {
+                Some(program_id) => registry.insert(program_id, idl),
+                None => warn!("IDL {} has no program address, skipping", path.display()),
+            }
+        }
+        info!("Loaded {} IDL(s) from {}", registry.len(), dir.display());
+        Ok(registry)
+    }
+
+    pub fn insert(&mut self, program_id: Pubkey, idl: Idl) {
+        self.idls.insert(program_id, idl);
+    }
+
+    pub fn get(&self, program_id: &Pubkey) -> Option<&Idl> {
+        self.idls.get(program_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.idls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.idls.is_empty()
+    }
+
+    /// Fetch and register the IDL a program published on-chain
+    pub async fn fetch(&mut self, solana: &SolanaService, program_id: &Pubkey) -> Result<&Idl> {
+        let address = idl_address(program_id)?;
+        let account = solana
+            .get_account_info(&address)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Program {} has no on-chain IDL", program_id))?;
+        let mut idl = Idl::parse(&inflate_idl_account(&account.data)?)?;
+        idl.program_id = Some(*program_id);
+        self.idls.insert(*program_id, idl);
+        Ok(&self.idls[program_id])
+    }
+
+    /// Decode `Program data:` events in transaction logs, attributing each to the program
+    /// executing at that point; lines from programs without an IDL are skipped
+    pub fn decode_logs(&self, logs: &[String]) -> Vec<(Pubkey, Result<Decoded>)> {
+        let mut stack: Vec<Pubkey> = Vec::new();
+        let mut events = Vec::new();
+        for line in logs {
+            if let Some(data) = line.strip_prefix(PROGRAM_DATA_PREFIX) {
+                let Some(program_id) = stack.last() else { continue };
+                if let Some(idl) = self.get(program_id) {
+                    let decoded = base64::engine::general_purpose::STANDARD
+                        .decode(data.trim())
+                        .map_err(anyhow::Error::from)
+                        .and_then(|bytes| idl.decode_event(&bytes));
+                    events.push((*program_id, decoded));
+                }
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            if words.next() != Some("Program") {
+                continue;
+            }
+            let (Some(program), Some(action)) = (words.next(), words.next()) else { continue };
+            match action {
+                "invoke" => {
+                    if let Ok(program_id) = Pubkey::from_str(program) {
+                        stack.push(program_id);
+                    }
+                }
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+/// Address of the account holding a program's Anchor IDL
+pub fn idl_address(program_id: &Pubkey) -> Result<Pubkey> {
+    let (base, _) = Pubkey::find_program_address(&[], program_id);
+    Ok(Pubkey::create_with_seed(&base, IDL_SEED, program_id)?)
+}
+
+/// IDL account layout: discriminator, authority, then a length-prefixed zlib JSON blob
+fn inflate_idl_account(data: &[u8]) -> Result<String> {
+    let mut reader = Reader::new(data);
+    reader.take(8 + 32)?;
+    let len = reader.u32()? as usize;
+    let compressed = reader.take(len)?;
+    let mut json = String::new();
+    flate2::read::ZlibDecoder::new(compressed).read_to_string(&mut json)?;
+    Ok(json)
+}
+
+fn discriminator(explicit: &Option<Vec<u8>>, namespace: &str, name: &str) -> [u8; 8] {
+    let mut out = [0u8; 8];
+    match explicit {
+        Some(bytes) if bytes.len() == 8 => out.copy_from_slice(bytes),
+        _ => out.copy_from_slice(&Sha256::digest(format!("{}:{}", namespace, name))[..8]),
+    }
+    out
+}
+
+fn find<'a, T>(items: &'a [([u8; 8], T)], data: &[u8]) -> Result<&'a T> {
+    let prefix: [u8; 8] = data
+        .get(..8)
+        .and_then(|p| p.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("Data shorter than a discriminator"))?;
+    items
+        .iter()
+        .find(|(d, _)| *d == prefix)
+        .map(|(_, item)| item)
+        .ok_or_else(|| anyhow::anyhow!("Unknown discriminator {}", hex::encode(prefix)))
+}
+
+/// Legacy IDLs nest composite account groups under `accounts`
+fn flatten_account_names(accounts: &[Value]) -> Vec<String> {
+    let mut names = Vec::new();
+    for account in accounts {
+        if let Some(nested) = account.get("accounts").and_then(Value::as_array) {
+            names.extend(flatten_account_names(nested));
+        } else if let Some(name) = account.get("name").and_then(Value::as_str) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Borsh reader over a byte slice
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.data.len() < n {
+            anyhow::bail!("Unexpected end of data: needed {} bytes, {} left", n, self.data.len());
+        }
+        let (head, tail) = self.data.split_at(n);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into()?)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn primitive(&mut self, name: &str) -> Result<Value> {
+        Ok(match name {
+            "bool" => Value::Bool(self.take(1)?[0] != 0),
+            "u8" => json!(self.take(1)?[0]),
+            "i8" => json!(self.take(1)?[0] as i8),
+            "u16" => json!(u16::from_le_bytes(self.array()?)),
+            "i16" => json!(i16::from_le_bytes(self.array()?)),
+            "u32" => json!(self.u32()?),
+            "i32" => json!(i32::from_le_bytes(self.array()?)),
+            "u64" => json!(u64::from_le_bytes(self.array()?)),
+            "i64" => json!(i64::from_le_bytes(self.array()?)),
+            // Beyond JSON number precision
+            "u128" => json!(u128::from_le_bytes(self.array()?).to_string()),
+            "i128" => json!(i128::from_le_bytes(self.array()?).to_string()),
+            "f32" => json!(f32::from_le_bytes(self.array()?)),
+            "f64" => json!(f64::from_le_bytes(self.array()?)),
+            "publicKey" | "pubkey" => json!(Pubkey::new_from_array(self.array()?).to_string()),
+            "string" => {
+                let len = self.u32()? as usize;
+                json!(std::str::from_utf8(self.take(len)?)?)
+            }
+            "bytes" => {
+                let len = self.u32()? as usize;
+                json!(hex::encode(self.take(len)?))
+            }
+            other => anyhow::bail!("Unsupported IDL type {}", other),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDL: &str = r#"{
+        "name": "demo_amm",
+        "metadata": { "address": "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc" },
+        "instructions": [{
+            "name": "swapExact",
+            "accounts": [{ "name": "pool" }, { "name": "vaults", "accounts": [{ "name": "vaultA" }, { "name": "vaultB" }] }],
+            "args": [{ "name": "amount", "type": "u64" }, { "name": "minOut", "type": { "option": "u64" } }]
+        }],
+        "accounts": [{
+            "name": "Pool",
+            "type": { "kind": "struct", "fields": [
+                { "name": "mint", "type": "publicKey" },
+                { "name": "status", "type": { "defined": "Status" } },
+                { "name": "fees", "type": { "array": ["u16", 2] } }
+            ] }
+        }],
+        "types": [{ "name": "Status", "type": { "kind": "enum", "variants": [{ "name": "Active" }, { "name": "Paused" }] } }],
+        "events": [{ "name": "Swapped", "fields": [{ "name": "out", "type": "u64", "index": false }] }]
+    }"#;
+
+    #[test]
+    fn test_decode_account_instruction_and_event() {
+        let idl = Idl::parse(IDL).unwrap();
+        let mint = Pubkey::new_unique();
+
+        let mut account = discriminator(&None, "account", "Pool").to_vec();
+        account.extend_from_slice(mint.as_ref());
+        account.push(1);
+        account.extend_from_slice(&[25, 0, 5, 0]);
+        let decoded = idl.decode_account(&account).unwrap();
+        assert_eq!(decoded.name, "Pool");
+        assert_eq!(decoded.data, json!({ "mint": mint.to_string(), "status": "Paused", "fees": [25, 5] }));
+
+        let mut ix = discriminator(&None, "global", "swap_exact").to_vec();
+        ix.extend_from_slice(&1_000u64.to_le_bytes());
+        ix.push(0);
+        let decoded = idl.decode_instruction(&ix).unwrap();
+        assert_eq!(decoded.args, json!({ "amount": 1000, "minOut": null }));
+        assert_eq!(decoded.account_names, vec!["pool", "vaultA", "vaultB"]);
+
+        let mut event = discriminator(&None, "event", "Swapped").to_vec();
+        event.extend_from_slice(&42u64.to_le_bytes());
+        let program = idl.program_id.unwrap();
+        let mut registry = IdlRegistry::new();
+        registry.insert(program, idl);
+        let logs = vec![
+            format!("Program {} invoke [1]", program),
+            format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(&event)),
+            format!("Program {} success", program),
+        ];
+        let events = registry.decode_logs(&logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1.as_ref().unwrap().data, json!({ "out": 42 }));
+    }
+}
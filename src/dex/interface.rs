@@ -2,8 +2,21 @@ use async_trait::async_trait;
 use anyhow::Result;
 use rust_decimal::Decimal;
 use solana_program::pubkey::Pubkey;
-use crate::models::{Token, Pool, PoolQuote, PoolState, PoolMetrics};
+use solana_sdk::transaction::Transaction;
+use std::collections::HashMap;
+use std::sync::Arc;
+use solana_sdk::signature::Signer;
+use crate::config::{AppConfig, PrecisionConfig};
+use crate::models::{Token, Pool, PoolQuote, PoolState, PoolMetrics, SendOptions};
 use crate::dex::DexType;
+use crate::services::{
+    keepalive::KeepAliveRegistry,
+    rebroadcast::{RebroadcastingSender, Rebroadcaster},
+    sender::{RpcSender, TransactionSender},
+    solana::SolanaService,
+    tpu::TpuSender,
+    wallet::Wallets,
+};
 
 /// Common DEX interface; all DEX implementations must implement this trait
 #[async_trait]
@@ -41,22 +54,33 @@ pub trait DexInterface: Send + Sync {
         pool_address: Option<&Pubkey>,
     ) -> Result<PoolQuote>;
     
-    /// Execute token swap
+    /// Execute token swap, sending it with the execution's `options`
     async fn execute_swap(
         &self,
         quote: &PoolQuote,
         wallet: &Pubkey,
         slippage_tolerance: Decimal,
+        options: &SendOptions,
     ) -> Result<String>; // Returns transaction signature
     
+    /// Build and sign the swap without sending it, e.g. to submit it as part of a bundle
+    async fn build_swap_transaction(
+        &self,
+        _quote: &PoolQuote,
+        _wallet: &Pubkey,
+        _slippage_tolerance: Decimal,
+    ) -> Result<Transaction> {
+        Err(DexError::InvalidResponse(format!("{} does not build swap transactions", self.get_name())).into())
+    }
+    
     /// Get pool metrics
     async fn get_pool_metrics(&self, pool_address: &Pubkey) -> Result<PoolMetrics>;
     
     /// Get DEX-level metrics
     async fn get_dex_metrics(&self) -> Result<DexMetrics>;
     
-    /// Subscribe to pool updates
-    async fn subscribe_pool_updates(&self, pool_address: &Pubkey) -> Result<PoolUpdateStream>;
+    /// Stream reserve changes of a pool as its on-chain accounts change
+    async fn subscribe_pool_updates(&self, pool: &Pool) -> Result<PoolUpdateStream>;
     
     /// Get supported token list
     async fn get_supported_tokens(&self) -> Result<Vec<Token>>;
@@ -109,9 +133,85 @@ pub struct DexConnectionConfig {
     pub api_key: Option<String>,
     pub timeout_seconds: u64,
     pub max_retries: u32,
+    /// API requests per minute, 0 for unlimited
     pub rate_limit: u32,
 }
 
+/// Marks the value `execute_swap` returns for a swap that was built but not sent in dry-run mode
+pub const DRY_RUN_SIGNATURE_PREFIX: &str = "dry-run:";
+
+/// What an adapter needs to sign and submit its own swaps
+#[derive(Clone)]
+pub struct SwapSigner {
+    pub solana: Arc<SolanaService>,
+    pub wallets: Arc<Wallets>,
+    pub sender: Arc<dyn TransactionSender>,
+    pub precision: PrecisionConfig,
+    /// Build and sign swaps but never send them
+    pub dry_run: bool,
+    /// Wallets of tenants with their own, by owner
+    pub tenant_wallets: HashMap<Pubkey, Arc<Wallets>>,
+}
+
+impl SwapSigner {
+    /// Signer for the configured wallet, submitting over RPC or the TPU and rebroadcasting what
+    /// asks for it; None when no keypair is configured
+    pub fn from_config(config: &AppConfig) -> Result<Option<Self>> {
+        if config.wallet.keypair_path.is_empty() {
+            return Ok(None);
+        }
+        let solana = Arc::new(
+            SolanaService::with_connections(&config.solana.rpc_url, &config.connections)?
+                .with_account_cache(&config.account_cache)
+                .with_rpc_config(&config.solana),
+        );
+        KeepAliveRegistry::global().register(solana.clone());
+        let sender: Arc<dyn TransactionSender> = if config.tpu.enabled {
+            Arc::new(TpuSender::new(solana.clone(), config.tpu.clone())?)
+        } else {
+            Arc::new(RpcSender::new(solana.clone()))
+        };
+        let rebroadcaster = Rebroadcaster::from_config(solana.clone(), &config.submission)?;
+        let sender = Arc::new(RebroadcastingSender::new(sender, Arc::new(rebroadcaster)));
+        Ok(Some(Self {
+            wallets: Arc::new(Wallets::load(&config.wallet)?),
+            sender,
+            solana,
+            precision: config.precision.clone(),
+            dry_run: config.submission.dry_run,
+            tenant_wallets: Self::load_tenant_wallets(config)?,
+        }))
+    }
+
+    fn load_tenant_wallets(config: &AppConfig) -> Result<HashMap<Pubkey, Arc<Wallets>>> {
+        if !config.tenancy.enabled {
+            return Ok(HashMap::new());
+        }
+        let mut wallets = HashMap::new();
+        for tenant in config.tenancy.tenants.iter().filter(|t| !t.wallet.keypair_path.is_empty()) {
+            let tenant_wallets = Wallets::load(&tenant.wallet)
+                .map_err(|e| anyhow::anyhow!("Tenant {}: {}", tenant.namespace, e))?;
+            wallets.insert(tenant_wallets.owner().pubkey(), Arc::new(tenant_wallets));
+        }
+        Ok(wallets)
+    }
+
+    /// Wallets signing for `owner`: the main wallet or a tenant's
+    pub fn wallets_for(&self, owner: &Pubkey) -> Result<&Wallets> {
+        if self.wallets.owner().pubkey() == *owner {
+            return Ok(&self.wallets);
+        }
+        self.tenant_wallets.get(owner).map(|wallets| wallets.as_ref()).ok_or_else(|| {
+            DexError::AuthenticationFailed(format!(
+                "Swap requested for wallet {} but the signer is {}",
+                owner,
+                self.wallets.owner().pubkey()
+            ))
+            .into()
+        })
+    }
+}
+
 /// DEX error type
 #[derive(Debug, thiserror::Error)]
 pub enum DexError {
@@ -146,6 +246,37 @@ pub enum DexError {
     Internal(String),
 }
 
+impl DexError {
+    /// Error for an unsuccessful API response; throttling and server errors are worth retrying
+    pub fn from_status(status: reqwest::StatusCode, body: String) -> Self {
+        let message = format!("HTTP {}: {}", status, body);
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            DexError::RateLimitExceeded(message)
+        } else if status.is_server_error() {
+            DexError::ConnectionFailed(message)
+        } else {
+            DexError::InvalidResponse(message)
+        }
+    }
+
+    /// Whether the same call could succeed if made again
+    pub fn is_transient(&self) -> bool {
+        matches!(self, DexError::ConnectionFailed(_) | DexError::RateLimitExceeded(_) | DexError::Timeout(_))
+    }
+}
+
+/// Whether a failed DEX API call is worth retrying: timeouts, dropped connections, throttling
+/// and server errors are; bad requests and unparseable responses are not
+pub fn is_transient_error(error: &anyhow::Error) -> bool {
+    if let Some(e) = error.downcast_ref::<DexError>() {
+        return e.is_transient();
+    }
+    if let Some(e) = error.downcast_ref::<reqwest::Error>() {
+        return e.is_timeout() || e.is_connect() || e.status().is_some_and(|s| s.is_server_error());
+    }
+    error.is::<tokio::time::error::Elapsed>()
+}
+
 /// DEX connection status
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DexConnectionStatus {
@@ -0,0 +1,504 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use base64::Engine;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use solana_program::pubkey::Pubkey;
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use tokio::time::{timeout, Duration};
+
+use solana_sdk::{signature::Signer, transaction::Transaction};
+
+use crate::{
+    config::RoundingMode,
+    dex::{DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, DexType, SwapSigner, DRY_RUN_SIGNATURE_PREFIX},
+    dex::interface::is_transient_error,
+    dex::rate_limit::DexRateLimiter,
+    utils::retry::RetryPolicy,
+    models::{Token, Pool, PoolQuote, PoolState, PoolMetrics, SendOptions},
+    services::wallet::{SignedTransactions, Wallets},
+    utils::precision::{from_base_units, to_base_units},
+};
+
+/// Slippage requested for quotes that are not about to be executed, matching the other adapters' 0.5%
+const QUOTE_SLIPPAGE_BPS: u64 = 50;
+
+/// Jupiter aggregator. It has no pools of its own: quotes are the best route across every venue
+/// Jupiter indexes, and swaps are transactions Jupiter builds for that route.
+pub struct JupiterDex {
+    config: DexConnectionConfig,
+    client: Client,
+    rate_limiter: DexRateLimiter,
+    retry: RetryPolicy,
+    base_url: String,
+    signer: Option<SwapSigner>,
+    /// The executor wraps SOL into the wallet's wSOL account and closes it with the bundle tip, so
+    /// Jupiter must leave that account alone
+    wrapped_sol: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterQuote {
+    in_amount: String,
+    out_amount: String,
+    /// Minimum output after slippage
+    other_amount_threshold: String,
+    #[serde(default)]
+    price_impact_pct: String,
+    #[serde(default)]
+    route_plan: Vec<RoutePlanStep>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoutePlanStep {
+    swap_info: SwapInfo,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SwapInfo {
+    amm_key: String,
+    #[serde(default)]
+    label: Option<String>,
+    input_mint: String,
+    output_mint: String,
+    #[serde(default)]
+    fee_amount: String,
+    #[serde(default)]
+    fee_mint: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterSwap {
+    /// Base64 of the serialized transaction, with the user's signature missing
+    swap_transaction: String,
+}
+
+impl JupiterDex {
+    pub fn new(config: DexConnectionConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()?;
+
+        let rate_limiter = DexRateLimiter::from_config("Jupiter", &config);
+        let retry = RetryPolicy::new(config.max_retries);
+        Ok(Self {
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            config,
+            client,
+            rate_limiter,
+            retry,
+            signer: None,
+            wrapped_sol: false,
+        })
+    }
+
+    /// Whether the executor manages the wallet's wSOL account; see `submission.wrap_sol`
+    pub fn with_wrapped_sol(mut self, wrapped_sol: bool) -> Self {
+        self.wrapped_sol = wrapped_sol;
+        self
+    }
+
+    /// Sign and submit swaps with the given wallet
+    pub fn with_signer(mut self, signer: SwapSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// The signer and the wallets it holds for `wallet`
+    fn signer_for(&self, wallet: &Pubkey) -> Result<(&SwapSigner, &Wallets)> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or_else(|| DexError::AuthenticationFailed("No wallet configured to sign Jupiter swaps".to_string()))?;
+        Ok((signer, signer.wallets_for(wallet)?))
+    }
+
+    async fn send_request<T>(&self, request: RequestBuilder) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let request = match self.config.api_key.as_deref() {
+            Some(key) if !key.is_empty() => request.header("x-api-key", key),
+            _ => request,
+        };
+        self.retry.run(|| async {
+            let attempt = request
+                .try_clone()
+                .ok_or_else(|| DexError::Internal("Jupiter request body cannot be resent".to_string()))?;
+            self.rate_limiter.acquire().await?;
+            let response = timeout(Duration::from_secs(self.config.timeout_seconds), attempt.send()).await??;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                return Err(DexError::from_status(status, response.text().await?).into());
+            }
+
+            let data: T = response.json().await?;
+            Ok(data)
+        }, is_transient_error).await
+    }
+
+    /// Best route for `input_amount`, as the raw response Jupiter expects back at `/swap` and parsed
+    async fn fetch_quote(
+        &self,
+        input_token: &Token,
+        output_token: &Token,
+        input_amount: Decimal,
+        slippage_bps: u64,
+    ) -> Result<(serde_json::Value, PoolQuote)> {
+        let amount = to_base_units(input_amount, input_token.decimals, RoundingMode::Floor)?;
+        let request = self.client.get(format!("{}/quote", self.base_url)).query(&[
+            ("inputMint", input_token.mint.to_string()),
+            ("outputMint", output_token.mint.to_string()),
+            ("amount", amount.to_string()),
+            ("slippageBps", slippage_bps.to_string()),
+            // Legacy transactions, so swaps go through the same senders and bundles as the other venues
+            ("asLegacyTransaction", "true".to_string()),
+        ]);
+        let raw: serde_json::Value = self.send_request(request).await?;
+        let quote = parse_quote(&raw, input_token, output_token)?;
+        Ok((raw, quote))
+    }
+
+    /// Re-quote at the caller's slippage, check the route still delivers what was quoted, and have
+    /// Jupiter build the swap for the owner, paid for by the fee payer
+    async fn build_signed_swap(
+        &self,
+        wallets: &Wallets,
+        quote: &PoolQuote,
+        slippage_tolerance: Decimal,
+    ) -> Result<Transaction> {
+        let slippage_bps = (slippage_tolerance * Decimal::from(10_000)).to_u64().unwrap_or(QUOTE_SLIPPAGE_BPS);
+        let (raw, fresh) = self.fetch_quote(&quote.input_token, &quote.output_token, quote.input_amount, slippage_bps).await?;
+        let floor = quote.output_amount * (Decimal::ONE - slippage_tolerance);
+        if fresh.output_amount < floor {
+            return Err(DexError::SlippageExceeded(format!(
+                "Jupiter route now returns {} {}, below {}",
+                fresh.output_amount, quote.output_token.symbol, floor
+            ))
+            .into());
+        }
+
+        let owner = wallets.owner();
+        let payer = wallets.fee_payer().pubkey();
+        let body = serde_json::json!({
+            "quoteResponse": raw,
+            "userPublicKey": owner.pubkey().to_string(),
+            "payer": payer.to_string(),
+            // Closing the wSOL account mid-bundle would fail the tip transaction's own close
+            "wrapAndUnwrapSol": !self.wrapped_sol,
+            "asLegacyTransaction": true,
+            "dynamicComputeUnitLimit": true,
+        });
+        let swap: JupiterSwap = self.send_request(self.client.post(format!("{}/swap", self.base_url)).json(&body)).await?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&swap.swap_transaction)
+            .map_err(|e| DexError::InvalidResponse(format!("Jupiter swap transaction is not base64: {}", e)))?;
+        let mut transaction: Transaction = bincode::deserialize(&bytes)
+            .map_err(|e| DexError::InvalidResponse(format!("Jupiter swap transaction does not decode: {}", e)))?;
+
+        // Refuse a transaction paid by anyone but the configured fee payer rather than sign it
+        if transaction.message.account_keys.first() != Some(&payer) {
+            return Err(DexError::InvalidResponse("Jupiter swap transaction is not paid by the fee payer".to_string()).into());
+        }
+        let blockhash = transaction.message.recent_blockhash;
+        transaction.try_partial_sign(&wallets.signers_for(&transaction.message), blockhash)?;
+        if !transaction.is_signed() {
+            return Err(DexError::InvalidResponse("Jupiter swap transaction needs signatures the bot does not hold".to_string()).into());
+        }
+        SignedTransactions::global().record(&transaction);
+        Ok(transaction)
+    }
+}
+
+/// Token for a mint on the route; intermediate hops are only known by mint
+fn route_token(mint: &str, input: &Token, output: &Token) -> Result<Token> {
+    let mint = mint.parse::<Pubkey>()?;
+    Ok(if mint == input.mint {
+        input.clone()
+    } else if mint == output.mint {
+        output.clone()
+    } else {
+        Token::new(mint, "UNKNOWN".to_string(), "Unknown Token".to_string(), 0)
+    })
+}
+
+/// Turn a `/quote` response into a quote whose route lists the underlying pools
+fn parse_quote(raw: &serde_json::Value, input_token: &Token, output_token: &Token) -> Result<PoolQuote> {
+    let quote: JupiterQuote = serde_json::from_value(raw.clone())?;
+    let base_units = |field: &str, value: &str, decimals: u8| -> Result<Decimal> {
+        let units: u64 = value
+            .parse()
+            .map_err(|_| DexError::InvalidResponse(format!("Invalid {} in Jupiter quote: {}", field, value)))?;
+        Ok(from_base_units(units, decimals))
+    };
+
+    let route = quote
+        .route_plan
+        .iter()
+        .map(|step| {
+            let info = &step.swap_info;
+            let address = info.amm_key.parse::<Pubkey>()?;
+            Ok(Pool::new(
+                info.label.clone().unwrap_or_else(|| info.amm_key.clone()),
+                DexType::Jupiter,
+                route_token(&info.input_mint, input_token, output_token)?,
+                route_token(&info.output_mint, input_token, output_token)?,
+                address,
+                Pubkey::default(),
+                Pubkey::default(),
+            ))
+        })
+        .collect::<Result<Vec<Pool>>>()?;
+    if route.is_empty() {
+        return Err(DexError::PoolNotFound("Jupiter found no route for the pair".to_string()).into());
+    }
+
+    // Fees charged in the input token; hops that take fees in other mints are already in out_amount
+    let mut fee_amount = Decimal::ZERO;
+    for step in &quote.route_plan {
+        if step.swap_info.fee_mint == input_token.mint.to_string() && !step.swap_info.fee_amount.is_empty() {
+            fee_amount += base_units("feeAmount", &step.swap_info.fee_amount, input_token.decimals)?;
+        }
+    }
+
+    let route_name = route.iter().map(|pool| pool.id.as_str()).collect::<Vec<_>>().join(" > ");
+    let pool = Pool::new(
+        format!("jupiter:{}", route_name),
+        DexType::Jupiter,
+        input_token.clone(),
+        output_token.clone(),
+        route[0].pool_address,
+        Pubkey::default(),
+        Pubkey::default(),
+    );
+
+    Ok(PoolQuote {
+        pool,
+        input_token: input_token.clone(),
+        output_token: output_token.clone(),
+        input_amount: base_units("inAmount", &quote.in_amount, input_token.decimals)?,
+        output_amount: base_units("outAmount", &quote.out_amount, output_token.decimals)?,
+        price_impact: quote.price_impact_pct.parse().unwrap_or(Decimal::ZERO),
+        fee_amount,
+        minimum_output: base_units("otherAmountThreshold", &quote.other_amount_threshold, output_token.decimals)?,
+        route,
+    })
+}
+
+#[async_trait]
+impl DexInterface for JupiterDex {
+    fn get_dex_type(&self) -> DexType {
+        DexType::Jupiter
+    }
+
+    fn get_name(&self) -> &str {
+        "Jupiter"
+    }
+
+    fn get_version(&self) -> &str {
+        "1.0.0"
+    }
+
+    async fn is_connected(&self) -> Result<bool> {
+        let request = self.client.get(format!("{}/program-id-to-label", self.base_url));
+        match self.send_request::<serde_json::Value>(request).await {
+            Ok(_) => Ok(true),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// An aggregator has no pools to scan; its prices are compared per quote
+    async fn get_pools(&self) -> Result<Vec<Pool>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_pools_by_tokens(&self, _token_a: &Token, _token_b: &Token) -> Result<Vec<Pool>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_pool_state(&self, pool_address: &Pubkey) -> Result<PoolState> {
+        Err(DexError::PoolNotFound(format!("Jupiter routes through pools it does not own: {}", pool_address)).into())
+    }
+
+    async fn get_token_price(&self, token: &Token, quote_token: &Token) -> Result<Decimal> {
+        let quote = self.get_quote(token, quote_token, Decimal::ONE, None).await?;
+        Ok(quote.output_amount / quote.input_amount.max(Decimal::new(1, 28)))
+    }
+
+    /// Best route across venues; `pool_address` is ignored since Jupiter picks the pools
+    async fn get_quote(
+        &self,
+        input_token: &Token,
+        output_token: &Token,
+        input_amount: Decimal,
+        _pool_address: Option<&Pubkey>,
+    ) -> Result<PoolQuote> {
+        let (_, quote) = self.fetch_quote(input_token, output_token, input_amount, QUOTE_SLIPPAGE_BPS).await?;
+        Ok(quote)
+    }
+
+    async fn execute_swap(
+        &self,
+        quote: &PoolQuote,
+        wallet: &Pubkey,
+        slippage_tolerance: Decimal,
+        options: &SendOptions,
+    ) -> Result<String> {
+        let (signer, wallets) = self.signer_for(wallet)?;
+        let transaction = self.build_signed_swap(wallets, quote, slippage_tolerance).await?;
+        if signer.dry_run {
+            tracing::info!("Dry run: built Jupiter swap {} for wallet {} without sending", transaction.signatures[0], wallet);
+            return Ok(format!("{}{}", DRY_RUN_SIGNATURE_PREFIX, transaction.signatures[0]));
+        }
+        let signature = signer.sender.send(&transaction, options).await?;
+        tracing::info!("Submitted Jupiter swap {} for wallet {}", signature, wallet);
+        Ok(signature.to_string())
+    }
+
+    async fn build_swap_transaction(
+        &self,
+        quote: &PoolQuote,
+        wallet: &Pubkey,
+        slippage_tolerance: Decimal,
+    ) -> Result<Transaction> {
+        let (_, wallets) = self.signer_for(wallet)?;
+        self.build_signed_swap(wallets, quote, slippage_tolerance).await
+    }
+
+    async fn get_pool_metrics(&self, pool_address: &Pubkey) -> Result<PoolMetrics> {
+        Err(DexError::PoolNotFound(format!("Jupiter has no metrics for pool {}", pool_address)).into())
+    }
+
+    async fn get_dex_metrics(&self) -> Result<DexMetrics> {
+        Ok(DexMetrics {
+            total_volume_24h: Decimal::ZERO,
+            total_tvl: Decimal::ZERO,
+            total_pools: 0,
+            active_pools: 0,
+            total_trades_24h: 0,
+            average_gas_price: Decimal::ZERO,
+        })
+    }
+
+    async fn subscribe_pool_updates(&self, pool: &Pool) -> Result<PoolUpdateStream> {
+        Err(DexError::ConnectionFailed(format!("Jupiter routes have no pool account to follow: {}", pool.id)).into())
+    }
+
+    async fn get_supported_tokens(&self) -> Result<Vec<Token>> {
+        Ok(Vec::new())
+    }
+
+    async fn validate_transaction(&self, _transaction_data: &[u8]) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fixtures;
+
+    #[test]
+    fn test_parses_multi_hop_quote() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let (hop, first, second) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let raw = serde_json::json!({
+            "inputMint": sol.mint.to_string(),
+            "inAmount": "2000000000",
+            "outputMint": usdc.mint.to_string(),
+            "outAmount": "301250000",
+            "otherAmountThreshold": "299743750",
+            "swapMode": "ExactIn",
+            "slippageBps": 50,
+            "priceImpactPct": "0.0012",
+            "routePlan": [
+                {"swapInfo": {"ammKey": first.to_string(), "label": "Whirlpool", "inputMint": sol.mint.to_string(),
+                              "outputMint": hop.to_string(), "inAmount": "2000000000", "outAmount": "55",
+                              "feeAmount": "5000000", "feeMint": sol.mint.to_string()}, "percent": 100},
+                {"swapInfo": {"ammKey": second.to_string(), "label": "Raydium CLMM", "inputMint": hop.to_string(),
+                              "outputMint": usdc.mint.to_string(), "inAmount": "55", "outAmount": "301250000",
+                              "feeAmount": "1", "feeMint": hop.to_string()}, "percent": 100}
+            ]
+        });
+
+        let quote = parse_quote(&raw, &sol, &usdc).unwrap();
+        assert_eq!(quote.input_amount, Decimal::from(2));
+        assert_eq!(quote.output_amount, Decimal::new(30125, 2));
+        assert_eq!(quote.minimum_output, Decimal::new(299743750, 6));
+        assert_eq!(quote.fee_amount, Decimal::new(5, 3));
+        assert_eq!(quote.price_impact, Decimal::new(12, 4));
+        assert_eq!(quote.pool.id, "jupiter:Whirlpool > Raydium CLMM");
+        assert_eq!(quote.route.len(), 2);
+        assert_eq!((quote.route[0].token_a.mint, quote.route[0].token_b.mint), (sol.mint, hop));
+        assert_eq!(quote.route[1].pool_address, second);
+
+        let no_route = serde_json::json!({"inAmount": "1", "outAmount": "0", "otherAmountThreshold": "0", "routePlan": []});
+        assert!(parse_quote(&no_route, &sol, &usdc).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_swaps_leave_managed_wsol_alone_and_are_paid_by_the_fee_payer() {
+        use axum::{extract::State, routing::{get, post}, Json, Router};
+        use solana_sdk::{message::Message, signature::Keypair};
+        use std::sync::{Arc, Mutex};
+
+        type Swaps = Arc<Mutex<(Vec<serde_json::Value>, Option<Pubkey>)>>;
+
+        let (sol, usdc) = (
+            Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "Solana".to_string(), 9),
+            Token::new(Pubkey::new_unique(), "USDC".to_string(), "USD Coin".to_string(), 6),
+        );
+        let raw = serde_json::json!({
+            "inputMint": sol.mint.to_string(), "inAmount": "1000000000",
+            "outputMint": usdc.mint.to_string(), "outAmount": "150000000", "otherAmountThreshold": "149250000",
+            "routePlan": [{"swapInfo": {"ammKey": Pubkey::new_unique().to_string(), "label": "Whirlpool",
+                           "inputMint": sol.mint.to_string(), "outputMint": usdc.mint.to_string()}, "percent": 100}]
+        });
+
+        // Builds a transfer out of the user's wallet, paid by the requested payer unless overridden
+        async fn swap(State(swaps): State<Swaps>, Json(body): Json<serde_json::Value>) -> Json<serde_json::Value> {
+            let user: Pubkey = body["userPublicKey"].as_str().unwrap().parse().unwrap();
+            let requested: Pubkey = body["payer"].as_str().unwrap().parse().unwrap();
+            let mut swaps = swaps.lock().unwrap();
+            let payer = swaps.1.unwrap_or(requested);
+            swaps.0.push(body);
+            let transfer = solana_system_interface::instruction::transfer(&user, &Pubkey::new_unique(), 1);
+            let mut message = Message::new(&[transfer], Some(&payer));
+            message.recent_blockhash = solana_sdk::hash::Hash::new_unique();
+            let bytes = bincode::serialize(&Transaction::new_unsigned(message)).unwrap();
+            Json(serde_json::json!({"swapTransaction": base64::engine::general_purpose::STANDARD.encode(bytes)}))
+        }
+
+        let swaps: Swaps = Arc::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let quote_response = raw.clone();
+        let app = Router::new()
+            .route("/quote", get(move || async move { Json(quote_response) }))
+            .route("/swap", post(swap))
+            .with_state(swaps.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let config = DexConnectionConfig { base_url: url, api_key: None, timeout_seconds: 5, max_retries: 0, rate_limit: 0 };
+        let jupiter = JupiterDex::new(config).unwrap().with_wrapped_sol(true);
+        let wallets = Wallets::new(Keypair::new(), Some(Keypair::new()));
+        let quote = parse_quote(&raw, &sol, &usdc).unwrap();
+
+        let transaction = jupiter.build_signed_swap(&wallets, &quote, Decimal::new(1, 2)).await.unwrap();
+        assert_eq!(transaction.message.account_keys[0], wallets.fee_payer().pubkey());
+        assert!(transaction.verify().is_ok());
+        let body = swaps.lock().unwrap().0[0].clone();
+        assert_eq!(body["wrapAndUnwrapSol"], false);
+        assert_eq!(body["payer"], wallets.fee_payer().pubkey().to_string());
+
+        // A route Jupiter has the owner pay for is not what was asked for
+        swaps.lock().unwrap().1 = Some(wallets.owner().pubkey());
+        assert!(jupiter.build_signed_swap(&wallets, &quote, Decimal::new(1, 2)).await.is_err());
+    }
+}
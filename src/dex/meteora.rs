@@ -2,19 +2,18 @@ use async_trait::async_trait;
 use anyhow::Result;
 use rust_decimal::Decimal;
 use solana_program::pubkey::Pubkey;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tokio::time::{timeout, Duration};
 
 use crate::{
-    dex::{DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, PoolUpdate, DexType},
-    models::{Token, Pool, PoolQuote, PoolState, PoolMetrics},
+    dex::{pool_accounts, DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, PoolUpdate, DexType},
+    dex::http::DexHttpClient,
+    models::{Token, Pool, PoolQuote, PoolState, PoolMetrics, SendOptions},
+    services::account_subscriber::AccountSubscriber,
 };
 
 pub struct MeteoraDex {
-    config: DexConnectionConfig,
-    client: Client,
-    base_url: String,
+    http: DexHttpClient,
+    subscriber: Option<AccountSubscriber>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,37 +33,16 @@ struct MeteoraPool {
 
 impl MeteoraDex {
     pub fn new(config: DexConnectionConfig) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
-            .build()?;
-
-        let base_url = config.base_url.clone();
         Ok(Self {
-            config,
-            client,
-            base_url,
+            http: DexHttpClient::new("Meteora", &config)?,
+            subscriber: None,
         })
     }
 
-    async fn make_request<T>(&self, endpoint: &str) -> Result<T>
-    where
-        T: for<'de> Deserialize<'de>,
-    {
-        let url = format!("{}{}", self.base_url, endpoint);
-        let response = timeout(
-            Duration::from_secs(self.config.timeout_seconds),
-            self.client.get(&url).send()
-        ).await??;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            return Err(DexError::InvalidResponse(
-                format!("HTTP {}: {}", status, response.text().await?)
-            ).into());
-        }
-
-        let data: T = response.json().await?;
-        Ok(data)
+    /// Stream pool reserve changes over the shared account websocket
+    pub fn with_subscriber(mut self, subscriber: AccountSubscriber) -> Self {
+        self.subscriber = Some(subscriber);
+        self
     }
 
     fn parse_pool(&self, meteora_pool: &MeteoraPool) -> Result<Pool> {
@@ -119,14 +97,14 @@ impl DexInterface for MeteoraDex {
     }
 
     async fn is_connected(&self) -> Result<bool> {
-        match self.make_request::<serde_json::Value>("/health").await {
+        match self.http.get::<serde_json::Value>("/health").await {
             Ok(_) => Ok(true),
             Err(_) => Ok(false),
         }
     }
 
     async fn get_pools(&self) -> Result<Vec<Pool>> {
-        let pools: Vec<MeteoraPool> = self.make_request("/pools").await?;
+        let pools: Vec<MeteoraPool> = self.http.get("/pools").await?;
         let mut result = Vec::new();
         
         for pool in pools {
@@ -154,7 +132,7 @@ impl DexInterface for MeteoraDex {
 
     async fn get_pool_state(&self, pool_address: &Pubkey) -> Result<PoolState> {
         let endpoint = format!("/pool/{}", pool_address);
-        let pool_data: MeteoraPool = self.make_request(&endpoint).await?;
+        let pool_data: MeteoraPool = self.http.get(&endpoint).await?;
         let pool = self.parse_pool(&pool_data)?;
         
         let current_price = pool.get_price(&pool.token_a).unwrap_or(Decimal::ZERO);
@@ -228,6 +206,7 @@ impl DexInterface for MeteoraDex {
         quote: &PoolQuote,
         wallet: &Pubkey,
         slippage_tolerance: Decimal,
+        _options: &SendOptions,
     ) -> Result<String> {
         tracing::info!("Executing Meteora swap for wallet: {}", wallet);
         Ok("mock_transaction_signature".to_string())
@@ -262,13 +241,11 @@ impl DexInterface for MeteoraDex {
         })
     }
 
-    async fn subscribe_pool_updates(&self, pool_address: &Pubkey) -> Result<PoolUpdateStream> {
-        let (_, receiver) = tokio::sync::mpsc::channel(100);
-        
-        Ok(PoolUpdateStream {
-            pool_address: *pool_address,
-            update_receiver: receiver,
-        })
+    async fn subscribe_pool_updates(&self, pool: &Pool) -> Result<PoolUpdateStream> {
+        match &self.subscriber {
+            Some(subscriber) => pool_accounts::subscribe_reserves(subscriber, pool),
+            None => Err(DexError::ConnectionFailed("websocket subscriptions are disabled".to_string()).into()),
+        }
     }
 
     async fn get_supported_tokens(&self) -> Result<Vec<Token>> {
@@ -0,0 +1,251 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::Rng;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::{
+    dex::{DexError, DexInterface, DexMetrics, DexType, PoolUpdate, PoolUpdateStream},
+    models::{Pool, PoolMetrics, PoolQuote, PoolState, SendOptions, Token},
+    utils::panic_guard::lock_or_recover,
+};
+
+/// In-process DEX whose pool reserves random-walk on every read, for soak runs and tests
+pub struct MockDex {
+    dex_type: DexType,
+    pools: Arc<Mutex<Vec<Pool>>>,
+    /// Largest relative reserve move per read
+    volatility: f64,
+    update_interval: Duration,
+}
+
+impl MockDex {
+    pub fn new(dex_type: DexType, pools: Vec<Pool>) -> Self {
+        Self {
+            dex_type,
+            pools: Arc::new(Mutex::new(pools)),
+            volatility: 0.002,
+            update_interval: Duration::from_millis(100),
+        }
+    }
+
+    pub fn with_volatility(mut self, volatility: f64) -> Self {
+        self.volatility = volatility;
+        self
+    }
+
+    /// One SOL/USDC pool on each venue, slightly offset so spreads appear
+    pub fn standard_set() -> HashMap<DexType, Box<dyn DexInterface>> {
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "Solana".to_string(), 9);
+        let usdc = Token::new(Pubkey::new_unique(), "USDC".to_string(), "USD Coin".to_string(), 6);
+
+        let venues = [DexType::Raydium, DexType::Meteora, DexType::Whirlpool, DexType::Pump];
+        let mut dexes: HashMap<DexType, Box<dyn DexInterface>> = HashMap::new();
+        for (i, dex_type) in venues.into_iter().enumerate() {
+            let pool = Pool::new(
+                format!("mock-{}-sol-usdc", dex_type.to_string().to_lowercase()),
+                dex_type.clone(),
+                sol.clone(),
+                usdc.clone(),
+                Pubkey::new_unique(),
+                Pubkey::default(),
+                Pubkey::default(),
+            )
+            .update_reserves(Decimal::from(10_000), Decimal::from(1_500_000 + 5_000 * i as i64))
+            .with_fee_rate(Decimal::new(25, 4));
+            dexes.insert(dex_type.clone(), Box::new(MockDex::new(dex_type, vec![pool])));
+        }
+        dexes
+    }
+
+    fn snapshot(&self) -> Vec<Pool> {
+        lock_or_recover(&self.pools, "Mock pools").clone()
+    }
+
+    /// Move every pool's reserves and return the new state
+    fn step(&self) -> Vec<Pool> {
+        let mut rng = rand::thread_rng();
+        let mut pools = lock_or_recover(&self.pools, "Mock pools");
+        for pool in pools.iter_mut() {
+            let factor = Decimal::from_f64(1.0 + rng.gen_range(-self.volatility..=self.volatility))
+                .unwrap_or(Decimal::ONE);
+            pool.reserve_b *= factor;
+            pool.last_updated = chrono::Utc::now();
+        }
+        pools.clone()
+    }
+
+    fn find_pool(&self, pool_address: &Pubkey) -> Result<Pool> {
+        self.snapshot()
+            .into_iter()
+            .find(|p| &p.pool_address == pool_address)
+            .ok_or_else(|| DexError::PoolNotFound(pool_address.to_string()).into())
+    }
+}
+
+#[async_trait]
+impl DexInterface for MockDex {
+    fn get_dex_type(&self) -> DexType {
+        self.dex_type.clone()
+    }
+
+    fn get_name(&self) -> &str {
+        "Mock"
+    }
+
+    fn get_version(&self) -> &str {
+        "mock"
+    }
+
+    async fn is_connected(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn get_pools(&self) -> Result<Vec<Pool>> {
+        Ok(self.step())
+    }
+
+    async fn get_pools_by_tokens(&self, token_a: &Token, token_b: &Token) -> Result<Vec<Pool>> {
+        Ok(self
+            .snapshot()
+            .into_iter()
+            .filter(|pool| {
+                (pool.token_a.mint == token_a.mint && pool.token_b.mint == token_b.mint)
+                    || (pool.token_a.mint == token_b.mint && pool.token_b.mint == token_a.mint)
+            })
+            .collect())
+    }
+
+    async fn get_pool_state(&self, pool_address: &Pubkey) -> Result<PoolState> {
+        let pool = self.find_pool(pool_address)?;
+        Ok(PoolState {
+            current_price: pool.get_price(&pool.token_a).unwrap_or(Decimal::ZERO),
+            price_impact: Decimal::ZERO,
+            volume_24h: Decimal::ZERO,
+            tvl: pool.reserve_a + pool.reserve_b,
+            apy: None,
+            pool,
+        })
+    }
+
+    async fn get_token_price(&self, token: &Token, quote_token: &Token) -> Result<Decimal> {
+        let pools = self.get_pools_by_tokens(token, quote_token).await?;
+        let pool = pools
+            .first()
+            .ok_or_else(|| DexError::PoolNotFound("No pools found for token pair".to_string()))?;
+        pool.get_price(token)
+            .ok_or_else(|| DexError::InsufficientLiquidity("Empty pool".to_string()).into())
+    }
+
+    async fn get_quote(
+        &self,
+        input_token: &Token,
+        output_token: &Token,
+        input_amount: Decimal,
+        pool_address: Option<&Pubkey>,
+    ) -> Result<PoolQuote> {
+        let pools = match pool_address {
+            Some(address) => vec![self.find_pool(address)?],
+            None => self.get_pools_by_tokens(input_token, output_token).await?,
+        };
+        let pool = pools
+            .first()
+            .ok_or_else(|| DexError::PoolNotFound("No pools found for token pair".to_string()))?;
+        let output_amount = pool
+            .calculate_output_amount(input_amount, input_token)
+            .ok_or_else(|| DexError::InsufficientLiquidity("Cannot calculate output amount".to_string()))?;
+
+        Ok(PoolQuote {
+            pool: pool.clone(),
+            input_token: input_token.clone(),
+            output_token: output_token.clone(),
+            input_amount,
+            output_amount,
+            price_impact: pool.calculate_price_impact(input_amount, input_token).unwrap_or(Decimal::ZERO),
+            fee_amount: input_amount * pool.fee_rate,
+            minimum_output: output_amount,
+            route: pools,
+        })
+    }
+
+    async fn execute_swap(&self, _quote: &PoolQuote, _wallet: &Pubkey, _slippage_tolerance: Decimal, _options: &SendOptions) -> Result<String> {
+        Ok(solana_sdk::signature::Signature::new_unique().to_string())
+    }
+
+    async fn get_pool_metrics(&self, pool_address: &Pubkey) -> Result<PoolMetrics> {
+        let state = self.get_pool_state(pool_address).await?;
+        Ok(PoolMetrics {
+            pool_id: state.pool.id.clone(),
+            dex_type: self.dex_type.clone(),
+            volume_24h: Decimal::ZERO,
+            volume_7d: Decimal::ZERO,
+            tvl: state.tvl,
+            fee_revenue_24h: Decimal::ZERO,
+            unique_traders_24h: 0,
+            timestamp: chrono::Utc::now(),
+        })
+    }
+
+    async fn get_dex_metrics(&self) -> Result<DexMetrics> {
+        let pools = self.snapshot();
+        Ok(DexMetrics {
+            total_volume_24h: Decimal::ZERO,
+            total_tvl: pools.iter().map(|p| p.reserve_a + p.reserve_b).sum(),
+            total_pools: pools.len() as u64,
+            active_pools: pools.iter().filter(|p| p.is_active).count() as u64,
+            total_trades_24h: 0,
+            average_gas_price: Decimal::ZERO,
+        })
+    }
+
+    /// Streams reserve changes until the receiver is dropped
+    async fn subscribe_pool_updates(&self, pool: &Pool) -> Result<PoolUpdateStream> {
+        let (sender, receiver) = tokio::sync::mpsc::channel(100);
+        let pools = self.pools.clone();
+        let address = pool.pool_address;
+        let interval = self.update_interval;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let update = lock_or_recover(&pools, "Mock pools")
+                    .iter()
+                    .find(|p| p.pool_address == address)
+                    .cloned();
+                let Some(pool) = update else { break };
+                let change = PoolUpdate::ReserveChange {
+                    reserve_a: pool.reserve_a,
+                    reserve_b: pool.reserve_b,
+                    timestamp: chrono::Utc::now(),
+                };
+                if sender.send(change).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(PoolUpdateStream {
+            pool_address: address,
+            update_receiver: receiver,
+        })
+    }
+
+    async fn get_supported_tokens(&self) -> Result<Vec<Token>> {
+        let mut tokens: Vec<Token> = Vec::new();
+        for pool in self.snapshot() {
+            for token in [pool.token_a, pool.token_b] {
+                if !tokens.iter().any(|t| t.mint == token.mint) {
+                    tokens.push(token);
+                }
+            }
+        }
+        Ok(tokens)
+    }
+
+    async fn validate_transaction(&self, _transaction_data: &[u8]) -> Result<bool> {
+        Ok(true)
+    }
+}
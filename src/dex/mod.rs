@@ -1,9 +1,20 @@
 pub mod interface;
 pub mod raydium;
+pub mod raydium_amm;
 pub mod meteora;
 pub mod whirlpool;
 pub mod pump;
+pub mod orca;
+pub mod phoenix;
+pub mod jupiter;
 pub mod factory;
+pub mod health;
+pub mod errors;
+pub mod idl;
+pub mod mock;
+pub mod pool_accounts;
+pub mod rate_limit;
+pub mod http;
 
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +24,19 @@ pub enum DexType {
     Meteora,
     Whirlpool,
     Pump,
+    /// Orca's original constant-product pools, from before Whirlpools
+    OrcaLegacy,
+    /// Central limit order book; pools carry the book's depth
+    Phoenix,
+    /// Aggregated routes across venues rather than a pool venue of its own
+    Jupiter,
+}
+
+impl DexType {
+    /// Venues with pools of their own; Jupiter only routes across them
+    pub fn pool_venues() -> Vec<DexType> {
+        vec![DexType::Raydium, DexType::Meteora, DexType::Whirlpool, DexType::Pump, DexType::OrcaLegacy, DexType::Phoenix]
+    }
 }
 
 impl std::fmt::Display for DexType {
@@ -22,9 +46,14 @@ impl std::fmt::Display for DexType {
             DexType::Meteora => write!(f, "Meteora"),
             DexType::Whirlpool => write!(f, "Whirlpool"),
             DexType::Pump => write!(f, "Pump"),
+            DexType::OrcaLegacy => write!(f, "OrcaLegacy"),
+            DexType::Phoenix => write!(f, "Phoenix"),
+            DexType::Jupiter => write!(f, "Jupiter"),
         }
     }
 }
 
 pub use interface::*;
 pub use factory::*;
+pub use health::{DexHealthProber, DexHealthTracker};
+pub use errors::ProgramErrorInfo;
@@ -0,0 +1,270 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use solana_program::pubkey::Pubkey;
+use serde::Deserialize;
+
+use crate::{
+    dex::{DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, DexType},
+    dex::http::DexHttpClient,
+    models::{BookLevel, OrderBook, Token, Pool, PoolQuote, PoolState, PoolMetrics, SendOptions},
+};
+
+/// Phoenix v1 order book program
+const PHOENIX_PROGRAM_ID: &str = "PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY";
+
+/// Phoenix order book markets, each exposed as a pool whose quotes walk the visible depth
+pub struct PhoenixDex {
+    http: DexHttpClient,
+}
+
+#[derive(Debug, Deserialize)]
+struct PhoenixMarket {
+    id: String,
+    market_address: String,
+    base_mint: String,
+    quote_mint: String,
+    base_decimals: u8,
+    quote_decimals: u8,
+    taker_fee_bps: u32,
+    /// `[price, size]` in quote per base and whole base units
+    bids: Vec<(String, String)>,
+    asks: Vec<(String, String)>,
+}
+
+fn parse_levels(levels: &[(String, String)]) -> Result<Vec<BookLevel>> {
+    levels
+        .iter()
+        .map(|(price, size)| Ok(BookLevel { price: price.parse()?, size: size.parse()? }))
+        .collect()
+}
+
+fn parse_market(market: &PhoenixMarket) -> Result<Pool> {
+    let base_token = Token::new(
+        market.base_mint.parse::<Pubkey>()?,
+        "BASE".to_string(),
+        "Base Token".to_string(),
+        market.base_decimals,
+    );
+    let quote_token = Token::new(
+        market.quote_mint.parse::<Pubkey>()?,
+        "QUOTE".to_string(),
+        "Quote Token".to_string(),
+        market.quote_decimals,
+    );
+    let book = OrderBook::new(parse_levels(&market.bids)?, parse_levels(&market.asks)?);
+
+    Ok(Pool::new(
+        market.id.clone(),
+        DexType::Phoenix,
+        base_token,
+        quote_token,
+        market.market_address.parse::<Pubkey>()?,
+        Pubkey::default(),
+        PHOENIX_PROGRAM_ID.parse::<Pubkey>()?,
+    )
+    .with_order_book(book)
+    .with_fee_rate(Decimal::from(market.taker_fee_bps) / Decimal::from(10_000)))
+}
+
+impl PhoenixDex {
+    pub fn new(config: DexConnectionConfig) -> Result<Self> {
+        Ok(Self {
+            http: DexHttpClient::new("Phoenix", &config)?,
+        })
+    }
+
+}
+
+#[async_trait]
+impl DexInterface for PhoenixDex {
+    fn get_dex_type(&self) -> DexType {
+        DexType::Phoenix
+    }
+
+    fn get_name(&self) -> &str {
+        "Phoenix"
+    }
+
+    fn get_version(&self) -> &str {
+        "1.0.0"
+    }
+
+    async fn is_connected(&self) -> Result<bool> {
+        match self.http.get::<serde_json::Value>("/health").await {
+            Ok(_) => Ok(true),
+            Err(_) => Ok(false),
+        }
+    }
+
+    async fn get_pools(&self) -> Result<Vec<Pool>> {
+        let markets: Vec<PhoenixMarket> = self.http.get("/markets").await?;
+        let mut result = Vec::new();
+
+        for market in markets {
+            match parse_market(&market) {
+                Ok(pool) => result.push(pool),
+                Err(e) => tracing::warn!("Failed to parse Phoenix market: {}", e),
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn get_pools_by_tokens(&self, token_a: &Token, token_b: &Token) -> Result<Vec<Pool>> {
+        let all_pools = self.get_pools().await?;
+        Ok(all_pools
+            .into_iter()
+            .filter(|pool| {
+                (pool.token_a.mint == token_a.mint && pool.token_b.mint == token_b.mint)
+                    || (pool.token_a.mint == token_b.mint && pool.token_b.mint == token_a.mint)
+            })
+            .collect())
+    }
+
+    async fn get_pool_state(&self, pool_address: &Pubkey) -> Result<PoolState> {
+        let endpoint = format!("/market/{}", pool_address);
+        let market: PhoenixMarket = self.http.get(&endpoint).await?;
+        let pool = parse_market(&market)?;
+
+        let current_price = pool.get_price(&pool.token_a).unwrap_or(Decimal::ZERO);
+        let tvl = pool.reserve_a + pool.reserve_b;
+        Ok(PoolState {
+            pool,
+            current_price,
+            price_impact: Decimal::ZERO,
+            volume_24h: Decimal::ZERO,
+            tvl,
+            apy: None,
+        })
+    }
+
+    async fn get_token_price(&self, token: &Token, quote_token: &Token) -> Result<Decimal> {
+        let pools = self.get_pools_by_tokens(token, quote_token).await?;
+        let pool = pools.first().ok_or_else(|| DexError::PoolNotFound("No markets found for token pair".to_string()))?;
+        pool.get_price(token).ok_or_else(|| {
+            DexError::InsufficientLiquidity("Market has an empty side".to_string()).into()
+        })
+    }
+
+    async fn get_quote(
+        &self,
+        input_token: &Token,
+        output_token: &Token,
+        input_amount: Decimal,
+        pool_address: Option<&Pubkey>,
+    ) -> Result<PoolQuote> {
+        let pools = if let Some(addr) = pool_address {
+            vec![self.get_pool_state(addr).await?.pool]
+        } else {
+            self.get_pools_by_tokens(input_token, output_token).await?
+        };
+
+        let pool = pools.first().ok_or_else(|| DexError::PoolNotFound("No markets found for token pair".to_string()))?;
+        let output_amount = pool.calculate_output_amount(input_amount, input_token)
+            .ok_or_else(|| DexError::InsufficientLiquidity(format!("Book of {} cannot fill {}", pool.id, input_amount)))?;
+
+        let fee_amount = input_amount * pool.fee_rate;
+        let price_impact = pool.calculate_price_impact(input_amount, input_token)
+            .unwrap_or(Decimal::ZERO);
+
+        let minimum_output = output_amount * (Decimal::ONE - Decimal::from(5) / Decimal::from(1000));
+
+        Ok(PoolQuote {
+            pool: pool.clone(),
+            input_token: input_token.clone(),
+            output_token: output_token.clone(),
+            input_amount,
+            output_amount,
+            price_impact,
+            fee_amount,
+            minimum_output,
+            route: vec![pool.clone()],
+        })
+    }
+
+    async fn execute_swap(
+        &self,
+        quote: &PoolQuote,
+        _wallet: &Pubkey,
+        _slippage_tolerance: Decimal,
+        _options: &SendOptions,
+    ) -> Result<String> {
+        Err(DexError::TransactionFailed(format!("swaps on Phoenix market {} are not supported yet", quote.pool.id)).into())
+    }
+
+    async fn get_pool_metrics(&self, pool_address: &Pubkey) -> Result<PoolMetrics> {
+        let pool_state = self.get_pool_state(pool_address).await?;
+
+        Ok(PoolMetrics {
+            pool_id: pool_state.pool.id.clone(),
+            dex_type: DexType::Phoenix,
+            volume_24h: pool_state.volume_24h,
+            volume_7d: Decimal::ZERO,
+            tvl: pool_state.tvl,
+            fee_revenue_24h: Decimal::ZERO,
+            unique_traders_24h: 0,
+            timestamp: chrono::Utc::now(),
+        })
+    }
+
+    async fn get_dex_metrics(&self) -> Result<DexMetrics> {
+        let pools = self.get_pools().await?;
+        let total_tvl: Decimal = pools.iter().map(|p| p.reserve_a + p.reserve_b).sum();
+
+        Ok(DexMetrics {
+            total_volume_24h: Decimal::ZERO,
+            total_tvl,
+            total_pools: pools.len() as u64,
+            active_pools: pools.iter().filter(|p| p.is_active).count() as u64,
+            total_trades_24h: 0,
+            average_gas_price: Decimal::ZERO,
+        })
+    }
+
+    async fn subscribe_pool_updates(&self, _pool: &Pool) -> Result<PoolUpdateStream> {
+        Err(DexError::ConnectionFailed("Phoenix markets are polled, not streamed".to_string()).into())
+    }
+
+    async fn get_supported_tokens(&self) -> Result<Vec<Token>> {
+        Ok(Vec::new())
+    }
+
+    async fn validate_transaction(&self, _transaction_data: &[u8]) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_depth_prices_against_an_amm() {
+        let level = |price: &str, size: &str| (price.to_string(), size.to_string());
+        let market = PhoenixMarket {
+            id: "SOL/USDC".to_string(),
+            market_address: Pubkey::new_unique().to_string(),
+            base_mint: Pubkey::new_unique().to_string(),
+            quote_mint: Pubkey::new_unique().to_string(),
+            base_decimals: 9,
+            quote_decimals: 6,
+            taker_fee_bps: 0,
+            bids: vec![level("149", "10"), level("150", "5")],
+            asks: vec![level("151", "5"), level("152", "10")],
+        };
+        let book = parse_market(&market).unwrap();
+        let (sol, usdc) = (book.token_a.clone(), book.token_b.clone());
+
+        // Virtual reserves put the spot price at the mid
+        assert_eq!(book.get_price(&usdc), Some(Decimal::from(150) + Decimal::new(5, 1)));
+        // 5 SOL at 150, 3 at 149; an AMM at the same mid would slip further
+        assert_eq!(book.calculate_output_amount(Decimal::from(8), &sol), Some(Decimal::from(1197)));
+        let amm = Pool::new("amm".to_string(), DexType::Raydium, sol.clone(), usdc.clone(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default())
+            .update_reserves(book.reserve_a, book.reserve_b);
+        assert!(amm.calculate_output_amount(Decimal::from(8), &sol).unwrap() < Decimal::from(1197));
+        // Beyond the visible bids the market cannot fill
+        assert_eq!(book.calculate_output_amount(Decimal::from(16), &sol), None);
+        assert_eq!(book.calculate_price_impact(Decimal::from(5), &sol), Some(Decimal::ZERO));
+    }
+}
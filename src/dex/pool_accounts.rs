@@ -0,0 +1,213 @@
+use anyhow::Result;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+use crate::{
+    dex::{DexType, PoolUpdate, PoolUpdateStream},
+    models::Pool,
+    services::account_subscriber::{AccountSubscriber, AccountUpdate},
+};
+
+/// Reserves of a pool in whole tokens, decoded from its account data
+type ReserveDecoder = fn(&[u8], &Pool) -> Option<(Decimal, Decimal)>;
+
+/// Where a pool keeps the amounts its price follows from
+enum ReserveLayout {
+    /// Reserves are fields of the pool account itself
+    Inline(ReserveDecoder),
+    /// Reserves are the balances of two token vaults whose addresses sit at these offsets of the pool account
+    Vaults { vault_a: usize, vault_b: usize },
+}
+
+/// None for venues without pool accounts of their own
+fn reserve_layout(dex_type: &DexType) -> Option<ReserveLayout> {
+    Some(match dex_type {
+        // AmmInfo coin and pc vaults
+        DexType::Raydium => ReserveLayout::Vaults { vault_a: 336, vault_b: 368 },
+        // DLMM LbPair reserve_x and reserve_y
+        DexType::Meteora => ReserveLayout::Vaults { vault_a: 152, vault_b: 184 },
+        DexType::Whirlpool => ReserveLayout::Inline(whirlpool_reserves),
+        DexType::Pump => ReserveLayout::Inline(bonding_curve_reserves),
+        // SPL token-swap SwapV1 token_a and token_b, after the version byte
+        DexType::OrcaLegacy => ReserveLayout::Vaults { vault_a: 35, vault_b: 67 },
+        // Book depth is not a pair of balances; markets are refreshed from the API instead
+        DexType::Phoenix | DexType::Jupiter => return None,
+    })
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+fn read_u128(data: &[u8], offset: usize) -> Option<u128> {
+    Some(u128::from_le_bytes(data.get(offset..offset + 16)?.try_into().ok()?))
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    Some(Pubkey::new_from_array(data.get(offset..offset + 32)?.try_into().ok()?))
+}
+
+/// Raw on-chain amount in whole tokens
+fn ui_amount(raw: u64, decimals: u8) -> Decimal {
+    Decimal::from_i128_with_scale(raw as i128, decimals as u32)
+}
+
+/// Mint and amount of an SPL token account
+pub fn token_account_balance(data: &[u8]) -> Option<(Pubkey, u64)> {
+    Some((read_pubkey(data, 0)?, read_u64(data, 64)?))
+}
+
+/// Virtual reserves of a Whirlpool at its current price: `L / sqrt(P)` and `L * sqrt(P)`
+fn whirlpool_reserves(data: &[u8], pool: &Pool) -> Option<(Decimal, Decimal)> {
+    let liquidity = read_u128(data, 49)? as f64;
+    let sqrt_price = read_u128(data, 65)? as f64 / 2f64.powi(64);
+    if liquidity == 0.0 || sqrt_price == 0.0 {
+        return None;
+    }
+    let (mint_a, mint_b) = (read_pubkey(data, 101)?, read_pubkey(data, 181)?);
+    let raw = [(mint_a, liquidity / sqrt_price), (mint_b, liquidity * sqrt_price)];
+    let amount = |token: &crate::models::Token| {
+        let (_, raw) = raw.iter().find(|(mint, _)| *mint == token.mint)?;
+        Decimal::from_f64(raw / 10f64.powi(token.decimals as i32))
+    };
+    Some((amount(&pool.token_a)?, amount(&pool.token_b)?))
+}
+
+/// Virtual token and SOL reserves of a pump.fun bonding curve
+fn bonding_curve_reserves(data: &[u8], pool: &Pool) -> Option<(Decimal, Decimal)> {
+    let (token, sol) = (read_u64(data, 8)?, read_u64(data, 16)?);
+    let native = spl_token_interface::native_mint::id();
+    if pool.token_b.mint == native {
+        Some((ui_amount(token, pool.token_a.decimals), ui_amount(sol, pool.token_b.decimals)))
+    } else if pool.token_a.mint == native {
+        Some((ui_amount(sol, pool.token_a.decimals), ui_amount(token, pool.token_b.decimals)))
+    } else {
+        None
+    }
+}
+
+/// Wait on a receiver that may not exist yet
+async fn recv_optional(receiver: &mut Option<mpsc::Receiver<AccountUpdate>>) -> Option<AccountUpdate> {
+    match receiver {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Stream reserve changes of `pool`, decoded from its on-chain accounts.
+///
+/// Vault-based pools are resolved from the first pool account update, since the vaults are named there.
+pub fn subscribe_reserves(subscriber: &AccountSubscriber, pool: &Pool) -> Result<PoolUpdateStream> {
+    let layout = reserve_layout(&pool.dex_type)
+        .ok_or_else(|| anyhow::anyhow!("{} pool {} has no reserve layout", pool.dex_type, pool.id))?;
+    let (sender, receiver) = mpsc::channel(100);
+    let mut pool_updates = subscriber.subscribe(pool.pool_address)?;
+    let pool_address = pool.pool_address;
+    let subscriber = subscriber.clone();
+    let pool = pool.clone();
+
+    tokio::spawn(async move {
+        let send = |reserve_a, reserve_b| {
+            sender.try_send(PoolUpdate::ReserveChange { reserve_a, reserve_b, timestamp: chrono::Utc::now() })
+        };
+        match layout {
+            ReserveLayout::Inline(decode) => {
+                while let Some(update) = pool_updates.recv().await {
+                    let Some((reserve_a, reserve_b)) = decode(&update.data, &pool) else {
+                        debug!("Could not decode reserves of pool {}", pool.id);
+                        continue;
+                    };
+                    if let Err(mpsc::error::TrySendError::Closed(_)) = send(reserve_a, reserve_b) {
+                        break;
+                    }
+                }
+            }
+            ReserveLayout::Vaults { vault_a, vault_b } => {
+                let mut vaults = None;
+                let (mut updates_a, mut updates_b) = (None, None);
+                let (mut balance_a, mut balance_b) = (None, None);
+                loop {
+                    let update = tokio::select! {
+                        update = pool_updates.recv() => {
+                            let Some(update) = update else { break };
+                            let addresses = read_pubkey(&update.data, vault_a).zip(read_pubkey(&update.data, vault_b));
+                            if let Some((a, b)) = addresses.filter(|found| Some(*found) != vaults) {
+                                updates_a = subscriber.subscribe(a).ok();
+                                updates_b = subscriber.subscribe(b).ok();
+                                vaults = addresses;
+                            }
+                            continue;
+                        }
+                        Some(update) = recv_optional(&mut updates_a) => update,
+                        Some(update) = recv_optional(&mut updates_b) => update,
+                    };
+                    let Some((mint, amount)) = token_account_balance(&update.data) else {
+                        continue;
+                    };
+                    if mint == pool.token_a.mint {
+                        balance_a = Some(ui_amount(amount, pool.token_a.decimals));
+                    } else if mint == pool.token_b.mint {
+                        balance_b = Some(ui_amount(amount, pool.token_b.decimals));
+                    }
+                    if let (Some(reserve_a), Some(reserve_b)) = (balance_a, balance_b) {
+                        if let Err(mpsc::error::TrySendError::Closed(_)) = send(reserve_a, reserve_b) {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(PoolUpdateStream {
+        pool_address,
+        update_receiver: receiver,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Token;
+
+    fn pool(dex_type: DexType, token_a: Token, token_b: Token) -> Pool {
+        Pool::new("test".to_string(), dex_type, token_a, token_b, Pubkey::new_unique(), Pubkey::default(), Pubkey::default())
+    }
+
+    #[test]
+    fn test_decodes_reserves_from_account_data() {
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "Solana".to_string(), 9);
+        let usdc = Token::new(Pubkey::new_unique(), "USDC".to_string(), "USD Coin".to_string(), 6);
+
+        // SOL at 150 USDC: sqrt(P) in raw units is sqrt(150 * 10^6 / 10^9)
+        let sqrt_price = (0.15f64.sqrt() * 2f64.powi(64)) as u128;
+        let liquidity = 1_000_000_000_000u128;
+        let mut whirlpool = vec![0u8; 653];
+        whirlpool[49..65].copy_from_slice(&liquidity.to_le_bytes());
+        whirlpool[65..81].copy_from_slice(&sqrt_price.to_le_bytes());
+        whirlpool[101..133].copy_from_slice(sol.mint.as_ref());
+        whirlpool[181..213].copy_from_slice(usdc.mint.as_ref());
+        // Orientation follows the mints, not the order the API listed them in
+        let (reserve_usdc, reserve_sol) = whirlpool_reserves(&whirlpool, &pool(DexType::Whirlpool, usdc.clone(), sol.clone())).unwrap();
+        let price = reserve_usdc / reserve_sol;
+        assert!((price - Decimal::from(150)).abs() < Decimal::new(1, 6), "price {}", price);
+        assert!(whirlpool_reserves(&whirlpool[..100], &pool(DexType::Whirlpool, sol.clone(), usdc.clone())).is_none());
+
+        let meme = Token::new(Pubkey::new_unique(), "MEME".to_string(), "Meme".to_string(), 6);
+        let mut curve = vec![0u8; 49];
+        curve[8..16].copy_from_slice(&1_073_000_000_000_000u64.to_le_bytes());
+        curve[16..24].copy_from_slice(&30_000_000_000u64.to_le_bytes());
+        let (tokens, lamports) = bonding_curve_reserves(&curve, &pool(DexType::Pump, meme.clone(), sol.clone())).unwrap();
+        assert_eq!(tokens, Decimal::from(1_073_000_000));
+        assert_eq!(lamports, Decimal::from(30));
+
+        let mut vault = vec![0u8; 165];
+        vault[..32].copy_from_slice(usdc.mint.as_ref());
+        vault[64..72].copy_from_slice(&2_500_000u64.to_le_bytes());
+        assert_eq!(token_account_balance(&vault), Some((usdc.mint, 2_500_000)));
+        assert_eq!(ui_amount(2_500_000, usdc.decimals), Decimal::new(25, 1));
+    }
+}
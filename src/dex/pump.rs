@@ -1,20 +1,20 @@
 use async_trait::async_trait;
 use anyhow::Result;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use solana_program::pubkey::Pubkey;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tokio::time::{timeout, Duration};
 
 use crate::{
-    dex::{DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, PoolUpdate, DexType},
-    models::{Token, Pool, PoolQuote, PoolState, PoolMetrics},
+    dex::{pool_accounts, DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, PoolUpdate, DexType},
+    dex::http::DexHttpClient,
+    models::{Token, Pool, PoolQuote, PoolState, PoolMetrics, SendOptions},
+    services::account_subscriber::AccountSubscriber,
 };
 
 pub struct PumpDex {
-    config: DexConnectionConfig,
-    client: Client,
-    base_url: String,
+    http: DexHttpClient,
+    subscriber: Option<AccountSubscriber>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -30,41 +30,203 @@ struct PumpPool {
     pool_address: String,
     authority: String,
     program_id: String,
+    /// Bonding curve state in raw units; quotes fall back to the reserves without it
+    #[serde(default)]
+    virtual_token_reserves: Option<String>,
+    #[serde(default)]
+    virtual_sol_reserves: Option<String>,
+    #[serde(default)]
+    real_token_reserves: Option<String>,
+    #[serde(default)]
+    real_sol_reserves: Option<String>,
+    #[serde(default)]
+    complete: bool,
+}
+
+/// Fee on the SOL side of every curve trade
+const FEE_BASIS_POINTS: u64 = 100;
+/// Real SOL a curve collects before its liquidity migrates to an AMM
+pub const GRADUATION_LAMPORTS: u64 = 85_000_000_000;
+
+/// A pump.fun bonding curve in raw units: a constant product over virtual reserves, bounded by
+/// the tokens and SOL it really holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BondingCurve {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub complete: bool,
+}
+
+/// A trade on the curve in whole tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurveFill {
+    pub output_amount: Decimal,
+    /// In SOL
+    pub fee_amount: Decimal,
+    pub price_impact: Decimal,
+}
+
+fn raw_amount(amount: Decimal, decimals: u8) -> Option<u64> {
+    (amount * Decimal::from(10u64.checked_pow(decimals as u32)?)).trunc().to_u64()
+}
+
+fn ui_amount(raw: u64, decimals: u8) -> Decimal {
+    Decimal::from_i128_with_scale(raw as i128, decimals as u32)
+}
+
+impl BondingCurve {
+    /// Completed curves, and those past the threshold, no longer trade on the curve
+    pub fn is_graduated(&self) -> bool {
+        self.complete || self.real_token_reserves == 0 || self.real_sol_reserves >= GRADUATION_LAMPORTS
+    }
+
+    /// Lamports per raw token at the current point of the curve
+    pub fn spot_price(&self) -> Option<Decimal> {
+        (self.virtual_token_reserves > 0)
+            .then(|| Decimal::from(self.virtual_sol_reserves) / Decimal::from(self.virtual_token_reserves))
+    }
+
+    fn fee(lamports: u64) -> u64 {
+        (lamports as u128 * FEE_BASIS_POINTS as u128 / 10_000) as u64
+    }
+
+    /// Tokens bought with `lamports`, fee included; None when the curve has fewer tokens left
+    pub fn buy(&self, lamports: u64) -> Option<u64> {
+        let net = (lamports - Self::fee(lamports)) as u128;
+        let (tokens, sol) = (self.virtual_token_reserves as u128, self.virtual_sol_reserves as u128);
+        if net == 0 {
+            return None;
+        }
+        let out = u64::try_from(tokens * net / (sol + net)).ok()?;
+        (out <= self.real_token_reserves).then_some(out)
+    }
+
+    /// Lamports `tokens` sell for before the fee; None when the curve holds less SOL
+    fn sell_gross(&self, tokens: u64) -> Option<u64> {
+        let (virtual_tokens, sol) = (self.virtual_token_reserves as u128, self.virtual_sol_reserves as u128);
+        if tokens == 0 {
+            return None;
+        }
+        let gross = u64::try_from(sol * tokens as u128 / (virtual_tokens + tokens as u128)).ok()?;
+        (gross <= self.real_sol_reserves).then_some(gross)
+    }
+
+    /// Lamports received for `tokens` after the fee; None when the curve holds less SOL
+    pub fn sell(&self, tokens: u64) -> Option<u64> {
+        self.sell_gross(tokens).map(|gross| gross - Self::fee(gross))
+    }
+
+    /// SOL and curve token of `pool`, in that order
+    fn sides(pool: &Pool) -> Option<(&Token, &Token)> {
+        let native = spl_token_interface::native_mint::id();
+        if pool.token_a.mint == native {
+            Some((&pool.token_a, &pool.token_b))
+        } else if pool.token_b.mint == native {
+            Some((&pool.token_b, &pool.token_a))
+        } else {
+            None
+        }
+    }
+
+    /// Spot price of `token` in whole units of the other side of `pool`
+    pub fn price_of(&self, pool: &Pool, token: &Token) -> Option<Decimal> {
+        let (sol, curve_token) = Self::sides(pool)?;
+        let sol_per_token = self.spot_price()? * ui_amount(1, sol.decimals) / ui_amount(1, curve_token.decimals);
+        if token.mint == curve_token.mint {
+            Some(sol_per_token)
+        } else if token.mint == sol.mint && sol_per_token > Decimal::ZERO {
+            Some(Decimal::ONE / sol_per_token)
+        } else {
+            None
+        }
+    }
+
+    /// Trade `input_amount` of `input_token` on the curve backing `pool`, one side of which is SOL
+    pub fn fill(&self, pool: &Pool, input_token: &Token, input_amount: Decimal) -> Option<CurveFill> {
+        let (sol, token) = Self::sides(pool)?;
+        let before = self.spot_price()?;
+        let (output_amount, fee, after) = if input_token.mint == sol.mint {
+            let lamports = raw_amount(input_amount, sol.decimals)?;
+            let out = self.buy(lamports)?;
+            let net = lamports - Self::fee(lamports);
+            let after = Decimal::from(self.virtual_sol_reserves + net)
+                / Decimal::from(self.virtual_token_reserves.checked_sub(out).filter(|left| *left > 0)?);
+            (ui_amount(out, token.decimals), Self::fee(lamports), after)
+        } else if input_token.mint == token.mint {
+            let tokens = raw_amount(input_amount, token.decimals)?;
+            let gross = self.sell_gross(tokens)?;
+            let after = Decimal::from(self.virtual_sol_reserves - gross) / Decimal::from(self.virtual_token_reserves + tokens);
+            (ui_amount(gross - Self::fee(gross), sol.decimals), Self::fee(gross), after)
+        } else {
+            return None;
+        };
+        Some(CurveFill {
+            output_amount,
+            fee_amount: ui_amount(fee, sol.decimals),
+            price_impact: ((after - before) / before).abs(),
+        })
+    }
+}
+
+/// Bonding curve of a pool as the API reports it, when it reports all of it
+fn parse_curve(pool: &PumpPool) -> Result<Option<BondingCurve>> {
+    let (Some(virtual_tokens), Some(virtual_sol), Some(real_tokens), Some(real_sol)) = (
+        &pool.virtual_token_reserves,
+        &pool.virtual_sol_reserves,
+        &pool.real_token_reserves,
+        &pool.real_sol_reserves,
+    ) else {
+        return Ok(None);
+    };
+    Ok(Some(BondingCurve {
+        virtual_token_reserves: virtual_tokens.parse()?,
+        virtual_sol_reserves: virtual_sol.parse()?,
+        real_token_reserves: real_tokens.parse()?,
+        real_sol_reserves: real_sol.parse()?,
+        complete: pool.complete,
+    }))
 }
 
 impl PumpDex {
     pub fn new(config: DexConnectionConfig) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
-            .build()?;
-
-        let base_url = config.base_url.clone();
         Ok(Self {
-            config,
-            client,
-            base_url,
+            http: DexHttpClient::new("Pump", &config)?,
+            subscriber: None,
         })
     }
 
-    async fn make_request<T>(&self, endpoint: &str) -> Result<T>
-    where
-        T: for<'de> Deserialize<'de>,
-    {
-        let url = format!("{}{}", self.base_url, endpoint);
-        let response = timeout(
-            Duration::from_secs(self.config.timeout_seconds),
-            self.client.get(&url).send()
-        ).await??;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            return Err(DexError::InvalidResponse(
-                format!("HTTP {}: {}", status, response.text().await?)
-            ).into());
-        }
+    /// Stream pool reserve changes over the shared account websocket
+    pub fn with_subscriber(mut self, subscriber: AccountSubscriber) -> Self {
+        self.subscriber = Some(subscriber);
+        self
+    }
 
-        let data: T = response.json().await?;
-        Ok(data)
+    /// Pools for the pair, or the one at `pool_address`, with their curves
+    async fn curve_pools(&self, token_a: &Token, token_b: &Token, pool_address: Option<&Pubkey>) -> Result<Vec<(Pool, Option<BondingCurve>)>> {
+        let raw: Vec<PumpPool> = match pool_address {
+            Some(addr) => vec![self.http.get(&format!("/pool/{}", addr)).await?],
+            None => self.http.get("/pools").await?,
+        };
+        let mut result = Vec::new();
+        for pool in raw {
+            let parsed = match self.parse_pool(&pool).and_then(|p| Ok((p, parse_curve(&pool)?))) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    tracing::warn!("Failed to parse Pump pool: {}", e);
+                    continue;
+                }
+            };
+            let mints = (parsed.0.token_a.mint, parsed.0.token_b.mint);
+            if mints == (token_a.mint, token_b.mint) || mints == (token_b.mint, token_a.mint) {
+                result.push(parsed);
+            }
+        }
+        if result.is_empty() {
+            return Err(DexError::PoolNotFound("No pools found for token pair".to_string()).into());
+        }
+        Ok(result)
     }
 
     fn parse_pool(&self, pump_pool: &PumpPool) -> Result<Pool> {
@@ -88,11 +250,27 @@ impl PumpDex {
             pump_pool.quote_decimals,
         );
 
-        let reserve_a = pump_pool.base_reserve.parse::<Decimal>()?;
-        let reserve_b = pump_pool.quote_reserve.parse::<Decimal>()?;
-        let fee_rate = pump_pool.fee_rate.parse::<Decimal>()?;
+        let mut reserve_a = pump_pool.base_reserve.parse::<Decimal>()?;
+        let mut reserve_b = pump_pool.quote_reserve.parse::<Decimal>()?;
+        let mut fee_rate = pump_pool.fee_rate.parse::<Decimal>()?;
+        let curve = parse_curve(pump_pool)?;
+
+        // The curve's virtual reserves price it like any constant product pool
+        if let Some(curve) = &curve {
+            let native = spl_token_interface::native_mint::id();
+            let sol_reserve = |decimals| ui_amount(curve.virtual_sol_reserves, decimals);
+            let token_reserve = |decimals| ui_amount(curve.virtual_token_reserves, decimals);
+            (reserve_a, reserve_b) = if quote_mint == native {
+                (token_reserve(base_token.decimals), sol_reserve(quote_token.decimals))
+            } else if base_mint == native {
+                (sol_reserve(base_token.decimals), token_reserve(quote_token.decimals))
+            } else {
+                return Err(DexError::InvalidResponse(format!("Pump pool {} has no SOL side", pump_pool.id)).into());
+            };
+            fee_rate = Decimal::new(FEE_BASIS_POINTS as i64, 4);
+        }
 
-        Ok(Pool::new(
+        let mut pool = Pool::new(
             pump_pool.id.clone(),
             DexType::Pump,
             base_token,
@@ -100,7 +278,12 @@ impl PumpDex {
             pool_address,
             authority,
             program_id,
-        ).update_reserves(reserve_a, reserve_b).with_fee_rate(fee_rate))
+        ).update_reserves(reserve_a, reserve_b).with_fee_rate(fee_rate);
+        if curve.is_some_and(|curve| curve.is_graduated()) {
+            pool.is_graduated = true;
+            pool.is_active = false;
+        }
+        Ok(pool)
     }
 }
 
@@ -119,14 +302,14 @@ impl DexInterface for PumpDex {
     }
 
     async fn is_connected(&self) -> Result<bool> {
-        match self.make_request::<serde_json::Value>("/health").await {
+        match self.http.get::<serde_json::Value>("/health").await {
             Ok(_) => Ok(true),
             Err(_) => Ok(false),
         }
     }
 
     async fn get_pools(&self) -> Result<Vec<Pool>> {
-        let pools: Vec<PumpPool> = self.make_request("/pools").await?;
+        let pools: Vec<PumpPool> = self.http.get("/pools").await?;
         let mut result = Vec::new();
         
         for pool in pools {
@@ -154,7 +337,7 @@ impl DexInterface for PumpDex {
 
     async fn get_pool_state(&self, pool_address: &Pubkey) -> Result<PoolState> {
         let endpoint = format!("/pool/{}", pool_address);
-        let pool_data: PumpPool = self.make_request(&endpoint).await?;
+        let pool_data: PumpPool = self.http.get(&endpoint).await?;
         let pool = self.parse_pool(&pool_data)?;
         
         let current_price = pool.get_price(&pool.token_a).unwrap_or(Decimal::ZERO);
@@ -171,14 +354,14 @@ impl DexInterface for PumpDex {
         })
     }
 
+    /// Price of `token` in `quote_token` at the current point of the curve
     async fn get_token_price(&self, token: &Token, quote_token: &Token) -> Result<Decimal> {
-        let pools = self.get_pools_by_tokens(token, quote_token).await?;
-        if pools.is_empty() {
-            return Err(DexError::PoolNotFound("No pools found for token pair".to_string()).into());
-        }
-        
-        let pool = &pools[0];
-        pool.get_price(token).ok_or_else(|| {
+        let (pool, curve) = self.curve_pools(token, quote_token, None).await?.remove(0);
+        let price = match curve {
+            Some(curve) => curve.price_of(&pool, token),
+            None => pool.get_price(token),
+        };
+        price.ok_or_else(|| {
             DexError::InsufficientLiquidity("Cannot calculate price from pool".to_string()).into()
         })
     }
@@ -190,28 +373,32 @@ impl DexInterface for PumpDex {
         input_amount: Decimal,
         pool_address: Option<&Pubkey>,
     ) -> Result<PoolQuote> {
-        let pools = if let Some(addr) = pool_address {
-            vec![self.get_pool_state(addr).await?.pool]
-        } else {
-            self.get_pools_by_tokens(input_token, output_token).await?
-        };
+        let pools = self.curve_pools(input_token, output_token, pool_address).await?;
+        let (pool, curve) = pools[0].clone();
+        let route: Vec<Pool> = pools.into_iter().map(|(pool, _)| pool).collect();
 
-        if pools.is_empty() {
-            return Err(DexError::PoolNotFound("No pools found for token pair".to_string()).into());
-        }
+        let (output_amount, fee_amount, price_impact) = match curve {
+            Some(curve) if curve.is_graduated() => {
+                return Err(DexError::PoolNotFound(format!("Pump pool {} has graduated off its bonding curve", pool.id)).into());
+            }
+            Some(curve) => {
+                let fill = curve.fill(&pool, input_token, input_amount)
+                    .ok_or_else(|| DexError::InsufficientLiquidity("Trade exceeds what the bonding curve holds".to_string()))?;
+                (fill.output_amount, fill.fee_amount, fill.price_impact)
+            }
+            None => {
+                let output_amount = pool.calculate_output_amount(input_amount, input_token)
+                    .ok_or_else(|| DexError::InsufficientLiquidity("Cannot calculate output amount".to_string()))?;
+                let price_impact = pool.calculate_price_impact(input_amount, input_token)
+                    .unwrap_or(Decimal::ZERO);
+                (output_amount, input_amount * pool.fee_rate, price_impact)
+            }
+        };
 
-        let pool = &pools[0];
-        let output_amount = pool.calculate_output_amount(input_amount, input_token)
-            .ok_or_else(|| DexError::InsufficientLiquidity("Cannot calculate output amount".to_string()))?;
-        
-        let fee_amount = input_amount * pool.fee_rate;
-        let price_impact = pool.calculate_price_impact(input_amount, input_token)
-            .unwrap_or(Decimal::ZERO);
-        
         let minimum_output = output_amount * (Decimal::ONE - Decimal::from(5) / Decimal::from(1000));
 
         Ok(PoolQuote {
-            pool: pool.clone(),
+            pool,
             input_token: input_token.clone(),
             output_token: output_token.clone(),
             input_amount,
@@ -219,7 +406,7 @@ impl DexInterface for PumpDex {
             price_impact,
             fee_amount,
             minimum_output,
-            route: pools,
+            route,
         })
     }
 
@@ -228,6 +415,7 @@ impl DexInterface for PumpDex {
         quote: &PoolQuote,
         wallet: &Pubkey,
         slippage_tolerance: Decimal,
+        _options: &SendOptions,
     ) -> Result<String> {
         tracing::info!("Executing Pump swap for wallet: {}", wallet);
         Ok("mock_transaction_signature".to_string())
@@ -262,13 +450,11 @@ impl DexInterface for PumpDex {
         })
     }
 
-    async fn subscribe_pool_updates(&self, pool_address: &Pubkey) -> Result<PoolUpdateStream> {
-        let (_, receiver) = tokio::sync::mpsc::channel(100);
-        
-        Ok(PoolUpdateStream {
-            pool_address: *pool_address,
-            update_receiver: receiver,
-        })
+    async fn subscribe_pool_updates(&self, pool: &Pool) -> Result<PoolUpdateStream> {
+        match &self.subscriber {
+            Some(subscriber) => pool_accounts::subscribe_reserves(subscriber, pool),
+            None => Err(DexError::ConnectionFailed("websocket subscriptions are disabled".to_string()).into()),
+        }
     }
 
     async fn get_supported_tokens(&self) -> Result<Vec<Token>> {
@@ -279,3 +465,68 @@ impl DexInterface for PumpDex {
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_prices_fills_and_graduation() {
+        let dex = PumpDex::new(DexConnectionConfig {
+            base_url: String::new(),
+            api_key: None,
+            timeout_seconds: 1,
+            max_retries: 0,
+            rate_limit: 0,
+        })
+        .unwrap();
+        let native = spl_token_interface::native_mint::id();
+        // A fresh curve: 1.073B virtual tokens against 30 virtual SOL, 793.1M tokens for sale
+        let mut raw = PumpPool {
+            id: "curve".to_string(),
+            base_mint: Pubkey::new_unique().to_string(),
+            quote_mint: native.to_string(),
+            base_decimals: 6,
+            quote_decimals: 9,
+            base_reserve: "0".to_string(),
+            quote_reserve: "0".to_string(),
+            fee_rate: "0".to_string(),
+            pool_address: Pubkey::new_unique().to_string(),
+            authority: Pubkey::new_unique().to_string(),
+            program_id: Pubkey::new_unique().to_string(),
+            virtual_token_reserves: Some("1073000000000000".to_string()),
+            virtual_sol_reserves: Some("30000000000".to_string()),
+            real_token_reserves: Some("793100000000000".to_string()),
+            real_sol_reserves: Some("0".to_string()),
+            complete: false,
+        };
+        let curve = parse_curve(&raw).unwrap().unwrap();
+        let pool = dex.parse_pool(&raw).unwrap();
+        assert_eq!((pool.reserve_a, pool.reserve_b), (Decimal::from(1_073_000_000), Decimal::from(30)));
+        assert!(!pool.is_graduated && pool.is_active);
+        let (token, sol) = (pool.token_a.clone(), pool.token_b.clone());
+
+        // Roughly 28 nanoSOL a token, and the inverse the other way
+        let price = curve.price_of(&pool, &token).unwrap();
+        assert_eq!(price.round_dp(12), Decimal::new(27959, 12));
+        assert_eq!((curve.price_of(&pool, &sol).unwrap() * price).round_dp(9), Decimal::ONE);
+
+        // Buying with 1 SOL pays a 0.01 SOL fee and lands where the pool's own math does
+        let buy = curve.fill(&pool, &sol, Decimal::ONE).unwrap();
+        assert_eq!(buy.fee_amount, Decimal::new(1, 2));
+        let expected = pool.calculate_output_amount(Decimal::ONE, &sol).unwrap();
+        assert!((buy.output_amount - expected).abs() < Decimal::new(1, 5));
+        assert!(buy.price_impact > Decimal::new(6, 2) && buy.price_impact < Decimal::new(7, 2));
+
+        // Selling needs SOL actually in the curve; more tokens than are left cannot be bought
+        assert_eq!(curve.fill(&pool, &token, Decimal::from(1_000_000)), None);
+        let funded = BondingCurve { real_sol_reserves: 10_000_000_000, ..curve };
+        let sell = funded.fill(&pool, &token, buy.output_amount).unwrap();
+        assert!(sell.output_amount < Decimal::new(99, 2));
+        assert_eq!(curve.fill(&pool, &sol, Decimal::from(100)), None);
+
+        raw.real_sol_reserves = Some(GRADUATION_LAMPORTS.to_string());
+        let graduated = dex.parse_pool(&raw).unwrap();
+        assert!(graduated.is_graduated && !graduated.is_active);
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::dex::interface::{DexConnectionConfig, DexError};
+use crate::utils::panic_guard::lock_or_recover;
+
+/// Token bucket pacing one DEX client's API calls to `rate_limit` requests per minute.
+///
+/// Up to a second's worth of calls go out at once. Calls beyond that reserve the next token and
+/// wait for it, so they are served in order; once the wait would exceed the request timeout the
+/// queue counts as saturated and the call fails with `DexError::RateLimitExceeded`.
+pub struct DexRateLimiter {
+    name: String,
+    per_second: f64,
+    burst: f64,
+    max_wait: Duration,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    /// Negative while calls are waiting on reserved tokens
+    tokens: f64,
+    updated: Instant,
+}
+
+impl DexRateLimiter {
+    /// Limiter for `rate_limit` requests per minute; zero leaves calls unlimited
+    pub fn new(name: &str, rate_limit: u32, max_wait: Duration) -> Self {
+        let per_second = f64::from(rate_limit) / 60.0;
+        let burst = per_second.max(1.0);
+        Self {
+            name: name.to_string(),
+            per_second,
+            burst,
+            max_wait,
+            bucket: Mutex::new(Bucket { tokens: burst, updated: Instant::now() }),
+        }
+    }
+
+    /// Limiter for a DEX client, queuing calls for at most its request timeout
+    pub fn from_config(name: &str, config: &DexConnectionConfig) -> Self {
+        Self::new(name, config.rate_limit, Duration::from_secs(config.timeout_seconds))
+    }
+
+    /// Take a token at `now`, returning how long the caller must wait before using it
+    fn reserve(&self, now: Instant) -> Result<Duration, DexError> {
+        if self.per_second <= 0.0 {
+            return Ok(Duration::ZERO);
+        }
+        let mut bucket = lock_or_recover(&self.bucket, "rate limiter");
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.burst);
+        bucket.updated = now;
+
+        let wait = Duration::from_secs_f64(((1.0 - bucket.tokens) / self.per_second).max(0.0));
+        if wait > self.max_wait {
+            return Err(DexError::RateLimitExceeded(format!(
+                "{} is limited to {} requests per minute and its queue is {:.1}s long",
+                self.name,
+                self.per_second * 60.0,
+                wait.as_secs_f64()
+            )));
+        }
+        bucket.tokens -= 1.0;
+        Ok(wait)
+    }
+
+    /// Wait for a token; fails without waiting when the queue is saturated
+    pub async fn acquire(&self) -> Result<(), DexError> {
+        let wait = self.reserve(Instant::now())?;
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calls_past_the_burst_queue_until_the_timeout() {
+        // Two requests a second, waiting at most a second for a token
+        let limiter = DexRateLimiter::new("test", 120, Duration::from_secs(1));
+        let start = Instant::now();
+
+        assert_eq!(limiter.reserve(start).unwrap(), Duration::ZERO);
+        assert_eq!(limiter.reserve(start).unwrap(), Duration::ZERO);
+        assert_eq!(limiter.reserve(start).unwrap(), Duration::from_millis(500));
+        assert_eq!(limiter.reserve(start).unwrap(), Duration::from_secs(1));
+        assert!(matches!(limiter.reserve(start), Err(DexError::RateLimitExceeded(_))));
+
+        // Tokens come back at the configured rate and a refused call reserves nothing
+        assert_eq!(limiter.reserve(start + Duration::from_secs(1)).unwrap(), Duration::from_millis(500));
+
+        let unlimited = DexRateLimiter::new("test", 0, Duration::ZERO);
+        for _ in 0..100 {
+            assert_eq!(unlimited.reserve(start).unwrap(), Duration::ZERO);
+        }
+    }
+}
@@ -2,20 +2,24 @@ use async_trait::async_trait;
 use anyhow::Result;
 use rust_decimal::Decimal;
 use solana_program::pubkey::Pubkey;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tokio::time::{timeout, Duration};
+
+use solana_sdk::{signature::Signer, transaction::Transaction};
 
 use crate::{
-    dex::{DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, PoolUpdate, DexType},
-    models::{Token, Pool, PoolQuote, PoolState, PoolMetrics},
+    dex::{pool_accounts, DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, PoolUpdate, DexType, SwapSigner, DRY_RUN_SIGNATURE_PREFIX},
+    dex::http::DexHttpClient,
+    dex::raydium_amm::{swap_base_in, AmmKeys, MarketKeys, SwapAccounts, RAYDIUM_AMM_V4},
+    models::{Token, Pool, PoolQuote, PoolState, PoolMetrics, SendOptions},
+    services::{account_subscriber::AccountSubscriber, wallet::Wallets},
+    utils::precision::SwapAmounts,
 };
 
 pub struct RaydiumDex {
-    config: DexConnectionConfig,
-    client: Client,
-    base_url: String,
+    http: DexHttpClient,
+    signer: Option<SwapSigner>,
+    subscriber: Option<AccountSubscriber>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -50,36 +54,101 @@ struct RaydiumQuote {
 
 impl RaydiumDex {
     pub fn new(config: DexConnectionConfig) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
-            .build()?;
-
         Ok(Self {
-            config: config.clone(),
-            client,
-            base_url: config.base_url.clone(),
+            http: DexHttpClient::new("Raydium", &config)?,
+            signer: None,
+            subscriber: None,
         })
     }
 
-    async fn make_request<T>(&self, endpoint: &str) -> Result<T>
-    where
-        T: for<'de> Deserialize<'de>,
-    {
-        let url = format!("{}{}", self.base_url, endpoint);
-        let response = timeout(
-            Duration::from_secs(self.config.timeout_seconds),
-            self.client.get(&url).send()
-        ).await??;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            return Err(DexError::InvalidResponse(
-                format!("HTTP {}: {}", status, response.text().await?)
-            ).into());
+    /// Sign and submit swaps with the given wallet
+    pub fn with_signer(mut self, signer: SwapSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Stream pool reserve changes over the shared account websocket
+    pub fn with_subscriber(mut self, subscriber: AccountSubscriber) -> Self {
+        self.subscriber = Some(subscriber);
+        self
+    }
+
+    /// The signer and the wallets it holds for `wallet`
+    fn signer_for(&self, wallet: &Pubkey) -> Result<(&SwapSigner, &Wallets)> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or_else(|| DexError::AuthenticationFailed("No wallet configured to sign Raydium swaps".to_string()))?;
+        Ok((signer, signer.wallets_for(wallet)?))
+    }
+
+    /// Build and sign a v4 `SwapBaseIn` transaction for the quote
+    async fn build_signed_swap(
+        &self,
+        signer: &SwapSigner,
+        wallets: &Wallets,
+        quote: &PoolQuote,
+        slippage_tolerance: Decimal,
+    ) -> Result<Transaction> {
+        let pool_address = quote.pool.pool_address;
+        let amm_account = signer
+            .solana
+            .get_account_info(&pool_address)
+            .await?
+            .ok_or_else(|| DexError::PoolNotFound(pool_address.to_string()))?;
+        if amm_account.owner != RAYDIUM_AMM_V4 {
+            return Err(DexError::InvalidResponse(format!(
+                "Pool {} is owned by {}, not the Raydium v4 program",
+                pool_address, amm_account.owner
+            ))
+            .into());
+        }
+        let amm = AmmKeys::parse(pool_address, amm_account.owner, &amm_account.data)?;
+
+        let market_account = signer
+            .solana
+            .get_account_info(&amm.market_id)
+            .await?
+            .ok_or_else(|| DexError::InvalidResponse(format!("Market {} not found", amm.market_id)))?;
+        let market = MarketKeys::parse(&amm.market_id, &amm.market_program_id, &market_account.data)?;
+
+        let (input_mint, output_mint) = (quote.input_token.mint, quote.output_token.mint);
+        let pool_mints = [amm.base_mint, amm.quote_mint];
+        if !pool_mints.contains(&input_mint) || !pool_mints.contains(&output_mint) || input_mint == output_mint {
+            return Err(DexError::InvalidResponse(format!(
+                "Pool {} does not swap {} for {}",
+                pool_address, input_mint, output_mint
+            ))
+            .into());
         }
 
-        let data: T = response.json().await?;
-        Ok(data)
+        // The v4 program passes every transfer through the classic token program
+        if let Some(token) = [&quote.input_token, &quote.output_token].into_iter().find(|token| token.is_token_2022()) {
+            return Err(DexError::InvalidResponse(format!(
+                "Pool {} cannot swap Token-2022 mint {}",
+                pool_address, token.mint
+            ))
+            .into());
+        }
+
+        let owner = wallets.owner().pubkey();
+        let user = SwapAccounts {
+            source: quote.input_token.associated_token_address(&owner),
+            destination: quote.output_token.associated_token_address(&owner),
+            owner,
+        };
+        let amounts = SwapAmounts::new(
+            &signer.precision,
+            &quote.input_token,
+            quote.input_amount,
+            &quote.output_token,
+            quote.output_amount,
+            slippage_tolerance,
+        )?;
+        let instruction = swap_base_in(&amm, &market, &user, amounts.amount_in, amounts.min_amount_out);
+
+        let blockhash = signer.solana.get_recent_blockhash().await?;
+        wallets.build_transaction(&[instruction], blockhash)
     }
 
     fn parse_pool(&self, raydium_pool: &RaydiumPool) -> Result<Pool> {
@@ -134,14 +203,14 @@ impl DexInterface for RaydiumDex {
     }
 
     async fn is_connected(&self) -> Result<bool> {
-        match self.make_request::<serde_json::Value>("/health").await {
+        match self.http.get::<serde_json::Value>("/health").await {
             Ok(_) => Ok(true),
             Err(_) => Ok(false),
         }
     }
 
     async fn get_pools(&self) -> Result<Vec<Pool>> {
-        let pools: Vec<RaydiumPool> = self.make_request("/pools").await?;
+        let pools: Vec<RaydiumPool> = self.http.get("/pools").await?;
         let mut result = Vec::new();
         
         for pool in pools {
@@ -169,7 +238,7 @@ impl DexInterface for RaydiumDex {
 
     async fn get_pool_state(&self, pool_address: &Pubkey) -> Result<PoolState> {
         let endpoint = format!("/pool/{}", pool_address);
-        let pool_data: RaydiumPool = self.make_request(&endpoint).await?;
+        let pool_data: RaydiumPool = self.http.get(&endpoint).await?;
         let pool = self.parse_pool(&pool_data)?;
         
         let current_price = pool.get_price(&pool.token_a).unwrap_or(Decimal::ZERO);
@@ -243,11 +312,27 @@ impl DexInterface for RaydiumDex {
         quote: &PoolQuote,
         wallet: &Pubkey,
         slippage_tolerance: Decimal,
+        options: &SendOptions,
     ) -> Result<String> {
-        // This would integrate with Raydium's swap program
-        // For now, return a mock transaction signature
-        tracing::info!("Executing Raydium swap for wallet: {}", wallet);
-        Ok("mock_transaction_signature".to_string())
+        let (signer, wallets) = self.signer_for(wallet)?;
+        let transaction = self.build_signed_swap(signer, wallets, quote, slippage_tolerance).await?;
+        if signer.dry_run {
+            tracing::info!("Dry run: built Raydium swap {} for wallet {} without sending", transaction.signatures[0], wallet);
+            return Ok(format!("{}{}", DRY_RUN_SIGNATURE_PREFIX, transaction.signatures[0]));
+        }
+        let signature = signer.sender.send(&transaction, options).await?;
+        tracing::info!("Submitted Raydium swap {} for wallet {}", signature, wallet);
+        Ok(signature.to_string())
+    }
+
+    async fn build_swap_transaction(
+        &self,
+        quote: &PoolQuote,
+        wallet: &Pubkey,
+        slippage_tolerance: Decimal,
+    ) -> Result<Transaction> {
+        let (signer, wallets) = self.signer_for(wallet)?;
+        self.build_signed_swap(signer, wallets, quote, slippage_tolerance).await
     }
 
     async fn get_pool_metrics(&self, pool_address: &Pubkey) -> Result<PoolMetrics> {
@@ -279,15 +364,11 @@ impl DexInterface for RaydiumDex {
         })
     }
 
-    async fn subscribe_pool_updates(&self, pool_address: &Pubkey) -> Result<PoolUpdateStream> {
-        // This would implement WebSocket subscription to Raydium
-        // For now, return a mock stream
-        let (_, receiver) = tokio::sync::mpsc::channel(100);
-        
-        Ok(PoolUpdateStream {
-            pool_address: *pool_address,
-            update_receiver: receiver,
-        })
+    async fn subscribe_pool_updates(&self, pool: &Pool) -> Result<PoolUpdateStream> {
+        match &self.subscriber {
+            Some(subscriber) => pool_accounts::subscribe_reserves(subscriber, pool),
+            None => Err(DexError::ConnectionFailed("websocket subscriptions are disabled".to_string()).into()),
+        }
     }
 
     async fn get_supported_tokens(&self) -> Result<Vec<Token>> {
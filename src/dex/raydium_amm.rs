@@ -0,0 +1,192 @@
+use anyhow::Result;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// Raydium liquidity pool v4 program
+pub const RAYDIUM_AMM_V4: Pubkey = Pubkey::from_str_const("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+/// `SwapBaseIn` instruction tag
+const SWAP_BASE_IN: u8 = 9;
+
+/// Seed of the PDA that owns every v4 pool vault
+const AUTHORITY_SEED: &[u8] = b"amm authority";
+
+/// Size of the v4 `AmmInfo` account
+pub const AMM_INFO_LEN: usize = 752;
+
+/// Size of an OpenBook/Serum v3 market account, including the 5-byte head and 7-byte tail padding
+pub const MARKET_STATE_LEN: usize = 388;
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().expect("8 bytes"))
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().expect("32 bytes"))
+}
+
+/// Accounts of a v4 pool, decoded from its `AmmInfo` account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmmKeys {
+    pub amm_id: Pubkey,
+    pub program_id: Pubkey,
+    pub authority: Pubkey,
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub market_id: Pubkey,
+    pub market_program_id: Pubkey,
+}
+
+impl AmmKeys {
+    pub fn parse(amm_id: Pubkey, program_id: Pubkey, data: &[u8]) -> Result<Self> {
+        if data.len() < AMM_INFO_LEN {
+            anyhow::bail!("Raydium pool {} has {} bytes of state, expected {}", amm_id, data.len(), AMM_INFO_LEN);
+        }
+        let nonce = read_u64(data, 8) as u8;
+        let authority = Pubkey::create_program_address(&[AUTHORITY_SEED, &[nonce]], &program_id)
+            .map_err(|e| anyhow::anyhow!("Invalid authority nonce {} for pool {}: {}", nonce, amm_id, e))?;
+
+        Ok(Self {
+            amm_id,
+            program_id,
+            authority,
+            base_vault: read_pubkey(data, 336),
+            quote_vault: read_pubkey(data, 368),
+            base_mint: read_pubkey(data, 400),
+            quote_mint: read_pubkey(data, 432),
+            open_orders: read_pubkey(data, 496),
+            market_id: read_pubkey(data, 528),
+            market_program_id: read_pubkey(data, 560),
+            target_orders: read_pubkey(data, 592),
+        })
+    }
+}
+
+/// Order book accounts the v4 program still requires on every swap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketKeys {
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_queue: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub vault_signer: Pubkey,
+}
+
+impl MarketKeys {
+    pub fn parse(market_id: &Pubkey, market_program_id: &Pubkey, data: &[u8]) -> Result<Self> {
+        if data.len() < MARKET_STATE_LEN {
+            anyhow::bail!("Market {} has {} bytes of state, expected {}", market_id, data.len(), MARKET_STATE_LEN);
+        }
+        let nonce = read_u64(data, 45);
+        let vault_signer = Pubkey::create_program_address(&[market_id.as_ref(), &nonce.to_le_bytes()], market_program_id)
+            .map_err(|e| anyhow::anyhow!("Invalid vault signer nonce for market {}: {}", market_id, e))?;
+
+        Ok(Self {
+            base_vault: read_pubkey(data, 117),
+            quote_vault: read_pubkey(data, 165),
+            event_queue: read_pubkey(data, 253),
+            bids: read_pubkey(data, 285),
+            asks: read_pubkey(data, 317),
+            vault_signer,
+        })
+    }
+}
+
+/// The user's side of a swap
+#[derive(Debug, Clone, Copy)]
+pub struct SwapAccounts {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub owner: Pubkey,
+}
+
+/// `SwapBaseIn`: spend exactly `amount_in` from `source`, failing if less than `min_amount_out` arrives.
+/// Direction follows from which mint the source account holds.
+pub fn swap_base_in(amm: &AmmKeys, market: &MarketKeys, user: &SwapAccounts, amount_in: u64, min_amount_out: u64) -> Instruction {
+    let mut data = Vec::with_capacity(17);
+    data.push(SWAP_BASE_IN);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    let accounts = vec![
+        AccountMeta::new_readonly(spl_token_interface::id(), false),
+        AccountMeta::new(amm.amm_id, false),
+        AccountMeta::new_readonly(amm.authority, false),
+        AccountMeta::new(amm.open_orders, false),
+        AccountMeta::new(amm.target_orders, false),
+        AccountMeta::new(amm.base_vault, false),
+        AccountMeta::new(amm.quote_vault, false),
+        AccountMeta::new_readonly(amm.market_program_id, false),
+        AccountMeta::new(amm.market_id, false),
+        AccountMeta::new(market.bids, false),
+        AccountMeta::new(market.asks, false),
+        AccountMeta::new(market.event_queue, false),
+        AccountMeta::new(market.base_vault, false),
+        AccountMeta::new(market.quote_vault, false),
+        AccountMeta::new_readonly(market.vault_signer, false),
+        AccountMeta::new(user.source, false),
+        AccountMeta::new(user.destination, false),
+        AccountMeta::new_readonly(user.owner, true),
+    ];
+
+    Instruction { program_id: amm.program_id, accounts, data }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(data: &mut [u8], offset: usize, key: &Pubkey) {
+        data[offset..offset + 32].copy_from_slice(key.as_ref());
+    }
+
+    #[test]
+    fn test_swap_instruction_from_pool_state() {
+        let (amm_id, market_id, market_program) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (base_mint, quote_mint, bids) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        // Find nonces the programs would have stored for these addresses
+        let amm_nonce = (0..=255u8)
+            .rev()
+            .find(|n| Pubkey::create_program_address(&[AUTHORITY_SEED, &[*n]], &RAYDIUM_AMM_V4).is_ok())
+            .unwrap();
+        let market_nonce = (0..100u64)
+            .find(|n| Pubkey::create_program_address(&[market_id.as_ref(), &n.to_le_bytes()], &market_program).is_ok())
+            .unwrap();
+
+        let mut amm_data = vec![0u8; AMM_INFO_LEN];
+        amm_data[8..16].copy_from_slice(&(amm_nonce as u64).to_le_bytes());
+        write(&mut amm_data, 400, &base_mint);
+        write(&mut amm_data, 432, &quote_mint);
+        write(&mut amm_data, 528, &market_id);
+        write(&mut amm_data, 560, &market_program);
+        let amm = AmmKeys::parse(amm_id, RAYDIUM_AMM_V4, &amm_data).unwrap();
+        assert_eq!(amm.base_mint, base_mint);
+        assert_eq!(amm.market_program_id, market_program);
+        // The well-known v4 vault authority
+        assert_eq!(amm.authority.to_string(), "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+
+        let mut market_data = vec![0u8; MARKET_STATE_LEN];
+        market_data[45..53].copy_from_slice(&market_nonce.to_le_bytes());
+        write(&mut market_data, 285, &bids);
+        let market = MarketKeys::parse(&market_id, &market_program, &market_data).unwrap();
+        assert_eq!(market.bids, bids);
+
+        let user = SwapAccounts { source: Pubkey::new_unique(), destination: Pubkey::new_unique(), owner: Pubkey::new_unique() };
+        let ix = swap_base_in(&amm, &market, &user, 1_000_000, 990_000);
+        assert_eq!(ix.program_id, RAYDIUM_AMM_V4);
+        assert_eq!(ix.accounts.len(), 18);
+        assert_eq!(ix.data[0], SWAP_BASE_IN);
+        assert_eq!(u64::from_le_bytes(ix.data[1..9].try_into().unwrap()), 1_000_000);
+        assert_eq!(u64::from_le_bytes(ix.data[9..17].try_into().unwrap()), 990_000);
+        assert!(ix.accounts[17].is_signer && ix.accounts[17].pubkey == user.owner);
+        assert!(AmmKeys::parse(amm_id, RAYDIUM_AMM_V4, &amm_data[..100]).is_err());
+    }
+}
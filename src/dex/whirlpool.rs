@@ -1,20 +1,20 @@
 use async_trait::async_trait;
 use anyhow::Result;
+use base64::Engine;
 use rust_decimal::Decimal;
 use solana_program::pubkey::Pubkey;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tokio::time::{timeout, Duration};
 
 use crate::{
-    dex::{DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, PoolUpdate, DexType},
-    models::{Token, Pool, PoolQuote, PoolState, PoolMetrics},
+    dex::{pool_accounts, DexInterface, DexError, DexMetrics, DexConnectionConfig, PoolUpdateStream, PoolUpdate, DexType},
+    dex::http::DexHttpClient,
+    models::{ClmmState, ClmmTick, Token, Pool, PoolQuote, PoolState, PoolMetrics, SendOptions},
+    services::account_subscriber::AccountSubscriber,
 };
 
 pub struct WhirlpoolDex {
-    config: DexConnectionConfig,
-    client: Client,
-    base_url: String,
+    http: DexHttpClient,
+    subscriber: Option<AccountSubscriber>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -30,41 +30,75 @@ struct WhirlpoolPool {
     pool_address: String,
     authority: String,
     program_id: String,
+    /// Q64.64 sqrt price, current tick and active liquidity; quotes fall back to the reserves without them
+    #[serde(default)]
+    sqrt_price: Option<String>,
+    #[serde(default)]
+    liquidity: Option<String>,
+    #[serde(default)]
+    tick_current_index: Option<i32>,
+    #[serde(default)]
+    tick_spacing: u16,
+    /// Base64 TickArray accounts around the current tick, as a swap would load them
+    #[serde(default)]
+    tick_arrays: Vec<String>,
+}
+
+const TICK_ARRAY_SIZE: usize = 88;
+/// initialized, liquidity_net, liquidity_gross, two fee growths and three reward growths
+const TICK_SIZE: usize = 113;
+
+/// Initialized ticks of a TickArray account
+pub fn decode_tick_array(data: &[u8], tick_spacing: u16) -> Option<Vec<ClmmTick>> {
+    let start = i32::from_le_bytes(data.get(8..12)?.try_into().ok()?);
+    let mut ticks = Vec::new();
+    for i in 0..TICK_ARRAY_SIZE {
+        let offset = 12 + i * TICK_SIZE;
+        let tick = data.get(offset..offset + TICK_SIZE)?;
+        if tick[0] == 0 {
+            continue;
+        }
+        ticks.push(ClmmTick {
+            index: start + i as i32 * tick_spacing as i32,
+            liquidity_net: i128::from_le_bytes(tick[1..17].try_into().ok()?),
+        });
+    }
+    Some(ticks)
+}
+
+/// Concentrated liquidity of a pool as the API reports it, when it reports all of it
+fn parse_clmm(pool: &WhirlpoolPool) -> Result<Option<ClmmState>> {
+    let (Some(sqrt_price), Some(liquidity), Some(tick_current)) = (&pool.sqrt_price, &pool.liquidity, pool.tick_current_index) else {
+        return Ok(None);
+    };
+    let mut ticks = Vec::new();
+    for encoded in &pool.tick_arrays {
+        let data = base64::engine::general_purpose::STANDARD.decode(encoded)?;
+        ticks.extend(
+            decode_tick_array(&data, pool.tick_spacing)
+                .ok_or_else(|| DexError::InvalidResponse(format!("Malformed tick array for pool {}", pool.id)))?,
+        );
+    }
+    Ok(Some(ClmmState::new(
+        sqrt_price.parse::<u128>()? as f64 / 2f64.powi(64),
+        liquidity.parse::<u128>()? as f64,
+        tick_current,
+        ticks,
+    )))
 }
 
 impl WhirlpoolDex {
     pub fn new(config: DexConnectionConfig) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
-            .build()?;
-
-        let base_url = config.base_url.clone();
         Ok(Self {
-            config,
-            client,
-            base_url,
+            http: DexHttpClient::new("Whirlpool", &config)?,
+            subscriber: None,
         })
     }
 
-    async fn make_request<T>(&self, endpoint: &str) -> Result<T>
-    where
-        T: for<'de> Deserialize<'de>,
-    {
-        let url = format!("{}{}", self.base_url, endpoint);
-        let response = timeout(
-            Duration::from_secs(self.config.timeout_seconds),
-            self.client.get(&url).send()
-        ).await??;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            return Err(DexError::InvalidResponse(
-                format!("HTTP {}: {}", status, response.text().await?)
-            ).into());
-        }
-
-        let data: T = response.json().await?;
-        Ok(data)
+    /// Stream pool reserve changes over the shared account websocket
+    pub fn with_subscriber(mut self, subscriber: AccountSubscriber) -> Self {
+        self.subscriber = Some(subscriber);
+        self
     }
 
     fn parse_pool(&self, whirlpool_pool: &WhirlpoolPool) -> Result<Pool> {
@@ -92,7 +126,7 @@ impl WhirlpoolDex {
         let reserve_b = whirlpool_pool.quote_reserve.parse::<Decimal>()?;
         let fee_rate = whirlpool_pool.fee_rate.parse::<Decimal>()?;
 
-        Ok(Pool::new(
+        let pool = Pool::new(
             whirlpool_pool.id.clone(),
             DexType::Whirlpool,
             base_token,
@@ -100,7 +134,12 @@ impl WhirlpoolDex {
             pool_address,
             authority,
             program_id,
-        ).update_reserves(reserve_a, reserve_b).with_fee_rate(fee_rate))
+        ).update_reserves(reserve_a, reserve_b).with_fee_rate(fee_rate);
+
+        Ok(match parse_clmm(whirlpool_pool)? {
+            Some(clmm) => pool.with_clmm(clmm),
+            None => pool,
+        })
     }
 }
 
@@ -119,14 +158,14 @@ impl DexInterface for WhirlpoolDex {
     }
 
     async fn is_connected(&self) -> Result<bool> {
-        match self.make_request::<serde_json::Value>("/health").await {
+        match self.http.get::<serde_json::Value>("/health").await {
             Ok(_) => Ok(true),
             Err(_) => Ok(false),
         }
     }
 
     async fn get_pools(&self) -> Result<Vec<Pool>> {
-        let pools: Vec<WhirlpoolPool> = self.make_request("/pools").await?;
+        let pools: Vec<WhirlpoolPool> = self.http.get("/pools").await?;
         let mut result = Vec::new();
         
         for pool in pools {
@@ -154,7 +193,7 @@ impl DexInterface for WhirlpoolDex {
 
     async fn get_pool_state(&self, pool_address: &Pubkey) -> Result<PoolState> {
         let endpoint = format!("/pool/{}", pool_address);
-        let pool_data: WhirlpoolPool = self.make_request(&endpoint).await?;
+        let pool_data: WhirlpoolPool = self.http.get(&endpoint).await?;
         let pool = self.parse_pool(&pool_data)?;
         
         let current_price = pool.get_price(&pool.token_a).unwrap_or(Decimal::ZERO);
@@ -228,6 +267,7 @@ impl DexInterface for WhirlpoolDex {
         quote: &PoolQuote,
         wallet: &Pubkey,
         slippage_tolerance: Decimal,
+        _options: &SendOptions,
     ) -> Result<String> {
         tracing::info!("Executing Whirlpool swap for wallet: {}", wallet);
         Ok("mock_transaction_signature".to_string())
@@ -262,13 +302,11 @@ impl DexInterface for WhirlpoolDex {
         })
     }
 
-    async fn subscribe_pool_updates(&self, pool_address: &Pubkey) -> Result<PoolUpdateStream> {
-        let (_, receiver) = tokio::sync::mpsc::channel(100);
-        
-        Ok(PoolUpdateStream {
-            pool_address: *pool_address,
-            update_receiver: receiver,
-        })
+    async fn subscribe_pool_updates(&self, pool: &Pool) -> Result<PoolUpdateStream> {
+        match &self.subscriber {
+            Some(subscriber) => pool_accounts::subscribe_reserves(subscriber, pool),
+            None => Err(DexError::ConnectionFailed("websocket subscriptions are disabled".to_string()).into()),
+        }
     }
 
     async fn get_supported_tokens(&self) -> Result<Vec<Token>> {
@@ -279,3 +317,57 @@ impl DexInterface for WhirlpoolDex {
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quotes_follow_tick_arrays() {
+        // One array starting at tick -704 with spacing 64, liquidity 1e9 from tick -64 to 64
+        let mut array = vec![0u8; 8 + 4 + TICK_ARRAY_SIZE * TICK_SIZE + 32];
+        array[8..12].copy_from_slice(&(-704i32).to_le_bytes());
+        for (slot, net) in [(10usize, 1_000_000_000i128), (12, -1_000_000_000)] {
+            let offset = 12 + slot * TICK_SIZE;
+            array[offset] = 1;
+            array[offset + 1..offset + 17].copy_from_slice(&net.to_le_bytes());
+        }
+        let ticks = decode_tick_array(&array, 64).unwrap();
+        assert_eq!(ticks.iter().map(|t| t.index).collect::<Vec<_>>(), vec![-64, 64]);
+
+        let dex = WhirlpoolDex::new(DexConnectionConfig {
+            base_url: String::new(),
+            api_key: None,
+            timeout_seconds: 1,
+            max_retries: 0,
+            rate_limit: 0,
+        })
+        .unwrap();
+        let raw = WhirlpoolPool {
+            id: "whirlpool".to_string(),
+            base_mint: Pubkey::new_unique().to_string(),
+            quote_mint: Pubkey::new_unique().to_string(),
+            base_decimals: 6,
+            quote_decimals: 6,
+            base_reserve: "5000000".to_string(),
+            quote_reserve: "5000000".to_string(),
+            fee_rate: "0".to_string(),
+            pool_address: Pubkey::new_unique().to_string(),
+            authority: Pubkey::new_unique().to_string(),
+            program_id: Pubkey::new_unique().to_string(),
+            sqrt_price: Some((1u128 << 64).to_string()),
+            liquidity: Some("1000000000".to_string()),
+            tick_current_index: Some(0),
+            tick_spacing: 64,
+            tick_arrays: vec![base64::engine::general_purpose::STANDARD.encode(&array)],
+        };
+        let pool = dex.parse_pool(&raw).unwrap();
+        // Virtual reserves replace the API's, which would have quoted far deeper than the range
+        assert_eq!(pool.reserve_a, Decimal::from(1000));
+        let output = pool.calculate_output_amount(Decimal::ONE, &pool.token_a).unwrap();
+        assert!((output - Decimal::from(1000) / Decimal::from(1001)).abs() < Decimal::new(1, 9));
+        // The range holds roughly 3.2 of each token; beyond it nothing is known
+        assert!(pool.calculate_output_amount(Decimal::from(4), &pool.token_a).is_none());
+        assert!(pool.calculate_price_impact(Decimal::from(3), &pool.token_b).unwrap() > Decimal::new(5, 3));
+    }
+}
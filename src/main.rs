@@ -1,13 +1,31 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use tracing::{info, error, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use offchain_bot::{
-    config::AppConfig,
-    services::database::DatabaseService,
+    config::{integrity, AppConfig, DatabaseConfig},
+    services::database::{AggregateBy, DatabaseService, ExecutionQuery},
+    services::doctor::Doctor,
+    services::readiness::{self, Readiness},
+    services::codec::CodecKind,
+    services::journal_import::{self, JournalImporter},
+    services::control_api::{self, ControlApiState},
+    services::tenancy::TenantRegistry,
+    services::oracle::PythOracle,
     dex::DexFactory,
     DexType,
     arbitrage::ArbitrageEngine,
+    arbitrage::bootstrap::Bootstrapper,
+    arbitrage::backtest::{self, Backtester},
+    arbitrage::simulator::{Scenario, Simulator},
+    arbitrage::soak::SoakRunner,
+    arbitrage::projection::{PnlProjection, ProjectionSettings},
+    arbitrage::benchmark::{self, BenchmarkBaseline, HodlComparison},
+    arbitrage::accounting::{self, Period},
+    arbitrage::position_watcher::HoldingReport,
+    models::ExecutionStatus,
+    utils::affinity::CorePlan,
+    utils::secret::SecretKeypair,
 };
 
 #[derive(Parser)]
@@ -34,10 +52,144 @@ struct Cli {
     /// Force use memory store only
     #[arg(long)]
     memory_only: bool,
+    
+    /// Exit 0 when the running bot reports itself ready, for container health probes
+    #[arg(long)]
+    healthcheck: bool,
+    
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
+#[derive(Subcommand)]
+enum Command {
+    /// Rank pairs across all DEXes and write a suggested scanner config fragment
+    Bootstrap {
+        /// Output path for the generated config fragment
+        #[arg(short, long, default_value = "config/bootstrap.toml")]
+        output: String,
+        
+        /// Number of pairs to include in the allowlist
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+    },
+    /// Run startup self-tests and print a pass/fail table
+    Doctor,
+    /// Replay a synthetic pool scenario through the detection pipeline
+    Simulate {
+        /// Scenario TOML file
+        scenario: String,
+    },
+    /// Replay recorded pool snapshots through the scanner and strategy and report paper results
+    Backtest {
+        /// Snapshot file (.csv or .jsonl); read from the database when omitted
+        snapshots: Option<String>,
+        
+        /// Start of the database range (RFC 3339)
+        #[arg(long)]
+        from: Option<chrono::DateTime<chrono::Utc>>,
+        
+        /// End of the database range (RFC 3339), exclusive
+        #[arg(long)]
+        to: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    /// Summarize recorded executions and project P&L by resampling them
+    Report {
+        /// Days of execution history to sample from
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+        
+        /// Simulated runs
+        #[arg(long, default_value_t = 2000)]
+        runs: usize,
+        
+        /// Executions per run; defaults to as many as the sampled history holds
+        #[arg(long)]
+        trades: Option<usize>,
+        
+        /// Capital drawdowns are measured against, in quote units
+        #[arg(long, default_value_t = 100.0)]
+        capital: f64,
+        
+        /// Seed for reproducible projections
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        
+        /// Only sample executions carrying this tag, as key=value; repeatable
+        #[arg(long = "tag", value_parser = parse_tag)]
+        tags: Vec<(String, String)>,
+        
+        /// Print the running bot's holdings from its control API instead
+        #[arg(long)]
+        positions: bool,
+        
+        /// Operator token for the control API when tenancy is enabled
+        #[arg(long)]
+        api_token: Option<String>,
+    },
+    /// Export realized P&L per token, pair and strategy, with fees and tips, for tax and performance review
+    Accounting {
+        /// Start of the range (RFC 3339); defaults to the first execution
+        #[arg(long)]
+        from: Option<chrono::DateTime<chrono::Utc>>,
+        
+        /// End of the range (RFC 3339), exclusive; defaults to now
+        #[arg(long)]
+        to: Option<chrono::DateTime<chrono::Utc>>,
+        
+        /// "daily" or "monthly"
+        #[arg(long, default_value = "daily")]
+        period: String,
+        
+        /// "csv" or "json"
+        #[arg(long, default_value = "csv")]
+        format: String,
+        
+        /// File to write; printed when omitted
+        #[arg(short, long)]
+        output: Option<String>,
+        
+        /// Only book executions carrying this tag, as key=value; repeatable
+        #[arg(long = "tag", value_parser = parse_tag)]
+        tags: Vec<(String, String)>,
+        
+        /// Also break P&L down by the values of this tag; repeatable
+        #[arg(long = "by-tag")]
+        by_tags: Vec<String>,
+    },
+    /// Import journals from a memory-only run into a database; safe to run again
+    MigrateJournal {
+        /// Journal file, or a directory of `*.journal` files
+        #[arg(long)]
+        from: String,
+        
+        /// Database URL to import into
+        #[arg(long)]
+        to: String,
+    },
+    /// Write signature and checksum sidecars for config or strategy files
+    SignConfig {
+        /// Solana CLI keypair file to sign with; its address is the verifying key
+        #[arg(long)]
+        keypair: String,
+        
+        /// Files to sign
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+    /// Run the engine against mock DEXes for hours and check resource usage stays bounded
+    Soak {
+        /// Total run time in minutes
+        #[arg(long, default_value_t = 240)]
+        minutes: u64,
+        
+        /// Seconds between resource samples
+        #[arg(long, default_value_t = 30)]
+        sample_seconds: u64,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
     
     // Initialize logging
@@ -48,18 +200,49 @@ async fn main() -> anyhow::Result<()> {
     info!("Configuration: {}", cli.config);
     info!("Log level: {}", cli.log_level);
     info!("Debug mode: {}", cli.debug);
-    info!("Dry run mode: {}", cli.dry_run);
-    info!("Memory only mode: {}", cli.memory_only);
+        info!("Memory only mode: {}", cli.memory_only);
     
     // Load configuration
-    let config = load_config(&cli.config)?;
+    let mut config = load_config(&cli.config)?;
+    config.submission.dry_run |= cli.dry_run;
     info!("Configuration loaded successfully");
+    info!("Dry run mode: {}", config.submission.dry_run);
+    
+    if cli.healthcheck {
+        return run_healthcheck(&config);
+    }
+    
+    // The runtime is built by hand so its worker threads can be pinned
+    let cores = CorePlan::resolve(&config.affinity)?;
+    cores.runtime_builder().build()?.block_on(run(cli, config))
+}
+
+/// Run the selected command, or the bot itself
+async fn run(cli: Cli, config: AppConfig) -> anyhow::Result<()> {
+    if let Some(command) = cli.command {
+        return match command {
+            Command::Bootstrap { output, top } => run_bootstrap(&config, &output, top).await,
+            Command::Doctor => run_doctor(&config).await,
+            Command::Simulate { scenario } => run_simulation(&config, &scenario).await,
+            Command::MigrateJournal { from, to } => run_migrate_journal(&config, &from, &to).await,
+            Command::SignConfig { keypair, files } => run_sign_config(&keypair, &files),
+            Command::Soak { minutes, sample_seconds } => run_soak(&config, minutes, sample_seconds).await,
+            Command::Backtest { snapshots, from, to } => run_backtest(&config, snapshots.as_deref(), from, to).await,
+            Command::Report { positions: true, api_token, .. } => report_positions(&config, api_token.as_deref()).await,
+            Command::Report { days, runs, trades, capital, seed, tags, .. } => {
+                run_report(&config, days, runs, trades, capital, seed, tags).await
+            }
+            Command::Accounting { from, to, period, format, output, tags, by_tags } => {
+                run_accounting(&config, from, to, &period, &format, output.as_deref(), tags, &by_tags).await
+            }
+        };
+    }
     
     // Initialize storage services based on configuration
     let database = if cli.memory_only || !config.is_memory_store_enabled() {
         None
     } else {
-        match DatabaseService::new(&config.database.url).await {
+        match DatabaseService::new(&config.database).await {
             Ok(db) => {
                 info!("Database service initialized successfully");
                 Some(std::sync::Arc::new(db))
@@ -79,12 +262,33 @@ async fn main() -> anyhow::Result<()> {
     let dex_instances = create_dex_instances(&config).await?;
     info!("DEX instances created: {:?}", dex_instances.keys().collect::<Vec<_>>());
     
+    // Never report ready on a node that fails its self-tests
+    let readiness = Readiness::new(&config.service);
+    if config.service.self_tests {
+        readiness.starting("Running self-tests");
+        let report = Doctor::new(&config, &dex_instances).run().await;
+        if !report.is_healthy() {
+            println!("{}", report.render_table());
+            readiness.starting("Self-tests failed");
+            anyhow::bail!("{} self-test(s) failed; refusing to start", report.failures());
+        }
+    }
+    
     // Create arbitrage engine
     let mut arbitrage_engine = ArbitrageEngine::new(
         config.clone(),
         database,
         dex_instances,
-    );
+    )
+    .with_tenants(TenantRegistry::from_config(&config.tenancy)?);
+    
+    // The engine's start runs its main loop, so the API has to be up first
+    let _control_api = if config.control_api.enabled {
+        let state = ControlApiState::new(arbitrage_engine.handle(), arbitrage_engine.dex_instances(), &config.control_api);
+        Some(control_api::serve(&config.control_api, state).await?)
+    } else {
+        None
+    };
     
     // Start arbitrage engine
     info!("Starting arbitrage engine...");
@@ -98,6 +302,7 @@ async fn main() -> anyhow::Result<()> {
     // Wait for interrupt signal
     tokio::signal::ctrl_c().await?;
     info!("Received interrupt signal, shutting down...");
+    readiness.stopping();
     
     Ok(())
 }
@@ -127,6 +332,301 @@ fn load_config(_config_path: &str) -> anyhow::Result<AppConfig> {
     Ok(config)
 }
 
+/// Check the status the running bot reports, for container health probes
+fn run_healthcheck(config: &AppConfig) -> anyhow::Result<()> {
+    let health = readiness::healthcheck(&config.service, chrono::Utc::now())?;
+    println!("healthy: pid {}, {} (updated {})", health.pid, health.status, health.updated_at.to_rfc3339());
+    Ok(())
+}
+
+/// Sign config files so nodes with the matching verifying key will load them
+fn run_sign_config(keypair: &str, files: &[String]) -> anyhow::Result<()> {
+    use solana_sdk::signature::Signer;
+
+    let keypair = SecretKeypair::read_file(keypair)?;
+    for file in files {
+        let signature = integrity::sign(std::path::Path::new(file), keypair.keypair())?;
+        println!("Signed {} -> {}", file, signature.display());
+    }
+    println!("Verify with {}={}", integrity::VERIFY_KEY_ENV, keypair.keypair().pubkey());
+    println!("Nodes verifying signatures refuse {}_* overrides; put every setting in a signed file", integrity::ENV_OVERRIDE_PREFIX);
+    Ok(())
+}
+
+/// Run the cold-start bootstrap and write the config fragment
+async fn run_bootstrap(config: &AppConfig, output: &str, top: usize) -> anyhow::Result<()> {
+    let dex_instances = create_dex_instances(config).await?;
+    let report = Bootstrapper::run(&dex_instances, top).await?;
+    
+    if report.candidates.is_empty() {
+        warn!("No pairs quoted on two or more pools were found; nothing written");
+        return Ok(());
+    }
+    
+    for (rank, candidate) in report.candidates.iter().enumerate() {
+        println!(
+            "{:>3}. {}/{} pools={} dexes={} volume_24h={} tvl={}",
+            rank + 1,
+            candidate.symbol_a,
+            candidate.symbol_b,
+            candidate.pool_count,
+            candidate.dex_count,
+            candidate.volume_24h,
+            candidate.total_tvl
+        );
+    }
+    
+    Bootstrapper::write_fragment(&report, output)?;
+    info!(
+        "Wrote {} pairs (min_liquidity = {}) to {}",
+        report.suggested.pair_allowlist.len(),
+        report.suggested.min_liquidity,
+        output
+    );
+    Ok(())
+}
+
+/// Run the startup self-test and fail if any check fails
+async fn run_doctor(config: &AppConfig) -> anyhow::Result<()> {
+    let dex_instances = DexFactory::create_all_dexes(config).await?;
+    let report = Doctor::new(config, &dex_instances).run().await;
+    
+    print!("{}", report.render_table());
+    if !report.is_healthy() {
+        return Err(anyhow::anyhow!("{} check(s) failed", report.failures()));
+    }
+    info!("All checks passed");
+    Ok(())
+}
+
+/// Run a scenario in accelerated time and print the decisions
+async fn run_simulation(config: &AppConfig, path: &str) -> anyhow::Result<()> {
+    let scenario = Scenario::load(path)?;
+    info!("Simulating scenario '{}' ({}s)", scenario.name, scenario.duration_seconds);
+    
+    let report = Simulator::new(config.clone(), scenario)?.run().await?;
+    for line in Simulator::render(&report) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Replay snapshots from a file or the database and print the paper executions
+async fn run_backtest(
+    config: &AppConfig,
+    path: Option<&str>,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+) -> anyhow::Result<()> {
+    let snapshots = match path {
+        Some(path) => backtest::load_snapshots(path)?,
+        None => {
+            let database = DatabaseService::new(&config.database).await?;
+            let from = from.unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC);
+            database.get_pool_snapshots(from, to.unwrap_or_else(chrono::Utc::now)).await?
+        }
+    };
+    info!("Backtesting over {} pool snapshots", snapshots.len());
+    
+    let report = Backtester::new(config.clone())?.run(snapshots).await?;
+    for line in report.render() {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Summarize recorded executions of the current strategy mix and print a Monte Carlo P&L projection
+async fn run_report(
+    config: &AppConfig,
+    days: i64,
+    runs: usize,
+    trades: Option<usize>,
+    capital: f64,
+    seed: u64,
+    tags: Vec<(String, String)>,
+) -> anyhow::Result<()> {
+    let database = DatabaseService::new(&config.database).await?;
+    let now = chrono::Utc::now();
+    // The current mix: strategies still active, or everything when none are known
+    let active = database.get_strategies().await?.into_iter().filter(|s| s.is_active).map(|s| s.name).collect();
+    let query = ExecutionQuery::between(now - chrono::Duration::days(days), now)
+        .with_strategies(active)
+        .with_statuses(vec![ExecutionStatus::Confirmed, ExecutionStatus::Failed])
+        .with_tags(tags);
+    
+    let total = database.aggregate_executions(&query, AggregateBy::Total).await?;
+    if let Some(total) = total.first() {
+        println!(
+            "{} executions over {} days, {} confirmed, net profit {}",
+            total.executions,
+            days,
+            total.confirmed,
+            total.net_profit().round_dp(6)
+        );
+    }
+    for strategy in database.aggregate_executions(&query, AggregateBy::Strategy).await? {
+        println!(
+            "  {}: {} executions, {} confirmed, net profit {}",
+            strategy.key.as_deref().unwrap_or("untagged"),
+            strategy.executions,
+            strategy.confirmed,
+            strategy.net_profit().round_dp(6)
+        );
+    }
+    
+    let outcomes = database.execution_outcomes(&query).await?;
+    let settings = ProjectionSettings {
+        runs,
+        trades: trades.unwrap_or(outcomes.len()),
+        starting_capital: rust_decimal::Decimal::try_from(capital)?,
+        seed,
+    };
+    match PnlProjection::simulate(&outcomes, &settings) {
+        Some(projection) => projection.render().iter().for_each(|line| println!("{}", line)),
+        None => warn!("No finished executions to project from"),
+    }
+    
+    if config.benchmark.enabled {
+        report_benchmark(config, &database).await?;
+    }
+    Ok(())
+}
+
+/// Print the running bot's latest position watcher reports, read over its control API
+async fn report_positions(config: &AppConfig, api_token: Option<&str>) -> anyhow::Result<()> {
+    let mut request = reqwest::Client::new().get(format!("http://{}/reports/positions", config.control_api.bind));
+    if let Some(token) = api_token {
+        request = request.bearer_auth(token);
+    }
+    let reports: Vec<HoldingReport> = request.send().await?.error_for_status()?.json().await?;
+    if reports.is_empty() {
+        println!("No non-quote holdings reported");
+        return Ok(());
+    }
+    for report in &reports {
+        let exit = match (&report.exit_value, &report.exit_pool) {
+            (Some(value), Some(pool)) => format!("exits for {} via {}", value.round_dp(6), pool),
+            _ => "no exit".to_string(),
+        };
+        println!("{} {} in {}: {:?}, {} (checked {})", report.amount, report.mint, report.wallet, report.status, exit, report.checked_at.to_rfc3339());
+    }
+    Ok(())
+}
+
+/// Print the bot's P&L since the recorded baseline against holding the benchmark asset
+async fn report_benchmark(config: &AppConfig, database: &DatabaseService) -> anyhow::Result<()> {
+    let Some(baseline) = BenchmarkBaseline::load(std::path::Path::new(&config.benchmark.path))? else {
+        warn!("No benchmark baseline at {}; it is recorded when the bot starts", config.benchmark.path);
+        return Ok(());
+    };
+    let oracle = PythOracle::new(&config.oracle)?;
+    let price = benchmark::asset_price(&config.benchmark, &oracle).await?;
+    // Every strategy counts here, retired or not: this is the bot's whole record
+    let query = ExecutionQuery::between(baseline.started_at, chrono::Utc::now())
+        .with_statuses(vec![ExecutionStatus::Confirmed, ExecutionStatus::Failed]);
+    let net_profit = database
+        .aggregate_executions(&query, AggregateBy::Total)
+        .await?
+        .first()
+        .map(|total| total.net_profit())
+        .unwrap_or_default();
+    HodlComparison::new(baseline, price, net_profit).render().iter().for_each(|line| println!("{}", line));
+    Ok(())
+}
+
+/// Parse a `key=value` tag filter
+fn parse_tag(s: &str) -> anyhow::Result<(String, String)> {
+    let (key, value) = s.split_once('=').ok_or_else(|| anyhow::anyhow!("Expected a tag as key=value, got '{}'", s))?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Book recorded executions into per-period P&L lines and write them as CSV or JSON
+async fn run_accounting(
+    config: &AppConfig,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+    period: &str,
+    format: &str,
+    output: Option<&str>,
+    tags: Vec<(String, String)>,
+    by_tags: &[String],
+) -> anyhow::Result<()> {
+    const PAGE: usize = 1000;
+    let period: Period = period.parse()?;
+    let database = DatabaseService::new(&config.database).await?;
+    let query = ExecutionQuery {
+        from,
+        to: Some(to.unwrap_or_else(chrono::Utc::now)),
+        ..Default::default()
+    }
+    .with_statuses(vec![ExecutionStatus::Confirmed, ExecutionStatus::Failed])
+    .with_tags(tags);
+    
+    let mut executions = Vec::new();
+    loop {
+        let page = database.query_executions(&query, PAGE, executions.len()).await?;
+        let done = page.len() < PAGE;
+        executions.extend(page);
+        if done {
+            break;
+        }
+    }
+    
+    let lines = accounting::ledger(&executions, period, by_tags);
+    let rendered = match format {
+        "csv" => accounting::to_csv(&lines),
+        "json" => accounting::to_json(&lines)?,
+        other => anyhow::bail!("Unknown format '{}', expected csv or json", other),
+    };
+    match output {
+        Some(path) => {
+            std::fs::write(path, rendered)?;
+            info!("Wrote {} P&L lines from {} executions to {}", lines.len(), executions.len(), path);
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Replay journal files into the database at `url`
+async fn run_migrate_journal(config: &AppConfig, from: &str, url: &str) -> anyhow::Result<()> {
+    let files = journal_import::journal_files(std::path::Path::new(from))?;
+    if files.is_empty() {
+        anyhow::bail!("No journal files found in {}", from);
+    }
+    // Imports go straight to the database, so this run has no write buffer of its own
+    let database = DatabaseService::new(&DatabaseConfig {
+        url: url.to_string(),
+        buffer_path: String::new(),
+        ..config.database.clone()
+    })
+    .await?;
+    
+    let codec = CodecKind::parse(&config.journal.codec)?.codec();
+    let report = JournalImporter::new(&database).with_codec(codec).import_all(&files).await?;
+    println!("{}", report.render());
+    Ok(())
+}
+
+async fn run_soak(config: &AppConfig, minutes: u64, sample_seconds: u64) -> anyhow::Result<()> {
+    info!("Soaking engine against mock DEXes for {} minutes", minutes);
+    
+    let report = SoakRunner::new(
+        config.clone(),
+        std::time::Duration::from_secs(minutes * 60),
+        std::time::Duration::from_secs(sample_seconds.max(1)),
+    )
+    .run()
+    .await?;
+    for line in report.render() {
+        println!("{}", line);
+    }
+    if !report.is_bounded() {
+        anyhow::bail!("Soak run found {} resource leak(s)", report.violations.len());
+    }
+    Ok(())
+}
+
 /// Create DEX instances based on configuration
 async fn create_dex_instances(config: &AppConfig) -> anyhow::Result<std::collections::HashMap<DexType, Box<dyn offchain_bot::dex::DexInterface>>> {
     let dex_instances = DexFactory::create_all_dexes(config).await?;
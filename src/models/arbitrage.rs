@@ -1,9 +1,16 @@
 use serde::{Deserialize, Serialize};
 use solana_program::pubkey::Pubkey;
 use rust_decimal::Decimal;
-use crate::models::{Token, Pool};
-use crate::dex::DexType;
+use crate::models::{Token, Pool, SendOptions};
+use crate::dex::{DexType, ProgramErrorInfo};
 use chrono::{DateTime, Utc};
+use crate::utils::clock::{Clock, SystemClock};
+use crate::config::StrategyConfig;
+use rust_decimal::prelude::FromPrimitive;
+use std::collections::HashMap;
+
+/// Freeform key-value labels used for experiment tracking and grouping
+pub type Tags = HashMap<String, String>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArbitrageOpportunity {
@@ -23,6 +30,11 @@ pub struct ArbitrageOpportunity {
     pub timestamp: DateTime<Utc>,
     pub expiry: DateTime<Utc>,
     pub status: OpportunityStatus,
+    #[serde(default)]
+    pub tags: Tags,
+    /// Every hop of a multi-pool cycle; None for two-pool opportunities
+    #[serde(default)]
+    pub route: Option<ArbitrageRoute>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +65,61 @@ pub struct ArbitrageExecution {
     pub actual_profit: Option<Decimal>,
     pub execution_time: DateTime<Utc>,
     pub error_message: Option<String>,
+    #[serde(default)]
+    pub failure_reason: Option<FailureReason>,
+    /// Decoded custom program error when the transaction failed on-chain
+    #[serde(default)]
+    pub program_error: Option<ProgramErrorInfo>,
+    #[serde(default)]
+    pub tags: Tags,
+    #[serde(default)]
+    pub config_snapshot: Option<ConfigSnapshot>,
+    #[serde(default)]
+    pub send_options: SendOptions,
+    /// Program logs of the pre-flight simulation, whether it passed or not
+    #[serde(default)]
+    pub simulation_logs: Vec<String>,
+}
+
+/// Effective engine configuration at the moment an execution was created
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfigSnapshot {
+    pub min_profit_threshold: f64,
+    pub max_slippage: f64,
+    pub gas_price_multiplier: f64,
+    pub max_concurrent_opportunities: usize,
+    pub execution_timeout_seconds: u64,
+    pub strategy_id: Option<String>,
+    pub strategy_name: Option<String>,
+    pub strategy_version: Option<DateTime<Utc>>,
+    pub strategy_min_profit_threshold: Option<Decimal>,
+    pub strategy_max_slippage: Option<Decimal>,
+    #[serde(default)]
+    pub lamports_per_signature: u64,
+    #[serde(default)]
+    pub priority_fee_lamports: u64,
+    #[serde(default)]
+    pub token_account_rent_lamports: u64,
+    /// Tip paid with each bundle; 0 when bundles are disabled
+    #[serde(default)]
+    pub bundle_tip_lamports: u64,
+    #[serde(default)]
+    pub leader_aware: bool,
+    #[serde(default)]
+    pub non_jito_fee_multiplier: f64,
+    #[serde(default)]
+    pub compute_unit_margin: f64,
+    pub captured_at: DateTime<Utc>,
+    /// SHA256 over every field except `captured_at` and the hash itself
+    pub hash: String,
+    /// Fields `hash` covers: 1 before the fee and submission settings, which snapshots stored
+    /// without a version predate, and `ConfigSnapshot::HASH_VERSION` since
+    #[serde(default = "first_hash_version")]
+    pub hash_version: u32,
+}
+
+fn first_hash_version() -> u32 {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -63,7 +130,27 @@ pub enum RiskScore {
     Critical,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+impl RiskScore {
+    /// One level riskier, saturating at Critical
+    pub fn escalate(self) -> Self {
+        match self {
+            RiskScore::Low => RiskScore::Medium,
+            RiskScore::Medium => RiskScore::High,
+            RiskScore::High | RiskScore::Critical => RiskScore::Critical,
+        }
+    }
+}
+
+/// How quickly a strategy's transactions need to land
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Urgency {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum OpportunityStatus {
     Pending,
     Executing,
@@ -81,6 +168,49 @@ pub enum ExecutionStatus {
     Confirmed,
     Failed,
     Cancelled,
+    /// Settled from quotes in a dry run. Nothing was sent, so it counts as no trade anywhere.
+    Simulated,
+}
+
+/// Why an execution failed, derived from its error message and transaction logs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum FailureReason {
+    SlippageExceeded,
+    BlockhashExpired,
+    InsufficientFunds,
+    /// Custom program error code not covered by a more specific reason
+    ProgramError(u32),
+    Timeout,
+    FrontRun,
+}
+
+impl FailureReason {
+    /// Stable name used for metric labels and report keys
+    pub fn label(&self) -> &'static str {
+        match self {
+            FailureReason::SlippageExceeded => "slippage_exceeded",
+            FailureReason::BlockhashExpired => "blockhash_expired",
+            FailureReason::InsufficientFunds => "insufficient_funds",
+            FailureReason::ProgramError(_) => "program_error",
+            FailureReason::Timeout => "timeout",
+            FailureReason::FrontRun => "front_run",
+        }
+    }
+
+    /// Whether the pools traded through are to blame, as opposed to the network, the wallet or
+    /// other traders; only these count towards blacklisting a pool
+    pub fn is_pool_attributable(&self) -> bool {
+        matches!(self, FailureReason::SlippageExceeded | FailureReason::ProgramError(_))
+    }
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureReason::ProgramError(code) => write!(f, "program_error(0x{:x})", code),
+            other => f.write_str(other.label()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +227,36 @@ pub struct ArbitrageStrategy {
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub tags: Tags,
+    #[serde(default)]
+    pub urgency: Urgency,
+    /// Tenant owning the strategy; empty for the operator's own
+    #[serde(default)]
+    pub namespace: String,
+    /// Registered implementation that evaluates opportunities for the strategy
+    #[serde(default = "default_strategy_kind")]
+    pub kind: String,
+    /// Settings of the kind's own, read when the strategy is built
+    #[serde(default)]
+    pub parameters: serde_json::Value,
+    /// Scale on the configured trade size
+    #[serde(default = "default_position_size_multiplier")]
+    pub position_size_multiplier: Decimal,
+    /// Largest trade in the start token; None for no cap
+    #[serde(default)]
+    pub max_trade_amount: Option<Decimal>,
+}
+
+fn default_position_size_multiplier() -> Decimal {
+    Decimal::ONE
+}
+
+/// Kind of strategy that filters on its thresholds alone
+pub const THRESHOLD_STRATEGY_KIND: &str = "threshold";
+
+fn default_strategy_kind() -> String {
+    THRESHOLD_STRATEGY_KIND.to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -109,16 +269,36 @@ pub struct ArbitrageMetrics {
     pub net_profit: Decimal,
     pub success_rate: Decimal,
     pub average_execution_time: Option<u64>,
+    /// Failed executions keyed by `FailureReason::label`, "unclassified" when none applied
+    #[serde(default)]
+    pub failure_reasons: HashMap<String, u64>,
     pub timestamp: DateTime<Utc>,
 }
 
 impl ArbitrageOpportunity {
+    /// "BASE/QUOTE" by symbol
+    pub fn pair(&self) -> String {
+        format!("{}/{}", self.base_token.symbol, self.quote_token.symbol)
+    }
+
     pub fn new(
         base_token: Token,
         quote_token: Token,
         buy_pool: Pool,
         sell_pool: Pool,
     ) -> Self {
+        Self::new_with_clock(base_token, quote_token, buy_pool, sell_pool, &SystemClock)
+    }
+
+    /// Opportunity detected at `clock`'s current time, expiring 30 seconds later
+    pub fn new_with_clock(
+        base_token: Token,
+        quote_token: Token,
+        buy_pool: Pool,
+        sell_pool: Pool,
+        clock: &dyn Clock,
+    ) -> Self {
+        let now = clock.now();
         let buy_price = buy_pool.get_price(&base_token).unwrap_or(Decimal::ZERO);
         let sell_price = sell_pool.get_price(&base_token).unwrap_or(Decimal::ZERO);
         
@@ -134,11 +314,12 @@ impl ArbitrageOpportunity {
             Decimal::ZERO
         };
 
-        let estimated_profit = Decimal::ZERO; // Will be calculated based on amount
-        let estimated_fees = Decimal::ZERO; // Will be calculated
+        // Depend on the trade size; see `estimate_round_trip`
+        let estimated_profit = Decimal::ZERO;
+        let estimated_fees = Decimal::ZERO;
         let net_profit = estimated_profit - estimated_fees;
         
-        let risk_score = Self::calculate_risk_score(&buy_pool, &sell_pool, profit_percentage);
+        let risk_score = Self::risk_score_at(&buy_pool, &sell_pool, profit_percentage, now);
         
         Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -154,13 +335,77 @@ impl ArbitrageOpportunity {
             estimated_fees,
             net_profit,
             risk_score,
-            timestamp: Utc::now(),
-            expiry: Utc::now() + chrono::Duration::seconds(30), // 30 seconds expiry
+            timestamp: now,
+            expiry: now + chrono::Duration::seconds(30), // 30 seconds expiry
             status: OpportunityStatus::Pending,
+            tags: Tags::new(),
+            route: None,
         }
     }
 
+    /// Opportunity for a cycle that starts and ends at the route's input token.
+    ///
+    /// The first and last pools stand in as buy and sell pools; profit is in the start token.
+    pub fn from_cycle(route: ArbitrageRoute, clock: &dyn Clock) -> Option<Self> {
+        let first = route.pools.first()?;
+        let last = route.pools.last()?;
+        let quote_token = if first.token_a.mint == route.input_token.mint {
+            first.token_b.clone()
+        } else {
+            first.token_a.clone()
+        };
+
+        let mut opportunity = Self::new_with_clock(route.input_token.clone(), quote_token, first.clone(), last.clone(), clock);
+        opportunity.price_difference = route.expected_output - route.input_amount;
+        opportunity.profit_percentage = if route.input_amount > Decimal::ZERO {
+            opportunity.price_difference / route.input_amount
+        } else {
+            Decimal::ZERO
+        };
+        opportunity.estimated_profit = opportunity.price_difference;
+        opportunity.net_profit = opportunity.estimated_profit - opportunity.estimated_fees;
+        opportunity.risk_score = Self::risk_score_at(first, last, opportunity.profit_percentage, opportunity.timestamp);
+        opportunity.route = Some(route);
+        Some(opportunity)
+    }
+
+    /// Profit-maximizing input: the route's own input, else the closed-form optimum of selling the
+    /// base token into the buy pool and buying it back from the sell pool
+    pub fn optimal_input(&self) -> Option<Decimal> {
+        match &self.route {
+            Some(route) => Some(route.input_amount),
+            None => self.buy_pool.optimal_round_trip_input(&self.sell_pool, &self.base_token),
+        }
+    }
+
+    /// Fill in estimated profit, fees and net profit, in base tokens, for selling `input` base
+    /// tokens into the buy pool and buying them back from the sell pool, returning the net profit.
+    ///
+    /// `estimated_profit` is the round trip as if neither pool charged a swap fee; `estimated_fees`
+    /// is what swap fees take from it plus `network_cost`. Price impact of the size is included in
+    /// both. None, leaving the fields as they were, when either pool cannot quote the size.
+    pub fn estimate_round_trip(&mut self, input: Decimal, network_cost: Decimal) -> Option<Decimal> {
+        let round_trip = |buy_pool: &Pool, sell_pool: &Pool| {
+            let quote = buy_pool.calculate_output_amount(input, &self.base_token)?;
+            sell_pool.calculate_output_amount(quote, &self.quote_token)
+        };
+        let output = round_trip(&self.buy_pool, &self.sell_pool)?;
+        let fee_free = round_trip(
+            &self.buy_pool.clone().with_fee_rate(Decimal::ZERO),
+            &self.sell_pool.clone().with_fee_rate(Decimal::ZERO),
+        )?;
+        self.estimated_profit = fee_free - input;
+        self.net_profit = output - input - network_cost;
+        self.estimated_fees = self.estimated_profit - self.net_profit;
+        Some(self.net_profit)
+    }
+
     pub fn calculate_risk_score(buy_pool: &Pool, sell_pool: &Pool, profit_percentage: Decimal) -> RiskScore {
+        Self::risk_score_at(buy_pool, sell_pool, profit_percentage, Utc::now())
+    }
+
+    /// Risk score with pool staleness judged at `now`
+    pub fn risk_score_at(buy_pool: &Pool, sell_pool: &Pool, profit_percentage: Decimal, now: DateTime<Utc>) -> RiskScore {
         let mut risk_score = 0u8;
         
         // Check liquidity
@@ -179,7 +424,7 @@ impl ArbitrageOpportunity {
         }
         
         // Check pool age/activity
-        let pool_age = Utc::now().signed_duration_since(buy_pool.last_updated);
+        let pool_age = now.signed_duration_since(buy_pool.last_updated);
         if pool_age.num_hours() > 24 {
             risk_score += 1;
         }
@@ -196,13 +441,151 @@ impl ArbitrageOpportunity {
         self.net_profit > min_profit_threshold
     }
 
+    /// Net profit as a fraction of the input it was estimated at: the scanner's sized input, else
+    /// the optimal one. None when the opportunity has no size
+    pub fn net_profit_ratio(&self) -> Option<Decimal> {
+        let estimated = self.tags.get("estimated_input").and_then(|input| input.parse().ok());
+        let input = estimated.or_else(|| self.optimal_input())?;
+        (input > Decimal::ZERO).then(|| self.net_profit / input)
+    }
+
+    /// Whether net profit, as a fraction of the input, exceeds `threshold`
+    pub fn clears_profit_threshold(&self, threshold: Decimal) -> bool {
+        self.net_profit_ratio().is_some_and(|ratio| ratio > threshold)
+    }
+
     pub fn is_expired(&self) -> bool {
-        Utc::now() > self.expiry
+        self.is_expired_on(&SystemClock)
+    }
+
+    pub fn is_expired_on(&self, clock: &dyn Clock) -> bool {
+        clock.now() > self.expiry
+    }
+
+    /// Take prices, pools, profit, risk and expiry from a later detection of the same
+    /// opportunity, keeping the ID, status and tags
+    pub fn refresh_from(&mut self, newer: &ArbitrageOpportunity) {
+        self.buy_pool = newer.buy_pool.clone();
+        self.sell_pool = newer.sell_pool.clone();
+        self.buy_price = newer.buy_price;
+        self.sell_price = newer.sell_price;
+        self.price_difference = newer.price_difference;
+        self.profit_percentage = newer.profit_percentage;
+        self.estimated_profit = newer.estimated_profit;
+        self.estimated_fees = newer.estimated_fees;
+        self.net_profit = newer.net_profit;
+        self.risk_score = newer.risk_score.clone();
+        self.timestamp = newer.timestamp;
+        self.expiry = newer.expiry;
+        self.route = newer.route.clone();
     }
 
     pub fn update_status(&mut self, status: OpportunityStatus) {
         self.status = status;
     }
+
+    /// Merge tags into the opportunity, keeping existing values on conflict
+    pub fn merge_tags(&mut self, tags: &Tags) {
+        for (key, value) in tags {
+            self.tags.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+
+    /// Fold in how far the pool prices sit from the oracle reference price; a pool that is off
+    /// by more than half the tolerated deviation raises the risk one level
+    pub fn apply_oracle_deviation(&mut self, deviation: Decimal, max_deviation: Decimal) {
+        self.tags.insert("oracle_deviation".to_string(), deviation.round_dp(6).normalize().to_string());
+        if deviation * Decimal::TWO > max_deviation {
+            self.risk_score = self.risk_score.clone().escalate();
+        }
+    }
+
+    pub fn has_tag(&self, key: &str, value: &str) -> bool {
+        self.tags.get(key).map(|v| v == value).unwrap_or(false)
+    }
+}
+
+impl ConfigSnapshot {
+    /// Version new snapshots are sealed with
+    pub const HASH_VERSION: u32 = 2;
+
+    /// Capture and seal the effective config for an execution
+    pub fn capture(config: &crate::config::AppConfig, strategy: Option<&ArbitrageStrategy>) -> Self {
+        let (arbitrage, fees, submission) = (&config.arbitrage, &config.fees, &config.submission);
+        let mut snapshot = Self {
+            min_profit_threshold: arbitrage.min_profit_threshold,
+            max_slippage: arbitrage.max_slippage,
+            gas_price_multiplier: arbitrage.gas_price_multiplier,
+            max_concurrent_opportunities: arbitrage.max_concurrent_opportunities,
+            execution_timeout_seconds: arbitrage.execution_timeout_seconds,
+            strategy_id: strategy.map(|s| s.id.clone()),
+            strategy_name: strategy.map(|s| s.name.clone()),
+            strategy_version: strategy.map(|s| s.updated_at),
+            strategy_min_profit_threshold: strategy.map(|s| s.min_profit_threshold),
+            strategy_max_slippage: strategy.map(|s| s.max_slippage),
+            lamports_per_signature: fees.lamports_per_signature,
+            priority_fee_lamports: fees.priority_fee_lamports,
+            token_account_rent_lamports: fees.token_account_rent_lamports,
+            bundle_tip_lamports: if submission.bundles_enabled { submission.bundle_tip_lamports } else { 0 },
+            leader_aware: submission.leader_aware,
+            non_jito_fee_multiplier: submission.non_jito_fee_multiplier,
+            compute_unit_margin: submission.compute_unit_margin,
+            captured_at: Utc::now(),
+            hash: String::new(),
+            hash_version: Self::HASH_VERSION,
+        };
+        snapshot.hash = snapshot.compute_hash().unwrap_or_default();
+        snapshot
+    }
+
+    /// Hash of the fields `hash_version` covers; None for a version this build does not know
+    fn compute_hash(&self) -> Option<String> {
+        let mut fields = vec![
+            serde_json::json!(self.min_profit_threshold),
+            serde_json::json!(self.max_slippage),
+            serde_json::json!(self.gas_price_multiplier),
+            serde_json::json!(self.max_concurrent_opportunities),
+            serde_json::json!(self.execution_timeout_seconds),
+            serde_json::json!(self.strategy_id),
+            serde_json::json!(self.strategy_name),
+            serde_json::json!(self.strategy_version),
+            serde_json::json!(self.strategy_min_profit_threshold),
+            serde_json::json!(self.strategy_max_slippage),
+        ];
+        match self.hash_version {
+            1 => {}
+            2 => fields.extend([
+                serde_json::json!(self.lamports_per_signature),
+                serde_json::json!(self.priority_fee_lamports),
+                serde_json::json!(self.token_account_rent_lamports),
+                serde_json::json!(self.bundle_tip_lamports),
+                serde_json::json!(self.leader_aware),
+                serde_json::json!(self.non_jito_fee_multiplier),
+                serde_json::json!(self.compute_unit_margin),
+            ]),
+            _ => return None,
+        }
+        Some(crate::utils::crypto::HashUtils::hash_string(&serde_json::Value::Array(fields).to_string()))
+    }
+
+    /// Whether the snapshot fields still match the sealed hash
+    pub fn verify(&self) -> bool {
+        self.compute_hash().is_some_and(|hash| hash == self.hash)
+    }
+}
+
+impl ArbitrageExecution {
+    /// Mark the execution failed, classifying the error and any transaction logs
+    pub fn mark_failed(&mut self, error: &str, logs: &[String]) {
+        self.execution_status = ExecutionStatus::Failed;
+        self.error_message = Some(error.to_string());
+        self.failure_reason = crate::arbitrage::outcome::classify_failure(error, logs);
+        self.program_error = crate::dex::errors::decode_failure(error, logs);
+    }
+
+    pub fn has_tag(&self, key: &str, value: &str) -> bool {
+        self.tags.get(key).map(|v| v == value).unwrap_or(false)
+    }
 }
 
 impl ArbitrageRoute {
@@ -274,7 +657,90 @@ impl ArbitrageStrategy {
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            tags: Tags::new(),
+            urgency: Urgency::default(),
+            namespace: String::new(),
+            kind: default_strategy_kind(),
+            parameters: serde_json::Value::Null,
+            position_size_multiplier: Decimal::ONE,
+            max_trade_amount: None,
+        }
+    }
+
+    /// Strategy defined by a `[[strategies]]` entry, identified by its name
+    pub fn from_config(config: &StrategyConfig) -> anyhow::Result<Self> {
+        let decimal = |field: &str, value: f64| {
+            Decimal::from_f64(value).ok_or_else(|| anyhow::anyhow!("{} = {} is not a finite number", field, value))
+        };
+        let supported_dexes =
+            if config.supported_dexes.is_empty() { DexType::pool_venues() } else { config.supported_dexes.clone() };
+        let mut strategy = Self::new(
+            config.name.clone(),
+            config.description.clone(),
+            decimal("min_profit_threshold", config.min_profit_threshold)?,
+            decimal("max_slippage", config.max_slippage)?,
+            decimal("max_price_impact", config.max_price_impact)?,
+            decimal("min_liquidity", config.min_liquidity)?,
+            supported_dexes,
+            config.risk_tolerance.clone(),
+        )
+        .with_kind(&config.kind, config.parameters.clone())
+        .with_urgency(config.urgency);
+        strategy.id = config.name.clone();
+        strategy.is_active = config.enabled;
+        strategy.tags = config.tags.clone();
+        strategy.position_size_multiplier = decimal("position_size_multiplier", config.position_size_multiplier)?;
+        if config.max_trade_amount > 0.0 {
+            strategy.max_trade_amount = Some(decimal("max_trade_amount", config.max_trade_amount)?);
         }
+        Ok(strategy)
+    }
+
+    /// Size of a trade when `configured` is the configured trade size
+    pub fn trade_size(&self, configured: Decimal) -> Decimal {
+        let size = configured * self.position_size_multiplier;
+        self.max_trade_amount.map_or(size, |max| size.min(max))
+    }
+
+    /// Strategy id unique across tenants: `namespace/id`, or the bare id outside any namespace
+    pub fn scoped_id(namespace: &str, id: &str) -> String {
+        if namespace.is_empty() || id.starts_with(&format!("{}/", namespace)) {
+            id.to_string()
+        } else {
+            format!("{}/{}", namespace, id)
+        }
+    }
+
+    /// The id as its tenant knows it, without the namespace prefix
+    pub fn local_id(&self) -> &str {
+        self.id
+            .strip_prefix(self.namespace.as_str())
+            .and_then(|id| id.strip_prefix('/'))
+            .unwrap_or(&self.id)
+    }
+
+    /// Move the strategy into a tenant's namespace, scoping its id
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.id = Self::scoped_id(namespace, self.local_id());
+        self.namespace = namespace.to_string();
+        self
+    }
+
+    pub fn with_urgency(mut self, urgency: Urgency) -> Self {
+        self.urgency = urgency;
+        self
+    }
+
+    /// Evaluate through the registered `kind`, built with `parameters`
+    pub fn with_kind(mut self, kind: &str, parameters: serde_json::Value) -> Self {
+        self.kind = kind.to_string();
+        self.parameters = parameters;
+        self
+    }
+
+    pub fn with_tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.insert(key.to_string(), value.to_string());
+        self
     }
 
     pub fn is_opportunity_suitable(&self, opportunity: &ArbitrageOpportunity) -> bool {
@@ -283,6 +749,55 @@ impl ArbitrageStrategy {
             && self.supported_dexes.contains(&opportunity.buy_pool.dex_type)
             && self.supported_dexes.contains(&opportunity.sell_pool.dex_type)
     }
+
+    /// Thresholds that differ in `updated`, with their old and new values
+    pub fn threshold_changes(&self, updated: &ArbitrageStrategy) -> Vec<ParameterChange> {
+        [
+            ("min_profit_threshold", self.min_profit_threshold, updated.min_profit_threshold),
+            ("max_slippage", self.max_slippage, updated.max_slippage),
+            ("max_price_impact", self.max_price_impact, updated.max_price_impact),
+            ("min_liquidity", self.min_liquidity, updated.min_liquidity),
+        ]
+        .into_iter()
+        .filter(|(_, previous, new)| previous != new)
+        .map(|(field, previous, new)| ParameterChange { field: field.to_string(), previous, new })
+        .collect()
+    }
+}
+
+/// A strategy parameter before and after a runtime update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterChange {
+    pub field: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub previous: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub new: Decimal,
+}
+
+impl ParameterChange {
+    /// How many times larger or smaller the new value is; None when only one side is zero
+    pub fn factor(&self) -> Option<Decimal> {
+        let (previous, new) = (self.previous.abs(), self.new.abs());
+        if previous == new {
+            return Some(Decimal::ONE);
+        }
+        if previous.is_zero() || new.is_zero() {
+            return None;
+        }
+        Some(previous.max(new) / previous.min(new))
+    }
+
+    /// Whether the change is a bigger step than `max_factor` allows
+    pub fn exceeds(&self, max_factor: Decimal) -> bool {
+        self.factor().is_none_or(|factor| factor > max_factor)
+    }
+}
+
+impl std::fmt::Display for ParameterChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} -> {}", self.field, self.previous, self.new)
+    }
 }
 
 impl std::fmt::Display for ArbitrageOpportunity {
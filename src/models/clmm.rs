@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+/// An initialized tick; `liquidity_net` is added to the active liquidity when the price crosses it upwards
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ClmmTick {
+    pub index: i32,
+    pub liquidity_net: i128,
+}
+
+/// Concentrated liquidity state of a pool in raw token units, token A being the pool's token_a.
+///
+/// Only the ticks loaded around the current price are known, so swaps that would run past them
+/// cannot be quoted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClmmState {
+    /// sqrt(token B per token A)
+    pub sqrt_price: f64,
+    /// Liquidity active at the current price
+    pub liquidity: f64,
+    pub tick_current: i32,
+    /// Initialized ticks, ascending by index
+    pub ticks: Vec<ClmmTick>,
+}
+
+/// Result of swapping through the ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClmmSwap {
+    pub amount_out: f64,
+    pub sqrt_price_after: f64,
+}
+
+pub fn sqrt_price_at_tick(tick: i32) -> f64 {
+    1.0001f64.powf(tick as f64 / 2.0)
+}
+
+pub fn tick_at_sqrt_price(sqrt_price: f64) -> i32 {
+    (2.0 * sqrt_price.ln() / 1.0001f64.ln()).floor() as i32
+}
+
+impl ClmmState {
+    pub fn new(sqrt_price: f64, liquidity: f64, tick_current: i32, mut ticks: Vec<ClmmTick>) -> Self {
+        ticks.sort_by_key(|tick| tick.index);
+        Self { sqrt_price, liquidity, tick_current, ticks }
+    }
+
+    /// Virtual reserves at the current price: `L / sqrt(P)` of token A and `L * sqrt(P)` of token B
+    pub fn virtual_reserves(&self) -> (f64, f64) {
+        (self.liquidity / self.sqrt_price, self.liquidity * self.sqrt_price)
+    }
+
+    /// Move to the price and active liquidity implied by virtual reserves, keeping the known ticks
+    pub fn sync_to_reserves(&mut self, reserve_a: f64, reserve_b: f64) {
+        if reserve_a <= 0.0 || reserve_b <= 0.0 {
+            return;
+        }
+        self.sqrt_price = (reserve_b / reserve_a).sqrt();
+        self.liquidity = (reserve_a * reserve_b).sqrt();
+        self.tick_current = tick_at_sqrt_price(self.sqrt_price);
+    }
+
+    /// Swap `amount_in` (after fees) of token A for B when `a_to_b`, else B for A, crossing
+    /// initialized ticks as the price moves; None when the known ticks run out first
+    pub fn swap(&self, amount_in: f64, a_to_b: bool) -> Option<ClmmSwap> {
+        if amount_in <= 0.0 || self.sqrt_price <= 0.0 {
+            return None;
+        }
+        let mut remaining = amount_in;
+        let mut amount_out = 0.0;
+        let mut sqrt_price = self.sqrt_price;
+        let mut liquidity = self.liquidity;
+
+        // Ticks in the order the price meets them
+        let mut next_ticks: Box<dyn Iterator<Item = &ClmmTick>> = if a_to_b {
+            Box::new(self.ticks.iter().rev().filter(|tick| tick.index <= self.tick_current))
+        } else {
+            Box::new(self.ticks.iter().filter(|tick| tick.index > self.tick_current))
+        };
+
+        loop {
+            let tick = next_ticks.next()?;
+            let target = sqrt_price_at_tick(tick.index);
+            if liquidity > 0.0 {
+                // Input needed to reach the tick, and the output it yields
+                let (needed, yielded) = if a_to_b {
+                    (liquidity * (1.0 / target - 1.0 / sqrt_price), liquidity * (sqrt_price - target))
+                } else {
+                    (liquidity * (target - sqrt_price), liquidity * (1.0 / sqrt_price - 1.0 / target))
+                };
+                if remaining < needed {
+                    let after = if a_to_b {
+                        liquidity * sqrt_price / (liquidity + remaining * sqrt_price)
+                    } else {
+                        sqrt_price + remaining / liquidity
+                    };
+                    amount_out += if a_to_b {
+                        liquidity * (sqrt_price - after)
+                    } else {
+                        liquidity * (1.0 / sqrt_price - 1.0 / after)
+                    };
+                    return Some(ClmmSwap { amount_out, sqrt_price_after: after });
+                }
+                remaining -= needed;
+                amount_out += yielded;
+            }
+            sqrt_price = target;
+            let net = tick.liquidity_net as f64;
+            liquidity = if a_to_b { liquidity - net } else { liquidity + net }.max(0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swaps_cross_ticks_and_lose_depth() {
+        // Price 1.0 at tick 0 with liquidity 1000 between ticks -100 and 100, and 4000 more above
+        let state = ClmmState::new(
+            1.0,
+            1000.0,
+            0,
+            vec![
+                ClmmTick { index: 100, liquidity_net: 3000 },
+                ClmmTick { index: -100, liquidity_net: 1000 },
+                ClmmTick { index: 300, liquidity_net: -4000 },
+            ],
+        );
+        assert_eq!(state.virtual_reserves(), (1000.0, 1000.0));
+
+        // Within the range it is a constant product over the virtual reserves
+        let small = state.swap(1.0, true).unwrap();
+        assert!((small.amount_out - 1000.0 / 1001.0).abs() < 1e-9);
+
+        // Selling A runs out of liquidity below tick -100; ticks are only known that far
+        let edge = 1000.0 * (1.0 / sqrt_price_at_tick(-100) - 1.0);
+        assert!(state.swap(edge * 0.99, true).is_some());
+        assert!(state.swap(edge * 1.01, true).is_none());
+
+        // Buying A past tick 100 meets 4x the liquidity, so it slips less than a plain curve would
+        let big = state.swap(20.0, false).unwrap();
+        let (reserve_a, reserve_b) = state.virtual_reserves();
+        let constant_product = 20.0 * reserve_a / (reserve_b + 20.0);
+        assert!(big.amount_out > constant_product);
+        assert!(big.sqrt_price_after > sqrt_price_at_tick(100));
+    }
+}
@@ -1,9 +1,13 @@
 pub mod token;
 pub mod pool;
+pub mod order_book;
+pub mod clmm;
 pub mod arbitrage;
 pub mod transaction;
 
 pub use token::*;
 pub use pool::*;
+pub use order_book::*;
+pub use clmm::*;
 pub use arbitrage::*;
 pub use transaction::*;
@@ -0,0 +1,106 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Resting size at one price; price in quote per base, size in base
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BookLevel {
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+/// Visible depth of an order book market, best levels first on both sides
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct OrderBook {
+    /// Highest price first
+    pub bids: Vec<BookLevel>,
+    /// Lowest price first
+    pub asks: Vec<BookLevel>,
+}
+
+impl OrderBook {
+    /// Book from levels in any order; empty levels are dropped
+    pub fn new(mut bids: Vec<BookLevel>, mut asks: Vec<BookLevel>) -> Self {
+        let live = |level: &BookLevel| level.price > Decimal::ZERO && level.size > Decimal::ZERO;
+        bids.retain(live);
+        asks.retain(live);
+        bids.sort_by_key(|level| std::cmp::Reverse(level.price));
+        asks.sort_by_key(|level| level.price);
+        Self { bids, asks }
+    }
+
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.first().map(|level| level.price)
+    }
+
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.first().map(|level| level.price)
+    }
+
+    /// Midpoint of the touch; None when either side is empty
+    pub fn mid_price(&self) -> Option<Decimal> {
+        Some((self.best_bid()? + self.best_ask()?) / Decimal::TWO)
+    }
+
+    /// Base size resting on both sides
+    pub fn base_depth(&self) -> Decimal {
+        self.bids.iter().chain(&self.asks).map(|level| level.size).sum()
+    }
+
+    /// Quote received for selling `base` into the bids; None when the bids cannot absorb it all
+    pub fn sell_base(&self, base: Decimal) -> Option<Decimal> {
+        let mut remaining = base;
+        let mut quote = Decimal::ZERO;
+        for level in &self.bids {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            let fill = remaining.min(level.size);
+            quote += fill * level.price;
+            remaining -= fill;
+        }
+        (remaining <= Decimal::ZERO).then_some(quote)
+    }
+
+    /// Base bought by spending `quote` on the asks; None when the asks run out first
+    pub fn buy_base(&self, quote: Decimal) -> Option<Decimal> {
+        let mut remaining = quote;
+        let mut base = Decimal::ZERO;
+        for level in &self.asks {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            let fill = (remaining / level.price).min(level.size);
+            base += fill;
+            remaining -= fill * level.price;
+        }
+        (remaining <= Decimal::ZERO).then_some(base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(price: i64, size: i64) -> BookLevel {
+        BookLevel { price: Decimal::from(price), size: Decimal::from(size) }
+    }
+
+    #[test]
+    fn test_fills_walk_the_book() {
+        let book = OrderBook::new(
+            vec![level(99, 1), level(100, 2), level(98, 0)],
+            vec![level(102, 3), level(101, 1)],
+        );
+        assert_eq!(book.best_bid(), Some(Decimal::from(100)));
+        assert_eq!(book.best_ask(), Some(Decimal::from(101)));
+        assert_eq!(book.mid_price(), Some(Decimal::new(1005, 1)));
+        assert_eq!(book.base_depth(), Decimal::from(7));
+
+        // 2 at 100, then 0.5 at 99
+        assert_eq!(book.sell_base(Decimal::new(25, 1)), Some(Decimal::new(2495, 1)));
+        assert_eq!(book.sell_base(Decimal::from(4)), None);
+        // 1 at 101, then 1 at 102
+        assert_eq!(book.buy_base(Decimal::from(203)), Some(Decimal::from(2)));
+        assert_eq!(book.buy_base(Decimal::from(1000)), None);
+    }
+}
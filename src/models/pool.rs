@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use solana_program::pubkey::Pubkey;
 use rust_decimal::Decimal;
+use crate::models::clmm::{ClmmState, ClmmSwap};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use crate::models::order_book::OrderBook;
 use crate::models::token::Token;
 use crate::dex::DexType;
 use std::collections::HashMap;
@@ -20,6 +23,33 @@ pub struct Pool {
     pub version: String,
     pub is_active: bool,
     pub last_updated: chrono::DateTime<chrono::Utc>,
+    /// Depth of an order book market, token_a being the base; reserves are then virtual
+    #[serde(default)]
+    pub order_book: Option<OrderBook>,
+    /// Concentrated liquidity around the current price; reserves are then virtual
+    #[serde(default)]
+    pub clmm: Option<ClmmState>,
+    /// A bonding curve that has completed and moved its liquidity to an AMM; it no longer trades
+    #[serde(default)]
+    pub is_graduated: bool,
+}
+
+/// Reserves of one pool at one moment, flat so it reads the same from CSV, JSONL or the database
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PoolSnapshot {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub dex: DexType,
+    pub pool_address: String,
+    pub token_a_mint: String,
+    pub token_a_symbol: String,
+    pub token_a_decimals: u8,
+    pub token_b_mint: String,
+    pub token_b_symbol: String,
+    pub token_b_decimals: u8,
+    pub reserve_a: Decimal,
+    pub reserve_b: Decimal,
+    #[serde(default)]
+    pub fee_rate: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +121,9 @@ impl Pool {
             version: "1.0".to_string(),
             is_active: true,
             last_updated: chrono::Utc::now(),
+            order_book: None,
+            clmm: None,
+            is_graduated: false,
         }
     }
 
@@ -106,6 +139,52 @@ impl Pool {
         self
     }
 
+    /// Price the pool off an order book: virtual reserves of the book's base depth at its mid price,
+    /// so spot prices and liquidity filters treat it like an AMM while fills walk the levels
+    pub fn with_order_book(mut self, book: OrderBook) -> Self {
+        let depth = book.base_depth();
+        let (reserve_a, reserve_b) = match book.mid_price() {
+            Some(mid) => (depth, depth * mid),
+            None => (Decimal::ZERO, Decimal::ZERO),
+        };
+        self.order_book = Some(book);
+        self.update_reserves(reserve_a, reserve_b)
+    }
+
+    /// Quote the pool off its concentrated liquidity, with reserves set to the virtual reserves at its price
+    pub fn with_clmm(mut self, clmm: ClmmState) -> Self {
+        let (raw_a, raw_b) = clmm.virtual_reserves();
+        let reserve_a = Decimal::from_f64(raw_a / 10f64.powi(self.token_a.decimals as i32)).unwrap_or_default();
+        let reserve_b = Decimal::from_f64(raw_b / 10f64.powi(self.token_b.decimals as i32)).unwrap_or_default();
+        self.clmm = Some(clmm);
+        self.update_reserves(reserve_a, reserve_b)
+    }
+
+    /// Apply streamed reserves; a concentrated liquidity pool moves its price to match
+    pub fn set_reserves(&mut self, reserve_a: Decimal, reserve_b: Decimal, timestamp: chrono::DateTime<chrono::Utc>) {
+        self.reserve_a = reserve_a;
+        self.reserve_b = reserve_b;
+        self.last_updated = timestamp;
+        if let Some(clmm) = self.clmm.as_mut() {
+            let raw = |amount: Decimal, decimals: u8| amount.to_f64().unwrap_or_default() * 10f64.powi(decimals as i32);
+            clmm.sync_to_reserves(raw(reserve_a, self.token_a.decimals), raw(reserve_b, self.token_b.decimals));
+        }
+    }
+
+    /// Swap through the concentrated liquidity in whole tokens; the output and the price after
+    fn clmm_swap(&self, clmm: &ClmmState, input_amount: Decimal, input_token: &Token) -> Option<(Decimal, ClmmSwap)> {
+        let a_to_b = input_token.mint == self.token_a.mint;
+        let (input_decimals, output_decimals) = if a_to_b {
+            (self.token_a.decimals, self.token_b.decimals)
+        } else {
+            (self.token_b.decimals, self.token_a.decimals)
+        };
+        let raw_in = input_amount.to_f64()? * 10f64.powi(input_decimals as i32);
+        let swap = clmm.swap(raw_in, a_to_b)?;
+        let output = Decimal::from_f64(swap.amount_out / 10f64.powi(output_decimals as i32))?;
+        Some((output, swap))
+    }
+
     pub fn get_price(&self, base_token: &Token) -> Option<Decimal> {
         if base_token.mint == self.token_a.mint {
             if self.reserve_b > Decimal::ZERO {
@@ -141,19 +220,92 @@ impl Pool {
             return None;
         }
 
+        let output_token = if input_token.mint == self.token_a.mint {
+            &self.token_b
+        } else {
+            &self.token_a
+        };
+
+        // Token-2022 transfer fees are taken on the way into and out of the pool
+        let input_amount = input_token.amount_after_transfer_fee(input_amount);
+
         let fee_multiplier = Decimal::ONE - self.fee_rate;
         let input_with_fee = input_amount * fee_multiplier;
+
+        if let Some(clmm) = &self.clmm {
+            let (output, _) = self.clmm_swap(clmm, input_with_fee, input_token)?;
+            return Some(output_token.amount_after_transfer_fee(output));
+        }
+
+        if let Some(book) = &self.order_book {
+            let output = if input_token.mint == self.token_a.mint {
+                book.sell_base(input_with_fee)?
+            } else {
+                book.buy_base(input_with_fee)?
+            };
+            return Some(output_token.amount_after_transfer_fee(output));
+        }
         let numerator = input_with_fee * output_reserve;
         let denominator = input_reserve + input_with_fee;
 
         if denominator > Decimal::ZERO {
-            Some(numerator / denominator)
+            Some(output_token.amount_after_transfer_fee(numerator / denominator))
         } else {
             None
         }
     }
 
+    /// Input that maximizes profit when `input_token` is swapped here and the proceeds swapped back
+    /// through `back`, treating both as constant-product pools; None when no size profits.
+    ///
+    /// The two legs compose into one constant-product curve with virtual reserves
+    /// `r_in = x1·y2 / (y2 + γ2·y1)` and `r_out = γ2·y1·x2 / (y2 + γ2·y1)`, where `γ` is one minus
+    /// the fee rate. Setting the derivative of `out(a) − a` to zero gives
+    /// `a* = (√(γ1·r_in·r_out) − r_in) / γ1`.
+    pub fn optimal_round_trip_input(&self, back: &Pool, input_token: &Token) -> Option<Decimal> {
+        let (x1, y1, middle) = if input_token.mint == self.token_a.mint {
+            (self.reserve_a, self.reserve_b, &self.token_b)
+        } else if input_token.mint == self.token_b.mint {
+            (self.reserve_b, self.reserve_a, &self.token_a)
+        } else {
+            return None;
+        };
+        let (y2, x2) = if middle.mint == back.token_a.mint && input_token.mint == back.token_b.mint {
+            (back.reserve_a, back.reserve_b)
+        } else if middle.mint == back.token_b.mint && input_token.mint == back.token_a.mint {
+            (back.reserve_b, back.reserve_a)
+        } else {
+            return None;
+        };
+        if [x1, y1, y2, x2].iter().any(|reserve| *reserve <= Decimal::ZERO) {
+            return None;
+        }
+
+        let (gamma1, gamma2) = (Decimal::ONE - self.fee_rate, Decimal::ONE - back.fee_rate);
+        let denominator = y2 + gamma2 * y1;
+        let r_in = x1 * y2 / denominator;
+        let r_out = gamma2 * y1 * x2 / denominator;
+        // Past this point even the first unit loses
+        if gamma1 * r_out <= r_in {
+            return None;
+        }
+        let root = Decimal::from_f64((gamma1 * r_in * r_out).to_f64()?.sqrt())?;
+        let optimal = (root - r_in) / gamma1;
+        (optimal > Decimal::ZERO).then_some(optimal)
+    }
+
     pub fn calculate_price_impact(&self, input_amount: Decimal, input_token: &Token) -> Option<Decimal> {
+        if let Some(clmm) = &self.clmm {
+            // Move of the pool price itself, which is what the next trade sees
+            let input_with_fee = input_token.amount_after_transfer_fee(input_amount) * (Decimal::ONE - self.fee_rate);
+            let (_, swap) = self.clmm_swap(clmm, input_with_fee, input_token)?;
+            let before = clmm.sqrt_price * clmm.sqrt_price;
+            let after = swap.sqrt_price_after * swap.sqrt_price_after;
+            return Decimal::from_f64(((after - before) / before).abs());
+        }
+        if let Some(book) = &self.order_book {
+            return self.book_price_impact(book, input_amount, input_token);
+        }
         let price_before = self.get_price(input_token)?;
         let output_amount = self.calculate_output_amount(input_amount, input_token)?;
         
@@ -176,6 +328,62 @@ impl Pool {
         
         Some(price_change.abs())
     }
+
+    /// How far the average fill lands from the touch it starts at
+    fn book_price_impact(&self, book: &OrderBook, input_amount: Decimal, input_token: &Token) -> Option<Decimal> {
+        let filled = input_amount * (Decimal::ONE - self.fee_rate);
+        if filled <= Decimal::ZERO {
+            return None;
+        }
+        if input_token.mint == self.token_a.mint {
+            let touch = book.best_bid()?;
+            let average = book.sell_base(filled)? / filled;
+            Some((touch - average) / touch)
+        } else {
+            let touch = book.best_ask()?;
+            let average = filled / book.buy_base(filled)?;
+            Some((average - touch) / touch)
+        }
+    }
+}
+
+impl PoolSnapshot {
+    pub fn from_pool(pool: &Pool, timestamp: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            timestamp,
+            dex: pool.dex_type.clone(),
+            pool_address: pool.pool_address.to_string(),
+            token_a_mint: pool.token_a.mint.to_string(),
+            token_a_symbol: pool.token_a.symbol.clone(),
+            token_a_decimals: pool.token_a.decimals,
+            token_b_mint: pool.token_b.mint.to_string(),
+            token_b_symbol: pool.token_b.symbol.clone(),
+            token_b_decimals: pool.token_b.decimals,
+            reserve_a: pool.reserve_a,
+            reserve_b: pool.reserve_b,
+            fee_rate: pool.fee_rate,
+        }
+    }
+
+    /// The pool as it stood at the snapshot, identified by its address
+    pub fn to_pool(&self) -> anyhow::Result<Pool> {
+        let token = |mint: &str, symbol: &str, decimals| -> anyhow::Result<Token> {
+            Ok(Token::new(mint.parse()?, symbol.to_string(), symbol.to_string(), decimals))
+        };
+        let mut pool = Pool::new(
+            self.pool_address.clone(),
+            self.dex.clone(),
+            token(&self.token_a_mint, &self.token_a_symbol, self.token_a_decimals)?,
+            token(&self.token_b_mint, &self.token_b_symbol, self.token_b_decimals)?,
+            self.pool_address.parse()?,
+            Pubkey::default(),
+            Pubkey::default(),
+        )
+        .update_reserves(self.reserve_a, self.reserve_b)
+        .with_fee_rate(self.fee_rate);
+        pool.last_updated = self.timestamp;
+        Ok(pool)
+    }
 }
 
 impl std::fmt::Display for Pool {
@@ -187,3 +395,37 @@ impl std::fmt::Display for Pool {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fixtures;
+
+    #[test]
+    fn test_optimal_round_trip_input_maximizes_profit() {
+        let (sol, usdc) = fixtures::sol_usdc();
+        let pool = |sol_reserve: i64, usdc_reserve: i64| {
+            let mut pool = fixtures::pool(DexType::Raydium, &sol, &usdc);
+            pool.reserve_a = Decimal::from(sol_reserve);
+            pool.reserve_b = Decimal::from(usdc_reserve);
+            pool.fee_rate = Decimal::new(3, 3);
+            pool
+        };
+        // SOL sells for 155 USDC in one pool and buys back at 150 in the other
+        let (rich, cheap) = (pool(1_000, 155_000), pool(1_000, 150_000));
+        let profit = |amount: Decimal| {
+            let usdc_out = rich.calculate_output_amount(amount, &sol).unwrap();
+            cheap.calculate_output_amount(usdc_out, &usdc).unwrap() - amount
+        };
+
+        let optimal = rich.optimal_round_trip_input(&cheap, &sol).unwrap();
+        assert!(profit(optimal) > Decimal::ZERO);
+        let step = optimal / Decimal::from(20);
+        assert!(profit(optimal) >= profit(optimal - step));
+        assert!(profit(optimal) >= profit(optimal + step));
+
+        // The other way round, or once fees eat the spread, nothing profits
+        assert_eq!(cheap.optimal_round_trip_input(&rich, &sol), None);
+        assert_eq!(pool(1_000, 150_200).optimal_round_trip_input(&cheap, &sol), None);
+    }
+}
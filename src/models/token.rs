@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use solana_program::pubkey::Pubkey;
 use rust_decimal::Decimal;
+use spl_associated_token_account_interface::address::get_associated_token_address_with_program_id;
+use spl_token_2022_interface::extension::ExtensionType;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -11,6 +13,61 @@ pub struct Token {
     pub decimals: u8,
     pub logo_uri: Option<String>,
     pub coingecko_id: Option<String>,
+    /// Token-2022 transfer fee, when the mint has the extension
+    #[serde(default)]
+    pub transfer_fee: Option<TransferFee>,
+    /// Program owning the mint: SPL Token or Token-2022
+    #[serde(default = "spl_token_program")]
+    pub token_program: Pubkey,
+    /// Token-2022 extensions of the mint
+    #[serde(default)]
+    pub extensions: Vec<TokenExtension>,
+}
+
+/// The classic SPL Token program, owner of mints recorded before Token-2022 support
+pub fn spl_token_program() -> Pubkey {
+    spl_token_interface::id()
+}
+
+/// Token-2022 mint extensions that change how a token trades
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum TokenExtension {
+    TransferFee,
+    TransferHook,
+    InterestBearing,
+    NonTransferable,
+    PermanentDelegate,
+    DefaultAccountState,
+    ConfidentialTransfer,
+    Pausable,
+    ScaledUiAmount,
+    /// Metadata, groups and other extensions that leave transfers alone
+    Other,
+}
+
+impl From<ExtensionType> for TokenExtension {
+    fn from(extension: ExtensionType) -> Self {
+        match extension {
+            ExtensionType::TransferFeeConfig => TokenExtension::TransferFee,
+            ExtensionType::TransferHook => TokenExtension::TransferHook,
+            ExtensionType::InterestBearingConfig => TokenExtension::InterestBearing,
+            ExtensionType::NonTransferable => TokenExtension::NonTransferable,
+            ExtensionType::PermanentDelegate => TokenExtension::PermanentDelegate,
+            ExtensionType::DefaultAccountState => TokenExtension::DefaultAccountState,
+            ExtensionType::ConfidentialTransferMint => TokenExtension::ConfidentialTransfer,
+            ExtensionType::Pausable => TokenExtension::Pausable,
+            ExtensionType::ScaledUiAmount => TokenExtension::ScaledUiAmount,
+            _ => TokenExtension::Other,
+        }
+    }
+}
+
+/// Token-2022 transfer fee charged on every transfer of the mint
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct TransferFee {
+    pub basis_points: u16,
+    /// Maximum fee per transfer in raw token units
+    pub maximum_fee: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +111,9 @@ impl Token {
             decimals,
             logo_uri: None,
             coingecko_id: None,
+            transfer_fee: None,
+            token_program: spl_token_program(),
+            extensions: Vec::new(),
         }
     }
 
@@ -66,6 +126,72 @@ impl Token {
         self.coingecko_id = Some(coingecko_id);
         self
     }
+
+    pub fn with_transfer_fee(mut self, transfer_fee: TransferFee) -> Self {
+        self.transfer_fee = Some(transfer_fee);
+        self
+    }
+
+    /// Mark the mint as owned by `token_program` with the given extensions
+    pub fn with_token_program(mut self, token_program: Pubkey, extensions: Vec<TokenExtension>) -> Self {
+        self.token_program = token_program;
+        self.extensions = extensions;
+        self
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.token_program == spl_token_2022_interface::id()
+    }
+
+    pub fn has_extension(&self, extension: TokenExtension) -> bool {
+        self.extensions.contains(&extension)
+    }
+
+    /// The owner's associated token account, derived under the mint's program
+    pub fn associated_token_address(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+
+    pub fn has_transfer_fee(&self) -> bool {
+        self.transfer_fee.is_some_and(|fee| fee.basis_points > 0)
+    }
+
+    /// Transfer fee as a fraction of the amount, ignoring the maximum fee cap
+    pub fn transfer_fee_rate(&self) -> Decimal {
+        self.transfer_fee
+            .map(|fee| Decimal::from(fee.basis_points) / Decimal::from(10_000))
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// Amount received after the transfer fee, in UI units
+    pub fn amount_after_transfer_fee(&self, amount: Decimal) -> Decimal {
+        let Some(fee) = self.transfer_fee else {
+            return amount;
+        };
+
+        let rate_fee = amount * self.transfer_fee_rate();
+        let max_fee = Decimal::from(fee.maximum_fee) / Decimal::from(10u64.pow(self.decimals as u32));
+        amount - rate_fee.min(max_fee)
+    }
+
+    /// Amount to send for `net` to arrive after the transfer fee, in UI units
+    pub fn amount_before_transfer_fee(&self, net: Decimal) -> Decimal {
+        let Some(fee) = self.transfer_fee else {
+            return net;
+        };
+        let max_fee = Decimal::from(fee.maximum_fee) / Decimal::from(10u64.pow(self.decimals as u32));
+        let rate = self.transfer_fee_rate();
+        if rate >= Decimal::ONE {
+            return net + max_fee;
+        }
+        // Below the cap the fee scales with the gross amount; above it, it is the cap
+        let gross = net / (Decimal::ONE - rate);
+        if gross * rate > max_fee {
+            net + max_fee
+        } else {
+            gross.round_dp_with_strategy(self.decimals as u32, rust_decimal::RoundingStrategy::AwayFromZero)
+        }
+    }
 }
 
 impl std::fmt::Display for Token {
@@ -73,3 +199,37 @@ impl std::fmt::Display for Token {
         write!(f, "{} ({})", self.symbol, self.mint)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_2022_accounts_and_fee_math() {
+        let owner = Pubkey::new_unique();
+        let classic = Token::new(Pubkey::new_unique(), "USDC".to_string(), "USD Coin".to_string(), 6);
+        assert!(!classic.is_token_2022());
+        assert_eq!(
+            classic.associated_token_address(&owner),
+            spl_associated_token_account_interface::address::get_associated_token_address(&owner, &classic.mint)
+        );
+
+        let taxed = Token::new(Pubkey::new_unique(), "TAX".to_string(), "Taxed".to_string(), 6)
+            .with_token_program(spl_token_2022_interface::id(), vec![TokenExtension::TransferFee])
+            .with_transfer_fee(TransferFee { basis_points: 100, maximum_fee: 5_000_000 });
+        assert!(taxed.is_token_2022() && taxed.has_extension(TokenExtension::TransferFee));
+        assert_ne!(taxed.associated_token_address(&owner), classic.associated_token_address(&owner));
+
+        // 1% of 100 is 1, so 99 arrives; sending 100 is what it takes to receive 99
+        assert_eq!(taxed.amount_after_transfer_fee(Decimal::from(100)), Decimal::from(99));
+        assert_eq!(taxed.amount_before_transfer_fee(Decimal::from(99)), Decimal::from(100));
+        // Past the 5 token cap the fee is flat
+        assert_eq!(taxed.amount_before_transfer_fee(Decimal::from(1_000)), Decimal::from(1_005));
+
+        // Mints recorded before Token-2022 support belonged to the classic program
+        let mut json = serde_json::to_value(&classic).unwrap();
+        json.as_object_mut().unwrap().remove("token_program");
+        let restored: Token = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.token_program, spl_token_program());
+    }
+}
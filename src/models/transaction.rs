@@ -4,7 +4,8 @@ use solana_sdk::{
     transaction::Transaction,
     signature::Signature,
 };
-use solana_commitment_config::CommitmentConfig;
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+use crate::models::Urgency;
 use rust_decimal::Decimal;
 use chrono::{DateTime, Utc};
 
@@ -103,6 +104,18 @@ pub struct TransactionConfig {
     pub max_priority_fee: u64,
 }
 
+/// Per-transaction submission options
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SendOptions {
+    pub skip_preflight: bool,
+    pub preflight_commitment: CommitmentLevel,
+    /// RPC-side retries; `None` leaves it to the node default
+    pub max_retries: Option<usize>,
+    /// Keep re-sending to every rebroadcast node until the transaction lands or its blockhash expires
+    #[serde(default)]
+    pub rebroadcast: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionPool {
     pub pending_transactions: Vec<TransactionRequest>,
@@ -240,6 +253,40 @@ impl Default for TransactionConfig {
     }
 }
 
+impl SendOptions {
+    /// Derive send options from strategy urgency
+    pub fn for_urgency(urgency: Urgency) -> Self {
+        match urgency {
+            // Latency matters more than a failed-simulation fee; the node's slow retry queue is
+            // replaced by the rebroadcaster
+            Urgency::High => Self {
+                skip_preflight: true,
+                preflight_commitment: CommitmentLevel::Processed,
+                max_retries: Some(0),
+                rebroadcast: true,
+            },
+            Urgency::Normal => Self {
+                skip_preflight: false,
+                preflight_commitment: CommitmentLevel::Processed,
+                max_retries: Some(2),
+                rebroadcast: false,
+            },
+            Urgency::Low => Self {
+                skip_preflight: false,
+                preflight_commitment: CommitmentLevel::Confirmed,
+                max_retries: None,
+                rebroadcast: false,
+            },
+        }
+    }
+}
+
+impl Default for SendOptions {
+    fn default() -> Self {
+        Self::for_urgency(Urgency::default())
+    }
+}
+
 impl TransactionPool {
     pub fn new(max_pool_size: usize) -> Self {
         Self {
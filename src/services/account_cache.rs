@@ -0,0 +1,136 @@
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::config::AccountCacheConfig;
+use crate::utils::panic_guard::lock_or_recover;
+
+/// An account as of the slot it was fetched at; `None` when it did not exist
+#[derive(Debug, Clone)]
+struct CachedAccount {
+    slot: u64,
+    account: Option<Account>,
+}
+
+/// Newest slot seen in an RPC response and when it was seen
+#[derive(Debug, Clone, Copy)]
+struct SlotClock {
+    slot: u64,
+    observed_at: Instant,
+}
+
+/// Account reads keyed by pubkey and tagged with the slot they were fetched at.
+///
+/// The current slot is estimated from the newest response slot plus the time since, so checking
+/// freshness never costs an RPC call.
+pub struct AccountCache {
+    config: AccountCacheConfig,
+    entries: Mutex<HashMap<Pubkey, CachedAccount>>,
+    clock: Mutex<Option<SlotClock>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl AccountCache {
+    pub fn new(config: AccountCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+            clock: Mutex::new(None),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Estimated current slot, or None before any response was seen
+    pub fn current_slot(&self) -> Option<u64> {
+        let clock = (*lock_or_recover(&self.clock, "Account cache clock"))?;
+        let elapsed = clock.observed_at.elapsed().as_millis() as u64 / self.config.slot_ms.max(1);
+        Some(clock.slot + elapsed)
+    }
+
+    /// Record the context slot of an RPC response
+    pub fn observe_slot(&self, slot: u64) {
+        let mut clock = lock_or_recover(&self.clock, "Account cache clock");
+        if clock.is_none_or(|c| slot > c.slot) {
+            *clock = Some(SlotClock { slot, observed_at: Instant::now() });
+        }
+    }
+
+    /// The cached account if it was fetched within `max_age_slots` of the current slot.
+    /// The outer `None` is a miss; `Some(None)` is a cached "account does not exist".
+    pub fn get(&self, pubkey: &Pubkey) -> Option<Option<Account>> {
+        let fresh = self.current_slot().and_then(|current| {
+            let entries = lock_or_recover(&self.entries, "Account cache");
+            let entry = entries.get(pubkey)?;
+            (current.saturating_sub(entry.slot) <= self.config.max_age_slots).then(|| entry.account.clone())
+        });
+        let counter = if fresh.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        fresh
+    }
+
+    /// Store accounts fetched at `slot`
+    pub fn insert(&self, slot: u64, accounts: impl IntoIterator<Item = (Pubkey, Option<Account>)>) {
+        self.observe_slot(slot);
+        let mut entries = lock_or_recover(&self.entries, "Account cache");
+        for (pubkey, account) in accounts {
+            entries.insert(pubkey, CachedAccount { slot, account });
+        }
+        if entries.len() > self.config.max_entries {
+            // Evict down to half capacity, oldest fetches first, rather than one entry per insert
+            let excess = entries.len() - self.config.max_entries / 2;
+            let mut by_age: Vec<(u64, Pubkey)> = entries.iter().map(|(pubkey, e)| (e.slot, *pubkey)).collect();
+            by_age.sort_unstable();
+            for (_, pubkey) in by_age.into_iter().take(excess) {
+                entries.remove(&pubkey);
+            }
+        }
+    }
+
+    /// Forget an account, e.g. after sending a transaction that changes it
+    pub fn invalidate(&self, pubkey: &Pubkey) {
+        lock_or_recover(&self.entries, "Account cache").remove(pubkey);
+    }
+
+    /// Hits and misses since creation
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_expire_by_slot_age() {
+        let cache = AccountCache::new(AccountCacheConfig { enabled: true, max_age_slots: 1, slot_ms: 60_000, max_entries: 4 });
+        let (present, missing) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(cache.get(&present).is_none());
+
+        let account = Account { lamports: 42, ..Default::default() };
+        cache.insert(100, [(present, Some(account)), (missing, None)]);
+        assert_eq!(cache.get(&present).unwrap().unwrap().lamports, 42);
+        assert!(cache.get(&missing).unwrap().is_none());
+
+        // One slot later the entry is still within max_age_slots; two slots later it is stale
+        cache.observe_slot(101);
+        assert!(cache.get(&present).is_some());
+        cache.observe_slot(102);
+        assert!(cache.get(&present).is_none());
+        assert_eq!(cache.stats(), (3, 2));
+
+        cache.insert(102, [(present, None)]);
+        cache.invalidate(&present);
+        assert!(cache.get(&present).is_none());
+
+        // Overflow evicts the oldest fetches
+        cache.insert(103, (0..4).map(|_| (Pubkey::new_unique(), None)));
+        let entries = cache.entries.lock().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(!entries.contains_key(&missing));
+    }
+}
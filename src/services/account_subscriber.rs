@@ -0,0 +1,345 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::{AppConfig, GeyserConfig, WebSocketConfig},
+    services::geyser::GeyserConnection,
+    utils::affinity::{spawn_pinned, CorePlan},
+};
+
+/// Updates buffered per subscriber before new ones are dropped
+const SUBSCRIBER_BUFFER: usize = 64;
+
+/// Largest message accepted before the connection is dropped
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+pub(crate) const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
+/// New contents of a subscribed account
+#[derive(Debug, Clone)]
+pub struct AccountUpdate {
+    pub pubkey: Pubkey,
+    pub slot: u64,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+pub(crate) enum Command {
+    Subscribe(Pubkey, mpsc::Sender<AccountUpdate>),
+}
+
+/// `accountSubscribe` over one websocket shared by every caller.
+///
+/// The connection runs on its own thread, pinned to the ingestion core when one is configured, and
+/// re-subscribes everything after a reconnect. Subscriptions end when their receiver is dropped.
+#[derive(Clone)]
+pub struct AccountSubscriber {
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl AccountSubscriber {
+    /// Start the ingestion thread when websocket or geyser subscriptions are enabled; geyser wins
+    /// when both are
+    pub fn spawn(config: &AppConfig) -> Result<Option<Self>> {
+        if !config.geyser.enabled && !config.websocket.enabled {
+            return Ok(None);
+        }
+        let core = CorePlan::resolve(&config.affinity)?.ingestion_core;
+        if config.geyser.enabled {
+            return Self::start_geyser(&config.geyser, core).map(Some);
+        }
+        Self::start(&config.solana.ws_url, &config.websocket, core).map(Some)
+    }
+
+    pub fn start(url: &str, config: &WebSocketConfig, core: Option<usize>) -> Result<Self> {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let connection = Connection::new(url, config);
+        spawn_pinned("ws-ingestion", core, move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("build websocket ingestion runtime");
+            runtime.block_on(connection.run(receiver));
+        })
+        .context("Cannot start websocket ingestion thread")?;
+        info!("Subscribing to account updates over {}", url);
+        Ok(Self { commands })
+    }
+
+    /// Follow accounts over a Yellowstone gRPC stream instead of the websocket
+    pub fn start_geyser(config: &GeyserConfig, core: Option<usize>) -> Result<Self> {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let connection = GeyserConnection::new(config)?;
+        spawn_pinned("geyser-ingestion", core, move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("build geyser ingestion runtime");
+            runtime.block_on(connection.run(receiver));
+        })
+        .context("Cannot start geyser ingestion thread")?;
+        info!("Subscribing to account updates over geyser {}", config.endpoint);
+        Ok(Self { commands })
+    }
+
+    /// Stream every change to `pubkey` until the receiver is dropped
+    pub fn subscribe(&self, pubkey: Pubkey) -> Result<mpsc::Receiver<AccountUpdate>> {
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_BUFFER);
+        self.subscribe_into(pubkey, sender)?;
+        Ok(receiver)
+    }
+
+    /// Send changes to `pubkey` into an existing channel, so one receiver can follow many accounts
+    pub fn subscribe_into(&self, pubkey: Pubkey, sender: mpsc::Sender<AccountUpdate>) -> Result<()> {
+        self.commands
+            .send(Command::Subscribe(pubkey, sender))
+            .map_err(|_| anyhow::anyhow!("Account ingestion thread has stopped"))
+    }
+}
+
+struct Connection {
+    url: String,
+    commitment: String,
+    ping_interval: Duration,
+    reconnect_backoff: Duration,
+    subscribers: HashMap<Pubkey, Vec<mpsc::Sender<AccountUpdate>>>,
+    /// Subscribe requests awaiting their subscription id, by request id
+    pending: HashMap<u64, Pubkey>,
+    subscriptions: HashMap<u64, Pubkey>,
+    next_id: u64,
+}
+
+impl Connection {
+    fn new(url: &str, config: &WebSocketConfig) -> Self {
+        Self {
+            url: url.to_string(),
+            commitment: config.commitment.clone(),
+            ping_interval: Duration::from_secs(config.ping_interval_seconds.max(1)),
+            reconnect_backoff: Duration::from_millis(config.reconnect_backoff_ms.max(1)),
+            subscribers: HashMap::new(),
+            pending: HashMap::new(),
+            subscriptions: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Keep a session open until every `AccountSubscriber` handle is gone
+    async fn run(mut self, mut commands: mpsc::UnboundedReceiver<Command>) {
+        let mut backoff = self.reconnect_backoff;
+        loop {
+            let started = tokio::time::Instant::now();
+            match self.session(&mut commands).await {
+                Ok(()) => return,
+                Err(e) => warn!("Account websocket {} dropped: {}", self.url, e),
+            }
+            // A session that stayed up for a while resets the backoff
+            if started.elapsed() > MAX_RECONNECT_BACKOFF {
+                backoff = self.reconnect_backoff;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
+        }
+    }
+
+    async fn session(&mut self, commands: &mut mpsc::UnboundedReceiver<Command>) -> Result<()> {
+        let limits = tokio_tungstenite::tungstenite::protocol::WebSocketConfig::default()
+            .max_message_size(Some(MAX_MESSAGE_BYTES))
+            .max_frame_size(Some(MAX_MESSAGE_BYTES));
+        let (mut socket, _) = tokio_tungstenite::connect_async_with_config(self.url.as_str(), Some(limits), true)
+            .await
+            .with_context(|| format!("Cannot connect to {}", self.url))?;
+        self.pending.clear();
+        self.subscriptions.clear();
+        let accounts: Vec<Pubkey> = self.subscribers.keys().copied().collect();
+        for pubkey in accounts {
+            self.send_subscribe(&mut socket, pubkey).await?;
+        }
+
+        let mut ping = tokio::time::interval(self.ping_interval);
+        ping.tick().await;
+        loop {
+            tokio::select! {
+                // Pings are answered by the socket itself on its next write or flush
+                message = socket.next() => match message.context("closed by server")?? {
+                    Message::Text(text) => {
+                        for request in self.handle_message(&text) {
+                            socket.send(Message::text(request.to_string())).await?;
+                        }
+                    }
+                    Message::Ping(_) => socket.flush().await?,
+                    Message::Close(_) => anyhow::bail!("closed by server"),
+                    Message::Binary(_) | Message::Pong(_) | Message::Frame(_) => {}
+                },
+                command = commands.recv() => match command {
+                    Some(Command::Subscribe(pubkey, sender)) => {
+                        let subscribers = self.subscribers.entry(pubkey).or_default();
+                        subscribers.push(sender);
+                        if subscribers.len() == 1 {
+                            self.send_subscribe(&mut socket, pubkey).await?;
+                        }
+                    }
+                    None => {
+                        let _ = socket.close(None).await;
+                        return Ok(());
+                    }
+                },
+                _ = ping.tick() => socket.send(Message::Ping(Default::default())).await?,
+            }
+        }
+    }
+
+    async fn send_subscribe(&mut self, socket: &mut WebSocket, pubkey: Pubkey) -> Result<()> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.insert(id, pubkey);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "accountSubscribe",
+            "params": [pubkey.to_string(), {"encoding": "base64", "commitment": self.commitment}],
+        });
+        socket.send(Message::text(request.to_string())).await?;
+        Ok(())
+    }
+
+    /// Apply a server message, returning requests to send back
+    fn handle_message(&mut self, text: &str) -> Vec<Value> {
+        let Ok(message) = serde_json::from_str::<Value>(text) else {
+            debug!("Ignoring malformed websocket message");
+            return Vec::new();
+        };
+
+        if let Some(id) = message.get("id").and_then(Value::as_u64) {
+            let Some(pubkey) = self.pending.remove(&id) else {
+                return Vec::new();
+            };
+            match message.get("result").and_then(Value::as_u64) {
+                Some(subscription) => {
+                    self.subscriptions.insert(subscription, pubkey);
+                }
+                None => warn!("accountSubscribe for {} failed: {}", pubkey, message["error"]),
+            }
+            return Vec::new();
+        }
+
+        if message.get("method").and_then(Value::as_str) != Some("accountNotification") {
+            return Vec::new();
+        }
+        let params = &message["params"];
+        let Some(subscription) = params["subscription"].as_u64() else {
+            return Vec::new();
+        };
+        let Some(&pubkey) = self.subscriptions.get(&subscription) else {
+            return Vec::new();
+        };
+        let slot = params["result"]["context"]["slot"].as_u64().unwrap_or_default();
+        let value = &params["result"]["value"];
+        // Closed accounts arrive with a null value and are passed on as empty with no lamports
+        let data = match value["data"][0].as_str() {
+            Some(data) => match base64::engine::general_purpose::STANDARD.decode(data) {
+                Ok(data) => data,
+                Err(_) => return Vec::new(),
+            },
+            None if value.is_null() => Vec::new(),
+            None => return Vec::new(),
+        };
+        let lamports = value["lamports"].as_u64().unwrap_or_default();
+
+        let update = AccountUpdate { pubkey, slot, lamports, data };
+        let subscribers = self.subscribers.entry(pubkey).or_default();
+        subscribers.retain(|sender| match sender.try_send(update.clone()) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                debug!("Subscriber for {} is behind; dropped an update", pubkey);
+                true
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        });
+        if !subscribers.is_empty() {
+            return Vec::new();
+        }
+
+        self.subscribers.remove(&pubkey);
+        self.subscriptions.remove(&subscription);
+        let id = self.next_id;
+        self.next_id += 1;
+        vec![json!({"jsonrpc": "2.0", "id": id, "method": "accountUnsubscribe", "params": [subscription]})]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::protocol::frame::{
+        coding::{Data, OpCode},
+        Frame,
+    };
+
+    async fn read_json(socket: &mut WebSocketStream<TcpStream>) -> Value {
+        loop {
+            if let Message::Text(text) = socket.next().await.unwrap().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscription_receives_account_notifications() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let account = Pubkey::new_unique();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            let subscribe = read_json(&mut socket).await;
+            assert_eq!(subscribe["method"], "accountSubscribe");
+            assert_eq!(subscribe["params"][0], account.to_string());
+            let reply = json!({"jsonrpc": "2.0", "result": 42, "id": subscribe["id"]});
+            socket.send(Message::text(reply.to_string())).await.unwrap();
+
+            let notification = json!({
+                "jsonrpc": "2.0",
+                "method": "accountNotification",
+                "params": {
+                    "subscription": 42,
+                    "result": {"context": {"slot": 1234}, "value": {"lamports": 5000, "data": [base64::engine::general_purpose::STANDARD.encode([1u8, 2, 3]), "base64"]}},
+                },
+            });
+            // Split across two frames to exercise fragmentation
+            let text = notification.to_string();
+            let (head, tail) = text.as_bytes().split_at(10);
+            let first = Frame::message(head.to_vec(), OpCode::Data(Data::Text), false);
+            socket.send(Message::Frame(first)).await.unwrap();
+            let rest = Frame::message(tail.to_vec(), OpCode::Data(Data::Continue), true);
+            socket.send(Message::Frame(rest)).await.unwrap();
+
+            // Hold the connection open until the client goes away
+            while let Some(Ok(_)) = socket.next().await {}
+        });
+
+        let subscriber = AccountSubscriber::start(&url, &WebSocketConfig::default(), None).unwrap();
+        let mut updates = subscriber.subscribe(account).unwrap();
+        let update = tokio::time::timeout(Duration::from_secs(5), updates.recv()).await.unwrap().unwrap();
+        assert_eq!(update.pubkey, account);
+        assert_eq!(update.slot, 1234);
+        assert_eq!(update.lamports, 5000);
+        assert_eq!(update.data, vec![1, 2, 3]);
+
+        drop(subscriber);
+        tokio::time::timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+    }
+}
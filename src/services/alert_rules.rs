@@ -0,0 +1,318 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::config::{AlertRuleConfig, AlertsConfig};
+use crate::services::metrics::MetricsRegistry;
+
+/// Comparison operator used in an alert condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    LessThan,
+    LessOrEqual,
+    GreaterThan,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "<" => Some(Comparison::LessThan),
+            "<=" => Some(Comparison::LessOrEqual),
+            ">" => Some(Comparison::GreaterThan),
+            ">=" => Some(Comparison::GreaterOrEqual),
+            "==" => Some(Comparison::Equal),
+            "!=" => Some(Comparison::NotEqual),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::LessThan => value < threshold,
+            Comparison::LessOrEqual => value <= threshold,
+            Comparison::GreaterThan => value > threshold,
+            Comparison::GreaterOrEqual => value >= threshold,
+            Comparison::Equal => (value - threshold).abs() < f64::EPSILON,
+            Comparison::NotEqual => (value - threshold).abs() >= f64::EPSILON,
+        }
+    }
+}
+
+impl std::fmt::Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Comparison::LessThan => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::GreaterThan => ">",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Equal => "==",
+            Comparison::NotEqual => "!=",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+/// Condition part of a rule, e.g. `success_rate_1h < 0.5`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertCondition {
+    pub metric: String,
+    pub comparison: Comparison,
+    pub threshold: f64,
+}
+
+/// Action taken when a rule fires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertAction {
+    /// Send a notification to the named channel (e.g. "telegram")
+    Notify(String),
+    /// Pause opportunity execution
+    Pause,
+    /// Resume opportunity execution
+    Resume,
+    /// Only write the alert to the log
+    Log,
+}
+
+impl AlertAction {
+    fn parse(tokens: &[&str]) -> Option<Self> {
+        match tokens {
+            ["notify", channel] => Some(AlertAction::Notify(channel.to_string())),
+            ["pause"] => Some(AlertAction::Pause),
+            ["resume"] => Some(AlertAction::Resume),
+            ["log"] => Some(AlertAction::Log),
+            _ => None,
+        }
+    }
+}
+
+/// A parsed alert rule
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub name: String,
+    pub condition: AlertCondition,
+    pub action: AlertAction,
+    pub cooldown: Duration,
+}
+
+impl AlertRule {
+    /// Parse a rule expression of the form `if <metric> <op> <value> then <action> [target]`
+    pub fn parse(name: &str, expression: &str, cooldown: Duration) -> Result<Self> {
+        let tokens: Vec<&str> = expression.split_whitespace().collect();
+
+        let then_index = tokens
+            .iter()
+            .position(|t| *t == "then")
+            .ok_or_else(|| anyhow::anyhow!("Alert rule '{}' is missing 'then'", name))?;
+
+        let condition_tokens = match tokens.first() {
+            Some(&"if") => &tokens[1..then_index],
+            _ => &tokens[..then_index],
+        };
+
+        let condition = match condition_tokens {
+            [metric, op, value] => {
+                let comparison = Comparison::parse(op).ok_or_else(|| {
+                    anyhow::anyhow!("Alert rule '{}' has unknown operator '{}'", name, op)
+                })?;
+                let threshold = value.parse::<f64>().map_err(|_| {
+                    anyhow::anyhow!("Alert rule '{}' has invalid threshold '{}'", name, value)
+                })?;
+                AlertCondition {
+                    metric: metric.to_string(),
+                    comparison,
+                    threshold,
+                }
+            }
+            _ => anyhow::bail!("Alert rule '{}' condition must be '<metric> <op> <value>'", name),
+        };
+
+        let action = AlertAction::parse(&tokens[then_index + 1..])
+            .ok_or_else(|| anyhow::anyhow!("Alert rule '{}' has an invalid action", name))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            condition,
+            action,
+            cooldown,
+        })
+    }
+
+    /// Build a rule from its config entry
+    pub fn from_config(config: &AlertRuleConfig) -> Result<Self> {
+        Self::parse(&config.name, &config.rule, Duration::from_secs(config.cooldown_seconds))
+    }
+
+    /// Check the rule against a metrics snapshot; missing metrics never fire
+    pub fn is_triggered(&self, metrics: &HashMap<String, f64>) -> Option<f64> {
+        let value = *metrics.get(&self.condition.metric)?;
+        if self.condition.comparison.matches(value, self.condition.threshold) {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+/// An alert produced by a fired rule
+#[derive(Debug, Clone)]
+pub struct FiredAlert {
+    pub rule_name: String,
+    pub action: AlertAction,
+    pub message: String,
+    pub value: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Evaluates a set of rules, applying per-rule cooldowns
+pub struct AlertRuleEngine {
+    rules: Vec<AlertRule>,
+    last_fired: HashMap<String, DateTime<Utc>>,
+}
+
+impl AlertRuleEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules,
+            last_fired: HashMap::new(),
+        }
+    }
+
+    /// Parse all rules in the alerts config
+    pub fn from_config(config: &AlertsConfig) -> Result<Self> {
+        let rules = config
+            .rules
+            .iter()
+            .map(AlertRule::from_config)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(rules))
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    /// Evaluate all rules against a metrics snapshot
+    pub fn evaluate(&mut self, metrics: &HashMap<String, f64>, now: DateTime<Utc>) -> Vec<FiredAlert> {
+        let mut fired = Vec::new();
+
+        for rule in &self.rules {
+            let Some(value) = rule.is_triggered(metrics) else {
+                continue;
+            };
+
+            if let Some(last) = self.last_fired.get(&rule.name) {
+                let cooldown = chrono::Duration::from_std(rule.cooldown).unwrap_or_default();
+                if now < *last + cooldown {
+                    continue;
+                }
+            }
+
+            self.last_fired.insert(rule.name.clone(), now);
+            fired.push(FiredAlert {
+                rule_name: rule.name.clone(),
+                action: rule.action.clone(),
+                message: format!(
+                    "{}: {} = {} ({} {})",
+                    rule.name,
+                    rule.condition.metric,
+                    value,
+                    rule.condition.comparison,
+                    rule.condition.threshold
+                ),
+                value,
+                timestamp: now,
+            });
+        }
+
+        fired
+    }
+}
+
+/// Background worker evaluating alert rules against the metrics registry
+pub struct RulesWorker {
+    engine: AlertRuleEngine,
+    metrics: MetricsRegistry,
+    alert_sender: mpsc::Sender<FiredAlert>,
+    interval: Duration,
+}
+
+impl RulesWorker {
+    pub fn new(
+        engine: AlertRuleEngine,
+        metrics: MetricsRegistry,
+        alert_sender: mpsc::Sender<FiredAlert>,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            engine,
+            metrics,
+            alert_sender,
+            interval,
+        }
+    }
+
+    /// Start the worker loop
+    pub async fn start(mut self) -> Result<()> {
+        info!("Starting alert rules worker with {} rules", self.engine.rules().len());
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            let snapshot = self.metrics.snapshot().await;
+            for alert in self.engine.evaluate(&snapshot, Utc::now()) {
+                if let Err(e) = self.alert_sender.send(alert).await {
+                    warn!("Failed to dispatch alert: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rule() {
+        let rule = AlertRule::parse(
+            "low_success",
+            "if success_rate_1h < 0.5 then notify telegram",
+            Duration::from_secs(60),
+        )
+        .unwrap();
+
+        assert_eq!(rule.condition.metric, "success_rate_1h");
+        assert_eq!(rule.condition.comparison, Comparison::LessThan);
+        assert_eq!(rule.condition.threshold, 0.5);
+        assert_eq!(rule.action, AlertAction::Notify("telegram".to_string()));
+
+        let rule = AlertRule::parse("low_sol", "wallet_sol < 0.2 then pause", Duration::ZERO).unwrap();
+        assert_eq!(rule.action, AlertAction::Pause);
+
+        assert!(AlertRule::parse("bad", "if x ~ 1 then pause", Duration::ZERO).is_err());
+        assert!(AlertRule::parse("bad", "if x < 1", Duration::ZERO).is_err());
+        assert!(AlertRule::parse("bad", "if x < 1 then explode", Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_rule_engine_cooldown() {
+        let rule = AlertRule::parse("low_sol", "if wallet_sol < 0.2 then pause", Duration::from_secs(60)).unwrap();
+        let mut engine = AlertRuleEngine::new(vec![rule]);
+
+        let mut metrics = HashMap::new();
+        let now = Utc::now();
+        assert!(engine.evaluate(&metrics, now).is_empty());
+
+        metrics.insert("wallet_sol".to_string(), 0.1);
+        assert_eq!(engine.evaluate(&metrics, now).len(), 1);
+        assert!(engine.evaluate(&metrics, now + chrono::Duration::seconds(30)).is_empty());
+        assert_eq!(engine.evaluate(&metrics, now + chrono::Duration::seconds(61)).len(), 1);
+    }
+}
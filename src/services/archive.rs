@@ -0,0 +1,330 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::config::ArchiveConfig;
+use crate::services::database::DatabaseService;
+use crate::utils::crypto::CryptoUtils;
+
+/// One file inside an archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub bytes: u64,
+    /// Hex SHA-256 of the original contents
+    pub sha256: String,
+}
+
+/// Written next to each archive as `<archive>.manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub created_at: DateTime<Utc>,
+    pub entries: Vec<ArchiveEntry>,
+    /// Hex SHA-256 of the compressed archive
+    pub sha256: String,
+}
+
+/// What one compaction pass did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    pub archives: usize,
+    pub files: usize,
+    pub snapshots: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+fn checksum(data: &[u8]) -> String {
+    hex::encode(CryptoUtils::sha256(data))
+}
+
+/// Write `entries` as one zstd stream of `name length, name, data length, data` records
+pub fn write_archive(path: &Path, entries: &[(String, Vec<u8>)], level: i32) -> Result<()> {
+    let staging = path.with_extension("zst.tmp");
+    let file = std::fs::File::create(&staging).with_context(|| format!("Cannot create {}", staging.display()))?;
+    let mut encoder = zstd::Encoder::new(file, level)?;
+    for (name, data) in entries {
+        encoder.write_all(&(name.len() as u32).to_le_bytes())?;
+        encoder.write_all(name.as_bytes())?;
+        encoder.write_all(&(data.len() as u64).to_le_bytes())?;
+        encoder.write_all(data)?;
+    }
+    encoder.finish()?.sync_all()?;
+    std::fs::rename(&staging, path)?;
+    Ok(())
+}
+
+/// Read every file back out of an archive
+pub fn read_archive(path: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut decoder = zstd::Decoder::new(std::fs::File::open(path)?)?;
+    let mut entries = Vec::new();
+    let mut len = [0u8; 4];
+    loop {
+        match decoder.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+        let mut name = vec![0u8; u32::from_le_bytes(len) as usize];
+        decoder.read_exact(&mut name)?;
+        let mut data_len = [0u8; 8];
+        decoder.read_exact(&mut data_len)?;
+        let mut data = vec![0u8; u64::from_le_bytes(data_len) as usize];
+        decoder.read_exact(&mut data)?;
+        entries.push((String::from_utf8(name)?, data));
+    }
+    Ok(entries)
+}
+
+/// Rolls files and pool snapshots past the retention into one archive per day.
+///
+/// Originals are only removed once the archive has been read back and every entry's checksum
+/// matches, so an interrupted pass leaves at worst an extra archive behind.
+pub struct Compactor {
+    config: ArchiveConfig,
+    /// Files in use by the running bot, never archived
+    protected: Vec<PathBuf>,
+    database: Option<Arc<DatabaseService>>,
+}
+
+impl Compactor {
+    pub fn new(config: ArchiveConfig, protected: Vec<PathBuf>, database: Option<Arc<DatabaseService>>) -> Self {
+        Self { config, protected, database }
+    }
+
+    /// Days before this one are past the retention
+    fn cutoff(&self, now: DateTime<Utc>) -> NaiveDate {
+        (now - Duration::days(self.config.retention_days as i64)).date_naive()
+    }
+
+    fn is_protected(&self, path: &Path) -> bool {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.protected
+            .iter()
+            .any(|protected| protected.canonicalize().unwrap_or_else(|_| protected.clone()) == canonical)
+    }
+
+    /// Files to archive, grouped by the day they were last modified
+    fn aged_files(&self, cutoff: NaiveDate) -> Result<BTreeMap<NaiveDate, Vec<PathBuf>>> {
+        let mut days: BTreeMap<NaiveDate, Vec<PathBuf>> = BTreeMap::new();
+        let dir = Path::new(&self.config.data_dir);
+        if !dir.is_dir() {
+            return Ok(days);
+        }
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let archivable = path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| self.config.extensions.iter().any(|e| e == ext));
+            if !archivable || self.is_protected(&path) {
+                continue;
+            }
+            let modified: DateTime<Utc> = std::fs::metadata(&path)?.modified()?.into();
+            if modified.date_naive() < cutoff {
+                days.entry(modified.date_naive()).or_default().push(path);
+            }
+        }
+        for files in days.values_mut() {
+            files.sort();
+        }
+        Ok(days)
+    }
+
+    /// `<stem>.zst` in the archive directory, numbered when a pass already wrote one that day
+    fn archive_path(&self, stem: &str) -> PathBuf {
+        let dir = Path::new(&self.config.archive_dir);
+        let mut path = dir.join(format!("{}.zst", stem));
+        let mut n = 2;
+        while path.exists() {
+            path = dir.join(format!("{}-{}.zst", stem, n));
+            n += 1;
+        }
+        path
+    }
+
+    /// Write, read back and verify an archive, then record its manifest. Returns its size.
+    fn archive(&self, stem: &str, entries: &[(String, Vec<u8>)]) -> Result<u64> {
+        std::fs::create_dir_all(&self.config.archive_dir)?;
+        let path = self.archive_path(stem);
+        let expected: Vec<ArchiveEntry> = entries
+            .iter()
+            .map(|(name, data)| ArchiveEntry { name: name.clone(), bytes: data.len() as u64, sha256: checksum(data) })
+            .collect();
+        write_archive(&path, entries, self.config.compression_level)?;
+
+        let written: Vec<ArchiveEntry> = read_archive(&path)?
+            .iter()
+            .map(|(name, data)| ArchiveEntry { name: name.clone(), bytes: data.len() as u64, sha256: checksum(data) })
+            .collect();
+        if written != expected {
+            std::fs::remove_file(&path)?;
+            anyhow::bail!("Archive {} did not verify; originals kept", path.display());
+        }
+
+        let compressed = std::fs::read(&path)?;
+        let manifest = ArchiveManifest { created_at: Utc::now(), entries: expected, sha256: checksum(&compressed) };
+        let mut manifest_path = path.clone().into_os_string();
+        manifest_path.push(".manifest.json");
+        std::fs::write(manifest_path, serde_json::to_vec_pretty(&manifest)?)?;
+        Ok(compressed.len() as u64)
+    }
+
+    fn compact_files(&self, cutoff: NaiveDate, report: &mut CompactionReport) -> Result<()> {
+        for (day, files) in self.aged_files(cutoff)? {
+            let mut entries = Vec::with_capacity(files.len());
+            for path in &files {
+                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                entries.push((name, std::fs::read(path)?));
+            }
+            report.bytes_out += self.archive(&format!("files-{}", day), &entries)?;
+            report.bytes_in += entries.iter().map(|(_, data)| data.len() as u64).sum::<u64>();
+            for path in &files {
+                std::fs::remove_file(path)?;
+            }
+            report.archives += 1;
+            report.files += files.len();
+        }
+        Ok(())
+    }
+
+    async fn compact_snapshots(&self, database: &DatabaseService, cutoff: NaiveDate, report: &mut CompactionReport) -> Result<()> {
+        let end = cutoff.and_hms_opt(0, 0, 0).map(|t| t.and_utc()).unwrap_or_else(Utc::now);
+        while let Some(oldest) = database.oldest_pool_snapshot().await? {
+            if oldest >= end {
+                break;
+            }
+            let day = oldest.date_naive();
+            let from = day.and_hms_opt(0, 0, 0).map(|t| t.and_utc()).unwrap_or(oldest);
+            let to = (from + Duration::days(1)).min(end);
+            let snapshots = database.get_pool_snapshots(from, to).await?;
+
+            let mut jsonl = Vec::new();
+            for snapshot in &snapshots {
+                serde_json::to_writer(&mut jsonl, snapshot)?;
+                jsonl.push(b'\n');
+            }
+            let name = format!("pool_snapshots-{}.jsonl", day);
+            report.bytes_out += self.archive(&format!("pool_snapshots-{}", day), &[(name, jsonl.clone())])?;
+            report.bytes_in += jsonl.len() as u64;
+            report.snapshots += database.delete_pool_snapshots(from, to).await?;
+            report.archives += 1;
+        }
+        Ok(())
+    }
+
+    /// Archive everything last touched before the retention
+    pub async fn compact(&self, now: DateTime<Utc>) -> Result<CompactionReport> {
+        let cutoff = self.cutoff(now);
+        let mut report = CompactionReport::default();
+        self.compact_files(cutoff, &mut report)?;
+        if let Some(database) = self.database.as_ref().filter(|_| self.config.database_snapshots) {
+            self.compact_snapshots(database, cutoff, &mut report).await?;
+        }
+        Ok(report)
+    }
+
+    /// Start the compaction loop
+    pub async fn start(self) -> Result<()> {
+        info!(
+            "Archiving {} and pool snapshots after {} days to {}",
+            self.config.data_dir, self.config.retention_days, self.config.archive_dir
+        );
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(self.config.interval_seconds.max(60)));
+        loop {
+            interval.tick().await;
+            match self.compact(Utc::now()).await {
+                Ok(report) if report.archives > 0 => info!(
+                    "Archived {} files and {} pool snapshots into {} archives ({} -> {} bytes)",
+                    report.files, report.snapshots, report.archives, report.bytes_in, report.bytes_out
+                ),
+                Ok(_) => {}
+                Err(e) => warn!("Archive compaction failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DatabaseConfig;
+    use crate::dex::DexType;
+    use crate::models::{Pool, PoolSnapshot, Token};
+    use solana_sdk::pubkey::Pubkey;
+
+    #[tokio::test]
+    async fn test_compacts_aged_files_and_snapshots_by_day() {
+        let root = std::env::temp_dir().join(format!("archive-{}", uuid::Uuid::new_v4()));
+        let data_dir = root.join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let now = Utc::now();
+        let write = |name: &str, contents: &str, age_days: i64| {
+            let path = data_dir.join(name);
+            std::fs::write(&path, contents).unwrap();
+            let modified = std::time::SystemTime::from(now - Duration::days(age_days));
+            std::fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+            path
+        };
+        write("old-a.jsonl", "a", 20);
+        write("old-b.csv", "b", 20);
+        write("older.jsonl", "c", 30);
+        let live = write("events.journal", "live", 30);
+        write("recent.jsonl", "d", 1);
+        write("notes.txt", "e", 30);
+
+        let db = Arc::new(
+            DatabaseService::new(&DatabaseConfig {
+                url: "sqlite::memory:".to_string(),
+                max_connections: 1,
+                timeout_seconds: 5,
+                buffer_path: String::new(),
+                buffer_capacity_mb: 1,
+                recovery_interval_seconds: 1,
+                capture_pool_snapshots: false,
+            })
+            .await
+            .unwrap(),
+        );
+        let token = |symbol: &str| Token::new(Pubkey::new_unique(), symbol.to_string(), symbol.to_string(), 6);
+        let pool = Pool::new("p".to_string(), DexType::Raydium, token("SOL"), token("USDC"), Pubkey::new_unique(), Pubkey::default(), Pubkey::default());
+        for age_days in [40, 40, 2] {
+            db.save_pool_snapshot(&PoolSnapshot::from_pool(&pool, now - Duration::days(age_days))).await.unwrap();
+        }
+
+        let config = ArchiveConfig {
+            enabled: true,
+            data_dir: data_dir.to_string_lossy().to_string(),
+            archive_dir: root.join("archive").to_string_lossy().to_string(),
+            retention_days: 14,
+            ..ArchiveConfig::default()
+        };
+        let compactor = Compactor::new(config, vec![live.clone()], Some(db.clone()));
+        let report = compactor.compact(now).await.unwrap();
+        assert_eq!((report.archives, report.files, report.snapshots), (3, 3, 2));
+
+        // Aged originals are gone; recent, protected and unlisted files stay
+        let mut left: Vec<String> = std::fs::read_dir(&data_dir).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().to_string()).collect();
+        left.sort();
+        assert_eq!(left, vec!["events.journal", "notes.txt", "recent.jsonl"]);
+        assert_eq!(db.get_pool_snapshots(now - Duration::days(100), now).await.unwrap().len(), 1);
+
+        let day = (now - Duration::days(20)).date_naive();
+        let archive = root.join("archive").join(format!("files-{}.zst", day));
+        let entries = read_archive(&archive).unwrap();
+        assert_eq!(entries, vec![("old-a.jsonl".to_string(), b"a".to_vec()), ("old-b.csv".to_string(), b"b".to_vec())]);
+        let manifest: ArchiveManifest =
+            serde_json::from_slice(&std::fs::read(archive.with_extension("zst.manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest.entries[0].sha256, checksum(b"a"));
+
+        // Nothing is left to do on the next pass
+        assert_eq!(compactor.compact(now).await.unwrap(), CompactionReport::default());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
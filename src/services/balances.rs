@@ -0,0 +1,339 @@
+use anyhow::Result;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::{BalanceConfig, RoundingMode},
+    dex::pool_accounts::token_account_balance,
+    models::Token,
+    services::{
+        account_subscriber::{AccountSubscriber, AccountUpdate},
+        metrics::MetricsRegistry,
+        solana::SolanaService,
+    },
+    utils::{
+        panic_guard::lock_or_recover,
+        precision::{from_base_units, round_to_decimals, to_base_units},
+    },
+};
+
+/// Updates buffered across the wallet and its token accounts
+const UPDATE_BUFFER: usize = 64;
+
+/// Why a trade was turned down before its legs were built
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BalanceError {
+    #[error("Wallet balances have not been read yet")]
+    Unknown,
+
+    #[error("Fee payer holds {lamports} lamports, below the {reserve} lamport fee reserve")]
+    FeeReserve { lamports: u64, reserve: u64 },
+
+    #[error("Wallet holds {available} {symbol}, too little for a trade of {requested}")]
+    Insufficient { symbol: String, requested: Decimal, available: Decimal },
+}
+
+/// Last known balances, in raw units
+#[derive(Debug, Default)]
+struct Balances {
+    lamports: Option<u64>,
+    /// Of a separate fee payer
+    fee_payer_lamports: Option<u64>,
+    /// By token account address
+    tokens: HashMap<Pubkey, u64>,
+}
+
+/// SOL and SPL balances of the trading wallet, read over RPC and kept current from account
+/// subscriptions, so a trade can be sized to what the wallet holds before anything is built.
+///
+/// A trade's input is set aside as soon as it is sized, so concurrent executions cannot spend the
+/// same tokens; the next read of the account restores the true balance.
+pub struct BalanceManager {
+    solana: Arc<SolanaService>,
+    wallet: Pubkey,
+    /// Pays transaction fees and tips, so holds the fee reserve; the wallet itself by default
+    fee_payer: Pubkey,
+    config: BalanceConfig,
+    /// Count SOL above the fee reserve towards wSOL, since the legs wrap what they spend
+    wrapped_sol: bool,
+    balances: Mutex<Balances>,
+    /// Receives `wallet_sol`, and `fee_payer_sol` for a separate fee payer, whenever balances change
+    metrics: Option<MetricsRegistry>,
+}
+
+impl BalanceManager {
+    pub fn new(solana: Arc<SolanaService>, wallet: Pubkey, config: &BalanceConfig) -> Self {
+        Self {
+            solana,
+            wallet,
+            fee_payer: wallet,
+            config: config.clone(),
+            wrapped_sol: false,
+            balances: Mutex::new(Balances::default()),
+            metrics: None,
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsRegistry) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn with_fee_payer(mut self, fee_payer: Pubkey) -> Self {
+        self.fee_payer = fee_payer;
+        self
+    }
+
+    pub fn with_wrapped_sol(mut self, wrapped_sol: bool) -> Self {
+        self.wrapped_sol = wrapped_sol;
+        self
+    }
+
+    pub fn wallet(&self) -> Pubkey {
+        self.wallet
+    }
+
+    /// Whether a separate wallet pays fees
+    fn separate_fee_payer(&self) -> bool {
+        self.fee_payer != self.wallet
+    }
+
+    /// Read the wallet's SOL, the fee payer's, and every token account the wallet holds
+    pub async fn refresh(&self) -> Result<()> {
+        let lamports = self.solana.get_balance(&self.wallet).await?;
+        let fee_payer_lamports =
+            if self.separate_fee_payer() { Some(self.solana.get_balance(&self.fee_payer).await?) } else { None };
+        let tokens = self
+            .solana
+            .get_token_holdings(&self.wallet)
+            .await?
+            .into_iter()
+            .map(|holding| (holding.address, holding.amount))
+            .collect();
+        *lock_or_recover(&self.balances, "balances") = Balances { lamports: Some(lamports), fee_payer_lamports, tokens };
+        self.publish().await;
+        Ok(())
+    }
+
+    /// Publish the SOL balances for alert rules such as `wallet_sol < 0.2`
+    async fn publish(&self) {
+        let Some(metrics) = &self.metrics else {
+            return;
+        };
+        let (lamports, fee_payer_lamports) = {
+            let balances = lock_or_recover(&self.balances, "balances");
+            (balances.lamports, balances.fee_payer_lamports)
+        };
+        let sol = |lamports: u64| lamports as f64 / 1_000_000_000.0;
+        if let Some(lamports) = lamports {
+            metrics.set("wallet_sol", sol(lamports)).await;
+        }
+        if let Some(lamports) = fee_payer_lamports {
+            metrics.set("fee_payer_sol", sol(lamports)).await;
+        }
+    }
+
+    /// Apply a subscription update to the wallet or one of its token accounts
+    fn apply(&self, update: &AccountUpdate) {
+        let mut balances = lock_or_recover(&self.balances, "balances");
+        if update.pubkey == self.wallet {
+            balances.lamports = Some(update.lamports);
+        } else if update.pubkey == self.fee_payer {
+            balances.fee_payer_lamports = Some(update.lamports);
+        } else {
+            // A closed or emptied token account holds nothing
+            let amount = token_account_balance(&update.data).map(|(_, amount)| amount).unwrap_or(0);
+            balances.tokens.insert(update.pubkey, amount);
+        }
+    }
+
+    pub fn lamports(&self) -> Option<u64> {
+        lock_or_recover(&self.balances, "balances").lamports
+    }
+
+    /// Raw balance of the wallet's associated account for `token`; zero when it has none
+    pub fn token_balance(&self, token: &Token) -> Option<u64> {
+        let balances = lock_or_recover(&self.balances, "balances");
+        balances.lamports?;
+        Some(balances.tokens.get(&token.associated_token_address(&self.wallet)).copied().unwrap_or(0))
+    }
+
+    /// Size a trade spending `amount` of `input` to what the wallet holds, and set that much aside.
+    ///
+    /// The full amount when the wallet holds it; otherwise the whole balance when downsizing is
+    /// allowed and it is at least `min_trade_fraction` of the amount. A fee payer holding less SOL
+    /// than the fee reserve rejects every trade.
+    pub fn claim(&self, input: &Token, amount: Decimal) -> Result<Decimal, BalanceError> {
+        let mut balances = lock_or_recover(&self.balances, "balances");
+        let lamports = balances.lamports.ok_or(BalanceError::Unknown)?;
+        let payer_lamports =
+            if self.separate_fee_payer() { balances.fee_payer_lamports.ok_or(BalanceError::Unknown)? } else { lamports };
+        if payer_lamports < self.config.fee_reserve_lamports {
+            return Err(BalanceError::FeeReserve { lamports: payer_lamports, reserve: self.config.fee_reserve_lamports });
+        }
+        // The reserve is only kept back from the wallet's own SOL when it pays the fees
+        let reserve = if self.separate_fee_payer() { 0 } else { self.config.fee_reserve_lamports };
+
+        let account = input.associated_token_address(&self.wallet);
+        let held = balances.tokens.get(&account).copied().unwrap_or(0);
+        let wrappable = if self.wrapped_sol && input.mint == spl_token_interface::native_mint::id() {
+            lamports.saturating_sub(reserve)
+        } else {
+            0
+        };
+        let raw = held.saturating_add(wrappable);
+        let available = from_base_units(raw, input.decimals);
+        let min_fraction = Decimal::from_f64(self.config.min_trade_fraction).unwrap_or(Decimal::ONE);
+        let sized = if available >= amount {
+            amount
+        } else if self.config.allow_downsize && available > Decimal::ZERO && available >= amount * min_fraction {
+            available
+        } else {
+            return Err(BalanceError::Insufficient { symbol: input.symbol.clone(), requested: amount, available });
+        };
+
+        let sized = round_to_decimals(sized, input.decimals, RoundingMode::Floor);
+        let spent = to_base_units(sized, input.decimals, RoundingMode::Floor).unwrap_or(raw);
+        balances.tokens.insert(account, held.saturating_sub(spent));
+        // Whatever the wSOL account did not cover is wrapped from SOL
+        let wrapped = spent.saturating_sub(held).min(wrappable);
+        balances.lamports = Some(lamports - wrapped);
+        Ok(sized)
+    }
+
+    /// Put back a claim whose trade sent nothing. A wSOL claim goes back to SOL when wrapping, which
+    /// makes the same amount available again whichever account it was taken from.
+    pub fn release(&self, input: &Token, amount: Decimal) {
+        let raw = to_base_units(amount, input.decimals, RoundingMode::Floor).unwrap_or(0);
+        let mut balances = lock_or_recover(&self.balances, "balances");
+        if self.wrapped_sol && input.mint == spl_token_interface::native_mint::id() {
+            balances.lamports = balances.lamports.map(|lamports| lamports.saturating_add(raw));
+        } else {
+            let held = balances.tokens.entry(input.associated_token_address(&self.wallet)).or_insert(0);
+            *held = held.saturating_add(raw);
+        }
+    }
+
+    /// Keep balances current: subscribe to the wallet and its token accounts when a subscriber
+    /// is available, and re-read everything over RPC every `refresh_interval_seconds`
+    pub async fn start(self: Arc<Self>, subscriber: Option<AccountSubscriber>) -> Result<()> {
+        self.refresh().await?;
+        info!("Tracking balances of wallet {}", self.wallet);
+
+        let (sender, mut updates) = mpsc::channel(UPDATE_BUFFER);
+        if let Some(subscriber) = &subscriber {
+            let accounts: Vec<Pubkey> = lock_or_recover(&self.balances, "balances").tokens.keys().copied().collect();
+            let wallets = if self.separate_fee_payer() { vec![self.wallet, self.fee_payer] } else { vec![self.wallet] };
+            for pubkey in wallets.into_iter().chain(accounts) {
+                subscriber.subscribe_into(pubkey, sender.clone())?;
+            }
+        }
+        // Without subscriptions the channel stays open and silent, leaving the timer in charge
+        let _sender = sender;
+
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(self.config.refresh_interval_seconds.max(1)));
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                Some(update) = updates.recv() => {
+                    self.apply(&update);
+                    self.publish().await;
+                }
+                _ = interval.tick() => {
+                    if let Err(e) = self.refresh().await {
+                        warn!("Could not refresh wallet balances: {}", e);
+                    } else {
+                        debug!("Refreshed balances of wallet {}", self.wallet);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claims_are_sized_to_the_balance_and_set_aside() {
+        let wallet = Pubkey::new_unique();
+        let usdc = Token::new(Pubkey::new_unique(), "USDC".to_string(), "USDC".to_string(), 6);
+        let account = usdc.associated_token_address(&wallet);
+        let solana = Arc::new(SolanaService::new("http://127.0.0.1:8899").unwrap());
+        let config = BalanceConfig { fee_reserve_lamports: 1_000, min_trade_fraction: 0.5, ..Default::default() };
+        let manager = BalanceManager::new(solana, wallet, &config);
+        assert_eq!(manager.claim(&usdc, Decimal::ONE), Err(BalanceError::Unknown));
+
+        // 150 USDC held
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&150_000_000u64.to_le_bytes());
+        manager.apply(&AccountUpdate { pubkey: wallet, slot: 1, lamports: 5_000, data: Vec::new() });
+        manager.apply(&AccountUpdate { pubkey: account, slot: 1, lamports: 2_039_280, data });
+
+        assert_eq!(manager.claim(&usdc, Decimal::from(100)), Ok(Decimal::from(100)));
+        // 50 left: a trade of 80 shrinks to it, and then nothing is left
+        assert_eq!(manager.claim(&usdc, Decimal::from(80)), Ok(Decimal::from(50)));
+        assert_eq!(manager.token_balance(&usdc), Some(0));
+        assert!(matches!(manager.claim(&usdc, Decimal::from(80)), Err(BalanceError::Insufficient { .. })));
+        // A trade that sent nothing hands its claim back
+        manager.release(&usdc, Decimal::from(50));
+        assert_eq!(manager.token_balance(&usdc), Some(50_000_000));
+        assert_eq!(manager.claim(&usdc, Decimal::from(50)), Ok(Decimal::from(50)));
+
+        // Less than the fee reserve in SOL stops every trade
+        manager.apply(&AccountUpdate { pubkey: wallet, slot: 2, lamports: 999, data: Vec::new() });
+        assert_eq!(manager.claim(&usdc, Decimal::ONE), Err(BalanceError::FeeReserve { lamports: 999, reserve: 1_000 }));
+
+        // With wrapping, SOL above the reserve counts towards a wSOL input
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "SOL".to_string(), 9);
+        let manager = BalanceManager::new(manager.solana.clone(), wallet, &config).with_wrapped_sol(true);
+        manager.apply(&AccountUpdate { pubkey: wallet, slot: 3, lamports: 5_000, data: Vec::new() });
+        assert_eq!(manager.claim(&sol, Decimal::new(3_000, 9)), Ok(Decimal::new(3_000, 9)));
+        assert_eq!(manager.lamports(), Some(2_000));
+        manager.release(&sol, Decimal::new(3_000, 9));
+        assert_eq!(manager.lamports(), Some(5_000));
+
+        // A separate fee payer holds the reserve, leaving all of the wallet's SOL to wrap
+        let fee_payer = Pubkey::new_unique();
+        let manager = BalanceManager::new(manager.solana.clone(), wallet, &config).with_wrapped_sol(true).with_fee_payer(fee_payer);
+        manager.apply(&AccountUpdate { pubkey: wallet, slot: 4, lamports: 5_000, data: Vec::new() });
+        assert_eq!(manager.claim(&sol, Decimal::new(5_000, 9)), Err(BalanceError::Unknown));
+        manager.apply(&AccountUpdate { pubkey: fee_payer, slot: 4, lamports: 999, data: Vec::new() });
+        assert_eq!(manager.claim(&sol, Decimal::new(5_000, 9)), Err(BalanceError::FeeReserve { lamports: 999, reserve: 1_000 }));
+        manager.apply(&AccountUpdate { pubkey: fee_payer, slot: 5, lamports: 1_000, data: Vec::new() });
+        assert_eq!(manager.claim(&sol, Decimal::new(5_000, 9)), Ok(Decimal::new(5_000, 9)));
+        assert_eq!(manager.lamports(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_publishes_wallet_sol_for_the_low_balance_rule() {
+        use crate::services::alert_rules::AlertRuleEngine;
+        use serde_json::json;
+
+        let url = crate::utils::fixtures::rpc_node(|method, _| {
+            Ok(match method {
+                // 0.1 SOL
+                "getBalance" => json!({ "context": { "slot": 1 }, "value": 100_000_000u64 }),
+                "getTokenAccountsByOwner" => json!({ "context": { "slot": 1 }, "value": [] }),
+                method => panic!("unexpected {}", method),
+            })
+        })
+        .await;
+
+        let metrics = MetricsRegistry::new();
+        let solana = Arc::new(SolanaService::new(&url).unwrap());
+        let manager = BalanceManager::new(solana, Pubkey::new_unique(), &BalanceConfig::default()).with_metrics(metrics.clone());
+        manager.refresh().await.unwrap();
+        assert_eq!(metrics.get("wallet_sol").await, Some(0.1));
+
+        let mut rules = AlertRuleEngine::from_config(&crate::config::AppConfig::bundled_default().alerts).unwrap();
+        let fired = rules.evaluate(&metrics.snapshot().await, chrono::Utc::now());
+        assert!(fired.iter().any(|alert| alert.rule_name == "low_wallet_balance"));
+    }
+}
@@ -0,0 +1,450 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use prost::Message;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::models::ExecutionStatus;
+use crate::services::events::BotEvent;
+
+/// Serialization format for event consumers
+pub trait Codec: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// MIME type for transports that carry one (webhooks, HTTP)
+    fn content_type(&self) -> &'static str;
+
+    fn encode(&self, event: &BotEvent) -> Result<Vec<u8>>;
+
+    fn decode(&self, bytes: &[u8]) -> Result<BotEvent>;
+}
+
+/// Available codecs, selectable by name in config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodecKind {
+    Json,
+    Bincode,
+    Proto,
+}
+
+impl CodecKind {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "json" => Ok(CodecKind::Json),
+            "bincode" => Ok(CodecKind::Bincode),
+            "proto" | "protobuf" => Ok(CodecKind::Proto),
+            other => Err(anyhow::anyhow!("Unknown event codec: {}", other)),
+        }
+    }
+
+    pub fn codec(self) -> Arc<dyn Codec> {
+        match self {
+            CodecKind::Json => Arc::new(JsonCodec),
+            CodecKind::Bincode => Arc::new(BincodeCodec),
+            CodecKind::Proto => Arc::new(ProtoCodec),
+        }
+    }
+}
+
+/// Human-readable JSON, for webhooks and logs
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn content_type(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn encode(&self, event: &BotEvent) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(event)?)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<BotEvent> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Compact bincode, for journals
+pub struct BincodeCodec;
+
+impl Codec for BincodeCodec {
+    fn name(&self) -> &'static str {
+        "bincode"
+    }
+
+    fn content_type(&self) -> &'static str {
+        "application/octet-stream"
+    }
+
+    fn encode(&self, event: &BotEvent) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(event)?)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<BotEvent> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+/// Protobuf matching `proto/events.proto`, for gRPC subscribers
+pub struct ProtoCodec;
+
+impl Codec for ProtoCodec {
+    fn name(&self) -> &'static str {
+        "proto"
+    }
+
+    fn content_type(&self) -> &'static str {
+        "application/x-protobuf"
+    }
+
+    fn encode(&self, event: &BotEvent) -> Result<Vec<u8>> {
+        Ok(proto::Envelope::from(event).encode_to_vec())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<BotEvent> {
+        proto::Envelope::decode(bytes)?.try_into()
+    }
+}
+
+fn to_nanos(timestamp: &DateTime<Utc>) -> i64 {
+    timestamp.timestamp_nanos_opt().unwrap_or_default()
+}
+
+fn from_nanos(nanos: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_nanos(nanos)
+}
+
+fn status_to_proto(status: &ExecutionStatus) -> i32 {
+    match status {
+        ExecutionStatus::Pending => 0,
+        ExecutionStatus::Executing => 1,
+        ExecutionStatus::Submitted => 2,
+        ExecutionStatus::Confirmed => 3,
+        ExecutionStatus::Failed => 4,
+        ExecutionStatus::Cancelled => 5,
+        ExecutionStatus::Simulated => 6,
+    }
+}
+
+fn status_from_proto(value: i32) -> Result<ExecutionStatus> {
+    match value {
+        0 => Ok(ExecutionStatus::Pending),
+        1 => Ok(ExecutionStatus::Executing),
+        2 => Ok(ExecutionStatus::Submitted),
+        3 => Ok(ExecutionStatus::Confirmed),
+        4 => Ok(ExecutionStatus::Failed),
+        5 => Ok(ExecutionStatus::Cancelled),
+        6 => Ok(ExecutionStatus::Simulated),
+        other => Err(anyhow::anyhow!("Unknown execution status {}", other)),
+    }
+}
+
+mod proto {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Envelope {
+        #[prost(oneof = "Event", tags = "1, 2, 3, 4, 5")]
+        pub event: Option<Event>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "1")]
+        OpportunityDetected(OpportunityDetected),
+        #[prost(message, tag = "2")]
+        ExecutionFinished(ExecutionFinished),
+        #[prost(message, tag = "3")]
+        AlertFired(AlertFired),
+        #[prost(message, tag = "4")]
+        EngineStateChanged(EngineStateChanged),
+        #[prost(message, tag = "5")]
+        StrategyUpdated(StrategyUpdated),
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct OpportunityDetected {
+        #[prost(string, tag = "1")]
+        pub id: String,
+        #[prost(string, tag = "2")]
+        pub base_mint: String,
+        #[prost(string, tag = "3")]
+        pub quote_mint: String,
+        #[prost(string, tag = "4")]
+        pub buy_pool: String,
+        #[prost(string, tag = "5")]
+        pub sell_pool: String,
+        #[prost(string, tag = "6")]
+        pub buy_dex: String,
+        #[prost(string, tag = "7")]
+        pub sell_dex: String,
+        #[prost(string, tag = "8")]
+        pub profit_percentage: String,
+        #[prost(string, tag = "9")]
+        pub estimated_profit: String,
+        #[prost(int64, tag = "10")]
+        pub timestamp_nanos: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExecutionFinished {
+        #[prost(string, tag = "1")]
+        pub id: String,
+        #[prost(string, tag = "2")]
+        pub opportunity_id: String,
+        #[prost(int32, tag = "3")]
+        pub status: i32,
+        #[prost(string, optional, tag = "4")]
+        pub signature: Option<String>,
+        #[prost(string, optional, tag = "5")]
+        pub actual_profit: Option<String>,
+        #[prost(string, optional, tag = "6")]
+        pub error: Option<String>,
+        #[prost(int64, tag = "7")]
+        pub timestamp_nanos: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AlertFired {
+        #[prost(string, tag = "1")]
+        pub rule: String,
+        #[prost(string, tag = "2")]
+        pub message: String,
+        #[prost(double, tag = "3")]
+        pub value: f64,
+        #[prost(int64, tag = "4")]
+        pub timestamp_nanos: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct EngineStateChanged {
+        #[prost(bool, tag = "1")]
+        pub paused: bool,
+        #[prost(int64, tag = "2")]
+        pub timestamp_nanos: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct StrategyParameterChange {
+        #[prost(string, tag = "1")]
+        pub field: String,
+        #[prost(string, tag = "2")]
+        pub previous: String,
+        #[prost(string, tag = "3")]
+        pub new: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct StrategyUpdated {
+        #[prost(string, tag = "1")]
+        pub id: String,
+        #[prost(message, repeated, tag = "2")]
+        pub changes: Vec<StrategyParameterChange>,
+        #[prost(bool, tag = "3")]
+        pub forced: bool,
+        #[prost(int64, tag = "4")]
+        pub timestamp_nanos: i64,
+    }
+
+    impl From<&BotEvent> for Envelope {
+        fn from(event: &BotEvent) -> Self {
+            let event = match event {
+                BotEvent::OpportunityDetected {
+                    id,
+                    base_mint,
+                    quote_mint,
+                    buy_pool,
+                    sell_pool,
+                    buy_dex,
+                    sell_dex,
+                    profit_percentage,
+                    estimated_profit,
+                    timestamp,
+                } => Event::OpportunityDetected(OpportunityDetected {
+                    id: id.clone(),
+                    base_mint: base_mint.clone(),
+                    quote_mint: quote_mint.clone(),
+                    buy_pool: buy_pool.clone(),
+                    sell_pool: sell_pool.clone(),
+                    buy_dex: buy_dex.clone(),
+                    sell_dex: sell_dex.clone(),
+                    profit_percentage: profit_percentage.to_string(),
+                    estimated_profit: estimated_profit.to_string(),
+                    timestamp_nanos: to_nanos(timestamp),
+                }),
+                BotEvent::ExecutionFinished {
+                    id,
+                    opportunity_id,
+                    status,
+                    signature,
+                    actual_profit,
+                    error,
+                    timestamp,
+                } => Event::ExecutionFinished(ExecutionFinished {
+                    id: id.clone(),
+                    opportunity_id: opportunity_id.clone(),
+                    status: status_to_proto(status),
+                    signature: signature.clone(),
+                    actual_profit: actual_profit.map(|p| p.to_string()),
+                    error: error.clone(),
+                    timestamp_nanos: to_nanos(timestamp),
+                }),
+                BotEvent::AlertFired { rule, message, value, timestamp } => Event::AlertFired(AlertFired {
+                    rule: rule.clone(),
+                    message: message.clone(),
+                    value: *value,
+                    timestamp_nanos: to_nanos(timestamp),
+                }),
+                BotEvent::EngineStateChanged { paused, timestamp } => Event::EngineStateChanged(EngineStateChanged {
+                    paused: *paused,
+                    timestamp_nanos: to_nanos(timestamp),
+                }),
+                BotEvent::StrategyUpdated { id, changes, forced, timestamp } => Event::StrategyUpdated(StrategyUpdated {
+                    id: id.clone(),
+                    changes: changes
+                        .iter()
+                        .map(|c| StrategyParameterChange {
+                            field: c.field.clone(),
+                            previous: c.previous.to_string(),
+                            new: c.new.to_string(),
+                        })
+                        .collect(),
+                    forced: *forced,
+                    timestamp_nanos: to_nanos(timestamp),
+                }),
+            };
+            Envelope { event: Some(event) }
+        }
+    }
+
+    impl TryFrom<Envelope> for BotEvent {
+        type Error = anyhow::Error;
+
+        fn try_from(envelope: Envelope) -> Result<Self> {
+            let event = envelope.event.ok_or_else(|| anyhow::anyhow!("Empty event envelope"))?;
+            Ok(match event {
+                Event::OpportunityDetected(e) => BotEvent::OpportunityDetected {
+                    id: e.id,
+                    base_mint: e.base_mint,
+                    quote_mint: e.quote_mint,
+                    buy_pool: e.buy_pool,
+                    sell_pool: e.sell_pool,
+                    buy_dex: e.buy_dex,
+                    sell_dex: e.sell_dex,
+                    profit_percentage: Decimal::from_str(&e.profit_percentage)?,
+                    estimated_profit: Decimal::from_str(&e.estimated_profit)?,
+                    timestamp: from_nanos(e.timestamp_nanos),
+                },
+                Event::ExecutionFinished(e) => BotEvent::ExecutionFinished {
+                    id: e.id,
+                    opportunity_id: e.opportunity_id,
+                    status: status_from_proto(e.status)?,
+                    signature: e.signature,
+                    actual_profit: e.actual_profit.as_deref().map(Decimal::from_str).transpose()?,
+                    error: e.error,
+                    timestamp: from_nanos(e.timestamp_nanos),
+                },
+                Event::AlertFired(e) => BotEvent::AlertFired {
+                    rule: e.rule,
+                    message: e.message,
+                    value: e.value,
+                    timestamp: from_nanos(e.timestamp_nanos),
+                },
+                Event::EngineStateChanged(e) => BotEvent::EngineStateChanged {
+                    paused: e.paused,
+                    timestamp: from_nanos(e.timestamp_nanos),
+                },
+                Event::StrategyUpdated(e) => BotEvent::StrategyUpdated {
+                    id: e.id,
+                    changes: e
+                        .changes
+                        .into_iter()
+                        .map(|c| {
+                            Ok(crate::models::ParameterChange {
+                                field: c.field,
+                                previous: Decimal::from_str(&c.previous)?,
+                                new: Decimal::from_str(&c.new)?,
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                    forced: e.forced,
+                    timestamp: from_nanos(e.timestamp_nanos),
+                },
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn sample_events() -> Vec<BotEvent> {
+        let timestamp = Utc::now();
+        vec![
+            BotEvent::OpportunityDetected {
+                id: "opp-1".to_string(),
+                base_mint: "So11111111111111111111111111111111111111112".to_string(),
+                quote_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                buy_pool: "pool-a".to_string(),
+                sell_pool: "pool-b".to_string(),
+                buy_dex: "Raydium".to_string(),
+                sell_dex: "Meteora".to_string(),
+                profit_percentage: Decimal::new(1234, 5),
+                estimated_profit: Decimal::new(4567, 2),
+                timestamp,
+            },
+            BotEvent::ExecutionFinished {
+                id: "exec-1".to_string(),
+                opportunity_id: "opp-1".to_string(),
+                status: ExecutionStatus::Confirmed,
+                signature: Some("5sig".to_string()),
+                actual_profit: Some(Decimal::new(-15, 3)),
+                error: None,
+                timestamp,
+            },
+            BotEvent::ExecutionFinished {
+                id: "exec-2".to_string(),
+                opportunity_id: "opp-2".to_string(),
+                status: ExecutionStatus::Failed,
+                signature: None,
+                actual_profit: None,
+                error: Some("slippage exceeded".to_string()),
+                timestamp,
+            },
+            BotEvent::AlertFired {
+                rule: "low_success".to_string(),
+                message: "success rate 0.4 < 0.5".to_string(),
+                value: 0.4,
+                timestamp,
+            },
+            BotEvent::engine_state(true),
+            BotEvent::strategy_updated(
+                "default",
+                vec![crate::models::ParameterChange {
+                    field: "min_profit_threshold".to_string(),
+                    previous: Decimal::new(3, 3),
+                    new: Decimal::new(5, 3),
+                }],
+                false,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_round_trip_all_codecs() {
+        for kind in [CodecKind::Json, CodecKind::Bincode, CodecKind::Proto] {
+            let codec = kind.codec();
+            assert_eq!(CodecKind::parse(codec.name()).unwrap(), kind);
+            for event in sample_events() {
+                let bytes = codec.encode(&event).unwrap();
+                assert_eq!(codec.decode(&bytes).unwrap(), event, "{} round trip of {}", codec.name(), event.kind());
+            }
+        }
+    }
+}
@@ -0,0 +1,548 @@
+use anyhow::Result;
+use axum::{
+    extract::{FromRequestParts, Path, Query, State},
+    http::{header::AUTHORIZATION, request::Parts, StatusCode},
+    routing::{get, post, put},
+    Json, Router,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::{error, info, warn};
+
+use crate::{
+    arbitrage::{
+        correlation::CorrelationMatrix, position_watcher::HoldingReport, seasonality::SeasonalityReport,
+        strategy::StrategyMetrics, EngineHandle,
+    },
+    config::ControlApiConfig,
+    dex::{DexInterface, DexType},
+    models::{ArbitrageExecution, ArbitrageOpportunity, ArbitrageStrategy, PoolQuote, Token},
+    services::{
+        tenancy::{Caller, TenantRegistry},
+        StorageUsage,
+    },
+};
+
+type DexInstances = Arc<HashMap<DexType, Box<dyn DexInterface>>>;
+
+/// Shared state behind the control API handlers
+#[derive(Clone)]
+pub struct ControlApiState {
+    pub engine: EngineHandle,
+    pub dex_instances: DexInstances,
+    pub quote_timeout: Duration,
+    pub max_strategy_change_factor: Decimal,
+    pub tenants: Arc<TenantRegistry>,
+}
+
+impl ControlApiState {
+    pub fn new(engine: EngineHandle, dex_instances: DexInstances, config: &ControlApiConfig) -> Self {
+        Self {
+            tenants: engine.tenants(),
+            engine,
+            dex_instances,
+            quote_timeout: Duration::from_millis(config.quote_timeout_ms),
+            max_strategy_change_factor: Decimal::try_from(config.max_strategy_change_factor).unwrap_or(Decimal::TWO),
+        }
+    }
+}
+
+/// Body of `POST /quotes`
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuoteRequest {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount: Decimal,
+}
+
+/// One venue's answer to a quote request
+#[derive(Debug, Clone, Serialize)]
+pub struct VenueQuote {
+    pub dex: DexType,
+    pub pool: Option<String>,
+    pub output_amount: Option<Decimal>,
+    pub minimum_output: Option<Decimal>,
+    pub price_impact: Option<Decimal>,
+    pub fee_amount: Option<Decimal>,
+    /// Route through the quoted pool, as pool addresses
+    pub route: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl VenueQuote {
+    fn from_quote(dex: DexType, quote: &PoolQuote) -> Self {
+        Self {
+            dex,
+            pool: Some(quote.pool.pool_address.to_string()),
+            output_amount: Some(quote.output_amount),
+            minimum_output: Some(quote.minimum_output),
+            price_impact: Some(quote.price_impact),
+            fee_amount: Some(quote.fee_amount),
+            route: vec![quote.pool.pool_address.to_string()],
+            error: None,
+        }
+    }
+
+    fn failed(dex: DexType, error: String) -> Self {
+        Self {
+            dex,
+            pool: None,
+            output_amount: None,
+            minimum_output: None,
+            price_impact: None,
+            fee_amount: None,
+            route: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+/// Every venue's quote side by side, plus the one returning the most output
+#[derive(Debug, Clone, Serialize)]
+pub struct QuoteComparison {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount: Decimal,
+    pub quotes: Vec<VenueQuote>,
+    pub best: Option<VenueQuote>,
+}
+
+/// Quote the swap on every enabled DEX concurrently
+///
+/// Only the mints are known here, so tokens are built without metadata or transfer fees.
+pub async fn compare_quotes(state: &ControlApiState, request: &QuoteRequest) -> Result<QuoteComparison> {
+    let input = Token::new(Pubkey::from_str(&request.input_mint)?, String::new(), String::new(), 0);
+    let output = Token::new(Pubkey::from_str(&request.output_mint)?, String::new(), String::new(), 0);
+    if request.amount <= Decimal::ZERO {
+        anyhow::bail!("amount must be positive");
+    }
+
+    let mut tasks = JoinSet::new();
+    for dex_type in state.dex_instances.keys().cloned() {
+        let dex_instances = state.dex_instances.clone();
+        let (input, output, amount) = (input.clone(), output.clone(), request.amount);
+        let timeout = state.quote_timeout;
+        tasks.spawn(async move {
+            let dex = &dex_instances[&dex_type];
+            let result = tokio::time::timeout(timeout, dex.get_quote(&input, &output, amount, None)).await;
+            match result {
+                Ok(Ok(quote)) => VenueQuote::from_quote(dex_type, &quote),
+                Ok(Err(e)) => VenueQuote::failed(dex_type, e.to_string()),
+                Err(_) => VenueQuote::failed(dex_type, format!("timed out after {:?}", timeout)),
+            }
+        });
+    }
+
+    let mut quotes = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        quotes.push(joined?);
+    }
+    quotes.sort_by_key(|q| q.dex.to_string());
+
+    Ok(QuoteComparison {
+        input_mint: request.input_mint.clone(),
+        output_mint: request.output_mint.clone(),
+        amount: request.amount,
+        best: best_quote(&quotes).cloned(),
+        quotes,
+    })
+}
+
+/// The successful quote with the highest output
+fn best_quote(quotes: &[VenueQuote]) -> Option<&VenueQuote> {
+    quotes
+        .iter()
+        .filter(|q| q.output_amount.is_some_and(|out| out > Decimal::ZERO))
+        .max_by_key(|q| q.output_amount)
+}
+
+async fn post_quotes(
+    State(state): State<ControlApiState>,
+    Json(request): Json<QuoteRequest>,
+) -> Result<Json<QuoteComparison>, (StatusCode, String)> {
+    compare_quotes(&state, &request)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+type ApiError = (StatusCode, String);
+
+/// Callers are identified by their `Authorization: Bearer` token when tenancy is enabled
+impl FromRequestParts<ControlApiState> for Caller {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &ControlApiState) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        state
+            .tenants
+            .authenticate(token)
+            .ok_or((StatusCode::UNAUTHORIZED, "missing or unknown API token".to_string()))
+    }
+}
+
+/// Query of `GET /executions`
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<usize>,
+}
+
+async fn get_opportunities(State(state): State<ControlApiState>, caller: Caller) -> Json<Vec<ArbitrageOpportunity>> {
+    let mut opportunities = state.engine.get_active_opportunities().await;
+    opportunities.retain(|o| caller.sees(&o.tags));
+    Json(opportunities)
+}
+
+async fn get_executions(
+    State(state): State<ControlApiState>,
+    caller: Caller,
+    Query(query): Query<HistoryQuery>,
+) -> Json<Vec<ArbitrageExecution>> {
+    let mut history = state.engine.get_execution_history(None).await;
+    history.retain(|e| caller.sees(&e.tags));
+    if let Some(limit) = query.limit {
+        history.truncate(limit);
+    }
+    Json(history)
+}
+
+/// Opportunity frequency and profit by hour and weekday per pair, over the history still held
+async fn get_seasonality(State(state): State<ControlApiState>, caller: Caller) -> Json<SeasonalityReport> {
+    let mut opportunities = state.engine.get_opportunity_history().await;
+    opportunities.retain(|o| caller.sees(&o.tags));
+    let mut executions = state.engine.get_execution_history(None).await;
+    executions.retain(|e| caller.sees(&e.tags));
+    Json(SeasonalityReport::build(&opportunities, &executions))
+}
+
+/// Spreads correlate across every namespace's pairs, so operator only
+async fn get_correlation(State(state): State<ControlApiState>, caller: Caller) -> Result<Json<CorrelationMatrix>, ApiError> {
+    if caller != Caller::Operator {
+        return Err((StatusCode::FORBIDDEN, "spread correlations are only available to the operator".to_string()));
+    }
+    Ok(Json(state.engine.spread_correlations()))
+}
+
+/// Holdings of the bot's wallets, which belong to no namespace, so operator only
+async fn get_positions(State(state): State<ControlApiState>, caller: Caller) -> Result<Json<Vec<HoldingReport>>, ApiError> {
+    if caller != Caller::Operator {
+        return Err((StatusCode::FORBIDDEN, "position reports are only available to the operator".to_string()));
+    }
+    Ok(Json(state.engine.get_position_reports().await))
+}
+
+/// Keeps the dead man switch from pausing execution; operator only
+async fn post_heartbeat(State(state): State<ControlApiState>, caller: Caller) -> Result<StatusCode, ApiError> {
+    if caller != Caller::Operator {
+        return Err((StatusCode::FORBIDDEN, "heartbeats are only accepted from the operator".to_string()));
+    }
+    state.engine.heartbeat();
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Instance-wide, so operator only
+async fn get_storage(State(state): State<ControlApiState>, caller: Caller) -> Result<Json<StorageUsage>, ApiError> {
+    if caller != Caller::Operator {
+        return Err((StatusCode::FORBIDDEN, "storage usage is only available to the operator".to_string()));
+    }
+    Ok(Json(state.engine.get_storage_usage().await))
+}
+
+async fn get_strategies(State(state): State<ControlApiState>, caller: Caller) -> Json<Vec<ArbitrageStrategy>> {
+    let mut strategies = state.engine.get_strategies().await;
+    strategies.retain(|s| caller.owns(s));
+    Json(strategies)
+}
+
+/// Create a strategy in the caller's namespace; the operator may name any configured tenant's
+async fn post_strategy(
+    State(state): State<ControlApiState>,
+    caller: Caller,
+    Json(strategy): Json<ArbitrageStrategy>,
+) -> Result<(StatusCode, Json<ArbitrageStrategy>), ApiError> {
+    let namespace = caller.namespace().unwrap_or(&strategy.namespace).to_string();
+    if !namespace.is_empty() && state.tenants.tenant(&namespace).is_none() {
+        return Err((StatusCode::BAD_REQUEST, format!("unknown namespace {}", namespace)));
+    }
+    let strategy = strategy.with_namespace(&namespace);
+    if state.engine.get_strategy(&strategy.id).await.is_some() {
+        return Err((StatusCode::CONFLICT, format!("strategy {} already exists", strategy.id)));
+    }
+    state.engine.add_strategy(strategy.clone()).await.map_err(bad_request)?;
+    Ok((StatusCode::CREATED, Json(strategy)))
+}
+
+/// Query of `PUT /strategies/{id}`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateQuery {
+    /// Apply threshold changes beyond `max_strategy_change_factor`
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Replace a strategy; the id in the path wins over the one in the body. A threshold moving by more
+/// than `max_strategy_change_factor` in one step is refused unless forced.
+async fn put_strategy(
+    State(state): State<ControlApiState>,
+    caller: Caller,
+    Path(id): Path<String>,
+    Query(query): Query<UpdateQuery>,
+    Json(mut strategy): Json<ArbitrageStrategy>,
+) -> Result<Json<ArbitrageStrategy>, ApiError> {
+    let id = caller.scope(&id);
+    let Some(existing) = state.engine.get_strategy(&id).await.filter(|s| caller.owns(s)) else {
+        return Err(not_found(&id));
+    };
+    let changes = existing.threshold_changes(&strategy);
+    let excessive: Vec<String> = changes
+        .iter()
+        .filter(|change| change.exceeds(state.max_strategy_change_factor))
+        .map(|change| change.to_string())
+        .collect();
+    if !excessive.is_empty() && !query.force {
+        warn!("Refused update of strategy {}: {}", id, excessive.join(", "));
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "{} changes by more than {}x in one step; resend with ?force=true to apply",
+                excessive.join(", "),
+                state.max_strategy_change_factor
+            ),
+        ));
+    }
+
+    strategy.id = id;
+    strategy.namespace = existing.namespace.clone();
+    strategy.created_at = existing.created_at;
+    strategy.updated_at = chrono::Utc::now();
+    state.engine.update_strategy(strategy.clone()).await.map_err(bad_request)?;
+    state.engine.record_strategy_change(&strategy.id, changes, !excessive.is_empty());
+    Ok(Json(strategy))
+}
+
+async fn delete_strategy(
+    State(state): State<ControlApiState>,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let id = caller.scope(&id);
+    if !state.engine.get_strategy(&id).await.is_some_and(|s| caller.owns(&s)) {
+        return Err(not_found(&id));
+    }
+    state.engine.remove_strategy(&id).await.map_err(internal)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Evaluation counts of one strategy
+async fn get_strategy_metrics(
+    State(state): State<ControlApiState>,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<StrategyMetrics>, ApiError> {
+    let id = caller.scope(&id);
+    if !state.engine.get_strategy(&id).await.is_some_and(|s| caller.owns(&s)) {
+        return Err(not_found(&id));
+    }
+    let mut metrics = state.engine.get_strategy_metrics().await;
+    Ok(Json(metrics.remove(&id).unwrap_or_default()))
+}
+
+fn not_found(strategy_id: &str) -> ApiError {
+    (StatusCode::NOT_FOUND, format!("strategy {} not found", strategy_id))
+}
+
+/// A strategy definition its kind refused to build
+fn bad_request(e: anyhow::Error) -> ApiError {
+    (StatusCode::BAD_REQUEST, e.to_string())
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+pub fn router(state: ControlApiState) -> Router {
+    Router::new()
+        .route("/quotes", post(post_quotes))
+        .route("/opportunities", get(get_opportunities))
+        .route("/executions", get(get_executions))
+        .route("/reports/seasonality", get(get_seasonality))
+        .route("/reports/correlation", get(get_correlation))
+        .route("/reports/positions", get(get_positions))
+        .route("/storage", get(get_storage))
+        .route("/heartbeat", post(post_heartbeat))
+        .route("/strategies", get(get_strategies).post(post_strategy))
+        .route("/strategies/{id}", put(put_strategy).delete(delete_strategy))
+        .route("/strategies/{id}/metrics", get(get_strategy_metrics))
+        .with_state(state)
+}
+
+/// Bind the control API and serve it in the background
+pub async fn serve(config: &ControlApiConfig, state: ControlApiState) -> Result<tokio::task::JoinHandle<()>> {
+    let listener = tokio::net::TcpListener::bind(&config.bind).await?;
+    info!("Control API listening on {}", listener.local_addr()?);
+    let app = router(state);
+    Ok(tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            error!("Control API stopped: {}", e);
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::ArbitrageEngine;
+    use crate::config::AppConfig;
+    use crate::services::BotEvent;
+
+    fn quote(dex: DexType, output: Option<i64>) -> VenueQuote {
+        match output {
+            Some(out) => VenueQuote {
+                output_amount: Some(Decimal::from(out)),
+                ..VenueQuote::failed(dex, String::new())
+            },
+            None => VenueQuote::failed(dex, "no pools".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_best_quote_skips_failures() {
+        let quotes = vec![
+            quote(DexType::Meteora, Some(98)),
+            quote(DexType::Raydium, None),
+            quote(DexType::Whirlpool, Some(101)),
+        ];
+        assert_eq!(best_quote(&quotes).unwrap().dex, DexType::Whirlpool);
+        assert!(best_quote(&quotes[1..2]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_strategies_are_managed_over_http() {
+        let config = AppConfig::bundled_default();
+        let engine = ArbitrageEngine::new(config.clone(), None, HashMap::new());
+        let state = ControlApiState::new(engine.handle(), engine.dex_instances(), &config.control_api);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+        let client = reqwest::Client::new();
+
+        let mut strategy = ArbitrageEngine::default_strategy(&config.arbitrage);
+        strategy.id = "runtime".to_string();
+        let created = client.post(format!("{}/strategies", base)).json(&strategy).send().await.unwrap();
+        assert_eq!(created.status(), reqwest::StatusCode::CREATED);
+        let duplicate = client.post(format!("{}/strategies", base)).json(&strategy).send().await.unwrap();
+        assert_eq!(duplicate.status(), reqwest::StatusCode::CONFLICT);
+        let metrics: StrategyMetrics =
+            client.get(format!("{}/strategies/runtime/metrics", base)).send().await.unwrap().json().await.unwrap();
+        assert_eq!(metrics, StrategyMetrics::default());
+        let mut unknown = strategy.clone().with_kind("martingale", serde_json::Value::Null);
+        unknown.id = "martingale".to_string();
+        let refused = client.post(format!("{}/strategies", base)).json(&unknown).send().await.unwrap();
+        assert_eq!(refused.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        let mut audit = engine.events().subscribe();
+        strategy.is_active = false;
+        strategy.min_profit_threshold *= Decimal::new(15, 1);
+        let updated = client.put(format!("{}/strategies/runtime", base)).json(&strategy).send().await.unwrap();
+        assert_eq!(updated.status(), reqwest::StatusCode::OK);
+        assert!(!engine.handle().get_strategy("runtime").await.unwrap().is_active);
+        let BotEvent::StrategyUpdated { changes, forced, .. } = audit.recv().await.unwrap() else {
+            panic!("expected a strategy update event");
+        };
+        assert_eq!((changes.len(), forced), (1, false));
+
+        // A tenfold jump is a likely typo and needs an explicit override
+        let previous = strategy.min_profit_threshold;
+        strategy.min_profit_threshold *= Decimal::TEN;
+        let refused = client.put(format!("{}/strategies/runtime", base)).json(&strategy).send().await.unwrap();
+        assert_eq!(refused.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(engine.handle().get_strategy("runtime").await.unwrap().min_profit_threshold, previous);
+        let forced = client.put(format!("{}/strategies/runtime?force=true", base)).json(&strategy).send().await.unwrap();
+        assert_eq!(forced.status(), reqwest::StatusCode::OK);
+        let BotEvent::StrategyUpdated { changes, forced, .. } = audit.recv().await.unwrap() else {
+            panic!("expected a strategy update event");
+        };
+        assert_eq!((changes[0].previous, changes[0].new, forced), (previous, strategy.min_profit_threshold, true));
+
+        let deleted = client.delete(format!("{}/strategies/runtime", base)).send().await.unwrap();
+        assert_eq!(deleted.status(), reqwest::StatusCode::NO_CONTENT);
+        let missing = client.delete(format!("{}/strategies/runtime", base)).send().await.unwrap();
+        assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let history: Vec<serde_json::Value> =
+            client.get(format!("{}/executions?limit=5", base)).send().await.unwrap().json().await.unwrap();
+        assert!(history.is_empty());
+        let storage: serde_json::Value = client.get(format!("{}/storage", base)).send().await.unwrap().json().await.unwrap();
+        assert_eq!(storage["strategies_count"], 0);
+        let positions: Vec<HoldingReport> =
+            client.get(format!("{}/reports/positions", base)).send().await.unwrap().json().await.unwrap();
+        assert!(positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tenants_only_see_their_own_namespace() {
+        let mut config = AppConfig::bundled_default();
+        config.tenancy.enabled = true;
+        config.tenancy.operator_token = "operator".to_string();
+        for namespace in ["alpha", "beta"] {
+            config.tenancy.tenants.push(crate::config::TenantConfig {
+                namespace: namespace.to_string(),
+                api_token: format!("{}-token", namespace),
+                wallet: Default::default(),
+                daily_loss_limit: 0.0,
+            });
+        }
+        let tenants = TenantRegistry::from_config(&config.tenancy).unwrap();
+        let engine = ArbitrageEngine::new(config.clone(), None, HashMap::new()).with_tenants(tenants);
+        let state = ControlApiState::new(engine.handle(), engine.dex_instances(), &config.control_api);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+        let client = reqwest::Client::new();
+
+        let anonymous = client.get(format!("{}/strategies", base)).send().await.unwrap();
+        assert_eq!(anonymous.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        // Both tenants may use the same id without colliding
+        let mut strategy = ArbitrageEngine::default_strategy(&config.arbitrage);
+        strategy.id = "spread".to_string();
+        for namespace in ["alpha", "beta"] {
+            let created = client
+                .post(format!("{}/strategies", base))
+                .bearer_auth(format!("{}-token", namespace))
+                .json(&strategy)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(created.status(), reqwest::StatusCode::CREATED);
+        }
+        let visible: Vec<ArbitrageStrategy> =
+            client.get(format!("{}/strategies", base)).bearer_auth("alpha-token").send().await.unwrap().json().await.unwrap();
+        assert_eq!(visible.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec!["alpha/spread"]);
+        let all: Vec<ArbitrageStrategy> =
+            client.get(format!("{}/strategies", base)).bearer_auth("operator").send().await.unwrap().json().await.unwrap();
+        assert_eq!(all.len(), 2);
+
+        // A tenant cannot reach another's strategy, even by its full id
+        let foreign = client.delete(format!("{}/strategies/beta%2Fspread", base)).bearer_auth("alpha-token").send().await.unwrap();
+        assert_eq!(foreign.status(), reqwest::StatusCode::NOT_FOUND);
+        let own = client.delete(format!("{}/strategies/spread", base)).bearer_auth("beta-token").send().await.unwrap();
+        assert_eq!(own.status(), reqwest::StatusCode::NO_CONTENT);
+        assert!(engine.handle().get_strategy("alpha/spread").await.is_some());
+
+        let storage = client.get(format!("{}/storage", base)).bearer_auth("alpha-token").send().await.unwrap();
+        assert_eq!(storage.status(), reqwest::StatusCode::FORBIDDEN);
+        let positions = client.get(format!("{}/reports/positions", base)).bearer_auth("alpha-token").send().await.unwrap();
+        assert_eq!(positions.status(), reqwest::StatusCode::FORBIDDEN);
+    }
+}
@@ -1,77 +1,1081 @@
-use anyhow::Result;
-// use sqlx::{PgPool, Row}; // Temporarily disabled due to dependency conflicts
+use anyhow::{Context, Result};
 use rust_decimal::Decimal;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use sqlx::any::{AnyArguments, AnyPoolOptions, AnyRow};
+use sqlx::query::Query;
+use sqlx::{Any, AnyPool, Row};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
 
+use crate::config::DatabaseConfig;
 use crate::models::{
     ArbitrageOpportunity, ArbitrageStrategy, ArbitrageExecution,
-    OpportunityStatus, ExecutionStatus,
+    OpportunityStatus, ExecutionStatus, PoolSnapshot, PoolState,
 };
+use crate::services::journal::Journal;
+use crate::services::metrics::MetricsRegistry;
+use crate::utils::panic_guard::lock_or_recover;
 
-/// Database service - temporary stub implementation
+/// Persistent storage behind the memory store. The URL picks the backend:
+/// `postgres://...` or `sqlite://path?mode=rwc`.
+///
+/// Losing the connection mid-run degrades to journaling writes, which are replayed in order by
+/// `recover` once the database answers again. Reads keep failing until then.
 pub struct DatabaseService {
-    // pool: PgPool, // Temporarily disabled
-    _placeholder: (), // Placeholder for future database implementation
+    pool: AnyPool,
+    buffer: Mutex<WriteBuffer>,
+}
+
+/// A write held back while the database is unreachable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BufferedWrite {
+    SaveOpportunity(Box<ArbitrageOpportunity>),
+    UpdateOpportunityStatus(Box<ArbitrageOpportunity>),
+    SaveStrategy(ArbitrageStrategy),
+    DeleteStrategy(String),
+    SaveExecution(Box<ArbitrageExecution>),
+    SavePoolSnapshot(PoolSnapshot),
+}
+
+struct WriteBuffer {
+    /// None when buffering is off, in which case degraded writes are dropped
+    journal: Option<Journal>,
+    degraded: bool,
+    dropped: u64,
+}
+
+impl WriteBuffer {
+    fn backlog(&self) -> usize {
+        self.journal.as_ref().map_or(0, Journal::len)
+    }
+}
+
+/// Outcome of one `recover` pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Recovery {
+    /// Buffered writes applied this pass
+    pub replayed: usize,
+    /// Buffered writes still waiting
+    pub backlog: usize,
+    /// Whether the database is back in use
+    pub recovered: bool,
+}
+
+/// Errors meaning the database cannot be reached, as opposed to a bad query or row
+fn is_connection_loss(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<sqlx::Error>(),
+        Some(
+            sqlx::Error::Io(_)
+                | sqlx::Error::Tls(_)
+                | sqlx::Error::Protocol(_)
+                | sqlx::Error::PoolTimedOut
+                | sqlx::Error::PoolClosed
+                | sqlx::Error::WorkerCrashed
+        )
+    )
+}
+
+/// Status as stored in the `status` columns, e.g. "Completed"
+fn status_label<T: Serialize>(status: &T) -> Result<String> {
+    match serde_json::to_value(status)? {
+        serde_json::Value::String(label) => Ok(label),
+        other => anyhow::bail!("Unexpected status encoding: {}", other),
+    }
+}
+
+/// Which executions a query covers; empty filters match everything
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionQuery {
+    /// Executed at or after
+    pub from: Option<DateTime<Utc>>,
+    /// Executed before
+    pub to: Option<DateTime<Utc>>,
+    /// "BASE/QUOTE" by symbol
+    pub pair: Option<String>,
+    /// Names from the execution's `strategy` tag
+    pub strategies: Vec<String>,
+    pub statuses: Vec<ExecutionStatus>,
+    /// Tags the execution must all carry, as key and value
+    pub tags: Vec<(String, String)>,
+}
+
+enum Param {
+    Int(i64),
+    Text(String),
+}
+
+impl ExecutionQuery {
+    pub fn between(from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        Self { from: Some(from), to: Some(to), ..Default::default() }
+    }
+
+    pub fn with_pair(mut self, pair: impl Into<String>) -> Self {
+        self.pair = Some(pair.into());
+        self
+    }
+
+    pub fn with_strategies(mut self, strategies: Vec<String>) -> Self {
+        self.strategies = strategies;
+        self
+    }
+
+    pub fn with_statuses(mut self, statuses: Vec<ExecutionStatus>) -> Self {
+        self.statuses = statuses;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<(String, String)>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// The WHERE clause, numbered from `$1`, and what to bind to it
+    fn filter(&self) -> Result<(String, Vec<Param>)> {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        let mut placeholder = |param: Param| {
+            params.push(param);
+            format!("${}", params.len())
+        };
+
+        if let Some(from) = self.from {
+            conditions.push(format!("executed_at >= {}", placeholder(Param::Int(from.timestamp_millis()))));
+        }
+        if let Some(to) = self.to {
+            conditions.push(format!("executed_at < {}", placeholder(Param::Int(to.timestamp_millis()))));
+        }
+        if let Some(pair) = &self.pair {
+            conditions.push(format!("pair = {}", placeholder(Param::Text(pair.clone()))));
+        }
+        if !self.strategies.is_empty() {
+            let list: Vec<String> = self.strategies.iter().map(|s| placeholder(Param::Text(s.clone()))).collect();
+            conditions.push(format!("strategy IN ({})", list.join(", ")));
+        }
+        if !self.statuses.is_empty() {
+            let list = self
+                .statuses
+                .iter()
+                .map(|s| Ok(placeholder(Param::Text(status_label(s)?))))
+                .collect::<Result<Vec<_>>>()?;
+            conditions.push(format!("status IN ({})", list.join(", ")));
+        }
+        for (key, value) in &self.tags {
+            conditions.push(format!(
+                "id IN (SELECT execution_id FROM execution_tags WHERE key = {} AND value = {})",
+                placeholder(Param::Text(key.clone())),
+                placeholder(Param::Text(value.clone()))
+            ));
+        }
+
+        let clause = if conditions.is_empty() { String::new() } else { format!(" WHERE {}", conditions.join(" AND ")) };
+        Ok((clause, params))
+    }
+}
+
+fn bind_all<'q>(mut query: Query<'q, Any, AnyArguments<'q>>, params: Vec<Param>) -> Query<'q, Any, AnyArguments<'q>> {
+    for param in params {
+        query = match param {
+            Param::Int(value) => query.bind(value),
+            Param::Text(value) => query.bind(value),
+        };
+    }
+    query
+}
+
+/// How `aggregate_executions` groups its totals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateBy {
+    Total,
+    Pair,
+    Strategy,
+}
+
+/// Totals over a set of executions, computed by the database
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionAggregate {
+    /// The pair or strategy grouped on; None for the overall total or untagged executions
+    pub key: Option<String>,
+    pub executions: u64,
+    pub confirmed: u64,
+    pub failed: u64,
+    /// Summed in floating point, so exact to about fifteen significant digits
+    pub total_profit: Decimal,
+    pub total_cost: Decimal,
+}
+
+impl ExecutionAggregate {
+    pub fn net_profit(&self) -> Decimal {
+        self.total_profit - self.total_cost
+    }
+}
+
+fn decode<T: DeserializeOwned>(row: &AnyRow) -> Result<T> {
+    let data: String = row.try_get("data")?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+fn decimal_column(row: &AnyRow, column: &str) -> Result<Decimal> {
+    let value: Option<String> = row.try_get(column)?;
+    match value {
+        Some(value) => value.parse().with_context(|| format!("Invalid decimal in {}: {}", column, value)),
+        None => Ok(Decimal::ZERO),
+    }
+}
+
+/// Fill in the query columns of executions recorded before they existed
+async fn backfill_execution_columns(pool: &AnyPool) -> Result<()> {
+    let rows = sqlx::query("SELECT data FROM executions WHERE pair IS NULL").fetch_all(pool).await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+    info!("Backfilling query columns of {} executions", rows.len());
+    for row in &rows {
+        let execution: ArbitrageExecution = decode(row)?;
+        sqlx::query("UPDATE executions SET pair = $1, strategy = $2 WHERE id = $3")
+            .bind(execution.opportunity.pair())
+            .bind(execution.tags.get("strategy").cloned())
+            .bind(&execution.id)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Index the tags of executions recorded before they were
+async fn backfill_execution_tags(pool: &AnyPool) -> Result<()> {
+    let rows = sqlx::query("SELECT data FROM executions WHERE tags IS NULL").fetch_all(pool).await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+    info!("Indexing tags of {} executions", rows.len());
+    for row in &rows {
+        let execution: ArbitrageExecution = decode(row)?;
+        index_tags(pool, &execution).await?;
+    }
+    Ok(())
+}
+
+/// Replace the indexed tags of a stored execution with its current ones
+async fn index_tags(pool: &AnyPool, execution: &ArbitrageExecution) -> Result<()> {
+    let mut transaction = pool.begin().await?;
+    sqlx::query("DELETE FROM execution_tags WHERE execution_id = $1")
+        .bind(&execution.id)
+        .execute(&mut *transaction)
+        .await?;
+    for (key, value) in &execution.tags {
+        sqlx::query("INSERT INTO execution_tags (execution_id, key, value) VALUES ($1, $2, $3)")
+            .bind(&execution.id)
+            .bind(key)
+            .bind(value)
+            .execute(&mut *transaction)
+            .await?;
+    }
+    sqlx::query("UPDATE executions SET tags = $1 WHERE id = $2")
+        .bind(serde_json::to_string(&execution.tags)?)
+        .bind(&execution.id)
+        .execute(&mut *transaction)
+        .await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Migrations a database has applied, and the bundled ones it has not
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStatus {
+    pub applied: usize,
+    pub pending: Vec<String>,
+}
+
+/// `url` opened read-only where the driver supports it
+fn read_only_url(url: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    if url.starts_with("sqlite:") && !url.contains(":memory:") {
+        format!("{}{}mode=ro", url, separator)
+    } else if url.starts_with("postgres") {
+        format!("{}{}options=-c%20default_transaction_read_only%3Don", url, separator)
+    } else {
+        url.to_string()
+    }
 }
 
 impl DatabaseService {
-    pub async fn new(_database_url: &str) -> Result<Self> {
-        // let pool = PgPool::connect(database_url).await?;
-        // Self::create_tables(&pool).await?;
-        
-        Ok(Self { 
-            _placeholder: () 
+    /// Connect, honoring the pool size and timeout, and apply pending migrations
+    pub async fn new(config: &DatabaseConfig) -> Result<Self> {
+        if config.url.is_empty() {
+            anyhow::bail!("No database URL configured");
+        }
+        sqlx::any::install_default_drivers();
+        let pool = AnyPoolOptions::new()
+            .max_connections(config.max_connections.max(1))
+            .acquire_timeout(Duration::from_secs(config.timeout_seconds.max(1)))
+            .connect(&config.url)
+            .await
+            .context("Failed to connect to database")?;
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .context("Failed to apply database migrations")?;
+        backfill_execution_columns(&pool).await?;
+        backfill_execution_tags(&pool).await?;
+
+        let journal = if config.buffer_path.is_empty() {
+            None
+        } else {
+            Some(Journal::open(&config.buffer_path, config.buffer_capacity_mb.max(1) * 1024 * 1024)?)
+        };
+        // Writes left over from a previous run are replayed on the first recovery pass
+        let leftover = journal.as_ref().is_some_and(|journal| !journal.is_empty());
+        if leftover {
+            warn!("Database write buffer {} holds writes from a previous run", config.buffer_path);
+        }
+
+        Ok(Self {
+            pool,
+            buffer: Mutex::new(WriteBuffer { journal, degraded: leftover, dropped: 0 }),
         })
     }
 
-    // Stub implementations - all methods succeed without actual operations
-    pub async fn save_opportunity(&self, _opportunity: &ArbitrageOpportunity) -> Result<()> {
-        // TODO: Implement with actual database
+    /// Compare the migrations a database has applied with the bundled ones over a read-only
+    /// connection, without applying them, backfilling, or opening the write buffer
+    pub async fn migration_status(config: &DatabaseConfig) -> Result<MigrationStatus> {
+        if config.url.is_empty() {
+            anyhow::bail!("No database URL configured");
+        }
+        sqlx::any::install_default_drivers();
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(Duration::from_secs(config.timeout_seconds.max(1)))
+            .connect(&read_only_url(&config.url))
+            .await
+            .context("Failed to connect to database")?;
+
+        // A database that was never migrated has no migrations table
+        let applied: Vec<i64> = match sqlx::query("SELECT version FROM _sqlx_migrations WHERE success").fetch_all(&pool).await {
+            Ok(rows) => rows.iter().map(|row| row.try_get("version")).collect::<Result<_, _>>()?,
+            Err(_) => Vec::new(),
+        };
+        pool.close().await;
+
+        let pending = sqlx::migrate!("./migrations")
+            .iter()
+            .filter(|migration| !applied.contains(&migration.version))
+            .map(|migration| format!("{} {}", migration.version, migration.description))
+            .collect();
+        Ok(MigrationStatus { applied: applied.len(), pending })
+    }
+
+    fn lock_buffer(&self) -> std::sync::MutexGuard<'_, WriteBuffer> {
+        lock_or_recover(&self.buffer, "database write buffer")
+    }
+
+    /// Whether writes are being buffered instead of reaching the database
+    pub fn is_degraded(&self) -> bool {
+        self.lock_buffer().degraded
+    }
+
+    /// Writes waiting to be replayed
+    pub fn backlog(&self) -> usize {
+        self.lock_buffer().backlog()
+    }
+
+    /// Writes lost because buffering was off or a buffered record was overwritten
+    pub fn dropped_writes(&self) -> u64 {
+        self.lock_buffer().dropped
+    }
+
+    fn degrade(&self, error: &anyhow::Error) {
+        let mut buffer = self.lock_buffer();
+        if !buffer.degraded {
+            warn!("Database connection lost ({}); buffering writes until it is back", error);
+            buffer.degraded = true;
+        }
+    }
+
+    fn buffer_write(&self, write: BufferedWrite) -> Result<()> {
+        let payload = serde_json::to_vec(&write)?;
+        let mut buffer = self.lock_buffer();
+        // A recovery may have finished since the caller looked; this write brings the backlog back
+        buffer.degraded = true;
+        let Some(journal) = buffer.journal.as_mut() else {
+            buffer.dropped += 1;
+            return Ok(());
+        };
+        let before = journal.len();
+        journal.append(&payload)?;
+        // The ring makes room by overwriting the oldest writes
+        buffer.dropped += (before + 1).saturating_sub(journal.len()) as u64;
         Ok(())
     }
 
-    pub async fn update_opportunity_status(&self, _opportunity: &ArbitrageOpportunity) -> Result<()> {
-        // TODO: Implement with actual database
+    /// Run `attempt` unless the database is lost, buffering the write instead when it is or
+    /// when the attempt finds it gone
+    async fn write(&self, attempt: impl Future<Output = Result<()>>, buffered: impl FnOnce() -> BufferedWrite) -> Result<()> {
+        if self.is_degraded() {
+            return self.buffer_write(buffered());
+        }
+        match attempt.await {
+            Err(e) if is_connection_loss(&e) => {
+                self.degrade(&e);
+                self.buffer_write(buffered())
+            }
+            other => other,
+        }
+    }
+
+    async fn apply(&self, write: &BufferedWrite) -> Result<()> {
+        match write {
+            BufferedWrite::SaveOpportunity(opportunity) => self.insert_opportunity(opportunity).await,
+            BufferedWrite::UpdateOpportunityStatus(opportunity) => self.set_opportunity_status(opportunity).await,
+            BufferedWrite::SaveStrategy(strategy) => self.upsert_strategy(strategy).await,
+            BufferedWrite::DeleteStrategy(strategy_id) => self.remove_strategy(strategy_id).await,
+            BufferedWrite::SaveExecution(execution) => self.upsert_execution(execution).await,
+            BufferedWrite::SavePoolSnapshot(snapshot) => self.insert_pool_snapshot(snapshot).await,
+        }
+    }
+
+    /// Probe a lost database and, once it answers, replay buffered writes oldest first.
+    ///
+    /// Writes the database rejects are dropped with a warning; losing it again stops the pass
+    /// with the rest still buffered.
+    pub async fn recover(&self) -> Result<Recovery> {
+        if !self.is_degraded() {
+            return Ok(Recovery { recovered: true, ..Recovery::default() });
+        }
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+
+        let mut replayed = 0;
+        loop {
+            let records = {
+                let mut buffer = self.lock_buffer();
+                let records = buffer.journal.as_ref().map(Journal::records).unwrap_or_default();
+                if records.is_empty() {
+                    buffer.degraded = false;
+                    info!("Database is back; replayed {} buffered writes", replayed);
+                    return Ok(Recovery { replayed, backlog: 0, recovered: true });
+                }
+                records
+            };
+            for (seq, payload) in records {
+                match serde_json::from_slice::<BufferedWrite>(&payload) {
+                    Ok(write) => match self.apply(&write).await {
+                        Ok(()) => replayed += 1,
+                        Err(e) if is_connection_loss(&e) => {
+                            warn!("Database lost again while replaying buffered writes: {}", e);
+                            return Ok(Recovery { replayed, backlog: self.backlog(), recovered: false });
+                        }
+                        Err(e) => warn!("Dropping buffered write the database rejected: {}", e),
+                    },
+                    Err(e) => warn!("Dropping unreadable buffered write: {}", e),
+                }
+                if let Some(journal) = self.lock_buffer().journal.as_mut() {
+                    journal.discard_through(seq);
+                }
+            }
+        }
+    }
+
+    pub async fn save_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        self.write(self.insert_opportunity(opportunity), || BufferedWrite::SaveOpportunity(Box::new(opportunity.clone()))).await
+    }
+
+    pub async fn update_opportunity_status(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        self.write(self.set_opportunity_status(opportunity), || BufferedWrite::UpdateOpportunityStatus(Box::new(opportunity.clone())))
+            .await
+    }
+
+    pub async fn save_strategy(&self, strategy: &ArbitrageStrategy) -> Result<()> {
+        self.write(self.upsert_strategy(strategy), || BufferedWrite::SaveStrategy(strategy.clone())).await
+    }
+
+    pub async fn update_strategy(&self, strategy: &ArbitrageStrategy) -> Result<()> {
+        self.save_strategy(strategy).await
+    }
+
+    pub async fn delete_strategy(&self, strategy_id: &str) -> Result<()> {
+        self.write(self.remove_strategy(strategy_id), || BufferedWrite::DeleteStrategy(strategy_id.to_string())).await
+    }
+
+    pub async fn save_execution(&self, execution: &ArbitrageExecution) -> Result<()> {
+        self.write(self.upsert_execution(execution), || BufferedWrite::SaveExecution(Box::new(execution.clone()))).await
+    }
+
+    pub async fn save_pool_snapshot(&self, snapshot: &PoolSnapshot) -> Result<()> {
+        self.write(self.insert_pool_snapshot(snapshot), || BufferedWrite::SavePoolSnapshot(snapshot.clone())).await
+    }
+
+    async fn insert_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        self.insert_opportunity_row(
+            opportunity,
+            "DO UPDATE SET status = excluded.status, net_profit = excluded.net_profit, data = excluded.data",
+        )
+        .await?;
         Ok(())
     }
 
-    pub async fn save_strategy(&self, _strategy: &ArbitrageStrategy) -> Result<()> {
-        // TODO: Implement with actual database
+    /// Insert with the given ON CONFLICT action, returning the rows written
+    async fn insert_opportunity_row(&self, opportunity: &ArbitrageOpportunity, on_conflict: &str) -> Result<u64> {
+        let sql = format!(
+            "INSERT INTO opportunities (id, status, created_at, net_profit, data) VALUES ($1, $2, $3, $4, $5) \
+             ON CONFLICT (id) {}",
+            on_conflict
+        );
+        let result = sqlx::query(&sql)
+            .bind(&opportunity.id)
+            .bind(status_label(&opportunity.status)?)
+            .bind(opportunity.timestamp.timestamp_millis())
+            .bind(opportunity.net_profit.to_string())
+            .bind(serde_json::to_string(opportunity)?)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn set_opportunity_status(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        sqlx::query("UPDATE opportunities SET status = $1, data = $2 WHERE id = $3")
+            .bind(status_label(&opportunity.status)?)
+            .bind(serde_json::to_string(opportunity)?)
+            .bind(&opportunity.id)
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
-    pub async fn update_strategy(&self, _strategy: &ArbitrageStrategy) -> Result<()> {
-        // TODO: Implement with actual database
+    async fn upsert_strategy(&self, strategy: &ArbitrageStrategy) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO strategies (id, name, data) VALUES ($1, $2, $3) \
+             ON CONFLICT (id) DO UPDATE SET name = excluded.name, data = excluded.data",
+        )
+        .bind(&strategy.id)
+        .bind(&strategy.name)
+        .bind(serde_json::to_string(strategy)?)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
-    pub async fn delete_strategy(&self, _strategy_id: &str) -> Result<()> {
-        // TODO: Implement with actual database
+    async fn remove_strategy(&self, strategy_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM strategies WHERE id = $1")
+            .bind(strategy_id)
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
     pub async fn get_strategies(&self) -> Result<Vec<ArbitrageStrategy>> {
-        // TODO: Implement with actual database
-        Ok(Vec::new())
+        let rows = sqlx::query("SELECT data FROM strategies ORDER BY name")
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(decode).collect()
+    }
+
+    async fn upsert_execution(&self, execution: &ArbitrageExecution) -> Result<()> {
+        self.insert_execution_row(
+            execution,
+            "DO UPDATE SET status = excluded.status, actual_profit = excluded.actual_profit, \
+             total_cost = excluded.total_cost, data = excluded.data, strategy = excluded.strategy, \
+             profit_value = excluded.profit_value, cost_value = excluded.cost_value",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Insert with the given ON CONFLICT action, returning the rows written
+    async fn insert_execution_row(&self, execution: &ArbitrageExecution, on_conflict: &str) -> Result<u64> {
+        let sql = format!(
+            "INSERT INTO executions (id, opportunity_id, status, executed_at, actual_profit, total_cost, data, \
+             pair, strategy, profit_value, cost_value) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) ON CONFLICT (id) {}",
+            on_conflict
+        );
+        let result = sqlx::query(&sql)
+            .bind(&execution.id)
+            .bind(&execution.opportunity.id)
+            .bind(status_label(&execution.execution_status)?)
+            .bind(execution.execution_time.timestamp_millis())
+            .bind(execution.actual_profit.map(|p| p.to_string()))
+            .bind(execution.total_cost.map(|c| c.to_string()))
+            .bind(serde_json::to_string(execution)?)
+            .bind(execution.opportunity.pair())
+            .bind(execution.tags.get("strategy").cloned())
+            .bind(execution.actual_profit.and_then(|p| p.to_f64()))
+            .bind(execution.total_cost.and_then(|c| c.to_f64()))
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() > 0 {
+            index_tags(&self.pool, execution).await?;
+        }
+        Ok(result.rows_affected())
+    }
+
+    /// Insert an opportunity unless one with its id is already stored; true when it was inserted
+    pub async fn import_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<bool> {
+        Ok(self.insert_opportunity_row(opportunity, "DO NOTHING").await? > 0)
     }
 
-    pub async fn save_execution(&self, _execution: &ArbitrageExecution) -> Result<()> {
-        // TODO: Implement with actual database
+    /// Insert an execution unless one with its id is already stored; true when it was inserted
+    pub async fn import_execution(&self, execution: &ArbitrageExecution) -> Result<bool> {
+        Ok(self.insert_execution_row(execution, "DO NOTHING").await? > 0)
+    }
+
+    /// Apply a write recovered from another run's buffer. Applying the same writes again leaves
+    /// the same state; snapshots already stored are skipped, and false is returned for them.
+    pub async fn import_write(&self, write: &BufferedWrite) -> Result<bool> {
+        if let BufferedWrite::SavePoolSnapshot(snapshot) = write {
+            let existing = sqlx::query("SELECT 1 FROM pool_snapshots WHERE pool_address = $1 AND captured_at = $2")
+                .bind(&snapshot.pool_address)
+                .bind(snapshot.timestamp.timestamp_millis())
+                .fetch_optional(&self.pool)
+                .await?;
+            if existing.is_some() {
+                return Ok(false);
+            }
+        }
+        self.apply(write).await?;
+        Ok(true)
+    }
+
+    pub async fn get_opportunities_by_status(&self, status: OpportunityStatus) -> Result<Vec<ArbitrageOpportunity>> {
+        let rows = sqlx::query("SELECT data FROM opportunities WHERE status = $1 ORDER BY created_at DESC")
+            .bind(status_label(&status)?)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(decode).collect()
+    }
+
+    pub async fn get_executions_by_status(&self, status: ExecutionStatus) -> Result<Vec<ArbitrageExecution>> {
+        let rows = sqlx::query("SELECT data FROM executions WHERE status = $1 ORDER BY executed_at DESC")
+            .bind(status_label(&status)?)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(decode).collect()
+    }
+
+    async fn insert_pool_snapshot(&self, snapshot: &PoolSnapshot) -> Result<()> {
+        sqlx::query("INSERT INTO pool_snapshots (pool_address, captured_at, data) VALUES ($1, $2, $3)")
+            .bind(&snapshot.pool_address)
+            .bind(snapshot.timestamp.timestamp_millis())
+            .bind(serde_json::to_string(snapshot)?)
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
-    pub async fn get_opportunities_by_status(&self, _status: OpportunityStatus) -> Result<Vec<ArbitrageOpportunity>> {
-        // TODO: Implement with actual database
-        Ok(Vec::new())
+    /// Snapshots captured in `[from, to)`, oldest first
+    pub async fn get_pool_snapshots(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<PoolSnapshot>> {
+        let rows = sqlx::query(
+            "SELECT data FROM pool_snapshots WHERE captured_at >= $1 AND captured_at < $2 ORDER BY captured_at",
+        )
+        .bind(from.timestamp_millis())
+        .bind(to.timestamp_millis())
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(decode).collect()
+    }
+
+    /// Capture time of the oldest stored snapshot
+    pub async fn oldest_pool_snapshot(&self) -> Result<Option<DateTime<Utc>>> {
+        let oldest: Option<i64> = sqlx::query("SELECT MIN(captured_at) AS oldest FROM pool_snapshots")
+            .fetch_one(&self.pool)
+            .await?
+            .try_get("oldest")?;
+        Ok(oldest.and_then(DateTime::from_timestamp_millis))
     }
 
-    pub async fn get_executions_by_status(&self, _status: ExecutionStatus) -> Result<Vec<ArbitrageExecution>> {
-        // TODO: Implement with actual database
-        Ok(Vec::new())
+    /// Remove snapshots captured in `[from, to)`, returning how many went
+    pub async fn delete_pool_snapshots(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM pool_snapshots WHERE captured_at >= $1 AND captured_at < $2")
+            .bind(from.timestamp_millis())
+            .bind(to.timestamp_millis())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
     }
 
-    pub async fn get_execution_stats(&self, _days: i64) -> Result<(u64, Decimal, Decimal)> {
-        // TODO: Implement with actual database
-        Ok((0, Decimal::ZERO, Decimal::ZERO))
+    /// The latest snapshot of a pool captured at or before `at`
+    pub async fn get_pool_snapshot_at(&self, pool_address: &str, at: DateTime<Utc>) -> Result<Option<PoolSnapshot>> {
+        let row = sqlx::query(
+            "SELECT data FROM pool_snapshots WHERE pool_address = $1 AND captured_at <= $2 \
+             ORDER BY captured_at DESC LIMIT 1",
+        )
+        .bind(pool_address)
+        .bind(at.timestamp_millis())
+        .fetch_optional(&self.pool)
+        .await?;
+        row.as_ref().map(decode).transpose()
     }
-}
\ No newline at end of file
+
+    /// The pool as the bot last saw it at `at`, rebuilt from the latest snapshot before then.
+    /// TVL is counted in token_b, with token_a valued at the pool's own price.
+    pub async fn get_pool_state_at(&self, pool_address: &str, at: DateTime<Utc>) -> Result<Option<PoolState>> {
+        let Some(snapshot) = self.get_pool_snapshot_at(pool_address, at).await? else {
+            return Ok(None);
+        };
+        let pool = snapshot.to_pool()?;
+        let reserve_a_value = pool.get_price(&pool.token_b).map_or(Decimal::ZERO, |price| pool.reserve_a * price);
+        Ok(Some(PoolState {
+            current_price: pool.get_price(&pool.token_a).unwrap_or(Decimal::ZERO),
+            price_impact: Decimal::ZERO,
+            volume_24h: Decimal::ZERO,
+            tvl: pool.reserve_b + reserve_a_value,
+            apy: None,
+            pool,
+        }))
+    }
+
+    /// One page of matching executions, newest first
+    pub async fn query_executions(&self, query: &ExecutionQuery, limit: usize, offset: usize) -> Result<Vec<ArbitrageExecution>> {
+        let (filter, params) = query.filter()?;
+        let sql = format!(
+            "SELECT data FROM executions{} ORDER BY executed_at DESC, id LIMIT {} OFFSET {}",
+            filter, limit, offset
+        );
+        let rows = bind_all(sqlx::query(&sql), params).fetch_all(&self.pool).await?;
+        rows.iter().map(decode).collect()
+    }
+
+    /// Realized profit less costs of each matching execution, oldest first, without decoding the records
+    pub async fn execution_outcomes(&self, query: &ExecutionQuery) -> Result<Vec<Decimal>> {
+        let (filter, params) = query.filter()?;
+        let sql = format!("SELECT actual_profit, total_cost FROM executions{} ORDER BY executed_at, id", filter);
+        let rows = bind_all(sqlx::query(&sql), params).fetch_all(&self.pool).await?;
+        rows.iter()
+            .map(|row| Ok(decimal_column(row, "actual_profit")? - decimal_column(row, "total_cost")?))
+            .collect()
+    }
+
+    /// Counts and sums over matching executions, grouped as asked, largest count first
+    pub async fn aggregate_executions(&self, query: &ExecutionQuery, by: AggregateBy) -> Result<Vec<ExecutionAggregate>> {
+        let (filter, params) = query.filter()?;
+        let key = match by {
+            AggregateBy::Total => None,
+            AggregateBy::Pair => Some("pair"),
+            AggregateBy::Strategy => Some("strategy"),
+        };
+        let count_status = |status: ExecutionStatus| -> Result<String> {
+            Ok(format!("COALESCE(SUM(CASE WHEN status = '{}' THEN 1 ELSE 0 END), 0)", status_label(&status)?))
+        };
+        let mut sql = format!(
+            "SELECT {}COUNT(*) AS executions, {} AS confirmed, {} AS failed, \
+             COALESCE(SUM(profit_value), 0.0) AS total_profit, COALESCE(SUM(cost_value), 0.0) AS total_cost \
+             FROM executions{}",
+            key.map(|column| format!("{} AS grouped, ", column)).unwrap_or_default(),
+            count_status(ExecutionStatus::Confirmed)?,
+            count_status(ExecutionStatus::Failed)?,
+            filter
+        );
+        if let Some(column) = key {
+            sql.push_str(&format!(" GROUP BY {} ORDER BY executions DESC, {}", column, column));
+        }
+
+        let rows = bind_all(sqlx::query(&sql), params).fetch_all(&self.pool).await?;
+        let amount = |row: &AnyRow, column: &str| -> Result<Decimal> {
+            let value: f64 = row.try_get(column)?;
+            Decimal::from_f64(value).with_context(|| format!("Sum out of range in {}: {}", column, value))
+        };
+        rows.iter()
+            .map(|row| {
+                Ok(ExecutionAggregate {
+                    key: match key {
+                        Some(_) => row.try_get("grouped")?,
+                        None => None,
+                    },
+                    executions: row.try_get::<i64, _>("executions")? as u64,
+                    confirmed: row.try_get::<i64, _>("confirmed")? as u64,
+                    failed: row.try_get::<i64, _>("failed")? as u64,
+                    total_profit: amount(row, "total_profit")?,
+                    total_cost: amount(row, "total_cost")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Executions in the last `days` days: count, total actual profit and total cost.
+    /// Amounts are stored as exact decimal text, so they are summed here rather than in SQL.
+    pub async fn get_execution_stats(&self, days: i64) -> Result<(u64, Decimal, Decimal)> {
+        let since = (Utc::now() - ChronoDuration::days(days)).timestamp_millis();
+        let rows = sqlx::query("SELECT actual_profit, total_cost FROM executions WHERE executed_at >= $1")
+            .bind(since)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let (mut profit, mut cost) = (Decimal::ZERO, Decimal::ZERO);
+        for row in &rows {
+            profit += decimal_column(row, "actual_profit")?;
+            cost += decimal_column(row, "total_cost")?;
+        }
+        Ok((rows.len() as u64, profit, cost))
+    }
+}
+
+/// Periodically tries to bring a lost database back, publishing the backlog as it goes
+pub struct RecoveryWorker {
+    database: Arc<DatabaseService>,
+    metrics: MetricsRegistry,
+    interval: Duration,
+}
+
+impl RecoveryWorker {
+    pub fn new(database: Arc<DatabaseService>, metrics: MetricsRegistry, interval: Duration) -> Self {
+        Self { database, metrics, interval }
+    }
+
+    /// Start the worker loop
+    pub async fn start(self) -> Result<()> {
+        let mut interval = tokio::time::interval(self.interval);
+        let mut down_since: Option<std::time::Instant> = None;
+
+        loop {
+            interval.tick().await;
+
+            if self.database.is_degraded() {
+                let since = *down_since.get_or_insert_with(std::time::Instant::now);
+                match self.database.recover().await {
+                    Ok(recovery) => {
+                        self.metrics.increment("db_replayed_writes", recovery.replayed as f64).await;
+                        if recovery.recovered {
+                            self.metrics.increment("db_recoveries", 1.0).await;
+                            self.metrics.set("db_last_outage_seconds", since.elapsed().as_secs_f64()).await;
+                            down_since = None;
+                        }
+                    }
+                    Err(e) => warn!("Database still unreachable: {}", e),
+                }
+            }
+            self.metrics.set("db_degraded", if self.database.is_degraded() { 1.0 } else { 0.0 }).await;
+            self.metrics.set("db_backlog", self.database.backlog() as f64).await;
+            self.metrics.set("db_dropped_writes", self.database.dropped_writes() as f64).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fixtures::opportunity;
+
+    #[tokio::test]
+    async fn test_round_trip_and_stats() {
+        // One connection, since every in-memory SQLite connection is a separate database
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            timeout_seconds: 5,
+            buffer_path: String::new(),
+            buffer_capacity_mb: 1,
+            recovery_interval_seconds: 1,
+            capture_pool_snapshots: false,
+        };
+        let db = DatabaseService::new(&config).await.unwrap();
+
+        let mut opportunity = opportunity();
+        db.save_opportunity(&opportunity).await.unwrap();
+        opportunity.update_status(OpportunityStatus::Completed);
+        db.update_opportunity_status(&opportunity).await.unwrap();
+        let completed = db.get_opportunities_by_status(OpportunityStatus::Completed).await.unwrap();
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].id, opportunity.id);
+        assert!(db.get_opportunities_by_status(OpportunityStatus::Pending).await.unwrap().is_empty());
+
+        let mut strategy = crate::arbitrage::ArbitrageEngine::default_strategy(&crate::config::AppConfig::bundled_default().arbitrage);
+        db.save_strategy(&strategy).await.unwrap();
+        strategy.name = "renamed".to_string();
+        db.update_strategy(&strategy).await.unwrap();
+        assert_eq!(db.get_strategies().await.unwrap()[0].name, "renamed");
+        db.delete_strategy(&strategy.id).await.unwrap();
+        assert!(db.get_strategies().await.unwrap().is_empty());
+
+        for profit in [Decimal::new(125, 2), Decimal::new(-25, 2)] {
+            let mut execution = ArbitrageExecution::new(opportunity.clone());
+            execution.execution_status = ExecutionStatus::Confirmed;
+            execution.actual_profit = Some(profit);
+            execution.total_cost = Some(Decimal::new(1, 2));
+            db.save_execution(&execution).await.unwrap();
+        }
+        assert_eq!(db.get_executions_by_status(ExecutionStatus::Confirmed).await.unwrap().len(), 2);
+        assert_eq!(
+            db.get_execution_stats(1).await.unwrap(),
+            (2, Decimal::new(100, 2), Decimal::new(2, 2))
+        );
+
+        let captured = Utc::now();
+        let snapshot = PoolSnapshot::from_pool(&opportunity.buy_pool, captured);
+        db.save_pool_snapshot(&snapshot).await.unwrap();
+        let window = db.get_pool_snapshots(captured - ChronoDuration::seconds(1), captured + ChronoDuration::seconds(1)).await;
+        assert_eq!(window.unwrap().len(), 1);
+        assert!(db.get_pool_snapshots(captured + ChronoDuration::seconds(1), Utc::now() + ChronoDuration::days(1)).await.unwrap().is_empty());
+
+        // The state at a moment is the latest snapshot before it
+        let mut moved = opportunity.buy_pool.clone().update_reserves(Decimal::from(2000), Decimal::from(10));
+        moved.last_updated = captured + ChronoDuration::seconds(10);
+        db.save_pool_snapshot(&PoolSnapshot::from_pool(&moved, moved.last_updated)).await.unwrap();
+        let address = snapshot.pool_address.as_str();
+        assert!(db.get_pool_state_at(address, captured - ChronoDuration::seconds(1)).await.unwrap().is_none());
+        let before = db.get_pool_state_at(address, captured + ChronoDuration::seconds(9)).await.unwrap().unwrap();
+        assert_eq!(before.pool.reserve_a, opportunity.buy_pool.reserve_a);
+        let after = db.get_pool_state_at(address, captured + ChronoDuration::seconds(10)).await.unwrap().unwrap();
+        assert_eq!((after.pool.reserve_a, after.current_price), (Decimal::from(2000), Decimal::from(200)));
+        // 2,000 SOL against 10 USDC is worth 10 USDC a side
+        assert_eq!(after.tvl, Decimal::from(20));
+
+        // A pool with an empty side holds only what is left
+        let mut drained = moved.clone().update_reserves(Decimal::ZERO, Decimal::from(10));
+        drained.last_updated = captured + ChronoDuration::seconds(20);
+        db.save_pool_snapshot(&PoolSnapshot::from_pool(&drained, drained.last_updated)).await.unwrap();
+        let drained = db.get_pool_state_at(address, captured + ChronoDuration::seconds(20)).await.unwrap().unwrap();
+        assert_eq!(drained.tvl, Decimal::from(10));
+    }
+
+    #[tokio::test]
+    async fn test_execution_queries_filter_page_and_aggregate() {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            timeout_seconds: 5,
+            buffer_path: String::new(),
+            buffer_capacity_mb: 1,
+            recovery_interval_seconds: 1,
+            capture_pool_snapshots: false,
+        };
+        let db = DatabaseService::new(&config).await.unwrap();
+        let start = Utc::now() - ChronoDuration::hours(10);
+        // One execution an hour, alternating strategies; every third one fails
+        for hour in 0..10 {
+            let mut execution = ArbitrageExecution::new(opportunity());
+            execution.execution_time = start + ChronoDuration::hours(hour);
+            execution.tags.insert("strategy".to_string(), if hour % 2 == 0 { "even" } else { "odd" }.to_string());
+            execution.tags.insert("experiment".to_string(), if hour < 4 { "a" } else { "b" }.to_string());
+            execution.execution_status = if hour % 3 == 0 { ExecutionStatus::Failed } else { ExecutionStatus::Confirmed };
+            execution.actual_profit = Some(Decimal::new(hour * 10, 2));
+            execution.total_cost = Some(Decimal::new(5, 2));
+            db.save_execution(&execution).await.unwrap();
+        }
+
+        let everything = ExecutionQuery::default();
+        let page = db.query_executions(&everything, 4, 4).await.unwrap();
+        assert_eq!(page.len(), 4);
+        // Newest first: hours 5 down to 2
+        assert_eq!(page[0].execution_time, start + ChronoDuration::hours(5));
+        assert!(db.query_executions(&everything, 4, 10).await.unwrap().is_empty());
+
+        let window = ExecutionQuery::between(start + ChronoDuration::hours(2), start + ChronoDuration::hours(8));
+        let odd = window.clone().with_strategies(vec!["odd".to_string()]).with_pair("SOL/USDC");
+        let total = db.aggregate_executions(&odd, AggregateBy::Total).await.unwrap();
+        // Hours 3, 5 and 7
+        assert_eq!(
+            total,
+            vec![ExecutionAggregate {
+                key: None,
+                executions: 3,
+                confirmed: 2,
+                failed: 1,
+                total_profit: Decimal::new(150, 2),
+                total_cost: Decimal::new(15, 2),
+            }]
+        );
+        assert_eq!(total[0].net_profit(), Decimal::new(135, 2));
+        assert_eq!(
+            db.execution_outcomes(&odd).await.unwrap(),
+            vec![Decimal::new(25, 2), Decimal::new(45, 2), Decimal::new(65, 2)]
+        );
+
+        let by_strategy = db.aggregate_executions(&everything, AggregateBy::Strategy).await.unwrap();
+        let keys: Vec<_> = by_strategy.iter().map(|a| (a.key.as_deref(), a.executions)).collect();
+        assert_eq!(keys, vec![(Some("even"), 5), (Some("odd"), 5)]);
+        let failed = window.with_statuses(vec![ExecutionStatus::Failed]);
+        assert_eq!(db.aggregate_executions(&failed, AggregateBy::Pair).await.unwrap()[0].executions, 2);
+        assert!(db.aggregate_executions(&everything.clone().with_pair("BONK/USDC"), AggregateBy::Pair).await.unwrap().is_empty());
+
+        // Tag filters all have to match, and follow a re-saved execution's tags
+        let tag = |key: &str, value: &str| (key.to_string(), value.to_string());
+        let experiment_a = everything.with_tags(vec![tag("experiment", "a")]);
+        let mut tagged = db.query_executions(&experiment_a, 10, 0).await.unwrap();
+        assert_eq!(tagged.len(), 4);
+        let odd_a = experiment_a.clone().with_tags(vec![tag("experiment", "a"), tag("strategy", "odd")]);
+        assert_eq!(db.aggregate_executions(&odd_a, AggregateBy::Total).await.unwrap()[0].executions, 2);
+        tagged[0].tags.insert("experiment".to_string(), "b".to_string());
+        db.save_execution(&tagged[0]).await.unwrap();
+        assert_eq!(db.query_executions(&experiment_a, 10, 0).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_writes_buffer_while_lost_and_replay_in_order() {
+        let path = std::env::temp_dir().join(format!("db-buffer-{}.journal", uuid::Uuid::new_v4()));
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            timeout_seconds: 5,
+            buffer_path: path.to_string_lossy().to_string(),
+            buffer_capacity_mb: 1,
+            recovery_interval_seconds: 1,
+            capture_pool_snapshots: false,
+        };
+        let db = DatabaseService::new(&config).await.unwrap();
+        assert!(is_connection_loss(&sqlx::Error::PoolTimedOut.into()));
+        assert!(!is_connection_loss(&sqlx::Error::RowNotFound.into()));
+
+        db.degrade(&anyhow::anyhow!("connection reset"));
+        let mut strategy = crate::arbitrage::ArbitrageEngine::default_strategy(&crate::config::AppConfig::bundled_default().arbitrage);
+        db.save_strategy(&strategy).await.unwrap();
+        strategy.name = "renamed".to_string();
+        db.update_strategy(&strategy).await.unwrap();
+        let mut execution = ArbitrageExecution::new(opportunity());
+        execution.execution_status = ExecutionStatus::Confirmed;
+        db.save_execution(&execution).await.unwrap();
+        // Nothing reached the database yet
+        assert_eq!(db.backlog(), 3);
+        assert!(db.get_strategies().await.unwrap().is_empty());
+
+        let recovery = db.recover().await.unwrap();
+        assert_eq!(recovery, Recovery { replayed: 3, backlog: 0, recovered: true });
+        assert!(!db.is_degraded());
+        // Replayed in order, so the rename wins
+        assert_eq!(db.get_strategies().await.unwrap()[0].name, "renamed");
+        assert_eq!(db.get_executions_by_status(ExecutionStatus::Confirmed).await.unwrap().len(), 1);
+        assert_eq!(db.dropped_writes(), 0);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_migration_status_reads_without_migrating() {
+        let path = std::env::temp_dir().join(format!("db-status-{}.sqlite", uuid::Uuid::new_v4()));
+        std::fs::File::create(&path).unwrap();
+        let config = DatabaseConfig {
+            url: format!("sqlite://{}", path.display()),
+            max_connections: 1,
+            timeout_seconds: 5,
+            buffer_path: String::new(),
+            buffer_capacity_mb: 1,
+            recovery_interval_seconds: 1,
+            capture_pool_snapshots: false,
+        };
+        assert_eq!(
+            DatabaseService::migration_status(&config).await.unwrap(),
+            MigrationStatus {
+                applied: 0,
+                pending: vec![
+                    "20240101000000 initial".to_string(),
+                    "20240201000000 pool snapshots".to_string(),
+                    "20240301000000 execution queries".to_string(),
+                    "20240401000000 execution tags".to_string(),
+                ],
+            }
+        );
+
+        let db = DatabaseService::new(&config).await.unwrap();
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = 20240301000000").execute(&db.pool).await.unwrap();
+        drop(db);
+        // Asking twice shows the status check applied nothing
+        for _ in 0..2 {
+            let status = DatabaseService::migration_status(&config).await.unwrap();
+            assert_eq!(status.applied, 3);
+            assert_eq!(status.pending, vec!["20240301000000 execution queries".to_string()]);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::config::DeadManConfig;
+use crate::services::alert_rules::{AlertAction, FiredAlert};
+use crate::utils::panic_guard::lock_or_recover;
+
+#[derive(Debug)]
+struct Inbound {
+    last_beat: DateTime<Utc>,
+    tripped: bool,
+}
+
+/// Pauses execution when the operator's heartbeats stop, and resumes when they come back.
+///
+/// The clock starts at creation, so the operator gets one full timeout to send the first beat.
+#[derive(Debug)]
+pub struct DeadManSwitch {
+    /// None when inbound heartbeats are not required
+    timeout: Option<Duration>,
+    channel: String,
+    inbound: Mutex<Inbound>,
+}
+
+impl DeadManSwitch {
+    pub fn new(config: &DeadManConfig, now: DateTime<Utc>) -> Self {
+        Self {
+            timeout: (config.enabled && config.require_inbound)
+                .then(|| Duration::seconds(config.inbound_timeout_seconds.max(1) as i64)),
+            channel: config.alert_channel.clone(),
+            inbound: Mutex::new(Inbound { last_beat: now, tripped: false }),
+        }
+    }
+
+    /// Record an inbound heartbeat
+    pub fn beat(&self, now: DateTime<Utc>) {
+        lock_or_recover(&self.inbound, "dead man switch").last_beat = now;
+    }
+
+    pub fn last_beat(&self) -> DateTime<Utc> {
+        lock_or_recover(&self.inbound, "dead man switch").last_beat
+    }
+
+    fn alert(&self, action: AlertAction, message: String, silent: Duration, now: DateTime<Utc>) -> FiredAlert {
+        FiredAlert {
+            rule_name: "dead_man".to_string(),
+            action,
+            message,
+            value: silent.num_seconds() as f64,
+            timestamp: now,
+        }
+    }
+
+    /// A notice and a pause once heartbeats have been missing for the timeout, a notice and a
+    /// resume on the first beat after that
+    pub fn evaluate(&self, now: DateTime<Utc>) -> Vec<FiredAlert> {
+        let Some(timeout) = self.timeout else {
+            return Vec::new();
+        };
+        let mut inbound = lock_or_recover(&self.inbound, "dead man switch");
+        let silent = now - inbound.last_beat;
+        let notify = AlertAction::Notify(self.channel.clone());
+
+        if silent >= timeout && !inbound.tripped {
+            inbound.tripped = true;
+            let message = format!("No operator heartbeat for {}s; pausing execution", silent.num_seconds());
+            vec![
+                self.alert(notify, message.clone(), silent, now),
+                self.alert(AlertAction::Pause, message, silent, now),
+            ]
+        } else if silent < timeout && inbound.tripped {
+            inbound.tripped = false;
+            let message = "Operator heartbeat is back; resuming execution".to_string();
+            vec![
+                self.alert(notify, message.clone(), silent, now),
+                self.alert(AlertAction::Resume, message, silent, now),
+            ]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Pings the external monitor and checks the switch every interval
+pub struct DeadManWorker {
+    switch: Arc<DeadManSwitch>,
+    client: reqwest::Client,
+    ping_url: Option<String>,
+    alert_sender: mpsc::Sender<FiredAlert>,
+    interval: std::time::Duration,
+}
+
+impl DeadManWorker {
+    pub fn new(config: &DeadManConfig, switch: Arc<DeadManSwitch>, alert_sender: mpsc::Sender<FiredAlert>) -> Result<Self> {
+        let interval = std::time::Duration::from_secs(config.interval_seconds.max(1));
+        Ok(Self {
+            switch,
+            // A ping slower than the interval is as good as missed
+            client: reqwest::Client::builder().timeout(interval).build()?,
+            ping_url: (!config.ping_url.is_empty()).then(|| config.ping_url.clone()),
+            alert_sender,
+            interval,
+        })
+    }
+
+    async fn ping(&self, url: &str) {
+        match self.client.get(url).send().await {
+            Ok(response) if response.status().is_success() => debug!("Heartbeat sent to {}", url),
+            Ok(response) => warn!("Heartbeat to {} answered HTTP {}", url, response.status()),
+            Err(e) => warn!("Failed to send heartbeat to {}: {}", url, e),
+        }
+    }
+
+    /// Start the worker loop
+    pub async fn start(self) -> Result<()> {
+        info!(
+            "Starting dead man switch (outbound: {}, inbound: {})",
+            self.ping_url.is_some(),
+            self.switch.timeout.is_some()
+        );
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Some(url) = &self.ping_url {
+                self.ping(url).await;
+            }
+            for alert in self.switch.evaluate(Utc::now()) {
+                if let Err(e) = self.alert_sender.send(alert).await {
+                    warn!("Failed to dispatch dead man alert: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_heartbeats_pause_until_they_return() {
+        let config = DeadManConfig {
+            enabled: true,
+            require_inbound: true,
+            inbound_timeout_seconds: 60,
+            ..DeadManConfig::default()
+        };
+        let start = Utc::now();
+        let switch = DeadManSwitch::new(&config, start);
+        let actions = |alerts: Vec<FiredAlert>| alerts.into_iter().map(|a| a.action).collect::<Vec<_>>();
+        let notify = AlertAction::Notify("ops".to_string());
+
+        // Startup counts as a beat
+        assert!(switch.evaluate(start + Duration::seconds(59)).is_empty());
+        assert_eq!(actions(switch.evaluate(start + Duration::seconds(60))), vec![notify.clone(), AlertAction::Pause]);
+        // Tripped once, not on every check
+        assert!(switch.evaluate(start + Duration::seconds(120)).is_empty());
+
+        switch.beat(start + Duration::seconds(130));
+        assert_eq!(actions(switch.evaluate(start + Duration::seconds(131))), vec![notify, AlertAction::Resume]);
+        assert!(switch.evaluate(start + Duration::seconds(150)).is_empty());
+
+        // Without inbound heartbeats required the switch never trips
+        let outbound_only = DeadManSwitch::new(&DeadManConfig { enabled: true, ..DeadManConfig::default() }, start);
+        assert!(outbound_only.evaluate(start + Duration::days(1)).is_empty());
+    }
+}
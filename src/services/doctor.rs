@@ -0,0 +1,333 @@
+use chrono::Utc;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+
+use crate::{
+    config::AppConfig,
+    dex::{idl::IdlRegistry, DexInterface, DexType},
+    services::{
+        database::DatabaseService,
+        jito::{JitoConfig, JitoService},
+        solana::SolanaService,
+        wallet::Wallets,
+    },
+};
+
+/// Wallets below this balance cannot reliably pay fees
+const MIN_WALLET_LAMPORTS: u64 = 10_000_000;
+/// Clock skew against the cluster above which a check warns
+const CLOCK_SKEW_WARN_SECONDS: i64 = 5;
+/// Clock skew against the cluster above which a check fails
+const CLOCK_SKEW_FAIL_SECONDS: i64 = 30;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of a single self-test check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "PASS"),
+            CheckStatus::Warn => write!(f, "WARN"),
+            CheckStatus::Fail => write!(f, "FAIL"),
+            CheckStatus::Skip => write!(f, "SKIP"),
+        }
+    }
+}
+
+/// A named check result
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Results of a full self-test run
+#[derive(Debug, Clone, Default)]
+pub struct DoctorReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    pub fn failures(&self) -> usize {
+        self.checks.iter().filter(|c| c.status == CheckStatus::Fail).count()
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.failures() == 0
+    }
+
+    /// Render the results as a fixed-width pass/fail table
+    pub fn render_table(&self) -> String {
+        let width = self.checks.iter().map(|c| c.name.len()).max().unwrap_or(0).max("CHECK".len());
+        let mut table = format!("{:<width$}  {:<6}  {}\n", "CHECK", "STATUS", "DETAIL", width = width);
+        for check in &self.checks {
+            table.push_str(&format!(
+                "{:<width$}  {:<6}  {}\n",
+                check.name,
+                check.status.to_string(),
+                check.detail,
+                width = width
+            ));
+        }
+        table
+    }
+}
+
+/// Pre-flight checks run by `offchain-bot doctor` before going live
+pub struct Doctor<'a> {
+    config: &'a AppConfig,
+    dex_instances: &'a HashMap<DexType, Box<dyn DexInterface>>,
+}
+
+impl<'a> Doctor<'a> {
+    pub fn new(config: &'a AppConfig, dex_instances: &'a HashMap<DexType, Box<dyn DexInterface>>) -> Self {
+        Self { config, dex_instances }
+    }
+
+    /// Run every check; individual failures are recorded rather than returned
+    pub async fn run(&self) -> DoctorReport {
+        let mut report = DoctorReport::default();
+        report.checks.push(self.check_config());
+
+        let solana = SolanaService::new(&self.config.solana.rpc_url);
+        match &solana {
+            Ok(solana) => report.checks.push(Self::check_rpc(solana).await),
+            Err(e) => report.checks.push(CheckResult::new("rpc", CheckStatus::Fail, e.to_string())),
+        }
+
+        report.checks.push(self.check_websocket().await);
+        report.checks.extend(self.check_dexes().await);
+        report.checks.push(self.check_jito().await);
+
+        match &solana {
+            Ok(solana) => {
+                report.checks.extend(self.check_wallets(solana).await);
+                report.checks.push(Self::check_clock(solana).await);
+            }
+            Err(_) => {
+                report.checks.push(CheckResult::new("wallets", CheckStatus::Skip, "RPC unavailable"));
+                report.checks.push(CheckResult::new("clock skew", CheckStatus::Skip, "RPC unavailable"));
+            }
+        }
+
+        report.checks.push(self.check_idls(solana.as_ref().ok()).await);
+        report.checks.push(self.check_database().await);
+        report
+    }
+
+    fn check_config(&self) -> CheckResult {
+        match self.config.validate() {
+            Ok(()) => CheckResult::new("config", CheckStatus::Pass, format!("environment {}", self.config.environment)),
+            Err(e) => CheckResult::new("config", CheckStatus::Fail, e.to_string()),
+        }
+    }
+
+    async fn check_rpc(solana: &SolanaService) -> CheckResult {
+        let started = Instant::now();
+        match solana.get_network_info().await {
+            Ok(version) => CheckResult::new(
+                "rpc",
+                CheckStatus::Pass,
+                format!("solana-core {} ({}ms)", version.solana_core, started.elapsed().as_millis()),
+            ),
+            Err(e) => CheckResult::new("rpc", CheckStatus::Fail, e.to_string()),
+        }
+    }
+
+    async fn check_websocket(&self) -> CheckResult {
+        let url = match reqwest::Url::parse(&self.config.solana.ws_url) {
+            Ok(url) => url,
+            Err(e) => return CheckResult::new("websocket", CheckStatus::Fail, format!("invalid ws_url: {}", e)),
+        };
+        let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+            return CheckResult::new("websocket", CheckStatus::Fail, "ws_url has no host");
+        };
+
+        match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((host, port))).await {
+            Ok(Ok(_)) => CheckResult::new("websocket", CheckStatus::Pass, format!("{}:{} reachable", host, port)),
+            Ok(Err(e)) => CheckResult::new("websocket", CheckStatus::Fail, e.to_string()),
+            Err(_) => CheckResult::new("websocket", CheckStatus::Fail, "connect timed out"),
+        }
+    }
+
+    async fn check_dexes(&self) -> Vec<CheckResult> {
+        let mut results = Vec::new();
+        for dex_instance in self.dex_instances.values() {
+            let name = format!("dex {}", dex_instance.get_name());
+            let started = Instant::now();
+            let result = match tokio::time::timeout(CONNECT_TIMEOUT, dex_instance.is_connected()).await {
+                Ok(Ok(true)) => CheckResult::new(name, CheckStatus::Pass, format!("{}ms", started.elapsed().as_millis())),
+                Ok(Ok(false)) => CheckResult::new(name, CheckStatus::Fail, "health endpoint unavailable"),
+                Ok(Err(e)) => CheckResult::new(name, CheckStatus::Fail, e.to_string()),
+                Err(_) => CheckResult::new(name, CheckStatus::Fail, "timed out"),
+            };
+            results.push(result);
+        }
+        results
+    }
+
+    async fn check_jito(&self) -> CheckResult {
+        let jito_config = JitoConfig {
+            base_url: self.config.solana.jito_url.clone(),
+            auth_header: self.config.solana.jito_auth_header.clone(),
+            timeout: CONNECT_TIMEOUT,
+            ..Default::default()
+        };
+        let service = match JitoService::new(jito_config) {
+            Ok(service) => service,
+            Err(e) => return CheckResult::new("jito", CheckStatus::Fail, e.to_string()),
+        };
+
+        match service.health_check().await {
+            Ok(true) => CheckResult::new("jito", CheckStatus::Pass, self.config.solana.jito_url.clone()),
+            Ok(false) => CheckResult::new("jito", CheckStatus::Fail, format!("{} unreachable", self.config.solana.jito_url)),
+            Err(e) => CheckResult::new("jito", CheckStatus::Fail, e.to_string()),
+        }
+    }
+
+    async fn check_wallets(&self, solana: &SolanaService) -> Vec<CheckResult> {
+        let wallets = match Wallets::load(&self.config.wallet) {
+            Ok(wallets) => wallets,
+            Err(e) => return vec![CheckResult::new("wallet", CheckStatus::Fail, e.to_string())],
+        };
+
+        let mut accounts = vec![("wallet owner", wallets.owner().pubkey())];
+        if wallets.has_separate_fee_payer() {
+            accounts.push(("wallet fee payer", wallets.fee_payer().pubkey()));
+        }
+
+        let mut results = Vec::new();
+        for (name, pubkey) in accounts {
+            let result = match solana.get_balance(&pubkey).await {
+                Ok(lamports) => {
+                    let status = if lamports >= MIN_WALLET_LAMPORTS { CheckStatus::Pass } else { CheckStatus::Warn };
+                    CheckResult::new(name, status, format!("{} {:.4} SOL", pubkey, lamports as f64 / 1e9))
+                }
+                Err(e) => CheckResult::new(name, CheckStatus::Fail, e.to_string()),
+            };
+            results.push(result);
+        }
+        results
+    }
+
+    async fn check_clock(solana: &SolanaService) -> CheckResult {
+        let block_time = match solana.get_slot_info().await {
+            Ok(slot) => solana.get_block_time(slot).await,
+            Err(e) => Err(e),
+        };
+
+        match block_time {
+            Ok(block_time) => {
+                let skew = Utc::now().timestamp() - block_time;
+                CheckResult::new("clock skew", classify_skew(skew), format!("{}s vs cluster", skew))
+            }
+            Err(e) => CheckResult::new("clock skew", CheckStatus::Warn, format!("block time unavailable: {}", e)),
+        }
+    }
+
+    async fn check_idls(&self, solana: Option<&SolanaService>) -> CheckResult {
+        let idl = &self.config.idl;
+        let mut registry = match IdlRegistry::load_dir(&idl.dir) {
+            Ok(registry) => registry,
+            Err(e) => return CheckResult::new("idl", CheckStatus::Fail, e.to_string()),
+        };
+
+        let mut missing = Vec::new();
+        for program in &idl.programs {
+            let Ok(program_id) = Pubkey::from_str(program) else {
+                return CheckResult::new("idl", CheckStatus::Fail, format!("invalid program id {}", program));
+            };
+            if registry.get(&program_id).is_some() {
+                continue;
+            }
+            let fetched = match (idl.fetch_on_chain, solana) {
+                (true, Some(solana)) => registry.fetch(solana, &program_id).await.is_ok(),
+                _ => false,
+            };
+            if !fetched {
+                missing.push(program.clone());
+            }
+        }
+
+        if missing.is_empty() {
+            CheckResult::new("idl", CheckStatus::Pass, format!("{} IDL(s) available", registry.len()))
+        } else {
+            CheckResult::new("idl", CheckStatus::Warn, format!("no IDL for {}", missing.join(", ")))
+        }
+    }
+
+    async fn check_database(&self) -> CheckResult {
+        if !self.config.is_memory_store_enabled() {
+            return CheckResult::new("database", CheckStatus::Skip, "memory store disabled");
+        }
+
+        // Read-only: the doctor must not migrate the database or touch the live write buffer
+        match DatabaseService::migration_status(&self.config.database).await {
+            Ok(status) if status.pending.is_empty() => {
+                CheckResult::new("database", CheckStatus::Pass, format!("connected, {} migrations applied", status.applied))
+            }
+            Ok(status) => CheckResult::new(
+                "database",
+                CheckStatus::Warn,
+                format!("connected, {} migrations applied, pending: {}", status.applied, status.pending.join(", ")),
+            ),
+            Err(e) => CheckResult::new("database", CheckStatus::Warn, format!("{} (memory store fallback)", e)),
+        }
+    }
+}
+
+/// Classify local clock skew against the cluster block time
+fn classify_skew(skew_seconds: i64) -> CheckStatus {
+    match skew_seconds.abs() {
+        s if s > CLOCK_SKEW_FAIL_SECONDS => CheckStatus::Fail,
+        s if s > CLOCK_SKEW_WARN_SECONDS => CheckStatus::Warn,
+        _ => CheckStatus::Pass,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_table_and_skew() {
+        assert_eq!(classify_skew(2), CheckStatus::Pass);
+        assert_eq!(classify_skew(-10), CheckStatus::Warn);
+        assert_eq!(classify_skew(120), CheckStatus::Fail);
+
+        let report = DoctorReport {
+            checks: vec![
+                CheckResult::new("rpc", CheckStatus::Pass, "solana-core 2.0.0"),
+                CheckResult::new("clock skew", classify_skew(45), "45s vs cluster"),
+            ],
+        };
+        assert_eq!(report.failures(), 1);
+        assert!(!report.is_healthy());
+
+        let table = report.render_table();
+        assert!(table.starts_with("CHECK       STATUS"));
+        assert!(table.contains("clock skew  FAIL    45s vs cluster"));
+    }
+}
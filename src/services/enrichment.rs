@@ -0,0 +1,412 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::config::EnrichmentConfig;
+use crate::services::token_registry::{TokenInfo, TokenSource};
+
+/// Assets per DAS `getAssetBatch` request
+const MAX_ASSETS_PER_REQUEST: usize = 1000;
+
+/// Prefix of the execution tags holding each hop's decoded fill, `fill_0`, `fill_1`, ...
+pub const FILL_TAG_PREFIX: &str = "fill_";
+
+/// Decoded swap leg from an enhanced transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapEvent {
+    pub signature: String,
+    pub source: Option<String>,
+    pub input_mint: String,
+    pub input_amount: Decimal,
+    pub output_mint: String,
+    pub output_amount: Decimal,
+    /// Output received per unit of input
+    pub price: Decimal,
+}
+
+impl SwapEvent {
+    /// Tag value recording the fill, e.g. `1.5 <mint> -> 2 <mint> @ 1.3333`
+    pub fn fill_tag(&self) -> String {
+        format!(
+            "{} {} -> {} {} @ {}",
+            self.input_amount.normalize(),
+            self.input_mint,
+            self.output_amount.normalize(),
+            self.output_mint,
+            self.price.round_dp(9).normalize()
+        )
+    }
+}
+
+/// One side of a swap: what an account sent or received
+struct Transfer {
+    account: Option<String>,
+    mint: String,
+    amount: Decimal,
+}
+
+impl Transfer {
+    /// Token transfers of an enhanced transaction in UI units, owned by `account_key`; both the
+    /// top-level (`rawTokenAmount`) and the inner swap (`tokenAmount`) shapes
+    fn list(items: &Value, account_key: &str) -> Vec<Self> {
+        items
+            .as_array()
+            .map(|items| items.iter().filter_map(|item| Self::parse(item, account_key)).collect())
+            .unwrap_or_default()
+    }
+
+    fn parse(item: &Value, account_key: &str) -> Option<Self> {
+        let mint = item["mint"].as_str()?.to_string();
+        let raw = &item["rawTokenAmount"];
+        let amount = match raw["tokenAmount"].as_str() {
+            Some(amount) => {
+                let decimals = raw["decimals"].as_u64().unwrap_or(0) as u32;
+                amount.parse::<Decimal>().ok()? / Decimal::from(10u64.pow(decimals))
+            }
+            None => Decimal::try_from(item["tokenAmount"].as_f64()?).ok()?,
+        };
+        let account = item[account_key].as_str().map(|s| s.to_string());
+        Some(Self { account, mint, amount })
+    }
+}
+
+/// Enrichment provider selected in config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnrichmentProvider {
+    None,
+    Helius,
+    Triton,
+}
+
+impl EnrichmentProvider {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "" | "none" => Some(EnrichmentProvider::None),
+            "helius" => Some(EnrichmentProvider::Helius),
+            "triton" => Some(EnrichmentProvider::Triton),
+            _ => None,
+        }
+    }
+}
+
+/// DAS (Digital Asset Standard) backend, served by both Helius and Triton.
+/// Enhanced transaction decoding is only available on Helius.
+pub struct DasBackend {
+    client: Client,
+    provider: EnrichmentProvider,
+    rpc_url: String,
+    enhanced_api_url: Option<String>,
+}
+
+impl DasBackend {
+    pub fn new(config: &EnrichmentConfig, provider: EnrichmentProvider) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()?;
+
+        let (rpc_url, enhanced_api_url) = match provider {
+            EnrichmentProvider::Helius => {
+                let rpc_url = if config.rpc_url.is_empty() {
+                    format!("https://mainnet.helius-rpc.com/?api-key={}", config.api_key)
+                } else {
+                    config.rpc_url.clone()
+                };
+                let enhanced = format!("https://api.helius.xyz/v0/transactions?api-key={}", config.api_key);
+                (rpc_url, Some(enhanced))
+            }
+            EnrichmentProvider::Triton => (config.rpc_url.clone(), None),
+            EnrichmentProvider::None => anyhow::bail!("DAS backend requires a provider"),
+        };
+
+        if rpc_url.is_empty() {
+            anyhow::bail!("Enrichment rpc_url is required for {:?}", provider);
+        }
+
+        Ok(Self {
+            client,
+            provider,
+            rpc_url,
+            enhanced_api_url,
+        })
+    }
+
+    /// The backend of the configured provider; None when there is none or it cannot be used, in
+    /// which case tokens are named from the token list and Metaplex metadata over plain RPC
+    pub fn from_config(config: &EnrichmentConfig) -> Option<Self> {
+        match EnrichmentProvider::parse(&config.provider) {
+            Some(EnrichmentProvider::None) => None,
+            Some(provider) => match Self::new(config, provider) {
+                Ok(backend) => Some(backend),
+                Err(e) => {
+                    warn!("Enrichment backend disabled: {}", e);
+                    None
+                }
+            },
+            None => {
+                warn!("Unknown enrichment provider '{}', using RPC fallback", config.provider);
+                None
+            }
+        }
+    }
+
+    /// Parse a DAS asset into token info; None for unknown assets and ones without a symbol
+    pub fn parse_asset(mint: &Pubkey, result: &Value) -> Option<TokenInfo> {
+        let metadata = &result["content"]["metadata"];
+        let token_info = &result["token_info"];
+        let non_empty = |v: &Value| v.as_str().filter(|s| !s.is_empty()).map(|s| s.to_string());
+
+        let symbol = non_empty(&token_info["symbol"]).or_else(|| non_empty(&metadata["symbol"]))?;
+        Some(TokenInfo {
+            mint: *mint,
+            name: non_empty(&metadata["name"]).unwrap_or_else(|| symbol.clone()),
+            symbol,
+            decimals: token_info["decimals"].as_u64().map_or(0, |d| d as u8),
+            logo_uri: non_empty(&result["content"]["links"]["image"]),
+        })
+    }
+
+    /// Parse an enhanced transaction into swap events, one per hop.
+    ///
+    /// Each inner swap is one program instruction and carries its own transfers. Without inner
+    /// swaps, the top-level inputs and outputs are paired by the user account that sent and
+    /// received them, never by position.
+    pub fn parse_swap_events(transaction: &Value) -> Vec<SwapEvent> {
+        let signature = transaction["signature"].as_str().unwrap_or_default().to_string();
+        let source = transaction["source"].as_str().map(|s| s.to_string());
+        let swap = &transaction["events"]["swap"];
+
+        let event = |source: Option<String>, input: Transfer, output: Transfer| SwapEvent {
+            signature: signature.clone(),
+            source,
+            price: if input.amount.is_zero() { Decimal::ZERO } else { output.amount / input.amount },
+            input_mint: input.mint,
+            input_amount: input.amount,
+            output_mint: output.mint,
+            output_amount: output.amount,
+        };
+
+        let inner = swap["innerSwaps"].as_array().map(Vec::as_slice).unwrap_or_default();
+        if !inner.is_empty() {
+            return inner
+                .iter()
+                .filter_map(|hop| {
+                    // An instruction moving several mints either way is not a single swap
+                    let mut inputs = Transfer::list(&hop["tokenInputs"], "fromUserAccount");
+                    let mut outputs = Transfer::list(&hop["tokenOutputs"], "toUserAccount");
+                    if inputs.len() != 1 || outputs.len() != 1 {
+                        return None;
+                    }
+                    let program = hop["programInfo"]["source"].as_str().map(|s| s.to_string());
+                    Some(event(program.or_else(|| source.clone()), inputs.remove(0), outputs.remove(0)))
+                })
+                .collect();
+        }
+
+        let mut outputs = Transfer::list(&swap["tokenOutputs"], "userAccount");
+        Transfer::list(&swap["tokenInputs"], "userAccount")
+            .into_iter()
+            .filter_map(|input| {
+                let index = outputs.iter().position(|output| output.account == input.account)?;
+                Some(event(source.clone(), input, outputs.remove(index)))
+            })
+            .collect()
+    }
+
+    /// Decode swap events for a transaction; empty when the provider has no enhanced API
+    pub async fn get_swap_events(&self, signature: &str) -> Result<Vec<SwapEvent>> {
+        let Some(url) = &self.enhanced_api_url else {
+            return Ok(Vec::new());
+        };
+
+        let response = self
+            .client
+            .post(url)
+            .json(&json!({ "transactions": [signature] }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("{} enhanced transactions failed: HTTP {}", self.name(), response.status());
+        }
+
+        let body: Vec<Value> = response.json().await?;
+        Ok(body.iter().flat_map(Self::parse_swap_events).collect())
+    }
+}
+
+/// Names tokens from the provider's DAS index; the token registry asks it alongside the token
+/// list, with Metaplex metadata over plain RPC as the fallback
+#[async_trait]
+impl TokenSource for DasBackend {
+    fn name(&self) -> &str {
+        match self.provider {
+            EnrichmentProvider::Helius => "helius",
+            EnrichmentProvider::Triton => "triton",
+            EnrichmentProvider::None => "none",
+        }
+    }
+
+    async fn lookup(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, TokenInfo>> {
+        let mut found = HashMap::new();
+        for mints in mints.chunks(MAX_ASSETS_PER_REQUEST) {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": "offchain-bot",
+                "method": "getAssetBatch",
+                "params": { "ids": mints.iter().map(|mint| mint.to_string()).collect::<Vec<_>>() },
+            });
+
+            let response = self.client.post(&self.rpc_url).json(&request).send().await?;
+            if !response.status().is_success() {
+                anyhow::bail!("{} getAssetBatch failed: HTTP {}", self.name(), response.status());
+            }
+
+            let body: Value = response.json().await?;
+            if let Some(error) = body.get("error") {
+                anyhow::bail!("{} getAssetBatch error: {}", self.name(), error);
+            }
+
+            // Results follow the requested ids, with null for assets the index does not hold
+            let assets = body["result"].as_array().map(Vec::as_slice).unwrap_or_default();
+            for (mint, asset) in mints.iter().zip(assets) {
+                if let Some(info) = Self::parse_asset(mint, asset) {
+                    found.insert(*mint, info);
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_asset() {
+        let mint = Pubkey::new_unique();
+        let result = json!({
+            "content": {
+                "metadata": { "name": "Bonk", "symbol": "Bonk" },
+                "links": { "image": "https://example.com/bonk.png" }
+            },
+            "token_info": {
+                "symbol": "BONK",
+                "decimals": 5,
+                "price_info": { "price_per_token": 0.000021 }
+            }
+        });
+
+        let info = DasBackend::parse_asset(&mint, &result).unwrap();
+        assert_eq!((info.symbol.as_str(), info.name.as_str()), ("BONK", "Bonk"));
+        assert_eq!(info.decimals, 5);
+        assert_eq!(info.logo_uri.as_deref(), Some("https://example.com/bonk.png"));
+        assert!(DasBackend::parse_asset(&mint, &Value::Null).is_none());
+        assert!(DasBackend::parse_asset(&mint, &json!({ "content": { "metadata": { "name": "Unnamed" } } })).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_configured_backend_names_registry_tokens() {
+        use crate::config::TokenRegistryConfig;
+        use crate::services::token_registry::TokenRegistry;
+
+        let url = crate::utils::fixtures::rpc_node(|method, params| {
+            assert_eq!(method, "getAssetBatch");
+            let ids = params["ids"].as_array().unwrap();
+            let result: Vec<Value> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, _)| match i {
+                    0 => json!({ "content": { "metadata": { "name": "Bonk", "symbol": "Bonk" } }, "token_info": { "symbol": "BONK", "decimals": 5 } }),
+                    _ => Value::Null,
+                })
+                .collect();
+            Ok(json!(result))
+        })
+        .await;
+
+        let config = |provider: &str, rpc_url: &str| EnrichmentConfig {
+            provider: provider.to_string(),
+            api_key: String::new(),
+            rpc_url: rpc_url.to_string(),
+            timeout_seconds: 5,
+        };
+        assert!(DasBackend::from_config(&config("none", &url)).is_none());
+        assert!(DasBackend::from_config(&config("quicknode", &url)).is_none());
+        // Triton has no default endpoint to fall back to
+        assert!(DasBackend::from_config(&config("triton", "")).is_none());
+
+        let backend = DasBackend::from_config(&config("triton", &url)).unwrap();
+        let registry = TokenRegistry::new(&TokenRegistryConfig::default()).with_source(std::sync::Arc::new(backend));
+        // The registry asks in mint order; the node knows only the first
+        let mut mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+        mints.sort_unstable();
+        registry.resolve(&mints, chrono::Utc::now()).await;
+        let named = registry.get(&mints[0]).unwrap();
+        assert_eq!((named.symbol.as_str(), named.name.as_str(), named.decimals), ("BONK", "Bonk", 5));
+        assert!(registry.get(&mints[1]).is_none());
+    }
+
+    #[test]
+    fn test_parse_swap_events() {
+        // Top-level transfers listed out of order pair by the account that sent and received them
+        let transaction = json!({
+            "signature": "sig",
+            "source": "RAYDIUM",
+            "events": { "swap": {
+                "tokenInputs": [
+                    { "userAccount": "u1", "mint": "A", "rawTokenAmount": { "tokenAmount": "1500000", "decimals": 6 } },
+                    { "userAccount": "u2", "mint": "C", "rawTokenAmount": { "tokenAmount": "4", "decimals": 0 } }
+                ],
+                "tokenOutputs": [
+                    { "userAccount": "u2", "mint": "D", "rawTokenAmount": { "tokenAmount": "1", "decimals": 0 } },
+                    { "userAccount": "u1", "mint": "B", "rawTokenAmount": { "tokenAmount": "2000000000", "decimals": 9 } }
+                ]
+            }}
+        });
+
+        let events = DasBackend::parse_swap_events(&transaction);
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].input_mint.as_str(), events[0].output_mint.as_str()), ("A", "B"));
+        assert_eq!(events[0].input_amount, Decimal::new(15, 1));
+        assert_eq!(events[0].output_amount, Decimal::from(2));
+        assert_eq!(events[0].source.as_deref(), Some("RAYDIUM"));
+        assert_eq!((events[1].input_mint.as_str(), events[1].output_mint.as_str()), ("C", "D"));
+        assert_eq!(events[1].price, Decimal::new(25, 2));
+        assert_eq!(events[1].fill_tag(), "4 C -> 1 D @ 0.25");
+
+        // Routed swaps decode one event per inner instruction
+        let transaction = json!({
+            "signature": "sig",
+            "source": "JUPITER",
+            "events": { "swap": {
+                "tokenInputs": [{ "userAccount": "u1", "mint": "A", "rawTokenAmount": { "tokenAmount": "2", "decimals": 0 } }],
+                "tokenOutputs": [{ "userAccount": "u1", "mint": "A", "rawTokenAmount": { "tokenAmount": "3", "decimals": 0 } }],
+                "innerSwaps": [
+                    {
+                        "tokenInputs": [{ "fromUserAccount": "u1", "mint": "A", "tokenAmount": 2.0 }],
+                        "tokenOutputs": [{ "toUserAccount": "u1", "mint": "B", "tokenAmount": 10.0 }],
+                        "programInfo": { "source": "ORCA" }
+                    },
+                    {
+                        "tokenInputs": [{ "fromUserAccount": "u1", "mint": "B", "tokenAmount": 10.0 }],
+                        "tokenOutputs": [{ "toUserAccount": "u1", "mint": "A", "tokenAmount": 3.0 }],
+                        "programInfo": {}
+                    }
+                ]
+            }}
+        });
+
+        let events = DasBackend::parse_swap_events(&transaction);
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].source.as_deref(), events[0].price), (Some("ORCA"), Decimal::from(5)));
+        assert_eq!((events[1].source.as_deref(), events[1].price), (Some("JUPITER"), Decimal::new(3, 1)));
+        assert_eq!(events[1].output_mint, "A");
+    }
+}
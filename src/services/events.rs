@@ -0,0 +1,189 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::models::{ArbitrageExecution, ArbitrageOpportunity, ExecutionStatus, ParameterChange};
+use crate::services::alert_rules::FiredAlert;
+use crate::services::codec::Codec;
+
+/// Engine events published to subscribers
+///
+/// Decimals serialize as strings so every codec, including bincode, round-trips them exactly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BotEvent {
+    OpportunityDetected {
+        id: String,
+        base_mint: String,
+        quote_mint: String,
+        buy_pool: String,
+        sell_pool: String,
+        buy_dex: String,
+        sell_dex: String,
+        #[serde(with = "rust_decimal::serde::str")]
+        profit_percentage: Decimal,
+        #[serde(with = "rust_decimal::serde::str")]
+        estimated_profit: Decimal,
+        timestamp: DateTime<Utc>,
+    },
+    ExecutionFinished {
+        id: String,
+        opportunity_id: String,
+        status: ExecutionStatus,
+        signature: Option<String>,
+        #[serde(with = "rust_decimal::serde::str_option")]
+        actual_profit: Option<Decimal>,
+        error: Option<String>,
+        timestamp: DateTime<Utc>,
+    },
+    AlertFired {
+        rule: String,
+        message: String,
+        value: f64,
+        timestamp: DateTime<Utc>,
+    },
+    EngineStateChanged {
+        paused: bool,
+        timestamp: DateTime<Utc>,
+    },
+    /// A strategy was replaced at runtime; `forced` when the change needed an override
+    StrategyUpdated {
+        id: String,
+        changes: Vec<ParameterChange>,
+        forced: bool,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl BotEvent {
+    pub fn opportunity(opportunity: &ArbitrageOpportunity) -> Self {
+        BotEvent::OpportunityDetected {
+            id: opportunity.id.clone(),
+            base_mint: opportunity.base_token.mint.to_string(),
+            quote_mint: opportunity.quote_token.mint.to_string(),
+            buy_pool: opportunity.buy_pool.pool_address.to_string(),
+            sell_pool: opportunity.sell_pool.pool_address.to_string(),
+            buy_dex: opportunity.buy_pool.dex_type.to_string(),
+            sell_dex: opportunity.sell_pool.dex_type.to_string(),
+            profit_percentage: opportunity.profit_percentage,
+            estimated_profit: opportunity.estimated_profit,
+            timestamp: opportunity.timestamp,
+        }
+    }
+
+    pub fn execution(execution: &ArbitrageExecution) -> Self {
+        BotEvent::ExecutionFinished {
+            id: execution.id.clone(),
+            opportunity_id: execution.opportunity.id.clone(),
+            status: execution.execution_status.clone(),
+            signature: execution.transaction_signature.clone(),
+            actual_profit: execution.actual_profit,
+            error: execution.error_message.clone(),
+            timestamp: execution.execution_time,
+        }
+    }
+
+    pub fn alert(alert: &FiredAlert) -> Self {
+        BotEvent::AlertFired {
+            rule: alert.rule_name.clone(),
+            message: alert.message.clone(),
+            value: alert.value,
+            timestamp: alert.timestamp,
+        }
+    }
+
+    pub fn engine_state(paused: bool) -> Self {
+        BotEvent::EngineStateChanged {
+            paused,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn strategy_updated(id: &str, changes: Vec<ParameterChange>, forced: bool) -> Self {
+        BotEvent::StrategyUpdated {
+            id: id.to_string(),
+            changes,
+            forced,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Short event name used for routing and logging
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BotEvent::OpportunityDetected { .. } => "opportunity_detected",
+            BotEvent::ExecutionFinished { .. } => "execution_finished",
+            BotEvent::AlertFired { .. } => "alert_fired",
+            BotEvent::EngineStateChanged { .. } => "engine_state_changed",
+            BotEvent::StrategyUpdated { .. } => "strategy_updated",
+        }
+    }
+}
+
+/// In-process fan-out of engine events
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<BotEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publish an event; dropped silently when nobody is subscribed
+    pub fn publish(&self, event: BotEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BotEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribe with events pre-encoded by the consumer's codec
+    pub fn subscribe_encoded(&self, codec: Arc<dyn Codec>) -> EncodedSubscription {
+        EncodedSubscription {
+            receiver: self.sender.subscribe(),
+            codec,
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+/// A subscription yielding encoded event payloads
+pub struct EncodedSubscription {
+    receiver: broadcast::Receiver<BotEvent>,
+    codec: Arc<dyn Codec>,
+}
+
+impl EncodedSubscription {
+    pub fn codec(&self) -> &dyn Codec {
+        self.codec.as_ref()
+    }
+
+    /// Next encoded event, or `None` once the bus is closed
+    pub async fn recv(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return self.codec.encode(&event).map(Some),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("{} subscriber lagged, skipped {} events", self.codec.name(), skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{
+    config::{AppConfig, FeeConfig},
+    dex::DexType,
+    models::{Pool, Token},
+    utils::{panic_guard::lock_or_recover, precision::from_base_units},
+};
+
+/// Base fee of one transaction signature
+pub const DEFAULT_LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Rent-exempt minimum of a 165-byte SPL token account
+pub const DEFAULT_TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280;
+
+/// What executing a route costs on top of its swap fees, in lamports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ExecutionCost {
+    pub base_fee: u64,
+    pub priority_fee: u64,
+    pub tip: u64,
+    /// Rent locked in token accounts the route opens
+    pub rent: u64,
+}
+
+/// `lamports` bid up by `multiplier`; never below the unscaled amount
+pub fn scale_bid(lamports: u64, multiplier: f64) -> u64 {
+    if multiplier <= 1.0 || !multiplier.is_finite() {
+        return lamports;
+    }
+    (lamports as f64 * multiplier).round().min(u64::MAX as f64) as u64
+}
+
+impl ExecutionCost {
+    /// The cost with the priority fee and tip bid up by a submission plan's `multiplier`
+    pub fn with_fee_multiplier(self, multiplier: f64) -> Self {
+        Self { priority_fee: scale_bid(self.priority_fee, multiplier), tip: scale_bid(self.tip, multiplier), ..self }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.base_fee
+            .saturating_add(self.priority_fee)
+            .saturating_add(self.tip)
+            .saturating_add(self.rent)
+    }
+}
+
+/// How much of each token one SOL buys, from the pool holding the most SOL against it.
+///
+/// Pools quote tokens against one another in their own units; pricing every token through SOL
+/// puts amounts of different tokens in one unit so they can be added up or compared.
+#[derive(Debug, Clone, Default)]
+pub struct SolPrices(HashMap<Pubkey, Decimal>);
+
+impl SolPrices {
+    pub fn from_pools<'a>(pools: impl IntoIterator<Item = &'a Pool>) -> Self {
+        let native = spl_token_interface::native_mint::id();
+        let mut depth: HashMap<Pubkey, Decimal> = HashMap::new();
+        let mut prices = HashMap::new();
+        for pool in pools {
+            let (token, sol_reserve, token_reserve) = if pool.token_a.mint == native {
+                (pool.token_b.mint, pool.reserve_a, pool.reserve_b)
+            } else if pool.token_b.mint == native {
+                (pool.token_a.mint, pool.reserve_b, pool.reserve_a)
+            } else {
+                continue;
+            };
+            if sol_reserve <= Decimal::ZERO || token_reserve <= Decimal::ZERO || token == native {
+                continue;
+            }
+            if depth.get(&token).is_none_or(|deepest| sol_reserve > *deepest) {
+                depth.insert(token, sol_reserve);
+                prices.insert(token, token_reserve / sol_reserve);
+            }
+        }
+        Self(prices)
+    }
+
+    /// Amount of `mint` one SOL buys; None until a pool pairing it with SOL has been seen
+    pub fn per_sol(&self, mint: &Pubkey) -> Option<Decimal> {
+        if *mint == spl_token_interface::native_mint::id() {
+            return Some(Decimal::ONE);
+        }
+        self.0.get(mint).copied()
+    }
+
+    /// `amount` of `from` in units of `to`, through SOL
+    pub fn convert(&self, amount: Decimal, from: &Pubkey, to: &Pubkey) -> Option<Decimal> {
+        if from == to {
+            return Some(amount);
+        }
+        Some(amount / self.per_sol(from)? * self.per_sol(to)?)
+    }
+}
+
+/// Execution cost estimates shared by the scanner, which nets them out of estimated profit, and
+/// the executor, which checks a quoted route still covers them before submitting.
+///
+/// Costs are paid in SOL but profit is counted in a route's start token; SOL is priced in each
+/// token from the deepest pool pairing the two, refreshed from every scan.
+pub struct FeeModel {
+    config: FeeConfig,
+    /// Tip paid with every bundle; zero when legs are not bundled
+    tip_lamports: u64,
+    sol_prices: Mutex<SolPrices>,
+}
+
+impl FeeModel {
+    pub fn new(config: &AppConfig) -> Self {
+        let submission = &config.submission;
+        Self {
+            config: config.fees.clone(),
+            tip_lamports: if submission.bundles_enabled { submission.bundle_tip_lamports } else { 0 },
+            sol_prices: Mutex::new(SolPrices::default()),
+        }
+    }
+
+    /// Cost of `legs` transactions opening `new_accounts` token accounts
+    pub fn estimate(&self, legs: usize, new_accounts: usize) -> ExecutionCost {
+        let legs = legs as u64;
+        ExecutionCost {
+            base_fee: legs.saturating_mul(self.config.lamports_per_signature),
+            priority_fee: legs.saturating_mul(self.config.priority_fee_lamports),
+            tip: self.tip_lamports,
+            rent: (new_accounts as u64).saturating_mul(self.config.token_account_rent_lamports),
+        }
+    }
+
+    /// `lamports` worth of `token`; None until a pool pairing it with SOL has been seen
+    pub fn in_token(&self, lamports: u64, token: &Token) -> Option<Decimal> {
+        let sol = from_base_units(lamports, 9);
+        lock_or_recover(&self.sol_prices, "SOL prices").per_sol(&token.mint).map(|price| sol * price)
+    }
+
+    /// The SOL prices as of the last scan
+    pub fn sol_prices(&self) -> SolPrices {
+        lock_or_recover(&self.sol_prices, "SOL prices").clone()
+    }
+
+    /// Price SOL in every token paired with it, from the pool holding the most SOL
+    pub fn update_sol_prices(&self, all_pools: &HashMap<DexType, Vec<Pool>>) {
+        *lock_or_recover(&self.sol_prices, "SOL prices") = SolPrices::from_pools(all_pools.values().flatten());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_costs_sum_per_leg_and_price_in_the_start_token() {
+        let mut config = AppConfig::bundled_default();
+        config.fees = FeeConfig { lamports_per_signature: 5_000, priority_fee_lamports: 20_000, token_account_rent_lamports: 2_000_000 };
+        config.submission.bundles_enabled = true;
+        config.submission.bundle_tip_lamports = 50_000;
+        let fees = FeeModel::new(&config);
+
+        let cost = fees.estimate(3, 1);
+        assert_eq!(cost, ExecutionCost { base_fee: 15_000, priority_fee: 60_000, tip: 50_000, rent: 2_000_000 });
+        assert_eq!(cost.total(), 2_125_000);
+        // Bidding up raises the priority fee and tip, never the base fee or rent
+        assert_eq!(
+            cost.with_fee_multiplier(1.5),
+            ExecutionCost { base_fee: 15_000, priority_fee: 90_000, tip: 75_000, rent: 2_000_000 }
+        );
+        assert_eq!(cost.with_fee_multiplier(0.5), cost);
+
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), "Solana".to_string(), 9);
+        let usdc = Token::new(Pubkey::new_unique(), "USDC".to_string(), "USD Coin".to_string(), 6);
+        assert_eq!(fees.in_token(1_000_000_000, &sol), Some(Decimal::ONE));
+        assert_eq!(fees.in_token(1_000_000_000, &usdc), None);
+
+        // The deeper pool sets the price
+        let pool = |sol_reserve: i64, usdc_reserve: i64| {
+            Pool::new(String::new(), DexType::Raydium, usdc.clone(), sol.clone(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default())
+                .update_reserves(Decimal::from(usdc_reserve), Decimal::from(sol_reserve))
+        };
+        fees.update_sol_prices(&HashMap::from([(DexType::Raydium, vec![pool(10, 1_600), pool(1_000, 150_000)])]));
+        assert_eq!(fees.in_token(cost.total(), &usdc), Some(Decimal::new(31875, 2) / Decimal::from(1_000)));
+
+        config.submission.bundles_enabled = false;
+        assert_eq!(FeeModel::new(&config).estimate(2, 0).total(), 50_000);
+    }
+}
@@ -0,0 +1,178 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::config::FingerprintConfig;
+use crate::models::ArbitrageOpportunity;
+use crate::utils::versioning::{self, FormatSpec};
+
+const FINGERPRINT_FORMAT: FormatSpec = FormatSpec {
+    name: "opportunity_fingerprints",
+    version: 1,
+    migrations: &[versioning::unchanged],
+};
+
+/// An opportunity that was executed recently
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FingerprintEntry {
+    pub fingerprint: String,
+    pub opportunity_id: String,
+    pub executed_at: DateTime<Utc>,
+}
+
+/// Fingerprints of recently executed opportunities, persisted so a restart does not re-trade
+/// a spread the previous instance already took
+pub struct FingerprintStore {
+    config: FingerprintConfig,
+    entries: RwLock<HashMap<String, FingerprintEntry>>,
+}
+
+impl FingerprintStore {
+    pub fn new(config: FingerprintConfig) -> Self {
+        Self { config, entries: RwLock::new(HashMap::new()) }
+    }
+
+    /// Create the store, restoring unexpired fingerprints when the file exists
+    pub fn load(config: FingerprintConfig) -> Result<Self> {
+        let mut entries = HashMap::new();
+        if !config.path.is_empty() && Path::new(&config.path).exists() {
+            let content = std::fs::read_to_string(&config.path)?;
+            let saved: Vec<FingerprintEntry> = FINGERPRINT_FORMAT.decode(&content)?;
+            let cutoff = Utc::now() - Duration::seconds(config.ttl_seconds as i64);
+            entries = saved
+                .into_iter()
+                .filter(|e| e.executed_at > cutoff)
+                .map(|e| (e.fingerprint.clone(), e))
+                .collect();
+            info!("Loaded {} opportunity fingerprints from {}", entries.len(), config.path);
+        }
+        Ok(Self { config, entries: RwLock::new(entries) })
+    }
+
+    /// Pair, pools and price buckets of an opportunity. Prices are bucketed geometrically, so the same
+    /// spread seen again a moment later maps to the same fingerprint.
+    pub fn fingerprint(&self, opportunity: &ArbitrageOpportunity) -> String {
+        let pools: Vec<String> = match &opportunity.route {
+            Some(route) => route.pools.iter().map(|p| p.pool_address.to_string()).collect(),
+            None => vec![opportunity.buy_pool.pool_address.to_string(), opportunity.sell_pool.pool_address.to_string()],
+        };
+        format!(
+            "{}/{}:{}:{}:{}",
+            opportunity.base_token.mint,
+            opportunity.quote_token.mint,
+            pools.join(">"),
+            self.price_bucket(opportunity.buy_price),
+            self.price_bucket(opportunity.sell_price),
+        )
+    }
+
+    fn price_bucket(&self, price: Decimal) -> i64 {
+        let price = price.to_f64().unwrap_or(0.0);
+        if price <= 0.0 {
+            return i64::MIN;
+        }
+        let step = (1.0 + self.config.price_bucket_bps.max(1) as f64 / 10_000.0).ln();
+        (price.ln() / step).floor() as i64
+    }
+
+    /// Whether an opportunity with the same fingerprint was executed within the TTL
+    pub async fn is_duplicate(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+        let cutoff = Utc::now() - Duration::seconds(self.config.ttl_seconds as i64);
+        self.entries
+            .read()
+            .await
+            .get(&self.fingerprint(opportunity))
+            .is_some_and(|e| e.executed_at > cutoff)
+    }
+
+    /// Remember an executed opportunity and persist the store
+    pub async fn record(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        let fingerprint = self.fingerprint(opportunity);
+        let entry = FingerprintEntry {
+            fingerprint: fingerprint.clone(),
+            opportunity_id: opportunity.id.clone(),
+            executed_at: Utc::now(),
+        };
+        self.entries.write().await.insert(fingerprint, entry);
+        self.save().await
+    }
+
+    /// Drop fingerprints older than the TTL
+    pub async fn prune_expired(&self) -> Result<usize> {
+        let cutoff = Utc::now() - Duration::seconds(self.config.ttl_seconds as i64);
+        let removed = {
+            let mut entries = self.entries.write().await;
+            let before = entries.len();
+            entries.retain(|_, e| e.executed_at > cutoff);
+            before - entries.len()
+        };
+        if removed > 0 {
+            self.save().await?;
+        }
+        Ok(removed)
+    }
+
+    async fn save(&self) -> Result<()> {
+        if self.config.path.is_empty() {
+            return Ok(());
+        }
+
+        let entries: Vec<FingerprintEntry> = self.entries.read().await.values().cloned().collect();
+        if let Some(parent) = Path::new(&self.config.path).parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        std::fs::write(&self.config.path, FINGERPRINT_FORMAT.encode(&entries)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fixtures;
+
+    #[tokio::test]
+    async fn test_fingerprints_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("fingerprints-{}.json", uuid::Uuid::new_v4()));
+        let config = FingerprintConfig {
+            enabled: true,
+            ttl_seconds: 300,
+            price_bucket_bps: 10,
+            path: path.to_string_lossy().to_string(),
+        };
+
+        let mut executed = fixtures::opportunity();
+        executed.buy_price = Decimal::new(15000, 2);
+        executed.sell_price = Decimal::new(15060, 2);
+
+        let store = FingerprintStore::load(config.clone()).unwrap();
+        store.record(&executed).await.unwrap();
+
+        // The same spread, re-detected by a fresh instance with a slightly moved price
+        let restarted = FingerprintStore::load(config).unwrap();
+        let mut seen_again = executed.clone();
+        seen_again.id = "new".to_string();
+        seen_again.buy_price = Decimal::new(150001, 3);
+        assert!(restarted.is_duplicate(&seen_again).await);
+
+        // A different price level is a different opportunity
+        seen_again.sell_price = Decimal::new(15200, 2);
+        assert!(!restarted.is_duplicate(&seen_again).await);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,387 @@
+use anyhow::{Context, Result};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tracing::{debug, warn};
+
+use crate::{
+    config::GeyserConfig,
+    services::account_subscriber::{AccountUpdate, Command, MAX_RECONNECT_BACKOFF},
+};
+
+/// Largest message accepted before the stream is dropped
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Name of the single account filter; one request carries every subscribed account
+const ACCOUNTS_FILTER: &str = "pools";
+
+/// Messages and client of `proto/geyser.proto`, the part of Yellowstone's geyser.proto needed to
+/// follow accounts
+pub(crate) mod proto {
+    use std::collections::HashMap;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeRequest {
+        #[prost(map = "string, message", tag = "1")]
+        pub accounts: HashMap<String, SubscribeRequestFilterAccounts>,
+        #[prost(int32, optional, tag = "6")]
+        pub commitment: Option<i32>,
+        #[prost(message, optional, tag = "9")]
+        pub ping: Option<SubscribeRequestPing>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeRequestFilterAccounts {
+        #[prost(string, repeated, tag = "2")]
+        pub account: Vec<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeRequestPing {
+        #[prost(int32, tag = "1")]
+        pub id: i32,
+    }
+
+    /// Slot, transaction and block updates are skipped as unknown fields
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeUpdate {
+        #[prost(string, repeated, tag = "1")]
+        pub filters: Vec<String>,
+        #[prost(oneof = "UpdateOneof", tags = "2, 6")]
+        pub update_oneof: Option<UpdateOneof>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum UpdateOneof {
+        #[prost(message, tag = "2")]
+        Account(SubscribeUpdateAccount),
+        #[prost(message, tag = "6")]
+        Ping(SubscribeUpdatePing),
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeUpdateAccount {
+        #[prost(message, optional, tag = "1")]
+        pub account: Option<SubscribeUpdateAccountInfo>,
+        #[prost(uint64, tag = "2")]
+        pub slot: u64,
+        #[prost(bool, tag = "3")]
+        pub is_startup: bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeUpdateAccountInfo {
+        #[prost(bytes = "vec", tag = "1")]
+        pub pubkey: Vec<u8>,
+        #[prost(uint64, tag = "2")]
+        pub lamports: u64,
+        #[prost(bytes = "vec", tag = "3")]
+        pub owner: Vec<u8>,
+        #[prost(bool, tag = "4")]
+        pub executable: bool,
+        #[prost(uint64, tag = "5")]
+        pub rent_epoch: u64,
+        #[prost(bytes = "vec", tag = "6")]
+        pub data: Vec<u8>,
+        #[prost(uint64, tag = "7")]
+        pub write_version: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeUpdatePing {}
+
+    /// `geyser.Geyser` client
+    #[derive(Debug, Clone)]
+    pub struct GeyserClient {
+        inner: tonic::client::Grpc<tonic::transport::Channel>,
+    }
+
+    impl GeyserClient {
+        pub const SUBSCRIBE_PATH: &'static str = "/geyser.Geyser/Subscribe";
+
+        pub fn new(channel: tonic::transport::Channel) -> Self {
+            Self { inner: tonic::client::Grpc::new(channel) }
+        }
+
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+
+        pub async fn subscribe(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = SubscribeRequest>,
+        ) -> Result<tonic::Response<tonic::Streaming<SubscribeUpdate>>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e)))?;
+            let path = tonic::codegen::http::uri::PathAndQuery::from_static(Self::SUBSCRIBE_PATH);
+            let codec = tonic::codec::ProstCodec::default();
+            self.inner.streaming(request.into_streaming_request(), path, codec).await
+        }
+    }
+}
+
+fn commitment_level(commitment: &str) -> Result<i32> {
+    match commitment {
+        "processed" => Ok(0),
+        "confirmed" => Ok(1),
+        "finalized" => Ok(2),
+        other => anyhow::bail!("Unknown geyser commitment '{}'", other),
+    }
+}
+
+/// Open a channel to `endpoint`, over TLS with the bundled web PKI roots for `https`
+async fn connect(endpoint: &Endpoint) -> Result<proto::GeyserClient> {
+    let mut endpoint = endpoint.clone().tcp_nodelay(true);
+    if endpoint.uri().scheme_str() == Some("https") {
+        endpoint = endpoint.tls_config(ClientTlsConfig::new().with_webpki_roots())?;
+    }
+    let channel: Channel = endpoint.connect().await?;
+    Ok(proto::GeyserClient::new(channel).max_decoding_message_size(MAX_MESSAGE_BYTES))
+}
+
+/// Yellowstone `Subscribe` stream following every subscribed account.
+///
+/// The filter set is replaced by sending a new request on the same stream whenever an account
+/// gains its first subscriber or loses its last one.
+pub(crate) struct GeyserConnection {
+    endpoint: Endpoint,
+    x_token: Option<tonic::metadata::MetadataValue<tonic::metadata::Ascii>>,
+    commitment: i32,
+    reconnect_backoff: Duration,
+    subscribers: HashMap<Pubkey, Vec<mpsc::Sender<AccountUpdate>>>,
+}
+
+impl GeyserConnection {
+    pub(crate) fn new(config: &GeyserConfig) -> Result<Self> {
+        let endpoint = Endpoint::from_shared(config.endpoint.clone())
+            .with_context(|| format!("Invalid geyser endpoint {}", config.endpoint))?;
+        if !matches!(endpoint.uri().scheme_str(), Some("http" | "https")) {
+            anyhow::bail!("Geyser endpoint must be http:// or https://");
+        }
+        let x_token = match config.x_token.as_str() {
+            "" => None,
+            token => Some(token.parse().context("Geyser x_token is not a valid header value")?),
+        };
+        Ok(Self {
+            endpoint,
+            x_token,
+            commitment: commitment_level(&config.commitment)?,
+            reconnect_backoff: Duration::from_millis(config.reconnect_backoff_ms.max(1)),
+            subscribers: HashMap::new(),
+        })
+    }
+
+    /// Keep a stream open until every `AccountSubscriber` handle is gone
+    pub(crate) async fn run(mut self, mut commands: mpsc::UnboundedReceiver<Command>) {
+        let mut backoff = self.reconnect_backoff;
+        loop {
+            let started = tokio::time::Instant::now();
+            match self.session(&mut commands).await {
+                Ok(()) => return,
+                Err(e) => warn!("Geyser stream {} dropped: {:#}", self.endpoint.uri(), e),
+            }
+            // A stream that stayed up for a while resets the backoff
+            if started.elapsed() > MAX_RECONNECT_BACKOFF {
+                backoff = self.reconnect_backoff;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
+        }
+    }
+
+    fn subscribe_request(&self) -> proto::SubscribeRequest {
+        let account = self.subscribers.keys().map(Pubkey::to_string).collect();
+        proto::SubscribeRequest {
+            accounts: HashMap::from([(ACCOUNTS_FILTER.to_string(), proto::SubscribeRequestFilterAccounts { account })]),
+            commitment: Some(self.commitment),
+            ping: None,
+        }
+    }
+
+    async fn session(&mut self, commands: &mut mpsc::UnboundedReceiver<Command>) -> Result<()> {
+        let mut client = connect(&self.endpoint).await?;
+        let (outbound, requests) = mpsc::unbounded_channel();
+        outbound.send(self.subscribe_request())?;
+        let mut request = tonic::Request::new(UnboundedReceiverStream::new(requests));
+        if let Some(token) = &self.x_token {
+            request.metadata_mut().insert("x-token", token.clone());
+        }
+        let mut inbound = client.subscribe(request).await?.into_inner();
+
+        loop {
+            tokio::select! {
+                update = inbound.message() => {
+                    let Some(update) = update? else {
+                        anyhow::bail!("stream ended by server");
+                    };
+                    match update.update_oneof {
+                        Some(proto::UpdateOneof::Account(update)) => {
+                            let shrank = self.dispatch(update);
+                            if shrank {
+                                outbound.send(self.subscribe_request())?;
+                            }
+                        }
+                        // Answered so proxies in between don't time the stream out
+                        Some(proto::UpdateOneof::Ping(_)) => {
+                            outbound.send(proto::SubscribeRequest {
+                                ping: Some(proto::SubscribeRequestPing { id: 1 }),
+                                ..Default::default()
+                            })?;
+                        }
+                        None => {}
+                    }
+                }
+                command = commands.recv() => match command {
+                    Some(Command::Subscribe(pubkey, sender)) => {
+                        let subscribers = self.subscribers.entry(pubkey).or_default();
+                        subscribers.push(sender);
+                        if subscribers.len() == 1 {
+                            outbound.send(self.subscribe_request())?;
+                        }
+                    }
+                    // Dropping the request stream ends it
+                    None => return Ok(()),
+                },
+            }
+        }
+    }
+
+    /// Fan an account update out to its subscribers, returning whether the filter set shrank
+    fn dispatch(&mut self, update: proto::SubscribeUpdateAccount) -> bool {
+        let Some(account) = update.account else {
+            return false;
+        };
+        let Ok(pubkey) = Pubkey::try_from(account.pubkey.as_slice()) else {
+            debug!("Ignoring geyser update with a malformed pubkey");
+            return false;
+        };
+        let Some(subscribers) = self.subscribers.get_mut(&pubkey) else {
+            return false;
+        };
+
+        let update = AccountUpdate { pubkey, slot: update.slot, lamports: account.lamports, data: account.data };
+        subscribers.retain(|sender| match sender.try_send(update.clone()) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                debug!("Subscriber for {} is behind; dropped an update", pubkey);
+                true
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        });
+        if !subscribers.is_empty() {
+            return false;
+        }
+        self.subscribers.remove(&pubkey);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::account_subscriber::AccountSubscriber;
+    use bytes::{Buf, BufMut, Bytes, BytesMut};
+    use prost::Message;
+    use tokio::net::TcpListener;
+
+    /// Length-prefixed gRPC message, uncompressed
+    fn encode_frame(message: &impl Message) -> Bytes {
+        let body = message.encode_to_vec();
+        let mut frame = BytesMut::with_capacity(5 + body.len());
+        frame.put_u8(0);
+        frame.put_u32(body.len() as u32);
+        frame.extend_from_slice(&body);
+        frame.freeze()
+    }
+
+    /// Next complete message in `buffer`, leaving any partial one buffered
+    fn decode_frame(buffer: &mut BytesMut) -> Option<Bytes> {
+        if buffer.len() < 5 {
+            return None;
+        }
+        let length = u32::from_be_bytes([buffer[1], buffer[2], buffer[3], buffer[4]]) as usize;
+        if buffer.len() < 5 + length {
+            return None;
+        }
+        buffer.advance(5);
+        Some(buffer.split_to(length).freeze())
+    }
+
+    #[tokio::test]
+    async fn test_account_updates_stream_over_grpc() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let account = Pubkey::new_unique();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut connection = h2::server::handshake(stream).await.unwrap();
+            let (request, mut respond) = connection.accept().await.unwrap().unwrap();
+            tokio::spawn(async move { while connection.accept().await.is_some() {} });
+            assert_eq!(request.uri().path(), proto::GeyserClient::SUBSCRIBE_PATH);
+            assert_eq!(request.headers()["x-token"], "secret");
+
+            let mut body = request.into_body();
+            let mut buffer = BytesMut::new();
+            let response = http::Response::builder().header("content-type", "application/grpc").body(()).unwrap();
+            let mut outbound = respond.send_response(response, false).unwrap();
+            // The first request may predate the subscription; wait for the one naming the account
+            loop {
+                let chunk = body.data().await.unwrap().unwrap();
+                let _ = body.flow_control().release_capacity(chunk.len());
+                buffer.extend_from_slice(&chunk);
+                let mut named = false;
+                while let Some(message) = decode_frame(&mut buffer) {
+                    let request = proto::SubscribeRequest::decode(message).unwrap();
+                    assert_eq!(request.commitment, Some(1));
+                    named |= request.accounts[ACCOUNTS_FILTER].account == vec![account.to_string()];
+                }
+                if named {
+                    break;
+                }
+            }
+
+            let update = proto::SubscribeUpdate {
+                filters: vec![ACCOUNTS_FILTER.to_string()],
+                update_oneof: Some(proto::UpdateOneof::Account(proto::SubscribeUpdateAccount {
+                    account: Some(proto::SubscribeUpdateAccountInfo {
+                        pubkey: account.to_bytes().to_vec(),
+                        lamports: 5000,
+                        data: vec![1, 2, 3],
+                        ..Default::default()
+                    }),
+                    slot: 1234,
+                    is_startup: false,
+                })),
+            };
+            // Split mid-message to exercise buffering
+            let frame = encode_frame(&update);
+            outbound.send_data(frame.slice(..7), false).unwrap();
+            outbound.send_data(frame.slice(7..), false).unwrap();
+
+            // Hold the stream open until the client goes away
+            while let Some(Ok(_)) = body.data().await {}
+        });
+
+        let config = GeyserConfig {
+            enabled: true,
+            endpoint,
+            x_token: "secret".to_string(),
+            commitment: "confirmed".to_string(),
+            ..GeyserConfig::default()
+        };
+        let subscriber = AccountSubscriber::start_geyser(&config, None).unwrap();
+        let mut updates = subscriber.subscribe(account).unwrap();
+        let update = tokio::time::timeout(Duration::from_secs(5), updates.recv()).await.unwrap().unwrap();
+        assert_eq!(update.pubkey, account);
+        assert_eq!(update.slot, 1234);
+        assert_eq!(update.lamports, 5000);
+        assert_eq!(update.data, vec![1, 2, 3]);
+
+        drop(subscriber);
+        tokio::time::timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+    }
+}
@@ -1,20 +1,56 @@
 use anyhow::Result;
+use base64::Engine;
 use solana_sdk::{
+    instruction::Instruction,
     transaction::Transaction,
     signature::Signature,
     pubkey::Pubkey,
 };
+use solana_system_interface::instruction as system_instruction;
 use std::str::FromStr;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use async_trait::async_trait;
 use reqwest::Client;
 use std::time::Duration;
 
+use crate::config::ConnectionConfig;
+use crate::services::keepalive::{tuned_client, ConnectionStats, KeepAliveTarget};
+
+/// Mainnet tip accounts; any one of them can receive a bundle's tip
+pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
+    Pubkey::from_str_const("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    Pubkey::from_str_const("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    Pubkey::from_str_const("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    Pubkey::from_str_const("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    Pubkey::from_str_const("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    Pubkey::from_str_const("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    Pubkey::from_str_const("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    Pubkey::from_str_const("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+/// The block engine rejects bundles with more transactions than this
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// Transfer `lamports` from `payer` to a tip account, spreading tips across accounts to reduce write contention
+pub fn tip_instruction(payer: &Pubkey, lamports: u64) -> Instruction {
+    let tip_account = JITO_TIP_ACCOUNTS[rand::random::<usize>() % JITO_TIP_ACCOUNTS.len()];
+    system_instruction::transfer(payer, &tip_account, lamports)
+}
+
+/// Whether the transfer pays one of the Jito tip accounts
+pub fn is_tip_instruction(instruction: &Instruction) -> bool {
+    instruction.program_id == solana_system_interface::program::ID
+        && instruction.accounts.get(1).is_some_and(|meta| JITO_TIP_ACCOUNTS.contains(&meta.pubkey))
+}
+
 /// Jito MEV protection service
 pub struct JitoService {
     client: Client,
     base_url: String,
     auth_header: String,
     timeout: Duration,
+    connections: ConnectionStats,
 }
 
 /// Jito transaction request
@@ -35,6 +71,79 @@ pub struct JitoTransactionResponse {
     pub err: Option<serde_json::Value>,
 }
 
+/// Landing status of a bundle, from `getBundleStatuses`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BundleStatus {
+    pub bundle_id: String,
+    /// Signatures of the bundle's transactions, in order
+    pub transactions: Vec<String>,
+    pub slot: u64,
+    /// "processed", "confirmed" or "finalized"
+    pub confirmation_status: Option<String>,
+    /// `{"Ok": null}` when the bundle executed
+    pub err: serde_json::Value,
+}
+
+impl BundleStatus {
+    pub fn is_landed(&self) -> bool {
+        matches!(self.confirmation_status.as_deref(), Some("confirmed") | Some("finalized"))
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.err.get("Ok").is_none() && !self.err.is_null()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<serde_json::Value>,
+}
+
+/// One transaction's outcome in a `simulateBundle` response
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBundleTransaction {
+    pub err: Option<serde_json::Value>,
+    #[serde(default)]
+    pub logs: Option<Vec<String>>,
+    pub units_consumed: Option<u64>,
+    /// Watched accounts after the transaction, when asked for
+    #[serde(default)]
+    pub post_execution_accounts: Option<Vec<solana_rpc_client_api::response::UiAccount>>,
+}
+
+/// Result of simulating a bundle as one atomic unit, each transaction seeing the state the
+/// previous ones left
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSimulation {
+    /// `"succeeded"`, or `{"failed": ...}` naming the failing transaction
+    pub summary: serde_json::Value,
+    pub transaction_results: Vec<SimulatedBundleTransaction>,
+}
+
+impl BundleSimulation {
+    /// Index and error of the transaction that failed the bundle, if any
+    pub fn failure(&self) -> Option<(usize, String)> {
+        if let Some((index, result)) = self.transaction_results.iter().enumerate().find(|(_, result)| result.err.is_some()) {
+            return Some((index, result.err.as_ref().map(ToString::to_string).unwrap_or_default()));
+        }
+        // Results stop at the failing transaction
+        (self.summary.as_str() != Some("succeeded")).then(|| (self.transaction_results.len(), self.summary.to_string()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcValue<T> {
+    value: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct BundleStatusesResult {
+    value: Vec<Option<BundleStatus>>,
+}
+
 /// Jito block builder info
 #[derive(Debug, Deserialize)]
 pub struct JitoBlockBuilderInfo {
@@ -71,18 +180,27 @@ impl Default for JitoConfig {
 impl JitoService {
     /// Create a new Jito service instance
     pub fn new(config: JitoConfig) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(config.timeout)
-            .build()?;
+        Self::with_connections(config, &ConnectionConfig::default())
+    }
+    
+    /// Create a Jito service whose HTTP connections are tuned for reuse
+    pub fn with_connections(config: JitoConfig, connection_config: &ConnectionConfig) -> Result<Self> {
+        let (client, connections) = tuned_client(connection_config, config.timeout)?;
         
         Ok(Self {
             client,
             base_url: config.base_url,
             auth_header: config.auth_header,
             timeout: config.timeout,
+            connections,
         })
     }
     
+    /// Tip accounts the block engine currently accepts
+    pub async fn get_tip_accounts(&self) -> Result<Vec<String>> {
+        self.bundle_rpc("getTipAccounts", json!([])).await
+    }
+    
     /// Send transaction to Jito
     pub async fn send_transaction(
         &self,
@@ -121,6 +239,99 @@ impl JitoService {
         Ok(jito_response)
     }
     
+    /// Submit transactions as an atomic bundle: all land in order in the same slot, or none do.
+    /// Returns the bundle id.
+    pub async fn send_bundle(&self, transactions: &[Transaction]) -> Result<String> {
+        if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            anyhow::bail!(
+                "A bundle holds 1 to {} transactions, got {}",
+                MAX_BUNDLE_TRANSACTIONS,
+                transactions.len()
+            );
+        }
+        let encoded = transactions
+            .iter()
+            .map(|tx| Ok(base64::engine::general_purpose::STANDARD.encode(bincode::serialize(tx)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.bundle_rpc("sendBundle", json!([encoded, { "encoding": "base64" }])).await
+    }
+
+    /// Statuses of previously sent bundles; None for bundles that have not landed
+    pub async fn get_bundle_statuses(&self, bundle_ids: &[String]) -> Result<Vec<Option<BundleStatus>>> {
+        let result: BundleStatusesResult = self.bundle_rpc("getBundleStatuses", json!([bundle_ids])).await?;
+        Ok(result.value)
+    }
+
+    /// Simulate `transactions` as a bundle on a Jito-Solana RPC node at `rpc_url`, returning the
+    /// state of `watch` after the last one. Signatures are not checked and blockhashes are replaced.
+    pub async fn simulate_bundle(&self, rpc_url: &str, transactions: &[Transaction], watch: &[Pubkey]) -> Result<BundleSimulation> {
+        if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            anyhow::bail!(
+                "A bundle holds 1 to {} transactions, got {}",
+                MAX_BUNDLE_TRANSACTIONS,
+                transactions.len()
+            );
+        }
+        let encoded = transactions
+            .iter()
+            .map(|tx| Ok(base64::engine::general_purpose::STANDARD.encode(bincode::serialize(tx)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let watched = json!({ "encoding": "base64", "addresses": watch.iter().map(Pubkey::to_string).collect::<Vec<_>>() });
+        let post_accounts: Vec<_> = (0..transactions.len())
+            .map(|i| if i + 1 == transactions.len() { watched.clone() } else { serde_json::Value::Null })
+            .collect();
+        let config = json!({
+            "transactionEncoding": "base64",
+            "skipSigVerify": true,
+            "replaceRecentBlockhash": true,
+            "preExecutionAccountsConfigs": vec![serde_json::Value::Null; transactions.len()],
+            "postExecutionAccountsConfigs": post_accounts,
+        });
+
+        let result: RpcValue<BundleSimulation> = self
+            .json_rpc(rpc_url, "simulateBundle", json!([{ "encodedTransactions": encoded }, config]))
+            .await?;
+        Ok(result.value)
+    }
+
+    /// JSON-RPC call against the block engine's bundle endpoint
+    async fn bundle_rpc<T>(&self, method: &str, params: serde_json::Value) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let url = format!("{}/api/v1/bundles", self.base_url.trim_end_matches('/'));
+        self.json_rpc(&url, method, params).await
+    }
+
+    async fn json_rpc<T>(&self, url: &str, method: &str, params: serde_json::Value) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+
+        let mut request_builder = self.client.post(url).json(&body).timeout(self.timeout);
+        // The bundle API authenticates with its own header rather than Authorization
+        if !self.auth_header.is_empty() {
+            request_builder = request_builder.header("x-jito-auth", &self.auth_header);
+        }
+
+        let response = request_builder.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await?;
+            anyhow::bail!("Jito API error: {} - {}", status, error_text);
+        }
+
+        let rpc_response: JsonRpcResponse<T> = response.json().await?;
+        if let Some(error) = rpc_response.error {
+            anyhow::bail!("Jito {} failed: {}", method, error);
+        }
+        rpc_response
+            .result
+            .ok_or_else(|| anyhow::anyhow!("Jito {} returned no result", method))
+    }
+    
     /// Get available block builders
     pub async fn get_block_builders(&self) -> Result<Vec<JitoBlockBuilderInfo>> {
         let url = format!("{}/v1/block-builders", self.base_url);
@@ -238,6 +449,21 @@ impl JitoService {
     }
 }
 
+#[async_trait]
+impl KeepAliveTarget for JitoService {
+    fn endpoint_name(&self) -> String {
+        format!("jito:{}", self.base_url)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.get_tip_accounts().await.map(|_| ())
+    }
+
+    fn connection_stats(&self) -> ConnectionStats {
+        self.connections.clone()
+    }
+}
+
 /// Jito MEV protection manager
 pub struct JitoMevProtection {
     jito_service: JitoService,
@@ -459,6 +685,37 @@ mod tests {
         assert!(!strategy.should_protect(0.5, 1.5));
     }
     
+    #[test]
+    fn test_bundle_status_and_tip() {
+        let payer = Pubkey::new_unique();
+        let tip = tip_instruction(&payer, 10_000);
+        assert!(is_tip_instruction(&tip));
+        assert!(!is_tip_instruction(&system_instruction::transfer(&payer, &Pubkey::new_unique(), 1)));
+
+        let response: JsonRpcResponse<BundleStatusesResult> = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "result": {
+                "context": { "slot": 242806119 },
+                "value": [
+                    {
+                        "bundle_id": "892b79ed49138bfb3aa5441f0df6e06ef34f9ee8f3976c15b323605bae0cf51d",
+                        "transactions": ["3bC2M9fiACSjkTXZDgeNAuQ4ScTsdKGwR42ytFdhUvikqTmBheUxfsR1fDVsM5ADCMMspuwGkdm1uKbU246x5aE3"],
+                        "slot": 242804011,
+                        "confirmation_status": "finalized",
+                        "err": { "Ok": null }
+                    },
+                    null
+                ]
+            },
+            "id": 1
+        }))
+        .unwrap();
+        let statuses = response.result.unwrap().value;
+        let landed = statuses[0].as_ref().unwrap();
+        assert!(landed.is_landed() && !landed.is_failed());
+        assert!(statuses[1].is_none());
+    }
+
     #[test]
     fn test_jito_config_builder() {
         let config = JitoConfigBuilder::new()
@@ -0,0 +1,352 @@
+use anyhow::Result;
+use memmap2::MmapMut;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::config::JournalConfig;
+use crate::services::codec::Codec;
+use crate::services::events::EventBus;
+use crate::utils::panic_guard::lock_or_recover;
+use crate::utils::versioning::FormatSpec;
+
+const MAGIC: &[u8; 8] = b"OBJRNL\0\0";
+const HEADER_LEN: usize = 64;
+/// Record length (u32) followed by sequence number (u64)
+const RECORD_HEADER_LEN: usize = 12;
+/// Length value marking that the rest of the buffer is padding
+const WRAP_MARKER: u32 = u32::MAX;
+
+const VERSION_OFFSET: usize = 8;
+const CAPACITY_OFFSET: usize = 16;
+const HEAD_OFFSET: usize = 24;
+const TAIL_OFFSET: usize = 32;
+const SEQ_OFFSET: usize = 40;
+
+/// On-disk layout of the journal file
+const JOURNAL_FORMAT: FormatSpec = FormatSpec {
+    name: "event_journal",
+    version: 1,
+    migrations: &[],
+};
+
+/// Fixed-size, memory-mapped ring buffer of records; the oldest records are overwritten.
+///
+/// Head and tail are logical byte offsets that only grow; the physical offset is modulo capacity.
+/// Appends are a memcpy into the mapping, and durability is left to a background flush.
+pub struct Journal {
+    mmap: MmapMut,
+    capacity: u64,
+    head: u64,
+    tail: u64,
+    next_seq: u64,
+}
+
+impl Journal {
+    /// Open the journal at `path`, creating it with `capacity` data bytes when missing
+    pub fn open(path: impl AsRef<Path>, capacity: u64) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        let existing = file.metadata()?.len();
+        let file_len = HEADER_LEN as u64 + capacity;
+        if existing == 0 {
+            file.set_len(file_len)?;
+        }
+
+        // Safety: the file is owned by this process for the lifetime of the mapping
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+
+        if existing == 0 {
+            mmap[..8].copy_from_slice(MAGIC);
+            mmap[VERSION_OFFSET..VERSION_OFFSET + 4].copy_from_slice(&JOURNAL_FORMAT.version.to_le_bytes());
+            mmap[CAPACITY_OFFSET..CAPACITY_OFFSET + 8].copy_from_slice(&capacity.to_le_bytes());
+            info!("Created event journal {} ({} bytes)", path.display(), capacity);
+            return Ok(Self { mmap, capacity, head: 0, tail: 0, next_seq: 0 });
+        }
+
+        if mmap.len() < HEADER_LEN || &mmap[..8] != MAGIC {
+            anyhow::bail!("{} is not an event journal", path.display());
+        }
+        let version = u32::from_le_bytes(mmap[VERSION_OFFSET..VERSION_OFFSET + 4].try_into()?);
+        JOURNAL_FORMAT.check_version(version)?;
+
+        let mut journal = Self {
+            capacity: 0,
+            head: 0,
+            tail: 0,
+            next_seq: 0,
+            mmap,
+        };
+        journal.capacity = journal.read_u64(CAPACITY_OFFSET);
+        journal.head = journal.read_u64(HEAD_OFFSET);
+        journal.tail = journal.read_u64(TAIL_OFFSET);
+        journal.next_seq = journal.read_u64(SEQ_OFFSET);
+
+        if journal.capacity != capacity {
+            warn!(
+                "Journal {} has capacity {} bytes, ignoring configured {}",
+                path.display(),
+                journal.capacity,
+                capacity
+            );
+        }
+        if HEADER_LEN as u64 + journal.capacity != existing {
+            anyhow::bail!("Journal {} is truncated", path.display());
+        }
+
+        Ok(journal)
+    }
+
+    /// Open a journal that must already exist, at whatever capacity it was created with
+    pub fn open_existing(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let len = std::fs::metadata(path).map_err(|e| anyhow::anyhow!("Cannot open journal {}: {}", path.display(), e))?.len();
+        if len <= HEADER_LEN as u64 {
+            anyhow::bail!("{} is not an event journal", path.display());
+        }
+        Self::open(path, len - HEADER_LEN as u64)
+    }
+
+    /// Append a record, returning its sequence number
+    pub fn append(&mut self, payload: &[u8]) -> Result<u64> {
+        let record_len = (RECORD_HEADER_LEN + payload.len()) as u64;
+        if record_len > self.capacity {
+            anyhow::bail!("Record of {} bytes exceeds journal capacity {}", payload.len(), self.capacity);
+        }
+
+        // Records never straddle the end of the buffer
+        let offset = self.head % self.capacity;
+        let mut start = self.head;
+        if offset + record_len > self.capacity {
+            let remaining = self.capacity - offset;
+            if remaining >= 4 {
+                self.write_data(offset, &WRAP_MARKER.to_le_bytes());
+            }
+            start += remaining;
+        }
+
+        let end = start + record_len;
+        while end - self.tail > self.capacity {
+            self.tail = self.next_record(self.tail);
+        }
+
+        let seq = self.next_seq;
+        let offset = start % self.capacity;
+        self.write_data(offset, &(payload.len() as u32).to_le_bytes());
+        self.write_data(offset + 4, &seq.to_le_bytes());
+        self.write_data(offset + RECORD_HEADER_LEN as u64, payload);
+
+        self.head = end;
+        self.next_seq += 1;
+        self.write_u64(HEAD_OFFSET, self.head);
+        self.write_u64(TAIL_OFFSET, self.tail);
+        self.write_u64(SEQ_OFFSET, self.next_seq);
+        Ok(seq)
+    }
+
+    /// Records still in the buffer, oldest first, as `(sequence, payload)`
+    pub fn records(&self) -> Vec<(u64, Vec<u8>)> {
+        let mut records = Vec::new();
+        let mut position = self.tail;
+        while position < self.head {
+            let offset = position % self.capacity;
+            if let Some(len) = self.record_len_at(offset) {
+                let data = HEADER_LEN + offset as usize + RECORD_HEADER_LEN;
+                let seq = self.read_u64(HEADER_LEN + offset as usize + 4);
+                records.push((seq, self.mmap[data..data + len as usize].to_vec()));
+            }
+            position = self.next_record(position);
+        }
+        records
+    }
+
+    /// Number of records in the buffer, without copying them out
+    pub fn len(&self) -> usize {
+        let mut count = 0;
+        let mut position = self.tail;
+        while position < self.head {
+            if self.record_len_at(position % self.capacity).is_some() {
+                count += 1;
+            }
+            position = self.next_record(position);
+        }
+        count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head == self.tail
+    }
+
+    /// Drop records up to and including sequence `seq`, e.g. once they have been replayed elsewhere
+    pub fn discard_through(&mut self, seq: u64) {
+        while self.tail < self.head {
+            let offset = self.tail % self.capacity;
+            if self.record_len_at(offset).is_some() && self.read_u64(HEADER_LEN + offset as usize + 4) > seq {
+                break;
+            }
+            self.tail = self.next_record(self.tail);
+        }
+        self.write_u64(TAIL_OFFSET, self.tail);
+    }
+
+    /// Number of records appended since the journal was created
+    pub fn total_appended(&self) -> u64 {
+        self.next_seq
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Schedule write-back of dirty pages without waiting
+    pub fn flush_async(&self) -> Result<()> {
+        Ok(self.mmap.flush_async()?)
+    }
+
+    /// Write back dirty pages and wait for completion
+    pub fn flush(&self) -> Result<()> {
+        Ok(self.mmap.flush()?)
+    }
+
+    /// Length of the record at a physical offset, or `None` for padding
+    fn record_len_at(&self, offset: u64) -> Option<u32> {
+        if self.capacity - offset < RECORD_HEADER_LEN as u64 {
+            return None;
+        }
+        let at = HEADER_LEN + offset as usize;
+        let len = u32::from_le_bytes(self.mmap[at..at + 4].try_into().ok()?);
+        (len != WRAP_MARKER).then_some(len)
+    }
+
+    /// Logical position of the record after the one at `position`
+    fn next_record(&self, position: u64) -> u64 {
+        let offset = position % self.capacity;
+        match self.record_len_at(offset) {
+            Some(len) => position + RECORD_HEADER_LEN as u64 + len as u64,
+            None => position + (self.capacity - offset),
+        }
+    }
+
+    fn write_data(&mut self, offset: u64, bytes: &[u8]) {
+        let at = HEADER_LEN + offset as usize;
+        self.mmap[at..at + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn read_u64(&self, at: usize) -> u64 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&self.mmap[at..at + 8]);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn write_u64(&mut self, at: usize, value: u64) {
+        self.mmap[at..at + 8].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Journal shared between the event writer and the background flusher
+#[derive(Clone)]
+pub struct SharedJournal {
+    inner: Arc<Mutex<Journal>>,
+}
+
+impl SharedJournal {
+    pub fn new(journal: Journal) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(journal)),
+        }
+    }
+
+    /// Open the journal described by the config
+    pub fn from_config(config: &JournalConfig) -> Result<Self> {
+        Ok(Self::new(Journal::open(&config.path, config.capacity_mb * 1024 * 1024)?))
+    }
+
+    pub fn append(&self, payload: &[u8]) -> Result<u64> {
+        self.lock().append(payload)
+    }
+
+    pub fn records(&self) -> Result<Vec<(u64, Vec<u8>)>> {
+        Ok(self.lock().records())
+    }
+
+    pub fn flush(&self) -> Result<()> {
+        self.lock().flush()
+    }
+
+    /// Append every event published on the bus, encoded with `codec`
+    pub fn capture(&self, bus: &EventBus, codec: Arc<dyn Codec>) -> tokio::task::JoinHandle<()> {
+        let journal = self.clone();
+        let mut subscription = bus.subscribe_encoded(codec);
+        tokio::spawn(async move {
+            loop {
+                match subscription.recv().await {
+                    Ok(Some(bytes)) => {
+                        if let Err(e) = journal.append(&bytes) {
+                            error!("Failed to journal event: {}", e);
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => error!("Failed to encode event for journal: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Periodically schedule write-back of the mapping
+    pub fn start_flusher(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let journal = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let result = journal.lock().flush_async();
+                if let Err(e) = result {
+                    warn!("Journal flush failed: {}", e);
+                }
+            }
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Journal> {
+        lock_or_recover(&self.inner, "Journal")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_overwrites_oldest_and_reopens() {
+        let path = std::env::temp_dir().join(format!("journal-{}.bin", uuid::Uuid::new_v4()));
+        {
+            let mut journal = Journal::open(&path, 100).unwrap();
+            for i in 0u8..20 {
+                journal.append(&[i; 10]).unwrap();
+            }
+            assert!(journal.append(&[0; 100]).is_err());
+
+            let records = journal.records();
+            // 22-byte records, four fit in 100 bytes
+            assert_eq!(records.len(), 4);
+            assert_eq!(records.first().unwrap(), &(16, vec![16; 10]));
+            assert_eq!(records.last().unwrap(), &(19, vec![19; 10]));
+            journal.flush().unwrap();
+        }
+
+        let mut journal = Journal::open(&path, 100).unwrap();
+        assert_eq!(journal.total_appended(), 20);
+        assert_eq!(journal.append(b"after reopen").unwrap(), 20);
+        assert_eq!(journal.records().last().unwrap().1, b"after reopen".to_vec());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,307 @@
+use anyhow::{Context, Result};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::dex::DexType;
+use crate::models::{ArbitrageExecution, ArbitrageOpportunity, ExecutionStatus, OpportunityStatus, Pool, Token};
+use crate::services::codec::{BincodeCodec, Codec};
+use crate::services::database::{BufferedWrite, DatabaseService};
+use crate::services::events::BotEvent;
+use crate::services::journal::Journal;
+
+/// Records between progress lines
+const PROGRESS_EVERY: usize = 1000;
+
+const DEXES: [DexType; 7] = [
+    DexType::Raydium,
+    DexType::Meteora,
+    DexType::Whirlpool,
+    DexType::Pump,
+    DexType::OrcaLegacy,
+    DexType::Phoenix,
+    DexType::Jupiter,
+];
+
+/// What an import wrote and passed over
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub files: usize,
+    pub records: usize,
+    /// Buffered database writes applied
+    pub writes: usize,
+    pub opportunities: usize,
+    pub executions: usize,
+    /// Records the database already held
+    pub existing: usize,
+    /// Records that could not be decoded, or executions whose opportunity fell out of the ring
+    pub skipped: usize,
+}
+
+impl ImportReport {
+    pub fn render(&self) -> String {
+        format!(
+            "{} files, {} records: {} buffered writes, {} opportunities and {} executions imported, \
+             {} already present, {} skipped",
+            self.files, self.records, self.writes, self.opportunities, self.executions, self.existing, self.skipped
+        )
+    }
+}
+
+/// The journal files to import: `path` itself, or the `*.journal` files in it by name
+pub fn journal_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+        .with_context(|| format!("Cannot read journal directory {}", path.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "journal"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Tokens as far as an event records them: the mint, which also stands in for the symbol
+fn token(mint: &str) -> Option<Token> {
+    let address = Pubkey::from_str(mint).ok()?;
+    Some(Token::new(address, mint.to_string(), mint.to_string(), 0))
+}
+
+fn pool(address: &str, dex: &str, base: &Token, quote: &Token) -> Option<Pool> {
+    let dex_type = DEXES.into_iter().find(|d| d.to_string() == dex)?;
+    let pool_address = Pubkey::from_str(address).ok()?;
+    Some(Pool::new(address.to_string(), dex_type, base.clone(), quote.clone(), pool_address, Pubkey::default(), Pubkey::default()))
+}
+
+/// An opportunity rebuilt from its detection event. Pool reserves and token metadata are not
+/// journaled, so only what reports use is filled in.
+fn opportunity_from(event: BotEvent) -> Option<ArbitrageOpportunity> {
+    let BotEvent::OpportunityDetected {
+        id,
+        base_mint,
+        quote_mint,
+        buy_pool,
+        sell_pool,
+        buy_dex,
+        sell_dex,
+        profit_percentage,
+        estimated_profit,
+        timestamp,
+    } = event
+    else {
+        return None;
+    };
+    let (base, quote) = (token(&base_mint)?, token(&quote_mint)?);
+    let buy_pool = pool(&buy_pool, &buy_dex, &base, &quote)?;
+    let sell_pool = pool(&sell_pool, &sell_dex, &base, &quote)?;
+
+    let mut opportunity = ArbitrageOpportunity::new(base, quote, buy_pool, sell_pool);
+    opportunity.id = id;
+    opportunity.profit_percentage = profit_percentage;
+    opportunity.estimated_profit = estimated_profit;
+    opportunity.net_profit = estimated_profit;
+    opportunity.timestamp = timestamp;
+    // Anything never executed has long since lapsed
+    opportunity.status = OpportunityStatus::Expired;
+    Some(opportunity)
+}
+
+/// Replays journals from a memory-only run into the database.
+///
+/// Event journals are rebuilt into opportunities and executions, inserted only where the database
+/// has no record with that id; write buffers are applied as they would have been. Running the
+/// same import twice changes nothing the second time.
+pub struct JournalImporter<'a> {
+    database: &'a DatabaseService,
+    /// Codec the event journals were written with
+    codec: Arc<dyn Codec>,
+}
+
+impl<'a> JournalImporter<'a> {
+    pub fn new(database: &'a DatabaseService) -> Self {
+        Self { database, codec: Arc::new(BincodeCodec) }
+    }
+
+    pub fn with_codec(mut self, codec: Arc<dyn Codec>) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    pub async fn import_all(&self, paths: &[PathBuf]) -> Result<ImportReport> {
+        let mut report = ImportReport::default();
+        for (index, path) in paths.iter().enumerate() {
+            info!("Importing {} ({}/{})", path.display(), index + 1, paths.len());
+            self.import_file(path, &mut report).await?;
+            report.files += 1;
+        }
+        Ok(report)
+    }
+
+    async fn import_file(&self, path: &Path, report: &mut ImportReport) -> Result<()> {
+        let records = Journal::open_existing(path)?.records();
+        let mut opportunities: Vec<ArbitrageOpportunity> = Vec::new();
+        let mut executions: Vec<BotEvent> = Vec::new();
+
+        for (index, (_, payload)) in records.iter().enumerate() {
+            report.records += 1;
+            // Write buffers hold JSON, event journals the configured codec
+            if let Ok(write) = serde_json::from_slice::<BufferedWrite>(payload) {
+                if self.database.import_write(&write).await? {
+                    report.writes += 1;
+                } else {
+                    report.existing += 1;
+                }
+            } else {
+                match self.codec.decode(payload) {
+                    Ok(event @ BotEvent::OpportunityDetected { .. }) => match opportunity_from(event) {
+                        Some(opportunity) => opportunities.push(opportunity),
+                        None => report.skipped += 1,
+                    },
+                    Ok(event @ BotEvent::ExecutionFinished { .. }) => executions.push(event),
+                    // Alerts and state changes have no table
+                    Ok(_) => {}
+                    Err(_) => report.skipped += 1,
+                }
+            }
+            if (index + 1) % PROGRESS_EVERY == 0 {
+                info!("{}: read {}/{} records", path.display(), index + 1, records.len());
+            }
+        }
+
+        let mut by_id: HashMap<String, usize> = HashMap::new();
+        for (index, opportunity) in opportunities.iter().enumerate() {
+            by_id.insert(opportunity.id.clone(), index);
+        }
+        let mut rebuilt = Vec::with_capacity(executions.len());
+        for event in executions {
+            let BotEvent::ExecutionFinished { id, opportunity_id, status, signature, actual_profit, error, timestamp } = event else {
+                continue;
+            };
+            let Some(&index) = by_id.get(&opportunity_id) else {
+                warn!("Execution {} refers to opportunity {} missing from the journal; skipped", id, opportunity_id);
+                report.skipped += 1;
+                continue;
+            };
+            let opportunity = &mut opportunities[index];
+            opportunity.status = match status {
+                ExecutionStatus::Confirmed => OpportunityStatus::Completed,
+                ExecutionStatus::Failed => OpportunityStatus::Failed,
+                ExecutionStatus::Cancelled => OpportunityStatus::Cancelled,
+                _ => OpportunityStatus::Executing,
+            };
+            let mut execution = ArbitrageExecution::new(opportunity.clone());
+            execution.id = id;
+            execution.execution_status = status;
+            execution.transaction_signature = signature;
+            execution.actual_profit = actual_profit;
+            execution.error_message = error;
+            execution.execution_time = timestamp;
+            rebuilt.push(execution);
+        }
+
+        for opportunity in &opportunities {
+            if self.database.import_opportunity(opportunity).await? {
+                report.opportunities += 1;
+            } else {
+                report.existing += 1;
+            }
+        }
+        for execution in &rebuilt {
+            if self.database.import_execution(execution).await? {
+                report.executions += 1;
+            } else {
+                report.existing += 1;
+            }
+        }
+        info!("{}: {} records done", path.display(), records.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DatabaseConfig;
+    use crate::services::codec::JsonCodec;
+    use rust_decimal::Decimal;
+
+    #[tokio::test]
+    async fn test_import_is_idempotent() {
+        let dir = std::env::temp_dir().join(format!("journal-import-{}", uuid::Uuid::new_v4()));
+        // JSON events sit beside the JSON write buffer without being mistaken for writes
+        let codec = JsonCodec;
+        let (sol, usdc) = (Pubkey::new_unique().to_string(), Pubkey::new_unique().to_string());
+        let detected = BotEvent::OpportunityDetected {
+            id: "opp-1".to_string(),
+            base_mint: sol.clone(),
+            quote_mint: usdc.clone(),
+            buy_pool: Pubkey::new_unique().to_string(),
+            sell_pool: Pubkey::new_unique().to_string(),
+            buy_dex: "Raydium".to_string(),
+            sell_dex: "Meteora".to_string(),
+            profit_percentage: Decimal::new(12, 3),
+            estimated_profit: Decimal::new(5, 1),
+            timestamp: chrono::Utc::now(),
+        };
+        let finished = |id: &str, opportunity_id: &str| BotEvent::ExecutionFinished {
+            id: id.to_string(),
+            opportunity_id: opportunity_id.to_string(),
+            status: ExecutionStatus::Confirmed,
+            signature: Some("sig".to_string()),
+            actual_profit: Some(Decimal::new(4, 1)),
+            error: None,
+            timestamp: chrono::Utc::now(),
+        };
+        {
+            let mut events = Journal::open(dir.join("events.journal"), 64 * 1024).unwrap();
+            events.append(&codec.encode(&detected).unwrap()).unwrap();
+            events.append(&codec.encode(&BotEvent::engine_state(true)).unwrap()).unwrap();
+            events.append(&codec.encode(&finished("exec-1", "opp-1")).unwrap()).unwrap();
+            // Its opportunity was overwritten in the ring
+            events.append(&codec.encode(&finished("exec-2", "opp-gone")).unwrap()).unwrap();
+            events.flush().unwrap();
+
+            let strategy = crate::arbitrage::ArbitrageEngine::default_strategy(&crate::config::AppConfig::bundled_default().arbitrage);
+            let mut buffer = Journal::open(dir.join("db_buffer.journal"), 64 * 1024).unwrap();
+            buffer.append(&serde_json::to_vec(&BufferedWrite::SaveStrategy(strategy)).unwrap()).unwrap();
+            buffer.flush().unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "not a journal").unwrap();
+
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            timeout_seconds: 5,
+            buffer_path: String::new(),
+            buffer_capacity_mb: 1,
+            recovery_interval_seconds: 1,
+            capture_pool_snapshots: false,
+        };
+        let db = DatabaseService::new(&config).await.unwrap();
+        let files = journal_files(&dir).unwrap();
+        assert_eq!(files.len(), 2);
+
+        let importer = JournalImporter::new(&db).with_codec(Arc::new(JsonCodec));
+        let first = importer.import_all(&files).await.unwrap();
+        assert_eq!((first.records, first.writes, first.opportunities, first.executions), (5, 1, 1, 1));
+        assert_eq!((first.existing, first.skipped), (0, 1));
+
+        let executions = db.get_executions_by_status(ExecutionStatus::Confirmed).await.unwrap();
+        assert_eq!(executions.len(), 1);
+        assert_eq!(executions[0].actual_profit, Some(Decimal::new(4, 1)));
+        assert_eq!(executions[0].opportunity.pair(), format!("{}/{}", sol, usdc));
+        assert_eq!(db.get_opportunities_by_status(OpportunityStatus::Completed).await.unwrap().len(), 1);
+        assert_eq!(db.get_strategies().await.unwrap().len(), 1);
+
+        // The strategy upsert is reapplied, everything else is already there
+        let second = importer.import_all(&files).await.unwrap();
+        assert_eq!((second.writes, second.opportunities, second.executions, second.existing), (1, 0, 0, 2));
+        assert_eq!(db.get_executions_by_status(ExecutionStatus::Confirmed).await.unwrap().len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::ConnectionConfig,
+    services::metrics::MetricsRegistry,
+    utils::panic_guard::lock_or_recover,
+};
+
+/// Counts connections a client opens; pooled requests that reuse a connection don't count
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionStats {
+    opened: Arc<AtomicU64>,
+}
+
+impl ConnectionStats {
+    pub fn opened(&self) -> u64 {
+        self.opened.load(Ordering::Relaxed)
+    }
+}
+
+/// Connector layer that increments `ConnectionStats` for every new connection
+#[derive(Clone)]
+struct CountConnections {
+    stats: ConnectionStats,
+}
+
+impl<S> tower_layer::Layer<S> for CountConnections {
+    type Service = CountingConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CountingConnector { inner, stats: self.stats.clone() }
+    }
+}
+
+#[derive(Clone)]
+struct CountingConnector<S> {
+    inner: S,
+    stats: ConnectionStats,
+}
+
+impl<S, R> tower_service::Service<R> for CountingConnector<S>
+where
+    S: tower_service::Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        self.stats.opened.fetch_add(1, Ordering::Relaxed);
+        self.inner.call(request)
+    }
+}
+
+/// HTTP client tuned to keep connections (and their TLS sessions) alive between submits
+pub fn tuned_client(config: &ConnectionConfig, timeout: Duration) -> Result<(reqwest::Client, ConnectionStats)> {
+    let stats = ConnectionStats::default();
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .tcp_nodelay(true)
+        .tcp_keepalive(Duration::from_secs(config.tcp_keepalive_seconds.max(1)))
+        .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_seconds.max(1)))
+        .http2_keep_alive_interval(Duration::from_secs(config.tcp_keepalive_seconds.max(1)))
+        .http2_keep_alive_while_idle(true)
+        .connector_layer(CountConnections { stats: stats.clone() })
+        .build()?;
+    Ok((client, stats))
+}
+
+/// An endpoint whose connections should be kept warm
+#[async_trait]
+pub trait KeepAliveTarget: Send + Sync {
+    fn endpoint_name(&self) -> String;
+
+    /// Cheapest request the endpoint serves
+    async fn ping(&self) -> Result<()>;
+
+    fn connection_stats(&self) -> ConnectionStats;
+}
+
+/// Submit-path endpoints to keep warm. Held weakly, so an endpoint is dropped once its owner is.
+#[derive(Clone, Default)]
+pub struct KeepAliveRegistry {
+    targets: Arc<Mutex<Vec<Weak<dyn KeepAliveTarget>>>>,
+}
+
+impl KeepAliveRegistry {
+    /// The process-wide registry. Endpoints are built deep inside adapters and senders,
+    /// so they register here rather than being threaded through to the worker.
+    pub fn global() -> &'static KeepAliveRegistry {
+        static REGISTRY: OnceLock<KeepAliveRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(KeepAliveRegistry::default)
+    }
+
+    pub fn register(&self, target: Arc<dyn KeepAliveTarget>) {
+        lock_or_recover(&self.targets, "keep-alive registry").push(Arc::downgrade(&target));
+    }
+
+    /// Live targets, pruning dropped ones
+    pub fn targets(&self) -> Vec<Arc<dyn KeepAliveTarget>> {
+        let mut targets = lock_or_recover(&self.targets, "keep-alive registry");
+        targets.retain(|target| target.strong_count() > 0);
+        targets.iter().filter_map(Weak::upgrade).collect()
+    }
+}
+
+/// Pings registered endpoints on an interval and reports latency and reconnects
+pub struct KeepAliveWorker {
+    registry: KeepAliveRegistry,
+    metrics: MetricsRegistry,
+    interval: Duration,
+    /// Connections each endpoint had open after its first successful ping
+    baselines: HashMap<String, u64>,
+}
+
+impl KeepAliveWorker {
+    pub fn new(registry: KeepAliveRegistry, metrics: MetricsRegistry, interval: Duration) -> Self {
+        Self {
+            registry,
+            metrics,
+            interval,
+            baselines: HashMap::new(),
+        }
+    }
+
+    /// Ping every endpoint once so the first submit doesn't pay for the handshake
+    pub async fn prewarm(&mut self) {
+        let started = Instant::now();
+        let targets = self.registry.targets();
+        self.ping_all().await;
+        info!("Pre-warmed {} endpoint connections in {}ms", targets.len(), started.elapsed().as_millis());
+    }
+
+    pub async fn start(mut self) -> Result<()> {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            self.ping_all().await;
+        }
+    }
+
+    async fn ping_all(&mut self) {
+        for target in self.registry.targets() {
+            let name = target.endpoint_name();
+            let labels = [("endpoint", name.as_str())];
+            let started = Instant::now();
+
+            match target.ping().await {
+                Ok(()) => {
+                    let latency = started.elapsed().as_secs_f64() * 1000.0;
+                    debug!("Keep-alive ping to {} took {:.1}ms", name, latency);
+                    self.metrics.set(&MetricsRegistry::labeled("endpoint_ping_ms", &labels), latency).await;
+                }
+                Err(e) => {
+                    warn!("Keep-alive ping to {} failed: {}", name, e);
+                    self.metrics
+                        .increment(&MetricsRegistry::labeled("endpoint_ping_failures", &labels), 1.0)
+                        .await;
+                    continue;
+                }
+            }
+
+            let opened = target.connection_stats().opened();
+            let baseline = *self.baselines.entry(name.clone()).or_insert(opened);
+            self.metrics
+                .set(
+                    &MetricsRegistry::labeled("endpoint_reconnects", &labels),
+                    opened.saturating_sub(baseline) as f64,
+                )
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    struct HttpTarget {
+        client: reqwest::Client,
+        stats: ConnectionStats,
+        url: String,
+    }
+
+    #[async_trait]
+    impl KeepAliveTarget for HttpTarget {
+        fn endpoint_name(&self) -> String {
+            "local".to_string()
+        }
+
+        async fn ping(&self) -> Result<()> {
+            self.client.get(&self.url).send().await?.error_for_status()?;
+            Ok(())
+        }
+
+        fn connection_stats(&self) -> ConnectionStats {
+            self.stats.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pings_reuse_one_connection() {
+        // Minimal keep-alive HTTP server
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let _ = socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok").await;
+                    }
+                });
+            }
+        });
+
+        let (client, stats) = tuned_client(&ConnectionConfig::default(), Duration::from_secs(5)).unwrap();
+        let target: Arc<dyn KeepAliveTarget> = Arc::new(HttpTarget { client, stats: stats.clone(), url });
+        let registry = KeepAliveRegistry::default();
+        registry.register(target.clone());
+
+        let metrics = MetricsRegistry::new();
+        let mut worker = KeepAliveWorker::new(registry.clone(), metrics.clone(), Duration::from_secs(1));
+        worker.prewarm().await;
+        worker.ping_all().await;
+        worker.ping_all().await;
+
+        assert_eq!(stats.opened(), 1);
+        assert_eq!(metrics.get(&MetricsRegistry::labeled("endpoint_reconnects", &[("endpoint", "local")])).await, Some(0.0));
+
+        drop(target);
+        assert!(registry.targets().is_empty());
+    }
+}
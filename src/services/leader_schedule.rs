@@ -0,0 +1,200 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::{debug, info};
+
+use crate::config::SubmissionConfig;
+use crate::services::solana::SolanaService;
+
+/// Target time between slots
+pub const SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// How a transaction should be submitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionRoute {
+    /// Send as a Jito bundle
+    Bundle,
+    /// Send through regular RPC with priority fees
+    Rpc,
+}
+
+/// Submission decision for the upcoming slots
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmissionPlan {
+    pub route: SubmissionRoute,
+    /// Multiplier applied to the tip (bundles) or priority fee (RPC)
+    pub fee_multiplier: f64,
+    /// Slots to wait before submitting
+    pub delay_slots: u64,
+    pub leader: Option<String>,
+}
+
+impl Default for SubmissionPlan {
+    /// Bundle right away, for when the leader is unknown
+    fn default() -> Self {
+        Self { route: SubmissionRoute::Bundle, fee_multiplier: 1.0, delay_slots: 0, leader: None }
+    }
+}
+
+/// Tracks the epoch leader schedule and decides how to submit based on upcoming leaders
+pub struct LeaderScheduleTracker {
+    config: SubmissionConfig,
+    jito_validators: HashSet<String>,
+    /// Absolute slot -> leader identity
+    leaders: HashMap<u64, String>,
+    epoch_start_slot: u64,
+    epoch_end_slot: u64,
+}
+
+impl LeaderScheduleTracker {
+    pub fn new(config: SubmissionConfig) -> Self {
+        let jito_validators = config.jito_validators.iter().cloned().collect();
+        Self {
+            config,
+            jito_validators,
+            leaders: HashMap::new(),
+            epoch_start_slot: 0,
+            epoch_end_slot: 0,
+        }
+    }
+
+    /// Load a schedule of identity -> slot indices relative to `epoch_start_slot`
+    pub fn load_schedule(
+        &mut self,
+        epoch_start_slot: u64,
+        slots_in_epoch: u64,
+        schedule: &HashMap<String, Vec<usize>>,
+    ) {
+        self.leaders.clear();
+        for (identity, slots) in schedule {
+            for slot_index in slots {
+                self.leaders.insert(epoch_start_slot + *slot_index as u64, identity.clone());
+            }
+        }
+        self.epoch_start_slot = epoch_start_slot;
+        self.epoch_end_slot = epoch_start_slot + slots_in_epoch;
+    }
+
+    /// Refresh the schedule from RPC when the current epoch is not loaded
+    pub async fn refresh(&mut self, solana: &SolanaService) -> Result<()> {
+        let epoch_info = solana.get_epoch_info().await?;
+        if self.covers(epoch_info.absolute_slot) {
+            return Ok(());
+        }
+
+        let epoch_start_slot = epoch_info.absolute_slot - epoch_info.slot_index;
+        let schedule = solana.get_leader_schedule().await?.unwrap_or_default();
+        self.load_schedule(epoch_start_slot, epoch_info.slots_in_epoch, &schedule);
+
+        info!(
+            "Loaded leader schedule for epoch {} ({} slots)",
+            epoch_info.epoch,
+            self.leaders.len()
+        );
+        Ok(())
+    }
+
+    /// Whether the loaded schedule covers the slot
+    pub fn covers(&self, slot: u64) -> bool {
+        !self.leaders.is_empty() && slot >= self.epoch_start_slot && slot < self.epoch_end_slot
+    }
+
+    pub fn leader_at(&self, slot: u64) -> Option<&str> {
+        self.leaders.get(&slot).map(|s| s.as_str())
+    }
+
+    pub fn is_jito_leader(&self, slot: u64) -> bool {
+        self.leader_at(slot)
+            .map(|leader| self.jito_validators.contains(leader))
+            .unwrap_or(false)
+    }
+
+    /// Decide how to submit at `current_slot`
+    pub fn plan(&self, current_slot: u64) -> SubmissionPlan {
+        let default_plan = SubmissionPlan::default();
+
+        if !self.config.leader_aware || !self.covers(current_slot) {
+            return default_plan;
+        }
+
+        if self.is_jito_leader(current_slot) {
+            return SubmissionPlan {
+                leader: self.leader_at(current_slot).map(|s| s.to_string()),
+                ..default_plan
+            };
+        }
+
+        // Wait for an upcoming Jito leader if one is close enough
+        for delay in 1..=self.config.max_delay_slots {
+            let slot = current_slot + delay;
+            if self.is_jito_leader(slot) {
+                debug!("Delaying submission {} slot(s) for Jito leader", delay);
+                return SubmissionPlan {
+                    route: SubmissionRoute::Bundle,
+                    fee_multiplier: 1.0,
+                    delay_slots: delay,
+                    leader: self.leader_at(slot).map(|s| s.to_string()),
+                };
+            }
+        }
+
+        // No Jito leader soon; a bundle would not land, so bid up priority fees instead
+        SubmissionPlan {
+            route: SubmissionRoute::Rpc,
+            fee_multiplier: self.config.non_jito_fee_multiplier,
+            delay_slots: 0,
+            leader: self.leader_at(current_slot).map(|s| s.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(max_delay_slots: u64) -> LeaderScheduleTracker {
+        let config = SubmissionConfig {
+            leader_aware: true,
+            jito_validators: vec!["jito".to_string()],
+            max_delay_slots,
+            non_jito_fee_multiplier: 2.0,
+            ..Default::default()
+        };
+        let mut tracker = LeaderScheduleTracker::new(config);
+
+        let mut schedule = HashMap::new();
+        schedule.insert("jito".to_string(), vec![0, 1, 2, 3]);
+        schedule.insert("other".to_string(), vec![4, 5, 6, 7, 8, 9, 10, 11]);
+        tracker.load_schedule(1000, 12, &schedule);
+        tracker
+    }
+
+    #[test]
+    fn test_plan_by_leader() {
+        let tracker = tracker(1);
+
+        let plan = tracker.plan(1001);
+        assert_eq!(plan.route, SubmissionRoute::Bundle);
+        assert_eq!(plan.delay_slots, 0);
+
+        let plan = tracker.plan(1005);
+        assert_eq!(plan.route, SubmissionRoute::Rpc);
+        assert_eq!(plan.fee_multiplier, 2.0);
+
+        // Outside the loaded epoch falls back to bundles
+        assert_eq!(tracker.plan(5000).route, SubmissionRoute::Bundle);
+    }
+
+    #[test]
+    fn test_plan_delays_for_jito_leader() {
+        let mut tracker = tracker(2);
+        let mut schedule = HashMap::new();
+        schedule.insert("other".to_string(), vec![0]);
+        schedule.insert("jito".to_string(), vec![1, 2]);
+        tracker.load_schedule(2000, 3, &schedule);
+
+        let plan = tracker.plan(2000);
+        assert_eq!(plan.route, SubmissionRoute::Bundle);
+        assert_eq!(plan.delay_slots, 1);
+    }
+}
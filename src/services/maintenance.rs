@@ -0,0 +1,200 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::config::{MaintenanceConfig, MaintenanceWindowConfig};
+use crate::services::alert_rules::{AlertAction, FiredAlert};
+use crate::utils::cron::CronSchedule;
+
+/// A recurring maintenance window
+#[derive(Debug, Clone)]
+pub struct MaintenanceWindow {
+    pub name: String,
+    schedule: CronSchedule,
+    duration: Duration,
+}
+
+/// Start of the minute containing `time`
+fn minute_of(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(time)
+}
+
+impl MaintenanceWindow {
+    pub fn from_config(config: &MaintenanceWindowConfig) -> Result<Self> {
+        if config.duration_minutes == 0 {
+            anyhow::bail!("Maintenance window '{}' has no duration", config.name);
+        }
+        Ok(Self {
+            name: config.name.clone(),
+            schedule: CronSchedule::parse(&config.schedule)
+                .map_err(|e| anyhow::anyhow!("Maintenance window '{}': {}", config.name, e))?,
+            duration: Duration::minutes(config.duration_minutes as i64),
+        })
+    }
+
+    /// Opening time of the occurrence covering `now`, if the window is open
+    pub fn open_since(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let minute = minute_of(now);
+        (0..self.duration.num_minutes())
+            .map(|back| minute - Duration::minutes(back))
+            .find(|start| self.schedule.matches(*start))
+    }
+
+    /// Next opening after `now` and no later than `horizon` ahead
+    pub fn next_opening(&self, now: DateTime<Utc>, horizon: Duration) -> Option<DateTime<Utc>> {
+        let minute = minute_of(now);
+        (1..=horizon.num_minutes())
+            .map(|ahead| minute + Duration::minutes(ahead))
+            .find(|start| self.schedule.matches(*start))
+    }
+}
+
+/// Turns the clock into notices, pauses and resumes for the configured windows
+pub struct MaintenanceSchedule {
+    windows: Vec<MaintenanceWindow>,
+    notice: Duration,
+    channel: String,
+    /// Opening each window was last announced for
+    announced: HashMap<String, DateTime<Utc>>,
+    paused: bool,
+}
+
+impl MaintenanceSchedule {
+    pub fn from_config(config: &MaintenanceConfig) -> Result<Self> {
+        Ok(Self {
+            windows: config.windows.iter().map(MaintenanceWindow::from_config).collect::<Result<_>>()?,
+            notice: Duration::minutes(config.notice_minutes as i64),
+            channel: config.alert_channel.clone(),
+            announced: HashMap::new(),
+            paused: false,
+        })
+    }
+
+    pub fn windows(&self) -> &[MaintenanceWindow] {
+        &self.windows
+    }
+
+    fn alert(&self, window: &str, action: AlertAction, message: String, now: DateTime<Utc>) -> FiredAlert {
+        FiredAlert {
+            rule_name: format!("maintenance:{}", window),
+            action,
+            message,
+            value: if self.paused { 1.0 } else { 0.0 },
+            timestamp: now,
+        }
+    }
+
+    /// Alerts due at `now`: notice ahead of each opening, a pause when a window opens and a
+    /// resume once no window is open
+    pub fn evaluate(&mut self, now: DateTime<Utc>) -> Vec<FiredAlert> {
+        let mut alerts = Vec::new();
+        let notify = AlertAction::Notify(self.channel.clone());
+
+        for window in &self.windows {
+            let Some(opening) = window.next_opening(now, self.notice) else {
+                continue;
+            };
+            if self.announced.insert(window.name.clone(), opening) != Some(opening) {
+                let message = format!(
+                    "Maintenance window '{}' opens at {}; execution pauses for {} minutes",
+                    window.name,
+                    opening.format("%H:%M UTC"),
+                    window.duration.num_minutes()
+                );
+                alerts.push(self.alert(&window.name, notify.clone(), message, now));
+            }
+        }
+
+        let open = self
+            .windows
+            .iter()
+            .find_map(|window| window.open_since(now).map(|since| (window, since + window.duration)));
+        match open {
+            Some((window, until)) if !self.paused => {
+                self.paused = true;
+                let message = format!("Maintenance window '{}' open until {}; pausing execution", window.name, until.format("%H:%M UTC"));
+                alerts.push(self.alert(&window.name, notify.clone(), message.clone(), now));
+                alerts.push(self.alert(&window.name, AlertAction::Pause, message, now));
+            }
+            None if self.paused => {
+                self.paused = false;
+                let message = "Maintenance over; resuming execution".to_string();
+                alerts.push(self.alert("closed", notify, message.clone(), now));
+                alerts.push(self.alert("closed", AlertAction::Resume, message, now));
+            }
+            _ => {}
+        }
+        alerts
+    }
+}
+
+/// Periodically evaluates the maintenance schedule and dispatches its alerts
+pub struct MaintenanceWorker {
+    schedule: MaintenanceSchedule,
+    alert_sender: mpsc::Sender<FiredAlert>,
+    interval: std::time::Duration,
+}
+
+impl MaintenanceWorker {
+    pub fn new(schedule: MaintenanceSchedule, alert_sender: mpsc::Sender<FiredAlert>, interval: std::time::Duration) -> Self {
+        Self {
+            schedule,
+            alert_sender,
+            interval,
+        }
+    }
+
+    /// Start the worker loop
+    pub async fn start(mut self) -> Result<()> {
+        info!("Starting maintenance worker with {} windows", self.schedule.windows().len());
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            for alert in self.schedule.evaluate(Utc::now()) {
+                if let Err(e) = self.alert_sender.send(alert).await {
+                    warn!("Failed to dispatch maintenance alert: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_window_notices_pauses_and_resumes() {
+        let config = MaintenanceConfig {
+            enabled: true,
+            windows: vec![MaintenanceWindowConfig {
+                name: "rpc".to_string(),
+                schedule: "0 4 * * 0".to_string(),
+                duration_minutes: 30,
+            }],
+            ..MaintenanceConfig::default()
+        };
+        let mut schedule = MaintenanceSchedule::from_config(&config).unwrap();
+        let actions = |alerts: Vec<FiredAlert>| alerts.into_iter().map(|a| a.action).collect::<Vec<_>>();
+        let notify = AlertAction::Notify("ops".to_string());
+        // Sunday 2026-10-18
+        let at = |hour, minute| Utc.with_ymd_and_hms(2026, 10, 18, hour, minute, 10).unwrap();
+
+        assert!(schedule.evaluate(at(3, 40)).is_empty());
+        assert_eq!(actions(schedule.evaluate(at(3, 50))), vec![notify.clone()]);
+        // Notice is given once per opening
+        assert!(schedule.evaluate(at(3, 55)).is_empty());
+        assert_eq!(actions(schedule.evaluate(at(4, 0))), vec![notify.clone(), AlertAction::Pause]);
+        assert!(schedule.evaluate(at(4, 29)).is_empty());
+        assert_eq!(actions(schedule.evaluate(at(4, 30))), vec![notify, AlertAction::Resume]);
+
+        // Starting inside a window pauses straight away
+        let mut restarted = MaintenanceSchedule::from_config(&config).unwrap();
+        assert_eq!(actions(restarted.evaluate(at(4, 15)))[1], AlertAction::Pause);
+    }
+}
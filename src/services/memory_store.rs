@@ -1,8 +1,13 @@
 use anyhow::Result;
 use rust_decimal::Decimal;
-use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::{RwLock, Mutex};
+use tokio::sync::{RwLock, RwLockReadGuard, Mutex};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
@@ -11,11 +16,25 @@ use crate::models::{
     OpportunityStatus, ExecutionStatus, RiskScore
 };
 use crate::dex::DexType;
+use crate::utils::clock::{SharedClock, SystemClock};
+use crate::utils::versioning::{self, FormatSpec};
+
+const SNAPSHOT_FORMAT: FormatSpec = FormatSpec {
+    name: "memory_store_snapshot",
+    version: 1,
+    migrations: &[versioning::unchanged],
+};
+
+/// Opportunity shards of a store built without an explicit count
+pub const DEFAULT_OPPORTUNITY_SHARDS: usize = 16;
+
+/// Fewest opportunities a shard holds; smaller stores use fewer shards
+const MIN_SHARD_CAPACITY: usize = 256;
 
 /// High-performance in-memory storage service optimized for high-frequency trading
 pub struct MemoryStore {
     // Use RwLock to separate reads/writes and improve concurrency
-    opportunities: Arc<RwLock<HashMap<String, ArbitrageOpportunity>>>,
+    opportunities: Arc<ShardedOpportunities>,
     strategies: Arc<RwLock<HashMap<String, ArbitrageStrategy>>>,
     executions: Arc<RwLock<VecDeque<ArbitrageExecution>>>,
     
@@ -26,10 +45,198 @@ pub struct MemoryStore {
     max_opportunities: usize,
     max_executions: usize,
     cleanup_interval: std::time::Duration,
+    clock: SharedClock,
+}
+
+/// Opportunities by id, with secondary indexes kept in step on every insert, update and removal
+#[derive(Debug, Default)]
+struct OpportunityIndex {
+    by_id: HashMap<String, ArbitrageOpportunity>,
+    by_status: HashMap<OpportunityStatus, HashSet<String>>,
+    /// Keyed by buy then sell DEX
+    by_pair: HashMap<(DexType, DexType), HashSet<String>>,
+    by_profit: BTreeSet<(Decimal, String)>,
+    by_age: BTreeSet<(DateTime<Utc>, String)>,
+}
+
+impl OpportunityIndex {
+    fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    fn get(&self, id: &str) -> Option<&ArbitrageOpportunity> {
+        self.by_id.get(id)
+    }
+
+    fn values(&self) -> impl Iterator<Item = &ArbitrageOpportunity> {
+        self.by_id.values()
+    }
+
+    fn pair(opportunity: &ArbitrageOpportunity) -> (DexType, DexType) {
+        (opportunity.buy_pool.dex_type.clone(), opportunity.sell_pool.dex_type.clone())
+    }
+
+    fn oldest(&self) -> Option<&(DateTime<Utc>, String)> {
+        self.by_age.first()
+    }
+
+    /// Insert or replace an opportunity, returning whether it is new
+    fn insert(&mut self, opportunity: ArbitrageOpportunity) -> bool {
+        let replaced = self.remove(&opportunity.id).is_some();
+        let id = opportunity.id.clone();
+        self.by_status.entry(opportunity.status.clone()).or_default().insert(id.clone());
+        self.by_pair.entry(Self::pair(&opportunity)).or_default().insert(id.clone());
+        self.by_profit.insert((opportunity.net_profit, id.clone()));
+        self.by_age.insert((opportunity.timestamp, id.clone()));
+        self.by_id.insert(id, opportunity);
+        !replaced
+    }
+
+    fn remove(&mut self, id: &str) -> Option<ArbitrageOpportunity> {
+        let opportunity = self.by_id.remove(id)?;
+        Self::unlink(&mut self.by_status, &opportunity.status, id);
+        Self::unlink(&mut self.by_pair, &Self::pair(&opportunity), id);
+        self.by_profit.remove(&(opportunity.net_profit, opportunity.id.clone()));
+        self.by_age.remove(&(opportunity.timestamp, opportunity.id.clone()));
+        Some(opportunity)
+    }
+
+    fn unlink<K: Eq + std::hash::Hash>(index: &mut HashMap<K, HashSet<String>>, key: &K, id: &str) {
+        if let Some(ids) = index.get_mut(key) {
+            ids.remove(id);
+            if ids.is_empty() {
+                index.remove(key);
+            }
+        }
+    }
+
+    fn set_status(&mut self, id: &str, status: OpportunityStatus) {
+        let Some(opportunity) = self.by_id.get_mut(id) else {
+            return;
+        };
+        if opportunity.status == status {
+            return;
+        }
+        Self::unlink(&mut self.by_status, &opportunity.status, id);
+        self.by_status.entry(status.clone()).or_default().insert(id.to_string());
+        opportunity.status = status;
+    }
+
+    fn with_status<'a>(&'a self, status: &OpportunityStatus) -> impl Iterator<Item = &'a ArbitrageOpportunity> {
+        self.by_status.get(status).into_iter().flatten().filter_map(|id| self.by_id.get(id))
+    }
+
+    fn with_pair<'a>(&'a self, buy_dex: &DexType, sell_dex: &DexType) -> impl Iterator<Item = &'a ArbitrageOpportunity> {
+        self.by_pair
+            .get(&(buy_dex.clone(), sell_dex.clone()))
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.by_id.get(id))
+    }
+
+    /// Opportunities netting at least `min_profit`, most profitable first
+    fn by_profit_from(&self, min_profit: Option<Decimal>) -> impl Iterator<Item = &ArbitrageOpportunity> {
+        let floor = (min_profit.unwrap_or(Decimal::MIN), String::new());
+        self.by_profit.range(floor..).rev().filter_map(|(_, id)| self.by_id.get(id))
+    }
+}
+
+/// Opportunities spread by id over independently locked indexes, so saves and lookups of
+/// different opportunities rarely wait on each other. Capacity is split evenly between shards
+/// and each evicts its own oldest, which with ids hashed uniformly tracks the oldest overall.
+struct ShardedOpportunities {
+    shards: Vec<RwLock<OpportunityIndex>>,
+    shard_capacity: usize,
+    len: AtomicUsize,
+}
+
+impl ShardedOpportunities {
+    fn new(shards: usize, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let shards = shards.min(capacity / MIN_SHARD_CAPACITY).max(1);
+        Self {
+            shards: (0..shards).map(|_| RwLock::new(OpportunityIndex::default())).collect(),
+            shard_capacity: capacity / shards,
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    fn shard(&self, id: &str) -> &RwLock<OpportunityIndex> {
+        let mut hasher = DefaultHasher::new();
+        id.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+
+    fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    async fn get(&self, id: &str) -> Option<ArbitrageOpportunity> {
+        self.shard(id).read().await.get(id).cloned()
+    }
+
+    /// Insert or replace an opportunity, evicting the shard's oldest when a new one would not fit
+    async fn insert(&self, opportunity: ArbitrageOpportunity) {
+        let mut index = self.shard(&opportunity.id).write().await;
+        if index.get(&opportunity.id).is_none() && index.len() >= self.shard_capacity {
+            if let Some((_, oldest)) = index.oldest().cloned() {
+                index.remove(&oldest);
+                self.len.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+        if index.insert(opportunity) {
+            self.len.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    async fn replace(&self, opportunity: &ArbitrageOpportunity) {
+        let mut index = self.shard(&opportunity.id).write().await;
+        if index.get(&opportunity.id).is_some() {
+            index.insert(opportunity.clone());
+        }
+    }
+
+    async fn set_status(&self, id: &str, status: OpportunityStatus) {
+        self.shard(id).write().await.set_status(id, status);
+    }
+
+    /// Read every shard, for queries spanning them; results are gathered by reference and cloned
+    /// once, as opportunities are too large to move around cheaply
+    async fn read_all(&self) -> Vec<RwLockReadGuard<'_, OpportunityIndex>> {
+        let mut guards = Vec::with_capacity(self.shards.len());
+        for shard in &self.shards {
+            guards.push(shard.read().await);
+        }
+        guards
+    }
+
+    async fn expire(&self, now: DateTime<Utc>) {
+        for shard in &self.shards {
+            let mut index = shard.write().await;
+            let expired: Vec<String> = index
+                .values()
+                .filter(|opp| opp.expiry < now)
+                .map(|opp| opp.id.clone())
+                .collect();
+            for id in expired {
+                index.set_status(&id, OpportunityStatus::Expired);
+            }
+        }
+    }
+
+    async fn replace_all(&self, opportunities: Vec<ArbitrageOpportunity>) {
+        for shard in &self.shards {
+            *shard.write().await = OpportunityIndex::default();
+        }
+        self.len.store(0, Ordering::Relaxed);
+        for opportunity in opportunities {
+            self.insert(opportunity).await;
+        }
+    }
 }
 
 /// Storage metrics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct StoreMetrics {
     total_opportunities: u64,
     total_executions: u64,
@@ -52,17 +259,39 @@ impl Default for StoreMetrics {
     }
 }
 
+/// Everything a memory store holds, written to disk so history and metrics survive a restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub opportunities: Vec<ArbitrageOpportunity>,
+    pub strategies: Vec<ArbitrageStrategy>,
+    /// Oldest first
+    pub executions: Vec<ArbitrageExecution>,
+    metrics: StoreMetrics,
+}
+
 impl MemoryStore {
     /// Create a new memory store instance
     pub fn new(max_opportunities: usize, max_executions: usize) -> Self {
+        Self::new_with_clock(max_opportunities, max_executions, SystemClock::shared())
+    }
+
+    /// Memory store whose expiry, retention and statistics windows follow `clock`
+    pub fn new_with_clock(max_opportunities: usize, max_executions: usize, clock: SharedClock) -> Self {
+        Self::new_sharded(max_opportunities, max_executions, DEFAULT_OPPORTUNITY_SHARDS, clock)
+    }
+
+    /// Memory store whose opportunities are spread over up to `shards` locks
+    pub fn new_sharded(max_opportunities: usize, max_executions: usize, shards: usize, clock: SharedClock) -> Self {
         let store = Self {
-            opportunities: Arc::new(RwLock::new(HashMap::new())),
+            opportunities: Arc::new(ShardedOpportunities::new(shards, max_opportunities)),
             strategies: Arc::new(RwLock::new(HashMap::new())),
             executions: Arc::new(RwLock::new(VecDeque::new())),
             metrics: Arc::new(Mutex::new(StoreMetrics::default())),
             max_opportunities,
             max_executions,
             cleanup_interval: std::time::Duration::from_secs(300), // Clean every 5 minutes
+            clock,
         };
 
         // Start background cleanup task
@@ -76,21 +305,8 @@ impl MemoryStore {
 
     /// Save an arbitrage opportunity
     pub async fn save_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
-        let mut opportunities = self.opportunities.write().await;
-        
         // If capacity is reached, remove the oldest opportunity
-        if opportunities.len() >= self.max_opportunities {
-            let oldest_key = opportunities
-                .iter()
-                .min_by_key(|(_, opp)| opp.timestamp)
-                .map(|(k, _)| k.clone());
-            
-            if let Some(key) = oldest_key {
-                opportunities.remove(&key);
-            }
-        }
-        
-        opportunities.insert(opportunity.id.clone(), opportunity.clone());
+        self.opportunities.insert(opportunity.clone()).await;
         
         // Update metrics
         let mut metrics = self.metrics.lock().await;
@@ -99,41 +315,54 @@ impl MemoryStore {
         Ok(())
     }
 
+    /// Replace a stored opportunity with a refreshed copy; unknown ones are left out
+    pub async fn update_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        self.opportunities.replace(opportunity).await;
+        Ok(())
+    }
+
     /// Update an arbitrage opportunity status
     pub async fn update_opportunity_status(&self, opportunity_id: &str, status: OpportunityStatus) -> Result<()> {
-        let mut opportunities = self.opportunities.write().await;
-        
-        if let Some(opportunity) = opportunities.get_mut(opportunity_id) {
-            opportunity.status = status;
-        }
-        
+        self.opportunities.set_status(opportunity_id, status).await;
         Ok(())
     }
 
     /// Get an arbitrage opportunity
     pub async fn get_opportunity(&self, opportunity_id: &str) -> Option<ArbitrageOpportunity> {
-        let opportunities = self.opportunities.read().await;
-        opportunities.get(opportunity_id).cloned()
+        self.opportunities.get(opportunity_id).await
     }
 
     /// Get all active opportunities
     pub async fn get_active_opportunities(&self) -> Vec<ArbitrageOpportunity> {
-        let opportunities = self.opportunities.read().await;
-        opportunities
-            .values()
-            .filter(|opp| opp.status == OpportunityStatus::Pending)
-            .cloned()
-            .collect()
+        self.get_opportunities_by_status(OpportunityStatus::Pending).await
     }
 
     /// Get opportunities by status
     pub async fn get_opportunities_by_status(&self, status: OpportunityStatus) -> Vec<ArbitrageOpportunity> {
-        let opportunities = self.opportunities.read().await;
-        opportunities
-            .values()
-            .filter(|opp| opp.status == status)
-            .cloned()
-            .collect()
+        let shards = self.opportunities.read_all().await;
+        shards.iter().flat_map(|index| index.with_status(&status)).cloned().collect()
+    }
+
+    /// Get opportunities buying on one DEX and selling on another
+    pub async fn get_opportunities_by_pair(&self, buy_dex: &DexType, sell_dex: &DexType) -> Vec<ArbitrageOpportunity> {
+        let shards = self.opportunities.read_all().await;
+        shards.iter().flat_map(|index| index.with_pair(buy_dex, sell_dex)).cloned().collect()
+    }
+
+    /// Get the most profitable pending opportunities, highest net profit first
+    pub async fn get_top_opportunities(&self, limit: usize) -> Vec<ArbitrageOpportunity> {
+        let shards = self.opportunities.read_all().await;
+        let mut top: Vec<&ArbitrageOpportunity> = shards
+            .iter()
+            .flat_map(|index| {
+                index
+                    .by_profit_from(None)
+                    .filter(|opp| opp.status == OpportunityStatus::Pending)
+                    .take(limit)
+            })
+            .collect();
+        top.sort_by_key(|opp| std::cmp::Reverse(opp.net_profit));
+        top.into_iter().take(limit).cloned().collect()
     }
 
     /// Save an arbitrage strategy
@@ -203,7 +432,7 @@ impl MemoryStore {
 
     /// Get execution statistics
     pub async fn get_execution_stats(&self, days: i64) -> Result<(u64, Decimal, Decimal)> {
-        let since = Utc::now() - chrono::Duration::days(days);
+        let since = self.clock.now() - chrono::Duration::days(days);
         let executions = self.executions.read().await;
         
         let filtered_executions: Vec<&ArbitrageExecution> = executions
@@ -224,6 +453,85 @@ impl MemoryStore {
         Ok((total_executions, total_profit, total_fees))
     }
 
+    /// Win rate and average win and loss of finished executions in the last `days`, as returns on
+    /// each trade's input; executions without a sized route are left out
+    pub async fn get_outcome_stats(&self, days: i64) -> OutcomeStats {
+        let since = self.clock.now() - chrono::Duration::days(days);
+        let executions = self.executions.read().await;
+        let mut stats = OutcomeStats::default();
+        let (mut win_total, mut loss_total) = (Decimal::ZERO, Decimal::ZERO);
+        for execution in executions.iter().filter(|exec| exec.execution_time >= since) {
+            if !matches!(execution.execution_status, ExecutionStatus::Confirmed | ExecutionStatus::Failed)
+                || execution.route.input_amount <= Decimal::ZERO
+            {
+                continue;
+            }
+            let net = execution.actual_profit.unwrap_or(Decimal::ZERO) - execution.total_cost.unwrap_or(Decimal::ZERO);
+            let trade_return = net / execution.route.input_amount;
+            stats.trades += 1;
+            if trade_return > Decimal::ZERO {
+                stats.wins += 1;
+                win_total += trade_return;
+            } else {
+                loss_total -= trade_return;
+            }
+        }
+        if stats.wins > 0 {
+            stats.average_win = win_total / Decimal::from(stats.wins);
+        }
+        if stats.trades > stats.wins {
+            stats.average_loss = loss_total / Decimal::from(stats.trades - stats.wins);
+        }
+        stats
+    }
+
+    /// Copy of the current contents
+    pub async fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot {
+            taken_at: self.clock.now(),
+            opportunities: self.opportunities.read_all().await.iter().flat_map(|index| index.values()).cloned().collect(),
+            strategies: self.strategies.read().await.values().cloned().collect(),
+            executions: self.executions.read().await.iter().cloned().collect(),
+            metrics: self.metrics.lock().await.clone(),
+        }
+    }
+
+    /// Replace the contents with a snapshot, keeping the newest records within capacity
+    pub async fn restore(&self, snapshot: MemorySnapshot) {
+        // Oldest first, so a full shard evicts older records rather than newer ones
+        let mut opportunities = snapshot.opportunities;
+        opportunities.sort_by_key(|opportunity| opportunity.timestamp);
+        self.opportunities.replace_all(opportunities).await;
+        *self.strategies.write().await = snapshot.strategies.into_iter().map(|s| (s.id.clone(), s)).collect();
+        let skip = snapshot.executions.len().saturating_sub(self.max_executions);
+        *self.executions.write().await = snapshot.executions.into_iter().skip(skip).collect();
+        *self.metrics.lock().await = snapshot.metrics;
+    }
+
+    /// Write a snapshot to `path`, through a staging file so a crash mid-write keeps the last one
+    pub async fn save_snapshot(&self, path: &Path) -> Result<()> {
+        let content = SNAPSHOT_FORMAT.encode(&self.snapshot().await)?;
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let staging = path.with_extension("tmp");
+        std::fs::write(&staging, content)?;
+        std::fs::rename(&staging, path)?;
+        Ok(())
+    }
+
+    /// Restore the snapshot at `path`, returning it; None when there is none yet
+    pub async fn load_snapshot(&self, path: &Path) -> Result<Option<MemorySnapshot>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let snapshot: MemorySnapshot = SNAPSHOT_FORMAT.decode(&std::fs::read_to_string(path)?)?;
+        self.restore(snapshot.clone()).await;
+        Ok(Some(snapshot))
+    }
+
     /// Get storage metrics
     pub async fn get_metrics(&self) -> StoreMetrics {
         let metrics = self.metrics.lock().await;
@@ -232,22 +540,10 @@ impl MemoryStore {
 
     /// Cleanup expired data
     async fn cleanup_expired_data(&self) -> Result<()> {
-        let now = Utc::now();
+        let now = self.clock.now();
         
         // Cleanup expired opportunities
-        let mut opportunities = self.opportunities.write().await;
-        let expired_opportunities: Vec<String> = opportunities
-            .iter()
-            .filter(|(_, opp)| opp.expiry < now)
-            .map(|(id, _)| id.clone())
-            .collect();
-        
-        for id in expired_opportunities {
-            if let Some(mut opportunity) = opportunities.remove(&id) {
-                opportunity.status = OpportunityStatus::Expired;
-                opportunities.insert(id, opportunity);
-            }
-        }
+        self.opportunities.expire(now).await;
         
         // Cleanup expired executions (keep last 7 days)
         let cutoff = now - chrono::Duration::days(7);
@@ -276,12 +572,11 @@ impl MemoryStore {
 
     /// Get storage usage
     pub async fn get_storage_usage(&self) -> StorageUsage {
-        let opportunities = self.opportunities.read().await;
         let strategies = self.strategies.read().await;
         let executions = self.executions.read().await;
         
         StorageUsage {
-            opportunities_count: opportunities.len(),
+            opportunities_count: self.opportunities.len(),
             strategies_count: strategies.len(),
             executions_count: executions.len(),
             max_opportunities: self.max_opportunities,
@@ -291,23 +586,11 @@ impl MemoryStore {
 
     /// Batch save opportunities (optimized for bulk operations)
     pub async fn batch_save_opportunities(&self, opportunities: Vec<ArbitrageOpportunity>) -> Result<()> {
-        let mut opps = self.opportunities.write().await;
         let opportunities_len = opportunities.len();
         
         for opportunity in opportunities {
             // If capacity is reached, remove the oldest opportunity
-            if opps.len() >= self.max_opportunities {
-                let oldest_key = opps
-                    .iter()
-                    .min_by_key(|(_, opp)| opp.timestamp)
-                    .map(|(k, _)| k.clone());
-                
-                if let Some(key) = oldest_key {
-                    opps.remove(&key);
-                }
-            }
-            
-            opps.insert(opportunity.id.clone(), opportunity);
+            self.opportunities.insert(opportunity).await;
         }
         
         // Bulk update metrics
@@ -317,49 +600,82 @@ impl MemoryStore {
         Ok(())
     }
 
-    /// Search opportunities (supports fast filtering)
+    /// Search opportunities, most profitable first; the profit floor is a range over the profit
+    /// index rather than a scan
     pub async fn search_opportunities(
         &self,
         min_profit: Option<Decimal>,
         max_risk: Option<RiskScore>,
         dex_types: Option<Vec<DexType>>,
     ) -> Vec<ArbitrageOpportunity> {
-        let opportunities = self.opportunities.read().await;
-        
-        opportunities
-            .values()
-            .filter(|opp| {
-                // Profit filter
-                if let Some(min_profit_threshold) = min_profit {
-                    if opp.net_profit < min_profit_threshold {
-                        return false;
-                    }
-                }
-                
-                // Risk filter
-                if let Some(max_risk_threshold) = &max_risk {
-                    if opp.risk_score > *max_risk_threshold {
-                        return false;
-                    }
+        let filter = |opp: &&ArbitrageOpportunity| {
+            // Risk filter
+            if let Some(max_risk_threshold) = &max_risk {
+                if opp.risk_score > *max_risk_threshold {
+                    return false;
                 }
-                
-                // DEX type filter
-                if let Some(allowed_dexes) = &dex_types {
-                    if !allowed_dexes.contains(&opp.buy_pool.dex_type) || 
-                       !allowed_dexes.contains(&opp.sell_pool.dex_type) {
-                        return false;
-                    }
+            }
+            
+            // DEX type filter
+            if let Some(allowed_dexes) = &dex_types {
+                if !allowed_dexes.contains(&opp.buy_pool.dex_type) || 
+                   !allowed_dexes.contains(&opp.sell_pool.dex_type) {
+                    return false;
                 }
-                
-                true
-            })
+            }
+            
+            true
+        };
+        let shards = self.opportunities.read_all().await;
+        let mut found: Vec<&ArbitrageOpportunity> =
+            shards.iter().flat_map(|index| index.by_profit_from(min_profit).filter(filter)).collect();
+        found.sort_by_key(|opp| std::cmp::Reverse(opp.net_profit));
+        found.into_iter().cloned().collect()
+    }
+
+    /// Get opportunities carrying a specific tag
+    pub async fn get_opportunities_by_tag(&self, key: &str, value: &str) -> Vec<ArbitrageOpportunity> {
+        let shards = self.opportunities.read_all().await;
+        shards
+            .iter()
+            .flat_map(|index| index.values())
+            .filter(|opp| opp.has_tag(key, value))
             .cloned()
             .collect()
     }
+
+    /// Get executions carrying a specific tag
+    pub async fn get_executions_by_tag(&self, key: &str, value: &str) -> Vec<ArbitrageExecution> {
+        let executions = self.executions.read().await;
+        executions
+            .iter()
+            .filter(|exec| exec.has_tag(key, value))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Profit distribution of finished executions, as returns on the input traded
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OutcomeStats {
+    pub trades: u64,
+    pub wins: u64,
+    pub average_win: Decimal,
+    /// Positive: the average fraction of input lost by trades that did not win
+    pub average_loss: Decimal,
+}
+
+impl OutcomeStats {
+    pub fn win_rate(&self) -> Decimal {
+        if self.trades == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.wins) / Decimal::from(self.trades)
+    }
 }
 
 /// Storage usage
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct StorageUsage {
     pub opportunities_count: usize,
     pub strategies_count: usize,
@@ -378,6 +694,7 @@ impl Clone for MemoryStore {
             max_opportunities: self.max_opportunities,
             max_executions: self.max_executions,
             cleanup_interval: self.cleanup_interval,
+            clock: self.clock.clone(),
         }
     }
 }
@@ -401,6 +718,31 @@ mod tests {
         assert_eq!(retrieved.unwrap().id, opportunity.id);
     }
 
+    #[tokio::test]
+    async fn test_memory_store_snapshot_round_trip() {
+        let path = std::env::temp_dir().join(format!("memory_store_{}.json", uuid::Uuid::new_v4()));
+        let store = MemoryStore::new(100, 1000);
+        let opportunity = create_test_opportunity();
+        store.save_opportunity(&opportunity).await.unwrap();
+        for _ in 0..3 {
+            store.save_execution(&ArbitrageExecution::new(opportunity.clone())).await.unwrap();
+        }
+        store.save_snapshot(&path).await.unwrap();
+
+        // A smaller store keeps only the newest executions
+        let restored = MemoryStore::new(100, 2);
+        let snapshot = restored.load_snapshot(&path).await.unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(snapshot.executions.len(), 3);
+        assert!(restored.get_opportunity(&opportunity.id).await.is_some());
+        let executions = restored.executions.read().await;
+        assert_eq!(executions.len(), 2);
+        assert_eq!(executions.back().unwrap().id, snapshot.executions[2].id);
+        drop(executions);
+        assert_eq!(restored.get_metrics().await.total_executions, 3);
+        assert!(restored.load_snapshot(&path).await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn test_memory_store_capacity_limits() {
         let store = MemoryStore::new(2, 3);
@@ -416,6 +758,71 @@ mod tests {
         assert_eq!(opportunities.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_memory_store_indexes_follow_updates() {
+        let store = MemoryStore::new(3, 10);
+        for (i, profit) in [5, 20, 10].into_iter().enumerate() {
+            let mut opp = create_test_opportunity();
+            opp.id = format!("opp_{}", i);
+            opp.net_profit = Decimal::from(profit);
+            opp.timestamp = Utc::now() + chrono::Duration::seconds(i as i64);
+            store.save_opportunity(&opp).await.unwrap();
+        }
+
+        let found = store.search_opportunities(Some(Decimal::from(10)), None, None).await;
+        let ids: Vec<&str> = found.iter().map(|opp| opp.id.as_str()).collect();
+        assert_eq!(ids, ["opp_1", "opp_2"]);
+
+        store.update_opportunity_status("opp_1", OpportunityStatus::Executing).await.unwrap();
+        assert_eq!(store.get_active_opportunities().await.len(), 2);
+        assert_eq!(store.get_opportunities_by_status(OpportunityStatus::Executing).await[0].id, "opp_1");
+        assert_eq!(store.get_top_opportunities(1).await[0].id, "opp_2");
+        assert_eq!(store.get_opportunities_by_pair(&DexType::Raydium, &DexType::Meteora).await.len(), 3);
+        assert!(store.get_opportunities_by_pair(&DexType::Meteora, &DexType::Raydium).await.is_empty());
+
+        // At capacity the oldest goes, from every index
+        let mut newest = create_test_opportunity();
+        newest.timestamp = Utc::now() + chrono::Duration::seconds(10);
+        store.save_opportunity(&newest).await.unwrap();
+        assert!(store.get_opportunity("opp_0").await.is_none());
+        assert_eq!(store.search_opportunities(None, None, None).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_sharded_store_stays_within_capacity() {
+        let store = MemoryStore::new_sharded(1024, 10, 4, SystemClock::shared());
+        let start = Utc::now();
+        for i in 0..3000 {
+            let mut opp = create_test_opportunity();
+            opp.id = format!("opp_{}", i);
+            opp.timestamp = start + chrono::Duration::milliseconds(i);
+            store.save_opportunity(&opp).await.unwrap();
+        }
+
+        let usage = store.get_storage_usage().await;
+        assert_eq!(usage.opportunities_count, 1024);
+        assert_eq!(store.search_opportunities(None, None, None).await.len(), 1024);
+        // Every shard evicts its own oldest, so the newest always survive
+        assert!(store.get_opportunity("opp_2999").await.is_some());
+        assert!(store.get_opportunity("opp_0").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_tag_filtering() {
+        let store = MemoryStore::new(100, 1000);
+        
+        let mut tagged = create_test_opportunity();
+        tagged.id = "tagged".to_string();
+        tagged.tags.insert("experiment".to_string(), "a".to_string());
+        store.save_opportunity(&tagged).await.unwrap();
+        store.save_opportunity(&create_test_opportunity()).await.unwrap();
+        
+        let found = store.get_opportunities_by_tag("experiment", "a").await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "tagged");
+        assert!(store.get_opportunities_by_tag("experiment", "b").await.is_empty());
+    }
+
     fn create_test_opportunity() -> ArbitrageOpportunity {
         // Create a mock Pubkey
         let mut bytes = [0u8; 32];
@@ -433,6 +840,9 @@ mod tests {
                 decimals: 9,
                 logo_uri: None,
                 coingecko_id: None,
+                transfer_fee: None,
+                token_program: crate::models::token::spl_token_program(),
+                extensions: Vec::new(),
             },
             quote_token: Token {
                 mint: pubkey,
@@ -441,6 +851,9 @@ mod tests {
                 decimals: 6,
                 logo_uri: None,
                 coingecko_id: None,
+                transfer_fee: None,
+                token_program: crate::models::token::spl_token_program(),
+                extensions: Vec::new(),
             },
             buy_pool: Pool {
                 id: "pool1".to_string(),
@@ -452,6 +865,9 @@ mod tests {
                     decimals: 9,
                     logo_uri: None,
                     coingecko_id: None,
+                    transfer_fee: None,
+                    token_program: crate::models::token::spl_token_program(),
+                    extensions: Vec::new(),
                 },
                 token_b: Token {
                     mint: pubkey,
@@ -460,6 +876,9 @@ mod tests {
                     decimals: 6,
                     logo_uri: None,
                     coingecko_id: None,
+                    transfer_fee: None,
+                    token_program: crate::models::token::spl_token_program(),
+                    extensions: Vec::new(),
                 },
                 reserve_a: Decimal::from(1000000),
                 reserve_b: Decimal::from(1000000),
@@ -470,6 +889,9 @@ mod tests {
                 version: "1.0".to_string(),
                 is_active: true,
                 last_updated: Utc::now(),
+                order_book: None,
+                clmm: None,
+                is_graduated: false,
             },
             sell_pool: Pool {
                 id: "pool2".to_string(),
@@ -481,6 +903,9 @@ mod tests {
                     decimals: 9,
                     logo_uri: None,
                     coingecko_id: None,
+                    transfer_fee: None,
+                    token_program: crate::models::token::spl_token_program(),
+                    extensions: Vec::new(),
                 },
                 token_b: Token {
                     mint: pubkey,
@@ -489,6 +914,9 @@ mod tests {
                     decimals: 6,
                     logo_uri: None,
                     coingecko_id: None,
+                    transfer_fee: None,
+                    token_program: crate::models::token::spl_token_program(),
+                    extensions: Vec::new(),
                 },
                 reserve_a: Decimal::from(1000000),
                 reserve_b: Decimal::from(1000000),
@@ -499,6 +927,9 @@ mod tests {
                 version: "1.0".to_string(),
                 is_active: true,
                 last_updated: Utc::now(),
+                order_book: None,
+                clmm: None,
+                is_graduated: false,
             },
             buy_price: Decimal::from(100),
             sell_price: Decimal::from(101),
@@ -511,6 +942,8 @@ mod tests {
             timestamp: Utc::now(),
             expiry: Utc::now() + chrono::Duration::minutes(5),
             status: OpportunityStatus::Pending,
+            tags: Default::default(),
+            route: None,
         }
     }
 }
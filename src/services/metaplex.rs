@@ -0,0 +1,235 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::services::solana::SolanaService;
+use crate::services::token_registry::{TokenInfo, TokenSource};
+
+/// Metaplex Token Metadata program
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = Pubkey::from_str_const("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Account discriminator of a `Metadata` account
+const METADATA_V1_KEY: u8 = 4;
+
+/// Accounts per `getMultipleAccounts` request
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Address of a mint's metadata account: the PDA of `["metadata", program, mint]`
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    let seeds: &[&[u8]] = &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()];
+    Pubkey::find_program_address(seeds, &TOKEN_METADATA_PROGRAM_ID).0
+}
+
+/// A creator share listed in metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+/// The leading fields of a Metaplex `Metadata` account, up to `is_mutable`.
+///
+/// Later fields (edition nonce, token standard, collection, ...) were appended over program
+/// versions and are not read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaplexMetadata {
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Vec<Creator>,
+    pub primary_sale_happened: bool,
+    /// Whether the update authority can still rewrite name, symbol and uri
+    pub is_mutable: bool,
+}
+
+impl MetaplexMetadata {
+    /// Deserialize the Borsh layout of a metadata account
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let mut reader = BorshReader { data };
+        let key = reader.u8()?;
+        if key != METADATA_V1_KEY {
+            bail!("Not a metadata account (key {})", key);
+        }
+        let update_authority = reader.pubkey()?;
+        let mint = reader.pubkey()?;
+        let name = reader.string()?;
+        let symbol = reader.string()?;
+        let uri = reader.string()?;
+        let seller_fee_basis_points = reader.u16()?;
+        let creators = if reader.bool()? {
+            (0..reader.u32()?)
+                .map(|_| Ok(Creator { address: reader.pubkey()?, verified: reader.bool()?, share: reader.u8()? }))
+                .collect::<Result<_>>()?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            update_authority,
+            mint,
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points,
+            creators,
+            primary_sale_happened: reader.bool()?,
+            is_mutable: reader.bool()?,
+        })
+    }
+
+    /// The update authority, while it can still change the metadata
+    pub fn mutable_by(&self) -> Option<Pubkey> {
+        self.is_mutable.then_some(self.update_authority)
+    }
+}
+
+/// Reads Borsh primitives off the front of a byte slice
+struct BorshReader<'a> {
+    data: &'a [u8],
+}
+
+impl BorshReader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8]> {
+        if self.data.len() < len {
+            bail!("Metadata account ends early");
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => bail!("Invalid bool {} in metadata account", other),
+        }
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into()?))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey> {
+        Ok(Pubkey::try_from(self.take(32)?)?)
+    }
+
+    /// A length-prefixed string; the program pads names, symbols and uris with NULs
+    fn string(&mut self) -> Result<String> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        let text = std::str::from_utf8(bytes).context("Metadata string is not UTF-8")?;
+        Ok(text.trim_end_matches('\0').trim().to_string())
+    }
+}
+
+/// Names tokens from their on-chain Metaplex metadata, for mints missing from the token lists
+pub struct MetaplexSource {
+    solana: Arc<SolanaService>,
+}
+
+impl MetaplexSource {
+    pub fn new(solana: Arc<SolanaService>) -> Self {
+        Self { solana }
+    }
+}
+
+#[async_trait]
+impl TokenSource for MetaplexSource {
+    fn name(&self) -> &str {
+        "metaplex"
+    }
+
+    async fn lookup(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, TokenInfo>> {
+        let mut found = HashMap::new();
+        for mints in mints.chunks(MAX_ACCOUNTS_PER_REQUEST / 2) {
+            let mut addresses: Vec<Pubkey> = mints.iter().map(metadata_address).collect();
+            addresses.extend_from_slice(mints);
+            let accounts = self.solana.get_multiple_accounts(&addresses).await?;
+            let (metadata, mint_accounts) = accounts.split_at(mints.len());
+            for ((mint, metadata), mint_account) in mints.iter().zip(metadata).zip(mint_accounts) {
+                let metadata = metadata.as_ref().filter(|account| account.owner == TOKEN_METADATA_PROGRAM_ID);
+                let Some(metadata) = metadata.and_then(|account| MetaplexMetadata::parse(&account.data).ok()) else {
+                    continue;
+                };
+                if metadata.mint != *mint || metadata.symbol.is_empty() {
+                    continue;
+                }
+                let decimals = mint_account.as_ref().and_then(|account| mint_decimals(&account.data)).unwrap_or(0);
+                found.insert(
+                    *mint,
+                    TokenInfo { mint: *mint, symbol: metadata.symbol, name: metadata.name, decimals, logo_uri: None },
+                );
+            }
+        }
+        Ok(found)
+    }
+}
+
+/// Decimals of an SPL Token or Token-2022 mint account
+fn mint_decimals(data: &[u8]) -> Option<u8> {
+    use spl_token_2022_interface::extension::StateWithExtensions;
+
+    StateWithExtensions::<spl_token_2022_interface::state::Mint>::unpack(data).ok().map(|state| state.base.decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn borsh_string(out: &mut Vec<u8>, text: &str, padded_to: usize) {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize(padded_to.max(bytes.len()), 0);
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&bytes);
+    }
+
+    #[test]
+    fn test_metadata_account_parses_with_padding_and_creators() {
+        let (authority, mint, creator) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![METADATA_V1_KEY];
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(mint.as_ref());
+        borsh_string(&mut data, "Bonk", 32);
+        borsh_string(&mut data, "Bonk", 10);
+        borsh_string(&mut data, "https://arweave.net/bonk.json", 200);
+        data.extend_from_slice(&500u16.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(creator.as_ref());
+        data.extend_from_slice(&[1, 100]);
+        data.extend_from_slice(&[0, 1]);
+        // Trailing fields of newer program versions are ignored
+        data.extend_from_slice(&[1, 255]);
+
+        let metadata = MetaplexMetadata::parse(&data).unwrap();
+        assert_eq!((metadata.name.as_str(), metadata.symbol.as_str()), ("Bonk", "Bonk"));
+        assert_eq!(metadata.uri, "https://arweave.net/bonk.json");
+        assert_eq!(metadata.seller_fee_basis_points, 500);
+        assert_eq!(metadata.creators, vec![Creator { address: creator, verified: true, share: 100 }]);
+        assert_eq!(metadata.mint, mint);
+        assert!(!metadata.primary_sale_happened);
+        assert_eq!(metadata.mutable_by(), Some(authority));
+
+        assert!(MetaplexMetadata::parse(&data[..80]).is_err());
+        data[0] = 6;
+        assert!(MetaplexMetadata::parse(&data).is_err());
+
+        let expected = Pubkey::find_program_address(&[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()], &TOKEN_METADATA_PROGRAM_ID).0;
+        assert_eq!(metadata_address(&mint), expected);
+        assert_ne!(metadata_address(&mint), metadata_address(&creator));
+    }
+}
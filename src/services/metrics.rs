@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Shared registry of named numeric gauges published by the engine and services
+#[derive(Debug, Clone, Default)]
+pub struct MetricsRegistry {
+    gauges: Arc<RwLock<HashMap<String, f64>>>,
+}
+
+impl MetricsRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a labelled gauge name, e.g. `executions_1h{experiment=a}`
+    pub fn labeled(name: &str, labels: &[(&str, &str)]) -> String {
+        if labels.is_empty() {
+            return name.to_string();
+        }
+        let labels: Vec<String> = labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        format!("{}{{{}}}", name, labels.join(","))
+    }
+
+    /// Set a gauge value
+    pub async fn set(&self, name: &str, value: f64) {
+        let mut gauges = self.gauges.write().await;
+        gauges.insert(name.to_string(), value);
+    }
+
+    /// Replace every labelled gauge of `name` with `values`, dropping label sets that are no
+    /// longer present
+    pub async fn set_family(&self, name: &str, values: HashMap<String, f64>) {
+        let prefix = format!("{}{{", name);
+        let mut gauges = self.gauges.write().await;
+        gauges.retain(|gauge, _| !gauge.starts_with(&prefix));
+        gauges.extend(values);
+    }
+
+    /// Add to a gauge value, starting from zero if it does not exist yet
+    pub async fn increment(&self, name: &str, delta: f64) {
+        let mut gauges = self.gauges.write().await;
+        *gauges.entry(name.to_string()).or_insert(0.0) += delta;
+    }
+
+    /// Get a gauge value
+    pub async fn get(&self, name: &str) -> Option<f64> {
+        let gauges = self.gauges.read().await;
+        gauges.get(name).copied()
+    }
+
+    /// Get a copy of all gauges
+    pub async fn snapshot(&self) -> HashMap<String, f64> {
+        let gauges = self.gauges.read().await;
+        gauges.clone()
+    }
+}
@@ -2,6 +2,45 @@ pub mod database;
 pub mod jito;
 pub mod solana;
 pub mod memory_store;
+pub mod metrics;
+pub mod alert_rules;
+pub mod enrichment;
+pub mod leader_schedule;
+pub mod rebroadcast;
+pub mod sender;
+pub mod wallet;
+pub mod pool_blacklist;
+pub mod doctor;
+pub mod events;
+pub mod codec;
+pub mod journal;
+pub mod journal_import;
+pub mod control_api;
+pub mod supervisor;
+pub mod keepalive;
+pub mod tpu;
+pub mod account_subscriber;
+pub mod geyser;
+pub mod fingerprints;
+pub mod account_cache;
+pub mod wallet_watcher;
+pub mod oracle;
+pub mod tenancy;
+pub mod maintenance;
+pub mod dead_man;
+pub mod archive;
+pub mod readiness;
+pub mod token_safety;
+pub mod rent_reclaimer;
+pub mod notifier;
+pub mod balances;
+pub mod fee_model;
+pub mod token_registry;
+pub mod metaplex;
 
 pub use database::DatabaseService;
-pub use memory_store::{MemoryStore, StorageUsage};
+pub use memory_store::{MemoryStore, OutcomeStats, StorageUsage};
+pub use metrics::MetricsRegistry;
+pub use enrichment::DasBackend;
+pub use pool_blacklist::PoolBlacklist;
+pub use events::{BotEvent, EventBus};
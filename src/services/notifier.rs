@@ -0,0 +1,420 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+use crate::config::{NotifierChannelConfig, NotifierConfig, NotifierTemplates};
+use crate::models::{ArbitrageExecution, ExecutionStatus};
+use crate::services::alert_rules::FiredAlert;
+use crate::utils::{clock::SharedClock, cron::CronSchedule};
+
+/// Something the operator is told about
+#[derive(Debug, Clone)]
+pub enum Notification {
+    /// A confirmed or failed execution
+    Execution(Box<ArbitrageExecution>),
+    /// An alert addressed to `channel`, e.g. a circuit-breaker trip
+    Alert { channel: String, alert: FiredAlert },
+    Summary(DailySummary),
+}
+
+/// Notifications waiting for the notifier worker; shared so a restarted worker resumes the queue
+pub type NotificationReceiver = Arc<Mutex<mpsc::Receiver<Notification>>>;
+
+/// Executions and P&L over the day before `until`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailySummary {
+    pub until: DateTime<Utc>,
+    pub executions: usize,
+    pub confirmed: usize,
+    pub failed: usize,
+    pub profit: Decimal,
+    pub cost: Decimal,
+}
+
+impl DailySummary {
+    pub fn from_executions(executions: &[ArbitrageExecution], until: DateTime<Utc>) -> Self {
+        let since = until - Duration::days(1);
+        let day: Vec<&ArbitrageExecution> = executions
+            .iter()
+            .filter(|e| e.execution_time >= since && e.execution_time < until)
+            .filter(|e| matches!(e.execution_status, ExecutionStatus::Confirmed | ExecutionStatus::Failed))
+            .collect();
+        Self {
+            until,
+            executions: day.len(),
+            confirmed: day.iter().filter(|e| e.execution_status == ExecutionStatus::Confirmed).count(),
+            failed: day.iter().filter(|e| e.execution_status == ExecutionStatus::Failed).count(),
+            profit: day.iter().filter_map(|e| e.actual_profit).sum(),
+            cost: day.iter().filter_map(|e| e.total_cost).sum(),
+        }
+    }
+
+    pub fn net(&self) -> Decimal {
+        self.profit - self.cost
+    }
+}
+
+/// Replace each `{key}` in `template` with its value; unknown placeholders are left as they are
+pub fn render_template(template: &str, values: &[(&str, String)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (key, value)| text.replace(&format!("{{{}}}", key), value))
+}
+
+/// Text of a notification under the configured templates
+pub fn render(notification: &Notification, templates: &NotifierTemplates) -> String {
+    let amount = |value: Option<Decimal>| value.map(|v| v.round_dp(6).to_string()).unwrap_or_else(|| "-".to_string());
+    match notification {
+        Notification::Execution(execution) => {
+            let mut values = vec![
+                ("pair", execution.opportunity.pair()),
+                ("strategy", execution.tags.get("strategy").cloned().unwrap_or_default()),
+                ("profit", amount(execution.actual_profit)),
+                ("cost", amount(execution.total_cost)),
+                ("signature", execution.transaction_signature.clone().unwrap_or_default()),
+            ];
+            if execution.execution_status == ExecutionStatus::Failed {
+                let reason = execution.failure_reason.map(|r| r.label()).unwrap_or(crate::arbitrage::outcome::UNCLASSIFIED);
+                values.push(("reason", reason.to_string()));
+                values.push(("error", execution.error_message.clone().unwrap_or_default()));
+                render_template(&templates.failure, &values)
+            } else {
+                render_template(&templates.execution, &values)
+            }
+        }
+        Notification::Alert { alert, .. } => render_template(
+            &templates.alert,
+            &[
+                ("rule", alert.rule_name.clone()),
+                ("message", alert.message.clone()),
+                ("value", alert.value.to_string()),
+            ],
+        ),
+        Notification::Summary(summary) => render_template(
+            &templates.summary,
+            &[
+                ("date", (summary.until - Duration::days(1)).format("%Y-%m-%d").to_string()),
+                ("executions", summary.executions.to_string()),
+                ("confirmed", summary.confirmed.to_string()),
+                ("failed", summary.failed.to_string()),
+                ("profit", summary.profit.round_dp(6).to_string()),
+                ("cost", summary.cost.round_dp(6).to_string()),
+                ("net", summary.net().round_dp(6).to_string()),
+            ],
+        ),
+    }
+}
+
+/// Where messages are delivered
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    fn kind(&self) -> &str;
+
+    async fn send(&self, text: &str) -> Result<()>;
+}
+
+/// Messages through the Telegram bot API
+pub struct TelegramSink {
+    client: reqwest::Client,
+    url: String,
+    chat_id: String,
+}
+
+impl TelegramSink {
+    pub fn new(client: reqwest::Client, api_url: &str, bot_token: &str, chat_id: &str) -> Self {
+        Self {
+            client,
+            url: format!("{}/bot{}/sendMessage", api_url.trim_end_matches('/'), bot_token),
+            chat_id: chat_id.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for TelegramSink {
+    fn kind(&self) -> &str {
+        "telegram"
+    }
+
+    async fn send(&self, text: &str) -> Result<()> {
+        let body = serde_json::json!({ "chat_id": self.chat_id, "text": text, "disable_web_page_preview": true });
+        let response = self.client.post(&self.url).json(&body).send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!("Telegram answered HTTP {}", response.status());
+        }
+        Ok(())
+    }
+}
+
+/// Messages posted to a Discord webhook
+pub struct DiscordSink {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl DiscordSink {
+    pub fn new(client: reqwest::Client, webhook_url: &str) -> Self {
+        Self { client, webhook_url: webhook_url.to_string() }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for DiscordSink {
+    fn kind(&self) -> &str {
+        "discord"
+    }
+
+    async fn send(&self, text: &str) -> Result<()> {
+        // Discord caps message content at 2000 characters
+        let content: String = text.chars().take(2000).collect();
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .json(&serde_json::json!({ "content": content }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("Discord answered HTTP {}", response.status());
+        }
+        Ok(())
+    }
+}
+
+fn sink(config: &NotifierChannelConfig, client: reqwest::Client) -> Result<Box<dyn NotificationSink>> {
+    match config.kind.as_str() {
+        "telegram" => Ok(Box::new(TelegramSink::new(client, &config.api_url, &config.bot_token, &config.chat_id))),
+        "discord" => Ok(Box::new(DiscordSink::new(client, &config.webhook_url))),
+        other => anyhow::bail!("Notifier channel '{}' has unknown kind '{}'", config.name, other),
+    }
+}
+
+/// Sliding one-minute window of messages sent to a channel
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    max_per_minute: usize,
+    sent: VecDeque<DateTime<Utc>>,
+    /// Messages dropped since the last one that went out
+    suppressed: usize,
+}
+
+impl RateLimiter {
+    pub fn new(max_per_minute: usize) -> Self {
+        Self { max_per_minute, ..Default::default() }
+    }
+
+    /// Whether a message may go out now; a refusal is counted as suppressed
+    pub fn allow(&mut self, now: DateTime<Utc>) -> bool {
+        while self.sent.front().is_some_and(|sent| now - *sent >= Duration::minutes(1)) {
+            self.sent.pop_front();
+        }
+        if self.max_per_minute > 0 && self.sent.len() >= self.max_per_minute {
+            self.suppressed += 1;
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+
+    /// Messages dropped since the last allowed one, resetting the count
+    pub fn take_suppressed(&mut self) -> usize {
+        std::mem::take(&mut self.suppressed)
+    }
+}
+
+/// Rate-limited delivery of notifications to named channels
+pub struct Notifier {
+    sinks: HashMap<String, Box<dyn NotificationSink>>,
+    limits: HashMap<String, RateLimiter>,
+    templates: NotifierTemplates,
+    /// Channel for executions and summaries; alerts go to the channel their rule names
+    channel: String,
+    notify_executions: bool,
+    notify_failures: bool,
+}
+
+impl Notifier {
+    pub fn from_config(config: &NotifierConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_seconds.max(1)))
+            .build()?;
+        let mut sinks = HashMap::new();
+        for channel in &config.channels {
+            sinks.insert(channel.name.clone(), sink(channel, client.clone())?);
+        }
+        Ok(Self::new(sinks, config))
+    }
+
+    pub fn new(sinks: HashMap<String, Box<dyn NotificationSink>>, config: &NotifierConfig) -> Self {
+        Self {
+            limits: sinks.keys().map(|name| (name.clone(), RateLimiter::new(config.max_per_minute))).collect(),
+            sinks,
+            templates: config.templates.clone(),
+            channel: config.channel.clone(),
+            notify_executions: config.notify_executions,
+            notify_failures: config.notify_failures,
+        }
+    }
+
+    /// Channel a notification goes to, or None when it is not wanted
+    fn channel_for<'a>(&'a self, notification: &'a Notification) -> Option<&'a str> {
+        match notification {
+            Notification::Execution(execution) => match execution.execution_status {
+                ExecutionStatus::Confirmed if self.notify_executions => Some(&self.channel),
+                ExecutionStatus::Failed if self.notify_failures => Some(&self.channel),
+                _ => None,
+            },
+            Notification::Alert { channel, .. } => Some(channel),
+            Notification::Summary(_) => Some(&self.channel),
+        }
+    }
+
+    /// Deliver a notification, unless its channel is unknown or over its rate limit
+    pub async fn notify(&mut self, notification: &Notification, now: DateTime<Utc>) {
+        let Some(channel) = self.channel_for(notification).map(str::to_string) else {
+            return;
+        };
+        let (Some(sink), Some(limit)) = (self.sinks.get(&channel), self.limits.get_mut(&channel)) else {
+            debug!("No notifier channel '{}', dropping notification", channel);
+            return;
+        };
+        if !limit.allow(now) {
+            return;
+        }
+
+        let mut text = render(notification, &self.templates);
+        let suppressed = limit.take_suppressed();
+        if suppressed > 0 {
+            text.push_str(&format!("\n({} earlier message(s) suppressed by rate limit)", suppressed));
+        }
+        if let Err(e) = sink.send(&text).await {
+            warn!("Failed to notify {} channel '{}': {}", sink.kind(), channel, e);
+        }
+    }
+}
+
+/// Delivers queued notifications and, on schedule, the summary of the past day's executions
+pub struct NotifierWorker {
+    notifier: Notifier,
+    receiver: NotificationReceiver,
+    executions: Arc<RwLock<Vec<ArbitrageExecution>>>,
+    summary_schedule: Option<CronSchedule>,
+    clock: SharedClock,
+}
+
+impl NotifierWorker {
+    pub fn new(
+        config: &NotifierConfig,
+        receiver: NotificationReceiver,
+        executions: Arc<RwLock<Vec<ArbitrageExecution>>>,
+        clock: SharedClock,
+    ) -> Result<Self> {
+        Ok(Self {
+            notifier: Notifier::from_config(config)?,
+            receiver,
+            executions,
+            summary_schedule: match config.summary_schedule.as_str() {
+                "" => None,
+                schedule => Some(CronSchedule::parse(schedule)?),
+            },
+            clock,
+        })
+    }
+
+    /// Start the worker loop
+    pub async fn start(mut self) -> Result<()> {
+        info!("Starting notifier with {} channel(s)", self.notifier.sinks.len());
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(20));
+        let mut last_summary: Option<DateTime<Utc>> = None;
+        let receiver = self.receiver.clone();
+        let mut receiver = receiver.lock().await;
+
+        loop {
+            tokio::select! {
+                notification = receiver.recv() => {
+                    let Some(notification) = notification else {
+                        return Ok(());
+                    };
+                    self.notifier.notify(&notification, self.clock.now()).await;
+                }
+
+                _ = ticker.tick() => {
+                    let now = self.clock.now();
+                    let minute = now.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(now);
+                    let due = self.summary_schedule.as_ref().is_some_and(|schedule| schedule.matches(now));
+                    if due && last_summary != Some(minute) {
+                        last_summary = Some(minute);
+                        let summary = DailySummary::from_executions(&self.executions.read().await, now);
+                        self.notifier.notify(&Notification::Summary(summary), now).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::alert_rules::AlertAction;
+    use std::sync::Mutex as StdMutex;
+
+    struct Recorder(Arc<StdMutex<Vec<String>>>);
+
+    #[async_trait]
+    impl NotificationSink for Recorder {
+        fn kind(&self) -> &str {
+            "recorder"
+        }
+
+        async fn send(&self, text: &str) -> Result<()> {
+            self.0.lock().unwrap().push(text.to_string());
+            Ok(())
+        }
+    }
+
+    fn alert(message: &str) -> Notification {
+        Notification::Alert {
+            channel: "ops".to_string(),
+            alert: FiredAlert {
+                rule_name: "risk_daily_loss".to_string(),
+                action: AlertAction::Notify("ops".to_string()),
+                message: message.to_string(),
+                value: 0.0,
+                timestamp: Utc::now(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_alerts_are_templated_and_rate_limited_per_channel() {
+        let sent = Arc::new(StdMutex::new(Vec::new()));
+        let config = NotifierConfig { max_per_minute: 2, ..Default::default() };
+        let sinks: HashMap<String, Box<dyn NotificationSink>> =
+            HashMap::from([("ops".to_string(), Box::new(Recorder(sent.clone())) as Box<dyn NotificationSink>)]);
+        let mut notifier = Notifier::new(sinks, &config);
+        let start = Utc::now();
+
+        for i in 0..4 {
+            notifier.notify(&alert(&format!("halt {}", i)), start).await;
+        }
+        // A minute later the window has room again and reports what was dropped
+        notifier.notify(&alert("halt 4"), start + Duration::seconds(61)).await;
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 3);
+        assert_eq!(sent[0], "[risk_daily_loss] halt 0");
+        assert!(sent[2].starts_with("[risk_daily_loss] halt 4"));
+        assert!(sent[2].contains("2 earlier message(s) suppressed"));
+    }
+
+    #[test]
+    fn test_render_template_fills_known_placeholders() {
+        let text = render_template("{pair}: {net} {unknown}", &[("pair", "SOL/USDC".to_string()), ("net", "1.5".to_string())]);
+        assert_eq!(text, "SOL/USDC: 1.5 {unknown}");
+    }
+}
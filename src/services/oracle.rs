@@ -0,0 +1,219 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+use crate::config::OracleConfig;
+
+/// A USD reference price with its confidence interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct OraclePrice {
+    pub price: Decimal,
+    /// Half-width of the publisher's confidence interval, in USD
+    pub confidence: Decimal,
+    pub publish_time: DateTime<Utc>,
+}
+
+impl OraclePrice {
+    /// Recent enough, and with a confidence interval narrow enough, to judge pools against
+    pub fn is_usable(&self, now: DateTime<Utc>, max_age_seconds: u64, max_deviation: Decimal) -> bool {
+        self.price > Decimal::ZERO
+            && now - self.publish_time <= Duration::seconds(max_age_seconds as i64)
+            && self.confidence / self.price <= max_deviation
+    }
+}
+
+/// Price of `base` in units of `quote`, from their USD prices
+pub fn reference_price(prices: &HashMap<Pubkey, OraclePrice>, base: &Pubkey, quote: &Pubkey) -> Option<Decimal> {
+    let (base, quote) = (prices.get(base)?, prices.get(quote)?);
+    (quote.price > Decimal::ZERO).then(|| base.price / quote.price)
+}
+
+/// Relative distance of `price` from `reference`
+pub fn deviation(price: Decimal, reference: Decimal) -> Decimal {
+    if reference.is_zero() {
+        return Decimal::MAX;
+    }
+    ((price - reference) / reference).abs()
+}
+
+/// Source of reference prices
+#[async_trait]
+pub trait PriceOracle: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// USD prices of the given mints; mints the oracle does not price are left out
+    async fn get_prices(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, OraclePrice>>;
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesResponse {
+    #[serde(default)]
+    parsed: Vec<HermesPriceUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesPriceUpdate {
+    id: String,
+    price: HermesPrice,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesPrice {
+    price: String,
+    conf: String,
+    expo: i32,
+    publish_time: i64,
+}
+
+impl HermesPrice {
+    fn to_oracle_price(&self) -> Option<OraclePrice> {
+        let scaled = |mantissa: &str| -> Option<Decimal> {
+            let mantissa: i64 = mantissa.parse().ok()?;
+            if self.expo <= 0 {
+                Decimal::try_from_i128_with_scale(mantissa as i128, (-self.expo) as u32).ok()
+            } else {
+                Decimal::from(mantissa).checked_mul(Decimal::from(10i64.checked_pow(self.expo as u32)?))
+            }
+        };
+        Some(OraclePrice {
+            price: scaled(&self.price)?,
+            confidence: scaled(&self.conf)?,
+            publish_time: DateTime::from_timestamp(self.publish_time, 0)?,
+        })
+    }
+}
+
+/// Feed ids are compared without the 0x prefix and case-insensitively, as Hermes returns them bare
+fn normalize_feed_id(id: &str) -> String {
+    id.trim_start_matches("0x").to_lowercase()
+}
+
+/// Parse a Hermes `updates/price/latest` response into prices keyed by feed id
+fn parse_hermes(body: &str) -> Result<HashMap<String, OraclePrice>> {
+    let response: HermesResponse = serde_json::from_str(body).context("Invalid Hermes response")?;
+    Ok(response
+        .parsed
+        .iter()
+        .filter_map(|update| Some((normalize_feed_id(&update.id), update.price.to_oracle_price()?)))
+        .collect())
+}
+
+/// Pyth prices from a Hermes endpoint. Every configured feed is fetched in one request, and the
+/// result is reused for `cache_ms` so a burst of scans costs a single call.
+pub struct PythOracle {
+    client: Client,
+    hermes_url: String,
+    /// Feed id per mint
+    feeds: HashMap<Pubkey, String>,
+    cache_ttl: std::time::Duration,
+    cache: RwLock<Option<(Instant, HashMap<Pubkey, OraclePrice>)>>,
+}
+
+impl PythOracle {
+    pub fn new(config: &OracleConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_seconds.max(1)))
+            .build()?;
+        let feeds = config
+            .feeds
+            .iter()
+            .map(|feed| {
+                let mint = Pubkey::from_str(&feed.mint).with_context(|| format!("Invalid oracle mint {}", feed.mint))?;
+                Ok((mint, normalize_feed_id(&feed.feed_id)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            client,
+            hermes_url: config.hermes_url.trim_end_matches('/').to_string(),
+            feeds,
+            cache_ttl: std::time::Duration::from_millis(config.cache_ms),
+            cache: RwLock::new(None),
+        })
+    }
+
+    async fn fetch(&self) -> Result<HashMap<Pubkey, OraclePrice>> {
+        let ids: Vec<(&str, &str)> = self.feeds.values().map(|id| ("ids[]", id.as_str())).collect();
+        let body = self
+            .client
+            .get(format!("{}/v2/updates/price/latest", self.hermes_url))
+            .query(&ids)
+            .query(&[("parsed", "true"), ("encoding", "base64")])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let by_feed = parse_hermes(&body)?;
+        Ok(self
+            .feeds
+            .iter()
+            .filter_map(|(mint, id)| Some((*mint, by_feed.get(id)?.clone())))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PriceOracle for PythOracle {
+    fn name(&self) -> &str {
+        "pyth"
+    }
+
+    async fn get_prices(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, OraclePrice>> {
+        if !mints.iter().any(|mint| self.feeds.contains_key(mint)) {
+            return Ok(HashMap::new());
+        }
+        let cached = self.cache.read().await.as_ref().filter(|(at, _)| at.elapsed() < self.cache_ttl).map(|(_, p)| p.clone());
+        let prices = match cached {
+            Some(prices) => prices,
+            None => {
+                let prices = self.fetch().await?;
+                *self.cache.write().await = Some((Instant::now(), prices.clone()));
+                prices
+            }
+        };
+        Ok(mints.iter().filter_map(|mint| Some((*mint, prices.get(mint)?.clone()))).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_hermes_prices() {
+        let body = r#"{
+            "binary": {"encoding": "base64", "data": []},
+            "parsed": [
+                {"id": "EF0D8B6FDA2CEBA41DA15D4095D1DA392A0D2F8ED0C6C7BC0F4CFAC8C280B56D",
+                 "price": {"price": "15012345678", "conf": "7500000", "expo": -8, "publish_time": 1714521600},
+                 "ema_price": {"price": "15000000000", "conf": "7000000", "expo": -8, "publish_time": 1714521600}},
+                {"id": "eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a",
+                 "price": {"price": "99990000", "conf": "20000", "expo": -8, "publish_time": 1714521590}}
+            ]
+        }"#;
+        let prices = parse_hermes(body).unwrap();
+        let sol = &prices[&normalize_feed_id("0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d")];
+        assert_eq!(sol.price, Decimal::new(15012345678, 8));
+        assert_eq!(sol.confidence, Decimal::new(75, 3));
+        assert_eq!(sol.publish_time.timestamp(), 1714521600);
+
+        let (sol_mint, usdc_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let usdc = prices["eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a"].clone();
+        let by_mint = HashMap::from([(sol_mint, sol.clone()), (usdc_mint, usdc)]);
+        let reference = reference_price(&by_mint, &sol_mint, &usdc_mint).unwrap();
+        assert!((reference - Decimal::new(15013847, 5)).abs() < Decimal::new(1, 3), "{}", reference);
+
+        let tolerance = Decimal::new(2, 2);
+        assert!(sol.is_usable(sol.publish_time + Duration::seconds(30), 60, tolerance));
+        assert!(!sol.is_usable(sol.publish_time + Duration::seconds(90), 60, tolerance));
+        assert_eq!(deviation(Decimal::from(153), Decimal::from(150)), Decimal::new(2, 2));
+    }
+}
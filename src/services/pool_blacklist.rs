@@ -0,0 +1,273 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::config::BlacklistConfig;
+use crate::models::{ArbitrageExecution, ExecutionStatus};
+use crate::utils::versioning::{self, FormatSpec};
+
+/// On-disk format of the blacklist file; version 0 was a bare JSON array
+const BLACKLIST_FORMAT: FormatSpec = FormatSpec {
+    name: "pool_blacklist",
+    version: 1,
+    migrations: &[versioning::unchanged],
+};
+
+/// A blacklisted pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlacklistEntry {
+    pub pool_address: String,
+    pub reason: String,
+    pub added_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl BlacklistEntry {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Pools excluded from scanning, populated automatically from repeated bad executions
+pub struct PoolBlacklist {
+    config: BlacklistConfig,
+    entries: RwLock<HashMap<String, BlacklistEntry>>,
+    failures: RwLock<HashMap<String, Vec<DateTime<Utc>>>>,
+}
+
+impl PoolBlacklist {
+    pub fn new(config: BlacklistConfig) -> Self {
+        Self {
+            config,
+            entries: RwLock::new(HashMap::new()),
+            failures: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Create the blacklist, restoring persisted entries when the file exists
+    pub fn load(config: BlacklistConfig) -> Result<Self> {
+        let mut entries = HashMap::new();
+        if !config.path.is_empty() && Path::new(&config.path).exists() {
+            let content = std::fs::read_to_string(&config.path)?;
+            let saved: Vec<BlacklistEntry> = BLACKLIST_FORMAT.decode(&content)?;
+            let now = Utc::now();
+            entries = saved
+                .into_iter()
+                .filter(|e| !e.is_expired(now))
+                .map(|e| (e.pool_address.clone(), e))
+                .collect();
+            info!("Loaded {} blacklisted pools from {}", entries.len(), config.path);
+        }
+
+        Ok(Self {
+            config,
+            entries: RwLock::new(entries),
+            failures: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Whether the pool is currently blacklisted
+    pub async fn is_blacklisted(&self, pool_address: &str) -> bool {
+        let entries = self.entries.read().await;
+        entries
+            .get(pool_address)
+            .map(|e| !e.is_expired(Utc::now()))
+            .unwrap_or(false)
+    }
+
+    /// Add a pool for the configured TTL
+    pub async fn add(&self, pool_address: &str, reason: &str) -> Result<()> {
+        let now = Utc::now();
+        let entry = BlacklistEntry {
+            pool_address: pool_address.to_string(),
+            reason: reason.to_string(),
+            added_at: now,
+            expires_at: now + Duration::seconds(self.config.ttl_seconds as i64),
+        };
+
+        warn!("Blacklisting pool {} until {}: {}", pool_address, entry.expires_at, reason);
+        self.entries.write().await.insert(pool_address.to_string(), entry);
+        self.save().await
+    }
+
+    pub async fn remove(&self, pool_address: &str) -> Result<bool> {
+        let removed = self.entries.write().await.remove(pool_address).is_some();
+        if removed {
+            self.save().await?;
+        }
+        Ok(removed)
+    }
+
+    /// Get active entries
+    pub async fn entries(&self) -> Vec<BlacklistEntry> {
+        let now = Utc::now();
+        let entries = self.entries.read().await;
+        entries.values().filter(|e| !e.is_expired(now)).cloned().collect()
+    }
+
+    /// Record an execution outcome at `now`, blacklisting pools that fail repeatedly. Only failures
+    /// the pools are to blame for count, against every pool of the route.
+    pub async fn record_execution(&self, execution: &ArbitrageExecution, now: DateTime<Utc>) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let reason = match execution.execution_status {
+            ExecutionStatus::Failed if execution.failure_reason.is_some_and(|reason| reason.is_pool_attributable()) => execution
+                .error_message
+                .clone()
+                .unwrap_or_else(|| "failed execution".to_string()),
+            ExecutionStatus::Confirmed if execution.actual_profit.is_some_and(|p| p <= Decimal::ZERO) => {
+                "unprofitable execution".to_string()
+            }
+            _ => return Ok(()),
+        };
+
+        let window_start = now - Duration::seconds(self.config.window_seconds as i64);
+        let opportunity = &execution.opportunity;
+        let mut pools = vec![opportunity.buy_pool.pool_address.to_string(), opportunity.sell_pool.pool_address.to_string()];
+        if let Some(route) = &opportunity.route {
+            pools.extend(route.pools.iter().map(|pool| pool.pool_address.to_string()));
+        }
+        pools.sort();
+        pools.dedup();
+
+        let mut to_blacklist = Vec::new();
+        {
+            let mut failures = self.failures.write().await;
+            for pool in pools {
+                let history = failures.entry(pool.clone()).or_default();
+                history.retain(|t| *t >= window_start);
+                history.push(now);
+
+                if history.len() >= self.config.failure_threshold {
+                    let count = history.len();
+                    failures.remove(&pool);
+                    to_blacklist.push((pool, count));
+                }
+            }
+        }
+
+        for (pool, count) in to_blacklist {
+            self.add(&pool, &format!("{} bad executions, last: {}", count, reason)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Drop expired entries
+    pub async fn prune_expired(&self) -> Result<usize> {
+        let now = Utc::now();
+        let removed = {
+            let mut entries = self.entries.write().await;
+            let before = entries.len();
+            entries.retain(|_, e| !e.is_expired(now));
+            before - entries.len()
+        };
+        if removed > 0 {
+            self.save().await?;
+        }
+        Ok(removed)
+    }
+
+    async fn save(&self) -> Result<()> {
+        if self.config.path.is_empty() {
+            return Ok(());
+        }
+
+        let entries: Vec<BlacklistEntry> = self.entries.read().await.values().cloned().collect();
+        if let Some(parent) = Path::new(&self.config.path).parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        std::fs::write(&self.config.path, BLACKLIST_FORMAT.encode(&entries)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ArbitrageRoute, FailureReason, Pool};
+    use crate::dex::DexType;
+    use crate::utils::fixtures;
+
+    fn failed_execution(buy_pool: &Pool, sell_pool: &Pool) -> ArbitrageExecution {
+        let token_a = buy_pool.token_a.clone();
+        let token_b = buy_pool.token_b.clone();
+        let opportunity = fixtures::opportunity_between(buy_pool.clone(), sell_pool.clone());
+        ArbitrageExecution {
+            id: uuid::Uuid::new_v4().to_string(),
+            opportunity,
+            route: ArbitrageRoute::new(vec![], token_a, token_b, Decimal::ZERO),
+            transaction_signature: None,
+            execution_status: ExecutionStatus::Failed,
+            gas_used: None,
+            gas_price: None,
+            total_cost: None,
+            actual_profit: None,
+            execution_time: Utc::now(),
+            error_message: Some("slippage exceeded".to_string()),
+            failure_reason: Some(FailureReason::SlippageExceeded),
+            program_error: None,
+            tags: Default::default(),
+            config_snapshot: None,
+            send_options: Default::default(),
+            simulation_logs: Vec::new(),
+        }
+    }
+
+    fn pool(dex_type: DexType) -> Pool {
+        let (sol, usdc) = fixtures::sol_usdc();
+        fixtures::pool(dex_type, &sol, &usdc)
+    }
+
+    #[tokio::test]
+    async fn test_auto_blacklist_after_threshold() {
+        let config = BlacklistConfig {
+            enabled: true,
+            failure_threshold: 2,
+            window_seconds: 3600,
+            ttl_seconds: 3600,
+            path: String::new(),
+        };
+        let blacklist = PoolBlacklist::new(config);
+        let buy_pool = pool(DexType::Raydium);
+        let sell_pool = pool(DexType::Meteora);
+        let address = buy_pool.pool_address.to_string();
+        let now = Utc::now();
+
+        blacklist.record_execution(&failed_execution(&buy_pool, &sell_pool), now).await.unwrap();
+        assert!(!blacklist.is_blacklisted(&address).await);
+
+        // Failures the pools are not to blame for do not count
+        let mut expired = failed_execution(&buy_pool, &sell_pool);
+        expired.error_message = Some("blockhash not found".to_string());
+        expired.failure_reason = Some(FailureReason::BlockhashExpired);
+        blacklist.record_execution(&expired, now).await.unwrap();
+        expired.failure_reason = None;
+        blacklist.record_execution(&expired, now).await.unwrap();
+        assert!(!blacklist.is_blacklisted(&address).await);
+
+        blacklist.record_execution(&failed_execution(&buy_pool, &sell_pool), now).await.unwrap();
+        assert!(blacklist.is_blacklisted(&address).await);
+        assert!(blacklist.entries().await[0].reason.contains("slippage exceeded"));
+
+        // A cycle counts against its middle pools too
+        let middle = pool(DexType::Whirlpool);
+        let (first, last) = (pool(DexType::Raydium), pool(DexType::Meteora));
+        for _ in 0..2 {
+            let mut cycle = failed_execution(&first, &last);
+            cycle.opportunity.route =
+                Some(ArbitrageRoute::new(vec![first.clone(), middle.clone(), last.clone()], first.token_a.clone(), first.token_a.clone(), Decimal::ONE));
+            blacklist.record_execution(&cycle, now).await.unwrap();
+        }
+        assert!(blacklist.is_blacklisted(&middle.pool_address.to_string()).await);
+    }
+}
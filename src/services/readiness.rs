@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use sd_notify::NotifyState;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{debug, info, warn};
+
+use crate::config::ServiceConfig;
+
+/// What the running bot last reported about itself, read back by `--healthcheck`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthStatus {
+    pub ready: bool,
+    pub pid: u32,
+    pub status: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Tells the service manager how startup is going.
+///
+/// Readiness goes to systemd over `NOTIFY_SOCKET` (a no-op when not started by systemd) and to a
+/// status file for container health probes. Once ready, the watchdog is kept fed at half the
+/// interval systemd asks for and the status file refreshed, so a wedged runtime turns stale.
+#[derive(Debug, Clone)]
+pub struct Readiness {
+    config: ServiceConfig,
+}
+
+impl Readiness {
+    pub fn new(config: &ServiceConfig) -> Self {
+        Self { config: config.clone() }
+    }
+
+    fn notify(&self, states: &[NotifyState]) {
+        if !self.config.notify {
+            return;
+        }
+        if let Err(e) = sd_notify::notify(false, states) {
+            warn!("Failed to notify service manager: {}", e);
+        }
+    }
+
+    fn write_status(&self, ready: bool, status: &str) {
+        if self.config.health_file.is_empty() {
+            return;
+        }
+        let health = HealthStatus {
+            ready,
+            pid: std::process::id(),
+            status: status.to_string(),
+            updated_at: Utc::now(),
+        };
+        if let Err(e) = write_health(Path::new(&self.config.health_file), &health) {
+            warn!("Failed to write health file {}: {}", self.config.health_file, e);
+        }
+    }
+
+    /// Progress while starting up; not yet ready
+    pub fn starting(&self, status: &str) {
+        debug!("Startup: {}", status);
+        self.notify(&[NotifyState::Status(status)]);
+        self.write_status(false, status);
+    }
+
+    /// Caches are warm and self-tests passed; traffic and alerts may be routed here
+    pub fn ready(&self) {
+        let status = "Engine running";
+        info!("Ready; notifying service manager");
+        self.notify(&[NotifyState::Ready, NotifyState::Status(status)]);
+        self.write_status(true, status);
+    }
+
+    pub fn stopping(&self) {
+        self.notify(&[NotifyState::Stopping]);
+        self.write_status(false, "Stopping");
+    }
+
+    /// Interval between keep-alives: half the watchdog timeout systemd set, else the configured one
+    fn keep_alive_interval(&self) -> (std::time::Duration, bool) {
+        let mut usec = 0;
+        if self.config.notify && sd_notify::watchdog_enabled(false, &mut usec) && usec > 0 {
+            return (std::time::Duration::from_micros(usec / 2), true);
+        }
+        (std::time::Duration::from_secs(self.config.status_interval_seconds.max(1)), false)
+    }
+
+    /// Feed the watchdog and refresh the status file until the process exits
+    pub fn start_keep_alive(&self) -> tokio::task::JoinHandle<()> {
+        let readiness = self.clone();
+        let (interval, watchdog) = self.keep_alive_interval();
+        if watchdog {
+            info!("systemd watchdog enabled; pinging every {:?}", interval);
+        }
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if watchdog {
+                    readiness.notify(&[NotifyState::Watchdog]);
+                }
+                readiness.write_status(true, "Engine running");
+            }
+        })
+    }
+}
+
+fn write_health(path: &Path, health: &HealthStatus) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    // Write then rename, so a probe never reads half a file
+    let staging = path.with_extension("tmp");
+    std::fs::write(&staging, serde_json::to_vec(health)?)?;
+    std::fs::rename(&staging, path)?;
+    Ok(())
+}
+
+/// Healthy when the bot reported ready and refreshed its status within the stale limit
+pub fn healthcheck(config: &ServiceConfig, now: DateTime<Utc>) -> Result<HealthStatus> {
+    let path = Path::new(&config.health_file);
+    let content = std::fs::read(path).with_context(|| format!("No health file at {}; is the bot running?", path.display()))?;
+    let health: HealthStatus = serde_json::from_slice(&content).with_context(|| format!("Unreadable health file {}", path.display()))?;
+    if !health.ready {
+        anyhow::bail!("Not ready: {}", health.status);
+    }
+    let age = now - health.updated_at;
+    if age > Duration::seconds(config.healthcheck_stale_seconds.max(1) as i64) {
+        anyhow::bail!("Status last refreshed {}s ago by pid {}", age.num_seconds(), health.pid);
+    }
+    Ok(health)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_healthcheck_follows_status_file() {
+        let dir = std::env::temp_dir().join(format!("readiness-{}", uuid::Uuid::new_v4()));
+        let config = ServiceConfig {
+            notify: false,
+            health_file: dir.join("health.json").to_string_lossy().to_string(),
+            healthcheck_stale_seconds: 30,
+            ..ServiceConfig::default()
+        };
+        let readiness = Readiness::new(&config);
+        assert!(healthcheck(&config, Utc::now()).is_err());
+
+        readiness.starting("Running self-tests");
+        let error = healthcheck(&config, Utc::now()).unwrap_err();
+        assert!(error.to_string().contains("Running self-tests"));
+
+        readiness.ready();
+        let health = healthcheck(&config, Utc::now()).unwrap();
+        assert_eq!(health.pid, std::process::id());
+        // A process that stopped refreshing is as good as down
+        assert!(healthcheck(&config, Utc::now() + Duration::seconds(31)).is_err());
+
+        readiness.stopping();
+        assert!(healthcheck(&config, Utc::now()).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use solana_rpc_client_api::config::RpcSendTransactionConfig;
+use solana_sdk::{signature::Signature, transaction::Transaction};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::config::SubmissionConfig;
+use crate::models::SendOptions;
+use crate::services::sender::TransactionSender;
+use crate::services::solana::SolanaService;
+
+/// Result of a rebroadcast run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebroadcastOutcome {
+    /// The transaction was seen on chain
+    Landed { signature: Signature, attempts: u32 },
+    /// The blockhash expired (or the timeout elapsed) before the transaction landed
+    Expired { signature: Signature, attempts: u32 },
+    /// The same signature is already being rebroadcast
+    AlreadyInFlight { signature: Signature },
+}
+
+/// Re-sends a signed transaction to several RPC nodes until it lands or its blockhash expires
+pub struct Rebroadcaster {
+    nodes: Vec<Arc<SolanaService>>,
+    interval: Duration,
+    timeout: Duration,
+    in_flight: Arc<Mutex<HashSet<Signature>>>,
+}
+
+impl Rebroadcaster {
+    pub fn new(nodes: Vec<Arc<SolanaService>>, interval: Duration, timeout: Duration) -> Self {
+        Self {
+            nodes,
+            interval,
+            timeout,
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Build from the submission config; the primary node is always included
+    pub fn from_config(primary: Arc<SolanaService>, config: &SubmissionConfig) -> Result<Self> {
+        let mut nodes = vec![primary];
+        for url in &config.rebroadcast_rpc_urls {
+            nodes.push(Arc::new(SolanaService::new(url)?));
+        }
+
+        Ok(Self::new(
+            nodes,
+            Duration::from_millis(config.rebroadcast_interval_ms),
+            Duration::from_secs(config.rebroadcast_timeout_seconds),
+        ))
+    }
+
+    /// Number of signatures currently being rebroadcast
+    pub async fn in_flight_count(&self) -> usize {
+        self.in_flight.lock().await.len()
+    }
+
+    /// Rebroadcast until the transaction lands or its blockhash expires
+    pub async fn broadcast(&self, transaction: &Transaction) -> Result<RebroadcastOutcome> {
+        let signature = *transaction
+            .signatures
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Transaction is not signed"))?;
+
+        if !self.in_flight.lock().await.insert(signature) {
+            debug!("Rebroadcast already running for {}", signature);
+            return Ok(RebroadcastOutcome::AlreadyInFlight { signature });
+        }
+
+        let outcome = self.run(transaction, signature).await;
+        self.in_flight.lock().await.remove(&signature);
+        outcome
+    }
+
+    async fn run(&self, transaction: &Transaction, signature: Signature) -> Result<RebroadcastOutcome> {
+        let primary = self
+            .nodes
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("No RPC nodes configured for rebroadcast"))?;
+
+        // Preflight already ran (or was skipped deliberately) on the first send
+        let send_config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            max_retries: Some(0),
+            ..Default::default()
+        };
+
+        let started = Instant::now();
+        let mut interval = tokio::time::interval(self.interval);
+        let mut attempts = 0;
+
+        loop {
+            interval.tick().await;
+            attempts += 1;
+
+            for node in &self.nodes {
+                if let Err(e) = node.send_transaction_with_config(transaction, send_config).await {
+                    debug!("Rebroadcast of {} failed on a node: {}", signature, e);
+                }
+            }
+
+            match primary.get_signature_statuses(&[signature]).await {
+                Ok(statuses) if statuses.first().copied().flatten().is_some() => {
+                    info!("Transaction {} landed after {} broadcast(s)", signature, attempts);
+                    return Ok(RebroadcastOutcome::Landed { signature, attempts });
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Failed to check status of {}: {}", signature, e),
+            }
+
+            let expired = match primary.is_blockhash_valid(&transaction.message.recent_blockhash).await {
+                Ok(valid) => !valid,
+                Err(_) => false,
+            };
+            if expired || started.elapsed() >= self.timeout {
+                warn!("Transaction {} expired after {} broadcast(s)", signature, attempts);
+                return Ok(RebroadcastOutcome::Expired { signature, attempts });
+            }
+        }
+    }
+}
+
+/// Sends through another sender, then rebroadcasts in the background whatever was sent with
+/// `rebroadcast` options, since those turn off the node's own retries
+pub struct RebroadcastingSender {
+    inner: Arc<dyn TransactionSender>,
+    rebroadcaster: Arc<Rebroadcaster>,
+}
+
+impl RebroadcastingSender {
+    pub fn new(inner: Arc<dyn TransactionSender>, rebroadcaster: Arc<Rebroadcaster>) -> Self {
+        Self { inner, rebroadcaster }
+    }
+}
+
+#[async_trait]
+impl TransactionSender for RebroadcastingSender {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn send(&self, transaction: &Transaction, options: &SendOptions) -> Result<Signature> {
+        let signature = self.inner.send(transaction, options).await?;
+        if options.rebroadcast {
+            let rebroadcaster = self.rebroadcaster.clone();
+            let transaction = transaction.clone();
+            tokio::spawn(async move {
+                if let Err(e) = rebroadcaster.broadcast(&transaction).await {
+                    warn!("Rebroadcast of {} stopped: {}", signature, e);
+                }
+            });
+        }
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fixtures;
+    use serde_json::{json, Value};
+    use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A JSON-RPC node that accepts every send, reports the transaction landed from the
+    /// `lands_after`th send on, and reports blockhashes valid while `blockhash_valid` says so
+    struct FakeNode {
+        signature: String,
+        sends: AtomicUsize,
+        lands_after: usize,
+        blockhash_valid: bool,
+    }
+
+    impl FakeNode {
+        fn rpc(&self, method: &str) -> Value {
+            let context = json!({ "slot": 1 });
+            match method {
+                "sendTransaction" => {
+                    self.sends.fetch_add(1, Ordering::SeqCst);
+                    json!(self.signature)
+                }
+                "getSignatureStatuses" if self.sends.load(Ordering::SeqCst) >= self.lands_after => json!({
+                    "context": context,
+                    "value": [{ "slot": 1, "confirmations": 0, "err": null, "status": { "Ok": null }, "confirmationStatus": "processed" }],
+                }),
+                "getSignatureStatuses" => json!({ "context": context, "value": [null] }),
+                "isBlockhashValid" => json!({ "context": context, "value": self.blockhash_valid }),
+                method => panic!("unexpected RPC call {}", method),
+            }
+        }
+    }
+
+    async fn node(signature: Signature, lands_after: usize, blockhash_valid: bool) -> (Arc<FakeNode>, Arc<SolanaService>) {
+        let node = Arc::new(FakeNode { signature: signature.to_string(), sends: AtomicUsize::new(0), lands_after, blockhash_valid });
+        let url = fixtures::rpc_node({
+            let node = node.clone();
+            move |method, _| Ok(node.rpc(method))
+        })
+        .await;
+        (node, Arc::new(SolanaService::new(&url).unwrap()))
+    }
+
+    #[tokio::test]
+    async fn test_rebroadcasts_until_the_transaction_lands_or_its_blockhash_expires() {
+        let payer = Keypair::new();
+        let instruction = solana_system_interface::instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&payer.pubkey()), &[&payer], Hash::default());
+        let signature = transaction.signatures[0];
+
+        // Sent to both nodes every round; seen on chain once the primary has had it three times
+        let (primary, primary_rpc) = node(signature, 3, true).await;
+        let (backup, backup_rpc) = node(signature, usize::MAX, true).await;
+        let rebroadcaster = Rebroadcaster::new(vec![primary_rpc, backup_rpc], Duration::from_millis(10), Duration::from_secs(5));
+        assert_eq!(rebroadcaster.broadcast(&transaction).await.unwrap(), RebroadcastOutcome::Landed { signature, attempts: 3 });
+        assert_eq!(backup.sends.load(Ordering::SeqCst), 3);
+        assert_eq!(primary.sends.load(Ordering::SeqCst), 3);
+        assert_eq!(rebroadcaster.in_flight_count().await, 0);
+
+        // An expired blockhash ends it after the round that noticed
+        let (_, expired_rpc) = node(signature, usize::MAX, false).await;
+        let rebroadcaster = Rebroadcaster::new(vec![expired_rpc], Duration::from_millis(10), Duration::from_secs(5));
+        assert_eq!(rebroadcaster.broadcast(&transaction).await.unwrap(), RebroadcastOutcome::Expired { signature, attempts: 1 });
+    }
+}
@@ -0,0 +1,314 @@
+use anyhow::Result;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::config::RentReclaimConfig;
+use crate::dex::{DexInterface, DexType};
+use crate::services::metrics::MetricsRegistry;
+use crate::services::solana::{SolanaService, TokenHolding};
+use crate::services::wallet::Wallets;
+
+/// Close instructions per transaction; well inside the size and compute limits
+const CLOSE_BATCH: usize = 8;
+
+/// How long a close transaction is polled for before its rent is left unreported
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_POLL: Duration = Duration::from_millis(500);
+
+/// What one reclaim run did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReclaimReport {
+    /// Accounts whose close confirmed, and the rent that returned
+    pub closed: usize,
+    pub lamports: u64,
+    /// Accounts left open because they hold a balance, are frozen or belong to a kept or traded mint
+    pub kept: usize,
+    pub failed: usize,
+    /// Accounts in close transactions that had not confirmed by `CONFIRM_TIMEOUT`
+    pub unconfirmed: usize,
+}
+
+/// Empty accounts that can be closed, at most `max` of them, richest in rent first.
+///
+/// Only accounts with a balance of exactly zero qualify: closing would otherwise need a burn,
+/// and dust of a tracked token may still be wanted.
+pub fn reclaimable<'a>(holdings: &'a [TokenHolding], keep: &HashSet<Pubkey>, max: usize) -> Vec<&'a TokenHolding> {
+    let mut candidates: Vec<_> = holdings
+        .iter()
+        .filter(|holding| holding.amount == 0 && !holding.is_frozen && !keep.contains(&holding.mint))
+        .collect();
+    candidates.sort_by_key(|holding| std::cmp::Reverse(holding.lamports));
+    candidates.truncate(max);
+    candidates
+}
+
+/// `CloseAccount` of the holding under its own token program, sending the rent to `destination`
+pub fn close_instruction(holding: &TokenHolding, owner: &Pubkey, destination: &Pubkey) -> Result<Instruction> {
+    Ok(spl_token_2022_interface::instruction::close_account(
+        &holding.program_id,
+        &holding.address,
+        destination,
+        owner,
+        &[],
+    )?)
+}
+
+/// Periodically closes the owner wallet's empty token accounts, returning their rent to the fee
+/// payer, and reports what was recovered in `rent_reclaimed_lamports`. Accounts of mints the
+/// venues' pools trade are kept, since the next trade would only pay the rent again.
+pub struct RentReclaimer {
+    solana: Arc<SolanaService>,
+    wallets: Arc<Wallets>,
+    metrics: MetricsRegistry,
+    keep: HashSet<Pubkey>,
+    dex_instances: Option<Arc<HashMap<DexType, Box<dyn DexInterface>>>>,
+    max_accounts: usize,
+    report_only: bool,
+    interval: std::time::Duration,
+}
+
+impl RentReclaimer {
+    pub fn new(config: &RentReclaimConfig, solana: Arc<SolanaService>, wallets: Arc<Wallets>, metrics: MetricsRegistry) -> Result<Self> {
+        let keep = config
+            .keep_mints
+            .iter()
+            .map(|mint| Pubkey::from_str(mint).map_err(|e| anyhow::anyhow!("Invalid kept mint {}: {}", mint, e)))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            solana,
+            wallets,
+            metrics,
+            keep,
+            dex_instances: None,
+            max_accounts: config.max_accounts_per_run,
+            report_only: config.report_only,
+            interval: std::time::Duration::from_secs(config.interval_seconds.max(60)),
+        })
+    }
+
+    /// Keep the accounts of every mint in the venues' pools
+    pub fn with_pools(mut self, dex_instances: Arc<HashMap<DexType, Box<dyn DexInterface>>>) -> Self {
+        self.dex_instances = Some(dex_instances);
+        self
+    }
+
+    /// Kept mints plus those of every pool the venues list. A venue that cannot list its pools
+    /// fails the run, since any of its mints could be closed.
+    async fn kept_mints(&self) -> Result<HashSet<Pubkey>> {
+        let mut keep = self.keep.clone();
+        for dex_instance in self.dex_instances.iter().flat_map(|dexes| dexes.values()) {
+            let pools = dex_instance
+                .get_pools()
+                .await
+                .map_err(|e| anyhow::anyhow!("Cannot list {} pools to keep their mints: {}", dex_instance.get_name(), e))?;
+            keep.extend(pools.iter().flat_map(|pool| [pool.token_a.mint, pool.token_b.mint]));
+        }
+        Ok(keep)
+    }
+
+    /// Poll a sent close until it lands or `CONFIRM_TIMEOUT` passes: Some(true) confirmed,
+    /// Some(false) failed, None still pending
+    async fn confirm(&self, signature: &Signature) -> Result<Option<bool>> {
+        let deadline = tokio::time::Instant::now() + CONFIRM_TIMEOUT;
+        loop {
+            if let Some(status) = self.solana.get_signature_confirmations(std::slice::from_ref(signature)).await?.remove(0) {
+                return Ok(Some(status.is_ok()));
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(CONFIRM_POLL).await;
+        }
+    }
+
+    /// Close what can be closed now
+    pub async fn run_once(&self) -> Result<ReclaimReport> {
+        let owner = self.wallets.owner().pubkey();
+        let destination = self.wallets.fee_payer().pubkey();
+        let keep = self.kept_mints().await?;
+        let holdings = self.solana.get_token_holdings(&owner).await?;
+        let candidates = reclaimable(&holdings, &keep, self.max_accounts);
+        let mut report = ReclaimReport { kept: holdings.len() - candidates.len(), ..ReclaimReport::default() };
+
+        if self.report_only {
+            let lamports: u64 = candidates.iter().map(|holding| holding.lamports).sum();
+            info!("Report only: {} empty token accounts could return {} lamports", candidates.len(), lamports);
+            return Ok(report);
+        }
+
+        for batch in candidates.chunks(CLOSE_BATCH) {
+            let instructions = batch
+                .iter()
+                .map(|holding| close_instruction(holding, &owner, &destination))
+                .collect::<Result<Vec<_>>>()?;
+            let blockhash = self.solana.get_recent_blockhash().await?;
+            let transaction = self.wallets.build_transaction(&instructions, blockhash)?;
+            let sent = self.solana.send_transaction_with_config(&transaction, Default::default()).await;
+            let confirmed = match sent {
+                Ok(signature) => self.confirm(&signature).await.map(|status| (signature, status)),
+                Err(e) => Err(e),
+            };
+            match confirmed {
+                Ok((signature, Some(true))) => {
+                    let lamports: u64 = batch.iter().map(|holding| holding.lamports).sum();
+                    debug!("Closed {} token accounts in {}", batch.len(), signature);
+                    report.closed += batch.len();
+                    report.lamports += lamports;
+                }
+                Ok((signature, None)) => {
+                    warn!("Close of {} token accounts in {} has not confirmed", batch.len(), signature);
+                    report.unconfirmed += batch.len();
+                }
+                Ok((signature, Some(false))) => {
+                    // Token-2022 accounts holding withheld fees refuse to close; the rest still can
+                    warn!("Close of {} token accounts failed in {}", batch.len(), signature);
+                    report.failed += batch.len();
+                }
+                Err(e) => {
+                    warn!("Failed to close {} token accounts: {}", batch.len(), e);
+                    report.failed += batch.len();
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Start the worker loop
+    pub async fn start(self) -> Result<()> {
+        info!("Starting rent reclaimer every {:?}{}", self.interval, if self.report_only { " (report only)" } else { "" });
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+            match self.run_once().await {
+                Ok(report) => {
+                    if report.closed > 0 {
+                        info!("Closed {} empty token accounts, reclaiming {} lamports", report.closed, report.lamports);
+                    }
+                    self.metrics.increment("rent_reclaimed_lamports", report.lamports as f64).await;
+                    self.metrics.increment("token_accounts_closed", report.closed as f64).await;
+                }
+                Err(e) => warn!("Rent reclaim run failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_only_empty_untracked_accounts_are_closed() {
+        let tracked = Pubkey::new_unique();
+        let holding = |mint: Pubkey, amount: u64, is_frozen: bool, lamports: u64| TokenHolding {
+            address: Pubkey::new_unique(),
+            mint,
+            amount,
+            decimals: 6,
+            is_frozen,
+            program_id: spl_token_interface::id(),
+            lamports,
+        };
+        let holdings = vec![
+            holding(Pubkey::new_unique(), 0, false, 2_039_280),
+            // Dust is left alone, tracked or not
+            holding(Pubkey::new_unique(), 1, false, 2_039_280),
+            holding(tracked, 0, false, 2_039_280),
+            holding(Pubkey::new_unique(), 0, true, 2_039_280),
+            holding(Pubkey::new_unique(), 0, false, 2_074_080),
+        ];
+        let keep = HashSet::from([tracked]);
+
+        let candidates = reclaimable(&holdings, &keep, 10);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].lamports, 2_074_080);
+        assert_eq!(reclaimable(&holdings, &keep, 1).len(), 1);
+
+        let owner = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let instruction = close_instruction(candidates[1], &owner, &payer).unwrap();
+        assert_eq!(instruction.program_id, spl_token_interface::id());
+        assert_eq!(instruction.accounts[0].pubkey, holdings[0].address);
+        assert_eq!(instruction.accounts[1].pubkey, payer);
+    }
+
+    /// A node holding the `holdings` (mint, amount) of any owner, landing every close with `status`
+    async fn reclaim_node(holdings: Vec<(Pubkey, u64)>, status: Value, sent: Arc<AtomicUsize>) -> Arc<SolanaService> {
+        use base64::Engine;
+
+        let url = crate::utils::fixtures::rpc_node(move |method, params| {
+            Ok(match method {
+                "getTokenAccountsByOwner" if params[1]["programId"] == spl_token_interface::id().to_string() => {
+                    let accounts: Vec<Value> = holdings
+                        .iter()
+                        .map(|(mint, amount)| {
+                            let info = json!({ "mint": mint.to_string(), "owner": Pubkey::new_unique().to_string(), "state": "initialized",
+                                "tokenAmount": { "amount": amount.to_string(), "decimals": 6, "uiAmount": null, "uiAmountString": "0" } });
+                            json!({ "pubkey": Pubkey::new_unique().to_string(), "account": { "lamports": 2_039_280,
+                                "data": { "program": "spl-token", "parsed": { "info": info, "type": "account" }, "space": 165 },
+                                "owner": spl_token_interface::id().to_string(), "executable": false, "rentEpoch": 0, "space": 165 } })
+                        })
+                        .collect();
+                    json!({ "context": { "slot": 1 }, "value": accounts })
+                }
+                "getTokenAccountsByOwner" => json!({ "context": { "slot": 1 }, "value": [] }),
+                "getLatestBlockhash" => json!({ "context": { "slot": 1 },
+                    "value": { "blockhash": solana_sdk::hash::Hash::new_unique().to_string(), "lastValidBlockHeight": 100 } }),
+                "sendTransaction" => {
+                    sent.fetch_add(1, Ordering::SeqCst);
+                    let bytes = base64::engine::general_purpose::STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+                    let transaction: solana_sdk::transaction::Transaction = bincode::deserialize(&bytes).unwrap();
+                    json!(transaction.signatures[0].to_string())
+                }
+                "getSignatureStatuses" => json!({ "context": { "slot": 1 }, "value": [status] }),
+                method => panic!("unexpected {}", method),
+            })
+        })
+        .await;
+        Arc::new(SolanaService::new(&url).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_only_confirmed_closes_of_untraded_mints_are_reported() {
+        use solana_sdk::signature::Keypair;
+
+        let dexes = Arc::new(crate::dex::mock::MockDex::standard_set());
+        let pools = dexes[&DexType::Raydium].get_pools().await.unwrap();
+        let (traded, kept, untraded) = (pools[0].token_b.mint, Pubkey::new_unique(), Pubkey::new_unique());
+        let holdings = vec![(traded, 0), (kept, 0), (untraded, 0), (Pubkey::new_unique(), 5)];
+        let config = RentReclaimConfig { enabled: true, keep_mints: vec![kept.to_string()], ..RentReclaimConfig::default() };
+        let landed = json!({ "slot": 1, "confirmations": null, "err": null, "status": { "Ok": null }, "confirmationStatus": "finalized" });
+        let reclaimer = |solana, config: &RentReclaimConfig| {
+            RentReclaimer::new(config, solana, Arc::new(Wallets::new(Keypair::new(), None)), MetricsRegistry::new())
+                .unwrap()
+                .with_pools(dexes.clone())
+        };
+
+        let sent = Arc::new(AtomicUsize::new(0));
+        let report = reclaimer(reclaim_node(holdings.clone(), landed, sent.clone()).await, &config).run_once().await.unwrap();
+        assert_eq!(report, ReclaimReport { closed: 1, lamports: 2_039_280, kept: 3, failed: 0, unconfirmed: 0 });
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+        // A close that lands failed returns nothing
+        let failed = json!({ "slot": 1, "confirmations": null, "err": { "InstructionError": [0, { "Custom": 11 }] },
+            "status": { "Err": { "InstructionError": [0, { "Custom": 11 }] } }, "confirmationStatus": "finalized" });
+        let report = reclaimer(reclaim_node(holdings.clone(), failed, sent.clone()).await, &config).run_once().await.unwrap();
+        assert_eq!((report.closed, report.lamports, report.failed), (0, 0, 1));
+
+        // Report only, as in a dry run, sends nothing
+        let sent = Arc::new(AtomicUsize::new(0));
+        let report_only = RentReclaimConfig { report_only: true, ..config };
+        let report = reclaimer(reclaim_node(holdings, Value::Null, sent.clone()).await, &report_only).run_once().await.unwrap();
+        assert_eq!(report.closed, 0);
+        assert_eq!(sent.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use solana_rpc_client_api::config::RpcSendTransactionConfig;
+use solana_sdk::{signature::Signature, transaction::Transaction};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::models::SendOptions;
+use crate::services::jito::{JitoConfig, JitoService};
+use crate::services::solana::SolanaService;
+
+/// Submits signed transactions to the network
+#[async_trait]
+pub trait TransactionSender: Send + Sync {
+    /// Sender name
+    fn name(&self) -> &str;
+
+    /// Send a signed transaction with per-execution options
+    async fn send(&self, transaction: &Transaction, options: &SendOptions) -> Result<Signature>;
+}
+
+/// Sends through a regular RPC node
+pub struct RpcSender {
+    solana: Arc<SolanaService>,
+}
+
+impl RpcSender {
+    pub fn new(solana: Arc<SolanaService>) -> Self {
+        Self { solana }
+    }
+}
+
+#[async_trait]
+impl TransactionSender for RpcSender {
+    fn name(&self) -> &str {
+        "rpc"
+    }
+
+    async fn send(&self, transaction: &Transaction, options: &SendOptions) -> Result<Signature> {
+        self.solana.send_transaction_with_config(transaction, rpc_send_config(options)).await
+    }
+}
+
+/// `sendTransaction` config carrying the execution's options
+fn rpc_send_config(options: &SendOptions) -> RpcSendTransactionConfig {
+    RpcSendTransactionConfig {
+        skip_preflight: options.skip_preflight,
+        preflight_commitment: Some(options.preflight_commitment),
+        max_retries: options.max_retries,
+        ..Default::default()
+    }
+}
+
+/// Sends through the Jito block engine
+pub struct JitoSender {
+    jito: JitoService,
+    config: JitoConfig,
+}
+
+impl JitoSender {
+    pub fn new(config: JitoConfig) -> Result<Self> {
+        Ok(Self {
+            jito: JitoService::new(config.clone())?,
+            config,
+        })
+    }
+}
+
+#[async_trait]
+impl TransactionSender for JitoSender {
+    fn name(&self) -> &str {
+        "jito"
+    }
+
+    async fn send(&self, transaction: &Transaction, options: &SendOptions) -> Result<Signature> {
+        let mut config = self.config.clone();
+        config.skip_preflight = options.skip_preflight;
+        if let Some(max_retries) = options.max_retries {
+            config.max_retries = max_retries as u32;
+        }
+
+        let response = self.jito.send_transaction(transaction, &config).await?;
+        Ok(Signature::from_str(&response.signature)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Urgency;
+    use solana_commitment_config::CommitmentLevel;
+
+    #[test]
+    fn test_rpc_config_carries_the_urgency_options() {
+        let high = rpc_send_config(&SendOptions::for_urgency(Urgency::High));
+        assert!(high.skip_preflight);
+        assert_eq!(high.preflight_commitment, Some(CommitmentLevel::Processed));
+        assert_eq!(high.max_retries, Some(0));
+
+        let low = rpc_send_config(&SendOptions::for_urgency(Urgency::Low));
+        assert!(!low.skip_preflight);
+        assert_eq!(low.preflight_commitment, Some(CommitmentLevel::Confirmed));
+        assert_eq!(low.max_retries, None);
+    }
+}
@@ -1,5 +1,9 @@
 use anyhow::Result;
-use solana_rpc_client::rpc_client::RpcClient;
+use async_trait::async_trait;
+use solana_rpc_client::{http_sender::HttpSender, nonblocking::rpc_client::RpcClient, rpc_client::RpcClientConfig};
+use solana_rpc_client_api::client_error::{Error as ClientError, ErrorKind as ClientErrorKind, Result as ClientResult};
+use solana_rpc_client_api::custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY;
+use solana_rpc_client_api::request::RpcError;
 use solana_commitment_config::CommitmentConfig;
 use solana_sdk::{
     pubkey::Pubkey,
@@ -8,44 +12,202 @@ use solana_sdk::{
 };
 use solana_system_interface::instruction as system_instruction;
 use solana_message::Message;
-use solana_transaction_status::UiTransactionEncoding;
+use solana_transaction_status::{UiTransactionEncoding, UiTransactionTokenBalance};
 use solana_program::program_pack::Pack;
+use std::collections::HashMap;
+use std::future::Future;
 use std::str::FromStr;
-use spl_associated_token_account_interface::address::get_associated_token_address;
+use spl_associated_token_account_interface::address::get_associated_token_address_with_program_id;
+use std::time::Duration;
+
+use crate::config::{AccountCacheConfig, ConnectionConfig};
+use crate::services::account_cache::AccountCache;
+use crate::services::keepalive::{tuned_client, ConnectionStats, KeepAliveTarget};
+use crate::services::metaplex::{self, MetaplexMetadata};
+use crate::services::wallet::SignedTransactions;
+use crate::utils::retry::RetryPolicy;
+
+/// A token account owned by a wallet
+#[derive(Debug, Clone)]
+pub struct TokenHolding {
+    pub address: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub decimals: u8,
+    pub is_frozen: bool,
+    /// SPL Token or Token-2022
+    pub program_id: Pubkey,
+    /// Rent held by the account
+    pub lamports: u64,
+}
+
+/// What a confirmed transaction did, read from its metadata
+#[derive(Debug, Clone, Default)]
+pub struct TransactionOutcome {
+    pub slot: u64,
+    /// Set when the transaction landed but failed
+    pub err: Option<String>,
+    /// Lamports charged to the fee payer
+    pub fee: u64,
+    pub compute_units: Option<u64>,
+    /// Raw balance change of the wallet's token accounts by mint, with the mint's decimals
+    pub token_deltas: HashMap<Pubkey, (i128, u8)>,
+    pub logs: Vec<String>,
+}
+
+/// Net raw balance change per mint across the token accounts `owner` holds before or after
+pub fn owner_token_deltas(
+    pre: &[UiTransactionTokenBalance],
+    post: &[UiTransactionTokenBalance],
+    owner: &str,
+) -> HashMap<Pubkey, (i128, u8)> {
+    let mut deltas: HashMap<Pubkey, (i128, u8)> = HashMap::new();
+    // Accounts opened by the transaction only appear after it, closed ones only before
+    let sides = pre.iter().map(|b| (b, -1)).chain(post.iter().map(|b| (b, 1)));
+    for (balance, sign) in sides {
+        let owned = Option::from(balance.owner.as_ref()).is_some_and(|o: &String| o == owner);
+        let (true, Ok(mint), Ok(amount)) = (owned, Pubkey::from_str(&balance.mint), balance.ui_token_amount.amount.parse::<i128>()) else {
+            continue;
+        };
+        let entry = deltas.entry(mint).or_insert((0, balance.ui_token_amount.decimals));
+        entry.0 += sign * amount;
+    }
+    deltas
+}
+
+/// Whether an RPC call is worth making again: timeouts, dropped connections, throttling, server
+/// errors and a node catching up are; rejected requests and transaction errors are not
+pub fn is_transient_rpc_error(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status().is_some_and(|status| status.as_u16() == 429 || status.is_server_error())
+        }
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+        _ => false,
+    }
+}
 
 /// Solana service
 pub struct SolanaService {
     rpc_client: RpcClient,
     commitment: CommitmentConfig,
+    rpc_url: String,
+    connections: ConnectionStats,
+    account_cache: Option<AccountCache>,
+    retry: RetryPolicy,
+    /// Limit on each attempt of an RPC call
+    timeout: Duration,
 }
 
+/// Per-call RPC timeout when none is configured
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
 impl SolanaService {
     /// Create a new Solana service instance
     pub fn new(rpc_url: &str) -> Result<Self> {
-        let rpc_client = RpcClient::new(rpc_url.to_string());
+        Self::with_connections(rpc_url, &ConnectionConfig::default())
+    }
+    
+    /// Create a Solana service whose HTTP connections are tuned for reuse
+    pub fn with_connections(rpc_url: &str, config: &ConnectionConfig) -> Result<Self> {
+        let (client, connections) = tuned_client(config, DEFAULT_RPC_TIMEOUT)?;
         let commitment = CommitmentConfig::confirmed();
+        let sender = HttpSender::new_with_client(rpc_url, client);
+        let rpc_client = RpcClient::new_sender(sender, RpcClientConfig::with_commitment(commitment));
         
         Ok(Self {
             rpc_client,
             commitment,
+            rpc_url: rpc_url.to_string(),
+            connections,
+            account_cache: None,
+            retry: RetryPolicy::default(),
+            timeout: DEFAULT_RPC_TIMEOUT,
         })
     }
+
+    /// Service for a network config, with its commitment, per-call timeout and retries
+    pub fn from_config(config: &SolanaConfig) -> Result<Self> {
+        let mut service = Self::new(&config.rpc_url)?
+            .with_timeout(config.timeout)
+            .with_retry(RetryPolicy::new(config.max_retries));
+        service.set_commitment(config.commitment);
+        Ok(service)
+    }
+
+    /// Apply the configured retries and per-call timeout
+    pub fn with_rpc_config(self, config: &crate::config::SolanaConfig) -> Self {
+        self.with_retry(RetryPolicy::new(config.max_retries))
+            .with_timeout(Duration::from_secs(config.timeout_seconds))
+    }
+
+    /// Fail each attempt of an RPC call that takes longer than `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retry RPC calls failing transiently under `policy`
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Make an RPC call, timing out each attempt and retrying it while it fails transiently
+    async fn rpc<T, F, Fut>(&self, call: F) -> ClientResult<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = ClientResult<T>>,
+    {
+        let attempt = || async {
+            match tokio::time::timeout(self.timeout, call()).await {
+                Ok(result) => result,
+                // Surfaced as I/O so the retry policy treats it like any other timeout
+                Err(_) => Err(ClientErrorKind::Io(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("RPC call timed out after {:?}", self.timeout),
+                ))
+                .into()),
+            }
+        };
+        self.retry.run(attempt, is_transient_rpc_error).await
+    }
+
+    /// Serve account reads from a slot-tagged cache when enabled
+    pub fn with_account_cache(mut self, config: &AccountCacheConfig) -> Self {
+        self.account_cache = config.enabled.then(|| AccountCache::new(config.clone()));
+        self
+    }
+
+    /// Account cache hits and misses, when the cache is enabled
+    pub fn account_cache_stats(&self) -> Option<(u64, u64)> {
+        self.account_cache.as_ref().map(AccountCache::stats)
+    }
     
     /// Get account balance
     pub async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64> {
-        let balance = self.rpc_client.get_balance_with_commitment(pubkey, self.commitment)?;
+        let balance = self.rpc(|| self.rpc_client.get_balance_with_commitment(pubkey, self.commitment)).await?;
         Ok(balance.value)
     }
     
     /// Get account info
     pub async fn get_account_info(&self, pubkey: &Pubkey) -> Result<Option<solana_sdk::account::Account>> {
-        let account = self.rpc_client.get_account_with_commitment(pubkey, self.commitment)?;
+        if let Some(cached) = self.account_cache.as_ref().and_then(|cache| cache.get(pubkey)) {
+            return Ok(cached);
+        }
+        let account = self.rpc(|| self.rpc_client.get_account_with_commitment(pubkey, self.commitment)).await?;
+        if let Some(cache) = &self.account_cache {
+            cache.insert(account.context.slot, [(*pubkey, account.value.clone())]);
+        }
         Ok(account.value)
     }
     
     /// Get recent blockhash
     pub async fn get_recent_blockhash(&self) -> Result<solana_sdk::hash::Hash> {
-        let blockhash = self.rpc_client.get_latest_blockhash()?;
+        let blockhash = self.rpc(|| self.rpc_client.get_latest_blockhash()).await?;
         Ok(blockhash)
     }
     
@@ -54,8 +216,9 @@ impl SolanaService {
         &self,
         signature: &Signature,
     ) -> Result<Option<bool>> {
-        let status = self.rpc_client.get_transaction(signature, UiTransactionEncoding::Json)?;
-        Ok(Some(true)) // If transaction info can be retrieved, the transaction exists
+        let status = self.rpc(|| self.rpc_client.get_transaction(signature, UiTransactionEncoding::Json)).await?;
+        // A retrievable transaction without status meta is reported as unknown
+        Ok(status.transaction.meta.map(|meta| meta.err.is_none()))
     }
     
     /// Send transaction
@@ -63,10 +226,66 @@ impl SolanaService {
         &self,
         transaction: &Transaction,
     ) -> Result<Signature> {
-        let signature = self.rpc_client.send_and_confirm_transaction(transaction)?;
+        let signature = self.rpc(|| self.rpc_client.send_and_confirm_transaction(transaction)).await?;
         Ok(signature)
     }
     
+    /// Send transaction without waiting for confirmation
+    pub async fn send_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        config: solana_rpc_client_api::config::RpcSendTransactionConfig,
+    ) -> Result<Signature> {
+        let signature = self.rpc(|| self.rpc_client.send_transaction_with_config(transaction, config)).await?;
+        Ok(signature)
+    }
+
+    /// Simulate a transaction against the latest state, returning the post-state of `watch`.
+    /// Signatures are not checked and the blockhash is replaced, so a stale build still simulates.
+    pub async fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+        watch: &[Pubkey],
+    ) -> Result<solana_rpc_client_api::response::RpcSimulateTransactionResult> {
+        use solana_rpc_client_api::config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig, UiAccountEncoding};
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(CommitmentConfig::processed()),
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                addresses: watch.iter().map(Pubkey::to_string).collect(),
+            }),
+            ..Default::default()
+        };
+        let result = self.rpc(|| self.rpc_client.simulate_transaction_with_config(transaction, config.clone())).await?;
+        Ok(result.value)
+    }
+
+    /// Run the checks an RPC node's preflight would: simulate with signatures verified against
+    /// state at `commitment`, and fail with the program logs when the transaction would fail
+    pub async fn preflight_transaction(&self, transaction: &Transaction, commitment: solana_commitment_config::CommitmentLevel) -> Result<()> {
+        use solana_rpc_client_api::config::RpcSimulateTransactionConfig;
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: true,
+            commitment: Some(CommitmentConfig { commitment }),
+            ..Default::default()
+        };
+        let result = self.rpc(|| self.rpc_client.simulate_transaction_with_config(transaction, config.clone())).await?.value;
+        match result.err {
+            Some(err) => anyhow::bail!("Preflight failed: {}; logs: {}", err, result.logs.unwrap_or_default().join(" | ")),
+            None => Ok(()),
+        }
+    }
+
+    /// Check whether a blockhash can still be used
+    pub async fn is_blockhash_valid(&self, blockhash: &solana_sdk::hash::Hash) -> Result<bool> {
+        let valid = self.rpc(|| self.rpc_client.is_blockhash_valid(blockhash, self.commitment)).await?;
+        Ok(valid)
+    }
+
     /// Confirm transaction
     pub async fn confirm_transaction(
         &self,
@@ -76,10 +295,9 @@ impl SolanaService {
         let mut retries = 0;
         
         while retries < max_retries {
+            // A landed transaction that failed will not succeed on a later poll
             if let Some(status) = self.get_transaction_status(signature).await? {
-                if status {
-                    return Ok(true);
-                }
+                return Ok(status);
             }
             
             retries += 1;
@@ -94,7 +312,7 @@ impl SolanaService {
         &self,
         program_id: &Pubkey,
     ) -> Result<Vec<(Pubkey, solana_sdk::account::Account)>> {
-        let accounts = self.rpc_client.get_program_accounts(program_id)?;
+        let accounts = self.rpc(|| self.rpc_client.get_program_accounts(program_id)).await?;
         
         Ok(accounts)
     }
@@ -104,33 +322,72 @@ impl SolanaService {
         &self,
         token_account: &Pubkey,
     ) -> Result<u64> {
-        let balance = self.rpc_client.get_token_account_balance_with_commitment(
+        let balance = self.rpc(|| self.rpc_client.get_token_account_balance_with_commitment(
             token_account,
             self.commitment,
-        )?;
+        )).await?;
         
         Ok(balance.value.amount.parse().unwrap_or(0))
     }
     
-    /// Get token account info
+    /// Get token account info; Token-2022 accounts are read without their extensions
     pub async fn get_token_account_info(
         &self,
         token_account: &Pubkey,
     ) -> Result<Option<spl_token_interface::state::Account>> {
         let account_info = self.get_account_info(token_account).await?;
         
-        if let Some(info) = account_info {
-            if info.owner == spl_token_interface::id() {
-                let account = spl_token_interface::state::Account::unpack(&info.data)?;
-                Ok(Some(account))
-            } else {
-                Ok(None)
+        match account_info {
+            Some(info) if info.owner == spl_token_interface::id() => {
+                Ok(Some(spl_token_interface::state::Account::unpack(&info.data)?))
             }
-        } else {
-            Ok(None)
+            // The base layout is shared, so the classic parser reads the leading bytes
+            Some(info) if info.owner == spl_token_2022_interface::id() => {
+                let base = info.data.get(..spl_token_interface::state::Account::LEN).unwrap_or(&info.data);
+                Ok(Some(spl_token_interface::state::Account::unpack(base)?))
+            }
+            _ => Ok(None),
         }
     }
     
+    /// Get all SPL Token and Token-2022 accounts owned by a wallet
+    pub async fn get_token_holdings(&self, owner: &Pubkey) -> Result<Vec<TokenHolding>> {
+        use solana_rpc_client_api::request::TokenAccountsFilter;
+
+        let mut holdings = Vec::new();
+        for program_id in [spl_token_interface::id(), spl_token_2022_interface::id()] {
+            let accounts = self.rpc(|| self.rpc_client.get_token_accounts_by_owner_with_commitment(
+                owner,
+                TokenAccountsFilter::ProgramId(program_id),
+                self.commitment,
+            )).await?;
+
+            for keyed in accounts.value {
+                // Accounts come back jsonParsed
+                let account = serde_json::to_value(&keyed.account)?;
+                let info = &account["data"]["parsed"]["info"];
+                let (Some(mint), Some(amount)) = (
+                    info["mint"].as_str().and_then(|m| Pubkey::from_str(m).ok()),
+                    info["tokenAmount"]["amount"].as_str().and_then(|a| a.parse::<u64>().ok()),
+                ) else {
+                    continue;
+                };
+
+                holdings.push(TokenHolding {
+                    address: Pubkey::from_str(&keyed.pubkey)?,
+                    mint,
+                    amount,
+                    decimals: info["tokenAmount"]["decimals"].as_u64().unwrap_or(0) as u8,
+                    is_frozen: info["state"].as_str() == Some("frozen"),
+                    program_id,
+                    lamports: keyed.account.lamports,
+                });
+            }
+        }
+
+        Ok(holdings)
+    }
+
     /// Create token account
     pub async fn create_token_account(
         &self,
@@ -138,21 +395,29 @@ impl SolanaService {
         mint: &Pubkey,
         owner: &Pubkey,
     ) -> Result<Pubkey> {
-        let associated_token_account = get_associated_token_address(owner, mint);
+        let token_program = match self.get_mint_program(mint).await? {
+            Some((program, _)) => program,
+            None => anyhow::bail!("{} is not a token mint", mint),
+        };
+        let associated_token_account = get_associated_token_address_with_program_id(owner, mint, &token_program);
         
         let instruction = spl_associated_token_account_interface::instruction::create_associated_token_account(
             &payer.pubkey(),
             owner,
             mint,
-            &spl_token_interface::id(),
+            &token_program,
         );
         
         let recent_blockhash = self.get_recent_blockhash().await?;
         let message = Message::new(&[instruction], Some(&payer.pubkey()));
         let transaction = Transaction::new(&[payer], message, recent_blockhash);
+        SignedTransactions::global().record(&transaction);
         
         let signature = self.send_transaction(&transaction).await?;
         self.confirm_transaction(&signature, 10).await?;
+        if let Some(cache) = &self.account_cache {
+            cache.invalidate(&associated_token_account);
+        }
         
         Ok(associated_token_account)
     }
@@ -168,74 +433,143 @@ impl SolanaService {
         let recent_blockhash = self.get_recent_blockhash().await?;
         let message = Message::new(&[instruction], Some(&from.pubkey()));
         let transaction = Transaction::new(&[from], message, recent_blockhash);
+        SignedTransactions::global().record(&transaction);
         
         let signature = self.send_transaction(&transaction).await?;
         Ok(signature)
     }
     
+    /// Signatures of transactions touching `address`, newest first, stopping before `until`
+    pub async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        until: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<solana_rpc_client_api::response::RpcConfirmedTransactionStatusWithSignature>> {
+        // The config is not Clone, so each attempt builds its own
+        let config = || solana_rpc_client::rpc_client::GetConfirmedSignaturesForAddress2Config {
+            before: None,
+            until,
+            limit: Some(limit),
+            commitment: Some(self.commitment),
+        };
+        Ok(self.rpc(|| self.rpc_client.get_signatures_for_address_with_config(address, config())).await?)
+    }
+
     /// Get network info
     pub async fn get_network_info(&self) -> Result<solana_rpc_client_api::response::RpcVersionInfo> {
-        let version = self.rpc_client.get_version()?;
+        let version = self.rpc(|| self.rpc_client.get_version()).await?;
         Ok(version)
     }
     
     /// Get slot info
     pub async fn get_slot_info(&self) -> Result<u64> {
-        let slot = self.rpc_client.get_slot_with_commitment(self.commitment)?;
+        let slot = self.rpc(|| self.rpc_client.get_slot_with_commitment(self.commitment)).await?;
         Ok(slot)
     }
     
     /// Get block height
     pub async fn get_block_height(&self) -> Result<u64> {
-        let height = self.rpc_client.get_block_height_with_commitment(self.commitment)?;
+        let height = self.rpc(|| self.rpc_client.get_block_height_with_commitment(self.commitment)).await?;
         Ok(height)
     }
     
     /// Get cluster nodes
     pub async fn get_cluster_nodes(&self) -> Result<Vec<solana_rpc_client_api::response::RpcContactInfo>> {
-        let nodes = self.rpc_client.get_cluster_nodes()?;
+        let nodes = self.rpc(|| self.rpc_client.get_cluster_nodes()).await?;
         Ok(nodes)
     }
     
     /// Get performance samples
     pub async fn get_performance_samples(&self) -> Result<Vec<solana_rpc_client_api::response::RpcPerfSample>> {
-        let samples = self.rpc_client.get_recent_performance_samples(Some(10))?;
+        let samples = self.rpc(|| self.rpc_client.get_recent_performance_samples(Some(10))).await?;
         Ok(samples)
     }
     
     /// Get vote accounts
     pub async fn get_vote_accounts(&self) -> Result<solana_rpc_client_api::response::RpcVoteAccountStatus> {
-        let vote_accounts = self.rpc_client.get_vote_accounts_with_commitment(self.commitment)?;
+        let vote_accounts = self.rpc(|| self.rpc_client.get_vote_accounts_with_commitment(self.commitment)).await?;
         Ok(vote_accounts)
     }
     
+    /// Get epoch info
+    pub async fn get_epoch_info(&self) -> Result<solana_sdk::epoch_info::EpochInfo> {
+        let epoch_info = self.rpc(|| self.rpc_client.get_epoch_info_with_commitment(self.commitment)).await?;
+        Ok(epoch_info)
+    }
+
     /// Get leader schedule
     pub async fn get_leader_schedule(&self) -> Result<Option<solana_rpc_client_api::response::RpcLeaderSchedule>> {
-        let schedule = self.rpc_client.get_leader_schedule_with_commitment(
-            Some(self.get_slot_info().await?),
-            self.commitment,
-        )?;
+        let slot = self.get_slot_info().await?;
+        let schedule = self.rpc(|| self.rpc_client.get_leader_schedule_with_commitment(Some(slot), self.commitment)).await?;
         Ok(schedule)
     }
     
     /// Get block time
     pub async fn get_block_time(&self, slot: u64) -> Result<i64> {
-        let time = self.rpc_client.get_block_time(slot)?;
+        let time = self.rpc(|| self.rpc_client.get_block_time(slot)).await?;
         Ok(time)
     }
     
     /// Get block
     pub async fn get_block(&self, slot: u64) -> Result<Option<String>> {
-        let block = self.rpc_client.get_block(slot)?;
+        let block = self.rpc(|| self.rpc_client.get_block(slot)).await?;
         Ok(Some(block.blockhash))
     }
     
+    /// Where each signature stands at the service's commitment: None while it has not reached it,
+    /// otherwise the slot it landed in or the error it failed with
+    pub async fn get_signature_confirmations(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<Result<u64, String>>>> {
+        let statuses = self.rpc(|| self.rpc_client.get_signature_statuses(signatures)).await?;
+        Ok(statuses
+            .value
+            .into_iter()
+            .map(|status| {
+                let status = status.filter(|s| s.satisfies_commitment(self.commitment))?;
+                Some(match status.err {
+                    Some(err) => Err(err.to_string()),
+                    None => Ok(status.slot),
+                })
+            })
+            .collect())
+    }
+
+    /// Read a confirmed transaction's fee, compute units, logs and the token balance changes of `owner`
+    pub async fn get_transaction_outcome(&self, signature: &Signature, owner: &Pubkey) -> Result<TransactionOutcome> {
+        use solana_rpc_client_api::config::RpcTransactionConfig;
+
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(self.commitment),
+            max_supported_transaction_version: Some(0),
+        };
+        let transaction = self.rpc(|| self.rpc_client.get_transaction_with_config(signature, config)).await?;
+        let meta = transaction
+            .transaction
+            .meta
+            .ok_or_else(|| anyhow::anyhow!("Transaction {} has no status metadata", signature))?;
+        let pre: Option<Vec<_>> = meta.pre_token_balances.into();
+        let post: Option<Vec<_>> = meta.post_token_balances.into();
+
+        Ok(TransactionOutcome {
+            slot: transaction.slot,
+            err: meta.err.map(|e| e.to_string()),
+            fee: meta.fee,
+            compute_units: meta.compute_units_consumed.into(),
+            token_deltas: owner_token_deltas(&pre.unwrap_or_default(), &post.unwrap_or_default(), &owner.to_string()),
+            logs: Option::from(meta.log_messages).unwrap_or_default(),
+        })
+    }
+
     /// Get signature statuses
     pub async fn get_signature_statuses(
         &self,
         signatures: &[Signature],
     ) -> Result<Vec<Option<bool>>> {
-        let statuses = self.rpc_client.get_signature_statuses(signatures)?;
+        let statuses = self.rpc(|| self.rpc_client.get_signature_statuses(signatures)).await?;
         Ok(statuses.value.into_iter().map(|s| s.map(|_| true)).collect())
     }
     
@@ -244,11 +578,23 @@ impl SolanaService {
         &self,
         pubkeys: &[Pubkey],
     ) -> Result<Vec<Option<solana_sdk::account::Account>>> {
-        let accounts = self.rpc_client.get_multiple_accounts_with_commitment(
-            pubkeys,
-            self.commitment,
-        )?;
-        Ok(accounts.value)
+        let Some(cache) = &self.account_cache else {
+            let accounts = self.rpc(|| self.rpc_client.get_multiple_accounts_with_commitment(pubkeys, self.commitment)).await?;
+            return Ok(accounts.value);
+        };
+
+        // Only the accounts missing from the cache go to RPC
+        let mut results: Vec<Option<Option<solana_sdk::account::Account>>> = pubkeys.iter().map(|p| cache.get(p)).collect();
+        let missing: Vec<Pubkey> = pubkeys.iter().zip(&results).filter(|(_, r)| r.is_none()).map(|(p, _)| *p).collect();
+        if !missing.is_empty() {
+            let fetched = self.rpc(|| self.rpc_client.get_multiple_accounts_with_commitment(&missing, self.commitment)).await?;
+            cache.insert(fetched.context.slot, missing.iter().copied().zip(fetched.value.iter().cloned()));
+            let mut fetched = fetched.value.into_iter();
+            for result in results.iter_mut().filter(|r| r.is_none()) {
+                *result = Some(fetched.next().flatten());
+            }
+        }
+        Ok(results.into_iter().map(Option::flatten).collect())
     }
     
     /// Get account history
@@ -257,7 +603,7 @@ impl SolanaService {
         pubkey: &Pubkey,
         limit: usize,
     ) -> Result<Vec<bool>> {
-        let history = self.rpc_client.get_signatures_for_address(pubkey)?;
+        let history = self.rpc(|| self.rpc_client.get_signatures_for_address(pubkey)).await?;
         
         let mut transactions = Vec::new();
         for sig_info in history.iter().take(limit) {
@@ -272,32 +618,57 @@ impl SolanaService {
     
     /// Get token supply
     pub async fn get_token_supply(&self, mint: &Pubkey) -> Result<u64> {
-        let supply = self.rpc_client.get_token_supply(mint)?;
+        let supply = self.rpc(|| self.rpc_client.get_token_supply(mint)).await?;
         Ok(supply.amount.parse().unwrap_or(0))
     }
-    
+
+    /// Get the largest token accounts of a mint with their raw balances, largest first
+    pub async fn get_token_largest_accounts(&self, mint: &Pubkey) -> Result<Vec<(Pubkey, u64)>> {
+        let accounts = self.rpc(|| self.rpc_client.get_token_largest_accounts(mint)).await?;
+        accounts
+            .into_iter()
+            .map(|account| Ok((Pubkey::from_str(&account.address)?, account.amount.amount.parse().unwrap_or(0))))
+            .collect()
+    }
+
     /// Get token max supply
     pub async fn get_token_max_supply(&self, mint: &Pubkey) -> Result<Option<u64>> {
-        let mint_info = self.get_account_info(mint).await?;
-        
-        if let Some(info) = mint_info {
-            if info.owner == spl_token_interface::id() {
-                let mint_state = spl_token_interface::state::Mint::unpack(&info.data)?;
-                Ok(Some(mint_state.supply))
-            } else {
-                Ok(None)
+        Ok(self.get_mint_state(mint).await?.map(|mint| mint.supply))
+    }
+    
+    /// Get token decimals from the mint account
+    pub async fn get_mint_decimals(&self, mint: &Pubkey) -> Result<Option<u8>> {
+        Ok(self.get_mint_state(mint).await?.map(|mint| mint.decimals))
+    }
+
+    /// Base mint state of an SPL Token or Token-2022 mint
+    async fn get_mint_state(&self, mint: &Pubkey) -> Result<Option<spl_token_2022_interface::state::Mint>> {
+        use spl_token_2022_interface::extension::StateWithExtensions;
+
+        match self.get_account_info(mint).await? {
+            Some(info) if info.owner == spl_token_interface::id() || info.owner == spl_token_2022_interface::id() => {
+                Ok(Some(StateWithExtensions::<spl_token_2022_interface::state::Mint>::unpack(&info.data)?.base))
             }
-        } else {
-            Ok(None)
+            _ => Ok(None),
         }
     }
-    
-    /// Get token metadata
-    pub async fn get_token_metadata(&self, _mint: &Pubkey) -> Result<Option<solana_sdk::account::Account>> {
-        // let metadata_address = spl_token_metadata::state::get_metadata_account(mint);  // Temporarily commented out due to incompatible dependency
-        // self.get_account_info(&metadata_address).await
-        // Temporarily return None until dependency issue is resolved
-        Ok(None)
+
+    /// Get the program owning a mint and the Token-2022 extensions it carries
+    pub async fn get_mint_program(&self, mint: &Pubkey) -> Result<Option<(Pubkey, Vec<crate::models::TokenExtension>)>> {
+        let Some(info) = self.get_account_info(mint).await? else {
+            return Ok(None);
+        };
+        // The transfer fee is left unread, so the epoch does not matter
+        let account = crate::services::token_registry::MintAccount::parse(&info.owner, &info.data, 0)?;
+        Ok(account.map(|account| (account.program, account.extensions)))
+    }
+
+    /// Get the Metaplex metadata of a mint, if it has any
+    pub async fn get_token_metadata(&self, mint: &Pubkey) -> Result<Option<MetaplexMetadata>> {
+        match self.get_account_info(&metaplex::metadata_address(mint)).await? {
+            Some(account) if account.owner == metaplex::TOKEN_METADATA_PROGRAM_ID => Ok(Some(MetaplexMetadata::parse(&account.data)?)),
+            _ => Ok(None),
+        }
     }
     
     /// Verify transaction
@@ -309,11 +680,10 @@ impl SolanaService {
     
     /// Estimate transaction fee
     pub async fn estimate_transaction_fee(&self, transaction: &Transaction) -> Result<u64> {
-        let _blockhash = self.rpc_client.get_latest_blockhash()?;
-        // In newer versions, fee calculation has changed; use a fixed fee
-        let lamports_per_signature = 5000; // Default signature fee
+        let _blockhash = self.rpc(|| self.rpc_client.get_latest_blockhash()).await?;
+        // In newer versions, fee calculation has changed; use the default signature fee
         let num_signatures = transaction.message.header.num_required_signatures as u64;
-        Ok(lamports_per_signature * num_signatures)
+        Ok(crate::services::fee_model::DEFAULT_LAMPORTS_PER_SIGNATURE * num_signatures)
     }
     
     /// Get RPC client reference
@@ -332,6 +702,22 @@ impl SolanaService {
     }
 }
 
+#[async_trait]
+impl KeepAliveTarget for SolanaService {
+    fn endpoint_name(&self) -> String {
+        format!("rpc:{}", self.rpc_url)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.rpc_client.get_health().await?;
+        Ok(())
+    }
+
+    fn connection_stats(&self) -> ConnectionStats {
+        self.connections.clone()
+    }
+}
+
 /// Solana network type
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SolanaNetwork {
@@ -442,6 +828,7 @@ impl SolanaConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     
     #[test]
     fn test_solana_network() {
@@ -450,10 +837,65 @@ mod tests {
         assert_eq!(SolanaNetwork::from_str("invalid"), None);
     }
     
+    #[test]
+    fn test_owner_token_deltas_span_opened_and_closed_accounts() {
+        let owner = Pubkey::new_unique().to_string();
+        let (usdc, bonk) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let balance = |index: u8, mint: &Pubkey, amount: u64, owner: &str| -> UiTransactionTokenBalance {
+            serde_json::from_value(serde_json::json!({
+                "accountIndex": index,
+                "mint": mint.to_string(),
+                "uiTokenAmount": { "uiAmount": null, "decimals": 6, "amount": amount.to_string(), "uiAmountString": "" },
+                "owner": owner,
+            }))
+            .unwrap()
+        };
+
+        // USDC 1000 -> 1012; a bonk account opened and emptied is absent; someone else's pool vault is ignored
+        let pre = vec![balance(1, &usdc, 1_000, &owner), balance(2, &usdc, 50_000, "pool")];
+        let post = vec![balance(1, &usdc, 1_012, &owner), balance(3, &bonk, 0, &owner), balance(2, &usdc, 49_988, "pool")];
+        let deltas = owner_token_deltas(&pre, &post, &owner);
+        assert_eq!(deltas[&usdc], (12, 6));
+        assert_eq!(deltas[&bonk], (0, 6));
+
+        // A closed account counts as spent
+        let deltas = owner_token_deltas(&[balance(1, &bonk, 40, &owner)], &[], &owner);
+        assert_eq!(deltas[&bonk], (-40, 6));
+    }
+
     #[test]
     fn test_solana_config() {
         let config = SolanaConfig::new(SolanaNetwork::Testnet);
         assert_eq!(config.network, SolanaNetwork::Testnet);
         assert_eq!(config.rpc_url, SolanaNetwork::Testnet.get_rpc_url());
     }
+
+    #[tokio::test]
+    async fn test_rpc_calls_time_out_per_attempt() {
+        // A node that accepts connections and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let accepts = AtomicUsize::new(0);
+        let silent_node = async {
+            let mut held = Vec::new();
+            loop {
+                held.push(listener.accept().await.unwrap());
+                accepts.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+
+        let service = SolanaService::new(&url).unwrap()
+            .with_timeout(Duration::from_millis(100))
+            .with_retry(RetryPolicy { max_retries: 1, base_delay: Duration::from_millis(1), jitter: 0.0, ..RetryPolicy::default() });
+        let started = std::time::Instant::now();
+        let error = tokio::select! {
+            result = service.get_slot_info() => result.unwrap_err(),
+            _ = silent_node => unreachable!(),
+        };
+
+        assert!(error.to_string().contains("timed out"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        // The timeout counts as transient, so the call was made twice
+        assert_eq!(accepts.load(Ordering::SeqCst), 2);
+    }
 }
@@ -0,0 +1,271 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{RestartPolicy, SupervisorConfig},
+    services::{
+        alert_rules::{AlertAction, FiredAlert},
+        metrics::MetricsRegistry,
+    },
+    utils::panic_guard::panic_message,
+};
+
+/// Health of a supervised task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskHealth {
+    Running,
+    /// Waiting out the backoff before the next attempt
+    Restarting,
+    /// Exited and will not be restarted
+    Stopped,
+}
+
+/// Registry entry for one background task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    pub name: String,
+    pub policy: RestartPolicy,
+    pub health: TaskHealth,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// How one run of a task ended
+enum Exit {
+    Finished,
+    Failed(String),
+    Panicked(String),
+}
+
+impl Exit {
+    fn describe(&self) -> String {
+        match self {
+            Exit::Finished => "exited".to_string(),
+            Exit::Failed(e) => format!("failed: {}", e),
+            Exit::Panicked(message) => format!("panicked: {}", message),
+        }
+    }
+}
+
+/// Spawns background tasks and restarts them according to their policy
+#[derive(Clone)]
+pub struct TaskSupervisor {
+    config: SupervisorConfig,
+    tasks: Arc<RwLock<HashMap<String, TaskStatus>>>,
+    metrics: MetricsRegistry,
+    alert_sender: Option<mpsc::Sender<FiredAlert>>,
+}
+
+impl TaskSupervisor {
+    pub fn new(config: SupervisorConfig, metrics: MetricsRegistry) -> Self {
+        Self {
+            config,
+            tasks: Arc::new(RwLock::new(HashMap::new())),
+            metrics,
+            alert_sender: None,
+        }
+    }
+
+    /// Raise an alert whenever a task fails or panics
+    pub fn with_alerts(mut self, alert_sender: mpsc::Sender<FiredAlert>) -> Self {
+        self.alert_sender = Some(alert_sender);
+        self
+    }
+
+    /// The configured policy for a task, falling back to `default`
+    pub fn policy_for(&self, name: &str, default: RestartPolicy) -> RestartPolicy {
+        self.config.policies.get(name).copied().unwrap_or(default)
+    }
+
+    /// Register a task and run it under supervision.
+    ///
+    /// `factory` builds a fresh future for every attempt, since a task's state is usually consumed
+    /// by the run that failed.
+    pub async fn spawn<F, Fut>(&self, name: &str, default_policy: RestartPolicy, mut factory: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let policy = self.policy_for(name, default_policy);
+        self.tasks.write().await.insert(
+            name.to_string(),
+            TaskStatus {
+                name: name.to_string(),
+                policy,
+                health: TaskHealth::Running,
+                restarts: 0,
+                last_error: None,
+                started_at: Utc::now(),
+            },
+        );
+
+        let supervisor = self.clone();
+        let name = name.to_string();
+        tokio::spawn(async move {
+            let mut consecutive_failures = 0u32;
+            loop {
+                let started = Instant::now();
+                // A separate task per attempt turns a panic into a JoinError instead of unwinding here
+                let exit = match tokio::spawn(factory()).await {
+                    Ok(Ok(())) => Exit::Finished,
+                    Ok(Err(e)) => Exit::Failed(e.to_string()),
+                    Err(e) if e.is_panic() => Exit::Panicked(panic_message(e.into_panic().as_ref())),
+                    Err(e) => Exit::Failed(e.to_string()),
+                };
+
+                // A long healthy run resets the backoff
+                if started.elapsed() >= supervisor.max_backoff() {
+                    consecutive_failures = 0;
+                }
+                consecutive_failures += 1;
+
+                let delay = supervisor.restart_delay(policy, consecutive_failures);
+                supervisor.record_exit(&name, &exit, delay.is_some()).await;
+                match delay {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => break,
+                }
+                supervisor.set_health(&name, TaskHealth::Running).await;
+            }
+        });
+    }
+
+    /// Delay before restarting after the given number of consecutive failures, or None to stop
+    fn restart_delay(&self, policy: RestartPolicy, consecutive_failures: u32) -> Option<Duration> {
+        let initial = Duration::from_millis(self.config.initial_backoff_ms.max(1));
+        match policy {
+            RestartPolicy::Never => None,
+            RestartPolicy::Always => Some(initial),
+            RestartPolicy::Backoff => {
+                let factor = 2u32.saturating_pow(consecutive_failures.saturating_sub(1).min(16));
+                Some(initial.saturating_mul(factor).min(self.max_backoff()))
+            }
+        }
+    }
+
+    fn max_backoff(&self) -> Duration {
+        Duration::from_millis(self.config.max_backoff_ms.max(self.config.initial_backoff_ms).max(1))
+    }
+
+    async fn record_exit(&self, name: &str, exit: &Exit, restarting: bool) {
+        let outcome = exit.describe();
+        {
+            let mut tasks = self.tasks.write().await;
+            if let Some(status) = tasks.get_mut(name) {
+                status.health = if restarting { TaskHealth::Restarting } else { TaskHealth::Stopped };
+                if restarting {
+                    status.restarts += 1;
+                }
+                if !matches!(exit, Exit::Finished) {
+                    status.last_error = Some(outcome.clone());
+                }
+            }
+        }
+
+        if restarting {
+            warn!("Task '{}' {}, restarting", name, outcome);
+            self.metrics
+                .increment(&MetricsRegistry::labeled("task_restarts", &[("task", name)]), 1.0)
+                .await;
+        } else {
+            error!("Task '{}' {}, not restarting", name, outcome);
+        }
+        self.publish_health().await;
+
+        if matches!(exit, Exit::Finished) {
+            return;
+        }
+        if let Some(sender) = &self.alert_sender {
+            let alert = FiredAlert {
+                rule_name: format!("task:{}", name),
+                action: AlertAction::Notify(self.config.alert_channel.clone()),
+                message: format!("Background task '{}' {}", name, outcome),
+                value: 1.0,
+                timestamp: Utc::now(),
+            };
+            if sender.try_send(alert).is_err() {
+                warn!("Alert queue full, dropped alert for task '{}'", name);
+            }
+        }
+    }
+
+    async fn set_health(&self, name: &str, health: TaskHealth) {
+        if let Some(status) = self.tasks.write().await.get_mut(name) {
+            info!("Task '{}' started (restart {})", name, status.restarts);
+            status.health = health;
+            status.started_at = Utc::now();
+        }
+        self.publish_health().await;
+    }
+
+    async fn publish_health(&self) {
+        let unhealthy = self
+            .tasks
+            .read()
+            .await
+            .values()
+            .filter(|status| status.health != TaskHealth::Running)
+            .count();
+        self.metrics.set("tasks_unhealthy", unhealthy as f64).await;
+    }
+
+    /// Status of every registered task, sorted by name
+    pub async fn statuses(&self) -> Vec<TaskStatus> {
+        let mut statuses: Vec<TaskStatus> = self.tasks.read().await.values().cloned().collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_panics_are_restarted_and_alerted() {
+        let config = SupervisorConfig { initial_backoff_ms: 1, max_backoff_ms: 4, ..Default::default() };
+        let (alert_sender, mut alerts) = mpsc::channel(10);
+        let supervisor = TaskSupervisor::new(config, MetricsRegistry::new()).with_alerts(alert_sender);
+
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counter = attempts.clone();
+        supervisor
+            .spawn("flaky", RestartPolicy::Backoff, move || {
+                let attempt = counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt == 0 {
+                        panic!("boom");
+                    }
+                    anyhow::bail!("attempt {} failed", attempt)
+                }
+            })
+            .await;
+        supervisor.spawn("once", RestartPolicy::Never, || async { anyhow::bail!("fatal") }).await;
+
+        let first = alerts.recv().await.unwrap();
+        assert!(first.rule_name.starts_with("task:"));
+        while attempts.load(Ordering::SeqCst) < 3 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let statuses = supervisor.statuses().await;
+        assert!(statuses[0].restarts >= 2);
+        assert_eq!(statuses[1].health, TaskHealth::Stopped);
+        assert_eq!(statuses[1].restarts, 0);
+
+        assert_eq!(supervisor.restart_delay(RestartPolicy::Backoff, 1), Some(Duration::from_millis(1)));
+        assert_eq!(supervisor.restart_delay(RestartPolicy::Backoff, 10), Some(Duration::from_millis(4)));
+        assert_eq!(supervisor.restart_delay(RestartPolicy::Never, 1), None);
+    }
+}
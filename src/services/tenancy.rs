@@ -0,0 +1,213 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    arbitrage::outcome,
+    config::TenancyConfig,
+    models::{ArbitrageExecution, ArbitrageStrategy, Tags},
+    utils::secret::SecretKeypair,
+};
+
+/// Tag naming the tenant an opportunity or execution belongs to
+pub const NAMESPACE_TAG: &str = "namespace";
+/// Tag naming the wallet a tenant's execution signs with
+pub const WALLET_TAG: &str = "wallet";
+
+/// A tenant as the engine and control API see it; the API token itself is not kept
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tenant {
+    pub namespace: String,
+    /// Signing wallet, when the tenant has its own
+    pub wallet: Option<Pubkey>,
+    /// Net loss per UTC day that stops the tenant's strategies; None when unlimited
+    pub daily_loss_limit: Option<Decimal>,
+}
+
+impl Tenant {
+    /// Net profit of the tenant's executions since `since`
+    pub fn net_profit_since(&self, executions: &[ArbitrageExecution], since: DateTime<Utc>) -> Decimal {
+        let own: Vec<ArbitrageExecution> = executions
+            .iter()
+            .filter(|e| e.execution_time >= since && e.has_tag(NAMESPACE_TAG, &self.namespace))
+            .cloned()
+            .collect();
+        outcome::summarize(0, &own).net_profit
+    }
+}
+
+/// Who is calling the control API
+#[derive(Debug, Clone, PartialEq)]
+pub enum Caller {
+    /// Every namespace; also the only caller when tenancy is off
+    Operator,
+    Tenant(Tenant),
+}
+
+impl Caller {
+    /// The namespace the caller is confined to; None for the operator
+    pub fn namespace(&self) -> Option<&str> {
+        match self {
+            Caller::Operator => None,
+            Caller::Tenant(tenant) => Some(&tenant.namespace),
+        }
+    }
+
+    /// Whether an opportunity or execution with these tags is visible to the caller
+    pub fn sees(&self, tags: &Tags) -> bool {
+        self.namespace().is_none_or(|namespace| tags.get(NAMESPACE_TAG).is_some_and(|tag| tag == namespace))
+    }
+
+    pub fn owns(&self, strategy: &ArbitrageStrategy) -> bool {
+        self.namespace().is_none_or(|namespace| strategy.namespace == namespace)
+    }
+
+    /// Strategy id as stored, from the id the caller used; tenants may leave out their namespace
+    pub fn scope(&self, id: &str) -> String {
+        ArbitrageStrategy::scoped_id(self.namespace().unwrap_or_default(), id)
+    }
+}
+
+/// Tenants sharing the instance, looked up by a digest of their API token
+#[derive(Debug, Default)]
+pub struct TenantRegistry {
+    enabled: bool,
+    operator: Option<[u8; 32]>,
+    by_token: HashMap<[u8; 32], Tenant>,
+}
+
+fn digest(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+impl TenantRegistry {
+    /// Registry for the configured tenants; tenant wallets are read only for their public keys
+    pub fn from_config(config: &TenancyConfig) -> Result<Self> {
+        let mut by_token = HashMap::new();
+        let mut namespaces = HashSet::new();
+        for tenant in &config.tenants {
+            if tenant.namespace.is_empty() || tenant.namespace.contains('/') {
+                anyhow::bail!("Invalid tenant namespace '{}'", tenant.namespace);
+            }
+            if tenant.api_token.is_empty() {
+                anyhow::bail!("Tenant {} has no API token", tenant.namespace);
+            }
+            if !namespaces.insert(tenant.namespace.clone()) {
+                anyhow::bail!("Tenant namespace {} is configured twice", tenant.namespace);
+            }
+            let wallet = match tenant.wallet.keypair_path.as_str() {
+                "" => None,
+                path => Some(
+                    SecretKeypair::read_file(path)
+                        .map_err(|e| anyhow::anyhow!("Failed to read wallet of tenant {}: {}", tenant.namespace, e))?
+                        .keypair()
+                        .pubkey(),
+                ),
+            };
+            let entry = Tenant {
+                namespace: tenant.namespace.clone(),
+                wallet,
+                daily_loss_limit: Decimal::from_f64(tenant.daily_loss_limit).filter(|limit| *limit > Decimal::ZERO),
+            };
+            if by_token.insert(digest(&tenant.api_token), entry).is_some() {
+                anyhow::bail!("Tenant {} shares its API token with another tenant", tenant.namespace);
+            }
+        }
+        let operator = (!config.operator_token.is_empty()).then(|| digest(&config.operator_token));
+        if operator.is_some_and(|operator| by_token.contains_key(&operator)) {
+            anyhow::bail!("The operator token is also a tenant token");
+        }
+        Ok(Self { enabled: config.enabled, operator, by_token })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The caller presenting `token`. Without tenancy everyone is the operator; with it, an unknown
+    /// or missing token is nobody.
+    pub fn authenticate(&self, token: Option<&str>) -> Option<Caller> {
+        if !self.enabled {
+            return Some(Caller::Operator);
+        }
+        let token = digest(token?);
+        if self.operator == Some(token) {
+            return Some(Caller::Operator);
+        }
+        self.by_token.get(&token).cloned().map(Caller::Tenant)
+    }
+
+    pub fn tenant(&self, namespace: &str) -> Option<&Tenant> {
+        self.by_token.values().find(|tenant| tenant.namespace == namespace)
+    }
+
+    /// Namespaces whose losses today have reached their daily limit
+    pub fn exhausted_budgets(&self, executions: &[ArbitrageExecution], now: DateTime<Utc>) -> HashSet<String> {
+        if !self.enabled {
+            return HashSet::new();
+        }
+        let midnight = now.date_naive().and_hms_opt(0, 0, 0).map(|t| t.and_utc()).unwrap_or(now);
+        self.by_token
+            .values()
+            .filter(|tenant| {
+                tenant
+                    .daily_loss_limit
+                    .is_some_and(|limit| tenant.net_profit_since(executions, midnight) <= -limit)
+            })
+            .map(|tenant| tenant.namespace.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{TenantConfig, WalletConfig};
+    use crate::models::ExecutionStatus;
+    use crate::utils::fixtures;
+
+    #[test]
+    fn test_tokens_resolve_to_tenants_with_budgets() {
+        let tenant = |namespace: &str, token: &str, limit: f64| TenantConfig {
+            namespace: namespace.to_string(),
+            api_token: token.to_string(),
+            wallet: WalletConfig::default(),
+            daily_loss_limit: limit,
+        };
+        let mut config = TenancyConfig {
+            enabled: true,
+            operator_token: "root".to_string(),
+            tenants: vec![tenant("alpha", "a-token", 10.0), tenant("beta", "b-token", 0.0)],
+        };
+        let registry = TenantRegistry::from_config(&config).unwrap();
+        assert_eq!(registry.authenticate(Some("root")), Some(Caller::Operator));
+        assert!(matches!(registry.authenticate(Some("a-token")), Some(Caller::Tenant(t)) if t.namespace == "alpha"));
+        assert_eq!(registry.authenticate(Some("nope")), None);
+        assert_eq!(registry.authenticate(None), None);
+
+        let loss = |namespace: &str, profit: i64| {
+            let mut opportunity = fixtures::opportunity();
+            opportunity.tags.insert(NAMESPACE_TAG.to_string(), namespace.to_string());
+            let mut execution = ArbitrageExecution::new(opportunity);
+            execution.execution_status = ExecutionStatus::Confirmed;
+            execution.actual_profit = Some(Decimal::from(profit));
+            execution.total_cost = Some(Decimal::ONE);
+            execution
+        };
+        // Beta has no limit; alpha's 11 lost today reaches its 10 limit
+        let executions = vec![loss("alpha", -4), loss("alpha", -5), loss("beta", -100)];
+        let now = executions[0].execution_time;
+        assert_eq!(registry.exhausted_budgets(&executions, now), HashSet::from(["alpha".to_string()]));
+        assert!(registry.exhausted_budgets(&executions[..1], now).is_empty());
+
+        config.tenants[1].api_token = "a-token".to_string();
+        assert!(TenantRegistry::from_config(&config).is_err());
+        config.enabled = false;
+        config.tenants.clear();
+        assert_eq!(TenantRegistry::from_config(&config).unwrap().authenticate(None), Some(Caller::Operator));
+    }
+}
@@ -0,0 +1,562 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::{
+    config::TokenRegistryConfig,
+    dex::DexType,
+    models::{Pool, Token, TokenExtension, TransferFee},
+    services::solana::SolanaService,
+    utils::panic_guard::lock_or_recover,
+};
+
+/// Accounts per `getMultipleAccounts` request
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// What a mint is called and how it is denominated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenInfo {
+    pub mint: Pubkey,
+    pub symbol: String,
+    pub name: String,
+    pub decimals: u8,
+    pub logo_uri: Option<String>,
+}
+
+impl TokenInfo {
+    /// Name the token after this mint; decimals only fill in for adapters that could not tell
+    pub fn apply(&self, token: &mut Token) {
+        token.symbol = self.symbol.clone();
+        token.name = self.name.clone();
+        if token.decimals == 0 {
+            token.decimals = self.decimals;
+        }
+        if token.logo_uri.is_none() {
+            token.logo_uri = self.logo_uri.clone();
+        }
+    }
+}
+
+/// What a mint account itself says about its token: the program owning it, its extensions and
+/// the transfer fee in force
+#[derive(Debug, Clone, PartialEq)]
+pub struct MintAccount {
+    pub program: Pubkey,
+    pub extensions: Vec<TokenExtension>,
+    pub transfer_fee: Option<TransferFee>,
+}
+
+impl MintAccount {
+    /// Parse an SPL Token or Token-2022 mint account, taking the transfer fee of `epoch`; None for
+    /// accounts of any other program
+    pub fn parse(owner: &Pubkey, data: &[u8], epoch: u64) -> Result<Option<Self>> {
+        use spl_token_2022_interface::extension::{
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+        };
+
+        if *owner == spl_token_interface::id() {
+            return Ok(Some(Self { program: *owner, extensions: Vec::new(), transfer_fee: None }));
+        }
+        if *owner != spl_token_2022_interface::id() {
+            return Ok(None);
+        }
+        let state = StateWithExtensions::<spl_token_2022_interface::state::Mint>::unpack(data)?;
+        let extensions = state.get_extension_types()?.into_iter().map(Into::into).collect();
+        let transfer_fee = state.get_extension::<TransferFeeConfig>().ok().map(|config| {
+            let fee = config.get_epoch_fee(epoch);
+            TransferFee { basis_points: u16::from(fee.transfer_fee_basis_points), maximum_fee: u64::from(fee.maximum_fee) }
+        });
+        Ok(Some(Self { program: *owner, extensions, transfer_fee }))
+    }
+
+    pub fn apply(&self, token: &mut Token) {
+        token.token_program = self.program;
+        token.extensions = self.extensions.clone();
+        token.transfer_fee = self.transfer_fee;
+    }
+}
+
+/// Somewhere mint identities can be looked up
+#[async_trait]
+pub trait TokenSource: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Identities of the given mints; mints the source does not know are left out
+    async fn lookup(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, TokenInfo>>;
+}
+
+#[derive(Debug, Deserialize)]
+struct JupiterToken {
+    address: String,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    #[serde(rename = "logoURI", default)]
+    logo_uri: Option<String>,
+}
+
+/// Parse a Jupiter token list into identities keyed by mint, skipping malformed entries
+fn parse_token_list(body: &str) -> Result<HashMap<Pubkey, TokenInfo>> {
+    let tokens: Vec<JupiterToken> = serde_json::from_str(body).context("Invalid Jupiter token list")?;
+    Ok(tokens
+        .into_iter()
+        .filter_map(|token| {
+            let mint = Pubkey::from_str(&token.address).ok()?;
+            Some((mint, TokenInfo { mint, symbol: token.symbol, name: token.name, decimals: token.decimals, logo_uri: token.logo_uri }))
+        })
+        .collect())
+}
+
+type TokenList = Arc<HashMap<Pubkey, TokenInfo>>;
+
+/// The Jupiter token list, downloaded whole and reused for `refresh_seconds`
+pub struct JupiterTokenList {
+    client: Client,
+    url: String,
+    refresh: std::time::Duration,
+    tokens: RwLock<Option<(Instant, TokenList)>>,
+}
+
+impl JupiterTokenList {
+    pub fn new(config: &TokenRegistryConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_seconds.max(1)))
+            .build()?;
+        Ok(Self {
+            client,
+            url: config.token_list_url.clone(),
+            refresh: std::time::Duration::from_secs(config.refresh_seconds.max(1)),
+            tokens: RwLock::new(None),
+        })
+    }
+
+    async fn tokens(&self) -> Result<TokenList> {
+        let cached = self.tokens.read().await.as_ref().filter(|(at, _)| at.elapsed() < self.refresh).map(|(_, t)| t.clone());
+        if let Some(tokens) = cached {
+            return Ok(tokens);
+        }
+        let body = self.client.get(&self.url).send().await?.error_for_status()?.text().await?;
+        let tokens = Arc::new(parse_token_list(&body)?);
+        debug!("Loaded {} tokens from the Jupiter token list", tokens.len());
+        *self.tokens.write().await = Some((Instant::now(), tokens.clone()));
+        Ok(tokens)
+    }
+}
+
+#[async_trait]
+impl TokenSource for JupiterTokenList {
+    fn name(&self) -> &str {
+        "jupiter"
+    }
+
+    async fn lookup(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, TokenInfo>> {
+        let tokens = self.tokens().await?;
+        Ok(mints.iter().filter_map(|mint| Some((*mint, tokens.get(mint)?.clone()))).collect())
+    }
+}
+
+/// When a mint account was read, and what it held if it was a mint
+type MintRead = (DateTime<Utc>, Option<MintAccount>);
+
+/// Mint identities resolved once and shared by every adapter's pools.
+///
+/// DEX APIs mostly return bare mints, which the adapters label "BASE" and "QUOTE". Unknown mints
+/// are asked of each source in turn; a mint none of them knows is not asked about again for
+/// `miss_retry_seconds`, so an obscure token does not cost a lookup every scan.
+///
+/// With an RPC attached the mint accounts are read as well, so Token-2022 tokens carry their
+/// program and extensions and their accounts are derived under the right program. Mints with a
+/// transfer fee are read again every `transfer_fee_refresh_seconds`, since the fee can change at
+/// an epoch boundary; an address that holds no mint waits out `miss_retry_seconds` like a miss.
+pub struct TokenRegistry {
+    sources: Vec<Arc<dyn TokenSource>>,
+    solana: Option<Arc<SolanaService>>,
+    miss_retry: Duration,
+    transfer_fee_refresh: Duration,
+    known: Mutex<HashMap<Pubkey, TokenInfo>>,
+    misses: Mutex<HashMap<Pubkey, DateTime<Utc>>>,
+    mint_accounts: Mutex<HashMap<Pubkey, MintRead>>,
+}
+
+impl TokenRegistry {
+    pub fn new(config: &TokenRegistryConfig) -> Self {
+        let wsol = TokenInfo {
+            mint: spl_token_interface::native_mint::id(),
+            symbol: "SOL".to_string(),
+            name: "Wrapped SOL".to_string(),
+            decimals: 9,
+            logo_uri: None,
+        };
+        Self {
+            sources: Vec::new(),
+            solana: None,
+            miss_retry: Duration::seconds(config.miss_retry_seconds as i64),
+            transfer_fee_refresh: Duration::seconds(config.transfer_fee_refresh_seconds as i64),
+            known: Mutex::new(HashMap::from([(wsol.mint, wsol)])),
+            misses: Mutex::new(HashMap::new()),
+            mint_accounts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The registry with the sources the config enables
+    pub fn from_config(config: &TokenRegistryConfig) -> Result<Self> {
+        let mut registry = Self::new(config);
+        if config.enabled && !config.token_list_url.is_empty() {
+            registry = registry.with_source(Arc::new(JupiterTokenList::new(config)?));
+        }
+        Ok(registry)
+    }
+
+    /// Ask this source after the ones already added
+    pub fn with_source(mut self, source: Arc<dyn TokenSource>) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Read mint accounts over this RPC
+    pub fn with_mint_accounts(mut self, solana: Arc<SolanaService>) -> Self {
+        self.solana = Some(solana);
+        self
+    }
+
+    pub fn get(&self, mint: &Pubkey) -> Option<TokenInfo> {
+        lock_or_recover(&self.known, "token registry").get(mint).cloned()
+    }
+
+    pub fn mint_account(&self, mint: &Pubkey) -> Option<MintAccount> {
+        lock_or_recover(&self.mint_accounts, "token registry mint accounts").get(mint).and_then(|(_, account)| account.clone())
+    }
+
+    /// Look up the mints not yet known, source by source, and read the mint accounts not yet read
+    pub async fn resolve(&self, mints: &[Pubkey], now: DateTime<Utc>) {
+        self.read_mint_accounts(mints, now).await;
+        self.lookup(mints, now).await;
+    }
+
+    async fn lookup(&self, mints: &[Pubkey], now: DateTime<Utc>) {
+        let mut pending: Vec<Pubkey> = {
+            let known = lock_or_recover(&self.known, "token registry");
+            let misses = lock_or_recover(&self.misses, "token registry misses");
+            let mut pending: Vec<Pubkey> = mints
+                .iter()
+                .filter(|mint| !known.contains_key(mint))
+                .filter(|mint| misses.get(mint).is_none_or(|missed| now - *missed >= self.miss_retry))
+                .copied()
+                .collect();
+            pending.sort_unstable();
+            pending.dedup();
+            pending
+        };
+
+        for source in &self.sources {
+            if pending.is_empty() {
+                return;
+            }
+            match source.lookup(&pending).await {
+                Ok(found) => {
+                    pending.retain(|mint| !found.contains_key(mint));
+                    lock_or_recover(&self.known, "token registry").extend(found);
+                }
+                Err(e) => warn!("Token lookup through {} failed: {}", source.name(), e),
+            }
+        }
+
+        if !pending.is_empty() {
+            debug!("{} mint(s) unknown to every token source", pending.len());
+            let mut misses = lock_or_recover(&self.misses, "token registry misses");
+            misses.extend(pending.into_iter().map(|mint| (mint, now)));
+        }
+    }
+
+    async fn read_mint_accounts(&self, mints: &[Pubkey], now: DateTime<Utc>) {
+        let Some(solana) = &self.solana else {
+            return;
+        };
+        let mut unread: Vec<Pubkey> = {
+            let read = lock_or_recover(&self.mint_accounts, "token registry mint accounts");
+            mints
+                .iter()
+                .filter(|mint| match read.get(mint) {
+                    Some((read_at, Some(account))) => account.transfer_fee.is_some() && now - *read_at >= self.transfer_fee_refresh,
+                    Some((read_at, None)) => now - *read_at >= self.miss_retry,
+                    None => true,
+                })
+                .copied()
+                .collect()
+        };
+        unread.sort_unstable();
+        unread.dedup();
+
+        for mints in unread.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+            let accounts = match solana.get_multiple_accounts(mints).await {
+                Ok(accounts) => accounts,
+                Err(e) => {
+                    warn!("Failed to read {} mint account(s): {}", mints.len(), e);
+                    return;
+                }
+            };
+            // Transfer fees are scheduled by epoch, so Token-2022 mints need the current one
+            let epoch = if accounts.iter().flatten().any(|account| account.owner == spl_token_2022_interface::id()) {
+                match solana.get_epoch_info().await {
+                    Ok(info) => info.epoch,
+                    Err(e) => {
+                        warn!("Failed to read the epoch for mint transfer fees: {}", e);
+                        return;
+                    }
+                }
+            } else {
+                0
+            };
+            let mut read = lock_or_recover(&self.mint_accounts, "token registry mint accounts");
+            for (mint, account) in mints.iter().zip(accounts) {
+                let parsed = match account.map(|account| MintAccount::parse(&account.owner, &account.data, epoch)) {
+                    Some(Ok(Some(parsed))) => Some(parsed),
+                    Some(Err(e)) => {
+                        warn!("Failed to parse mint account {}: {}", mint, e);
+                        None
+                    }
+                    _ => {
+                        debug!("{} is not a token mint", mint);
+                        None
+                    }
+                };
+                read.insert(*mint, (now, parsed));
+            }
+        }
+    }
+
+    /// Name a token after its mint, when the mint is known, and set its program and extensions
+    /// when its mint account has been read
+    pub fn enrich(&self, token: &mut Token) -> bool {
+        if let Some(account) = self.mint_account(&token.mint) {
+            account.apply(token);
+        }
+        match self.get(&token.mint) {
+            Some(info) => {
+                info.apply(token);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Resolve every mint the pools trade and fill in their tokens
+    pub async fn enrich_pools(&self, pools: &mut HashMap<DexType, Vec<Pool>>, now: DateTime<Utc>) {
+        let mints: Vec<Pubkey> = pools.values().flatten().flat_map(|pool| [pool.token_a.mint, pool.token_b.mint]).collect();
+        self.resolve(&mints, now).await;
+        for pool in pools.values_mut().flatten() {
+            self.enrich(&mut pool.token_a);
+            self.enrich(&mut pool.token_b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_associated_token_account_interface::address::get_associated_token_address_with_program_id;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    struct ListSource {
+        tokens: HashMap<Pubkey, TokenInfo>,
+        lookups: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TokenSource for ListSource {
+        fn name(&self) -> &str {
+            "list"
+        }
+
+        async fn lookup(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, TokenInfo>> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            Ok(mints.iter().filter_map(|mint| Some((*mint, self.tokens.get(mint)?.clone()))).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pools_are_named_from_the_token_list_and_misses_are_not_retried() {
+        let usdc = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+        let body = r#"[
+            {"address": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "name": "USD Coin", "symbol": "USDC", "decimals": 6,
+             "logoURI": "https://example.com/usdc.png", "tags": ["verified"]},
+            {"address": "not-a-mint", "name": "Broken", "symbol": "BRK", "decimals": 6}
+        ]"#;
+        let tokens = parse_token_list(body).unwrap();
+        assert_eq!(tokens.len(), 1);
+        let source = Arc::new(ListSource { tokens, lookups: AtomicUsize::new(0) });
+        let registry = TokenRegistry::new(&TokenRegistryConfig { miss_retry_seconds: 60, ..TokenRegistryConfig::default() })
+            .with_source(source.clone());
+
+        let unknown = Pubkey::new_unique();
+        let label = |mint: Pubkey, symbol: &str| Token::new(mint, symbol.to_string(), format!("{} Token", symbol), 0);
+        let pool = |a: Token, b: Token| Pool::new(String::new(), DexType::Raydium, a, b, Pubkey::new_unique(), Pubkey::default(), Pubkey::default());
+        let mut pools = HashMap::from([(
+            DexType::Raydium,
+            vec![
+                pool(label(spl_token_interface::native_mint::id(), "BASE"), label(usdc, "QUOTE")),
+                pool(label(unknown, "BASE"), label(usdc, "QUOTE")),
+            ],
+        )]);
+
+        let now = Utc::now();
+        registry.enrich_pools(&mut pools, now).await;
+        let named = &pools[&DexType::Raydium];
+        assert_eq!((named[0].token_a.symbol.as_str(), named[0].token_a.decimals), ("SOL", 9));
+        assert_eq!((named[0].token_b.symbol.as_str(), named[0].token_b.decimals), ("USDC", 6));
+        assert_eq!(named[0].token_b.logo_uri.as_deref(), Some("https://example.com/usdc.png"));
+        assert_eq!(named[1].token_a.symbol, "BASE");
+        assert_eq!(source.lookups.load(Ordering::SeqCst), 1);
+
+        // Known mints come from the cache and the miss waits out its retry interval
+        registry.enrich_pools(&mut pools, now + Duration::seconds(30)).await;
+        assert_eq!(source.lookups.load(Ordering::SeqCst), 1);
+        registry.enrich_pools(&mut pools, now + Duration::seconds(60)).await;
+        assert_eq!(source.lookups.load(Ordering::SeqCst), 2);
+    }
+
+    type MintState<'a> = spl_token_2022_interface::extension::StateWithExtensionsMut<'a, spl_token_2022_interface::state::Mint>;
+
+    /// A Token-2022 mint account with room for `extensions`, which `init` sets up
+    fn token_2022_mint(extensions: &[spl_token_2022_interface::extension::ExtensionType], init: impl FnOnce(&mut MintState)) -> Vec<u8> {
+        use spl_token_2022_interface::extension::{BaseStateWithExtensionsMut, ExtensionType};
+        use spl_token_2022_interface::state::Mint;
+
+        let mut data = vec![0; ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap()];
+        let mut state = MintState::unpack_uninitialized(&mut data).unwrap();
+        init(&mut state);
+        state.base = Mint { decimals: 6, is_initialized: true, ..Mint::default() };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    /// A node serving `accounts` to `getMultipleAccounts`, counting the requests, in epoch `epoch`
+    async fn mint_node(accounts: HashMap<Pubkey, (Pubkey, Vec<u8>)>, requests: Arc<AtomicUsize>, epoch: Arc<AtomicU64>) -> Arc<SolanaService> {
+        use base64::Engine;
+        use serde_json::{json, Value};
+
+        let url = crate::utils::fixtures::rpc_node(move |method, params| {
+            if method == "getEpochInfo" {
+                return Ok(json!({ "absoluteSlot": 1, "blockHeight": 1, "epoch": epoch.load(Ordering::SeqCst), "slotIndex": 0, "slotsInEpoch": 432000 }));
+            }
+            assert_eq!(method, "getMultipleAccounts");
+            requests.fetch_add(1, Ordering::SeqCst);
+            let value: Vec<Value> = params[0]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|key| {
+                    let key = Pubkey::from_str(key.as_str().unwrap()).unwrap();
+                    accounts.get(&key).map_or(Value::Null, |(owner, data)| {
+                        let data = base64::engine::general_purpose::STANDARD.encode(data);
+                        json!({ "lamports": 1, "data": [data, "base64"], "owner": owner.to_string(), "executable": false, "rentEpoch": 0, "space": 0 })
+                    })
+                })
+                .collect();
+            Ok(json!({ "context": { "slot": 1 }, "value": value }))
+        })
+        .await;
+        Arc::new(SolanaService::new(&url).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_pool_tokens_take_their_program_and_extensions_from_the_mint_account() {
+        use spl_token_2022_interface::extension::{
+            mint_close_authority::MintCloseAuthority, transfer_hook::TransferHook, BaseStateWithExtensionsMut, ExtensionType,
+        };
+
+        let (classic, token_2022, missing) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let classic_data = {
+            use solana_program::program_pack::Pack;
+            let mut data = vec![0; spl_token_interface::state::Mint::LEN];
+            let mint = spl_token_interface::state::Mint { decimals: 9, is_initialized: true, ..Default::default() };
+            mint.pack_into_slice(&mut data);
+            data
+        };
+        let accounts = HashMap::from([
+            (classic, (spl_token_interface::id(), classic_data)),
+            (token_2022, (spl_token_2022_interface::id(), token_2022_mint(&[ExtensionType::TransferHook, ExtensionType::MintCloseAuthority], |state| {
+                state.init_extension::<TransferHook>(true).unwrap();
+                state.init_extension::<MintCloseAuthority>(true).unwrap();
+            }))),
+        ]);
+        let requests = Arc::new(AtomicUsize::new(0));
+        let registry = TokenRegistry::new(&TokenRegistryConfig::default()).with_mint_accounts(mint_node(accounts, requests.clone(), Arc::default()).await);
+
+        let token = |mint: Pubkey| Token::new(mint, "BASE".to_string(), String::new(), 0);
+        let pool = |a: Pubkey, b: Pubkey| Pool::new(String::new(), DexType::Raydium, token(a), token(b), Pubkey::new_unique(), Pubkey::default(), Pubkey::default());
+        let mut pools = HashMap::from([(DexType::Raydium, vec![pool(classic, token_2022), pool(missing, token_2022)])]);
+        registry.enrich_pools(&mut pools, Utc::now()).await;
+
+        let loaded = &pools[&DexType::Raydium];
+        assert!(!loaded[0].token_a.is_token_2022());
+        assert!(loaded[0].token_b.is_token_2022());
+        assert_eq!(loaded[0].token_b.extensions, vec![TokenExtension::TransferHook, TokenExtension::Other]);
+        let owner = Pubkey::new_unique();
+        assert_eq!(
+            loaded[1].token_b.associated_token_address(&owner),
+            get_associated_token_address_with_program_id(&owner, &token_2022, &spl_token_2022_interface::id())
+        );
+        // An account that is not there leaves the classic program assumed
+        assert!(!loaded[1].token_a.is_token_2022());
+        assert_eq!(registry.mint_account(&missing), None);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Mint accounts already read are not asked for again; a missing one waits out the retry
+        let now = Utc::now();
+        registry.enrich_pools(&mut pools, now).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        registry.resolve(&[classic, token_2022, missing], now + Duration::seconds(600)).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_fees_are_read_at_the_current_epoch_and_refreshed() {
+        use spl_token_2022_interface::extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensionsMut, ExtensionType};
+
+        // No fee until epoch 10, then 1% capped at 5,000 raw units
+        let mint = Pubkey::new_unique();
+        let data = token_2022_mint(&[ExtensionType::TransferFeeConfig], |state| {
+            let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+            config.newer_transfer_fee.epoch = 10.into();
+            config.newer_transfer_fee.transfer_fee_basis_points = 100.into();
+            config.newer_transfer_fee.maximum_fee = 5_000.into();
+        });
+        let (requests, epoch) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicU64::new(9)));
+        let node = mint_node(HashMap::from([(mint, (spl_token_2022_interface::id(), data))]), requests.clone(), epoch.clone()).await;
+        let config = TokenRegistryConfig { transfer_fee_refresh_seconds: 300, ..TokenRegistryConfig::default() };
+        let registry = TokenRegistry::new(&config).with_mint_accounts(node);
+
+        let sol = Token::new(spl_token_interface::native_mint::id(), "SOL".to_string(), String::new(), 9);
+        let mut pools = HashMap::from([(
+            DexType::Raydium,
+            vec![Pool::new(String::new(), DexType::Raydium, Token::new(mint, "BASE".to_string(), String::new(), 6), sol, Pubkey::new_unique(), Pubkey::default(), Pubkey::default())],
+        )]);
+        let now = Utc::now();
+        registry.enrich_pools(&mut pools, now).await;
+        let token = &pools[&DexType::Raydium][0].token_a;
+        assert!(token.has_extension(TokenExtension::TransferFee));
+        assert_eq!(token.transfer_fee, Some(TransferFee { basis_points: 0, maximum_fee: 0 }));
+        assert!(!token.has_transfer_fee());
+
+        // The new fee is picked up once the mint is read again in its epoch
+        epoch.store(10, Ordering::SeqCst);
+        registry.enrich_pools(&mut pools, now + Duration::seconds(299)).await;
+        assert!(!pools[&DexType::Raydium][0].token_a.has_transfer_fee());
+        registry.enrich_pools(&mut pools, now + Duration::seconds(300)).await;
+        let token = &pools[&DexType::Raydium][0].token_a;
+        assert_eq!(token.transfer_fee, Some(TransferFee { basis_points: 100, maximum_fee: 5_000 }));
+        assert_eq!(token.transfer_fee_rate(), rust_decimal::Decimal::new(1, 2));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,338 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use solana_program::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+use crate::config::TokenSafetyConfig;
+use crate::models::ArbitrageOpportunity;
+use crate::services::solana::SolanaService;
+use crate::utils::panic_guard::lock_or_recover;
+
+/// Why a mint is refused
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SafetyIssue {
+    #[error("blocklisted")]
+    Blocklisted,
+
+    #[error("mint authority {0} can still inflate supply")]
+    MintAuthority(Pubkey),
+
+    #[error("freeze authority {0} can freeze holders")]
+    FreezeAuthority(Pubkey),
+
+    #[error("transfer fee of {bps} bps exceeds {limit}")]
+    TransferFee { bps: u16, limit: u16 },
+
+    #[error("transfer hook program {0} runs on every transfer")]
+    TransferHook(Pubkey),
+
+    #[error("metadata can still be rewritten by {0}")]
+    MutableMetadata(Pubkey),
+
+    #[error("largest account holds {share} of supply (limit {limit})")]
+    HolderConcentration { share: Decimal, limit: Decimal },
+
+    #[error("not an SPL Token or Token-2022 mint")]
+    NotAMint,
+
+    #[error("could not be screened: {0}")]
+    Unscreened(String),
+}
+
+impl SafetyIssue {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SafetyIssue::Blocklisted => "blocklisted",
+            SafetyIssue::MintAuthority(_) => "mint_authority",
+            SafetyIssue::FreezeAuthority(_) => "freeze_authority",
+            SafetyIssue::TransferFee { .. } => "transfer_fee",
+            SafetyIssue::TransferHook(_) => "transfer_hook",
+            SafetyIssue::MutableMetadata(_) => "mutable_metadata",
+            SafetyIssue::HolderConcentration { .. } => "holder_concentration",
+            SafetyIssue::NotAMint => "not_a_mint",
+            SafetyIssue::Unscreened(_) => "unscreened",
+        }
+    }
+}
+
+/// What the chain says about a mint
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MintFacts {
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+    pub supply: u64,
+    /// Transfer fee of the current epoch, for Token-2022 mints with one
+    pub transfer_fee_bps: Option<u16>,
+    pub transfer_hook: Option<Pubkey>,
+    /// Raw balance of the largest token account
+    pub largest_holding: Option<u64>,
+    /// Update authority of Metaplex metadata that is still mutable
+    pub metadata_authority: Option<Pubkey>,
+}
+
+impl MintFacts {
+    /// Parse an SPL Token or Token-2022 mint account; None for anything else
+    pub fn from_account(owner: &Pubkey, data: &[u8], epoch: u64) -> Result<Option<Self>> {
+        use spl_token_2022_interface::extension::{
+            transfer_fee::TransferFeeConfig, transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions,
+        };
+
+        if *owner == spl_token_interface::id() {
+            let mint = spl_token_interface::state::Mint::unpack(data)?;
+            return Ok(Some(Self {
+                mint_authority: mint.mint_authority.into(),
+                freeze_authority: mint.freeze_authority.into(),
+                supply: mint.supply,
+                ..Self::default()
+            }));
+        }
+        if *owner != spl_token_2022_interface::id() {
+            return Ok(None);
+        }
+
+        let state = StateWithExtensions::<spl_token_2022_interface::state::Mint>::unpack(data)?;
+        let transfer_fee_bps = state
+            .get_extension::<TransferFeeConfig>()
+            .ok()
+            .map(|config| u16::from(config.get_epoch_fee(epoch).transfer_fee_basis_points));
+        let transfer_hook = state
+            .get_extension::<TransferHook>()
+            .ok()
+            .and_then(|hook| Option::<Pubkey>::from(hook.program_id));
+        Ok(Some(Self {
+            mint_authority: state.base.mint_authority.into(),
+            freeze_authority: state.base.freeze_authority.into(),
+            supply: state.base.supply,
+            transfer_fee_bps,
+            transfer_hook,
+            ..Self::default()
+        }))
+    }
+
+    /// Fetch the mint account and its largest holder, and its Metaplex metadata when asked to
+    pub async fn fetch(solana: &SolanaService, mint: &Pubkey, with_metadata: bool) -> Result<Option<Self>> {
+        let Some(account) = solana.get_account_info(mint).await? else {
+            return Ok(None);
+        };
+        let epoch = solana.get_epoch_info().await?.epoch;
+        let Some(mut facts) = Self::from_account(&account.owner, &account.data, epoch)? else {
+            return Ok(None);
+        };
+        facts.largest_holding = solana.get_token_largest_accounts(mint).await?.first().map(|(_, amount)| *amount);
+        if with_metadata {
+            facts.metadata_authority = solana.get_token_metadata(mint).await?.and_then(|metadata| metadata.mutable_by());
+        }
+        Ok(Some(facts))
+    }
+}
+
+/// Outcome of screening one mint; safe when it raised no issues
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafetyReport {
+    pub mint: Pubkey,
+    pub issues: Vec<SafetyIssue>,
+    pub screened_at: DateTime<Utc>,
+}
+
+impl SafetyReport {
+    pub fn is_safe(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+fn parse_mints(mints: &[String], list: &str) -> HashSet<Pubkey> {
+    mints
+        .iter()
+        .filter_map(|mint| match Pubkey::from_str(mint) {
+            Ok(mint) => Some(mint),
+            Err(e) => {
+                warn!("Ignoring invalid {} mint {}: {}", list, mint, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Screens mints for rug risk before they are traded: authorities that can inflate supply or
+/// freeze holders, Token-2022 transfer fees and hooks, metadata that can be rewritten to pose as
+/// another token, and supply concentrated in one account.
+///
+/// The blocklist and allowlist always apply; on-chain screening only when enabled, with results
+/// cached for `cache_ttl_seconds`. A mint that cannot be fetched is refused but not cached.
+pub struct TokenScreener {
+    config: TokenSafetyConfig,
+    allowlist: HashSet<Pubkey>,
+    blocklist: HashSet<Pubkey>,
+    solana: Option<Arc<SolanaService>>,
+    cache: Mutex<HashMap<Pubkey, SafetyReport>>,
+}
+
+impl TokenScreener {
+    pub fn new(config: &TokenSafetyConfig) -> Self {
+        Self {
+            allowlist: parse_mints(&config.allowlist, "allowlist"),
+            blocklist: parse_mints(&config.blocklist, "blocklist"),
+            config: config.clone(),
+            solana: None,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_solana(mut self, solana: Arc<SolanaService>) -> Self {
+        self.solana = Some(solana);
+        self
+    }
+
+    /// Some(true) for allowlisted mints, Some(false) for blocklisted ones; the blocklist wins
+    pub fn listed(&self, mint: &Pubkey) -> Option<bool> {
+        if self.blocklist.contains(mint) {
+            Some(false)
+        } else if self.allowlist.contains(mint) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /// Judge a mint on its on-chain facts
+    pub fn evaluate(&self, mint: &Pubkey, facts: &MintFacts, now: DateTime<Utc>) -> SafetyReport {
+        let mut issues = Vec::new();
+        if self.config.reject_mint_authority {
+            issues.extend(facts.mint_authority.map(SafetyIssue::MintAuthority));
+        }
+        if self.config.reject_freeze_authority {
+            issues.extend(facts.freeze_authority.map(SafetyIssue::FreezeAuthority));
+        }
+        if let Some(bps) = facts.transfer_fee_bps.filter(|bps| *bps > self.config.max_transfer_fee_bps) {
+            issues.push(SafetyIssue::TransferFee { bps, limit: self.config.max_transfer_fee_bps });
+        }
+        if self.config.reject_transfer_hook {
+            issues.extend(facts.transfer_hook.map(SafetyIssue::TransferHook));
+        }
+        if self.config.reject_mutable_metadata {
+            issues.extend(facts.metadata_authority.map(SafetyIssue::MutableMetadata));
+        }
+        let limit = Decimal::from_f64(self.config.max_top_holder_share).filter(|limit| *limit > Decimal::ZERO);
+        if let (Some(limit), Some(largest)) = (limit, facts.largest_holding) {
+            if facts.supply > 0 {
+                let share = (Decimal::from(largest) / Decimal::from(facts.supply)).round_dp(4);
+                if share > limit {
+                    issues.push(SafetyIssue::HolderConcentration { share, limit });
+                }
+            }
+        }
+        SafetyReport { mint: *mint, issues, screened_at: now }
+    }
+
+    /// Screen a mint, from the lists, the cache or the chain
+    pub async fn screen(&self, mint: &Pubkey, now: DateTime<Utc>) -> SafetyReport {
+        match self.listed(mint) {
+            Some(true) => return SafetyReport { mint: *mint, issues: Vec::new(), screened_at: now },
+            Some(false) => return SafetyReport { mint: *mint, issues: vec![SafetyIssue::Blocklisted], screened_at: now },
+            None => {}
+        }
+        let Some(solana) = self.solana.as_ref().filter(|_| self.config.enabled) else {
+            return SafetyReport { mint: *mint, issues: Vec::new(), screened_at: now };
+        };
+
+        let ttl = Duration::seconds(self.config.cache_ttl_seconds as i64);
+        if let Some(report) = lock_or_recover(&self.cache, "token safety cache").get(mint) {
+            if now - report.screened_at < ttl {
+                return report.clone();
+            }
+        }
+
+        let report = match MintFacts::fetch(solana, mint, self.config.reject_mutable_metadata).await {
+            Ok(Some(facts)) => self.evaluate(mint, &facts, now),
+            Ok(None) => SafetyReport { mint: *mint, issues: vec![SafetyIssue::NotAMint], screened_at: now },
+            Err(e) => {
+                warn!("Failed to screen mint {}: {}", mint, e);
+                return SafetyReport { mint: *mint, issues: vec![SafetyIssue::Unscreened(e.to_string())], screened_at: now };
+            }
+        };
+        if report.is_safe() {
+            debug!("Mint {} passed screening", mint);
+        } else {
+            info!("Mint {} refused: {}", mint, report.issues.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "));
+        }
+        lock_or_recover(&self.cache, "token safety cache").insert(*mint, report.clone());
+        report
+    }
+
+    /// The first unsafe report among the tokens an opportunity trades, if any
+    pub async fn screen_opportunity(&self, opportunity: &ArbitrageOpportunity, now: DateTime<Utc>) -> Option<SafetyReport> {
+        let mut mints = vec![opportunity.base_token.mint, opportunity.quote_token.mint];
+        if let Some(route) = &opportunity.route {
+            mints.extend(route.pools.iter().flat_map(|pool| [pool.token_a.mint, pool.token_b.mint]));
+        }
+        let mut seen = HashSet::new();
+        for mint in mints.into_iter().filter(|mint| seen.insert(*mint)) {
+            let report = self.screen(&mint, now).await;
+            if !report.is_safe() {
+                return Some(report);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_screening_flags_risky_mints_and_honours_lists() {
+        let allowed = Pubkey::new_unique();
+        let blocked = Pubkey::new_unique();
+        let config = TokenSafetyConfig {
+            enabled: true,
+            allowlist: vec![allowed.to_string(), blocked.to_string(), "not-a-mint".to_string()],
+            blocklist: vec![blocked.to_string()],
+            max_transfer_fee_bps: 50,
+            max_top_holder_share: 0.5,
+            reject_mutable_metadata: true,
+            ..TokenSafetyConfig::default()
+        };
+        let screener = TokenScreener::new(&config);
+        let now = Utc::now();
+
+        assert_eq!(screener.listed(&allowed), Some(true));
+        assert_eq!(screener.listed(&blocked), Some(false));
+        assert!(screener.screen(&allowed, now).await.is_safe());
+        assert_eq!(screener.screen(&blocked, now).await.issues, vec![SafetyIssue::Blocklisted]);
+
+        let mint = Pubkey::new_unique();
+        let renounced = MintFacts { supply: 1_000, largest_holding: Some(200), transfer_fee_bps: Some(25), ..MintFacts::default() };
+        assert!(screener.evaluate(&mint, &renounced, now).is_safe());
+
+        let authority = Pubkey::new_unique();
+        let risky = MintFacts {
+            mint_authority: Some(authority),
+            freeze_authority: Some(authority),
+            supply: 1_000,
+            transfer_fee_bps: Some(300),
+            transfer_hook: Some(Pubkey::new_unique()),
+            largest_holding: Some(900),
+            metadata_authority: Some(authority),
+        };
+        let labels: Vec<_> = screener.evaluate(&mint, &risky, now).issues.iter().map(SafetyIssue::label).collect();
+        assert_eq!(labels, ["mint_authority", "freeze_authority", "transfer_fee", "transfer_hook", "mutable_metadata", "holder_concentration"]);
+
+        // An SPL Token mint account with both authorities renounced
+        let mut data = vec![0; spl_token_interface::state::Mint::LEN];
+        spl_token_interface::state::Mint::pack(
+            spl_token_interface::state::Mint { supply: 1_000, decimals: 6, is_initialized: true, ..Default::default() },
+            &mut data,
+        )
+        .unwrap();
+        let facts = MintFacts::from_account(&spl_token_interface::id(), &data, 0).unwrap().unwrap();
+        assert_eq!(facts, MintFacts { supply: 1_000, ..MintFacts::default() });
+        assert!(MintFacts::from_account(&Pubkey::new_unique(), &data, 0).unwrap().is_none());
+    }
+}
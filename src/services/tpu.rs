@@ -0,0 +1,325 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use quinn::{crypto::rustls::QuicClientConfig, ClientConfig, Connection, Endpoint, IdleTimeout, TransportConfig};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use solana_sdk::{signature::Signature, transaction::Transaction};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tracing::{debug, warn};
+
+use crate::config::{SubmissionConfig, TpuConfig};
+use crate::models::SendOptions;
+use crate::services::leader_schedule::{LeaderScheduleTracker, SLOT_DURATION};
+use crate::services::sender::TransactionSender;
+use crate::services::solana::SolanaService;
+
+/// ALPN protocol validators accept on their TPU QUIC port
+const ALPN_TPU: &[u8] = b"solana-tpu";
+
+/// Server name validators expect; their certificates are self-signed, so it is not checked
+const SERVER_NAME: &str = "connect";
+
+/// Retries of a fan-out that reached no leader when the options leave it to the sender
+const DEFAULT_SEND_RETRIES: usize = 2;
+
+/// Validators present self-signed certificates, so only the handshake signatures are verified
+#[derive(Debug)]
+struct SkipServerVerification(Arc<rustls::crypto::CryptoProvider>);
+
+impl ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn client_config() -> Result<ClientConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut crypto = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(SkipServerVerification(provider)))
+        .with_no_client_auth();
+    crypto.alpn_protocols = vec![ALPN_TPU.to_vec()];
+
+    let mut transport = TransportConfig::default();
+    transport.max_idle_timeout(Some(IdleTimeout::try_from(Duration::from_secs(10))?));
+    transport.keep_alive_interval(Some(Duration::from_secs(1)));
+
+    let mut config = ClientConfig::new(Arc::new(QuicClientConfig::try_from(crypto)?));
+    config.transport_config(Arc::new(transport));
+    Ok(config)
+}
+
+/// Leader identity -> TPU QUIC address, from gossip
+struct ContactCache {
+    addresses: HashMap<String, SocketAddr>,
+    refreshed_at: Option<Instant>,
+}
+
+/// Sends transactions over QUIC straight to the TPU ports of the current and upcoming leaders,
+/// skipping the RPC node's forwarding. Connections are unstaked, so they get the unstaked share
+/// of each leader's stream quota.
+pub struct TpuSender {
+    solana: Arc<SolanaService>,
+    endpoint: Endpoint,
+    config: TpuConfig,
+    schedule: Mutex<LeaderScheduleTracker>,
+    contacts: Mutex<ContactCache>,
+    connections: Arc<Mutex<HashMap<SocketAddr, Connection>>>,
+}
+
+impl TpuSender {
+    pub fn new(solana: Arc<SolanaService>, config: TpuConfig) -> Result<Self> {
+        let mut endpoint = Endpoint::client(SocketAddr::from(([0, 0, 0, 0], 0)))?;
+        endpoint.set_default_client_config(client_config()?);
+
+        Ok(Self {
+            solana,
+            endpoint,
+            config,
+            schedule: Mutex::new(LeaderScheduleTracker::new(SubmissionConfig::default())),
+            contacts: Mutex::new(ContactCache { addresses: HashMap::new(), refreshed_at: None }),
+            connections: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// TPU addresses of the leaders for the next `fanout_slots` slots, current leader first
+    pub async fn upcoming_leaders(&self) -> Result<Vec<SocketAddr>> {
+        let slot = self.solana.get_slot_info().await?;
+        let identities: Vec<String> = {
+            let mut schedule = self.schedule.lock().await;
+            schedule.refresh(&self.solana).await?;
+            (slot..slot + self.config.fanout_slots.max(1))
+                .filter_map(|s| schedule.leader_at(s).map(str::to_string))
+                .collect()
+        };
+
+        let mut contacts = self.contacts.lock().await;
+        let stale = contacts
+            .refreshed_at
+            .is_none_or(|at| at.elapsed() >= Duration::from_secs(self.config.contact_refresh_seconds));
+        if stale {
+            contacts.addresses = self
+                .solana
+                .get_cluster_nodes()
+                .await?
+                .into_iter()
+                .filter_map(|node| node.tpu_quic.map(|addr| (node.pubkey, addr)))
+                .collect();
+            contacts.refreshed_at = Some(Instant::now());
+        }
+
+        let mut addresses = Vec::new();
+        for identity in identities {
+            match contacts.addresses.get(&identity) {
+                Some(addr) if !addresses.contains(addr) => addresses.push(*addr),
+                Some(_) => {}
+                None => debug!("Leader {} has no TPU QUIC address in gossip", identity),
+            }
+        }
+        Ok(addresses)
+    }
+}
+
+/// Send the bytes on a fresh unidirectional stream, reusing the leader's connection when it is still open
+async fn send_to(
+    endpoint: Endpoint,
+    connections: Arc<Mutex<HashMap<SocketAddr, Connection>>>,
+    addr: SocketAddr,
+    bytes: Arc<Vec<u8>>,
+    connect_timeout: Duration,
+) -> Result<()> {
+    let cached = connections
+        .lock()
+        .await
+        .get(&addr)
+        .filter(|connection| connection.close_reason().is_none())
+        .cloned();
+    let connection = match cached {
+        Some(connection) => connection,
+        None => {
+            let connecting = endpoint.connect(addr, SERVER_NAME)?;
+            let connection = tokio::time::timeout(connect_timeout, connecting)
+                .await
+                .with_context(|| format!("QUIC connect to {} timed out", addr))??;
+            connections.lock().await.insert(addr, connection.clone());
+            connection
+        }
+    };
+
+    let mut stream = connection.open_uni().await?;
+    stream.write_all(&bytes).await?;
+    stream.finish()?;
+    Ok(())
+}
+
+#[async_trait]
+impl TransactionSender for TpuSender {
+    fn name(&self) -> &str {
+        "tpu"
+    }
+
+    /// Leaders run no preflight and do no retries themselves: unless `options` skip it, the
+    /// transaction is simulated over RPC first, and a fan-out that reaches no leader is tried
+    /// again a slot later up to `max_retries` times
+    async fn send(&self, transaction: &Transaction, options: &SendOptions) -> Result<Signature> {
+        let signature = *transaction
+            .signatures
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Transaction is not signed"))?;
+        if !options.skip_preflight {
+            self.solana.preflight_transaction(transaction, options.preflight_commitment).await?;
+        }
+
+        let bytes = Arc::new(bincode::serialize(transaction)?);
+        let mut last_error = None;
+        for round in 0..send_rounds(options) {
+            if round > 0 {
+                tokio::time::sleep(SLOT_DURATION).await;
+            }
+            match self.fan_out(signature, bytes.clone()).await {
+                Ok(()) => return Ok(signature),
+                Err(e) => {
+                    debug!("TPU send round {} of {} failed: {}", round + 1, signature, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Transaction {} was not sent", signature)))
+    }
+}
+
+impl TpuSender {
+    /// Send the bytes to every upcoming leader; succeeds when at least one received them
+    async fn fan_out(&self, signature: Signature, bytes: Arc<Vec<u8>>) -> Result<()> {
+        let leaders = self.upcoming_leaders().await?;
+        if leaders.is_empty() {
+            anyhow::bail!("No upcoming leader has a known TPU QUIC address");
+        }
+
+        let connect_timeout = Duration::from_millis(self.config.connect_timeout_ms);
+        let mut sends = JoinSet::new();
+        for addr in leaders {
+            sends.spawn(send_to(
+                self.endpoint.clone(),
+                self.connections.clone(),
+                addr,
+                bytes.clone(),
+                connect_timeout,
+            ));
+        }
+
+        let (mut delivered, mut last_error) = (0, None);
+        while let Some(result) = sends.join_next().await {
+            match result.map_err(anyhow::Error::from).and_then(|sent| sent) {
+                Ok(()) => delivered += 1,
+                Err(e) => {
+                    warn!("TPU send of {} failed: {}", signature, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match (delivered, last_error) {
+            (0, Some(e)) => Err(e.context("Transaction reached no leader")),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Fan-outs attempted for one transaction: the first plus `max_retries`, or the RPC-like default
+fn send_rounds(options: &SendOptions) -> usize {
+    1 + options.max_retries.unwrap_or(DEFAULT_SEND_RETRIES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stream_carries_serialized_transaction() {
+        // A local QUIC server standing in for a leader's TPU port
+        let cert = rcgen::generate_simple_self_signed(vec!["connect".to_string()]).unwrap();
+        let key = rustls::pki_types::PrivateKeyDer::Pkcs8(cert.key_pair.serialize_der().into());
+        let mut crypto = rustls::ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_protocol_versions(&[&rustls::version::TLS13])
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert.cert.der().clone()], key)
+            .unwrap();
+        crypto.alpn_protocols = vec![ALPN_TPU.to_vec()];
+        let server_config = quinn::ServerConfig::with_crypto(Arc::new(
+            quinn::crypto::rustls::QuicServerConfig::try_from(crypto).unwrap(),
+        ));
+        let server = Endpoint::server(server_config, SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+        let addr = server.local_addr().unwrap();
+        let received = tokio::spawn(async move {
+            let connection = server.accept().await.unwrap().await.unwrap();
+            let mut payloads = Vec::new();
+            for _ in 0..2 {
+                let mut stream = connection.accept_uni().await.unwrap();
+                payloads.push(stream.read_to_end(4096).await.unwrap());
+            }
+            payloads
+        });
+
+        let mut endpoint = Endpoint::client(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+        endpoint.set_default_client_config(client_config().unwrap());
+        let connections = Arc::new(Mutex::new(HashMap::new()));
+        let transaction = Transaction::default();
+        let bytes = Arc::new(bincode::serialize(&transaction).unwrap());
+        for _ in 0..2 {
+            send_to(endpoint.clone(), connections.clone(), addr, bytes.clone(), Duration::from_secs(5))
+                .await
+                .unwrap();
+        }
+
+        let payloads = received.await.unwrap();
+        assert_eq!(payloads, vec![bytes.to_vec(), bytes.to_vec()]);
+        // Both transactions went over the one cached connection
+        assert_eq!(connections.lock().await.len(), 1);
+    }
+
+    #[test]
+    fn test_send_rounds_follow_the_urgency_retries() {
+        use crate::models::Urgency;
+
+        assert_eq!(send_rounds(&SendOptions::for_urgency(Urgency::High)), 1);
+        assert_eq!(send_rounds(&SendOptions::for_urgency(Urgency::Normal)), 3);
+        assert_eq!(send_rounds(&SendOptions::for_urgency(Urgency::Low)), 1 + DEFAULT_SEND_RETRIES);
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use solana_message::Message;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Mutex, OnceLock};
+
+use crate::config::WalletConfig;
+use crate::utils::panic_guard::lock_or_recover;
+use crate::utils::secret::{assert_not_impl, SecretKeypair};
+
+/// Signatures remembered before the oldest are forgotten
+const SIGNED_CAPACITY: usize = 100_000;
+
+/// Signatures of transactions this process signed, so wallet activity it did not originate stands out
+pub struct SignedTransactions {
+    capacity: usize,
+    signatures: Mutex<(VecDeque<Signature>, HashSet<Signature>)>,
+}
+
+impl SignedTransactions {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, signatures: Mutex::new((VecDeque::new(), HashSet::new())) }
+    }
+
+    /// The process-wide record. Transactions are signed in several places, so they record here.
+    pub fn global() -> &'static SignedTransactions {
+        static SIGNED: OnceLock<SignedTransactions> = OnceLock::new();
+        SIGNED.get_or_init(|| SignedTransactions::new(SIGNED_CAPACITY))
+    }
+
+    /// Remember the transaction's first signature, which is its id
+    pub fn record(&self, transaction: &Transaction) {
+        let Some(signature) = transaction.signatures.first().copied() else {
+            return;
+        };
+        let mut guard = lock_or_recover(&self.signatures, "Signed transactions");
+        let (order, set) = &mut *guard;
+        if set.insert(signature) {
+            order.push_back(signature);
+            if order.len() > self.capacity {
+                if let Some(oldest) = order.pop_front() {
+                    set.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, signature: &Signature) -> bool {
+        lock_or_recover(&self.signatures, "Signed transactions").1.contains(signature)
+    }
+}
+
+/// Signing keys: the position wallet holding tokens and an optional dedicated fee payer.
+/// Neither `Debug` nor `Clone`; share it behind an `Arc`.
+pub struct Wallets {
+    owner: SecretKeypair,
+    fee_payer: Option<SecretKeypair>,
+}
+
+assert_not_impl!(Wallets: std::fmt::Debug, Clone, serde::Serialize);
+
+impl Wallets {
+    pub fn new(owner: Keypair, fee_payer: Option<Keypair>) -> Self {
+        Self { owner: SecretKeypair::new(owner), fee_payer: fee_payer.map(SecretKeypair::new) }
+    }
+
+    /// Load keypair files from the wallet config
+    pub fn load(config: &WalletConfig) -> Result<Self> {
+        let owner = SecretKeypair::read_file(&config.keypair_path)
+            .map_err(|e| anyhow::anyhow!("Failed to read wallet keypair: {}", e))?;
+
+        let fee_payer = match &config.fee_payer_keypair_path {
+            Some(path) if !path.is_empty() => Some(
+                SecretKeypair::read_file(path).map_err(|e| anyhow::anyhow!("Failed to read fee payer keypair: {}", e))?,
+            ),
+            _ => None,
+        };
+
+        Ok(Self { owner, fee_payer })
+    }
+
+    /// Token-holding wallet
+    pub fn owner(&self) -> &Keypair {
+        self.owner.keypair()
+    }
+
+    /// Account paying transaction fees; the owner when no fee payer is configured
+    pub fn fee_payer(&self) -> &Keypair {
+        self.fee_payer.as_ref().unwrap_or(&self.owner).keypair()
+    }
+
+    pub fn has_separate_fee_payer(&self) -> bool {
+        self.fee_payer.is_some()
+    }
+
+    /// The wallets `message` requires signatures from. A transaction that only spends the fee
+    /// payer's lamports, like a bundle tip, does not list the owner as a signer.
+    pub fn signers_for(&self, message: &Message) -> Vec<&Keypair> {
+        let required = &message.account_keys[..(message.header.num_required_signatures as usize).min(message.account_keys.len())];
+        let mut signers = vec![self.fee_payer()];
+        if self.has_separate_fee_payer() {
+            signers.push(self.owner());
+        }
+        signers.retain(|signer| required.contains(&signer.pubkey()));
+        signers
+    }
+
+    /// Build and sign a transaction with the fee payer as payer, collecting every required signature
+    pub fn build_transaction(&self, instructions: &[Instruction], recent_blockhash: Hash) -> Result<Transaction> {
+        let payer: Pubkey = self.fee_payer().pubkey();
+        let message = Message::new(instructions, Some(&payer));
+        let signers = self.signers_for(&message);
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.try_sign(&signers, recent_blockhash)?;
+        SignedTransactions::global().record(&transaction);
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_system_interface::instruction as system_instruction;
+
+    #[test]
+    fn test_separate_fee_payer() {
+        let wallets = Wallets::new(Keypair::new(), Some(Keypair::new()));
+        let owner = wallets.owner().pubkey();
+        let payer = wallets.fee_payer().pubkey();
+        assert_ne!(owner, payer);
+
+        let instruction = system_instruction::transfer(&owner, &Pubkey::new_unique(), 1);
+        let transaction = wallets.build_transaction(&[instruction], Hash::new_unique()).unwrap();
+
+        assert_eq!(transaction.message.account_keys[0], payer);
+        assert_eq!(transaction.signatures.len(), 2);
+        assert!(transaction.verify().is_ok());
+        assert!(SignedTransactions::global().contains(&transaction.signatures[0]));
+
+        // A tip paid from the fee payer's lamports never touches the owner
+        let tip = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let transaction = wallets.build_transaction(&[tip], Hash::new_unique()).unwrap();
+        assert_eq!(transaction.signatures.len(), 1);
+        assert!(transaction.verify().is_ok());
+    }
+
+    #[test]
+    fn test_owner_pays_by_default() {
+        let wallets = Wallets::new(Keypair::new(), None);
+        assert_eq!(wallets.fee_payer().pubkey(), wallets.owner().pubkey());
+        let instruction = system_instruction::transfer(&wallets.owner().pubkey(), &Pubkey::new_unique(), 1);
+        assert_eq!(wallets.signers_for(&Message::new(&[instruction], Some(&wallets.fee_payer().pubkey()))).len(), 1);
+    }
+}
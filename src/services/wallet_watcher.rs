@@ -0,0 +1,215 @@
+use anyhow::Result;
+use solana_rpc_client_api::response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::{
+    config::WalletWatchConfig,
+    dex::pool_accounts::token_account_balance,
+    services::{
+        account_subscriber::{AccountSubscriber, AccountUpdate},
+        alert_rules::{AlertAction, FiredAlert},
+        solana::SolanaService,
+        wallet::SignedTransactions,
+    },
+};
+
+/// Updates buffered across every watched account
+const UPDATE_BUFFER: usize = 256;
+
+/// What a watched account holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Holding {
+    /// A wallet's SOL, in lamports
+    Lamports,
+    /// A token account's balance, in raw units
+    Tokens,
+}
+
+/// A watched account's last balance and the newest signature already checked for it
+#[derive(Debug, Clone)]
+struct WatchedAccount {
+    holding: Holding,
+    wallet: Pubkey,
+    balance: Option<u64>,
+    checked_until: Option<Signature>,
+}
+
+impl WatchedAccount {
+    fn balance_of(&self, update: &AccountUpdate) -> u64 {
+        match self.holding {
+            Holding::Lamports => update.lamports,
+            // A closed or emptied token account holds nothing
+            Holding::Tokens => token_account_balance(&update.data).map(|(_, amount)| amount).unwrap_or(0),
+        }
+    }
+
+    /// Record a new balance, returning the previous one when it went down
+    fn debited(&mut self, balance: u64) -> Option<u64> {
+        let previous = self.balance.replace(balance)?;
+        (balance < previous).then_some(previous)
+    }
+}
+
+/// Successful transactions among `statuses` that this process did not sign
+fn foreign_signatures(statuses: &[RpcConfirmedTransactionStatusWithSignature], signed: &SignedTransactions) -> Vec<String> {
+    statuses
+        .iter()
+        .filter(|status| status.err.is_none())
+        .filter(|status| Signature::from_str(&status.signature).map_or(true, |s| !signed.contains(&s)))
+        .map(|status| status.signature.clone())
+        .collect()
+}
+
+/// Watches bot wallets and their token accounts over account subscriptions and raises an alert when
+/// funds leave in a transaction the bot did not sign, an early sign of a leaked key.
+///
+/// Token accounts are resolved at startup; ones opened later are picked up when the task restarts.
+pub struct WalletWatcher {
+    solana: Arc<SolanaService>,
+    subscriber: AccountSubscriber,
+    wallets: Vec<Pubkey>,
+    config: WalletWatchConfig,
+    alerts: mpsc::Sender<FiredAlert>,
+}
+
+impl WalletWatcher {
+    pub fn new(
+        solana: Arc<SolanaService>,
+        subscriber: AccountSubscriber,
+        wallets: Vec<Pubkey>,
+        config: &WalletWatchConfig,
+        alerts: mpsc::Sender<FiredAlert>,
+    ) -> Self {
+        Self { solana, subscriber, wallets, config: config.clone(), alerts }
+    }
+
+    pub async fn start(self) -> Result<()> {
+        let mut accounts = self.resolve_accounts().await?;
+        info!("Watching {} wallet(s) and {} account(s) for outside transfers", self.wallets.len(), accounts.len());
+
+        let (sender, mut updates) = mpsc::channel(UPDATE_BUFFER);
+        for pubkey in accounts.keys() {
+            self.subscriber.subscribe_into(*pubkey, sender.clone())?;
+        }
+        drop(sender);
+
+        while let Some(update) = updates.recv().await {
+            let Some(account) = accounts.get_mut(&update.pubkey) else {
+                continue;
+            };
+            let balance = account.balance_of(&update);
+            if let Some(previous) = account.debited(balance) {
+                if let Err(e) = self.check_debit(update.pubkey, account, previous, balance).await {
+                    warn!("Could not check debit from {}: {}", update.pubkey, e);
+                }
+            }
+        }
+        anyhow::bail!("Wallet account subscriptions ended")
+    }
+
+    /// Wallets plus their token accounts, with current balances and the newest signature as a baseline
+    async fn resolve_accounts(&self) -> Result<HashMap<Pubkey, WatchedAccount>> {
+        let mut accounts = HashMap::new();
+        for wallet in &self.wallets {
+            let balance = self.solana.get_balance(wallet).await?;
+            accounts.insert(*wallet, WatchedAccount { holding: Holding::Lamports, wallet: *wallet, balance: Some(balance), checked_until: None });
+            if self.config.watch_token_accounts {
+                for holding in self.solana.get_token_holdings(wallet).await? {
+                    let account = WatchedAccount { holding: Holding::Tokens, wallet: *wallet, balance: Some(holding.amount), checked_until: None };
+                    accounts.insert(holding.address, account);
+                }
+            }
+        }
+        for (pubkey, account) in accounts.iter_mut() {
+            let newest = self.solana.get_signatures_for_address(pubkey, None, 1).await?;
+            account.checked_until = newest.first().and_then(|s| Signature::from_str(&s.signature).ok());
+        }
+        Ok(accounts)
+    }
+
+    async fn check_debit(&self, pubkey: Pubkey, account: &mut WatchedAccount, previous: u64, balance: u64) -> Result<()> {
+        let statuses = self
+            .solana
+            .get_signatures_for_address(&pubkey, account.checked_until, self.config.signature_limit.max(1))
+            .await?;
+        if let Some(newest) = statuses.first() {
+            account.checked_until = Signature::from_str(&newest.signature).ok();
+        }
+        let foreign = foreign_signatures(&statuses, SignedTransactions::global());
+        if foreign.is_empty() {
+            return Ok(());
+        }
+
+        let what = match account.holding {
+            Holding::Lamports => "lamports",
+            Holding::Tokens => "tokens",
+        };
+        let message = format!(
+            "{} {} left {} (wallet {}) in transaction(s) not signed by the bot: {}",
+            previous - balance,
+            what,
+            pubkey,
+            account.wallet,
+            foreign.join(", ")
+        );
+        warn!("{}", message);
+
+        let mut actions = vec![AlertAction::Notify(self.config.alert_channel.clone())];
+        if self.config.auto_pause {
+            actions.push(AlertAction::Pause);
+        }
+        for action in actions {
+            let alert = FiredAlert {
+                rule_name: "wallet_watch".to_string(),
+                action,
+                message: message.clone(),
+                value: (previous - balance) as f64,
+                timestamp: chrono::Utc::now(),
+            };
+            self.alerts.send(alert).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, signature::Keypair, signer::Signer, transaction::Transaction};
+
+    fn status(signature: &Signature, failed: bool) -> RpcConfirmedTransactionStatusWithSignature {
+        RpcConfirmedTransactionStatusWithSignature {
+            signature: signature.to_string(),
+            slot: 1,
+            err: failed.then(|| solana_sdk::transaction::TransactionError::AccountNotFound.into()),
+            memo: None,
+            block_time: None,
+            confirmation_status: None,
+        }
+    }
+
+    #[test]
+    fn test_flags_debits_the_bot_did_not_sign() {
+        let mut account = WatchedAccount { holding: Holding::Tokens, wallet: Pubkey::new_unique(), balance: Some(500), checked_until: None };
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&200u64.to_le_bytes());
+        let update = AccountUpdate { pubkey: Pubkey::new_unique(), slot: 1, lamports: 2_039_280, data };
+        assert_eq!(account.debited(account.balance_of(&update)), Some(500));
+        assert_eq!(account.debited(300), None);
+        // A closed token account counts as drained
+        assert_eq!(account.balance_of(&AccountUpdate { data: Vec::new(), ..update }), 0);
+
+        let signed = SignedTransactions::new(2);
+        let payer = Keypair::new();
+        let own = Transaction::new_signed_with_payer(&[], Some(&payer.pubkey()), &[&payer], Hash::new_unique());
+        signed.record(&own);
+        let foreign = Signature::from([7u8; 64]);
+        let statuses = [status(&own.signatures[0], false), status(&foreign, false), status(&Signature::from([9u8; 64]), true)];
+        assert_eq!(foreign_signatures(&statuses, &signed), vec![foreign.to_string()]);
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use core_affinity::CoreId;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::config::AffinityConfig;
+
+/// Cores resolved from the affinity config
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorePlan {
+    pub runtime_cores: Vec<usize>,
+    pub ingestion_core: Option<usize>,
+}
+
+impl CorePlan {
+    /// Resolve configured cores against the machine. An empty plan leaves scheduling to the OS.
+    pub fn resolve(config: &AffinityConfig) -> Result<Self> {
+        if !config.enabled {
+            return Ok(Self::default());
+        }
+        let available: Vec<usize> = core_affinity::get_core_ids()
+            .context("Cannot enumerate CPU cores for pinning")?
+            .into_iter()
+            .map(|core| core.id)
+            .collect();
+
+        let mut runtime_cores = if !config.runtime_cores.is_empty() {
+            config.runtime_cores.clone()
+        } else if let Some(node) = config.numa_node {
+            numa_node_cores(node)?
+        } else {
+            available.clone()
+        };
+
+        if let Some(core) = config.ingestion_core {
+            // The ingestion thread keeps its core to itself
+            runtime_cores.retain(|&c| c != core);
+        }
+        let unknown: Vec<usize> = runtime_cores
+            .iter()
+            .chain(config.ingestion_core.iter())
+            .copied()
+            .filter(|c| !available.contains(c))
+            .collect();
+        if !unknown.is_empty() {
+            anyhow::bail!("Cores {:?} are not available to this process (have {:?})", unknown, available);
+        }
+        if runtime_cores.is_empty() {
+            anyhow::bail!("No cores left for the runtime after reserving the ingestion core");
+        }
+
+        Ok(Self { runtime_cores, ingestion_core: config.ingestion_core })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runtime_cores.is_empty() && self.ingestion_core.is_none()
+    }
+
+    /// Multi-threaded runtime with one worker per runtime core, each pinned to its core
+    pub fn runtime_builder(&self) -> tokio::runtime::Builder {
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder.enable_all();
+        if self.runtime_cores.is_empty() {
+            return builder;
+        }
+
+        let cores = Arc::new(self.runtime_cores.clone());
+        let started = Arc::new(AtomicUsize::new(0));
+        builder.worker_threads(cores.len()).on_thread_start(move || {
+            // Workers start when the runtime is built, before any blocking-pool thread,
+            // so the first N threads are the workers; blocking threads stay unpinned
+            let index = started.fetch_add(1, Ordering::Relaxed);
+            if let Some(&core) = cores.get(index) {
+                pin_current_thread(core);
+            }
+        });
+        info!("Pinning {} runtime workers to cores {:?}", self.runtime_cores.len(), self.runtime_cores);
+        builder
+    }
+}
+
+/// Pin the calling thread to `core`, logging rather than failing when the OS refuses
+pub fn pin_current_thread(core: usize) -> bool {
+    let pinned = core_affinity::set_for_current(CoreId { id: core });
+    if !pinned {
+        warn!("Failed to pin thread {:?} to core {}", std::thread::current().name(), core);
+    }
+    pinned
+}
+
+/// Spawn an OS thread, pinned to `core` when one is given
+pub fn spawn_pinned<F, T>(name: &str, core: Option<usize>, f: F) -> std::io::Result<std::thread::JoinHandle<T>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    std::thread::Builder::new().name(name.to_string()).spawn(move || {
+        if let Some(core) = core {
+            pin_current_thread(core);
+        }
+        f()
+    })
+}
+
+/// Cores of a NUMA node, from sysfs
+pub fn numa_node_cores(node: usize) -> Result<Vec<usize>> {
+    let path = format!("/sys/devices/system/node/node{}/cpulist", node);
+    let list = std::fs::read_to_string(&path).with_context(|| format!("Cannot read {}", path))?;
+    parse_cpu_list(&list)
+}
+
+/// Parse the kernel's cpulist format, e.g. "0-3,8-11,16"
+pub fn parse_cpu_list(list: &str) -> Result<Vec<usize>> {
+    let mut cores = Vec::new();
+    for part in list.trim().split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end): (usize, usize) = (start.parse()?, end.parse()?);
+                if start > end {
+                    anyhow::bail!("Invalid CPU range '{}'", part);
+                }
+                cores.extend(start..=end);
+            }
+            None => cores.push(part.parse()?),
+        }
+    }
+    Ok(cores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_list_and_plan() {
+        assert_eq!(parse_cpu_list("0-3,8-9,16\n").unwrap(), vec![0, 1, 2, 3, 8, 9, 16]);
+        assert!(parse_cpu_list("4-2").is_err());
+        assert!(CorePlan::resolve(&AffinityConfig::default()).unwrap().is_empty());
+
+        let available: Vec<usize> = core_affinity::get_core_ids().unwrap_or_default().iter().map(|c| c.id).collect();
+        if available.len() >= 2 {
+            let config = AffinityConfig {
+                enabled: true,
+                runtime_cores: available.clone(),
+                ingestion_core: Some(available[0]),
+                ..Default::default()
+            };
+            let plan = CorePlan::resolve(&config).unwrap();
+            assert!(!plan.runtime_cores.contains(&available[0]));
+            assert_eq!(plan.runtime_cores.len(), available.len() - 1);
+        }
+        let config = AffinityConfig { enabled: true, runtime_cores: vec![usize::MAX], ..Default::default() };
+        assert!(CorePlan::resolve(&config).is_err());
+    }
+}
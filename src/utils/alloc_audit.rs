@@ -0,0 +1,104 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Whether the counting allocator is compiled in (`--features alloc-audit`)
+pub const ENABLED: bool = cfg!(feature = "alloc-audit");
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// System allocator that counts every allocation and reallocation
+#[cfg(feature = "alloc-audit")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "alloc-audit")]
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        record(layout.size());
+        std::alloc::System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: std::alloc::Layout) -> *mut u8 {
+        record(layout.size());
+        std::alloc::System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        std::alloc::System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout)
+    }
+}
+
+#[cfg(feature = "alloc-audit")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[cfg(feature = "alloc-audit")]
+fn record(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+}
+
+/// Allocation counts since process start, or over a scope
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes: u64,
+}
+
+impl AllocStats {
+    pub fn current() -> Self {
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Measures allocations between `start` and `finish`.
+///
+/// Counters are process-wide, so allocations by tasks running concurrently are included.
+pub struct AllocScope {
+    start: AllocStats,
+}
+
+impl AllocScope {
+    pub fn start() -> Self {
+        Self { start: AllocStats::current() }
+    }
+
+    /// Allocations made since `start`, or None when the feature is off
+    pub fn finish(&self) -> Option<AllocStats> {
+        if !ENABLED {
+            return None;
+        }
+        let now = AllocStats::current();
+        Some(AllocStats {
+            allocations: now.allocations - self.start.allocations,
+            bytes: now.bytes - self.start.bytes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_counts_allocations_when_enabled() {
+        let scope = AllocScope::start();
+        let buffer: Vec<u64> = Vec::with_capacity(64);
+        drop(std::hint::black_box(buffer));
+
+        match scope.finish() {
+            Some(stats) => {
+                assert!(stats.allocations >= 1);
+                assert!(stats.bytes >= 512);
+            }
+            None => assert!(!ENABLED),
+        }
+    }
+}
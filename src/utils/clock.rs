@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::utils::panic_guard::lock_or_recover;
+
+/// Source of time for expiry, windows and cleanup, so time-based logic can be driven by tests
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// Wall-clock time, for timestamps and deadlines
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Monotonic reading for measuring latency; never goes back when the wall clock is adjusted
+    fn monotonic(&self) -> Duration;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn monotonic(&self) -> Duration {
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        ORIGIN.get_or_init(Instant::now).elapsed()
+    }
+}
+
+/// A clock that only moves when told to
+#[derive(Debug)]
+pub struct TestClock {
+    state: Mutex<(DateTime<Utc>, Duration)>,
+}
+
+impl TestClock {
+    pub fn new(start: DateTime<Utc>) -> Arc<Self> {
+        Arc::new(Self { state: Mutex::new((start, Duration::ZERO)) })
+    }
+
+    /// Move wall and monotonic time forward together
+    pub fn advance(&self, by: chrono::Duration) {
+        let mut state = lock_or_recover(&self.state, "test clock");
+        state.0 += by;
+        state.1 += by.to_std().unwrap_or_default();
+    }
+
+    /// Jump the wall clock, as an NTP correction would; monotonic time is unaffected
+    pub fn set(&self, now: DateTime<Utc>) {
+        lock_or_recover(&self.state, "test clock").0 = now;
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Utc> {
+        lock_or_recover(&self.state, "test clock").0
+    }
+
+    fn monotonic(&self) -> Duration {
+        lock_or_recover(&self.state, "test clock").1
+    }
+}
+
+/// Elapsed monotonic time since it was started
+#[derive(Debug, Clone)]
+pub struct Stopwatch {
+    clock: SharedClock,
+    started: Duration,
+}
+
+impl Stopwatch {
+    pub fn start(clock: SharedClock) -> Self {
+        let started = clock.monotonic();
+        Self { clock, started }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.clock.monotonic().saturating_sub(self.started)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_clock_drives_wall_and_monotonic_time() {
+        let start = Utc::now();
+        let clock = TestClock::new(start);
+        let stopwatch = Stopwatch::start(clock.clone());
+
+        clock.advance(chrono::Duration::milliseconds(250));
+        assert_eq!(clock.now(), start + chrono::Duration::milliseconds(250));
+        assert_eq!(stopwatch.elapsed(), Duration::from_millis(250));
+
+        // Stepping the wall clock back leaves latency measurements alone
+        clock.set(start - chrono::Duration::seconds(10));
+        assert_eq!(stopwatch.elapsed(), Duration::from_millis(250));
+
+        let system = Stopwatch::start(SystemClock::shared());
+        assert!(system.elapsed() < Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+/// A five-field cron expression (`minute hour day-of-month month day-of-week`), evaluated in UTC.
+///
+/// Fields take `*`, numbers, ranges `a-b`, steps `*/n` or `a-b/n`, and comma lists of those.
+/// Day of week runs 0-6 from Sunday, with 7 also meaning Sunday. As in cron, when both day
+/// fields are restricted a day matching either of them matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Whether each day field was `*`
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+/// Bit set of the values a field allows
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| anyhow::anyhow!("invalid step in '{}'", part))?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            anyhow::bail!("zero step in '{}'", part);
+        }
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (start.parse::<u32>()?, end.parse::<u32>()?),
+                None => {
+                    let value = range.parse::<u32>().map_err(|_| anyhow::anyhow!("invalid value '{}'", range))?;
+                    // `5/15` runs from 5 to the end of the field
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            anyhow::bail!("'{}' is outside {}-{}", part, min, max);
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            anyhow::bail!("cron expression '{}' must have five fields", expression);
+        };
+        let context = |e: anyhow::Error| anyhow::anyhow!("cron expression '{}': {}", expression, e);
+        let mut days_of_week = parse_field(day_of_week, 0, 7).map_err(context)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59).map_err(context)?,
+            hours: parse_field(hour, 0, 23).map_err(context)?,
+            days_of_month: parse_field(day_of_month, 1, 31).map_err(context)?,
+            months: parse_field(month, 1, 12).map_err(context)?,
+            days_of_week,
+            any_day_of_month: day_of_month == "*",
+            any_day_of_week: day_of_week == "*",
+        })
+    }
+
+    /// Whether the schedule fires in the minute containing `time`
+    pub fn matches(&self, time: DateTime<Utc>) -> bool {
+        let has = |bits: u64, value: u32| bits & (1 << value) != 0;
+        let day_of_month = has(self.days_of_month, time.day());
+        let day_of_week = has(self.days_of_week, time.weekday().num_days_from_sunday());
+        let day = match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        };
+        day && has(self.minutes, time.minute()) && has(self.hours, time.hour()) && has(self.months, time.month())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_matches_weekly_and_stepped_schedules() {
+        // Sundays at 04:00
+        let weekly = CronSchedule::parse("0 4 * * 7").unwrap();
+        let sunday = Utc.with_ymd_and_hms(2026, 10, 18, 4, 0, 30).unwrap();
+        assert!(weekly.matches(sunday));
+        assert!(!weekly.matches(sunday + chrono::Duration::minutes(1)));
+        assert!(!weekly.matches(sunday + chrono::Duration::days(1)));
+
+        let stepped = CronSchedule::parse("5/20 1-3 1,15 * 1").unwrap();
+        assert!(stepped.matches(Utc.with_ymd_and_hms(2026, 10, 15, 2, 45, 0).unwrap()));
+        // A Monday that is neither the 1st nor the 15th still matches
+        assert!(stepped.matches(Utc.with_ymd_and_hms(2026, 10, 19, 1, 25, 0).unwrap()));
+        assert!(!stepped.matches(Utc.with_ymd_and_hms(2026, 10, 19, 1, 20, 0).unwrap()));
+
+        assert!(CronSchedule::parse("0 4 * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    }
+}
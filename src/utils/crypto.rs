@@ -8,6 +8,8 @@ use aes::cipher::{
 use rand::{Rng, RngCore};
 use base64::{Engine as _, engine::general_purpose};
 
+use crate::utils::secret::Secret;
+
 /// Cryptographic utility functions
 pub struct CryptoUtils;
 
@@ -223,8 +225,10 @@ impl AesUtils {
     }
 
     /// Generate AES key
-    pub fn generate_aes_key() -> [u8; 32] {
-        Self::random_bytes(32).try_into().unwrap()
+    pub fn generate_aes_key() -> Secret<[u8; 32]> {
+        let mut key = Secret::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(key.expose_mut());
+        key
     }
 }
 
@@ -238,13 +242,13 @@ impl KeyDerivationUtils {
         salt: &[u8],
         iterations: u32,
         key_length: usize,
-    ) -> Vec<u8> {
-        use pbkdf2::{pbkdf2, Pbkdf2};
+    ) -> Result<Secret<Vec<u8>>, String> {
+        use pbkdf2::pbkdf2;
         use sha2::Sha256;
         
-        let mut key = vec![0u8; key_length];
-        pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, iterations, &mut key);
-        key
+        let mut key = Secret::new(vec![0u8; key_length]);
+        pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, iterations, key.expose_mut()).map_err(|e| e.to_string())?;
+        Ok(key)
     }
     
     /// Generate random salt
@@ -253,10 +257,9 @@ impl KeyDerivationUtils {
     }
     
     /// Secure key generation
-    pub fn generate_secure_key(length: usize) -> Vec<u8> {
-        let mut rng = rand::thread_rng();
-        let mut key = vec![0u8; length];
-        rng.fill_bytes(&mut key);
+    pub fn generate_secure_key(length: usize) -> Secret<Vec<u8>> {
+        let mut key = Secret::new(vec![0u8; length]);
+        rand::thread_rng().fill_bytes(key.expose_mut());
         key
     }
 }
@@ -304,19 +307,19 @@ impl SignatureUtils {
         Ok(public_key.verify(data, &signature).is_ok())
     }
     
-    /// Generate keypair
-    pub fn generate_keypair() -> (Vec<u8>, Vec<u8>) {
+    /// Generate keypair, returning the private and public key bytes
+    pub fn generate_keypair() -> (Secret<Vec<u8>>, Vec<u8>) {
         use ed25519_dalek::{Keypair, SecretKey, PublicKey};
         use rand::RngCore;
         
-        let mut seed = [0u8; 32];
-        rand::thread_rng().fill_bytes(&mut seed);
-        let secret = SecretKey::from_bytes(&seed).map_err(|e| format!("Invalid secret key: {}", e)).unwrap();
+        let mut seed = Secret::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(seed.expose_mut());
+        let secret = SecretKey::from_bytes(seed.expose()).map_err(|e| format!("Invalid secret key: {}", e)).unwrap();
         let public = PublicKey::from(&secret);
         let keypair = Keypair { secret, public };
         
         (
-            keypair.secret.to_bytes().to_vec(),
+            Secret::new(keypair.secret.as_bytes().to_vec()),
             keypair.public.to_bytes().to_vec(),
         )
     }
@@ -398,8 +401,8 @@ mod tests {
         let key = AesUtils::generate_aes_key();
         let data = b"This is a test message for AES encryption!";
         
-        let encrypted = AesUtils::encrypt_aes256(&key, data).unwrap();
-        let decrypted = AesUtils::decrypt_aes256(&key, &encrypted).unwrap();
+        let encrypted = AesUtils::encrypt_aes256(key.expose(), data).unwrap();
+        let decrypted = AesUtils::decrypt_aes256(key.expose(), &encrypted).unwrap();
         
         assert_eq!(data, decrypted.as_slice());
     }
@@ -430,7 +433,7 @@ mod tests {
         let (private_key, public_key) = SignatureUtils::generate_keypair();
         let data = b"Hello, World!";
         
-        let signature = SignatureUtils::create_signature(&private_key, data).unwrap();
+        let signature = SignatureUtils::create_signature(private_key.expose(), data).unwrap();
         let is_valid = SignatureUtils::verify_signature(&public_key, data, &signature).unwrap();
         
         assert!(is_valid);
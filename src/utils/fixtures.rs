@@ -0,0 +1,64 @@
+//! Test fixtures shared across modules: a SOL/USDC market and a fake JSON-RPC node
+
+use axum::{routing::post, Json, Router};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::dex::DexType;
+use crate::models::{ArbitrageOpportunity, Pool, Token};
+
+/// SOL and USDC, each with a fresh mint
+pub fn sol_usdc() -> (Token, Token) {
+    (
+        Token::new(Pubkey::new_unique(), "SOL".to_string(), "Solana".to_string(), 9),
+        Token::new(Pubkey::new_unique(), "USDC".to_string(), "USD Coin".to_string(), 6),
+    )
+}
+
+/// An empty `dex_type` pool of `base`/`quote` at a fresh address, named after its DEX
+pub fn pool(dex_type: DexType, base: &Token, quote: &Token) -> Pool {
+    Pool::new(
+        dex_type.to_string(),
+        dex_type,
+        base.clone(),
+        quote.clone(),
+        Pubkey::new_unique(),
+        Pubkey::default(),
+        Pubkey::default(),
+    )
+}
+
+/// Buying on `buy` and selling on `sell`, priced in their tokens
+pub fn opportunity_between(buy: Pool, sell: Pool) -> ArbitrageOpportunity {
+    ArbitrageOpportunity::new(buy.token_a.clone(), buy.token_b.clone(), buy, sell)
+}
+
+/// SOL/USDC bought on Raydium and sold on Meteora
+pub fn opportunity() -> ArbitrageOpportunity {
+    let (sol, usdc) = sol_usdc();
+    opportunity_between(pool(DexType::Raydium, &sol, &usdc), pool(DexType::Meteora, &sol, &usdc))
+}
+
+/// Serve JSON-RPC on a local port until the test ends, returning its URL.
+///
+/// `handler` gets each request's method and params and returns its result, or the error object
+/// to answer with.
+pub async fn rpc_node<F>(handler: F) -> String
+where
+    F: Fn(&str, &Value) -> Result<Value, Value> + Clone + Send + Sync + 'static,
+{
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new().route(
+        "/",
+        post(move |Json(request): Json<Value>| {
+            let reply = match handler(request["method"].as_str().unwrap_or_default(), &request["params"]) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+                Err(error) => json!({ "jsonrpc": "2.0", "id": request["id"], "error": error }),
+            };
+            async move { Json(reply) }
+        }),
+    );
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    url
+}
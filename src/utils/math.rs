@@ -286,9 +286,9 @@ impl MathUtils {
         let mut lower_band = Vec::new();
         
         for i in period - 1..values.len() {
-            let window = &values[i - period + 1..=i];
+            let window = &values[i + 1 - period..=i];
             if let Some(std_dev) = Self::standard_deviation(window) {
-                let middle = sma[i - period + 1];
+                let middle = sma[i + 1 - period];
                 upper_band.push(middle + std_dev_multiplier * std_dev);
                 lower_band.push(middle - std_dev_multiplier * std_dev);
             } else {
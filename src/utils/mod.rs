@@ -2,3 +2,14 @@ pub mod math;
 pub mod time;
 pub mod crypto;
 pub mod network;
+pub mod versioning;
+pub mod precision;
+pub mod panic_guard;
+pub mod alloc_audit;
+pub mod affinity;
+pub mod secret;
+pub mod cron;
+pub mod clock;
+pub mod retry;
+#[cfg(test)]
+pub mod fixtures;
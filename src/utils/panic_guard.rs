@@ -0,0 +1,107 @@
+use std::any::Any;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard};
+use std::task::{Context, Poll};
+use tracing::warn;
+
+/// A panic caught at a guarded boundary
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{context} panicked: {message}")]
+pub struct PanicError {
+    pub context: String,
+    pub message: String,
+}
+
+impl PanicError {
+    pub fn new(context: &str, payload: Box<dyn Any + Send>) -> Self {
+        Self {
+            context: context.to_string(),
+            message: panic_message(payload.as_ref()),
+        }
+    }
+}
+
+/// Text of a panic payload, which is a `&str` or `String` for `panic!` with a message
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Run `f`, turning a panic into a `PanicError`.
+///
+/// Callers must not rely on state `f` mutated before panicking.
+pub fn guard<T>(context: &str, f: impl FnOnce() -> T) -> Result<T, PanicError> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(|payload| PanicError::new(context, payload))
+}
+
+/// Await `future`, turning a panic during any poll into a `PanicError`
+pub async fn guard_async<F: Future>(context: &str, future: F) -> Result<F::Output, PanicError> {
+    CatchUnwind { inner: Box::pin(future) }
+        .await
+        .map_err(|payload| PanicError::new(context, payload))
+}
+
+struct CatchUnwind<F> {
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.inner.as_mut();
+        match catch_unwind(AssertUnwindSafe(|| inner.poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
+
+/// Lock a mutex, recovering the data if a previous holder panicked
+pub fn lock_or_recover<'a, T>(mutex: &'a Mutex<T>, name: &str) -> MutexGuard<'a, T> {
+    mutex.lock().unwrap_or_else(|poisoned| {
+        warn!("{} lock was poisoned by a panic; recovering", name);
+        mutex.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_panics_become_errors_and_poison_is_recovered() {
+        let err = guard("strategy 'x'", || -> u32 { panic!("overflow") }).unwrap_err();
+        assert_eq!(err.to_string(), "strategy 'x' panicked: overflow");
+        assert_eq!(guard("ok", || 7).unwrap(), 7);
+
+        let err = guard_async("adapter", async {
+            tokio::task::yield_now().await;
+            panic!("{}", String::from("bad account data"))
+        })
+        .await
+        .map(|_: ()| ())
+        .unwrap_err();
+        assert_eq!(err.message, "bad account data");
+
+        let shared = Arc::new(Mutex::new(1));
+        let poisoner = shared.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poison");
+        })
+        .join();
+        assert!(shared.is_poisoned());
+        *lock_or_recover(&shared, "shared") += 1;
+        assert!(!shared.is_poisoned());
+        assert_eq!(*shared.lock().unwrap(), 2);
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::Result;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::config::{PrecisionConfig, RoundingMode};
+use crate::models::Token;
+
+/// Largest number of decimals a `Decimal` can represent exactly
+const MAX_DECIMALS: u8 = 28;
+
+impl RoundingMode {
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            RoundingMode::Floor => RoundingStrategy::ToZero,
+            RoundingMode::Ceil => RoundingStrategy::AwayFromZero,
+            RoundingMode::Nearest => RoundingStrategy::MidpointAwayFromZero,
+        }
+    }
+}
+
+/// Round a UI amount to the number of decimals a mint supports
+pub fn round_to_decimals(amount: Decimal, decimals: u8, mode: RoundingMode) -> Decimal {
+    amount.round_dp_with_strategy(decimals.min(MAX_DECIMALS) as u32, mode.strategy())
+}
+
+/// Convert a UI amount to integer base units, rounding the sub-unit remainder
+pub fn to_base_units(amount: Decimal, decimals: u8, mode: RoundingMode) -> Result<u64> {
+    if amount.is_sign_negative() {
+        anyhow::bail!("Negative amount {}", amount);
+    }
+    if decimals > MAX_DECIMALS {
+        anyhow::bail!("Mint precision of {} decimals is not supported", decimals);
+    }
+    let scaled = round_to_decimals(amount, decimals, mode) * Decimal::from(10u128.pow(decimals as u32));
+    scaled
+        .to_u64()
+        .ok_or_else(|| anyhow::anyhow!("Amount {} overflows u64 at {} decimals", amount, decimals))
+}
+
+/// Convert integer base units back to a UI amount
+pub fn from_base_units(units: u64, decimals: u8) -> Decimal {
+    Decimal::from_i128_with_scale(units as i128, decimals.min(MAX_DECIMALS) as u32)
+}
+
+/// Amounts of a single swap as submitted on-chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapAmounts {
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+}
+
+impl SwapAmounts {
+    /// Convert a quoted swap to base units under the configured policy.
+    ///
+    /// The minimum output never exceeds the floored expected output, so rounding alone cannot make
+    /// an exactly-quoted swap fail.
+    pub fn new(
+        policy: &PrecisionConfig,
+        input: &Token,
+        amount_in: Decimal,
+        output: &Token,
+        expected_out: Decimal,
+        max_slippage: Decimal,
+    ) -> Result<Self> {
+        let amount_in = to_base_units(amount_in, input.decimals, policy.input_rounding)?;
+        let min_out = expected_out * (Decimal::ONE - max_slippage);
+        let min_amount_out = to_base_units(min_out.max(Decimal::ZERO), output.decimals, policy.min_out_rounding)?
+            .min(to_base_units(expected_out, output.decimals, RoundingMode::Floor)?);
+        Ok(Self { amount_in, min_amount_out })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    fn token(decimals: u8) -> Token {
+        Token::new(Pubkey::new_unique(), "T".to_string(), "T".to_string(), decimals)
+    }
+
+    #[test]
+    fn test_swap_amounts_respect_mint_precision() {
+        let d = |s: &str| Decimal::from_str(s).unwrap();
+        assert_eq!(to_base_units(d("1.2345679"), 6, RoundingMode::Floor).unwrap(), 1_234_567);
+        assert_eq!(to_base_units(d("1.2345671"), 6, RoundingMode::Ceil).unwrap(), 1_234_568);
+        assert_eq!(from_base_units(1_234_567, 6), d("1.234567"));
+        assert!(to_base_units(d("-1"), 6, RoundingMode::Floor).is_err());
+
+        let policy = PrecisionConfig::default();
+        let amounts = SwapAmounts::new(&policy, &token(9), d("0.1234567891"), &token(6), d("15.0000009"), d("0.01")).unwrap();
+        assert_eq!(amounts.amount_in, 123_456_789);
+        // 14.850000891 rounded up
+        assert_eq!(amounts.min_amount_out, 14_850_001);
+
+        // Without slippage, rounding up must not demand more than the quote
+        let exact = SwapAmounts::new(&policy, &token(9), d("1"), &token(6), d("15.0000009"), Decimal::ZERO).unwrap();
+        assert_eq!(exact.min_amount_out, 15_000_000);
+    }
+}
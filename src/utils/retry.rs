@@ -0,0 +1,122 @@
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+use tracing::debug;
+
+/// Exponential backoff for calls that can fail transiently, such as DEX API and RPC requests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts after the first; zero disables retrying
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each one after it
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Fraction of each delay drawn at random, 0 to 1, so callers failing together spread out
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Default backoff with `max_retries` retries
+    pub fn new(max_retries: u32) -> Self {
+        Self { max_retries, ..Self::default() }
+    }
+
+    /// Delay before retry number `retry`, counted from 1, without jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Backoff with its jittered fraction drawn at random
+    fn delay(&self, retry: u32) -> Duration {
+        let backoff = self.backoff(retry);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return backoff;
+        }
+        backoff.mul_f64(1.0 - jitter * rand::thread_rng().gen::<f64>())
+    }
+
+    /// Run `operation` until it succeeds, fails with an error `is_retryable` rejects, or the
+    /// retries run out; the last error is returned
+    pub async fn run<T, E, F, Fut>(&self, mut operation: F, is_retryable: impl Fn(&E) -> bool) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: std::fmt::Display,
+    {
+        let mut retry = 0;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if retry < self.max_retries && is_retryable(&e) => {
+                    retry += 1;
+                    let delay = self.delay(retry);
+                    debug!("Retry {}/{} in {:?} after: {}", retry, self.max_retries, delay, e);
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_retries_transient_errors_with_doubling_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(3),
+            jitter: 0.0,
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(1));
+        assert_eq!(policy.backoff(2), Duration::from_millis(2));
+        assert_eq!(policy.backoff(3), Duration::from_millis(3));
+        assert_eq!(policy.backoff(40), Duration::from_millis(3));
+
+        // Succeeds on the third attempt
+        let attempts = AtomicU32::new(0);
+        let result = policy
+            .run(
+                || async {
+                    let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                    if attempt < 3 { Err("timeout") } else { Ok(attempt) }
+                },
+                |e: &&str| *e == "timeout",
+            )
+            .await;
+        assert_eq!(result, Ok(3));
+
+        // Gives up after the retries run out
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), _> = policy
+            .run(|| async { attempts.fetch_add(1, Ordering::SeqCst); Err("timeout") }, |e: &&str| *e == "timeout")
+            .await;
+        assert_eq!(result, Err("timeout"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+
+        // Never retries a permanent error
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), _> = policy
+            .run(|| async { attempts.fetch_add(1, Ordering::SeqCst); Err("not found") }, |e: &&str| *e == "timeout")
+            .await;
+        assert_eq!(result, Err("not found"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
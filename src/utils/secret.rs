@@ -0,0 +1,110 @@
+use anyhow::{Context, Result};
+use solana_sdk::signature::Keypair;
+use std::path::Path;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Fails to compile when `$ty` implements any of the listed traits; guards secret types against
+/// gaining a `Debug`, `Clone` or `Serialize` impl.
+///
+/// Each trait gets a helper trait with two blanket impls; the call is ambiguous, and so rejected,
+/// exactly when the second impl applies.
+macro_rules! assert_not_impl {
+    ($ty:ty: $($tr:path),+ $(,)?) => {
+        $(
+            const _: fn() = || {
+                trait AmbiguousIfImpl<A> {
+                    fn some_item() {}
+                }
+                impl<T: ?Sized> AmbiguousIfImpl<()> for T {}
+                #[allow(dead_code)]
+                struct Invalid;
+                impl<T: ?Sized + $tr> AmbiguousIfImpl<Invalid> for T {}
+                let _ = <$ty as AmbiguousIfImpl<_>>::some_item;
+            };
+        )+
+    };
+}
+pub(crate) use assert_not_impl;
+
+/// Key material that is wiped when dropped. Deliberately neither `Debug` nor `Clone`, so it cannot
+/// be formatted into a log line or silently copied; read it with [`Secret::expose`].
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Fill the secret in place, e.g. from an RNG or a KDF, without an intermediate copy
+    pub fn expose_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> ZeroizeOnDrop for Secret<T> {}
+
+assert_not_impl!(Secret<Vec<u8>>: std::fmt::Debug, Clone, serde::Serialize);
+
+/// A signing keypair. ed25519-dalek wipes the signing key when it is dropped; this wrapper drops
+/// the `Debug` impl and keeps the copies made while loading it in zeroizing buffers.
+pub struct SecretKeypair(Keypair);
+
+impl SecretKeypair {
+    pub fn new(keypair: Keypair) -> Self {
+        Self(keypair)
+    }
+
+    /// Read a keypair file in the Solana CLI format, a JSON array of the 64 keypair bytes
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = Zeroizing::new(std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?);
+        // Errors from here on would quote the file, so they name it instead
+        let bytes: Zeroizing<Vec<u8>> = Zeroizing::new(
+            serde_json::from_str(content.trim()).map_err(|_| anyhow::anyhow!("{} is not a JSON keypair file", path.display()))?,
+        );
+        let keypair = Keypair::try_from(bytes.as_slice()).map_err(|_| anyhow::anyhow!("{} does not hold a valid keypair", path.display()))?;
+        Ok(Self(keypair))
+    }
+
+    pub fn keypair(&self) -> &Keypair {
+        &self.0
+    }
+}
+
+assert_not_impl!(SecretKeypair: std::fmt::Debug, Clone, serde::Serialize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Signer;
+
+    fn zeroizes_on_drop<T: ZeroizeOnDrop>() {}
+
+    #[test]
+    fn test_keypair_file_loads_into_secret_container() {
+        zeroizes_on_drop::<Secret<Vec<u8>>>();
+        zeroizes_on_drop::<Secret<[u8; 32]>>();
+
+        let keypair = Keypair::new();
+        let path = std::env::temp_dir().join(format!("keypair-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap()).unwrap();
+        let loaded = SecretKeypair::read_file(&path).unwrap();
+        assert_eq!(loaded.keypair().pubkey(), keypair.pubkey());
+
+        // A malformed file is reported by name without echoing its contents
+        std::fs::write(&path, "[1, 2, 3]").unwrap();
+        let error = SecretKeypair::read_file(&path).err().unwrap().to_string();
+        assert!(error.contains("does not hold a valid keypair") && !error.contains("1, 2"), "{}", error);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
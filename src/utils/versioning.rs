@@ -0,0 +1,141 @@
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// Upgrades data written at version `n` to version `n + 1`
+pub type Migration = fn(Value) -> Result<Value>;
+
+/// Errors raised when persisted data does not match what this binary understands
+#[derive(Debug, thiserror::Error)]
+pub enum FormatError {
+    #[error("Expected {expected} data but found {found}")]
+    WrongFormat { expected: String, found: String },
+
+    #[error("{format} data is version {found} but this release only supports up to version {supported}; upgrade the bot to read it")]
+    TooNew { format: String, found: u32, supported: u32 },
+
+    #[error("No migration registered for {format} version {from}")]
+    MissingMigration { format: String, from: u32 },
+}
+
+/// Header wrapped around every versioned file
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    format: String,
+    version: u32,
+    data: T,
+}
+
+/// Name, current version and upgrade path of a persisted data format.
+/// Version 0 is legacy data written without a header.
+#[derive(Debug, Clone, Copy)]
+pub struct FormatSpec {
+    pub name: &'static str,
+    pub version: u32,
+    /// `migrations[n]` upgrades version `n` to `n + 1`
+    pub migrations: &'static [Migration],
+}
+
+impl FormatSpec {
+    /// Refuse data from a newer release
+    pub fn check_version(&self, found: u32) -> Result<(), FormatError> {
+        if found > self.version {
+            return Err(FormatError::TooNew {
+                format: self.name.to_string(),
+                found,
+                supported: self.version,
+            });
+        }
+        Ok(())
+    }
+
+    /// Apply migrations from `from` up to the current version
+    pub fn upgrade(&self, from: u32, mut data: Value) -> Result<Value> {
+        self.check_version(from)?;
+        for version in from..self.version {
+            let migration = self.migrations.get(version as usize).ok_or_else(|| FormatError::MissingMigration {
+                format: self.name.to_string(),
+                from: version,
+            })?;
+            data = migration(data)?;
+        }
+        Ok(data)
+    }
+
+    /// Serialize with a format header at the current version
+    pub fn encode<T: Serialize>(&self, data: &T) -> Result<String> {
+        let envelope = Envelope {
+            format: self.name.to_string(),
+            version: self.version,
+            data,
+        };
+        Ok(serde_json::to_string_pretty(&envelope)?)
+    }
+
+    /// Deserialize, upgrading older versions and refusing newer or foreign ones
+    pub fn decode<T: DeserializeOwned>(&self, content: &str) -> Result<T> {
+        let value: Value = serde_json::from_str(content)?;
+        let (version, data) = match serde_json::from_value::<Envelope<Value>>(value.clone()) {
+            Ok(envelope) => {
+                if envelope.format != self.name {
+                    return Err(FormatError::WrongFormat {
+                        expected: self.name.to_string(),
+                        found: envelope.format,
+                    }
+                    .into());
+                }
+                (envelope.version, envelope.data)
+            }
+            Err(_) => (0, value),
+        };
+
+        let data = self.upgrade(version, data)?;
+        Ok(serde_json::from_value(data)?)
+    }
+}
+
+/// Migration for formats whose first header-wrapped version kept the legacy layout
+pub fn unchanged(data: Value) -> Result<Value> {
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split_name(mut data: Value) -> Result<Value> {
+        let name = data["name"].as_str().unwrap_or_default().to_string();
+        let (first, last) = name.split_once(' ').unwrap_or((&name, ""));
+        data["first"] = Value::from(first);
+        data["last"] = Value::from(last);
+        Ok(data)
+    }
+
+    const SPEC: FormatSpec = FormatSpec {
+        name: "person",
+        version: 2,
+        migrations: &[unchanged, split_name],
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Person {
+        first: String,
+        last: String,
+    }
+
+    #[test]
+    fn test_upgrade_and_refuse() {
+        let person: Person = SPEC.decode(r#"{"name": "Ada Lovelace"}"#).unwrap();
+        assert_eq!(person, Person { first: "Ada".to_string(), last: "Lovelace".to_string() });
+
+        let encoded = SPEC.encode(&person).unwrap();
+        assert_eq!(SPEC.decode::<Person>(&encoded).unwrap(), person);
+
+        let newer = r#"{"format": "person", "version": 3, "data": {}}"#;
+        let err = SPEC.decode::<Person>(newer).unwrap_err();
+        assert!(matches!(err.downcast_ref::<FormatError>(), Some(FormatError::TooNew { found: 3, .. })));
+
+        let foreign = r#"{"format": "pool_blacklist", "version": 1, "data": []}"#;
+        assert!(SPEC.decode::<Person>(foreign).is_err());
+    }
+}